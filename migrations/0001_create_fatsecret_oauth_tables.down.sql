@@ -0,0 +1,2 @@
+DROP TABLE IF EXISTS fatsecret_oauth_token;
+DROP TABLE IF EXISTS fatsecret_oauth_pending;
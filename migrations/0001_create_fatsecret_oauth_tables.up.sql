@@ -0,0 +1,19 @@
+-- OAuth tokens for the FatSecret 3-legged flow, one connected account.
+-- IF NOT EXISTS lets databases set up before migrations existed adopt this one.
+
+-- Request tokens between oauth_start and oauth_complete (15 minute lifetime)
+CREATE TABLE IF NOT EXISTS fatsecret_oauth_pending (
+    oauth_token TEXT PRIMARY KEY,
+    oauth_token_secret TEXT NOT NULL,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    expires_at TIMESTAMPTZ NOT NULL
+);
+
+-- The access token; a single row with id = 1
+CREATE TABLE IF NOT EXISTS fatsecret_oauth_token (
+    id INTEGER PRIMARY KEY DEFAULT 1 CHECK (id = 1),
+    oauth_token TEXT NOT NULL,
+    oauth_token_secret TEXT NOT NULL,
+    connected_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    last_used_at TIMESTAMPTZ
+);
@@ -0,0 +1,10 @@
+-- Only the 'default' user's access token fits the single-row schema;
+-- tokens of other users are deleted.
+
+DROP INDEX IF EXISTS fatsecret_oauth_pending_user_idx;
+ALTER TABLE fatsecret_oauth_pending DROP COLUMN IF EXISTS user_id;
+
+DELETE FROM fatsecret_oauth_token WHERE user_id <> 'default';
+ALTER TABLE fatsecret_oauth_token DROP COLUMN user_id;
+ALTER TABLE fatsecret_oauth_token
+    ADD COLUMN id INTEGER PRIMARY KEY DEFAULT 1 CHECK (id = 1);
@@ -0,0 +1,27 @@
+-- Tokens belong to a user so several FatSecret accounts can be connected.
+-- Existing rows become the 'default' user's (storage::DEFAULT_USER).
+
+ALTER TABLE fatsecret_oauth_pending
+    ADD COLUMN IF NOT EXISTS user_id TEXT NOT NULL DEFAULT 'default';
+
+CREATE INDEX IF NOT EXISTS fatsecret_oauth_pending_user_idx
+    ON fatsecret_oauth_pending (user_id, created_at DESC);
+
+-- Re-key the singleton access token row by user. Skipped for tables that
+-- were already created per user.
+DO $$
+BEGIN
+    IF EXISTS (
+        SELECT 1 FROM information_schema.columns
+        WHERE table_schema = current_schema()
+          AND table_name = 'fatsecret_oauth_token'
+          AND column_name = 'id'
+    ) THEN
+        ALTER TABLE fatsecret_oauth_token ADD COLUMN IF NOT EXISTS user_id TEXT;
+        UPDATE fatsecret_oauth_token SET user_id = 'default' WHERE user_id IS NULL;
+        ALTER TABLE fatsecret_oauth_token DROP COLUMN id;
+        ALTER TABLE fatsecret_oauth_token ALTER COLUMN user_id SET NOT NULL;
+        ALTER TABLE fatsecret_oauth_token ADD PRIMARY KEY (user_id);
+    END IF;
+END
+$$;
@@ -0,0 +1,3 @@
+-- Set when FatSecret rejects the access token (see fatsecret::health);
+-- cleared when the token works again or a new one is stored.
+ALTER TABLE fatsecret_oauth_token ADD COLUMN IF NOT EXISTS revoked_at TIMESTAMPTZ;
@@ -0,0 +1,3 @@
+DROP TABLE IF EXISTS fatsecret_oauth_token;
+DROP INDEX IF EXISTS fatsecret_oauth_pending_user_idx;
+DROP TABLE IF EXISTS fatsecret_oauth_pending;
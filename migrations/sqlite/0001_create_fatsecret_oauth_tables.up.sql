@@ -0,0 +1,23 @@
+-- OAuth tokens for the FatSecret 3-legged flow, keyed by user.
+-- Timestamps are RFC 3339 text in UTC; compare them with julianday().
+
+-- Request tokens between oauth_start and oauth_complete (15 minute lifetime)
+CREATE TABLE IF NOT EXISTS fatsecret_oauth_pending (
+    oauth_token TEXT PRIMARY KEY,
+    oauth_token_secret TEXT NOT NULL,
+    user_id TEXT NOT NULL DEFAULT 'default',
+    created_at TEXT NOT NULL DEFAULT (strftime('%Y-%m-%dT%H:%M:%fZ', 'now')),
+    expires_at TEXT NOT NULL
+);
+
+CREATE INDEX IF NOT EXISTS fatsecret_oauth_pending_user_idx
+    ON fatsecret_oauth_pending (user_id, created_at DESC);
+
+-- One access token per user
+CREATE TABLE IF NOT EXISTS fatsecret_oauth_token (
+    user_id TEXT PRIMARY KEY,
+    oauth_token TEXT NOT NULL,
+    oauth_token_secret TEXT NOT NULL,
+    connected_at TEXT NOT NULL DEFAULT (strftime('%Y-%m-%dT%H:%M:%fZ', 'now')),
+    last_used_at TEXT
+);
@@ -0,0 +1 @@
+DROP TABLE IF EXISTS fatsecret_diary_sync;
@@ -0,0 +1,10 @@
+-- Idempotency ledger for diary entries created by the meal plan sync.
+-- A key is claimed before its diary entry is created; food_entry_id is
+-- filled in once the entry exists.
+CREATE TABLE IF NOT EXISTS fatsecret_diary_sync (
+    idempotency_key TEXT PRIMARY KEY,
+    meal_plan_id INTEGER NOT NULL,
+    date_int INTEGER NOT NULL,
+    food_entry_id TEXT,
+    created_at TEXT NOT NULL DEFAULT (strftime('%Y-%m-%dT%H:%M:%fZ', 'now'))
+);
@@ -0,0 +1 @@
+ALTER TABLE fatsecret_oauth_token DROP COLUMN revoked_at;
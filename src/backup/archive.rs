@@ -0,0 +1,204 @@
+//! Backup archive
+//!
+//! A zip file with `manifest.json`, one `data/<resource>.json` array per
+//! [`Resource`], and recipe images under `images/`, named by the recipe's
+//! ID in the backup.
+
+use super::{BackupError, Manifest, Resource, FORMAT, FORMAT_VERSION};
+use serde_json::Value;
+use std::collections::BTreeMap;
+use std::io::{Read, Seek, Write};
+use std::path::Path;
+use zip::write::SimpleFileOptions;
+use zip::{ZipArchive, ZipWriter};
+
+const MANIFEST: &str = "manifest.json";
+const IMAGES: &str = "images/";
+
+/// A recipe image
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RecipeImageFile {
+    /// File name with extension (`42.jpg`)
+    pub file_name: String,
+    pub data: Vec<u8>,
+}
+
+/// A backup in memory
+#[derive(Debug, Default)]
+pub struct Backup {
+    pub manifest: Manifest,
+    /// Records per resource, as the API returned them
+    pub records: BTreeMap<Resource, Vec<Value>>,
+    /// Images by recipe ID (in the backup)
+    pub images: BTreeMap<i64, RecipeImageFile>,
+}
+
+fn archive_error(e: impl std::fmt::Display) -> BackupError {
+    BackupError::Archive(e.to_string())
+}
+
+impl Backup {
+    /// Records of one resource
+    pub fn records(&self, resource: Resource) -> &[Value] {
+        self.records.get(&resource).map_or(&[], Vec::as_slice)
+    }
+
+    /// Write the archive
+    pub fn write<W: Write + Seek>(&self, writer: W) -> Result<W, BackupError> {
+        let mut zip = ZipWriter::new(writer);
+        let options = SimpleFileOptions::default();
+
+        zip.start_file(MANIFEST, options).map_err(archive_error)?;
+        serde_json::to_writer_pretty(&mut zip, &self.manifest).map_err(archive_error)?;
+        for (resource, records) in &self.records {
+            zip.start_file(format!("data/{}.json", resource.file_stem()), options)
+                .map_err(archive_error)?;
+            serde_json::to_writer(&mut zip, records).map_err(archive_error)?;
+        }
+        // Images are compressed already
+        let stored = options.compression_method(zip::CompressionMethod::Stored);
+        for image in self.images.values() {
+            zip.start_file(format!("{IMAGES}{}", image.file_name), stored)
+                .map_err(archive_error)?;
+            zip.write_all(&image.data)?;
+        }
+        zip.finish().map_err(archive_error)
+    }
+
+    /// Read an archive, checking its format and version
+    pub fn read<R: Read + Seek>(reader: R) -> Result<Self, BackupError> {
+        let mut zip = ZipArchive::new(reader).map_err(archive_error)?;
+        let manifest: Manifest = {
+            let file = zip
+                .by_name(MANIFEST)
+                .map_err(|_| BackupError::Archive("no manifest.json".to_string()))?;
+            serde_json::from_reader(file).map_err(archive_error)?
+        };
+        if manifest.format != FORMAT {
+            return Err(BackupError::Archive(format!(
+                "not a Tandoor backup ({})",
+                manifest.format
+            )));
+        }
+        if manifest.version > FORMAT_VERSION {
+            return Err(BackupError::UnsupportedVersion(manifest.version));
+        }
+
+        let mut backup = Self {
+            manifest,
+            ..Self::default()
+        };
+        for resource in Resource::ALL {
+            let name = format!("data/{}.json", resource.file_stem());
+            let Ok(file) = zip.by_name(&name) else {
+                continue;
+            };
+            let records: Vec<Value> = serde_json::from_reader(file).map_err(archive_error)?;
+            backup.records.insert(resource, records);
+        }
+        backup.read_images(&mut zip)?;
+        Ok(backup)
+    }
+
+    fn read_images<R: Read + Seek>(&mut self, zip: &mut ZipArchive<R>) -> Result<(), BackupError> {
+        for index in 0..zip.len() {
+            let mut file = zip.by_index(index).map_err(archive_error)?;
+            let Some(file_name) = file.name().strip_prefix(IMAGES).map(str::to_string) else {
+                continue;
+            };
+            let Some(id) = file_name
+                .split('.')
+                .next()
+                .and_then(|stem| stem.parse().ok())
+            else {
+                continue;
+            };
+            let mut data = Vec::new();
+            file.read_to_end(&mut data)?;
+            self.images.insert(id, RecipeImageFile { file_name, data });
+        }
+        Ok(())
+    }
+
+    /// Save the archive to a file
+    pub fn save(&self, path: &Path) -> Result<(), BackupError> {
+        self.write(std::fs::File::create(path)?)?;
+        Ok(())
+    }
+
+    /// Load an archive from a file
+    pub fn load(path: &Path) -> Result<Self, BackupError> {
+        Self::read(std::fs::File::open(path)?)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+    use std::io::Cursor;
+
+    fn backup() -> Backup {
+        Backup {
+            manifest: Manifest {
+                format: FORMAT.to_string(),
+                version: FORMAT_VERSION,
+                created_at: "2026-10-19T08:00:00Z".to_string(),
+                source: "http://localhost:8090".to_string(),
+                counts: BTreeMap::from([(Resource::Recipe, 1)]),
+                images: 1,
+            },
+            records: BTreeMap::from([
+                (Resource::Recipe, vec![json!({"id": 7, "name": "Pancakes"})]),
+                (Resource::UnitConversion, vec![]),
+            ]),
+            images: BTreeMap::from([(
+                7,
+                RecipeImageFile {
+                    file_name: "7.png".to_string(),
+                    data: vec![0x89, b'P', b'N', b'G'],
+                },
+            )]),
+        }
+    }
+
+    #[test]
+    fn test_round_trip() {
+        let original = backup();
+        let bytes = original
+            .write(Cursor::new(Vec::new()))
+            .unwrap()
+            .into_inner();
+        let restored = Backup::read(Cursor::new(bytes)).unwrap();
+        assert_eq!(restored.manifest, original.manifest);
+        assert_eq!(restored.records, original.records);
+        assert_eq!(restored.images, original.images);
+        assert!(restored.records(Resource::MealPlan).is_empty());
+    }
+
+    #[test]
+    fn test_rejects_newer_version() {
+        let mut newer = backup();
+        newer.manifest.version = FORMAT_VERSION + 1;
+        let bytes = newer.write(Cursor::new(Vec::new())).unwrap().into_inner();
+        assert!(matches!(
+            Backup::read(Cursor::new(bytes)),
+            Err(BackupError::UnsupportedVersion(_))
+        ));
+    }
+
+    #[test]
+    fn test_rejects_other_archives() {
+        let mut other = backup();
+        other.manifest.format = "something-else".to_string();
+        let bytes = other.write(Cursor::new(Vec::new())).unwrap().into_inner();
+        assert!(matches!(
+            Backup::read(Cursor::new(bytes)),
+            Err(BackupError::Archive(_))
+        ));
+        assert!(matches!(
+            Backup::read(Cursor::new(b"not a zip".to_vec())),
+            Err(BackupError::Archive(_))
+        ));
+    }
+}
@@ -0,0 +1,261 @@
+//! Full Tandoor backup and restore
+//!
+//! [`create_backup`] walks every list endpoint the client knows (keywords,
+//! units, property types, supermarkets, foods, unit conversions, meal types,
+//! recipes with their steps, meal plans, recipe books and their entries, and
+//! shopping list entries), downloads recipe images, and returns a
+//! [`Backup`] that saves as a versioned zip archive:
+//!
+//! ```text
+//! manifest.json               format, version, source and counts
+//! data/<resource>.json        records, as the API returned them
+//! images/<recipe id>.<ext>    recipe images
+//! ```
+//!
+//! [`restore_backup`] replays an archive into an empty instance, resource by
+//! resource in dependency order. The new instance hands out new IDs, so
+//! references between records (meal plan to recipe and meal type, book entry
+//! to book and recipe, food to recipe, step to recipe) are remapped as the
+//! records are created. Nested foods, units and keywords are matched by
+//! name, as Tandoor's API does, and a named record that already exists in
+//! the target is reused rather than duplicated.
+//!
+//! Not replayed: users and sharing, keyword and food hierarchies (restored
+//! flat) and supermarket category order.
+//!
+//! ```rust,no_run
+//! use meal_planner::backup::{create_backup, restore_backup, Backup, RestoreOptions};
+//! use meal_planner::tandoor::{TandoorClient, TandoorConfig};
+//! use std::path::Path;
+//!
+//! # fn main() -> Result<(), Box<dyn std::error::Error>> {
+//! let source = TandoorClient::new(&TandoorConfig {
+//!     base_url: "http://old-tandoor:8090".to_string(),
+//!     api_token: "old-token".to_string(),
+//! })?;
+//! let (backup, report) = create_backup(&source, true)?;
+//! backup.save(Path::new("tandoor-backup.zip"))?;
+//! println!("{} images, {} failures", report.images, report.failed.len());
+//!
+//! let target = TandoorClient::new(&TandoorConfig {
+//!     base_url: "http://new-tandoor:8090".to_string(),
+//!     api_token: "new-token".to_string(),
+//! })?;
+//! let backup = Backup::load(Path::new("tandoor-backup.zip"))?;
+//! let report = restore_backup(&target, &backup, &RestoreOptions::default())?;
+//! println!("{} records failed", report.failed.len());
+//! # Ok(())
+//! # }
+//! ```
+
+pub mod archive;
+pub mod restore;
+pub mod snapshot;
+
+pub use archive::{Backup, RecipeImageFile};
+pub use restore::{restore_backup, RestoreOptions, RestoreReport};
+pub use snapshot::{create_backup, BackupReport};
+
+use crate::tandoor::{all_pages, PaginatedResponse, TandoorClient, TandoorError};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::BTreeMap;
+use thiserror::Error;
+
+/// Archive format name, stored in the manifest
+pub const FORMAT: &str = "meal-planner-tandoor-backup";
+
+/// Archive format version written by this build
+pub const FORMAT_VERSION: u32 = 1;
+
+/// Page size when listing records
+const PAGE_SIZE: u32 = 100;
+
+/// Backup and restore errors
+#[derive(Debug, Error)]
+pub enum BackupError {
+    #[error("Tandoor error: {0}")]
+    Tandoor(#[from] TandoorError),
+
+    #[error("I/O error: {0}")]
+    Io(#[from] std::io::Error),
+
+    #[error("Invalid backup archive: {0}")]
+    Archive(String),
+
+    #[error("Unsupported backup version {0} (this build reads up to {FORMAT_VERSION})")]
+    UnsupportedVersion(u32),
+
+    #[error("Target instance is not empty ({0} recipes); set force to restore anyway")]
+    NotEmpty(i64),
+}
+
+/// A kind of record in a backup
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Resource {
+    Keyword,
+    Unit,
+    PropertyType,
+    Supermarket,
+    Food,
+    UnitConversion,
+    MealType,
+    Recipe,
+    MealPlan,
+    RecipeBook,
+    RecipeBookEntry,
+    ShoppingListEntry,
+}
+
+impl Resource {
+    /// Every resource, in restore order (dependencies first)
+    pub const ALL: [Self; 12] = [
+        Self::Keyword,
+        Self::Unit,
+        Self::PropertyType,
+        Self::Supermarket,
+        Self::Food,
+        Self::UnitConversion,
+        Self::MealType,
+        Self::Recipe,
+        Self::MealPlan,
+        Self::RecipeBook,
+        Self::RecipeBookEntry,
+        Self::ShoppingListEntry,
+    ];
+
+    /// API path segment (`unit-conversion`)
+    pub const fn endpoint(self) -> &'static str {
+        match self {
+            Self::Keyword => "keyword",
+            Self::Unit => "unit",
+            Self::PropertyType => "property-type",
+            Self::Supermarket => "supermarket",
+            Self::Food => "food",
+            Self::UnitConversion => "unit-conversion",
+            Self::MealType => "meal-type",
+            Self::Recipe => "recipe",
+            Self::MealPlan => "meal-plan",
+            Self::RecipeBook => "recipe-book",
+            Self::RecipeBookEntry => "recipe-book-entry",
+            Self::ShoppingListEntry => "shopping-list-entry",
+        }
+    }
+
+    /// File name stem in the archive (`unit_conversion`)
+    pub fn file_stem(self) -> String {
+        self.endpoint().replace('-', "_")
+    }
+
+    /// Whether records have a name to match existing ones by
+    pub const fn is_named(self) -> bool {
+        match self {
+            Self::Keyword
+            | Self::Unit
+            | Self::PropertyType
+            | Self::Supermarket
+            | Self::Food
+            | Self::MealType
+            | Self::Recipe
+            | Self::RecipeBook => true,
+            Self::UnitConversion
+            | Self::MealPlan
+            | Self::RecipeBookEntry
+            | Self::ShoppingListEntry => false,
+        }
+    }
+}
+
+/// Archive manifest
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Manifest {
+    /// Always [`FORMAT`]
+    pub format: String,
+    pub version: u32,
+    /// When the backup was taken (RFC 3339)
+    pub created_at: String,
+    /// Base URL of the instance backed up
+    pub source: String,
+    /// Records per resource
+    pub counts: BTreeMap<Resource, usize>,
+    /// Recipe images
+    pub images: usize,
+}
+
+/// A record that could not be backed up or restored
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct FailedRecord {
+    pub resource: Resource,
+    /// ID in the backup, if the record has one
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub id: Option<i64>,
+    pub error: String,
+}
+
+/// ID of a record
+pub(crate) fn record_id(record: &Value) -> Option<i64> {
+    record.get("id").and_then(Value::as_i64)
+}
+
+/// Name of a record, for matching
+pub(crate) fn record_name(record: &Value) -> Option<String> {
+    record
+        .get("name")
+        .and_then(Value::as_str)
+        .map(|name| name.trim().to_lowercase())
+        .filter(|name| !name.is_empty())
+}
+
+/// Every record of a resource, following pagination
+///
+/// Endpoints that answer with a plain array are read in one request.
+pub(crate) fn list_all(
+    client: &TandoorClient,
+    resource: Resource,
+) -> Result<Vec<Value>, TandoorError> {
+    all_pages(|page| {
+        let response = client.list_resource(resource.endpoint(), Some(page), Some(PAGE_SIZE))?;
+        if let Value::Array(results) = response {
+            return Ok(PaginatedResponse {
+                count: i64::try_from(results.len()).unwrap_or(i64::MAX),
+                next: None,
+                previous: None,
+                results,
+            });
+        }
+        serde_json::from_value(response).map_err(|e| TandoorError::ParseError(e.to_string()))
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_resources_in_dependency_order() {
+        let position = |resource| Resource::ALL.iter().position(|r| *r == resource);
+        assert!(position(Resource::Unit) < position(Resource::UnitConversion));
+        assert!(position(Resource::Food) < position(Resource::Recipe));
+        assert!(position(Resource::MealType) < position(Resource::MealPlan));
+        assert!(position(Resource::Recipe) < position(Resource::MealPlan));
+        assert!(position(Resource::RecipeBook) < position(Resource::RecipeBookEntry));
+        assert_eq!(Resource::UnitConversion.file_stem(), "unit_conversion");
+    }
+
+    #[test]
+    fn test_manifest_counts_serialize_by_name() {
+        let manifest = Manifest {
+            format: FORMAT.to_string(),
+            version: FORMAT_VERSION,
+            counts: BTreeMap::from([(Resource::MealPlan, 3), (Resource::Unit, 12)]),
+            ..Manifest::default()
+        };
+        let json = serde_json::to_value(&manifest).unwrap();
+        assert_eq!(
+            json["counts"],
+            serde_json::json!({"unit": 12, "meal_plan": 3})
+        );
+        assert_eq!(serde_json::from_value::<Manifest>(json).unwrap(), manifest);
+    }
+}
@@ -0,0 +1,479 @@
+//! Restoring a backup
+//!
+//! Each record is sent back as it was backed up, minus its IDs and the
+//! fields the server sets (creator, timestamps, sharing). Nested objects
+//! lose their IDs too, so Tandoor matches nested foods, units and keywords
+//! by name. References to other records are remapped to the new IDs:
+//!
+//! - meal plans: recipe and meal type
+//! - recipe book entries: book and recipe
+//! - recipe steps: the recipe a step links to (recipes without such links
+//!   are restored first)
+//! - foods: the recipe a food links to, set once recipes exist
+//!
+//! A record whose reference wasn't restored fails on its own; the rest of
+//! the restore carries on.
+
+use super::archive::{Backup, RecipeImageFile};
+use super::{list_all, record_id, record_name, BackupError, FailedRecord, Resource};
+use crate::tandoor::TandoorClient;
+use serde::Serialize;
+use serde_json::Value;
+use std::collections::{BTreeMap, HashMap, HashSet};
+
+/// Top-level fields not sent back: set by the server, tied to users, or
+/// restored separately
+const DROPPED_FIELDS: &[&str] = &[
+    "created_at",
+    "created_by",
+    "full_name",
+    "image",
+    "numchild",
+    "numrecipe",
+    "parent",
+    "shared",
+    "updated_at",
+];
+
+/// Backup IDs to the IDs of the restored records
+type IdMap = HashMap<(Resource, i64), i64>;
+
+/// Restore options
+#[derive(Debug, Clone)]
+pub struct RestoreOptions {
+    /// Restore into an instance that already has recipes
+    pub force: bool,
+    /// Upload recipe images
+    pub images: bool,
+}
+
+impl Default for RestoreOptions {
+    fn default() -> Self {
+        Self {
+            force: false,
+            images: true,
+        }
+    }
+}
+
+/// Outcome for one resource
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct RestoredResource {
+    pub resource: Resource,
+    pub created: usize,
+    /// Matched by name to a record already in the instance
+    pub existing: usize,
+    pub failed: usize,
+}
+
+/// Restore outcome
+#[derive(Debug, Default, Serialize)]
+pub struct RestoreReport {
+    /// One entry per resource, in restore order
+    pub resources: Vec<RestoredResource>,
+    /// Recipe images uploaded
+    pub images: usize,
+    /// Records that could not be restored
+    pub failed: Vec<FailedRecord>,
+    /// Links between records that could not be kept
+    pub warnings: Vec<String>,
+}
+
+/// Replay a backup into an instance
+///
+/// Refuses an instance that already has recipes unless `force` is set:
+/// meal plans, book entries and shopping entries are always created, so
+/// restoring twice duplicates them.
+pub fn restore_backup(
+    client: &TandoorClient,
+    backup: &Backup,
+    options: &RestoreOptions,
+) -> Result<RestoreReport, BackupError> {
+    if !options.force {
+        let recipes = client.list_recipes(Some(1), Some(1))?.count;
+        if recipes > 0 {
+            return Err(BackupError::NotEmpty(recipes));
+        }
+    }
+
+    let mut restorer = Restorer {
+        client,
+        ids: IdMap::new(),
+        created_recipes: HashSet::new(),
+        report: RestoreReport::default(),
+    };
+    for resource in Resource::ALL {
+        restorer.restore(resource, backup.records(resource))?;
+    }
+    restorer.link_food_recipes(backup.records(Resource::Food));
+    if options.images {
+        restorer.upload_images(&backup.images);
+    }
+    Ok(restorer.report)
+}
+
+struct Restorer<'a> {
+    client: &'a TandoorClient,
+    ids: IdMap,
+    /// Backup IDs of the recipes created (not matched)
+    created_recipes: HashSet<i64>,
+    report: RestoreReport,
+}
+
+impl Restorer<'_> {
+    /// Create (or match) every record of a resource
+    fn restore(&mut self, resource: Resource, records: &[Value]) -> Result<(), BackupError> {
+        let existing = self.existing(resource)?;
+        let mut summary = RestoredResource {
+            resource,
+            created: 0,
+            existing: 0,
+            failed: 0,
+        };
+
+        for record in ordered(resource, records) {
+            let id = record_id(record);
+            if let Some(&new_id) = record_name(record).and_then(|name| existing.get(&name)) {
+                self.remember(resource, id, Some(new_id));
+                summary.existing += 1;
+                continue;
+            }
+            match self.create(resource, record) {
+                Ok(new_id) => {
+                    self.remember(resource, id, new_id);
+                    if resource == Resource::Recipe {
+                        self.created_recipes.extend(id);
+                    }
+                    summary.created += 1;
+                }
+                Err(error) => {
+                    summary.failed += 1;
+                    self.report.failed.push(FailedRecord {
+                        resource,
+                        id,
+                        error,
+                    });
+                }
+            }
+        }
+
+        self.report.resources.push(summary);
+        Ok(())
+    }
+
+    /// Named records already in the instance, by name
+    fn existing(&self, resource: Resource) -> Result<HashMap<String, i64>, BackupError> {
+        if !resource.is_named() {
+            return Ok(HashMap::new());
+        }
+        Ok(list_all(self.client, resource)?
+            .iter()
+            .filter_map(|record| Some((record_name(record)?, record_id(record)?)))
+            .collect())
+    }
+
+    fn remember(&mut self, resource: Resource, id: Option<i64>, new_id: Option<i64>) {
+        if let (Some(id), Some(new_id)) = (id, new_id) {
+            self.ids.insert((resource, id), new_id);
+        }
+    }
+
+    fn create(&mut self, resource: Resource, record: &Value) -> Result<Option<i64>, String> {
+        let request = payload(resource, record, &self.ids, &mut self.report.warnings)?;
+        let created = self
+            .client
+            .create_resource(resource.endpoint(), &request)
+            .map_err(|e| e.to_string())?;
+        Ok(record_id(&created))
+    }
+
+    /// Point foods at their recipes, now that recipes exist
+    fn link_food_recipes(&mut self, foods: &[Value]) {
+        for food in foods {
+            let (Some(food_id), Some(recipe)) = (record_id(food), food.get("recipe")) else {
+                continue;
+            };
+            let Some(recipe_id) = reference(Some(recipe)) else {
+                continue;
+            };
+            let new_ids = (
+                self.ids.get(&(Resource::Food, food_id)),
+                self.ids.get(&(Resource::Recipe, recipe_id)),
+            );
+            let (Some(&new_food), Some(&new_recipe)) = new_ids else {
+                self.report.warnings.push(format!(
+                    "food {food_id}: linked recipe {recipe_id} was not restored"
+                ));
+                continue;
+            };
+            let request = serde_json::json!({ "recipe": with_id(recipe, new_recipe) });
+            if let Err(e) =
+                self.client
+                    .update_resource(Resource::Food.endpoint(), new_food, &request)
+            {
+                self.report.failed.push(FailedRecord {
+                    resource: Resource::Food,
+                    id: Some(food_id),
+                    error: format!("recipe link: {e}"),
+                });
+            }
+        }
+    }
+
+    /// Upload the images of the recipes created
+    fn upload_images(&mut self, images: &BTreeMap<i64, RecipeImageFile>) {
+        for (id, image) in images {
+            let new_id = self
+                .ids
+                .get(&(Resource::Recipe, *id))
+                .filter(|_| self.created_recipes.contains(id));
+            let Some(&new_id) = new_id else {
+                continue;
+            };
+            match self.client.upload_recipe_image_bytes(
+                new_id,
+                &image.file_name,
+                image.data.clone(),
+            ) {
+                Ok(_) => self.report.images += 1,
+                Err(e) => self.report.failed.push(FailedRecord {
+                    resource: Resource::Recipe,
+                    id: Some(*id),
+                    error: format!("image: {e}"),
+                }),
+            }
+        }
+    }
+}
+
+/// Records in restore order: recipes that link to other recipes go last
+fn ordered(resource: Resource, records: &[Value]) -> Vec<&Value> {
+    let mut ordered: Vec<&Value> = records.iter().collect();
+    if resource == Resource::Recipe {
+        ordered.sort_by_key(|recipe| links_recipes(recipe));
+    }
+    ordered
+}
+
+fn links_recipes(recipe: &Value) -> bool {
+    recipe
+        .get("steps")
+        .and_then(Value::as_array)
+        .is_some_and(|steps| {
+            steps
+                .iter()
+                .any(|step| reference(step.get("step_recipe")).is_some())
+        })
+}
+
+/// An ID reference: a number, or an object with an `id`
+fn reference(value: Option<&Value>) -> Option<i64> {
+    value.and_then(|value| value.as_i64().or_else(|| record_id(value)))
+}
+
+/// A reference like `original`, pointing at `id`
+fn with_id(original: &Value, id: i64) -> Value {
+    match original {
+        Value::Object(fields) => {
+            let mut fields = fields.clone();
+            fields.insert("id".to_string(), Value::from(id));
+            Value::Object(fields)
+        }
+        Value::Null | Value::Bool(_) | Value::Number(_) | Value::String(_) | Value::Array(_) => {
+            Value::from(id)
+        }
+    }
+}
+
+/// The new ID for a reference
+fn remap(ids: &IdMap, resource: Resource, reference: Option<i64>) -> Result<i64, String> {
+    let id = reference.ok_or_else(|| format!("no {} reference", resource.endpoint()))?;
+    ids.get(&(resource, id))
+        .copied()
+        .ok_or_else(|| format!("{} {id} was not restored", resource.endpoint()))
+}
+
+/// Remove every `id`, at any depth
+fn strip_ids(value: &mut Value) {
+    match value {
+        Value::Object(fields) => {
+            fields.remove("id");
+            fields.values_mut().for_each(strip_ids);
+        }
+        Value::Array(items) => items.iter_mut().for_each(strip_ids),
+        Value::Null | Value::Bool(_) | Value::Number(_) | Value::String(_) => {}
+    }
+}
+
+/// The create request for a backed-up record, with references remapped
+fn payload(
+    resource: Resource,
+    record: &Value,
+    ids: &IdMap,
+    warnings: &mut Vec<String>,
+) -> Result<Value, String> {
+    let mut request = record.clone();
+    strip_ids(&mut request);
+    let Some(fields) = request.as_object_mut() else {
+        return Err("record is not an object".to_string());
+    };
+    for field in DROPPED_FIELDS {
+        fields.remove(*field);
+    }
+
+    match resource {
+        // Linked once recipes exist
+        Resource::Food => {
+            fields.remove("recipe");
+        }
+        Resource::Recipe => remap_step_recipes(record, fields, ids, warnings),
+        Resource::MealPlan => {
+            let recipe = reference(record.get("recipe"))
+                .map(|id| remap(ids, Resource::Recipe, Some(id)))
+                .transpose()?;
+            fields.insert(
+                "recipe".to_string(),
+                recipe.map_or(Value::Null, Value::from),
+            );
+            let meal_type = remap(ids, Resource::MealType, reference(record.get("meal_type")))?;
+            fields.insert("meal_type".to_string(), Value::from(meal_type));
+        }
+        // Saved filters aren't backed up
+        Resource::RecipeBook => {
+            fields.remove("filter");
+        }
+        Resource::RecipeBookEntry => {
+            for key in ["recipe_book", "book"] {
+                if record.get(key).is_some() {
+                    let book = remap(ids, Resource::RecipeBook, reference(record.get(key)))?;
+                    fields.insert(key.to_string(), Value::from(book));
+                }
+            }
+            let recipe = remap(ids, Resource::Recipe, reference(record.get("recipe")))?;
+            fields.insert("recipe".to_string(), Value::from(recipe));
+        }
+        // Meal plan links are recreated by adding meal plans to shopping
+        Resource::ShoppingListEntry => {
+            for field in ["list_recipe", "recipe_mealplan", "mealplan"] {
+                fields.remove(field);
+            }
+        }
+        Resource::Keyword
+        | Resource::Unit
+        | Resource::PropertyType
+        | Resource::Supermarket
+        | Resource::UnitConversion
+        | Resource::MealType => {}
+    }
+    Ok(request)
+}
+
+/// Point steps that link to another recipe at its new ID (or unlink them)
+fn remap_step_recipes(
+    record: &Value,
+    fields: &mut serde_json::Map<String, Value>,
+    ids: &IdMap,
+    warnings: &mut Vec<String>,
+) {
+    let originals = record.get("steps").and_then(Value::as_array);
+    let steps = fields.get_mut("steps").and_then(Value::as_array_mut);
+    let (Some(originals), Some(steps)) = (originals, steps) else {
+        return;
+    };
+    for (step, original) in steps.iter_mut().zip(originals) {
+        let Some(linked) = reference(original.get("step_recipe")) else {
+            continue;
+        };
+        let new_id = ids.get(&(Resource::Recipe, linked)).copied();
+        if new_id.is_none() {
+            warnings.push(format!(
+                "recipe {}: step link to recipe {linked} dropped, it was not restored",
+                record_id(record).unwrap_or_default()
+            ));
+        }
+        if let Some(step) = step.as_object_mut() {
+            step.insert(
+                "step_recipe".to_string(),
+                new_id.map_or(Value::Null, Value::from),
+            );
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn ids() -> IdMap {
+        IdMap::from([
+            ((Resource::Recipe, 5), 105),
+            ((Resource::MealType, 2), 102),
+            ((Resource::RecipeBook, 3), 103),
+        ])
+    }
+
+    #[test]
+    fn test_payload_strips_ids_and_server_fields() {
+        let food = json!({
+            "id": 9, "name": "Tomato", "created_by": 1, "numchild": 0,
+            "supermarket_category": {"id": 4, "name": "Produce"},
+            "recipe": {"id": 5, "name": "Tomato Sauce"}
+        });
+        let request = payload(Resource::Food, &food, &ids(), &mut Vec::new()).unwrap();
+        assert_eq!(
+            request,
+            json!({"name": "Tomato", "supermarket_category": {"name": "Produce"}})
+        );
+    }
+
+    #[test]
+    fn test_payload_remaps_meal_plan() {
+        let plan = json!({
+            "id": 1, "title": "", "servings": 2.0, "from_date": "2026-10-19T00:00:00+02:00",
+            "recipe": {"id": 5, "name": "Pancakes"}, "meal_type": {"id": 2, "name": "Breakfast"},
+            "shared": [{"id": 1}]
+        });
+        let request = payload(Resource::MealPlan, &plan, &ids(), &mut Vec::new()).unwrap();
+        assert_eq!(request["recipe"], 105);
+        assert_eq!(request["meal_type"], 102);
+        assert!(request.get("shared").is_none());
+
+        let note_only = json!({"id": 2, "title": "Eat out", "recipe": null, "meal_type": 2});
+        let request = payload(Resource::MealPlan, &note_only, &ids(), &mut Vec::new()).unwrap();
+        assert_eq!(request["recipe"], Value::Null);
+    }
+
+    #[test]
+    fn test_payload_fails_on_missing_reference() {
+        let entry = json!({"id": 8, "recipe_book": 3, "recipe": 6});
+        assert_eq!(
+            payload(Resource::RecipeBookEntry, &entry, &ids(), &mut Vec::new()),
+            Err("recipe 6 was not restored".to_string())
+        );
+        let entry = json!({"id": 8, "book": 3, "recipe": 5});
+        let request = payload(Resource::RecipeBookEntry, &entry, &ids(), &mut Vec::new()).unwrap();
+        assert_eq!(request, json!({"book": 103, "recipe": 105}));
+    }
+
+    #[test]
+    fn test_payload_remaps_step_recipes() {
+        let recipe = json!({
+            "id": 7, "name": "Pancake Breakfast",
+            "steps": [
+                {"id": 70, "instruction": "Make pancakes", "step_recipe": 5, "ingredients": []},
+                {"id": 71, "instruction": "Make jam", "step_recipe": 6, "ingredients": []}
+            ]
+        });
+        let mut warnings = Vec::new();
+        let request = payload(Resource::Recipe, &recipe, &ids(), &mut warnings).unwrap();
+        assert_eq!(request["steps"][0]["step_recipe"], 105);
+        assert_eq!(request["steps"][1]["step_recipe"], Value::Null);
+        assert!(request["steps"][0].get("id").is_none());
+        assert_eq!(warnings.len(), 1);
+
+        let plain = json!({"id": 5, "steps": [{"step_recipe": null}]});
+        let records = [recipe.clone(), plain.clone()];
+        assert_eq!(ordered(Resource::Recipe, &records), [&plain, &recipe]);
+    }
+}
@@ -0,0 +1,158 @@
+//! Taking a backup
+//!
+//! Records are kept exactly as the API returns them, so a backup holds
+//! everything the instance exposes, not just the fields this crate models.
+//! Recipes are listed, then fetched one by one for their steps and
+//! ingredients.
+
+use super::archive::{Backup, RecipeImageFile};
+use super::{
+    list_all, record_id, BackupError, FailedRecord, Manifest, Resource, FORMAT, FORMAT_VERSION,
+};
+use crate::tandoor::TandoorClient;
+use serde::Serialize;
+use serde_json::Value;
+use std::collections::BTreeMap;
+
+/// Image extensions kept as they are; anything else is saved as `.jpg`
+const IMAGE_EXTENSIONS: &[&str] = &["jpg", "jpeg", "png", "gif", "webp"];
+
+/// Backup outcome
+#[derive(Debug, Default, Serialize)]
+pub struct BackupReport {
+    /// Archive format version
+    pub version: u32,
+    /// Records per resource
+    pub counts: BTreeMap<Resource, usize>,
+    /// Recipe images downloaded
+    pub images: usize,
+    /// Recipes and images that could not be fetched
+    pub failed: Vec<FailedRecord>,
+}
+
+/// Back up every resource (and, with `images`, recipe images)
+///
+/// A recipe or image that can't be fetched is reported and left out;
+/// failing to list a resource aborts the backup.
+pub fn create_backup(
+    client: &TandoorClient,
+    images: bool,
+) -> Result<(Backup, BackupReport), BackupError> {
+    let mut backup = Backup::default();
+    let mut failed = Vec::new();
+
+    for resource in Resource::ALL {
+        let mut records = list_all(client, resource)?;
+        if resource == Resource::Recipe {
+            records = recipe_details(client, &records, &mut failed);
+        }
+        backup.records.insert(resource, records);
+    }
+    if images {
+        backup.images = recipe_images(client, backup.records(Resource::Recipe), &mut failed);
+    }
+
+    let counts: BTreeMap<Resource, usize> = backup
+        .records
+        .iter()
+        .map(|(resource, records)| (*resource, records.len()))
+        .collect();
+    backup.manifest = Manifest {
+        format: FORMAT.to_string(),
+        version: FORMAT_VERSION,
+        created_at: chrono::Utc::now().to_rfc3339(),
+        source: client.base_url().to_string(),
+        counts: counts.clone(),
+        images: backup.images.len(),
+    };
+    let report = BackupReport {
+        version: FORMAT_VERSION,
+        counts,
+        images: backup.images.len(),
+        failed,
+    };
+    Ok((backup, report))
+}
+
+/// Full recipes (with steps) for the listed ones
+fn recipe_details(
+    client: &TandoorClient,
+    summaries: &[Value],
+    failed: &mut Vec<FailedRecord>,
+) -> Vec<Value> {
+    summaries
+        .iter()
+        .filter_map(record_id)
+        .filter_map(|id| {
+            client
+                .get_recipe(id)
+                .map_err(|e| {
+                    failed.push(FailedRecord {
+                        resource: Resource::Recipe,
+                        id: Some(id),
+                        error: e.to_string(),
+                    });
+                })
+                .ok()
+        })
+        .collect()
+}
+
+/// Download each recipe's image
+fn recipe_images(
+    client: &TandoorClient,
+    recipes: &[Value],
+    failed: &mut Vec<FailedRecord>,
+) -> BTreeMap<i64, RecipeImageFile> {
+    let mut images = BTreeMap::new();
+    for recipe in recipes {
+        let Some(id) = record_id(recipe) else {
+            continue;
+        };
+        let Some(url) = recipe
+            .get("image")
+            .and_then(Value::as_str)
+            .filter(|url| !url.is_empty())
+        else {
+            continue;
+        };
+        match client.download_media(url) {
+            Ok(data) => {
+                let file_name = format!("{id}.{}", image_extension(url));
+                images.insert(id, RecipeImageFile { file_name, data });
+            }
+            Err(e) => failed.push(FailedRecord {
+                resource: Resource::Recipe,
+                id: Some(id),
+                error: format!("image: {e}"),
+            }),
+        }
+    }
+    images
+}
+
+/// Extension of an image URL, ignoring any query string
+fn image_extension(url: &str) -> String {
+    let path = url.split(['?', '#']).next().unwrap_or(url);
+    let file = path.rsplit('/').next().unwrap_or(path);
+    file.rsplit_once('.')
+        .map(|(_, extension)| extension.to_ascii_lowercase())
+        .filter(|extension| IMAGE_EXTENSIONS.contains(&extension.as_str()))
+        .unwrap_or_else(|| "jpg".to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_image_extension() {
+        assert_eq!(image_extension("/media/recipes/abc.PNG"), "png");
+        assert_eq!(
+            image_extension("https://cdn.example/r/42.webp?v=3#top"),
+            "webp"
+        );
+        assert_eq!(image_extension("/media/recipes/abc"), "jpg");
+        assert_eq!(image_extension("/media/recipes.v2/abc.exe"), "jpg");
+    }
+}
@@ -0,0 +1,319 @@
+//! Planned vs eaten nutrition adherence report
+//!
+//! Pulls Tandoor meal plans and the `FatSecret` food diary, exercise diary and
+//! profile, then reports calories in, calories out, net calories against the
+//! profile goal and the macro split against a target.
+//! This is a 3-legged OAuth request (requires user access token).
+//!
+//! JSON input (CLI arg or stdin):
+//!   `{"tandoor": {...}, "fatsecret": {...}, "access_token": "...", "access_secret": "...",
+//!     "date": "2024-01-15", "mode": "day",
+//!     "macro_target": {"carbohydrate_pct": 50, "protein_pct": 20, "fat_pct": 30}}`
+//!
+//! `mode` is `day` (default) or `week`. Week mode covers seven days starting at
+//! `date` and uses the diary and exercise month summaries.
+//!
+//! JSON stdout: `{"success": true, "mode": "day", "report": {...}, "markdown": "..."}`
+
+#![allow(clippy::exit, clippy::unwrap_used, clippy::expect_used)]
+
+use meal_planner::fatsecret::core::{AccessToken, FatSecretConfig};
+use meal_planner::fatsecret::diary::adherence::{
+    day_markdown, day_report, months_in_range, week_markdown, week_report, DayAdherence, DayData,
+    MacroSplit, WeekAdherence, WeekData, DEFAULT_MACRO_TARGET,
+};
+use meal_planner::fatsecret::diary::sync::plan_diary_entries;
+use meal_planner::fatsecret::diary::{
+    date_to_int, get_food_entries, get_month_summary, int_to_date,
+};
+use meal_planner::fatsecret::exercise::{get_exercise_entries, get_exercise_month_summary};
+use meal_planner::fatsecret::profile::get_profile;
+use meal_planner::tandoor::{MealPlansWithRecipes, TandoorClient, TandoorConfig};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::io::{self, Read};
+
+/// Days covered by week mode
+const WEEK_DAYS: i32 = 7;
+
+/// `FatSecret` resource (matches Windmill resource-fatsecret format)
+#[derive(Deserialize)]
+struct FatSecretResource {
+    consumer_key: String,
+    consumer_secret: String,
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "lowercase")]
+enum Mode {
+    #[default]
+    Day,
+    Week,
+}
+
+#[derive(Deserialize)]
+struct Input {
+    /// Tandoor configuration (URL and token)
+    tandoor: TandoorConfig,
+    /// `FatSecret` credentials (optional - falls back to env vars)
+    fatsecret: Option<FatSecretResource>,
+    /// OAuth access token (required for 3-legged requests)
+    access_token: String,
+    /// OAuth access secret (required for 3-legged requests)
+    access_secret: String,
+    /// Report day, or first day of the week (YYYY-MM-DD)
+    date: String,
+    /// Report mode
+    #[serde(default)]
+    mode: Mode,
+    /// Target macro split (defaults to 50/20/30 carbs/protein/fat)
+    #[serde(default)]
+    macro_target: Option<MacroSplit>,
+}
+
+#[derive(Serialize)]
+#[serde(untagged)]
+enum Report {
+    Day(Box<DayAdherence>),
+    Week(Box<WeekAdherence>),
+}
+
+#[derive(Serialize)]
+struct Output {
+    success: bool,
+    mode: Mode,
+    report: Report,
+    markdown: String,
+}
+
+#[derive(Serialize)]
+struct ErrorOutput {
+    success: bool,
+    error: String,
+}
+
+/// Everything fetched from `FatSecret` and Tandoor for a report
+struct Context {
+    config: FatSecretConfig,
+    token: AccessToken,
+    tandoor: TandoorConfig,
+    from_date_int: i32,
+    to_date_int: i32,
+    macro_target: MacroSplit,
+}
+
+#[tokio::main]
+async fn main() {
+    match run().await {
+        Ok(output) => {
+            println!(
+                "{}",
+                serde_json::to_string(&output).expect("Failed to serialize output JSON")
+            );
+        }
+        Err(e) => {
+            let error = ErrorOutput {
+                success: false,
+                error: e.to_string(),
+            };
+            println!(
+                "{}",
+                serde_json::to_string(&error).expect("Failed to serialize error JSON")
+            );
+            std::process::exit(1);
+        }
+    }
+}
+
+async fn run() -> Result<Output, Box<dyn std::error::Error>> {
+    let input = read_input()?;
+    let from_date_int = date_to_int(&input.date)?;
+    let to_date_int = match input.mode {
+        Mode::Day => from_date_int,
+        Mode::Week => from_date_int + WEEK_DAYS - 1,
+    };
+
+    // Get config: prefer input, fall back to environment
+    let config = match input.fatsecret {
+        Some(resource) => FatSecretConfig::new(resource.consumer_key, resource.consumer_secret)
+            .map_err(|e| format!("Invalid FatSecret credentials: {}", e))?,
+        None => FatSecretConfig::from_env().map_err(|e| format!("Invalid configuration: {}", e))?,
+    };
+    let context = Context {
+        config,
+        token: AccessToken::new(input.access_token, input.access_secret),
+        tandoor: input.tandoor,
+        from_date_int,
+        to_date_int,
+        macro_target: input.macro_target.unwrap_or(DEFAULT_MACRO_TARGET),
+    };
+
+    let (report, markdown) = match input.mode {
+        Mode::Day => {
+            let report = build_day_report(&context).await?;
+            let markdown = day_markdown(&report);
+            (Report::Day(Box::new(report)), markdown)
+        }
+        Mode::Week => {
+            let report = build_week_report(&context).await?;
+            let markdown = week_markdown(&report);
+            (Report::Week(Box::new(report)), markdown)
+        }
+    };
+
+    Ok(Output {
+        success: true,
+        mode: input.mode,
+        report,
+        markdown,
+    })
+}
+
+fn read_input() -> Result<Input, Box<dyn std::error::Error>> {
+    if let Some(arg) = std::env::args().nth(1) {
+        Ok(serde_json::from_str(&arg)?)
+    } else {
+        let mut input_str = String::new();
+        io::stdin().read_to_string(&mut input_str)?;
+        Ok(serde_json::from_str(&input_str)?)
+    }
+}
+
+/// Daily calorie goal from the `FatSecret` profile
+async fn calorie_goal(context: &Context) -> Result<Option<f64>, Box<dyn std::error::Error>> {
+    let profile = get_profile(&context.config, &context.token).await?;
+    Ok(profile
+        .calorie_goal
+        .and_then(|goal| i32::try_from(goal).ok())
+        .map(f64::from))
+}
+
+/// Fetch Tandoor plans and recipes for the report range off the async runtime
+async fn tandoor_data(
+    context: &Context,
+) -> Result<MealPlansWithRecipes, Box<dyn std::error::Error>> {
+    let tandoor = context.tandoor.clone();
+    let from_date = int_to_date(context.from_date_int)?;
+    let to_date = int_to_date(context.to_date_int)?;
+    Ok(
+        tokio::task::spawn_blocking(move || fetch_tandoor_data(&tandoor, &from_date, &to_date))
+            .await??,
+    )
+}
+
+/// Fetch every meal plan in range plus the full recipe for each planned recipe
+fn fetch_tandoor_data(
+    tandoor: &TandoorConfig,
+    from_date: &str,
+    to_date: &str,
+) -> Result<MealPlansWithRecipes, String> {
+    TandoorClient::new(tandoor)
+        .and_then(|client| client.get_meal_plans_with_recipes(from_date, to_date))
+        .map_err(|e| e.to_string())
+}
+
+async fn build_day_report(context: &Context) -> Result<DayAdherence, Box<dyn std::error::Error>> {
+    let date_int = context.from_date_int;
+    let data = tandoor_data(context).await?;
+    let (planned, skipped) = plan_diary_entries(
+        &data.plans,
+        &data.recipes,
+        &HashMap::new(),
+        date_int,
+        date_int,
+    );
+
+    let food_entries = get_food_entries(&context.config, &context.token, date_int).await?;
+    let calories_out = get_exercise_entries(&context.config, &context.token, date_int)
+        .await?
+        .iter()
+        .map(|entry| entry.calories)
+        .sum();
+
+    Ok(day_report(&DayData {
+        date_int,
+        planned: &planned,
+        skipped: &skipped,
+        food_entries: &food_entries,
+        calories_out,
+        calorie_goal: calorie_goal(context).await?,
+        macro_target: context.macro_target,
+    })?)
+}
+
+async fn build_week_report(context: &Context) -> Result<WeekAdherence, Box<dyn std::error::Error>> {
+    let data = tandoor_data(context).await?;
+    let (planned, _) = plan_diary_entries(
+        &data.plans,
+        &data.recipes,
+        &HashMap::new(),
+        context.from_date_int,
+        context.to_date_int,
+    );
+
+    let mut diary_days = Vec::new();
+    let mut exercise_days = Vec::new();
+    for month in months_in_range(context.from_date_int, context.to_date_int)? {
+        diary_days.extend(
+            get_month_summary(&context.config, &context.token, month.date_int)
+                .await?
+                .days,
+        );
+        exercise_days.extend(
+            get_exercise_month_summary(&context.config, &context.token, month.year, month.month)
+                .await?
+                .days,
+        );
+    }
+
+    Ok(week_report(&WeekData {
+        from_date_int: context.from_date_int,
+        to_date_int: context.to_date_int,
+        planned: &planned,
+        diary_days: &diary_days,
+        exercise_days: &exercise_days,
+        calorie_goal: calorie_goal(context).await?,
+        macro_target: context.macro_target,
+    })?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_input_parsing_defaults() {
+        let json = r#"{"tandoor": {"base_url": "http://localhost:8090", "api_token": "test"}, "access_token": "t", "access_secret": "s", "date": "2024-01-15"}"#;
+        let input: Input = serde_json::from_str(json).expect("Failed to parse test JSON");
+        assert_eq!(input.date, "2024-01-15");
+        assert_eq!(input.mode, Mode::Day);
+        assert!(input.fatsecret.is_none());
+        assert!(input.macro_target.is_none());
+    }
+
+    #[test]
+    fn test_input_parsing_week_with_target() {
+        let json = r#"{"tandoor": {"base_url": "http://localhost:8090", "api_token": "test"}, "fatsecret": {"consumer_key": "k", "consumer_secret": "s"}, "access_token": "t", "access_secret": "s", "date": "2024-01-15", "mode": "week", "macro_target": {"carbohydrate_pct": 40, "protein_pct": 30, "fat_pct": 30}}"#;
+        let input: Input = serde_json::from_str(json).expect("Failed to parse test JSON");
+        assert_eq!(input.mode, Mode::Week);
+        let target = input.macro_target.expect("macro_target should parse");
+        assert!((target.protein_pct - 30.0).abs() < f64::EPSILON);
+    }
+
+    #[test]
+    fn test_invalid_mode_rejected() {
+        let json = r#"{"tandoor": {"base_url": "http://localhost:8090", "api_token": "test"}, "access_token": "t", "access_secret": "s", "date": "2024-01-15", "mode": "month"}"#;
+        assert!(serde_json::from_str::<Input>(json).is_err());
+    }
+
+    #[test]
+    fn test_error_output_serialize() {
+        let error = ErrorOutput {
+            success: false,
+            error: "Invalid date format".to_string(),
+        };
+        let json = serde_json::to_string(&error).expect("Failed to serialize error JSON");
+        assert!(json.contains(r#""success":false"#));
+        assert!(json.contains("Invalid date format"));
+    }
+}
@@ -0,0 +1,159 @@
+//! List `FatSecret` food brands
+//!
+//! Returns brands whose name starts with the given characters, optionally
+//! filtered by brand type. Useful for brand autocomplete and for choosing a
+//! brand to filter `foods_search` results by.
+//! This is a 2-legged OAuth request (no user token required, `premier` scope).
+//!
+//! JSON input (CLI arg or stdin):
+//!   `{"fatsecret": {...}, "starts_with": "Kel", "brand_type": "manufacturer",
+//!     "region": "US", "language": "en"}`
+//!
+//! `brand_type` is `manufacturer`, `restaurant` or `supermarket`.
+//!
+//! JSON stdout: `{"success": true, "brands": [{"brand_id": "...", "brand_name": "...", ...}]}`
+
+#![allow(clippy::exit, clippy::unwrap_used, clippy::expect_used)]
+
+use meal_planner::fatsecret::core::FatSecretConfig;
+use meal_planner::fatsecret::foods::{get_food_brands, Brand, BrandType};
+use serde::{Deserialize, Serialize};
+use std::io::{self, Read};
+
+/// `FatSecret` resource (matches Windmill resource-fatsecret format)
+#[derive(Deserialize)]
+struct FatSecretResource {
+    consumer_key: String,
+    consumer_secret: String,
+}
+
+#[derive(Deserialize)]
+struct Input {
+    /// `FatSecret` credentials (optional - falls back to env vars)
+    fatsecret: Option<FatSecretResource>,
+    /// Leading characters of the brand name
+    starts_with: String,
+    /// Optional brand type filter
+    #[serde(default)]
+    brand_type: Option<BrandType>,
+    /// Optional ISO region code (e.g., "US")
+    #[serde(default)]
+    region: Option<String>,
+    /// Optional ISO language code (e.g., "en")
+    #[serde(default)]
+    language: Option<String>,
+}
+
+#[derive(Serialize)]
+struct Output {
+    success: bool,
+    brands: Vec<Brand>,
+}
+
+#[derive(Serialize)]
+struct ErrorOutput {
+    success: bool,
+    error: String,
+}
+
+#[tokio::main]
+async fn main() {
+    match run().await {
+        Ok(output) => {
+            println!(
+                "{}",
+                serde_json::to_string(&output).expect("Failed to serialize output JSON")
+            );
+        }
+        Err(e) => {
+            let error = ErrorOutput {
+                success: false,
+                error: e.to_string(),
+            };
+            println!(
+                "{}",
+                serde_json::to_string(&error).expect("Failed to serialize error JSON")
+            );
+            std::process::exit(1);
+        }
+    }
+}
+
+async fn run() -> Result<Output, Box<dyn std::error::Error>> {
+    let input = read_input()?;
+    if input.starts_with.trim().is_empty() {
+        return Err("starts_with must not be empty".into());
+    }
+
+    // Get config: prefer input, fall back to environment
+    let config = match input.fatsecret {
+        Some(resource) => FatSecretConfig::new(resource.consumer_key, resource.consumer_secret)
+            .map_err(|e| format!("Invalid FatSecret credentials: {}", e))?,
+        None => FatSecretConfig::from_env().map_err(|e| format!("Invalid configuration: {}", e))?,
+    };
+
+    let response = get_food_brands(
+        &config,
+        input.starts_with.trim(),
+        input.brand_type,
+        input.region.as_deref(),
+        input.language.as_deref(),
+    )
+    .await?;
+
+    Ok(Output {
+        success: true,
+        brands: response.brands,
+    })
+}
+
+fn read_input() -> Result<Input, Box<dyn std::error::Error>> {
+    if let Some(arg) = std::env::args().nth(1) {
+        Ok(serde_json::from_str(&arg)?)
+    } else {
+        let mut input_str = String::new();
+        io::stdin().read_to_string(&mut input_str)?;
+        Ok(serde_json::from_str(&input_str)?)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_input_parsing_defaults() {
+        let json = r#"{"starts_with": "Kel"}"#;
+        let input: Input = serde_json::from_str(json).expect("Failed to parse test JSON");
+        assert!(input.fatsecret.is_none());
+        assert_eq!(input.starts_with, "Kel");
+        assert!(input.brand_type.is_none());
+        assert!(input.region.is_none());
+        assert!(input.language.is_none());
+    }
+
+    #[test]
+    fn test_input_parsing_full() {
+        let json = r#"{"fatsecret": {"consumer_key": "k", "consumer_secret": "s"}, "starts_with": "Mc", "brand_type": "restaurant", "region": "US", "language": "en"}"#;
+        let input: Input = serde_json::from_str(json).expect("Failed to parse test JSON");
+        assert_eq!(input.brand_type, Some(BrandType::Restaurant));
+        assert_eq!(input.region.as_deref(), Some("US"));
+        assert_eq!(input.language.as_deref(), Some("en"));
+    }
+
+    #[test]
+    fn test_invalid_brand_type_rejected() {
+        let json = r#"{"starts_with": "Kel", "brand_type": "bakery"}"#;
+        assert!(serde_json::from_str::<Input>(json).is_err());
+    }
+
+    #[test]
+    fn test_output_serialize_empty() {
+        let output = Output {
+            success: true,
+            brands: Vec::new(),
+        };
+        let json = serde_json::to_string(&output).expect("Failed to serialize output JSON");
+        assert_eq!(json, r#"{"success":true,"brands":[]}"#);
+    }
+}
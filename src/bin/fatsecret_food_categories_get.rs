@@ -0,0 +1,142 @@
+//! List `FatSecret` food categories
+//!
+//! Returns the top-level food categories (e.g., "Fruits", "Breads & Cereals").
+//! Use a category ID with `food_sub_categories_get` to drill down.
+//! This is a 2-legged OAuth request (no user token required, `premier` scope).
+//!
+//! JSON input (CLI arg or stdin):
+//!   `{"fatsecret": {...}, "region": "US", "language": "en"}`
+//!
+//! JSON stdout: `{"success": true, "categories": [{"food_category_id": "...", ...}]}`
+
+#![allow(clippy::exit, clippy::unwrap_used, clippy::expect_used)]
+
+use meal_planner::fatsecret::core::FatSecretConfig;
+use meal_planner::fatsecret::foods::{get_food_categories, FoodCategory};
+use serde::{Deserialize, Serialize};
+use std::io::{self, Read};
+
+/// `FatSecret` resource (matches Windmill resource-fatsecret format)
+#[derive(Deserialize)]
+struct FatSecretResource {
+    consumer_key: String,
+    consumer_secret: String,
+}
+
+#[derive(Deserialize)]
+struct Input {
+    /// `FatSecret` credentials (optional - falls back to env vars)
+    fatsecret: Option<FatSecretResource>,
+    /// Optional ISO region code (e.g., "US")
+    #[serde(default)]
+    region: Option<String>,
+    /// Optional ISO language code (e.g., "en")
+    #[serde(default)]
+    language: Option<String>,
+}
+
+#[derive(Serialize)]
+struct Output {
+    success: bool,
+    categories: Vec<FoodCategory>,
+}
+
+#[derive(Serialize)]
+struct ErrorOutput {
+    success: bool,
+    error: String,
+}
+
+#[tokio::main]
+async fn main() {
+    match run().await {
+        Ok(output) => {
+            println!(
+                "{}",
+                serde_json::to_string(&output).expect("Failed to serialize output JSON")
+            );
+        }
+        Err(e) => {
+            let error = ErrorOutput {
+                success: false,
+                error: e.to_string(),
+            };
+            println!(
+                "{}",
+                serde_json::to_string(&error).expect("Failed to serialize error JSON")
+            );
+            std::process::exit(1);
+        }
+    }
+}
+
+async fn run() -> Result<Output, Box<dyn std::error::Error>> {
+    let input = read_input()?;
+
+    // Get config: prefer input, fall back to environment
+    let config = match input.fatsecret {
+        Some(resource) => FatSecretConfig::new(resource.consumer_key, resource.consumer_secret)
+            .map_err(|e| format!("Invalid FatSecret credentials: {}", e))?,
+        None => FatSecretConfig::from_env().map_err(|e| format!("Invalid configuration: {}", e))?,
+    };
+
+    let response =
+        get_food_categories(&config, input.region.as_deref(), input.language.as_deref()).await?;
+
+    Ok(Output {
+        success: true,
+        categories: response.categories,
+    })
+}
+
+fn read_input() -> Result<Input, Box<dyn std::error::Error>> {
+    if let Some(arg) = std::env::args().nth(1) {
+        Ok(serde_json::from_str(&arg)?)
+    } else {
+        let mut input_str = String::new();
+        io::stdin().read_to_string(&mut input_str)?;
+        Ok(serde_json::from_str(&input_str)?)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_input_parsing_defaults() {
+        let input: Input = serde_json::from_str("{}").expect("Failed to parse test JSON");
+        assert!(input.fatsecret.is_none());
+        assert!(input.region.is_none());
+        assert!(input.language.is_none());
+    }
+
+    #[test]
+    fn test_input_parsing_full() {
+        let json = r#"{"fatsecret": {"consumer_key": "k", "consumer_secret": "s"}, "region": "GB", "language": "en"}"#;
+        let input: Input = serde_json::from_str(json).expect("Failed to parse test JSON");
+        assert!(input.fatsecret.is_some());
+        assert_eq!(input.region.as_deref(), Some("GB"));
+        assert_eq!(input.language.as_deref(), Some("en"));
+    }
+
+    #[test]
+    fn test_invalid_input_rejected() {
+        assert!(serde_json::from_str::<Input>(r#"{"region": 1}"#).is_err());
+    }
+
+    #[test]
+    fn test_output_serialize() {
+        let categories: Vec<FoodCategory> = serde_json::from_str(
+            r#"[{"food_category_id": "1", "food_category_name": "Fruits", "food_category_description": "Fresh and dried fruits"}]"#,
+        )
+        .expect("Failed to parse test JSON");
+        let output = Output {
+            success: true,
+            categories,
+        };
+        let json = serde_json::to_string(&output).expect("Failed to serialize output JSON");
+        assert!(json.contains(r#""food_category_id":"1""#));
+        assert!(json.contains(r#""food_category_name":"Fruits""#));
+    }
+}
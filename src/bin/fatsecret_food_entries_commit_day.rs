@@ -0,0 +1,156 @@
+//! Commit a `FatSecret` food diary day
+//!
+//! Marks a day's diary as complete (`food_entry.commit_day`).
+//! This is a 3-legged OAuth request (requires user access token).
+//!
+//! JSON input (CLI arg or stdin):
+//!   `{"fatsecret": {...}, "access_token": "...", "access_secret": "...", "date": "2024-01-15"}`
+//!
+//! `date` defaults to today.
+//!
+//! JSON stdout: `{"success": true, "date_int": 19737}`
+
+#![allow(clippy::exit, clippy::unwrap_used, clippy::expect_used)]
+
+use meal_planner::fatsecret::core::{AccessToken, FatSecretConfig};
+use meal_planner::fatsecret::diary::{commit_day, date_to_int};
+use serde::{Deserialize, Serialize};
+use std::io::{self, Read};
+
+/// `FatSecret` resource (matches Windmill resource-fatsecret format)
+#[derive(Deserialize)]
+struct FatSecretResource {
+    consumer_key: String,
+    consumer_secret: String,
+}
+
+#[derive(Deserialize)]
+struct Input {
+    /// `FatSecret` credentials (optional - falls back to env vars)
+    fatsecret: Option<FatSecretResource>,
+    /// OAuth access token
+    access_token: String,
+    /// OAuth access token secret
+    access_secret: String,
+    /// Day to commit (YYYY-MM-DD, defaults to today)
+    #[serde(default)]
+    date: Option<String>,
+}
+
+#[derive(Serialize)]
+struct Output {
+    success: bool,
+    date_int: i32,
+}
+
+#[derive(Serialize)]
+struct ErrorOutput {
+    success: bool,
+    error: String,
+}
+
+#[tokio::main]
+async fn main() {
+    match run().await {
+        Ok(output) => {
+            println!(
+                "{}",
+                serde_json::to_string(&output).expect("Failed to serialize output JSON")
+            );
+        }
+        Err(e) => {
+            let error = ErrorOutput {
+                success: false,
+                error: e.to_string(),
+            };
+            println!(
+                "{}",
+                serde_json::to_string(&error).expect("Failed to serialize error JSON")
+            );
+            std::process::exit(1);
+        }
+    }
+}
+
+async fn run() -> Result<Output, Box<dyn std::error::Error>> {
+    let input = read_input()?;
+    let date_int = diary_date_int(input.date.as_deref())?;
+
+    // Get config: prefer input, fall back to environment
+    let config = match input.fatsecret {
+        Some(resource) => FatSecretConfig::new(resource.consumer_key, resource.consumer_secret)
+            .map_err(|e| format!("Invalid FatSecret credentials: {}", e))?,
+        None => FatSecretConfig::from_env().map_err(|e| format!("Invalid configuration: {}", e))?,
+    };
+
+    let token = AccessToken::new(input.access_token, input.access_secret);
+    commit_day(&config, &token, date_int).await?;
+
+    Ok(Output {
+        success: true,
+        date_int,
+    })
+}
+
+fn read_input() -> Result<Input, Box<dyn std::error::Error>> {
+    if let Some(arg) = std::env::args().nth(1) {
+        Ok(serde_json::from_str(&arg)?)
+    } else {
+        let mut input_str = String::new();
+        io::stdin().read_to_string(&mut input_str)?;
+        Ok(serde_json::from_str(&input_str)?)
+    }
+}
+
+/// Diary date: the given date or today
+fn diary_date_int(date: Option<&str>) -> Result<i32, String> {
+    date.map_or_else(
+        || {
+            date_to_int(
+                &chrono::Local::now()
+                    .date_naive()
+                    .format("%Y-%m-%d")
+                    .to_string(),
+            )
+        },
+        date_to_int,
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_input_parsing() {
+        let json = r#"{"fatsecret": {"consumer_key": "k", "consumer_secret": "s"}, "access_token": "t", "access_secret": "s", "date": "2024-01-15"}"#;
+        let input: Input = serde_json::from_str(json).expect("Failed to parse test JSON");
+        assert!(input.fatsecret.is_some());
+        assert_eq!(diary_date_int(input.date.as_deref()), Ok(19737));
+    }
+
+    #[test]
+    fn test_date_defaults_to_today() {
+        let json = r#"{"access_token": "t", "access_secret": "s"}"#;
+        let input: Input = serde_json::from_str(json).expect("Failed to parse test JSON");
+        assert!(input.date.is_none());
+        assert!(diary_date_int(None).is_ok());
+    }
+
+    #[test]
+    fn test_invalid_date() {
+        assert!(diary_date_int(Some("2024-13-01")).is_err());
+        let json = r#"{"access_token": "t"}"#;
+        assert!(serde_json::from_str::<Input>(json).is_err());
+    }
+
+    #[test]
+    fn test_output_serialize() {
+        let output = Output {
+            success: true,
+            date_int: 19737,
+        };
+        let json = serde_json::to_string(&output).expect("Failed to serialize output JSON");
+        assert_eq!(json, r#"{"success":true,"date_int":19737}"#);
+    }
+}
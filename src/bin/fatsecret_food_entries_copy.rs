@@ -0,0 +1,172 @@
+//! Copy `FatSecret` food diary entries between days
+//!
+//! Copies every food entry from one day to another (`food_entry.copy`).
+//! This is a 3-legged OAuth request (requires user access token).
+//!
+//! JSON input (CLI arg or stdin):
+//!   `{"fatsecret": {...}, "access_token": "...", "access_secret": "...",
+//!     "from_date": "2024-01-14", "to_date": "2024-01-15"}`
+//!
+//! `to_date` defaults to today.
+//!
+//! JSON stdout: `{"success": true, "from_date_int": 19736, "to_date_int": 19737}`
+
+#![allow(clippy::exit, clippy::unwrap_used, clippy::expect_used)]
+
+use meal_planner::fatsecret::core::{AccessToken, FatSecretConfig};
+use meal_planner::fatsecret::diary::{copy_entries, date_to_int};
+use serde::{Deserialize, Serialize};
+use std::io::{self, Read};
+
+/// `FatSecret` resource (matches Windmill resource-fatsecret format)
+#[derive(Deserialize)]
+struct FatSecretResource {
+    consumer_key: String,
+    consumer_secret: String,
+}
+
+#[derive(Deserialize)]
+struct Input {
+    /// `FatSecret` credentials (optional - falls back to env vars)
+    fatsecret: Option<FatSecretResource>,
+    /// OAuth access token
+    access_token: String,
+    /// OAuth access token secret
+    access_secret: String,
+    /// Day to copy from (YYYY-MM-DD)
+    from_date: String,
+    /// Day to copy to (YYYY-MM-DD, defaults to today)
+    #[serde(default)]
+    to_date: Option<String>,
+}
+
+#[derive(Serialize)]
+struct Output {
+    success: bool,
+    from_date_int: i32,
+    to_date_int: i32,
+}
+
+#[derive(Serialize)]
+struct ErrorOutput {
+    success: bool,
+    error: String,
+}
+
+#[tokio::main]
+async fn main() {
+    match run().await {
+        Ok(output) => {
+            println!(
+                "{}",
+                serde_json::to_string(&output).expect("Failed to serialize output JSON")
+            );
+        }
+        Err(e) => {
+            let error = ErrorOutput {
+                success: false,
+                error: e.to_string(),
+            };
+            println!(
+                "{}",
+                serde_json::to_string(&error).expect("Failed to serialize error JSON")
+            );
+            std::process::exit(1);
+        }
+    }
+}
+
+async fn run() -> Result<Output, Box<dyn std::error::Error>> {
+    let input = read_input()?;
+    let (from_date_int, to_date_int) = copy_dates(&input.from_date, input.to_date.as_deref())?;
+
+    // Get config: prefer input, fall back to environment
+    let config = match input.fatsecret {
+        Some(resource) => FatSecretConfig::new(resource.consumer_key, resource.consumer_secret)
+            .map_err(|e| format!("Invalid FatSecret credentials: {}", e))?,
+        None => FatSecretConfig::from_env().map_err(|e| format!("Invalid configuration: {}", e))?,
+    };
+
+    let token = AccessToken::new(input.access_token, input.access_secret);
+    copy_entries(&config, &token, from_date_int, to_date_int).await?;
+
+    Ok(Output {
+        success: true,
+        from_date_int,
+        to_date_int,
+    })
+}
+
+fn read_input() -> Result<Input, Box<dyn std::error::Error>> {
+    if let Some(arg) = std::env::args().nth(1) {
+        Ok(serde_json::from_str(&arg)?)
+    } else {
+        let mut input_str = String::new();
+        io::stdin().read_to_string(&mut input_str)?;
+        Ok(serde_json::from_str(&input_str)?)
+    }
+}
+
+/// Source and target date_int; the target defaults to today
+fn copy_dates(from_date: &str, to_date: Option<&str>) -> Result<(i32, i32), String> {
+    let from_date_int = date_to_int(from_date)?;
+    let to_date_int = to_date.map_or_else(
+        || {
+            date_to_int(
+                &chrono::Local::now()
+                    .date_naive()
+                    .format("%Y-%m-%d")
+                    .to_string(),
+            )
+        },
+        date_to_int,
+    )?;
+    if from_date_int == to_date_int {
+        return Err("from_date and to_date must be different days".to_string());
+    }
+    Ok((from_date_int, to_date_int))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_input_parsing() {
+        let json = r#"{"fatsecret": {"consumer_key": "k", "consumer_secret": "s"}, "access_token": "t", "access_secret": "s", "from_date": "2024-01-14", "to_date": "2024-01-15"}"#;
+        let input: Input = serde_json::from_str(json).expect("Failed to parse test JSON");
+        assert!(input.fatsecret.is_some());
+        assert_eq!(
+            copy_dates(&input.from_date, input.to_date.as_deref()),
+            Ok((19736, 19737))
+        );
+    }
+
+    #[test]
+    fn test_to_date_defaults_to_today() {
+        let json = r#"{"access_token": "t", "access_secret": "s", "from_date": "2024-01-14"}"#;
+        let input: Input = serde_json::from_str(json).expect("Failed to parse test JSON");
+        assert!(input.to_date.is_none());
+        assert!(copy_dates(&input.from_date, None).is_ok());
+    }
+
+    #[test]
+    fn test_rejects_same_or_invalid_dates() {
+        assert!(copy_dates("2024-01-14", Some("2024-01-14")).is_err());
+        assert!(copy_dates("14/01/2024", Some("2024-01-15")).is_err());
+        let json = r#"{"access_token": "t", "access_secret": "s"}"#;
+        assert!(serde_json::from_str::<Input>(json).is_err());
+    }
+
+    #[test]
+    fn test_output_serialize() {
+        let output = Output {
+            success: true,
+            from_date_int: 19736,
+            to_date_int: 19737,
+        };
+        let json = serde_json::to_string(&output).expect("Failed to serialize output JSON");
+        assert!(json.contains(r#""success":true"#));
+        assert!(json.contains(r#""to_date_int":19737"#));
+    }
+}
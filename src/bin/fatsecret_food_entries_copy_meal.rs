@@ -0,0 +1,192 @@
+//! Copy one meal of a `FatSecret` food diary day to another day or meal
+//!
+//! Copies the entries of a single meal (`food_entry.copy_meal`), e.g.
+//! yesterday's breakfast to today's breakfast or today's lunch.
+//! This is a 3-legged OAuth request (requires user access token).
+//!
+//! JSON input (CLI arg or stdin):
+//!   `{"fatsecret": {...}, "access_token": "...", "access_secret": "...",
+//!     "from_date": "2024-01-14", "from_meal": "breakfast",
+//!     "to_date": "2024-01-15", "to_meal": "breakfast"}`
+//!
+//! `to_date` defaults to today and `to_meal` to `from_meal`.
+//!
+//! JSON stdout: `{"success": true, "from_date_int": 19736, "from_meal": "breakfast",
+//!   "to_date_int": 19737, "to_meal": "breakfast"}`
+
+#![allow(clippy::exit, clippy::unwrap_used, clippy::expect_used)]
+
+use meal_planner::fatsecret::core::{AccessToken, FatSecretConfig};
+use meal_planner::fatsecret::diary::{copy_meal, date_to_int, MealType};
+use serde::{Deserialize, Serialize};
+use std::io::{self, Read};
+
+/// `FatSecret` resource (matches Windmill resource-fatsecret format)
+#[derive(Deserialize)]
+struct FatSecretResource {
+    consumer_key: String,
+    consumer_secret: String,
+}
+
+#[derive(Deserialize)]
+struct Input {
+    /// `FatSecret` credentials (optional - falls back to env vars)
+    fatsecret: Option<FatSecretResource>,
+    /// OAuth access token
+    access_token: String,
+    /// OAuth access token secret
+    access_secret: String,
+    /// Day to copy from (YYYY-MM-DD)
+    from_date: String,
+    /// Meal to copy: breakfast, lunch, dinner, or other
+    from_meal: MealType,
+    /// Day to copy to (YYYY-MM-DD, defaults to today)
+    #[serde(default)]
+    to_date: Option<String>,
+    /// Meal to copy into (defaults to `from_meal`)
+    #[serde(default)]
+    to_meal: Option<MealType>,
+}
+
+#[derive(Serialize)]
+struct Output {
+    success: bool,
+    from_date_int: i32,
+    from_meal: MealType,
+    to_date_int: i32,
+    to_meal: MealType,
+}
+
+#[derive(Serialize)]
+struct ErrorOutput {
+    success: bool,
+    error: String,
+}
+
+#[tokio::main]
+async fn main() {
+    match run().await {
+        Ok(output) => {
+            println!(
+                "{}",
+                serde_json::to_string(&output).expect("Failed to serialize output JSON")
+            );
+        }
+        Err(e) => {
+            let error = ErrorOutput {
+                success: false,
+                error: e.to_string(),
+            };
+            println!(
+                "{}",
+                serde_json::to_string(&error).expect("Failed to serialize error JSON")
+            );
+            std::process::exit(1);
+        }
+    }
+}
+
+async fn run() -> Result<Output, Box<dyn std::error::Error>> {
+    let input = read_input()?;
+    let from_date_int = date_to_int(&input.from_date)?;
+    let to_date_int = diary_date_int(input.to_date.as_deref())?;
+    let to_meal = input.to_meal.unwrap_or(input.from_meal);
+    if from_date_int == to_date_int && to_meal == input.from_meal {
+        return Err("Cannot copy a meal onto itself".into());
+    }
+
+    // Get config: prefer input, fall back to environment
+    let config = match input.fatsecret {
+        Some(resource) => FatSecretConfig::new(resource.consumer_key, resource.consumer_secret)
+            .map_err(|e| format!("Invalid FatSecret credentials: {}", e))?,
+        None => FatSecretConfig::from_env().map_err(|e| format!("Invalid configuration: {}", e))?,
+    };
+
+    let token = AccessToken::new(input.access_token, input.access_secret);
+    copy_meal(
+        &config,
+        &token,
+        from_date_int,
+        input.from_meal,
+        to_date_int,
+        to_meal,
+    )
+    .await?;
+
+    Ok(Output {
+        success: true,
+        from_date_int,
+        from_meal: input.from_meal,
+        to_date_int,
+        to_meal,
+    })
+}
+
+fn read_input() -> Result<Input, Box<dyn std::error::Error>> {
+    if let Some(arg) = std::env::args().nth(1) {
+        Ok(serde_json::from_str(&arg)?)
+    } else {
+        let mut input_str = String::new();
+        io::stdin().read_to_string(&mut input_str)?;
+        Ok(serde_json::from_str(&input_str)?)
+    }
+}
+
+/// Diary date: the given date or today
+fn diary_date_int(date: Option<&str>) -> Result<i32, String> {
+    date.map_or_else(
+        || {
+            date_to_int(
+                &chrono::Local::now()
+                    .date_naive()
+                    .format("%Y-%m-%d")
+                    .to_string(),
+            )
+        },
+        date_to_int,
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_input_parsing() {
+        let json = r#"{"fatsecret": {"consumer_key": "k", "consumer_secret": "s"}, "access_token": "t", "access_secret": "s", "from_date": "2024-01-14", "from_meal": "breakfast", "to_date": "2024-01-15", "to_meal": "other"}"#;
+        let input: Input = serde_json::from_str(json).expect("Failed to parse test JSON");
+        assert_eq!(input.from_meal, MealType::Breakfast);
+        assert_eq!(input.to_meal, Some(MealType::Snack));
+        assert_eq!(diary_date_int(input.to_date.as_deref()), Ok(19737));
+    }
+
+    #[test]
+    fn test_input_parsing_defaults() {
+        let json = r#"{"access_token": "t", "access_secret": "s", "from_date": "2024-01-14", "from_meal": "lunch"}"#;
+        let input: Input = serde_json::from_str(json).expect("Failed to parse test JSON");
+        assert!(input.fatsecret.is_none());
+        assert!(input.to_date.is_none());
+        assert!(input.to_meal.is_none());
+        assert!(diary_date_int(None).is_ok());
+    }
+
+    #[test]
+    fn test_input_rejects_unknown_meal() {
+        let json = r#"{"access_token": "t", "access_secret": "s", "from_date": "2024-01-14", "from_meal": "brunch"}"#;
+        assert!(serde_json::from_str::<Input>(json).is_err());
+    }
+
+    #[test]
+    fn test_output_serialize() {
+        let output = Output {
+            success: true,
+            from_date_int: 19736,
+            from_meal: MealType::Breakfast,
+            to_date_int: 19737,
+            to_meal: MealType::Snack,
+        };
+        let json = serde_json::to_string(&output).expect("Failed to serialize output JSON");
+        assert!(json.contains(r#""from_meal":"breakfast""#));
+        assert!(json.contains(r#""to_meal":"other""#));
+    }
+}
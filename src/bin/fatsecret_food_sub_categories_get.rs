@@ -0,0 +1,150 @@
+//! List `FatSecret` food sub-categories
+//!
+//! Returns the sub-categories of a food category (e.g., "Berries" under "Fruits").
+//! Sub-category names can be passed as `category` to `foods_search`.
+//! This is a 2-legged OAuth request (no user token required, `premier` scope).
+//!
+//! JSON input (CLI arg or stdin):
+//!   `{"fatsecret": {...}, "food_category_id": "1", "region": "US", "language": "en"}`
+//!
+//! JSON stdout: `{"success": true, "sub_categories": [...]}`
+
+#![allow(clippy::exit, clippy::unwrap_used, clippy::expect_used)]
+
+use meal_planner::fatsecret::core::serde_utils::deserialize_flexible_i64;
+use meal_planner::fatsecret::core::FatSecretConfig;
+use meal_planner::fatsecret::foods::{
+    get_food_sub_categories, FoodCategoryId, FoodSubCategoryEntry,
+};
+use serde::{Deserialize, Serialize};
+use std::io::{self, Read};
+
+/// `FatSecret` resource (matches Windmill resource-fatsecret format)
+#[derive(Deserialize)]
+struct FatSecretResource {
+    consumer_key: String,
+    consumer_secret: String,
+}
+
+#[derive(Deserialize)]
+struct Input {
+    /// `FatSecret` credentials (optional - falls back to env vars)
+    fatsecret: Option<FatSecretResource>,
+    /// Parent food category ID (string or number)
+    #[serde(deserialize_with = "deserialize_flexible_i64")]
+    food_category_id: i64,
+    /// Optional ISO region code (e.g., "US")
+    #[serde(default)]
+    region: Option<String>,
+    /// Optional ISO language code (e.g., "en")
+    #[serde(default)]
+    language: Option<String>,
+}
+
+#[derive(Serialize)]
+struct Output {
+    success: bool,
+    sub_categories: Vec<FoodSubCategoryEntry>,
+}
+
+#[derive(Serialize)]
+struct ErrorOutput {
+    success: bool,
+    error: String,
+}
+
+#[tokio::main]
+async fn main() {
+    match run().await {
+        Ok(output) => {
+            println!(
+                "{}",
+                serde_json::to_string(&output).expect("Failed to serialize output JSON")
+            );
+        }
+        Err(e) => {
+            let error = ErrorOutput {
+                success: false,
+                error: e.to_string(),
+            };
+            println!(
+                "{}",
+                serde_json::to_string(&error).expect("Failed to serialize error JSON")
+            );
+            std::process::exit(1);
+        }
+    }
+}
+
+async fn run() -> Result<Output, Box<dyn std::error::Error>> {
+    let input = read_input()?;
+
+    // Get config: prefer input, fall back to environment
+    let config = match input.fatsecret {
+        Some(resource) => FatSecretConfig::new(resource.consumer_key, resource.consumer_secret)
+            .map_err(|e| format!("Invalid FatSecret credentials: {}", e))?,
+        None => FatSecretConfig::from_env().map_err(|e| format!("Invalid configuration: {}", e))?,
+    };
+
+    let response = get_food_sub_categories(
+        &config,
+        &FoodCategoryId::new(input.food_category_id.to_string()),
+        input.region.as_deref(),
+        input.language.as_deref(),
+    )
+    .await?;
+
+    Ok(Output {
+        success: true,
+        sub_categories: response.sub_categories,
+    })
+}
+
+fn read_input() -> Result<Input, Box<dyn std::error::Error>> {
+    if let Some(arg) = std::env::args().nth(1) {
+        Ok(serde_json::from_str(&arg)?)
+    } else {
+        let mut input_str = String::new();
+        io::stdin().read_to_string(&mut input_str)?;
+        Ok(serde_json::from_str(&input_str)?)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_input_parsing_defaults() {
+        let json = r#"{"food_category_id": "1"}"#;
+        let input: Input = serde_json::from_str(json).expect("Failed to parse test JSON");
+        assert!(input.fatsecret.is_none());
+        assert_eq!(input.food_category_id, 1);
+        assert!(input.region.is_none());
+        assert!(input.language.is_none());
+    }
+
+    #[test]
+    fn test_input_parsing_full() {
+        let json = r#"{"fatsecret": {"consumer_key": "k", "consumer_secret": "s"}, "food_category_id": 12, "region": "US", "language": "en"}"#;
+        let input: Input = serde_json::from_str(json).expect("Failed to parse test JSON");
+        assert_eq!(input.food_category_id, 12);
+        assert_eq!(input.region.as_deref(), Some("US"));
+    }
+
+    #[test]
+    fn test_missing_category_rejected() {
+        assert!(serde_json::from_str::<Input>(r#"{"region": "US"}"#).is_err());
+        assert!(serde_json::from_str::<Input>(r#"{"food_category_id": "fruit"}"#).is_err());
+    }
+
+    #[test]
+    fn test_output_serialize_names() {
+        let output = Output {
+            success: true,
+            sub_categories: vec![FoodSubCategoryEntry::Name("Berries".to_string())],
+        };
+        let json = serde_json::to_string(&output).expect("Failed to serialize output JSON");
+        assert_eq!(json, r#"{"success":true,"sub_categories":["Berries"]}"#);
+    }
+}
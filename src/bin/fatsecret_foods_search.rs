@@ -5,7 +5,12 @@
 //!
 //! JSON input (CLI arg or stdin):
 //!   `{"fatsecret": {"consumer_key": "...", "consumer_secret": "..."},`
-//!   `"query": "chicken breast", "page": 0, "max_results": 20}`
+//!   `"query": "chicken breast", "page": 0, "max_results": 20,`
+//!   `"brand": "Tyson", "category": "Poultry", "generic_only": false}`
+//!
+//! `brand`, `category` and `generic_only` filter the returned page; filtered
+//! results list generic foods first. A `category` filter searches with
+//! `foods.search.v3` so each result carries its sub-categories.
 //!
 //! JSON stdout: `{"success": true, "foods": {...}}`
 
@@ -13,7 +18,9 @@
 #![allow(clippy::exit, clippy::unwrap_used, clippy::expect_used)]
 
 use meal_planner::fatsecret::core::FatSecretConfig;
-use meal_planner::fatsecret::foods::search_foods;
+use meal_planner::fatsecret::foods::{
+    filter_results, search_foods, search_foods_with_sub_categories, FoodSearchFilter,
+};
 use serde::{Deserialize, Serialize};
 use std::io::{self, Read};
 
@@ -36,6 +43,9 @@ struct Input {
     /// Maximum results per page
     #[serde(default)]
     max_results: Option<u32>,
+    /// Brand, category and generic-only filters
+    #[serde(flatten)]
+    filter: FoodSearchFilter,
 }
 
 #[derive(Serialize)]
@@ -94,8 +104,16 @@ async fn run() -> Result<Output, Box<dyn std::error::Error>> {
     let page = input.page.unwrap_or(0);
     let max_results = input.max_results.unwrap_or(20);
 
-    // Search foods
-    let result = search_foods(&config, &input.query, page, max_results).await?;
+    // Search foods (v3 when filtering by category, as only it returns sub-categories)
+    let mut result = if input.filter.category.is_some() {
+        search_foods_with_sub_categories(&config, &input.query, Some(page), Some(max_results))
+            .await?
+    } else {
+        search_foods(&config, &input.query, page, max_results).await?
+    };
+    if !input.filter.is_empty() {
+        result.foods = filter_results(&result.foods, &input.filter);
+    }
 
     Ok(Output {
         success: true,
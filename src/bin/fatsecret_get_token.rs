@@ -2,31 +2,39 @@
 //!
 //! Retrieves and decrypts the stored access token for use by other scripts.
 //! Returns the token validity status and optionally the token itself.
+//! Tokens are stored per user; `user` defaults to `"default"`.
 //!
-//! JSON stdin: `{}` (empty object, no input required)
-//! JSON stdout: `{"success": true, "status": "valid", "oauth_token": "...", "oauth_token_secret": "..."}`
+//! JSON stdin: `{"user": "alex", "check_only": false}` (all fields optional)
+//! JSON stdout: `{"success": true, "user": "alex", "status": "valid", "oauth_token": "...", "oauth_token_secret": "..."}`
 
 // CLI binaries: exit and JSON unwrap are acceptable at the top level
 #![allow(clippy::exit, clippy::unwrap_used)]
 #![allow(clippy::too_many_lines, clippy::cognitive_complexity)]
 
-use meal_planner::fatsecret::{TokenStorage, TokenValidity};
+use meal_planner::db::Database;
+use meal_planner::fatsecret::{
+    validate_user_id, TokenStorage, TokenStore, TokenValidity, DEFAULT_USER,
+};
 use serde::{Deserialize, Serialize};
-use sqlx::postgres::PgPoolOptions;
 use std::env;
 use std::io::{self, Read};
 
-#[derive(Deserialize)]
+#[derive(Default, Deserialize)]
 struct Input {
     /// If true, only check validity without returning the actual token
     #[serde(default)]
     check_only: bool,
+    /// User whose token to retrieve (defaults to "default")
+    #[serde(default)]
+    user: Option<String>,
 }
 
 #[derive(Serialize)]
 struct Output {
     success: bool,
-    /// Token status: "valid", "not_found", or "old"
+    /// User the token belongs to
+    user: String,
+    /// Token status: "valid", "not_found", "old", or "revoked"
     status: String,
     /// Days since token was connected (if old)
     #[serde(skip_serializing_if = "Option::is_none")]
@@ -69,19 +77,20 @@ async fn run() -> Result<Output, Box<dyn std::error::Error>> {
 
     // Handle empty input
     let input: Input = if input_str.trim().is_empty() {
-        Input { check_only: false }
+        Input::default()
     } else {
         serde_json::from_str(&input_str)?
     };
 
+    let user = input.user.unwrap_or_else(|| DEFAULT_USER.to_string());
+    validate_user_id(&user)?;
+
     // Get database connection
     let database_url = env::var("DATABASE_URL").map_err(|_| "DATABASE_URL not set")?;
-    let pool = PgPoolOptions::new()
-        .max_connections(1)
-        .connect(&database_url)
-        .await?;
+    let db = Database::connect(&database_url).await?;
 
-    let storage = TokenStorage::new(pool);
+    let storage = TokenStorage::new(db).with_user(user.clone());
+    storage.ensure_schema().await?;
 
     // Check token validity
     let validity = storage.check_token_validity().await?;
@@ -91,6 +100,7 @@ async fn run() -> Result<Output, Box<dyn std::error::Error>> {
             if input.check_only {
                 Ok(Output {
                     success: true,
+                    user,
                     status: "valid".to_string(),
                     days_since_connected: None,
                     oauth_token: None,
@@ -108,6 +118,7 @@ async fn run() -> Result<Output, Box<dyn std::error::Error>> {
 
                 Ok(Output {
                     success: true,
+                    user,
                     status: "valid".to_string(),
                     days_since_connected: None,
                     oauth_token: Some(token.oauth_token),
@@ -117,11 +128,21 @@ async fn run() -> Result<Output, Box<dyn std::error::Error>> {
         }
         TokenValidity::NotFound => Ok(Output {
             success: true,
+            user,
             status: "not_found".to_string(),
             days_since_connected: None,
             oauth_token: None,
             oauth_token_secret: None,
         }),
+        // FatSecret rejected the token; run fatsecret_token_health to re-authorize
+        TokenValidity::Revoked => Ok(Output {
+            success: true,
+            user,
+            status: "revoked".to_string(),
+            days_since_connected: None,
+            oauth_token: None,
+            oauth_token_secret: None,
+        }),
         TokenValidity::Old {
             days_since_connected,
         } => {
@@ -129,6 +150,7 @@ async fn run() -> Result<Output, Box<dyn std::error::Error>> {
             if input.check_only {
                 Ok(Output {
                     success: true,
+                    user,
                     status: "old".to_string(),
                     days_since_connected: Some(days_since_connected),
                     oauth_token: None,
@@ -144,6 +166,7 @@ async fn run() -> Result<Output, Box<dyn std::error::Error>> {
 
                 Ok(Output {
                     success: true,
+                    user,
                     status: "old".to_string(),
                     days_since_connected: Some(days_since_connected),
                     oauth_token: Some(token.oauth_token),
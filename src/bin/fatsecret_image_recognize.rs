@@ -0,0 +1,234 @@
+//! `FatSecret` meal photo recognition
+//!
+//! Reads a meal photo, uploads it base64-encoded to the image recognition
+//! endpoint and returns the detected foods with eaten portions, suggested
+//! servings and nutrition. With `commit: true` the detections are logged to
+//! the food diary as `FromFood` entries.
+//! Recognition is an OAuth 2.0 request (`image-recognition` scope); committing
+//! is a 3-legged OAuth request and needs the user access token.
+//!
+//! JSON input (CLI arg or stdin):
+//!   `{"fatsecret": {...}, "image_path": "/tmp/dinner.jpg", "meal": "dinner",
+//!     "date": "2024-01-15", "commit": false, "access_token": "...", "access_secret": "..."}`
+//!
+//! `date` defaults to today. Photos must be under about 730 KB (the base64
+//! limit is 999,982 characters).
+//!
+//! JSON stdout: `{"success": true, "foods": [...], "total": {...}, "entries": [...],
+//!   "committed": false, "food_entry_ids": []}`
+
+#![allow(clippy::exit, clippy::unwrap_used, clippy::expect_used)]
+
+use meal_planner::fatsecret::core::{AccessToken, FatSecretConfig};
+use meal_planner::fatsecret::diary::{create_food_entry, date_to_int, FoodEntryInput, MealType};
+use meal_planner::fatsecret::recognition::{
+    encode_image, recognize_image, EatenFood, ImageRecognitionRequest, RecognizedFood,
+    RecognizedNutrition,
+};
+use serde::{Deserialize, Serialize};
+use std::io::{self, Read};
+
+/// `FatSecret` resource (matches Windmill resource-fatsecret format)
+#[derive(Deserialize)]
+struct FatSecretResource {
+    consumer_key: String,
+    consumer_secret: String,
+}
+
+#[derive(Deserialize)]
+struct Input {
+    /// `FatSecret` credentials (optional - falls back to env vars)
+    fatsecret: Option<FatSecretResource>,
+    /// Path to the meal photo (JPEG or PNG)
+    image_path: String,
+    /// Meal type: breakfast, lunch, dinner, or other
+    meal: MealType,
+    /// Diary date (YYYY-MM-DD, defaults to today)
+    #[serde(default)]
+    date: Option<String>,
+    /// Log the detections to the diary
+    #[serde(default)]
+    commit: bool,
+    /// OAuth access token (required when committing)
+    #[serde(default)]
+    access_token: Option<String>,
+    /// OAuth access secret (required when committing)
+    #[serde(default)]
+    access_secret: Option<String>,
+    /// Previously eaten foods, used as matching context
+    #[serde(default)]
+    eaten_foods: Vec<EatenFood>,
+    /// Include full food data for each detected food
+    #[serde(default)]
+    include_food_data: bool,
+}
+
+#[derive(Serialize)]
+struct Output {
+    success: bool,
+    foods: Vec<RecognizedFood>,
+    total: RecognizedNutrition,
+    entries: Vec<FoodEntryInput>,
+    committed: bool,
+    food_entry_ids: Vec<String>,
+}
+
+#[derive(Serialize)]
+struct ErrorOutput {
+    success: bool,
+    error: String,
+}
+
+#[tokio::main]
+async fn main() {
+    match run().await {
+        Ok(output) => {
+            println!(
+                "{}",
+                serde_json::to_string(&output).expect("Failed to serialize output JSON")
+            );
+        }
+        Err(e) => {
+            let error = ErrorOutput {
+                success: false,
+                error: e.to_string(),
+            };
+            println!(
+                "{}",
+                serde_json::to_string(&error).expect("Failed to serialize error JSON")
+            );
+            std::process::exit(1);
+        }
+    }
+}
+
+async fn run() -> Result<Output, Box<dyn std::error::Error>> {
+    let input = read_input()?;
+    let date_int = diary_date_int(input.date.as_deref())?;
+    let image = std::fs::read(&input.image_path)
+        .map_err(|e| format!("Failed to read image {}: {}", input.image_path, e))?;
+    let image_b64 = encode_image(&image)?;
+    // Check credentials before calling the API so a commit never half-runs
+    let token = if input.commit {
+        Some(commit_token(input.access_token, input.access_secret)?)
+    } else {
+        None
+    };
+
+    // Get config: prefer input, fall back to environment
+    let config = match input.fatsecret {
+        Some(resource) => FatSecretConfig::new(resource.consumer_key, resource.consumer_secret)
+            .map_err(|e| format!("Invalid FatSecret credentials: {}", e))?,
+        None => FatSecretConfig::from_env().map_err(|e| format!("Invalid configuration: {}", e))?,
+    };
+
+    let request = ImageRecognitionRequest {
+        image_b64,
+        include_food_data: input.include_food_data,
+        eaten_foods: input.eaten_foods,
+        region: None,
+        language: None,
+    };
+    let response = recognize_image(&config, &request).await?;
+    let entries = response.to_food_entries(input.meal, date_int);
+
+    let mut food_entry_ids = Vec::new();
+    if let Some(token) = &token {
+        for entry in &entries {
+            let id = create_food_entry(&config, token, entry.clone()).await?;
+            food_entry_ids.push(id.to_string());
+        }
+    }
+
+    Ok(Output {
+        success: true,
+        total: response.total_nutrition(),
+        foods: response.food_response,
+        entries,
+        committed: token.is_some(),
+        food_entry_ids,
+    })
+}
+
+fn read_input() -> Result<Input, Box<dyn std::error::Error>> {
+    if let Some(arg) = std::env::args().nth(1) {
+        Ok(serde_json::from_str(&arg)?)
+    } else {
+        let mut input_str = String::new();
+        io::stdin().read_to_string(&mut input_str)?;
+        Ok(serde_json::from_str(&input_str)?)
+    }
+}
+
+/// Diary date: the given date or today
+fn diary_date_int(date: Option<&str>) -> Result<i32, String> {
+    date.map_or_else(
+        || {
+            date_to_int(
+                &chrono::Local::now()
+                    .date_naive()
+                    .format("%Y-%m-%d")
+                    .to_string(),
+            )
+        },
+        date_to_int,
+    )
+}
+
+/// User token for committing entries
+fn commit_token(
+    access_token: Option<String>,
+    access_secret: Option<String>,
+) -> Result<AccessToken, String> {
+    match (access_token, access_secret) {
+        (Some(token), Some(secret)) => Ok(AccessToken::new(token, secret)),
+        _ => Err("access_token and access_secret are required when commit is true".to_string()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_input_parsing_defaults() {
+        let json = r#"{"image_path": "/tmp/dinner.jpg", "meal": "dinner"}"#;
+        let input: Input = serde_json::from_str(json).expect("Failed to parse test JSON");
+        assert!(input.fatsecret.is_none());
+        assert_eq!(input.image_path, "/tmp/dinner.jpg");
+        assert_eq!(input.meal, MealType::Dinner);
+        assert!(!input.commit);
+        assert!(!input.include_food_data);
+        assert!(diary_date_int(input.date.as_deref()).is_ok());
+    }
+
+    #[test]
+    fn test_input_parsing_commit() {
+        let json = r#"{"fatsecret": {"consumer_key": "k", "consumer_secret": "s"}, "image_path": "meal.png", "meal": "lunch", "date": "2024-01-15", "commit": true, "access_token": "t", "access_secret": "s", "include_food_data": true}"#;
+        let input: Input = serde_json::from_str(json).expect("Failed to parse test JSON");
+        assert_eq!(diary_date_int(input.date.as_deref()), Ok(19737));
+        assert!(input.include_food_data);
+        assert!(commit_token(input.access_token, input.access_secret).is_ok());
+    }
+
+    #[test]
+    fn test_invalid_input_rejected() {
+        assert!(serde_json::from_str::<Input>(r#"{"meal": "dinner"}"#).is_err());
+        assert!(commit_token(None, Some("s".to_string())).is_err());
+    }
+
+    #[test]
+    fn test_output_serialize_dry_run() {
+        let output = Output {
+            success: true,
+            foods: Vec::new(),
+            total: RecognizedNutrition::default(),
+            entries: Vec::new(),
+            committed: false,
+            food_entry_ids: Vec::new(),
+        };
+        let json = serde_json::to_string(&output).expect("Failed to serialize output JSON");
+        assert!(json.contains(r#""success":true"#));
+        assert!(json.contains(r#""committed":false"#));
+    }
+}
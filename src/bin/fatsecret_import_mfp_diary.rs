@@ -0,0 +1,162 @@
+//! Import a MyFitnessPal diary export into the `FatSecret` food diary
+//!
+//! Reads MyFitnessPal's "Nutrition Summary" CSV (one row per meal per day)
+//! and logs each meal as a custom diary entry with its calories and macros,
+//! named "MyFitnessPal <meal>". Meals already logged under that name are
+//! skipped, so the import can be rerun.
+//!
+//! Runs as a dry run unless `dry_run` is `false`: review the report, then
+//! run again to import.
+//! This is a 3-legged OAuth request (requires user access token).
+//!
+//! JSON input (CLI arg or stdin):
+//!   `{"fatsecret": {...}, "access_token": "...", "access_secret": "...",
+//!     "path": "Nutrition-Summary-2024-01-01-to-2024-03-31.csv", "dry_run": true}`
+//!
+//! JSON stdout: `{"success": true, "dry_run": true,
+//!   "meals": [{"date": "2024-01-15", "meal": "breakfast", "food_entry_name": "MyFitnessPal Breakfast",
+//!   "calories": 412.5, "action": "created"}], "unreadable": []}`
+
+#![allow(clippy::exit, clippy::unwrap_used, clippy::expect_used)]
+
+use meal_planner::fatsecret::core::{AccessToken, FatSecretConfig};
+use meal_planner::import::mfp::{import_diary, read_mfp_diary, DiaryReport};
+use serde::{Deserialize, Serialize};
+use std::io::{self, Read};
+use std::path::PathBuf;
+
+/// `FatSecret` resource (matches Windmill resource-fatsecret format)
+#[derive(Deserialize)]
+struct FatSecretResource {
+    consumer_key: String,
+    consumer_secret: String,
+}
+
+#[derive(Deserialize)]
+struct Input {
+    /// `FatSecret` credentials (optional - falls back to env vars)
+    fatsecret: Option<FatSecretResource>,
+    /// OAuth access token
+    access_token: String,
+    /// OAuth access token secret
+    access_secret: String,
+    /// Nutrition Summary CSV exported from MyFitnessPal
+    path: PathBuf,
+    /// Report what would be logged without writing (defaults to true)
+    #[serde(default = "default_dry_run")]
+    dry_run: bool,
+}
+
+const fn default_dry_run() -> bool {
+    true
+}
+
+#[derive(Serialize)]
+struct Output {
+    success: bool,
+    #[serde(flatten)]
+    report: DiaryReport,
+}
+
+#[derive(Serialize)]
+struct ErrorOutput {
+    success: bool,
+    error: String,
+}
+
+#[tokio::main]
+async fn main() {
+    match run().await {
+        Ok(output) => {
+            println!(
+                "{}",
+                serde_json::to_string(&output).expect("Failed to serialize output JSON")
+            );
+        }
+        Err(e) => {
+            let error = ErrorOutput {
+                success: false,
+                error: e.to_string(),
+            };
+            println!(
+                "{}",
+                serde_json::to_string(&error).expect("Failed to serialize error JSON")
+            );
+            std::process::exit(1);
+        }
+    }
+}
+
+async fn run() -> Result<Output, Box<dyn std::error::Error>> {
+    let input = read_input()?;
+    let csv = std::fs::read(&input.path).map_err(|e| format!("{}: {e}", input.path.display()))?;
+    let diary = read_mfp_diary(&csv)?;
+
+    // Get config: prefer input, fall back to environment
+    let config = match input.fatsecret {
+        Some(resource) => FatSecretConfig::new(resource.consumer_key, resource.consumer_secret)
+            .map_err(|e| format!("Invalid FatSecret credentials: {}", e))?,
+        None => FatSecretConfig::from_env().map_err(|e| format!("Invalid configuration: {}", e))?,
+    };
+    let token = AccessToken::new(input.access_token, input.access_secret);
+
+    let report = import_diary(&config, &token, diary, input.dry_run).await?;
+    Ok(Output {
+        success: true,
+        report,
+    })
+}
+
+fn read_input() -> Result<Input, Box<dyn std::error::Error>> {
+    if let Some(arg) = std::env::args().nth(1) {
+        Ok(serde_json::from_str(&arg)?)
+    } else {
+        let mut input_str = String::new();
+        io::stdin().read_to_string(&mut input_str)?;
+        Ok(serde_json::from_str(&input_str)?)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_input_defaults_to_dry_run() {
+        let json = r#"{"access_token": "t", "access_secret": "s", "path": "summary.csv"}"#;
+        let input: Input = serde_json::from_str(json).expect("Failed to parse test JSON");
+        assert!(input.fatsecret.is_none());
+        assert!(input.dry_run);
+    }
+
+    #[test]
+    fn test_input_parsing_explicit() {
+        let json = r#"{"fatsecret": {"consumer_key": "k", "consumer_secret": "s"}, "access_token": "t", "access_secret": "s", "path": "summary.csv", "dry_run": false}"#;
+        let input: Input = serde_json::from_str(json).expect("Failed to parse test JSON");
+        assert!(input.fatsecret.is_some());
+        assert!(!input.dry_run);
+        assert_eq!(input.path, PathBuf::from("summary.csv"));
+    }
+
+    #[test]
+    fn test_output_flattens_report() {
+        let output = Output {
+            success: true,
+            report: DiaryReport {
+                dry_run: true,
+                ..DiaryReport::default()
+            },
+        };
+        let json = serde_json::to_string(&output).expect("Failed to serialize");
+        assert_eq!(
+            json,
+            r#"{"success":true,"dry_run":true,"meals":[],"unreadable":[]}"#
+        );
+    }
+
+    #[test]
+    fn test_missing_path_is_rejected() {
+        let json = r#"{"access_token": "t", "access_secret": "s"}"#;
+        assert!(serde_json::from_str::<Input>(json).is_err());
+    }
+}
@@ -0,0 +1,353 @@
+//! Sync Tandoor meal plans into the `FatSecret` food diary
+//!
+//! Fetches Tandoor meal plans for a date range, computes per-serving nutrition
+//! for each planned recipe and creates custom `FatSecret` diary entries with the
+//! planned servings. Every entry is guarded by an idempotency key stored in
+//! the database (`DATABASE_URL`), so re-running the sync never duplicates entries.
+//! This is a 3-legged OAuth request (requires user access token).
+//!
+//! JSON input (CLI arg or stdin):
+//!   `{"tandoor": {...}, "fatsecret": {...}, "access_token": "...", "access_secret": "...",
+//!     "from_date": "2024-01-15", "to_date": "2024-01-21",
+//!     "meal_type_map": {"dessert": "other"}, "dry_run": false}`
+//!
+//! `to_date` defaults to `from_date`. `meal_type_map` maps Tandoor meal type
+//! names to breakfast, lunch, dinner or other. With `dry_run` nothing is written.
+//! `database_url` overrides the `DATABASE_URL` environment variable.
+//!
+//! JSON stdout: `{"success": true, "created": [...], "already_synced": [...], "skipped": [...], "failed": [...]}`
+
+#![allow(clippy::exit, clippy::unwrap_used, clippy::expect_used)]
+
+use meal_planner::db::Database;
+use meal_planner::fatsecret::core::{AccessToken, FatSecretConfig};
+use meal_planner::fatsecret::diary::sync::{
+    plan_diary_entries, PlannedDiaryEntry, SkippedMealPlan,
+};
+use meal_planner::fatsecret::diary::{create_food_entry, date_to_int};
+use meal_planner::fatsecret::{DiarySyncStorage, DiarySyncStore};
+use meal_planner::tandoor::{MealPlansWithRecipes, TandoorClient, TandoorConfig};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::io::{self, Read};
+
+/// `FatSecret` resource (matches Windmill resource-fatsecret format)
+#[derive(Deserialize)]
+struct FatSecretResource {
+    consumer_key: String,
+    consumer_secret: String,
+}
+
+#[derive(Deserialize)]
+struct Input {
+    /// Tandoor configuration (URL and token)
+    tandoor: TandoorConfig,
+    /// `FatSecret` credentials (optional - falls back to env vars)
+    fatsecret: Option<FatSecretResource>,
+    /// OAuth access token
+    access_token: String,
+    /// OAuth access token secret
+    access_secret: String,
+    /// First day to sync (YYYY-MM-DD)
+    from_date: String,
+    /// Last day to sync (YYYY-MM-DD, defaults to `from_date`)
+    #[serde(default)]
+    to_date: Option<String>,
+    /// Tandoor meal type name → `FatSecret` meal overrides
+    #[serde(default)]
+    meal_type_map: HashMap<String, String>,
+    /// Report what would be created without writing anything
+    #[serde(default)]
+    dry_run: bool,
+    /// Postgres or SQLite URL for the idempotency ledger (optional - falls back to `DATABASE_URL`)
+    #[serde(default)]
+    database_url: Option<String>,
+}
+
+#[derive(Serialize)]
+struct SyncedEntry {
+    idempotency_key: String,
+    meal_plan_id: i64,
+    date: String,
+    food_entry_name: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    food_entry_id: Option<String>,
+}
+
+#[derive(Serialize)]
+struct FailedEntry {
+    idempotency_key: String,
+    meal_plan_id: i64,
+    error: String,
+}
+
+#[derive(Serialize)]
+struct Output {
+    success: bool,
+    dry_run: bool,
+    created: Vec<SyncedEntry>,
+    already_synced: Vec<String>,
+    skipped: Vec<SkippedMealPlan>,
+    failed: Vec<FailedEntry>,
+}
+
+#[derive(Serialize)]
+struct ErrorOutput {
+    success: bool,
+    error: String,
+}
+
+#[tokio::main]
+async fn main() {
+    match run().await {
+        Ok(output) => {
+            println!(
+                "{}",
+                serde_json::to_string(&output).expect("Failed to serialize output JSON")
+            );
+            if !output.success {
+                std::process::exit(1);
+            }
+        }
+        Err(e) => {
+            let error = ErrorOutput {
+                success: false,
+                error: e.to_string(),
+            };
+            println!(
+                "{}",
+                serde_json::to_string(&error).expect("Failed to serialize error JSON")
+            );
+            std::process::exit(1);
+        }
+    }
+}
+
+async fn run() -> Result<Output, Box<dyn std::error::Error>> {
+    let input = read_input()?;
+
+    let to_date = input
+        .to_date
+        .clone()
+        .unwrap_or_else(|| input.from_date.clone());
+    let (from_date_int, to_date_int) = date_range(&input.from_date, &to_date)?;
+
+    // Get config: prefer input, fall back to environment
+    let config = match input.fatsecret {
+        Some(resource) => FatSecretConfig::new(resource.consumer_key, resource.consumer_secret)
+            .map_err(|e| format!("Invalid FatSecret credentials: {}", e))?,
+        None => FatSecretConfig::from_env().map_err(|e| format!("Invalid configuration: {}", e))?,
+    };
+    let token = AccessToken::new(input.access_token, input.access_secret);
+
+    // Tandoor client is blocking: fetch plans and recipes off the async runtime
+    let tandoor = input.tandoor;
+    let from_date = input.from_date.clone();
+    let data =
+        tokio::task::spawn_blocking(move || fetch_tandoor_data(&tandoor, &from_date, &to_date))
+            .await??;
+
+    let (planned, skipped) = plan_diary_entries(
+        &data.plans,
+        &data.recipes,
+        &lowercase_keys(input.meal_type_map),
+        from_date_int,
+        to_date_int,
+    );
+
+    let storage = connect_storage(input.database_url).await?;
+
+    let mut output = Output {
+        success: true,
+        dry_run: input.dry_run,
+        created: Vec::new(),
+        already_synced: Vec::new(),
+        skipped,
+        failed: Vec::new(),
+    };
+    for planned_entry in planned {
+        if input.dry_run {
+            preview_entry(&storage, planned_entry, &mut output).await?;
+        } else {
+            sync_entry(&storage, &config, &token, planned_entry, &mut output).await?;
+        }
+    }
+    output.success = output.failed.is_empty();
+
+    Ok(output)
+}
+
+/// Read input: prefer CLI arg, fall back to stdin
+fn read_input() -> Result<Input, Box<dyn std::error::Error>> {
+    if let Some(arg) = std::env::args().nth(1) {
+        Ok(serde_json::from_str(&arg)?)
+    } else {
+        let mut input_str = String::new();
+        io::stdin().read_to_string(&mut input_str)?;
+        Ok(serde_json::from_str(&input_str)?)
+    }
+}
+
+/// Parse the sync range into date_ints, rejecting reversed ranges
+fn date_range(from_date: &str, to_date: &str) -> Result<(i32, i32), String> {
+    let from_date_int = date_to_int(from_date)?;
+    let to_date_int = date_to_int(to_date)?;
+    if to_date_int < from_date_int {
+        return Err(format!("to_date {to_date} is before from_date {from_date}"));
+    }
+    Ok((from_date_int, to_date_int))
+}
+
+/// Connect to the database and make sure the sync ledger exists
+async fn connect_storage(
+    database_url: Option<String>,
+) -> Result<DiarySyncStorage, Box<dyn std::error::Error>> {
+    let database_url = match database_url {
+        Some(url) => url,
+        None => std::env::var("DATABASE_URL").map_err(|_| "DATABASE_URL not set")?,
+    };
+    let db = Database::connect(&database_url).await?;
+    let storage = DiarySyncStorage::new(db);
+    storage.ensure_schema().await?;
+    Ok(storage)
+}
+
+/// Report whether an entry would be created, without writing anything
+async fn preview_entry(
+    storage: &DiarySyncStorage,
+    planned: PlannedDiaryEntry,
+    output: &mut Output,
+) -> Result<(), Box<dyn std::error::Error>> {
+    if storage.is_synced(&planned.idempotency_key).await? {
+        output.already_synced.push(planned.idempotency_key);
+    } else {
+        output.created.push(synced_entry(&planned, None));
+    }
+    Ok(())
+}
+
+/// Claim the idempotency key, create the diary entry, then record its ID
+///
+/// The claim is released again if `FatSecret` rejects the entry, so the next
+/// run retries it.
+async fn sync_entry(
+    storage: &DiarySyncStorage,
+    config: &FatSecretConfig,
+    token: &AccessToken,
+    planned: PlannedDiaryEntry,
+    output: &mut Output,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let claimed = storage
+        .claim(
+            &planned.idempotency_key,
+            planned.meal_plan_id,
+            planned.entry.date_int(),
+        )
+        .await?;
+    if !claimed {
+        output.already_synced.push(planned.idempotency_key);
+        return Ok(());
+    }
+
+    match create_food_entry(config, token, planned.entry.clone()).await {
+        Ok(entry_id) => {
+            storage
+                .complete(&planned.idempotency_key, entry_id.as_str())
+                .await?;
+            output
+                .created
+                .push(synced_entry(&planned, Some(entry_id.to_string())));
+        }
+        Err(e) => {
+            storage.release(&planned.idempotency_key).await?;
+            output.failed.push(FailedEntry {
+                idempotency_key: planned.idempotency_key,
+                meal_plan_id: planned.meal_plan_id,
+                error: e.to_string(),
+            });
+        }
+    }
+    Ok(())
+}
+
+/// Fetch every meal plan in range plus the full recipe for each planned recipe
+fn fetch_tandoor_data(
+    tandoor: &TandoorConfig,
+    from_date: &str,
+    to_date: &str,
+) -> Result<MealPlansWithRecipes, String> {
+    TandoorClient::new(tandoor)
+        .and_then(|client| client.get_meal_plans_with_recipes(from_date, to_date))
+        .map_err(|e| e.to_string())
+}
+
+fn lowercase_keys(map: HashMap<String, String>) -> HashMap<String, String> {
+    map.into_iter()
+        .map(|(name, meal)| (name.to_lowercase(), meal))
+        .collect()
+}
+
+fn synced_entry(planned: &PlannedDiaryEntry, food_entry_id: Option<String>) -> SyncedEntry {
+    SyncedEntry {
+        idempotency_key: planned.idempotency_key.clone(),
+        meal_plan_id: planned.meal_plan_id,
+        date: planned.date.clone(),
+        food_entry_name: planned.entry.food_entry_name().to_string(),
+        food_entry_id,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_input_parsing_defaults() {
+        let json = r#"{"tandoor": {"base_url": "http://localhost:8090", "api_token": "test"}, "access_token": "t", "access_secret": "s", "from_date": "2024-01-15"}"#;
+        let input: Input = serde_json::from_str(json).expect("Failed to parse test JSON");
+        assert_eq!(input.from_date, "2024-01-15");
+        assert!(input.to_date.is_none());
+        assert!(input.fatsecret.is_none());
+        assert!(input.meal_type_map.is_empty());
+        assert!(!input.dry_run);
+        assert!(input.database_url.is_none());
+    }
+
+    #[test]
+    fn test_input_parsing_full() {
+        let json = r#"{"tandoor": {"base_url": "http://localhost:8090", "api_token": "test"}, "fatsecret": {"consumer_key": "k", "consumer_secret": "s"}, "access_token": "t", "access_secret": "s", "from_date": "2024-01-15", "to_date": "2024-01-21", "meal_type_map": {"Dessert": "other"}, "dry_run": true}"#;
+        let input: Input = serde_json::from_str(json).expect("Failed to parse test JSON");
+        assert_eq!(input.to_date.as_deref(), Some("2024-01-21"));
+        assert!(input.dry_run);
+        let map = lowercase_keys(input.meal_type_map);
+        assert_eq!(map.get("dessert").map(String::as_str), Some("other"));
+    }
+
+    #[test]
+    fn test_date_range() {
+        assert_eq!(date_range("1970-01-01", "1970-01-03"), Ok((0, 2)));
+        assert!(date_range("2024-01-21", "2024-01-15").is_err());
+        assert!(date_range("not-a-date", "2024-01-15").is_err());
+    }
+
+    #[test]
+    fn test_output_serialize() {
+        let output = Output {
+            success: true,
+            dry_run: false,
+            created: vec![SyncedEntry {
+                idempotency_key: "tandoor-meal-plan:1:19737".to_string(),
+                meal_plan_id: 1,
+                date: "2024-01-15".to_string(),
+                food_entry_name: "Chili".to_string(),
+                food_entry_id: Some("555".to_string()),
+            }],
+            already_synced: vec![],
+            skipped: vec![],
+            failed: vec![],
+        };
+        let json = serde_json::to_string(&output).expect("Failed to serialize output JSON");
+        assert!(json.contains(r#""food_entry_id":"555""#));
+        assert!(json.contains(r#""already_synced":[]"#));
+    }
+}
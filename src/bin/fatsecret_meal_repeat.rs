@@ -0,0 +1,258 @@
+//! Repeat a logged `FatSecret` meal on the following days
+//!
+//! "Repeat yesterday's breakfast for the next 5 days": copies one meal to each
+//! of the next `days` days with `food_entry.copy_meal`. With `dry_run: true`
+//! nothing is written; the output lists the entries that would be copied and
+//! the planned copies.
+//! This is a 3-legged OAuth request (requires user access token).
+//!
+//! JSON input (CLI arg or stdin):
+//!   `{"fatsecret": {...}, "access_token": "...", "access_secret": "...",
+//!     "days": 5, "meal": "breakfast", "from_date": "2024-01-14",
+//!     "start_date": "2024-01-15", "to_meal": "breakfast", "dry_run": true}`
+//!
+//! `meal` defaults to breakfast, `from_date` to yesterday, `start_date` to the
+//! day after `from_date` and `to_meal` to `meal`.
+//!
+//! JSON stdout: `{"success": true, "dry_run": true, "source_entries": [...],
+//!   "copies": [{"from_date": "2024-01-14", "to_date": "2024-01-15", ...}], "copied": 0}`
+
+#![allow(clippy::exit, clippy::unwrap_used, clippy::expect_used)]
+
+use meal_planner::fatsecret::core::{AccessToken, FatSecretConfig};
+use meal_planner::fatsecret::diary::repeat::{plan_repeat, source_entries, MealCopy, RepeatMeal};
+use meal_planner::fatsecret::diary::{
+    copy_meal, date_to_int, get_food_entries, int_to_date, FoodEntry, MealType,
+};
+use serde::{Deserialize, Serialize};
+use std::io::{self, Read};
+
+/// `FatSecret` resource (matches Windmill resource-fatsecret format)
+#[derive(Deserialize)]
+struct FatSecretResource {
+    consumer_key: String,
+    consumer_secret: String,
+}
+
+#[derive(Deserialize)]
+struct Input {
+    /// `FatSecret` credentials (optional - falls back to env vars)
+    fatsecret: Option<FatSecretResource>,
+    /// OAuth access token
+    access_token: String,
+    /// OAuth access token secret
+    access_secret: String,
+    /// Number of days to repeat the meal on
+    days: u32,
+    /// Meal to repeat (defaults to breakfast)
+    #[serde(default = "default_meal")]
+    meal: MealType,
+    /// Day the meal was logged (YYYY-MM-DD, defaults to yesterday)
+    #[serde(default)]
+    from_date: Option<String>,
+    /// First day to copy to (YYYY-MM-DD, defaults to the day after `from_date`)
+    #[serde(default)]
+    start_date: Option<String>,
+    /// Meal to log the copies as (defaults to `meal`)
+    #[serde(default)]
+    to_meal: Option<MealType>,
+    /// Show what would be copied without writing to the diary
+    #[serde(default)]
+    dry_run: bool,
+}
+
+const fn default_meal() -> MealType {
+    MealType::Breakfast
+}
+
+#[derive(Serialize)]
+struct Output {
+    success: bool,
+    dry_run: bool,
+    source_entries: Vec<FoodEntry>,
+    copies: Vec<MealCopy>,
+    copied: usize,
+}
+
+#[derive(Serialize)]
+struct ErrorOutput {
+    success: bool,
+    error: String,
+}
+
+#[tokio::main]
+async fn main() {
+    match run().await {
+        Ok(output) => {
+            println!(
+                "{}",
+                serde_json::to_string(&output).expect("Failed to serialize output JSON")
+            );
+        }
+        Err(e) => {
+            let error = ErrorOutput {
+                success: false,
+                error: e.to_string(),
+            };
+            println!(
+                "{}",
+                serde_json::to_string(&error).expect("Failed to serialize error JSON")
+            );
+            std::process::exit(1);
+        }
+    }
+}
+
+async fn run() -> Result<Output, Box<dyn std::error::Error>> {
+    let input = read_input()?;
+    let repeat = repeat_meal(&input)?;
+    let copies = plan_repeat(&repeat)?;
+
+    // Get config: prefer input, fall back to environment
+    let config = match input.fatsecret {
+        Some(resource) => FatSecretConfig::new(resource.consumer_key, resource.consumer_secret)
+            .map_err(|e| format!("Invalid FatSecret credentials: {}", e))?,
+        None => FatSecretConfig::from_env().map_err(|e| format!("Invalid configuration: {}", e))?,
+    };
+    let token = AccessToken::new(input.access_token, input.access_secret);
+
+    let entries = get_food_entries(&config, &token, repeat.from_date_int).await?;
+    let source_entries = source_entries(&entries, repeat.from_meal);
+    if source_entries.is_empty() {
+        return Err(format!(
+            "No {} entries on {}",
+            repeat.from_meal,
+            int_to_date(repeat.from_date_int)?
+        )
+        .into());
+    }
+
+    let mut copied = 0;
+    if !input.dry_run {
+        for copy in &copies {
+            copy_meal(
+                &config,
+                &token,
+                copy.from_date_int,
+                copy.from_meal,
+                copy.to_date_int,
+                copy.to_meal,
+            )
+            .await
+            .map_err(|e| {
+                format!(
+                    "Copied {copied} of {} days, failed on {}: {e}",
+                    copies.len(),
+                    copy.to_date
+                )
+            })?;
+            copied += 1;
+        }
+    }
+
+    Ok(Output {
+        success: true,
+        dry_run: input.dry_run,
+        source_entries,
+        copies,
+        copied,
+    })
+}
+
+fn read_input() -> Result<Input, Box<dyn std::error::Error>> {
+    if let Some(arg) = std::env::args().nth(1) {
+        Ok(serde_json::from_str(&arg)?)
+    } else {
+        let mut input_str = String::new();
+        io::stdin().read_to_string(&mut input_str)?;
+        Ok(serde_json::from_str(&input_str)?)
+    }
+}
+
+/// Resolve input defaults into a repeat
+fn repeat_meal(input: &Input) -> Result<RepeatMeal, String> {
+    let from_date_int = match &input.from_date {
+        Some(date) => date_to_int(date)?,
+        None => date_to_int(
+            &chrono::Local::now()
+                .date_naive()
+                .pred_opt()
+                .ok_or("Cannot determine yesterday's date")?
+                .format("%Y-%m-%d")
+                .to_string(),
+        )?,
+    };
+    let start_date_int = match &input.start_date {
+        Some(date) => date_to_int(date)?,
+        None => from_date_int
+            .checked_add(1)
+            .ok_or("from_date is out of range")?,
+    };
+
+    Ok(RepeatMeal {
+        from_date_int,
+        from_meal: input.meal,
+        to_meal: input.to_meal.unwrap_or(input.meal),
+        start_date_int,
+        days: input.days,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_input_parsing_defaults() {
+        let json = r#"{"access_token": "t", "access_secret": "s", "days": 5}"#;
+        let input: Input = serde_json::from_str(json).expect("Failed to parse test JSON");
+        assert!(input.fatsecret.is_none());
+        assert!(!input.dry_run);
+
+        let repeat = repeat_meal(&input).expect("Failed to resolve defaults");
+        assert_eq!(repeat.from_meal, MealType::Breakfast);
+        assert_eq!(repeat.to_meal, MealType::Breakfast);
+        assert_eq!(repeat.start_date_int, repeat.from_date_int + 1);
+        assert_eq!(repeat.days, 5);
+    }
+
+    #[test]
+    fn test_input_parsing_explicit() {
+        let json = r#"{"fatsecret": {"consumer_key": "k", "consumer_secret": "s"}, "access_token": "t", "access_secret": "s", "days": 3, "meal": "lunch", "from_date": "2024-01-14", "start_date": "2024-01-20", "to_meal": "dinner", "dry_run": true}"#;
+        let input: Input = serde_json::from_str(json).expect("Failed to parse test JSON");
+        assert!(input.dry_run);
+
+        let copies =
+            plan_repeat(&repeat_meal(&input).expect("Failed to resolve input")).expect("plan");
+        let dates: Vec<_> = copies.iter().map(|c| c.to_date.as_str()).collect();
+        assert_eq!(dates, vec!["2024-01-20", "2024-01-21", "2024-01-22"]);
+        assert!(copies
+            .iter()
+            .all(|c| c.from_meal == MealType::Lunch && c.to_meal == MealType::Dinner));
+    }
+
+    #[test]
+    fn test_invalid_input() {
+        let json =
+            r#"{"access_token": "t", "access_secret": "s", "days": 2, "from_date": "yesterday"}"#;
+        let input: Input = serde_json::from_str(json).expect("Failed to parse test JSON");
+        assert!(repeat_meal(&input).is_err());
+
+        let json = r#"{"access_token": "t", "access_secret": "s"}"#;
+        assert!(serde_json::from_str::<Input>(json).is_err());
+    }
+
+    #[test]
+    fn test_output_serialize_dry_run() {
+        let output = Output {
+            success: true,
+            dry_run: true,
+            source_entries: Vec::new(),
+            copies: Vec::new(),
+            copied: 0,
+        };
+        let json = serde_json::to_string(&output).expect("Failed to serialize output JSON");
+        assert!(json.contains(r#""dry_run":true"#));
+        assert!(json.contains(r#""copied":0"#));
+    }
+}
@@ -1,22 +1,29 @@
 //! FatSecret OAuth callback handler
 //!
 //! Starts a temporary HTTP server to receive the OAuth callback,
-//! exchanges the request token for an access token, and stores it.
+//! exchanges the request token for an access token, and stores it for the
+//! user who started the flow (the owner of the pending token).
 //!
-//! JSON stdin: `{"port": 8765, "timeout_secs": 300}`
-//! JSON stdout: `{"success": true, "message": "Connected to FatSecret"}`
+//! JSON stdin: `{"port": 8765, "timeout_secs": 300, "user": "alex"}`
+//! JSON stdout: `{"success": true, "user": "alex", "message": "Connected to FatSecret"}`
+//!
+//! `user` is optional; when given, the callback must be for that user's flow.
 
 // CLI binaries: exit and JSON unwrap are acceptable at the top level
 #![allow(clippy::exit, clippy::unwrap_used)]
-#![allow(clippy::too_many_lines, clippy::clone_on_ref_ptr)]
+#![allow(
+    clippy::too_many_lines,
+    clippy::cognitive_complexity,
+    clippy::clone_on_ref_ptr
+)]
 #![allow(clippy::significant_drop_in_scrutinee, clippy::let_underscore_must_use)]
 #![allow(clippy::indexing_slicing)]
 
+use meal_planner::db::Database;
 use meal_planner::fatsecret::core::oauth::get_access_token;
 use meal_planner::fatsecret::core::{FatSecretConfig, FatSecretError};
-use meal_planner::fatsecret::TokenStorage;
+use meal_planner::fatsecret::{validate_user_id, TokenStorage, TokenStore};
 use serde::{Deserialize, Serialize};
-use sqlx::postgres::PgPoolOptions;
 use std::env;
 use std::io::{self, Read};
 use std::net::SocketAddr;
@@ -32,6 +39,9 @@ struct Input {
     /// Timeout in seconds to wait for callback (default: 300 = 5 min)
     #[serde(default = "default_timeout")]
     timeout_secs: u64,
+    /// Expected user (optional; defaults to whoever started the flow)
+    #[serde(default)]
+    user: Option<String>,
 }
 
 fn default_port() -> u16 {
@@ -45,6 +55,7 @@ fn default_timeout() -> u64 {
 #[derive(Serialize)]
 struct Output {
     success: bool,
+    user: String,
     message: String,
 }
 
@@ -83,18 +94,19 @@ async fn run() -> Result<Output, Box<dyn std::error::Error + Send + Sync>> {
     let mut input_str = String::new();
     io::stdin().read_to_string(&mut input_str)?;
     let input: Input = serde_json::from_str(&input_str)?;
+    if let Some(user) = &input.user {
+        validate_user_id(user)?;
+    }
 
     // Get config from environment
     let config = FatSecretConfig::from_env().map_err(|_| FatSecretError::ConfigMissing)?;
 
     // Get database connection
     let database_url = env::var("DATABASE_URL").map_err(|_| "DATABASE_URL not set")?;
-    let pool = PgPoolOptions::new()
-        .max_connections(2)
-        .connect(&database_url)
-        .await?;
+    let db = Database::connect(&database_url).await?;
 
-    let storage = Arc::new(TokenStorage::new(pool));
+    let storage = Arc::new(TokenStorage::new(db));
+    storage.ensure_schema().await?;
 
     // Channel to receive callback result
     let (tx, rx) = oneshot::channel::<CallbackParams>();
@@ -198,6 +210,9 @@ async fn run() -> Result<Output, Box<dyn std::error::Error + Send + Sync>> {
     // Get the callback params
     let params = rx.await.map_err(|_| "Failed to receive callback")?;
 
+    let user = pending_token_user(&storage, &params.oauth_token, input.user.as_deref()).await?;
+    let storage = storage.as_ref().clone().with_user(user.clone());
+
     // Retrieve pending token from database
     let request_token = storage
         .get_pending_token(&params.oauth_token)
@@ -220,6 +235,26 @@ async fn run() -> Result<Output, Box<dyn std::error::Error + Send + Sync>> {
 
     Ok(Output {
         success: true,
+        user,
         message: "Successfully connected to FatSecret. Access token stored.".to_string(),
     })
 }
+
+/// User who started the flow for a pending token
+///
+/// Fails if there is no pending token or it belongs to someone other than
+/// `expected`.
+async fn pending_token_user(
+    storage: &TokenStorage,
+    oauth_token: &str,
+    expected: Option<&str>,
+) -> Result<String, Box<dyn std::error::Error + Send + Sync>> {
+    let user = storage
+        .get_pending_token_user(oauth_token)
+        .await?
+        .ok_or_else(|| format!("No pending token found for oauth_token: {}", oauth_token))?;
+    if let Some(expected) = expected.filter(|expected| *expected != user) {
+        return Err(format!("Callback is for user {user}, expected {expected}").into());
+    }
+    Ok(user)
+}
@@ -12,15 +12,21 @@
 //! JSON stdin (standalone format - uses env vars for credentials):
 //!   `{"oauth_token": "...", "oauth_token_secret": "...", "oauth_verifier": "..."}`
 //!
+//! With `"user": "alex"` the access token is also stored in the database
+//! (`DATABASE_URL`) for that user and the user's pending token is removed.
+//!
 //! JSON stdout: `{"success": true, "oauth_token": "...", "oauth_token_secret": "..."}`
 
 // CLI binaries: exit and unwrap/expect are acceptable at the top level
 #![allow(clippy::exit, clippy::unwrap_used, clippy::expect_used)]
 
+use meal_planner::db::Database;
 use meal_planner::fatsecret::core::oauth::{get_access_token, RequestToken};
 use meal_planner::fatsecret::core::FatSecretConfig;
 use meal_planner::fatsecret::crypto::validate_encryption_at_startup;
+use meal_planner::fatsecret::{validate_user_id, TokenStorage, TokenStore};
 use serde::{Deserialize, Serialize};
+use std::env;
 use std::io::{self, Read};
 
 /// `FatSecret` resource (matches Windmill resource-fatsecret format)
@@ -40,6 +46,9 @@ struct Input {
     oauth_token_secret: String,
     /// OAuth verifier code from user authorization
     oauth_verifier: String,
+    /// User to store the access token for (optional)
+    #[serde(default)]
+    user: Option<String>,
 }
 
 #[derive(Serialize)]
@@ -49,6 +58,9 @@ struct Output {
     oauth_token: String,
     /// Access token secret for signing requests
     oauth_token_secret: String,
+    /// User the access token was stored for
+    #[serde(skip_serializing_if = "Option::is_none")]
+    user: Option<String>,
 }
 
 #[derive(Serialize)]
@@ -91,6 +103,9 @@ async fn run() -> Result<Output, Box<dyn std::error::Error>> {
     let mut input_str = String::new();
     io::stdin().read_to_string(&mut input_str)?;
     let input: Input = serde_json::from_str(&input_str)?;
+    if let Some(user) = &input.user {
+        validate_user_id(user)?;
+    }
 
     // Get config: prefer input, fall back to environment
     let config = match input.fatsecret {
@@ -99,7 +114,7 @@ async fn run() -> Result<Output, Box<dyn std::error::Error>> {
         None => FatSecretConfig::from_env().map_err(|e| format!("Invalid configuration: {}", e))?,
     };
 
-    // Reconstruct request token from input (no database needed)
+    // Reconstruct request token from input
     let pending = RequestToken {
         oauth_token: input.oauth_token,
         oauth_token_secret: input.oauth_token_secret,
@@ -109,9 +124,20 @@ async fn run() -> Result<Output, Box<dyn std::error::Error>> {
     // Exchange request token for access token
     let access_token = get_access_token(&config, &pending, &input.oauth_verifier).await?;
 
+    if let Some(user) = &input.user {
+        let database_url =
+            env::var("DATABASE_URL").map_err(|_| "DATABASE_URL not set (required with user)")?;
+        let db = Database::connect(&database_url).await?;
+        let storage = TokenStorage::new(db).with_user(user.clone());
+        storage.ensure_schema().await?;
+        storage.store_access_token(&access_token).await?;
+        storage.delete_pending_token(&pending.oauth_token).await?;
+    }
+
     Ok(Output {
         success: true,
         oauth_token: access_token.oauth_token,
         oauth_token_secret: access_token.oauth_token_secret,
+        user: input.user,
     })
 }
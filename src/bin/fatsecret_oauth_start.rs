@@ -12,15 +12,23 @@
 //! JSON stdin (standalone format - uses env vars for credentials):
 //!   `{"callback_url": "http://localhost:8765/callback"}`
 //!
+//! With `"user": "alex"` the pending token is also stored in the database
+//! (`DATABASE_URL`) for that user, so `fatsecret_oauth_callback` or
+//! `fatsecret_oauth_complete` can finish the flow and store the access token
+//! under the same user.
+//!
 //! JSON stdout: `{"success": true, "auth_url": "https://...", "oauth_token": "...", "oauth_token_secret": "..."}`
 
 // CLI binaries: exit and unwrap/expect are acceptable at the top level
 #![allow(clippy::exit, clippy::unwrap_used, clippy::expect_used)]
 
+use meal_planner::db::Database;
 use meal_planner::fatsecret::core::oauth::get_request_token;
 use meal_planner::fatsecret::core::FatSecretConfig;
 use meal_planner::fatsecret::crypto::validate_encryption_at_startup;
+use meal_planner::fatsecret::{validate_user_id, TokenStorage, TokenStore};
 use serde::{Deserialize, Serialize};
+use std::env;
 use std::io::{self, Read};
 
 /// `FatSecret` resource (matches Windmill resource-fatsecret format)
@@ -36,6 +44,9 @@ struct Input {
     fatsecret: Option<FatSecretResource>,
     /// Callback URL for OAuth redirect (e.g., "<http://localhost:8765/callback>" or "oob")
     callback_url: String,
+    /// User starting the flow; stores the pending token for them (optional)
+    #[serde(default)]
+    user: Option<String>,
 }
 
 #[derive(Serialize)]
@@ -47,6 +58,9 @@ struct Output {
     oauth_token: String,
     /// Request token secret (needed for `oauth_complete`)
     oauth_token_secret: String,
+    /// User the pending token was stored for
+    #[serde(skip_serializing_if = "Option::is_none")]
+    user: Option<String>,
 }
 
 #[derive(Serialize)]
@@ -90,6 +104,9 @@ async fn run() -> Result<Output, Box<dyn std::error::Error>> {
     let mut input_str = String::new();
     io::stdin().read_to_string(&mut input_str)?;
     let input: Input = serde_json::from_str(&input_str)?;
+    if let Some(user) = &input.user {
+        validate_user_id(user)?;
+    }
 
     // Get config: prefer input, fall back to environment
     let config = match input.fatsecret {
@@ -98,9 +115,19 @@ async fn run() -> Result<Output, Box<dyn std::error::Error>> {
         None => FatSecretConfig::from_env().map_err(|e| format!("Invalid configuration: {}", e))?,
     };
 
-    // Get request token from FatSecret (no database needed)
+    // Get request token from FatSecret
     let request_token = get_request_token(&config, &input.callback_url).await?;
 
+    // Tie the pending token to the user who started the flow
+    if let Some(user) = &input.user {
+        let database_url =
+            env::var("DATABASE_URL").map_err(|_| "DATABASE_URL not set (required with user)")?;
+        let db = Database::connect(&database_url).await?;
+        let storage = TokenStorage::new(db).with_user(user.clone());
+        storage.ensure_schema().await?;
+        storage.store_pending_token(&request_token).await?;
+    }
+
     // Build authorization URL
     let auth_url = config.authorization_url(&request_token.oauth_token);
 
@@ -109,5 +136,6 @@ async fn run() -> Result<Output, Box<dyn std::error::Error>> {
         auth_url,
         oauth_token: request_token.oauth_token,
         oauth_token_secret: request_token.oauth_token_secret,
+        user: input.user,
     })
 }
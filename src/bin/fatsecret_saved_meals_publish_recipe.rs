@@ -0,0 +1,239 @@
+//! Publish a Tandoor recipe as a `FatSecret` saved meal
+//!
+//! Fetches the recipe from Tandoor, matches each ingredient to a `FatSecret`
+//! food and serving, and creates a saved meal holding those items. Re-running
+//! for the same recipe updates the existing saved meal in place.
+//! This is a 3-legged OAuth request (requires user access token).
+//!
+//! JSON input (CLI arg or stdin):
+//!   `{"tandoor": {...}, "fatsecret": {...}, "access_token": "...", "access_secret": "...",
+//!     "recipe_id": 42, "portions": 1, "meals": "lunch,dinner",
+//!     "food_overrides": {"olive oil": {"food_id": "33815", "serving_id": "31990"}}}`
+//!
+//! `portions` is the number of recipe servings one logged meal represents
+//! (default 1). `meals` defaults to all meal types. `food_overrides` pins
+//! ingredients (by name, case-insensitive) to specific `FatSecret` foods.
+//!
+//! JSON stdout: `{"success": true, "saved_meal_id": "...", "created": true, "unresolved": [...], ...}`
+
+#![allow(clippy::exit, clippy::unwrap_used, clippy::expect_used)]
+
+use meal_planner::fatsecret::core::{AccessToken, FatSecretConfig};
+use meal_planner::fatsecret::saved_meals::publish::{
+    publish_recipe, FoodOverride, PublishOptions, PublishReport,
+};
+use meal_planner::fatsecret::saved_meals::MealType;
+use meal_planner::tandoor::{TandoorClient, TandoorConfig};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::io::{self, Read};
+
+/// `FatSecret` resource (matches Windmill resource-fatsecret format)
+#[derive(Deserialize)]
+struct FatSecretResource {
+    consumer_key: String,
+    consumer_secret: String,
+}
+
+#[derive(Deserialize)]
+struct Input {
+    /// Tandoor configuration (URL and token)
+    tandoor: TandoorConfig,
+    /// `FatSecret` credentials (optional - falls back to env vars)
+    fatsecret: Option<FatSecretResource>,
+    /// OAuth access token (required for 3-legged requests)
+    access_token: String,
+    /// OAuth access secret (required for 3-legged requests)
+    access_secret: String,
+    /// Tandoor recipe ID
+    recipe_id: i64,
+    /// Recipe servings per logged meal (default 1)
+    #[serde(default)]
+    portions: Option<f64>,
+    /// Meal types (comma-separated: breakfast,lunch,dinner,other)
+    #[serde(default)]
+    meals: Option<String>,
+    /// Ingredient name → `FatSecret` food
+    #[serde(default)]
+    food_overrides: HashMap<String, FoodOverride>,
+}
+
+#[derive(Serialize)]
+struct Output {
+    success: bool,
+    #[serde(flatten)]
+    report: PublishReport,
+}
+
+#[derive(Serialize)]
+struct ErrorOutput {
+    success: bool,
+    error: String,
+}
+
+#[tokio::main]
+async fn main() {
+    match run().await {
+        Ok(output) => {
+            println!(
+                "{}",
+                serde_json::to_string(&output).expect("Failed to serialize output JSON")
+            );
+        }
+        Err(e) => {
+            let error = ErrorOutput {
+                success: false,
+                error: e.to_string(),
+            };
+            println!(
+                "{}",
+                serde_json::to_string(&error).expect("Failed to serialize error JSON")
+            );
+            std::process::exit(1);
+        }
+    }
+}
+
+async fn run() -> Result<Output, Box<dyn std::error::Error>> {
+    let input = read_input()?;
+    let options = publish_options(&input)?;
+
+    // Get config: prefer input, fall back to environment
+    let config = match input.fatsecret {
+        Some(resource) => FatSecretConfig::new(resource.consumer_key, resource.consumer_secret)
+            .map_err(|e| format!("Invalid FatSecret credentials: {}", e))?,
+        None => FatSecretConfig::from_env().map_err(|e| format!("Invalid configuration: {}", e))?,
+    };
+    let token = AccessToken::new(input.access_token, input.access_secret);
+
+    // Tandoor client is blocking: fetch the recipe off the async runtime
+    let tandoor = input.tandoor;
+    let recipe_id = input.recipe_id;
+    let recipe = tokio::task::spawn_blocking(move || {
+        TandoorClient::new(&tandoor)
+            .and_then(|client| client.get_recipe(recipe_id))
+            .map_err(|e| e.to_string())
+    })
+    .await??;
+
+    let report = publish_recipe(&config, &token, &recipe, &options).await?;
+
+    Ok(Output {
+        success: true,
+        report,
+    })
+}
+
+fn read_input() -> Result<Input, Box<dyn std::error::Error>> {
+    if let Some(arg) = std::env::args().nth(1) {
+        Ok(serde_json::from_str(&arg)?)
+    } else {
+        let mut input_str = String::new();
+        io::stdin().read_to_string(&mut input_str)?;
+        Ok(serde_json::from_str(&input_str)?)
+    }
+}
+
+fn parse_meals(meals_str: &str) -> Result<Vec<MealType>, String> {
+    meals_str
+        .split(',')
+        .map(str::trim)
+        .filter(|s| !s.is_empty())
+        .map(|s| match s {
+            "breakfast" => Ok(MealType::Breakfast),
+            "lunch" => Ok(MealType::Lunch),
+            "dinner" => Ok(MealType::Dinner),
+            "other" | "snack" => Ok(MealType::Snack),
+            _ => Err(format!("Invalid meal type: {s}")),
+        })
+        .collect::<Result<Vec<_>, _>>()
+}
+
+fn publish_options(input: &Input) -> Result<PublishOptions, String> {
+    let mut options = PublishOptions::default();
+    if let Some(portions) = input.portions {
+        if portions <= 0.0 {
+            return Err(format!("portions must be positive, got {portions}"));
+        }
+        options.portions = portions;
+    }
+    if let Some(meals) = input.meals.as_deref() {
+        options.meals = parse_meals(meals)?;
+    }
+    options.food_overrides = input
+        .food_overrides
+        .iter()
+        .map(|(name, food)| (name.trim().to_lowercase(), food.clone()))
+        .collect();
+    Ok(options)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use meal_planner::fatsecret::saved_meals::SavedMealId;
+
+    const TANDOOR: &str =
+        r#""tandoor": {"base_url": "http://localhost:8090", "api_token": "test"}"#;
+
+    #[test]
+    fn test_input_parsing_defaults() {
+        let json =
+            format!(r#"{{{TANDOOR}, "access_token": "t", "access_secret": "s", "recipe_id": 42}}"#);
+        let input: Input = serde_json::from_str(&json).expect("Failed to parse test JSON");
+        assert_eq!(input.recipe_id, 42);
+        let options = publish_options(&input).expect("Failed to build options");
+        assert!((options.portions - 1.0).abs() < f64::EPSILON);
+        assert_eq!(options.meals.len(), 4);
+        assert!(options.food_overrides.is_empty());
+    }
+
+    #[test]
+    fn test_input_parsing_full() {
+        let json = format!(
+            r#"{{{TANDOOR}, "fatsecret": {{"consumer_key": "k", "consumer_secret": "s"}}, "access_token": "t", "access_secret": "s", "recipe_id": 7, "portions": 2, "meals": "lunch, dinner", "food_overrides": {{"Olive Oil": {{"food_id": "33815"}}}}}}"#
+        );
+        let input: Input = serde_json::from_str(&json).expect("Failed to parse test JSON");
+        let options = publish_options(&input).expect("Failed to build options");
+        assert!((options.portions - 2.0).abs() < f64::EPSILON);
+        assert_eq!(options.meals, vec![MealType::Lunch, MealType::Dinner]);
+        let olive_oil = options
+            .food_overrides
+            .get("olive oil")
+            .expect("Override should be keyed by lowercase name");
+        assert_eq!(olive_oil.food_id, "33815");
+        assert!(olive_oil.serving_id.is_none());
+    }
+
+    #[test]
+    fn test_invalid_options() {
+        let json = format!(
+            r#"{{{TANDOOR}, "access_token": "t", "access_secret": "s", "recipe_id": 7, "portions": 0}}"#
+        );
+        let input: Input = serde_json::from_str(&json).expect("Failed to parse test JSON");
+        assert!(publish_options(&input).is_err());
+        assert!(parse_meals("brunch").is_err());
+    }
+
+    #[test]
+    fn test_output_serialize() {
+        let output = Output {
+            success: true,
+            report: PublishReport {
+                saved_meal_id: SavedMealId::new("123"),
+                saved_meal_name: "Chili".to_string(),
+                created: true,
+                resolved: vec![],
+                unresolved: vec![],
+                items_added: 3,
+                items_updated: 0,
+                items_removed: 0,
+                items_unchanged: 0,
+            },
+        };
+        let json = serde_json::to_string(&output).expect("Failed to serialize output JSON");
+        assert!(json.contains(r#""success":true"#));
+        assert!(json.contains(r#""saved_meal_id":"123""#));
+        assert!(json.contains(r#""items_added":3"#));
+    }
+}
@@ -0,0 +1,234 @@
+//! Check a stored FatSecret access token and start re-authorization if needed
+//!
+//! Probes the user's stored token with `profile.get` and records the result:
+//! a working token has its `last_used_at` updated, a token FatSecret rejects
+//! with an auth error is marked revoked (`fatsecret_get_token` then reports
+//! `"revoked"`).
+//!
+//! When the token is revoked or missing, a new OAuth flow is started for the
+//! user: the pending token is stored and the authorization URL is returned,
+//! so the flow can be finished with `fatsecret_oauth_complete` or
+//! `fatsecret_oauth_callback`.
+//!
+//! JSON stdin: `{"fatsecret": {...}, "user": "alex", "callback_url": "oob"}` (all fields optional;
+//! credentials fall back to env vars, `user` to `"default"`)
+//!
+//! JSON stdout: `{"success": true, "user": "alex", "status": "revoked", "code": 9, "message": "...",
+//! "reauth_required": true, "auth_url": "https://...", "oauth_token": "...", "oauth_token_secret": "..."}`
+//!
+//! `status` is one of `"healthy"`, `"not_connected"`, `"revoked"` or `"unknown"`
+//! (the probe failed for another reason, e.g. network; `error` says why).
+
+// CLI binaries: exit and JSON unwrap are acceptable at the top level
+#![allow(clippy::exit, clippy::unwrap_used)]
+
+use meal_planner::db::Database;
+use meal_planner::fatsecret::core::oauth::get_request_token;
+use meal_planner::fatsecret::core::FatSecretConfig;
+use meal_planner::fatsecret::{
+    check_token_health, validate_user_id, TokenHealth, TokenStorage, TokenStore, DEFAULT_USER,
+};
+use serde::{Deserialize, Serialize};
+use std::env;
+use std::io::{self, Read};
+
+/// `FatSecret` resource (matches Windmill resource-fatsecret format)
+#[derive(Deserialize)]
+struct FatSecretResource {
+    consumer_key: String,
+    consumer_secret: String,
+}
+
+#[derive(Default, Deserialize)]
+struct Input {
+    /// `FatSecret` credentials (optional - falls back to env vars)
+    #[serde(default)]
+    fatsecret: Option<FatSecretResource>,
+    /// User whose token to check (defaults to "default")
+    #[serde(default)]
+    user: Option<String>,
+    /// Callback URL for a new OAuth flow (defaults to "oob")
+    #[serde(default)]
+    callback_url: Option<String>,
+}
+
+/// Authorization step of a new OAuth flow
+#[derive(Serialize)]
+struct Reauthorization {
+    /// URL the user should visit to authorize again
+    auth_url: String,
+    /// Request token (needed for `oauth_complete`)
+    oauth_token: String,
+    /// Request token secret (needed for `oauth_complete`)
+    oauth_token_secret: String,
+}
+
+#[derive(Serialize)]
+struct Output {
+    success: bool,
+    /// User the token belongs to
+    user: String,
+    /// Probe result: `status` plus its details
+    #[serde(flatten)]
+    health: TokenHealth,
+    /// Whether the user must authorize again
+    reauth_required: bool,
+    /// New OAuth flow (only when re-authorization is required)
+    #[serde(flatten, skip_serializing_if = "Option::is_none")]
+    reauthorization: Option<Reauthorization>,
+}
+
+#[derive(Serialize)]
+struct ErrorOutput {
+    success: bool,
+    error: String,
+}
+
+#[tokio::main]
+async fn main() {
+    match run().await {
+        Ok(output) => {
+            println!("{}", serde_json::to_string(&output).unwrap());
+        }
+        Err(e) => {
+            let error = ErrorOutput {
+                success: false,
+                error: e.to_string(),
+            };
+            println!("{}", serde_json::to_string(&error).unwrap());
+            std::process::exit(1);
+        }
+    }
+}
+
+async fn run() -> Result<Output, Box<dyn std::error::Error>> {
+    // Read input
+    let mut input_str = String::new();
+    io::stdin().read_to_string(&mut input_str)?;
+
+    // Handle empty input
+    let input: Input = if input_str.trim().is_empty() {
+        Input::default()
+    } else {
+        serde_json::from_str(&input_str)?
+    };
+
+    let user = input.user.unwrap_or_else(|| DEFAULT_USER.to_string());
+    validate_user_id(&user)?;
+
+    // Get config: prefer input, fall back to environment
+    let config = match input.fatsecret {
+        Some(resource) => FatSecretConfig::new(resource.consumer_key, resource.consumer_secret)
+            .map_err(|e| format!("Invalid FatSecret credentials: {e}"))?,
+        None => FatSecretConfig::from_env().map_err(|e| format!("Invalid configuration: {e}"))?,
+    };
+
+    let database_url = env::var("DATABASE_URL").map_err(|_| "DATABASE_URL not set")?;
+    let db = Database::connect(&database_url).await?;
+    let storage = TokenStorage::new(db).with_user(user.clone());
+    storage.ensure_schema().await?;
+
+    let health = check_token_health(&config, &storage).await?;
+    let reauth_required = health.reauth_required();
+
+    let reauthorization = if reauth_required {
+        let callback_url = input.callback_url.as_deref().unwrap_or("oob");
+        let request_token = get_request_token(&config, callback_url).await?;
+        storage.store_pending_token(&request_token).await?;
+
+        Some(Reauthorization {
+            auth_url: config.authorization_url(&request_token.oauth_token),
+            oauth_token: request_token.oauth_token,
+            oauth_token_secret: request_token.oauth_token_secret,
+        })
+    } else {
+        None
+    };
+
+    Ok(Output {
+        success: true,
+        user,
+        health,
+        reauth_required,
+        reauthorization,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_input_parsing_defaults() {
+        let input: Input = serde_json::from_str("{}").unwrap();
+        assert!(input.fatsecret.is_none());
+        assert!(input.user.is_none());
+        assert!(input.callback_url.is_none());
+    }
+
+    #[test]
+    fn test_input_parsing_full() {
+        let json = r#"{"fatsecret": {"consumer_key": "k", "consumer_secret": "s"}, "user": "alex", "callback_url": "http://localhost:8765/callback"}"#;
+        let input: Input = serde_json::from_str(json).unwrap();
+        assert!(input.fatsecret.is_some());
+        assert_eq!(input.user.as_deref(), Some("alex"));
+        assert_eq!(
+            input.callback_url.as_deref(),
+            Some("http://localhost:8765/callback")
+        );
+    }
+
+    #[test]
+    fn test_healthy_output_has_no_auth_url() {
+        let output = Output {
+            success: true,
+            user: "alex".to_string(),
+            health: TokenHealth::Healthy,
+            reauth_required: false,
+            reauthorization: None,
+        };
+        let json = serde_json::to_value(&output).unwrap();
+        assert_eq!(
+            json,
+            serde_json::json!({
+                "success": true,
+                "user": "alex",
+                "status": "healthy",
+                "reauth_required": false,
+            })
+        );
+    }
+
+    #[test]
+    fn test_revoked_output_includes_reauthorization() {
+        let output = Output {
+            success: true,
+            user: "default".to_string(),
+            health: TokenHealth::Revoked {
+                code: 9,
+                message: "Invalid access token".to_string(),
+            },
+            reauth_required: true,
+            reauthorization: Some(Reauthorization {
+                auth_url: "https://authentication.fatsecret.com/oauth/authorize?oauth_token=rt"
+                    .to_string(),
+                oauth_token: "rt".to_string(),
+                oauth_token_secret: "rs".to_string(),
+            }),
+        };
+        assert_eq!(
+            serde_json::to_value(&output).unwrap(),
+            serde_json::json!({
+                "success": true,
+                "user": "default",
+                "status": "revoked",
+                "code": 9,
+                "message": "Invalid access token",
+                "reauth_required": true,
+                "auth_url": "https://authentication.fatsecret.com/oauth/authorize?oauth_token=rt",
+                "oauth_token": "rt",
+                "oauth_token_secret": "rs",
+            })
+        );
+    }
+}
@@ -0,0 +1,203 @@
+//! `FatSecret` weight trend analytics
+//!
+//! Fetches several months of weigh-ins and computes a smoothed trend weight,
+//! weekly rate of change, estimated energy balance and, using the profile's
+//! goal weight, the projected date the goal will be reached.
+//! This is a 3-legged OAuth request (requires user access token).
+//!
+//! JSON input (CLI arg or stdin):
+//!   `{"fatsecret": {...}, "access_token": "...", "access_secret": "...",
+//!     "date": "2024-03-31", "months": 3, "goal_weight_kg": 75.0, "rate_window_days": 28}`
+//!
+//! `date` (end of the range) defaults to today. `months` defaults to 3.
+//! `goal_weight_kg` overrides the goal stored in the `FatSecret` profile.
+//!
+//! JSON stdout: `{"success": true, "trend": {"trend_weight_kg": ..., "chart": {...}, ...}}`
+//! (`trend` is null when no weigh-ins were found)
+
+#![allow(clippy::exit, clippy::unwrap_used, clippy::expect_used)]
+
+use meal_planner::fatsecret::core::{AccessToken, FatSecretConfig};
+use meal_planner::fatsecret::diary::date_to_int;
+use meal_planner::fatsecret::profile::get_profile;
+use meal_planner::fatsecret::weight::get_weight_month_summary;
+use meal_planner::fatsecret::weight::trend::{
+    analyze, month_dates, WeightTrend, DEFAULT_RATE_WINDOW_DAYS,
+};
+use serde::{Deserialize, Serialize};
+use std::io::{self, Read};
+
+/// Months fetched when none are requested
+const DEFAULT_MONTHS: u32 = 3;
+
+/// `FatSecret` resource (matches Windmill resource-fatsecret format)
+#[derive(Deserialize)]
+struct FatSecretResource {
+    consumer_key: String,
+    consumer_secret: String,
+}
+
+#[derive(Deserialize)]
+struct Input {
+    /// `FatSecret` credentials (optional - falls back to env vars)
+    fatsecret: Option<FatSecretResource>,
+    /// OAuth access token (required for 3-legged requests)
+    access_token: String,
+    /// OAuth access secret (required for 3-legged requests)
+    access_secret: String,
+    /// Last day of the range (YYYY-MM-DD, defaults to today)
+    #[serde(default)]
+    date: Option<String>,
+    /// Number of months to analyse
+    #[serde(default)]
+    months: Option<u32>,
+    /// Goal weight (defaults to the profile goal)
+    #[serde(default)]
+    goal_weight_kg: Option<f64>,
+    /// Days used for the rate of change
+    #[serde(default)]
+    rate_window_days: Option<usize>,
+}
+
+#[derive(Serialize)]
+struct Output {
+    success: bool,
+    trend: Option<WeightTrend>,
+}
+
+#[derive(Serialize)]
+struct ErrorOutput {
+    success: bool,
+    error: String,
+}
+
+#[tokio::main]
+async fn main() {
+    match run().await {
+        Ok(output) => {
+            println!(
+                "{}",
+                serde_json::to_string(&output).expect("Failed to serialize output JSON")
+            );
+        }
+        Err(e) => {
+            let error = ErrorOutput {
+                success: false,
+                error: e.to_string(),
+            };
+            println!(
+                "{}",
+                serde_json::to_string(&error).expect("Failed to serialize error JSON")
+            );
+            std::process::exit(1);
+        }
+    }
+}
+
+async fn run() -> Result<Output, Box<dyn std::error::Error>> {
+    let input = read_input()?;
+    let end_date_int = end_date_int(input.date.as_deref())?;
+    let months = input.months.unwrap_or(DEFAULT_MONTHS).max(1);
+
+    // Get config: prefer input, fall back to environment
+    let config = match input.fatsecret {
+        Some(resource) => FatSecretConfig::new(resource.consumer_key, resource.consumer_secret)
+            .map_err(|e| format!("Invalid FatSecret credentials: {}", e))?,
+        None => FatSecretConfig::from_env().map_err(|e| format!("Invalid configuration: {}", e))?,
+    };
+    let token = AccessToken::new(input.access_token, input.access_secret);
+
+    let mut days = Vec::new();
+    for date_int in month_dates(end_date_int, months)? {
+        days.extend(
+            get_weight_month_summary(&config, &token, date_int)
+                .await?
+                .days
+                .into_iter()
+                .filter(|day| day.date_int <= end_date_int),
+        );
+    }
+
+    let goal_weight_kg = match input.goal_weight_kg {
+        Some(goal) => Some(goal),
+        None => get_profile(&config, &token).await?.goal_weight_kg,
+    };
+
+    let trend = analyze(
+        &days,
+        goal_weight_kg,
+        input.rate_window_days.unwrap_or(DEFAULT_RATE_WINDOW_DAYS),
+    )?;
+
+    Ok(Output {
+        success: true,
+        trend,
+    })
+}
+
+fn read_input() -> Result<Input, Box<dyn std::error::Error>> {
+    if let Some(arg) = std::env::args().nth(1) {
+        Ok(serde_json::from_str(&arg)?)
+    } else {
+        let mut input_str = String::new();
+        io::stdin().read_to_string(&mut input_str)?;
+        Ok(serde_json::from_str(&input_str)?)
+    }
+}
+
+/// End of the analysed range: the given date or today
+fn end_date_int(date: Option<&str>) -> Result<i32, String> {
+    date.map_or_else(
+        || {
+            date_to_int(
+                &chrono::Local::now()
+                    .date_naive()
+                    .format("%Y-%m-%d")
+                    .to_string(),
+            )
+        },
+        date_to_int,
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_input_parsing_defaults() {
+        let json = r#"{"access_token": "t", "access_secret": "s"}"#;
+        let input: Input = serde_json::from_str(json).expect("Failed to parse test JSON");
+        assert!(input.fatsecret.is_none());
+        assert!(input.date.is_none());
+        assert!(input.months.is_none());
+        assert!(input.goal_weight_kg.is_none());
+        assert!(input.rate_window_days.is_none());
+    }
+
+    #[test]
+    fn test_input_parsing_full() {
+        let json = r#"{"fatsecret": {"consumer_key": "k", "consumer_secret": "s"}, "access_token": "t", "access_secret": "s", "date": "2024-03-31", "months": 6, "goal_weight_kg": 72.5, "rate_window_days": 14}"#;
+        let input: Input = serde_json::from_str(json).expect("Failed to parse test JSON");
+        assert_eq!(input.months, Some(6));
+        assert_eq!(input.goal_weight_kg, Some(72.5));
+        assert_eq!(input.rate_window_days, Some(14));
+        assert_eq!(end_date_int(input.date.as_deref()), Ok(19813));
+    }
+
+    #[test]
+    fn test_end_date_defaults_to_today() {
+        assert!(end_date_int(None).is_ok());
+        assert!(end_date_int(Some("31/03/2024")).is_err());
+    }
+
+    #[test]
+    fn test_output_serialize_without_data() {
+        let output = Output {
+            success: true,
+            trend: None,
+        };
+        let json = serde_json::to_string(&output).expect("Failed to serialize output JSON");
+        assert_eq!(json, r#"{"success":true,"trend":null}"#);
+    }
+}
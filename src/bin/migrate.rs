@@ -0,0 +1,217 @@
+//! Show, apply or revert the database schema migrations
+//!
+//! Migrations are embedded from `migrations/` for Postgres and
+//! `migrations/sqlite/` for SQLite (see `meal_planner::db`). Connects to
+//! `DATABASE_URL`; its scheme picks the backend.
+//!
+//! # Usage
+//!
+//! ```bash
+//! cargo run --bin migrate status
+//! cargo run --bin migrate up
+//! cargo run --bin migrate down                          # revert the latest migration
+//! cargo run --bin migrate '{"action": "down", "target": 1}'  # revert everything after 1
+//! ```
+//!
+//! JSON input (CLI arg or stdin): `{"action": "status" | "up" | "down", "target": 1}`;
+//! a bare action name is accepted too and empty input means `status`.
+//!
+//! JSON stdout: `{"success": true, "backend": "postgres", "action": "up", "applied": [2, 3], "reverted": [],
+//!   "migrations": [{"version": 1, "description": "create fatsecret oauth tables", "state": "applied"}, ...]}`
+
+// CLI binaries: exit and JSON unwrap are acceptable at the top level
+#![allow(clippy::exit, clippy::unwrap_used)]
+
+use meal_planner::db::{Backend, Database, MigrationState, MigrationStatus};
+use serde::{Deserialize, Serialize};
+use std::env;
+use std::io::{self, Read};
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "lowercase")]
+enum Action {
+    /// List migrations and their state
+    #[default]
+    Status,
+    /// Apply all pending migrations
+    Up,
+    /// Revert the latest migration, or all after `target`
+    Down,
+}
+
+#[derive(Debug, Default, Deserialize)]
+struct Input {
+    #[serde(default)]
+    action: Action,
+    /// For `down`: keep migrations up to and including this version
+    #[serde(default)]
+    target: Option<i64>,
+}
+
+#[derive(Serialize)]
+struct Output {
+    success: bool,
+    /// Database backend from the `DATABASE_URL` scheme
+    backend: Backend,
+    action: Action,
+    /// Versions applied by this run
+    applied: Vec<i64>,
+    /// Versions reverted by this run, newest first
+    reverted: Vec<i64>,
+    /// State of every migration afterwards
+    migrations: Vec<MigrationStatus>,
+}
+
+#[derive(Serialize)]
+struct ErrorOutput {
+    success: bool,
+    error: String,
+}
+
+#[tokio::main]
+async fn main() {
+    match run().await {
+        Ok(output) => {
+            println!("{}", serde_json::to_string(&output).unwrap());
+        }
+        Err(e) => {
+            let error = ErrorOutput {
+                success: false,
+                error: e.to_string(),
+            };
+            println!("{}", serde_json::to_string(&error).unwrap());
+            std::process::exit(1);
+        }
+    }
+}
+
+async fn run() -> Result<Output, Box<dyn std::error::Error>> {
+    let input = read_input()?;
+    if input.target.is_some() && input.action != Action::Down {
+        return Err("target is only supported for down".into());
+    }
+
+    let database_url = env::var("DATABASE_URL").map_err(|_| "DATABASE_URL not set")?;
+    let db = Database::connect(&database_url).await?;
+
+    let before = db.migration_status().await?;
+    let reverted = match input.action {
+        Action::Status => Vec::new(),
+        Action::Up => {
+            db.run_migrations().await?;
+            Vec::new()
+        }
+        Action::Down => db.revert_migrations(input.target).await?,
+    };
+    let migrations = db.migration_status().await?;
+
+    Ok(Output {
+        success: true,
+        backend: db.backend(),
+        action: input.action,
+        applied: newly_applied(&before, &migrations),
+        reverted,
+        migrations,
+    })
+}
+
+fn read_input() -> Result<Input, Box<dyn std::error::Error>> {
+    let input_str = if let Some(arg) = env::args().nth(1) {
+        arg
+    } else {
+        let mut input_str = String::new();
+        io::stdin().read_to_string(&mut input_str)?;
+        input_str
+    };
+    parse_input(&input_str)
+}
+
+/// Parse JSON input or a bare action name
+fn parse_input(input_str: &str) -> Result<Input, Box<dyn std::error::Error>> {
+    let input_str = input_str.trim();
+    if input_str.is_empty() {
+        return Ok(Input::default());
+    }
+    if input_str.starts_with('{') {
+        return Ok(serde_json::from_str(input_str)?);
+    }
+    Ok(Input {
+        action: serde_json::from_value(serde_json::Value::String(input_str.to_lowercase()))
+            .map_err(|_| format!("Unknown action {input_str:?} (expected status, up or down)"))?,
+        target: None,
+    })
+}
+
+/// Versions pending before the run and applied after it
+fn newly_applied(before: &[MigrationStatus], after: &[MigrationStatus]) -> Vec<i64> {
+    after
+        .iter()
+        .filter(|m| m.state == MigrationState::Applied)
+        .filter(|m| {
+            before
+                .iter()
+                .any(|b| b.version == m.version && b.state == MigrationState::Pending)
+        })
+        .map(|m| m.version)
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn status(version: i64, state: MigrationState) -> MigrationStatus {
+        MigrationStatus {
+            version,
+            description: format!("migration {version}"),
+            state,
+        }
+    }
+
+    #[test]
+    fn test_parse_input_json() {
+        let input = parse_input(r#"{"action": "down", "target": 1}"#).unwrap();
+        assert_eq!(input.action, Action::Down);
+        assert_eq!(input.target, Some(1));
+    }
+
+    #[test]
+    fn test_parse_input_bare_action_and_default() {
+        assert_eq!(parse_input("up\n").unwrap().action, Action::Up);
+        assert_eq!(parse_input("DOWN").unwrap().action, Action::Down);
+        assert_eq!(parse_input("").unwrap().action, Action::Status);
+        assert!(parse_input("sideways").is_err());
+    }
+
+    #[test]
+    fn test_newly_applied() {
+        let before = vec![
+            status(1, MigrationState::Applied),
+            status(2, MigrationState::Pending),
+            status(3, MigrationState::Pending),
+        ];
+        let after = vec![
+            status(1, MigrationState::Applied),
+            status(2, MigrationState::Applied),
+            status(3, MigrationState::Applied),
+        ];
+        assert_eq!(newly_applied(&before, &after), vec![2, 3]);
+        assert!(newly_applied(&after, &after).is_empty());
+    }
+
+    #[test]
+    fn test_output_serialize() {
+        let output = Output {
+            success: true,
+            backend: Backend::Sqlite,
+            action: Action::Status,
+            applied: Vec::new(),
+            reverted: Vec::new(),
+            migrations: vec![status(1, MigrationState::Applied)],
+        };
+        let json = serde_json::to_string(&output).unwrap();
+        assert!(json.contains(r#""backend":"sqlite""#));
+        assert!(json.contains(r#""action":"status""#));
+        assert!(json.contains(r#""state":"applied""#));
+    }
+}
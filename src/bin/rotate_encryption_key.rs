@@ -0,0 +1,173 @@
+//! Re-encrypt stored OAuth secrets with a new encryption key
+//!
+//! Rewrites every secret in `fatsecret_oauth_token` and
+//! `fatsecret_oauth_pending` with the current key, in a single transaction.
+//! If any secret cannot be decrypted, nothing is changed.
+//!
+//! # Usage
+//!
+//! ```bash
+//! # 1. Generate a new key
+//! cargo run --bin generate_encryption_key
+//!
+//! # 2. Make it current and keep the previous key for decryption
+//! export OAUTH_ENCRYPTION_KEY="new_key"
+//! export OAUTH_ENCRYPTION_OLD_KEYS="previous_key"
+//!
+//! # 3. Check that every secret can be read, then rotate
+//! echo '{"dry_run": true}' | cargo run --bin rotate_encryption_key
+//! cargo run --bin rotate_encryption_key < /dev/null
+//!
+//! # 4. Remove OAUTH_ENCRYPTION_OLD_KEYS
+//! ```
+//!
+//! Progress is reported on stderr, one line per row.
+//!
+//! JSON stdin: `{"dry_run": false}` (optional)
+//! JSON stdout: `{"success": true, "key_id": "1a2b3c4d", "old_key_ids": [...], "dry_run": false,
+//!   "tables": [{"table": "fatsecret_oauth_token", "total": 2, "reencrypted": 2, "already_current": 0}, ...]}`
+
+// CLI binaries: exit and JSON unwrap are acceptable at the top level
+#![allow(clippy::exit, clippy::unwrap_used)]
+
+use meal_planner::db::Database;
+use meal_planner::fatsecret::{KeyRing, RotationProgress, TableRotation, TokenStorage, TokenStore};
+use serde::{Deserialize, Serialize};
+use std::env;
+use std::io::{self, Read};
+
+#[derive(Default, Deserialize)]
+struct Input {
+    /// Decrypt and re-encrypt everything, then roll back
+    #[serde(default)]
+    dry_run: bool,
+}
+
+#[derive(Serialize)]
+struct Output {
+    success: bool,
+    /// Key ID all secrets are encrypted with afterwards
+    key_id: String,
+    /// Decrypt-only keys that were available
+    old_key_ids: Vec<String>,
+    dry_run: bool,
+    tables: Vec<TableRotation>,
+}
+
+#[derive(Serialize)]
+struct ErrorOutput {
+    success: bool,
+    error: String,
+}
+
+#[tokio::main]
+async fn main() {
+    match run().await {
+        Ok(output) => {
+            println!("{}", serde_json::to_string(&output).unwrap());
+        }
+        Err(e) => {
+            let error = ErrorOutput {
+                success: false,
+                error: e.to_string(),
+            };
+            println!("{}", serde_json::to_string(&error).unwrap());
+            std::process::exit(1);
+        }
+    }
+}
+
+async fn run() -> Result<Output, Box<dyn std::error::Error>> {
+    let input = read_input()?;
+    let keys = KeyRing::from_env()?;
+
+    let database_url = env::var("DATABASE_URL").map_err(|_| "DATABASE_URL not set")?;
+    let db = Database::connect(&database_url).await?;
+
+    let storage = TokenStorage::new(db);
+    storage.ensure_schema().await?;
+
+    let report = storage
+        .reencrypt_secrets(&keys, input.dry_run, |progress| {
+            eprintln!("{}", progress_line(progress));
+        })
+        .await?;
+
+    Ok(Output {
+        success: true,
+        key_id: report.key_id,
+        old_key_ids: keys.old_key_ids().into_iter().map(String::from).collect(),
+        dry_run: report.dry_run,
+        tables: report.tables,
+    })
+}
+
+fn read_input() -> Result<Input, Box<dyn std::error::Error>> {
+    let mut input_str = String::new();
+    io::stdin().read_to_string(&mut input_str)?;
+
+    if input_str.trim().is_empty() {
+        Ok(Input::default())
+    } else {
+        Ok(serde_json::from_str(&input_str)?)
+    }
+}
+
+/// Progress line for stderr
+fn progress_line(progress: &RotationProgress) -> String {
+    format!("{}: {}/{}", progress.table, progress.done, progress.total)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_input_parsing() {
+        let input: Input = serde_json::from_str(r#"{"dry_run": true}"#).unwrap();
+        assert!(input.dry_run);
+
+        let input: Input = serde_json::from_str("{}").unwrap();
+        assert!(!input.dry_run);
+    }
+
+    #[test]
+    fn test_progress_line() {
+        let progress = RotationProgress {
+            table: "fatsecret_oauth_token",
+            done: 3,
+            total: 10,
+        };
+        assert_eq!(progress_line(&progress), "fatsecret_oauth_token: 3/10");
+    }
+
+    #[test]
+    fn test_output_serialize() {
+        let output = Output {
+            success: true,
+            key_id: "1a2b3c4d".to_string(),
+            old_key_ids: vec!["5e6f7a8b".to_string()],
+            dry_run: false,
+            tables: vec![TableRotation {
+                table: "fatsecret_oauth_pending",
+                total: 2,
+                reencrypted: 1,
+                already_current: 1,
+            }],
+        };
+        let json = serde_json::to_string(&output).unwrap();
+        assert!(json.contains(r#""key_id":"1a2b3c4d""#));
+        assert!(json.contains(r#""table":"fatsecret_oauth_pending""#));
+        assert!(json.contains(r#""reencrypted":1"#));
+    }
+
+    #[test]
+    fn test_error_output_serialize() {
+        let error = ErrorOutput {
+            success: false,
+            error: "DATABASE_URL not set".to_string(),
+        };
+        let json = serde_json::to_string(&error).unwrap();
+        assert!(json.contains(r#""success":false"#));
+    }
+}
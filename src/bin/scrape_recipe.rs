@@ -0,0 +1,131 @@
+//! Scrape a schema.org recipe from a URL without Tandoor
+//!
+//! Output matches `tandoor_scrape_recipe`, so `recipe_json` can be passed to
+//! `tandoor_create_recipe` as its `recipe`. With `html`, the page is parsed
+//! as given instead of fetched (`url` is then only the recipe's source URL).
+//!
+//! JSON stdin:
+//!   `{"url": "https://...", "html": "<html>..."}` (`html` optional)
+//!
+//! JSON stdout:
+//!   `{"success": true, "recipe_json": {...}, "images": [...]}`
+//!   `{"success": false, "error": "..."}`
+
+// CLI binaries: exit and JSON unwrap are acceptable at the top level
+#![allow(clippy::exit, clippy::unwrap_used)]
+
+use meal_planner::scrape::{fetch_recipe, parse_recipe_html};
+use meal_planner::tandoor::SourceImportRecipe;
+use serde::{Deserialize, Serialize};
+use std::io::{self, Read};
+
+#[derive(Deserialize)]
+struct Input {
+    url: String,
+    #[serde(default)]
+    html: Option<String>,
+}
+
+#[derive(Serialize)]
+struct Output {
+    success: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    recipe_json: Option<SourceImportRecipe>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    images: Option<Vec<String>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    error: Option<String>,
+}
+
+fn main() {
+    let output = match run() {
+        Ok(o) => o,
+        Err(e) => Output {
+            success: false,
+            recipe_json: None,
+            images: None,
+            error: Some(e.to_string()),
+        },
+    };
+    println!("{}", serde_json::to_string(&output).unwrap());
+    if !output.success {
+        std::process::exit(1);
+    }
+}
+
+fn run() -> anyhow::Result<Output> {
+    let mut input = String::new();
+    io::stdin().read_to_string(&mut input)?;
+
+    let parsed: Input = serde_json::from_str(&input)?;
+    let recipe = match &parsed.html {
+        Some(html) => parse_recipe_html(html, Some(&parsed.url))?,
+        None => fetch_recipe(&parsed.url)?,
+    };
+
+    Ok(output_for(recipe))
+}
+
+fn output_for(recipe: SourceImportRecipe) -> Output {
+    Output {
+        success: true,
+        images: Some(recipe.image.iter().cloned().collect()),
+        recipe_json: Some(recipe),
+        error: None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const PAGE: &str = r#"<script type="application/ld+json">{"@type": "Recipe", "name": "Toast", "image": "/toast.jpg", "recipeIngredient": ["1 slice bread"], "recipeInstructions": "Toast it."}</script>"#;
+
+    #[test]
+    fn test_input_parsing() {
+        let input: Input = serde_json::from_str(r#"{"url": "https://example.com/toast"}"#).unwrap();
+        assert_eq!(input.url, "https://example.com/toast");
+        assert!(input.html.is_none());
+    }
+
+    #[test]
+    fn test_input_parsing_with_html() {
+        let json = serde_json::json!({"url": "https://example.com/toast", "html": PAGE});
+        let input: Input = serde_json::from_value(json).unwrap();
+        assert_eq!(input.html.as_deref(), Some(PAGE));
+    }
+
+    #[test]
+    fn test_output_matches_tandoor_scrape_recipe() {
+        let recipe = parse_recipe_html(PAGE, Some("https://example.com/toast")).unwrap();
+        let json = serde_json::to_value(output_for(recipe)).unwrap();
+
+        let field = |pointer| json.pointer(pointer).cloned();
+        assert_eq!(field("/success"), Some(serde_json::json!(true)));
+        assert_eq!(field("/recipe_json/name"), Some("Toast".into()));
+        assert_eq!(
+            field("/recipe_json/source_url"),
+            Some("https://example.com/toast".into())
+        );
+        assert_eq!(
+            field("/images"),
+            Some(serde_json::json!(["https://example.com/toast.jpg"]))
+        );
+        assert!(json.get("error").is_none());
+    }
+
+    #[test]
+    fn test_error_output() {
+        let output = Output {
+            success: false,
+            recipe_json: None,
+            images: None,
+            error: Some("No schema.org Recipe found in page".to_string()),
+        };
+        let json = serde_json::to_string(&output).unwrap();
+        assert_eq!(
+            json,
+            r#"{"success":false,"error":"No schema.org Recipe found in page"}"#
+        );
+    }
+}
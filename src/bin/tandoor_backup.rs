@@ -0,0 +1,134 @@
+//! Back up a whole Tandoor instance to a zip archive
+//!
+//! Saves every recipe (with steps), food, unit, unit conversion, keyword,
+//! meal type, meal plan, recipe book and entry, supermarket, property type
+//! and shopping list entry, plus recipe images unless `images` is `false`.
+//! Restore it with `tandoor_restore`.
+//!
+//! JSON stdin:
+//!   `{"tandoor": {...}, "path": "tandoor-backup.zip"}`
+//!   `{"tandoor": {...}, "path": "tandoor-backup.zip", "images": false}`
+//!
+//! JSON stdout:
+//!   `{"success": true, "path": "tandoor-backup.zip", "version": 1, "counts": {"recipe": 120, ...}, "images": 97, "failed": []}`
+//!   `{"success": false, "error": "..."}`
+
+// CLI binaries: exit and JSON unwrap are acceptable at the top level
+#![allow(clippy::exit, clippy::unwrap_used)]
+
+use meal_planner::backup::{create_backup, BackupReport};
+use meal_planner::tandoor::{TandoorClient, TandoorConfig};
+use serde::{Deserialize, Serialize};
+use std::io::{self, Read};
+use std::path::PathBuf;
+
+#[derive(Deserialize)]
+struct Input {
+    tandoor: TandoorConfig,
+    path: PathBuf,
+    #[serde(default = "default_images")]
+    images: bool,
+}
+
+fn default_images() -> bool {
+    true
+}
+
+#[derive(Serialize)]
+struct Output {
+    success: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    path: Option<PathBuf>,
+    #[serde(flatten, skip_serializing_if = "Option::is_none")]
+    report: Option<BackupReport>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    error: Option<String>,
+}
+
+fn main() {
+    let output = match run() {
+        Ok(o) => o,
+        Err(e) => Output {
+            success: false,
+            path: None,
+            report: None,
+            error: Some(e.to_string()),
+        },
+    };
+    println!("{}", serde_json::to_string(&output).unwrap());
+    if !output.success {
+        std::process::exit(1);
+    }
+}
+
+fn run() -> anyhow::Result<Output> {
+    let mut input = String::new();
+    io::stdin().read_to_string(&mut input)?;
+
+    let parsed: Input = serde_json::from_str(&input)?;
+    let client = TandoorClient::new(&parsed.tandoor)?;
+    let (backup, report) = create_backup(&client, parsed.images)?;
+    backup
+        .save(&parsed.path)
+        .map_err(|e| anyhow::anyhow!("{}: {e}", parsed.path.display()))?;
+
+    Ok(Output {
+        success: true,
+        path: Some(parsed.path),
+        report: Some(report),
+        error: None,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_input_defaults_to_images() {
+        let json = r#"{"tandoor": {"base_url": "http://localhost:8090", "api_token": "test"}, "path": "backup.zip"}"#;
+        let input: Input = serde_json::from_str(json).unwrap();
+        assert_eq!(input.path, PathBuf::from("backup.zip"));
+        assert!(input.images);
+    }
+
+    #[test]
+    fn test_input_without_images() {
+        let json = r#"{"tandoor": {"base_url": "http://localhost:8090", "api_token": "test"}, "path": "backup.zip", "images": false}"#;
+        let input: Input = serde_json::from_str(json).unwrap();
+        assert!(!input.images);
+    }
+
+    #[test]
+    fn test_output_flattens_report() {
+        let output = Output {
+            success: true,
+            path: Some(PathBuf::from("backup.zip")),
+            report: Some(BackupReport {
+                version: 1,
+                ..BackupReport::default()
+            }),
+            error: None,
+        };
+        let json = serde_json::to_string(&output).unwrap();
+        assert_eq!(
+            json,
+            r#"{"success":true,"path":"backup.zip","version":1,"counts":{},"images":0,"failed":[]}"#
+        );
+    }
+
+    #[test]
+    fn test_error_output() {
+        let output = Output {
+            success: false,
+            path: None,
+            report: None,
+            error: Some("backup.zip: Permission denied (os error 13)".to_string()),
+        };
+        let json = serde_json::to_string(&output).unwrap();
+        assert_eq!(
+            json,
+            r#"{"success":false,"error":"backup.zip: Permission denied (os error 13)"}"#
+        );
+    }
+}
@@ -0,0 +1,224 @@
+//! Crawl a recipe site and import its recipes into Tandoor
+//!
+//! Recipe URLs come from a sitemap, category listing pages, or a site's
+//! robots.txt sitemaps. URLs whose recipe is already in Tandoor (same source
+//! URL, or a name at least `min_name_similarity` alike) are skipped. Requests
+//! to the site honor robots.txt and wait `delay_ms` (default 2000) apart.
+//!
+//! Progress is saved to `state_path`; running again with the same file
+//! resumes the crawl. `max_imports` caps imports per run, `dry_run` reports
+//! what would be imported without importing or saving anything.
+//!
+//! JSON stdin:
+//!   `{"tandoor": {...}, "sitemap_url": "https://.../sitemap.xml", "state_path": "crawl.json"}`
+//!   `{"tandoor": {...}, "listing_urls": ["https://.../recipes"], "url_pattern": "/recipes/", "state_path": "crawl.json"}`
+//!   `{"tandoor": {...}, "site": "https://...", "state_path": "crawl.json", "max_imports": 20, "dry_run": true}`
+//!
+//! Optional: `delay_ms`, `max_imports`, `min_name_similarity`, `dry_run`,
+//! `retry_failed`, `rediscover`, `keywords`
+//!
+//! JSON stdout:
+//!   `{"success": true, "discovered": 120, "imported": 20, "duplicates": 3, ..., "results": [...]}`
+//!   `{"success": false, "error": "..."}`
+
+// CLI binaries: exit and JSON unwrap are acceptable at the top level
+#![allow(clippy::exit, clippy::unwrap_used)]
+
+use meal_planner::scrape::crawler::{DEFAULT_DELAY, DEFAULT_MIN_NAME_SIMILARITY};
+use meal_planner::scrape::{crawl, http_client, CrawlOptions, CrawlReport, PoliteFetcher, Source};
+use meal_planner::tandoor::{TandoorClient, TandoorConfig};
+use serde::{Deserialize, Serialize};
+use std::io::{self, Read};
+use std::path::PathBuf;
+use std::time::Duration;
+
+#[derive(Deserialize)]
+struct Input {
+    tandoor: TandoorConfig,
+    #[serde(default)]
+    sitemap_url: Option<String>,
+    #[serde(default)]
+    listing_urls: Vec<String>,
+    #[serde(default)]
+    site: Option<String>,
+    #[serde(default)]
+    url_pattern: Option<String>,
+    state_path: PathBuf,
+    #[serde(default)]
+    delay_ms: Option<u64>,
+    #[serde(default)]
+    max_imports: Option<usize>,
+    #[serde(default)]
+    min_name_similarity: Option<f64>,
+    #[serde(default)]
+    dry_run: bool,
+    #[serde(default)]
+    retry_failed: bool,
+    #[serde(default)]
+    rediscover: bool,
+    #[serde(default)]
+    keywords: Vec<String>,
+}
+
+impl Input {
+    fn sources(&self) -> Vec<Source> {
+        self.sitemap_url
+            .iter()
+            .cloned()
+            .map(Source::Sitemap)
+            .chain(self.listing_urls.iter().cloned().map(Source::Listing))
+            .chain(self.site.iter().cloned().map(Source::Site))
+            .collect()
+    }
+
+    fn delay(&self) -> Duration {
+        self.delay_ms.map_or(DEFAULT_DELAY, Duration::from_millis)
+    }
+
+    fn options(&self) -> CrawlOptions {
+        CrawlOptions {
+            sources: self.sources(),
+            url_pattern: self.url_pattern.clone(),
+            min_name_similarity: self
+                .min_name_similarity
+                .unwrap_or(DEFAULT_MIN_NAME_SIMILARITY),
+            max_imports: self.max_imports,
+            dry_run: self.dry_run,
+            retry_failed: self.retry_failed,
+            rediscover: self.rediscover,
+            keywords: self.keywords.clone(),
+        }
+    }
+}
+
+#[derive(Serialize)]
+struct Output {
+    success: bool,
+    #[serde(flatten, skip_serializing_if = "Option::is_none")]
+    report: Option<CrawlReport>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    error: Option<String>,
+}
+
+fn main() {
+    let output = match run() {
+        Ok(o) => o,
+        Err(e) => Output {
+            success: false,
+            report: None,
+            error: Some(e.to_string()),
+        },
+    };
+    println!("{}", serde_json::to_string(&output).unwrap());
+    if !output.success {
+        std::process::exit(1);
+    }
+}
+
+fn run() -> anyhow::Result<Output> {
+    let mut input = String::new();
+    io::stdin().read_to_string(&mut input)?;
+
+    let parsed: Input = serde_json::from_str(&input)?;
+    let options = parsed.options();
+    if options.sources.is_empty() {
+        anyhow::bail!("One of sitemap_url, listing_urls or site is required");
+    }
+
+    let tandoor = TandoorClient::new(&parsed.tandoor)?;
+    let mut fetcher = PoliteFetcher::new(http_client()?, parsed.delay());
+    let report = crawl(&mut fetcher, &tandoor, &options, &parsed.state_path)?;
+
+    Ok(Output {
+        success: true,
+        report: Some(report),
+        error: None,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn input(json: serde_json::Value) -> Input {
+        let mut base = serde_json::json!({
+            "tandoor": {"base_url": "http://localhost:8090", "api_token": "test"},
+            "state_path": "/tmp/crawl.json"
+        });
+        if let (Some(base), serde_json::Value::Object(extra)) = (base.as_object_mut(), json) {
+            base.extend(extra);
+        }
+        serde_json::from_value(base).unwrap()
+    }
+
+    #[test]
+    fn test_input_defaults() {
+        let input = input(serde_json::json!({"sitemap_url": "https://example.com/sitemap.xml"}));
+        let options = input.options();
+        assert_eq!(
+            options.sources,
+            [Source::Sitemap(
+                "https://example.com/sitemap.xml".to_string()
+            )]
+        );
+        assert_eq!(input.delay(), DEFAULT_DELAY);
+        assert!((options.min_name_similarity - DEFAULT_MIN_NAME_SIMILARITY).abs() < f64::EPSILON);
+        assert!(!options.dry_run);
+        assert!(options.max_imports.is_none());
+    }
+
+    #[test]
+    fn test_input_all_sources_and_options() {
+        let input = input(serde_json::json!({
+            "listing_urls": ["https://example.com/c/bbq", "https://example.com/c/sides"],
+            "site": "https://example.com",
+            "url_pattern": "/recipes/",
+            "delay_ms": 500,
+            "max_imports": 10,
+            "dry_run": true,
+            "keywords": ["imported"]
+        }));
+        let options = input.options();
+        assert_eq!(options.sources.len(), 3);
+        assert_eq!(
+            options.sources.last(),
+            Some(&Source::Site("https://example.com".to_string()))
+        );
+        assert_eq!(options.url_pattern.as_deref(), Some("/recipes/"));
+        assert_eq!(input.delay(), Duration::from_millis(500));
+        assert_eq!(options.max_imports, Some(10));
+        assert!(options.dry_run);
+        assert_eq!(options.keywords, ["imported"]);
+    }
+
+    #[test]
+    fn test_output_flattens_report() {
+        let output = Output {
+            success: true,
+            report: Some(CrawlReport {
+                discovered: 4,
+                imported: 1,
+                ..CrawlReport::default()
+            }),
+            error: None,
+        };
+        let json = serde_json::to_value(&output).unwrap();
+        assert_eq!(json.get("discovered"), Some(&serde_json::json!(4)));
+        assert_eq!(json.get("imported"), Some(&serde_json::json!(1)));
+        assert_eq!(json.get("results"), Some(&serde_json::json!([])));
+    }
+
+    #[test]
+    fn test_error_output() {
+        let output = Output {
+            success: false,
+            report: None,
+            error: Some("Crawl state error: crawl.json: EOF".to_string()),
+        };
+        let json = serde_json::to_string(&output).unwrap();
+        assert_eq!(
+            json,
+            r#"{"success":false,"error":"Crawl state error: crawl.json: EOF"}"#
+        );
+    }
+}
@@ -0,0 +1,135 @@
+//! Export Tandoor recipes to JSON-LD, Markdown and Cooklang files
+//!
+//! Exports every recipe of a recipe book, or every recipe tagged with a
+//! keyword, into `output_dir`: one `<id>-<name>.json`, `.md` and `.cook` file
+//! per recipe (or only the requested `formats`).
+//!
+//! JSON stdin:
+//!   `{"tandoor": {...}, "recipe_book_id": 3, "output_dir": "export/weeknight"}`
+//!   `{"tandoor": {...}, "keyword": "bbq", "output_dir": "export/bbq", "formats": ["markdown", "cooklang"]}`
+//!
+//! JSON stdout:
+//!   `{"success": true, "exported": [{"id": 42, "name": "...", "files": [...]}], "failed": []}`
+//!   `{"success": false, "error": "..."}`
+
+// CLI binaries: exit and JSON unwrap are acceptable at the top level
+#![allow(clippy::exit, clippy::unwrap_used)]
+
+use meal_planner::export::{
+    book_recipe_ids, export_recipes, keyword_recipe_ids, ExportFormat, ExportReport,
+};
+use meal_planner::tandoor::{TandoorClient, TandoorConfig};
+use serde::{Deserialize, Serialize};
+use std::io::{self, Read};
+use std::path::PathBuf;
+
+#[derive(Deserialize)]
+struct Input {
+    tandoor: TandoorConfig,
+    #[serde(default)]
+    recipe_book_id: Option<i64>,
+    #[serde(default)]
+    keyword: Option<String>,
+    output_dir: PathBuf,
+    #[serde(default = "all_formats")]
+    formats: Vec<ExportFormat>,
+}
+
+fn all_formats() -> Vec<ExportFormat> {
+    ExportFormat::ALL.to_vec()
+}
+
+#[derive(Serialize)]
+struct Output {
+    success: bool,
+    #[serde(flatten, skip_serializing_if = "Option::is_none")]
+    report: Option<ExportReport>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    error: Option<String>,
+}
+
+fn main() {
+    let output = match run() {
+        Ok(o) => o,
+        Err(e) => Output {
+            success: false,
+            report: None,
+            error: Some(e.to_string()),
+        },
+    };
+    println!("{}", serde_json::to_string(&output).unwrap());
+    if !output.success {
+        std::process::exit(1);
+    }
+}
+
+fn run() -> anyhow::Result<Output> {
+    let mut input = String::new();
+    io::stdin().read_to_string(&mut input)?;
+
+    let parsed: Input = serde_json::from_str(&input)?;
+    let client = TandoorClient::new(&parsed.tandoor)?;
+
+    let ids = match (parsed.recipe_book_id, parsed.keyword.as_deref()) {
+        (Some(book_id), None) => book_recipe_ids(&client, book_id)?,
+        (None, Some(keyword)) => keyword_recipe_ids(&client, keyword)?,
+        _ => anyhow::bail!("Exactly one of recipe_book_id or keyword is required"),
+    };
+    let report = export_recipes(&client, &ids, &parsed.output_dir, &parsed.formats)?;
+
+    Ok(Output {
+        success: true,
+        report: Some(report),
+        error: None,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_input_parsing_book() {
+        let json = r#"{"tandoor": {"base_url": "http://localhost:8090", "api_token": "test"}, "recipe_book_id": 3, "output_dir": "export"}"#;
+        let input: Input = serde_json::from_str(json).unwrap();
+        assert_eq!(input.recipe_book_id, Some(3));
+        assert!(input.keyword.is_none());
+        assert_eq!(input.formats, ExportFormat::ALL);
+    }
+
+    #[test]
+    fn test_input_parsing_keyword_and_formats() {
+        let json = r#"{"tandoor": {"base_url": "http://localhost:8090", "api_token": "test"}, "keyword": "bbq", "output_dir": "export", "formats": ["markdown", "cooklang"]}"#;
+        let input: Input = serde_json::from_str(json).unwrap();
+        assert_eq!(input.keyword.as_deref(), Some("bbq"));
+        assert_eq!(
+            input.formats,
+            [ExportFormat::Markdown, ExportFormat::Cooklang]
+        );
+    }
+
+    #[test]
+    fn test_output_flattens_report() {
+        let output = Output {
+            success: true,
+            report: Some(ExportReport::default()),
+            error: None,
+        };
+        let json = serde_json::to_string(&output).unwrap();
+        assert_eq!(json, r#"{"success":true,"exported":[],"failed":[]}"#);
+    }
+
+    #[test]
+    fn test_error_output() {
+        let output = Output {
+            success: false,
+            report: None,
+            error: Some("Keyword not found: bbq".to_string()),
+        };
+        let json = serde_json::to_string(&output).unwrap();
+        assert_eq!(
+            json,
+            r#"{"success":false,"error":"Keyword not found: bbq"}"#
+        );
+    }
+}
@@ -126,7 +126,9 @@ mod tests {
             food: Some(Food {
                 id: 1,
                 name: "Test".to_string(),
+                plural_name: None,
                 description: None,
+                supermarket_category: None,
             }),
             error: None,
         };
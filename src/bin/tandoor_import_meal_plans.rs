@@ -0,0 +1,162 @@
+//! Import meal plans from an iCalendar file or a CSV into Tandoor
+//!
+//! Reads the file at `path` (by extension, or as `format` says), matches
+//! each meal's recipe by ID or by fuzzy name and its meal type by name, and
+//! creates or updates the meal plan in its slot. Importing the same file
+//! again leaves Tandoor unchanged. Meals whose recipe name matches several
+//! recipes are reported as `ambiguous`, with candidates, for manual review.
+//!
+//! CSV columns: `date` (YYYY-MM-DD), `recipe` (name or ID), and optionally
+//! `meal_type`, `servings`, `note`.
+//!
+//! Runs as a dry run unless `dry_run` is `false`: review the report, then
+//! run again to import.
+//!
+//! JSON stdin:
+//!   `{"tandoor": {...}, "path": "week.csv"}`
+//!   `{"tandoor": {...}, "path": "plan.ics", "dry_run": false, "min_similarity": 0.7, "default_meal_type": "Dinner"}`
+//!
+//! JSON stdout:
+//!   `{"success": true, "dry_run": true, "meals": [{"source": "row 2", "date": "2026-10-19", "meal_type": "Dinner", "recipe": "Chili", "action": "created", "recipe_id": 12, "matched_recipe": "Chili con Carne"}], "unreadable": []}`
+//!   `{"success": false, "error": "..."}`
+
+// CLI binaries: exit and JSON unwrap are acceptable at the top level
+#![allow(clippy::exit, clippy::unwrap_used)]
+
+use meal_planner::import::{
+    import_meal_plans, MealPlanImportOptions, MealPlanImportReport, PlanFormat,
+};
+use meal_planner::tandoor::{TandoorClient, TandoorConfig};
+use serde::{Deserialize, Serialize};
+use std::io::{self, Read};
+use std::path::PathBuf;
+
+#[derive(Deserialize)]
+struct Input {
+    tandoor: TandoorConfig,
+    path: PathBuf,
+    /// `ics` or `csv`; by default from the file extension
+    #[serde(default)]
+    format: Option<PlanFormat>,
+    #[serde(default = "default_dry_run")]
+    dry_run: bool,
+    #[serde(default)]
+    min_similarity: Option<f64>,
+    #[serde(default)]
+    default_meal_type: Option<String>,
+}
+
+fn default_dry_run() -> bool {
+    true
+}
+
+#[derive(Serialize)]
+struct Output {
+    success: bool,
+    #[serde(flatten, skip_serializing_if = "Option::is_none")]
+    report: Option<MealPlanImportReport>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    error: Option<String>,
+}
+
+fn main() {
+    let output = match run() {
+        Ok(o) => o,
+        Err(e) => Output {
+            success: false,
+            report: None,
+            error: Some(e.to_string()),
+        },
+    };
+    println!("{}", serde_json::to_string(&output).unwrap());
+    if !output.success {
+        std::process::exit(1);
+    }
+}
+
+fn run() -> anyhow::Result<Output> {
+    let mut input = String::new();
+    io::stdin().read_to_string(&mut input)?;
+
+    let parsed: Input = serde_json::from_str(&input)?;
+    let format = parsed
+        .format
+        .or_else(|| PlanFormat::of(&parsed.path))
+        .ok_or_else(|| {
+            anyhow::anyhow!(
+                "{}: not an .ics or .csv file (set format)",
+                parsed.path.display()
+            )
+        })?;
+    let bytes = std::fs::read(&parsed.path)
+        .map_err(|e| anyhow::anyhow!("{}: {e}", parsed.path.display()))?;
+    let file = format.read(&bytes)?;
+
+    let defaults = MealPlanImportOptions::default();
+    let options = MealPlanImportOptions {
+        dry_run: parsed.dry_run,
+        min_similarity: parsed.min_similarity.unwrap_or(defaults.min_similarity),
+        default_meal_type: parsed.default_meal_type,
+    };
+    let client = TandoorClient::new(&parsed.tandoor)?;
+    let report = import_meal_plans(&client, file, &options)?;
+
+    Ok(Output {
+        success: true,
+        report: Some(report),
+        error: None,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_input_defaults_to_dry_run() {
+        let json = r#"{"tandoor": {"base_url": "http://localhost:8090", "api_token": "test"}, "path": "week.csv"}"#;
+        let input: Input = serde_json::from_str(json).unwrap();
+        assert!(input.dry_run);
+        assert_eq!(input.format, None);
+        assert_eq!(input.min_similarity, None);
+        assert_eq!(input.default_meal_type, None);
+    }
+
+    #[test]
+    fn test_input_with_options() {
+        let json = r#"{"tandoor": {"base_url": "http://localhost:8090", "api_token": "test"}, "path": "plan.txt", "format": "ics", "dry_run": false, "min_similarity": 0.7, "default_meal_type": "Dinner"}"#;
+        let input: Input = serde_json::from_str(json).unwrap();
+        assert_eq!(input.format, Some(PlanFormat::Ics));
+        assert!(!input.dry_run);
+        assert_eq!(input.min_similarity, Some(0.7));
+        assert_eq!(input.default_meal_type.as_deref(), Some("Dinner"));
+    }
+
+    #[test]
+    fn test_output_flattens_report() {
+        let output = Output {
+            success: true,
+            report: Some(MealPlanImportReport::default()),
+            error: None,
+        };
+        let json = serde_json::to_string(&output).unwrap();
+        assert_eq!(
+            json,
+            r#"{"success":true,"dry_run":false,"meals":[],"unreadable":[]}"#
+        );
+    }
+
+    #[test]
+    fn test_error_output() {
+        let output = Output {
+            success: false,
+            report: None,
+            error: Some("Invalid recipe file: not an iCalendar file".to_string()),
+        };
+        let json = serde_json::to_string(&output).unwrap();
+        assert_eq!(
+            json,
+            r#"{"success":false,"error":"Invalid recipe file: not an iCalendar file"}"#
+        );
+    }
+}
@@ -6,7 +6,7 @@
 #![allow(clippy::exit, clippy::unwrap_used, clippy::expect_used)]
 
 use meal_planner::fatsecret::core::FatSecretConfig;
-use meal_planner::fatsecret::foods::{get_food, search_foods_simple};
+use meal_planner::fatsecret::foods::{best_match, get_food, search_foods_simple};
 use meal_planner::tandoor::nutrition::{convert_to_grams, scale_nutrition_to_grams};
 use serde::{Deserialize, Serialize};
 use serde_json::json;
@@ -79,12 +79,13 @@ async fn run() -> Result<Output, Box<dyn std::error::Error>> {
         None => FatSecretConfig::from_env()?,
     };
 
-    // Search for food
+    // Search for food, preferring generic foods over branded products
     let search_results = search_foods_simple(&config, &input.ingredient_name).await?;
-    let first_food = search_results.foods.first().ok_or("No foods found")?;
+    let matched_food =
+        best_match(&search_results.foods, &input.ingredient_name).ok_or("No foods found")?;
 
     // Get detailed food info
-    let food = get_food(&config, &first_food.food_id).await?;
+    let food = get_food(&config, &matched_food.food_id).await?;
 
     // Find default serving or use first
     let serving = food
@@ -117,8 +118,8 @@ async fn run() -> Result<Output, Box<dyn std::error::Error>> {
 
     Ok(Output {
         success: true,
-        food_id: first_food.food_id.to_string(),
-        food_name: first_food.food_name.clone(),
+        food_id: matched_food.food_id.to_string(),
+        food_name: matched_food.food_name.clone(),
         nutrition: scaled_nutrition,
     })
 }
@@ -0,0 +1,162 @@
+//! Export Tandoor meal plans for a date range as an iCalendar feed
+//!
+//! Builds the calendar locally (unlike `tandoor_meal_plan_export_ical`, which
+//! proxies Tandoor's export of a single meal plan). Events start at the meal
+//! type's time and last the recipe's working plus waiting time; each keeps
+//! the same UID across exports, so calendar clients update rather than
+//! duplicate them. Recipe steps that need a head start ("marinate
+//! overnight") add reminders that far ahead unless `prep_reminders` is
+//! `false`; `reminder_minutes` adds a reminder before every meal.
+//!
+//! JSON input (CLI arg or stdin):
+//!   `{"tandoor": {...}, "from_date": "2026-10-19", "to_date": "2026-10-25", "reminder_minutes": 30}`
+//!
+//! `to_date` defaults to `from_date`.
+//!
+//! JSON stdout:
+//!   `{"success": true, "ical": "BEGIN:VCALENDAR\r\n...", "events": 14, "skipped": []}`
+//!   `{"success": false, "error": "..."}`
+
+// CLI binaries: exit and JSON unwrap are acceptable at the top level
+#![allow(clippy::exit, clippy::unwrap_used)]
+
+use meal_planner::tandoor::calendar::{meal_plan_calendar, CalendarOptions, MealPlanCalendar};
+use meal_planner::tandoor::{MealPlansWithRecipes, TandoorClient, TandoorConfig};
+use serde::{Deserialize, Serialize};
+use std::io::{self, Read};
+
+#[derive(Deserialize)]
+struct Input {
+    tandoor: TandoorConfig,
+    /// First day (YYYY-MM-DD)
+    from_date: String,
+    /// Last day (YYYY-MM-DD, defaults to `from_date`)
+    #[serde(default)]
+    to_date: Option<String>,
+    /// Reminder before each meal, in minutes
+    #[serde(default)]
+    reminder_minutes: Option<u32>,
+    /// Reminders for steps that need a head start
+    #[serde(default = "default_prep_reminders")]
+    prep_reminders: bool,
+}
+
+fn default_prep_reminders() -> bool {
+    true
+}
+
+#[derive(Serialize)]
+struct Output {
+    success: bool,
+    #[serde(flatten, skip_serializing_if = "Option::is_none")]
+    calendar: Option<MealPlanCalendar>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    error: Option<String>,
+}
+
+fn main() {
+    let output = match run() {
+        Ok(o) => o,
+        Err(e) => Output {
+            success: false,
+            calendar: None,
+            error: Some(e.to_string()),
+        },
+    };
+    println!("{}", serde_json::to_string(&output).unwrap());
+    if !output.success {
+        std::process::exit(1);
+    }
+}
+
+fn run() -> anyhow::Result<Output> {
+    // Read input: prefer CLI arg, fall back to stdin
+    let input: Input = if let Some(arg) = std::env::args().nth(1) {
+        serde_json::from_str(&arg)?
+    } else {
+        let mut input = String::new();
+        io::stdin().read_to_string(&mut input)?;
+        serde_json::from_str(&input)?
+    };
+    let to_date = input.to_date.as_deref().unwrap_or(&input.from_date);
+
+    let client = TandoorClient::new(&input.tandoor)?;
+    // Recipe steps are only needed for prep reminders
+    let MealPlansWithRecipes { plans, recipes } = if input.prep_reminders {
+        client.get_meal_plans_with_recipes(&input.from_date, to_date)?
+    } else {
+        MealPlansWithRecipes {
+            plans: client.list_all_meal_plans_by_date(&input.from_date, to_date)?,
+            ..MealPlansWithRecipes::default()
+        }
+    };
+
+    let options = CalendarOptions {
+        base_url: input.tandoor.base_url.clone(),
+        reminder_minutes: input.reminder_minutes,
+        prep_reminders: input.prep_reminders,
+    };
+    let calendar = meal_plan_calendar(&plans, &recipes, &options, chrono::Utc::now());
+
+    Ok(Output {
+        success: true,
+        calendar: Some(calendar),
+        error: None,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_input_defaults() {
+        let json = r#"{"tandoor": {"base_url": "http://localhost:8090", "api_token": "test"}, "from_date": "2026-10-19"}"#;
+        let input: Input = serde_json::from_str(json).unwrap();
+        assert_eq!(input.from_date, "2026-10-19");
+        assert_eq!(input.to_date, None);
+        assert_eq!(input.reminder_minutes, None);
+        assert!(input.prep_reminders);
+    }
+
+    #[test]
+    fn test_input_with_options() {
+        let json = r#"{"tandoor": {"base_url": "http://localhost:8090", "api_token": "test"}, "from_date": "2026-10-19", "to_date": "2026-10-25", "reminder_minutes": 30, "prep_reminders": false}"#;
+        let input: Input = serde_json::from_str(json).unwrap();
+        assert_eq!(input.to_date.as_deref(), Some("2026-10-25"));
+        assert_eq!(input.reminder_minutes, Some(30));
+        assert!(!input.prep_reminders);
+    }
+
+    #[test]
+    fn test_output_flattens_calendar() {
+        let output = Output {
+            success: true,
+            calendar: Some(MealPlanCalendar {
+                ical: "BEGIN:VCALENDAR\r\nEND:VCALENDAR\r\n".to_string(),
+                events: 0,
+                skipped: vec![],
+            }),
+            error: None,
+        };
+        let json = serde_json::to_string(&output).unwrap();
+        assert_eq!(
+            json,
+            r#"{"success":true,"ical":"BEGIN:VCALENDAR\r\nEND:VCALENDAR\r\n","events":0,"skipped":[]}"#
+        );
+    }
+
+    #[test]
+    fn test_error_output() {
+        let output = Output {
+            success: false,
+            calendar: None,
+            error: Some("API error (400): invalid date".to_string()),
+        };
+        let json = serde_json::to_string(&output).unwrap();
+        assert_eq!(
+            json,
+            r#"{"success":false,"error":"API error (400): invalid date"}"#
+        );
+    }
+}
@@ -1,6 +1,7 @@
 //! Export meal plan as iCalendar format
 //!
 //! Exports a meal plan with all its meals as an iCalendar (.ics) file format string.
+//! For a date range with reminders, built locally, use `tandoor_meal_plan_calendar`.
 //!
 //! JSON input (CLI arg or stdin):
 //!   `{"tandoor": {...}, "id": 123}`
@@ -0,0 +1,135 @@
+//! Import recipes from a Paprika, Mealie or MyFitnessPal export into Tandoor
+//!
+//! Reads the export at `path` and creates each recipe, matching ingredient
+//! foods and units to existing ones (creating the missing ones). Recipes
+//! whose name is already in Tandoor are skipped.
+//!
+//! Runs as a dry run unless `dry_run` is `false`: review the report, then
+//! run again to import.
+//!
+//! JSON stdin:
+//!   `{"tandoor": {...}, "app": "paprika", "path": "My Recipes.paprikarecipes"}`
+//!   `{"tandoor": {...}, "app": "mealie", "path": "mealie.zip", "dry_run": false}`
+//!   (`app` is `paprika`, `mealie` or `myfitnesspal`)
+//!
+//! JSON stdout:
+//!   `{"success": true, "dry_run": true, "recipes": [{"name": "...", "action": "created", "ingredients": 8, "steps": 4}], "created_foods": [...], "created_units": [...], "unreadable": []}`
+//!   `{"success": false, "error": "..."}`
+
+// CLI binaries: exit and JSON unwrap are acceptable at the top level
+#![allow(clippy::exit, clippy::unwrap_used)]
+
+use meal_planner::import::{import_recipes, App, MigrationReport};
+use meal_planner::tandoor::{TandoorClient, TandoorConfig};
+use serde::{Deserialize, Serialize};
+use std::io::{self, Read};
+use std::path::PathBuf;
+
+#[derive(Deserialize)]
+struct Input {
+    tandoor: TandoorConfig,
+    app: App,
+    path: PathBuf,
+    #[serde(default = "default_dry_run")]
+    dry_run: bool,
+}
+
+fn default_dry_run() -> bool {
+    true
+}
+
+#[derive(Serialize)]
+struct Output {
+    success: bool,
+    #[serde(flatten, skip_serializing_if = "Option::is_none")]
+    report: Option<MigrationReport>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    error: Option<String>,
+}
+
+fn main() {
+    let output = match run() {
+        Ok(o) => o,
+        Err(e) => Output {
+            success: false,
+            report: None,
+            error: Some(e.to_string()),
+        },
+    };
+    println!("{}", serde_json::to_string(&output).unwrap());
+    if !output.success {
+        std::process::exit(1);
+    }
+}
+
+fn run() -> anyhow::Result<Output> {
+    let mut input = String::new();
+    io::stdin().read_to_string(&mut input)?;
+
+    let parsed: Input = serde_json::from_str(&input)?;
+    let bytes = std::fs::read(&parsed.path)
+        .map_err(|e| anyhow::anyhow!("{}: {e}", parsed.path.display()))?;
+    let export = parsed.app.read(&bytes)?;
+
+    let client = TandoorClient::new(&parsed.tandoor)?;
+    let report = import_recipes(&client, export, parsed.dry_run)?;
+
+    Ok(Output {
+        success: true,
+        report: Some(report),
+        error: None,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_input_defaults_to_dry_run() {
+        let json = r#"{"tandoor": {"base_url": "http://localhost:8090", "api_token": "test"}, "app": "paprika", "path": "export.paprikarecipes"}"#;
+        let input: Input = serde_json::from_str(json).unwrap();
+        assert_eq!(input.app, App::Paprika);
+        assert!(input.dry_run);
+    }
+
+    #[test]
+    fn test_input_apps() {
+        for (name, app) in [("mealie", App::Mealie), ("myfitnesspal", App::MyFitnessPal)] {
+            let json = format!(
+                r#"{{"tandoor": {{"base_url": "http://localhost:8090", "api_token": "test"}}, "app": "{name}", "path": "export", "dry_run": false}}"#
+            );
+            let input: Input = serde_json::from_str(&json).unwrap();
+            assert_eq!(input.app, app);
+            assert!(!input.dry_run);
+        }
+    }
+
+    #[test]
+    fn test_output_flattens_report() {
+        let output = Output {
+            success: true,
+            report: Some(MigrationReport::default()),
+            error: None,
+        };
+        let json = serde_json::to_string(&output).unwrap();
+        assert_eq!(
+            json,
+            r#"{"success":true,"dry_run":false,"recipes":[],"created_foods":[],"created_units":[],"unreadable":[]}"#
+        );
+    }
+
+    #[test]
+    fn test_error_output() {
+        let output = Output {
+            success: false,
+            report: None,
+            error: Some("Invalid recipe file: not a Paprika export".to_string()),
+        };
+        let json = serde_json::to_string(&output).unwrap();
+        assert_eq!(
+            json,
+            r#"{"success":false,"error":"Invalid recipe file: not a Paprika export"}"#
+        );
+    }
+}
@@ -0,0 +1,136 @@
+//! Restore a `tandoor_backup` archive into a Tandoor instance
+//!
+//! Recreates every record in dependency order and remaps the references
+//! between them (meal plans to recipes and meal types, book entries to
+//! books and recipes) to the new IDs. Named records that already exist
+//! (units, foods, keywords, ...) are reused. Refuses an instance that
+//! already has recipes unless `force` is `true`.
+//!
+//! JSON stdin:
+//!   `{"tandoor": {...}, "path": "tandoor-backup.zip"}`
+//!   `{"tandoor": {...}, "path": "tandoor-backup.zip", "force": true, "images": false}`
+//!
+//! JSON stdout:
+//!   `{"success": true, "resources": [{"resource": "keyword", "created": 40, "existing": 2, "failed": 0}, ...], "images": 97, "failed": [], "warnings": []}`
+//!   `{"success": false, "error": "..."}`
+
+// CLI binaries: exit and JSON unwrap are acceptable at the top level
+#![allow(clippy::exit, clippy::unwrap_used)]
+
+use meal_planner::backup::{restore_backup, Backup, RestoreOptions, RestoreReport};
+use meal_planner::tandoor::{TandoorClient, TandoorConfig};
+use serde::{Deserialize, Serialize};
+use std::io::{self, Read};
+use std::path::PathBuf;
+
+#[derive(Deserialize)]
+struct Input {
+    tandoor: TandoorConfig,
+    path: PathBuf,
+    #[serde(default)]
+    force: bool,
+    #[serde(default = "default_images")]
+    images: bool,
+}
+
+fn default_images() -> bool {
+    true
+}
+
+#[derive(Serialize)]
+struct Output {
+    success: bool,
+    #[serde(flatten, skip_serializing_if = "Option::is_none")]
+    report: Option<RestoreReport>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    error: Option<String>,
+}
+
+fn main() {
+    let output = match run() {
+        Ok(o) => o,
+        Err(e) => Output {
+            success: false,
+            report: None,
+            error: Some(e.to_string()),
+        },
+    };
+    println!("{}", serde_json::to_string(&output).unwrap());
+    if !output.success {
+        std::process::exit(1);
+    }
+}
+
+fn run() -> anyhow::Result<Output> {
+    let mut input = String::new();
+    io::stdin().read_to_string(&mut input)?;
+
+    let parsed: Input = serde_json::from_str(&input)?;
+    let backup = Backup::load(&parsed.path)
+        .map_err(|e| anyhow::anyhow!("{}: {e}", parsed.path.display()))?;
+
+    let client = TandoorClient::new(&parsed.tandoor)?;
+    let options = RestoreOptions {
+        force: parsed.force,
+        images: parsed.images,
+    };
+    let report = restore_backup(&client, &backup, &options)?;
+
+    Ok(Output {
+        success: true,
+        report: Some(report),
+        error: None,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_input_defaults() {
+        let json = r#"{"tandoor": {"base_url": "http://localhost:8090", "api_token": "test"}, "path": "backup.zip"}"#;
+        let input: Input = serde_json::from_str(json).unwrap();
+        assert!(!input.force);
+        assert!(input.images);
+    }
+
+    #[test]
+    fn test_input_with_options() {
+        let json = r#"{"tandoor": {"base_url": "http://localhost:8090", "api_token": "test"}, "path": "backup.zip", "force": true, "images": false}"#;
+        let input: Input = serde_json::from_str(json).unwrap();
+        assert!(input.force);
+        assert!(!input.images);
+    }
+
+    #[test]
+    fn test_output_flattens_report() {
+        let output = Output {
+            success: true,
+            report: Some(RestoreReport::default()),
+            error: None,
+        };
+        let json = serde_json::to_string(&output).unwrap();
+        assert_eq!(
+            json,
+            r#"{"success":true,"resources":[],"images":0,"failed":[],"warnings":[]}"#
+        );
+    }
+
+    #[test]
+    fn test_error_output() {
+        let output = Output {
+            success: false,
+            report: None,
+            error: Some(
+                "Target instance is not empty (12 recipes); set force to restore anyway"
+                    .to_string(),
+            ),
+        };
+        let json = serde_json::to_string(&output).unwrap();
+        assert_eq!(
+            json,
+            r#"{"success":false,"error":"Target instance is not empty (12 recipes); set force to restore anyway"}"#
+        );
+    }
+}
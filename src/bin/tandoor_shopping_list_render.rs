@@ -0,0 +1,185 @@
+//! Render a Tandoor shopping list in supermarket aisle order
+//!
+//! Collects shopping list entries for one or more meal plans, groups them by
+//! supermarket category and orders the groups in the chosen store's walking
+//! order. Foods are mapped to categories from Tandoor's food data, with
+//! optional overrides supplied in the input.
+//!
+//! JSON input (CLI arg or stdin):
+//!   `{"tandoor": {...}, "mealplan_ids": [1, 2], "supermarket_id": 3,
+//!     "format": "markdown", "title": "Weekly Shop",
+//!     "food_categories": {"milk": "Dairy"}}`
+//!
+//! `format` is one of `markdown` (default), `text` or `html`.
+//! `supermarket_id`, `title` and `food_categories` are optional.
+//!
+//! JSON stdout: `{"success": true, "format": "markdown", "content": "...", "sections": [...]}`
+//!   or `{"success": false, "error": "..."}`
+
+// CLI binaries: exit and unwrap/expect are acceptable at the top level
+#![allow(clippy::exit, clippy::unwrap_used, clippy::expect_used)]
+
+use meal_planner::tandoor::shopping::aisle::{
+    food_category_map, group_by_aisle, render, AisleLayout, AisleSection, ShoppingListFormat,
+};
+use meal_planner::tandoor::{Food, Supermarket, TandoorClient, TandoorConfig};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::io::{self, Read};
+
+/// Page size used when walking the food list
+const FOOD_PAGE_SIZE: u32 = 100;
+
+#[derive(Deserialize)]
+struct Input {
+    tandoor: TandoorConfig,
+    mealplan_ids: Vec<i64>,
+    #[serde(default)]
+    supermarket_id: Option<i64>,
+    #[serde(default)]
+    format: ShoppingListFormat,
+    #[serde(default)]
+    title: Option<String>,
+    /// Food name → category name overrides (take precedence over Tandoor data)
+    #[serde(default)]
+    food_categories: HashMap<String, String>,
+}
+
+#[derive(Serialize)]
+struct Output {
+    success: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    format: Option<ShoppingListFormat>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    content: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    sections: Option<Vec<AisleSection>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    error: Option<String>,
+}
+
+fn main() {
+    let output = match run() {
+        Ok(o) => o,
+        Err(e) => Output {
+            success: false,
+            format: None,
+            content: None,
+            sections: None,
+            error: Some(e.to_string()),
+        },
+    };
+    println!(
+        "{}",
+        serde_json::to_string(&output).expect("Failed to serialize output JSON")
+    );
+    if !output.success {
+        std::process::exit(1);
+    }
+}
+
+fn run() -> anyhow::Result<Output> {
+    let input: Input = if let Some(arg) = std::env::args().nth(1) {
+        serde_json::from_str(&arg)?
+    } else {
+        let mut input_str = String::new();
+        io::stdin().read_to_string(&mut input_str)?;
+        serde_json::from_str(&input_str)?
+    };
+
+    if input.mealplan_ids.is_empty() {
+        anyhow::bail!("mealplan_ids must contain at least one meal plan ID");
+    }
+
+    let client = TandoorClient::new(&input.tandoor)?;
+
+    let mut entries = Vec::new();
+    for mealplan_id in &input.mealplan_ids {
+        entries.extend(client.list_shopping_list_entries(*mealplan_id)?);
+    }
+
+    let layout = match input.supermarket_id {
+        Some(id) => {
+            let supermarket: Supermarket = serde_json::from_value(client.get_supermarket(id)?)?;
+            AisleLayout::from_supermarket(&supermarket)
+        }
+        None => AisleLayout::default(),
+    };
+
+    let mut categories = food_category_map(&fetch_all_foods(&client)?);
+    categories.extend(
+        input
+            .food_categories
+            .into_iter()
+            .map(|(food, category)| (food.to_lowercase(), category)),
+    );
+
+    let sections = group_by_aisle(&entries, &layout, &categories);
+    let title = input
+        .title
+        .or_else(|| layout.supermarket_name.clone())
+        .unwrap_or_else(|| "Shopping List".to_string());
+
+    Ok(Output {
+        success: true,
+        format: Some(input.format),
+        content: Some(render(input.format, &title, &sections)),
+        sections: Some(sections),
+        error: None,
+    })
+}
+
+/// Walk every page of `/api/food/` so each food's category is known
+fn fetch_all_foods(client: &TandoorClient) -> anyhow::Result<Vec<Food>> {
+    let mut foods = Vec::new();
+    let mut page = 1;
+    loop {
+        let response = client.list_foods(Some(page), Some(FOOD_PAGE_SIZE))?;
+        foods.extend(response.results);
+        if response.next.is_none() {
+            return Ok(foods);
+        }
+        page += 1;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_input_parsing_defaults() {
+        let json = r#"{"tandoor": {"base_url": "http://localhost:8090", "api_token": "test"}, "mealplan_ids": [1]}"#;
+        let input: Input = serde_json::from_str(json).expect("Failed to parse test JSON");
+        assert_eq!(input.mealplan_ids, vec![1]);
+        assert_eq!(input.format, ShoppingListFormat::Markdown);
+        assert!(input.supermarket_id.is_none());
+        assert!(input.food_categories.is_empty());
+    }
+
+    #[test]
+    fn test_input_parsing_full() {
+        let json = r#"{"tandoor": {"base_url": "http://localhost:8090", "api_token": "test"}, "mealplan_ids": [1, 2], "supermarket_id": 3, "format": "html", "title": "Saturday", "food_categories": {"Milk": "Dairy"}}"#;
+        let input: Input = serde_json::from_str(json).expect("Failed to parse test JSON");
+        assert_eq!(input.supermarket_id, Some(3));
+        assert_eq!(input.format, ShoppingListFormat::Html);
+        assert_eq!(input.title.as_deref(), Some("Saturday"));
+        assert_eq!(
+            input.food_categories.get("Milk").map(String::as_str),
+            Some("Dairy")
+        );
+    }
+
+    #[test]
+    fn test_error_output_serialize() {
+        let output = Output {
+            success: false,
+            format: None,
+            content: None,
+            sections: None,
+            error: Some("boom".to_string()),
+        };
+        let json = serde_json::to_string(&output).expect("Failed to serialize output JSON");
+        assert_eq!(json, r#"{"success":false,"error":"boom"}"#);
+    }
+}
@@ -0,0 +1,141 @@
+//! Sync a directory of Cooklang and Markdown recipes into Tandoor
+//!
+//! Every `.cook`, `.md` and `.markdown` file under `dir` becomes one recipe,
+//! keyed by its path: new files are created, changed files update their
+//! recipe, unchanged files are skipped. Ingredient foods and units are matched
+//! to existing ones, and created when missing.
+//!
+//! JSON stdin:
+//!   `{"tandoor": {...}, "dir": "recipes"}`
+//!   `{"tandoor": {...}, "dir": "recipes", "state_path": "sync.json", "dry_run": true, "force": false}`
+//!
+//! JSON stdout:
+//!   `{"success": true, "files": [{"path": "mains/chili.cook", "action": "created", "recipe_id": 42, "name": "Chili"}], "created_foods": [...], "created_units": [...], "missing": []}`
+//!   `{"success": false, "error": "..."}`
+
+// CLI binaries: exit and JSON unwrap are acceptable at the top level
+#![allow(clippy::exit, clippy::unwrap_used)]
+
+use meal_planner::import::{sync_directory, SyncOptions, SyncReport};
+use meal_planner::tandoor::{TandoorClient, TandoorConfig};
+use serde::{Deserialize, Serialize};
+use std::io::{self, Read};
+use std::path::PathBuf;
+
+#[derive(Deserialize)]
+struct Input {
+    tandoor: TandoorConfig,
+    dir: PathBuf,
+    #[serde(default)]
+    state_path: Option<PathBuf>,
+    #[serde(default)]
+    dry_run: bool,
+    #[serde(default)]
+    force: bool,
+}
+
+impl Input {
+    fn options(&self) -> SyncOptions {
+        let mut options = SyncOptions::new(&self.dir);
+        if let Some(state_path) = &self.state_path {
+            options.state_path.clone_from(state_path);
+        }
+        options.dry_run = self.dry_run;
+        options.force = self.force;
+        options
+    }
+}
+
+#[derive(Serialize)]
+struct Output {
+    success: bool,
+    #[serde(flatten, skip_serializing_if = "Option::is_none")]
+    report: Option<SyncReport>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    error: Option<String>,
+}
+
+fn main() {
+    let output = match run() {
+        Ok(o) => o,
+        Err(e) => Output {
+            success: false,
+            report: None,
+            error: Some(e.to_string()),
+        },
+    };
+    println!("{}", serde_json::to_string(&output).unwrap());
+    if !output.success {
+        std::process::exit(1);
+    }
+}
+
+fn run() -> anyhow::Result<Output> {
+    let mut input = String::new();
+    io::stdin().read_to_string(&mut input)?;
+
+    let parsed: Input = serde_json::from_str(&input)?;
+    let client = TandoorClient::new(&parsed.tandoor)?;
+    let report = sync_directory(&client, &parsed.options())?;
+
+    Ok(Output {
+        success: true,
+        report: Some(report),
+        error: None,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::path::Path;
+
+    #[test]
+    fn test_input_defaults() {
+        let json = r#"{"tandoor": {"base_url": "http://localhost:8090", "api_token": "test"}, "dir": "recipes"}"#;
+        let input: Input = serde_json::from_str(json).unwrap();
+        let options = input.options();
+        assert_eq!(options.dir, Path::new("recipes"));
+        assert_eq!(options.state_path, Path::new("recipes/.tandoor-sync.json"));
+        assert!(!options.dry_run);
+        assert!(!options.force);
+    }
+
+    #[test]
+    fn test_input_with_options() {
+        let json = r#"{"tandoor": {"base_url": "http://localhost:8090", "api_token": "test"}, "dir": "recipes", "state_path": "sync.json", "dry_run": true, "force": true}"#;
+        let input: Input = serde_json::from_str(json).unwrap();
+        let options = input.options();
+        assert_eq!(options.state_path, Path::new("sync.json"));
+        assert!(options.dry_run);
+        assert!(options.force);
+    }
+
+    #[test]
+    fn test_output_flattens_report() {
+        let output = Output {
+            success: true,
+            report: Some(SyncReport::default()),
+            error: None,
+        };
+        let json = serde_json::to_string(&output).unwrap();
+        assert_eq!(
+            json,
+            r#"{"success":true,"files":[],"created_foods":[],"created_units":[],"missing":[]}"#
+        );
+    }
+
+    #[test]
+    fn test_error_output() {
+        let output = Output {
+            success: false,
+            report: None,
+            error: Some("I/O error: No such file or directory".to_string()),
+        };
+        let json = serde_json::to_string(&output).unwrap();
+        assert_eq!(
+            json,
+            r#"{"success":false,"error":"I/O error: No such file or directory"}"#
+        );
+    }
+}
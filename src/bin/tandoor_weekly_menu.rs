@@ -0,0 +1,192 @@
+//! Render a printable weekly menu and prep sheet from Tandoor meal plans
+//!
+//! Lays the meal plans for a date range out as a grid (days × meal types)
+//! with per-person nutrition for each day, followed by a prep sheet for the
+//! day before the week: head starts (marinate, soak, thaw) and food that
+//! several meals cook or cut the same way ("Cook rice for Mon+Wed"), in the
+//! order to do them.
+//!
+//! JSON input (CLI arg or stdin):
+//!   `{"tandoor": {...}, "from_date": "2026-10-19", "to_date": "2026-10-25",
+//!     "prep_date": "2026-10-18", "format": "html", "title": "Week 43"}`
+//!
+//! `format` is one of `html` (default, printable; print to PDF from a
+//! browser), `markdown` or `text`. `to_date` defaults to six days after
+//! `from_date`, `prep_date` to the day before it.
+//!
+//! JSON stdout:
+//!   `{"success": true, "format": "html", "content": "<!DOCTYPE html>...", "title": "...", "meal_types": [...], "days": [...], "prep": {...}, "skipped": []}`
+//!   `{"success": false, "error": "..."}`
+
+// CLI binaries: exit and JSON unwrap are acceptable at the top level
+#![allow(clippy::exit, clippy::unwrap_used)]
+
+use chrono::NaiveDate;
+use meal_planner::tandoor::weekly::{
+    render, weekly_menu, MenuFormat, WeeklyMenu, WeeklyMenuOptions,
+};
+use meal_planner::tandoor::{MealPlansWithRecipes, TandoorClient, TandoorConfig};
+use serde::{Deserialize, Serialize};
+use std::io::{self, Read};
+
+/// Days after `from_date` shown by default
+const WEEK_DAYS: u64 = 6;
+
+#[derive(Deserialize)]
+struct Input {
+    tandoor: TandoorConfig,
+    /// First day (YYYY-MM-DD)
+    from_date: String,
+    /// Last day (YYYY-MM-DD)
+    #[serde(default)]
+    to_date: Option<String>,
+    /// Day of the prep session (YYYY-MM-DD)
+    #[serde(default)]
+    prep_date: Option<String>,
+    #[serde(default)]
+    format: MenuFormat,
+    #[serde(default)]
+    title: Option<String>,
+}
+
+#[derive(Serialize)]
+struct Output {
+    success: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    format: Option<MenuFormat>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    content: Option<String>,
+    #[serde(flatten, skip_serializing_if = "Option::is_none")]
+    menu: Option<WeeklyMenu>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    error: Option<String>,
+}
+
+fn main() {
+    let output = match run() {
+        Ok(o) => o,
+        Err(e) => Output {
+            success: false,
+            format: None,
+            content: None,
+            menu: None,
+            error: Some(e.to_string()),
+        },
+    };
+    println!("{}", serde_json::to_string(&output).unwrap());
+    if !output.success {
+        std::process::exit(1);
+    }
+}
+
+fn run() -> anyhow::Result<Output> {
+    // Read input: prefer CLI arg, fall back to stdin
+    let input: Input = if let Some(arg) = std::env::args().nth(1) {
+        serde_json::from_str(&arg)?
+    } else {
+        let mut input = String::new();
+        io::stdin().read_to_string(&mut input)?;
+        serde_json::from_str(&input)?
+    };
+    let from_date = parse_date(&input.from_date)?;
+    let to_date = match input.to_date.as_deref() {
+        Some(date) => parse_date(date)?,
+        None => from_date
+            .checked_add_days(chrono::Days::new(WEEK_DAYS))
+            .unwrap_or(from_date),
+    };
+    if to_date < from_date {
+        anyhow::bail!("to_date {to_date} is before from_date {from_date}");
+    }
+    let prep_date = input.prep_date.as_deref().map(parse_date).transpose()?;
+
+    let client = TandoorClient::new(&input.tandoor)?;
+    let MealPlansWithRecipes { plans, recipes } =
+        client.get_meal_plans_with_recipes(&from_date.to_string(), &to_date.to_string())?;
+
+    let options = WeeklyMenuOptions {
+        from: from_date,
+        to: to_date,
+        prep_date,
+        title: input.title,
+    };
+    let menu = weekly_menu(&plans, &recipes, &options);
+
+    Ok(Output {
+        success: true,
+        format: Some(input.format),
+        content: Some(render(input.format, &menu)),
+        menu: Some(menu),
+        error: None,
+    })
+}
+
+fn parse_date(date: &str) -> anyhow::Result<NaiveDate> {
+    NaiveDate::parse_from_str(date, "%Y-%m-%d")
+        .map_err(|e| anyhow::anyhow!("invalid date {date:?}: {e}"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use meal_planner::tandoor::weekly::PrepSheet;
+
+    #[test]
+    fn test_input_defaults() {
+        let json = r#"{"tandoor": {"base_url": "http://localhost:8090", "api_token": "test"}, "from_date": "2026-10-19"}"#;
+        let input: Input = serde_json::from_str(json).unwrap();
+        assert_eq!(input.from_date, "2026-10-19");
+        assert_eq!(input.to_date, None);
+        assert_eq!(input.prep_date, None);
+        assert_eq!(input.format, MenuFormat::Html);
+        assert_eq!(input.title, None);
+    }
+
+    #[test]
+    fn test_input_with_options() {
+        let json = r#"{"tandoor": {"base_url": "http://localhost:8090", "api_token": "test"}, "from_date": "2026-10-19", "to_date": "2026-10-21", "prep_date": "2026-10-19", "format": "markdown", "title": "Week 43"}"#;
+        let input: Input = serde_json::from_str(json).unwrap();
+        assert_eq!(input.to_date.as_deref(), Some("2026-10-21"));
+        assert_eq!(input.prep_date.as_deref(), Some("2026-10-19"));
+        assert_eq!(input.format, MenuFormat::Markdown);
+        assert_eq!(input.title.as_deref(), Some("Week 43"));
+    }
+
+    #[test]
+    fn test_output_flattens_menu() {
+        let output = Output {
+            success: true,
+            format: Some(MenuFormat::Text),
+            content: Some("Week 43".to_string()),
+            menu: Some(WeeklyMenu {
+                title: "Week 43".to_string(),
+                meal_types: vec![],
+                days: vec![],
+                prep: PrepSheet::default(),
+                skipped: vec![],
+            }),
+            error: None,
+        };
+        let json = serde_json::to_string(&output).unwrap();
+        assert_eq!(
+            json,
+            r#"{"success":true,"format":"text","content":"Week 43","title":"Week 43","meal_types":[],"days":[],"prep":{"prep_date":"","tasks":[],"later":[]},"skipped":[]}"#
+        );
+    }
+
+    #[test]
+    fn test_error_output() {
+        let output = Output {
+            success: false,
+            format: None,
+            content: None,
+            menu: None,
+            error: Some("API error (400): invalid date".to_string()),
+        };
+        let json = serde_json::to_string(&output).unwrap();
+        assert_eq!(
+            json,
+            r#"{"success":false,"error":"API error (400): invalid date"}"#
+        );
+    }
+}
@@ -0,0 +1,307 @@
+//! Database connections and schema migrations
+//!
+//! Data is stored in PostgreSQL or, for single-user setups, a SQLite file.
+//! [`Database::connect`] picks the backend from the `DATABASE_URL` scheme:
+//!
+//! - `postgres://…` or `postgresql://…` → [`Database::Postgres`]
+//! - `sqlite:…` (e.g. `sqlite://meal-planner.db`, `sqlite::memory:`) →
+//!   [`Database::Sqlite`]; the file is created if it doesn't exist
+//!
+//! Every table the crate uses is created and evolved by SQL migrations that
+//! are embedded into the binary at compile time: [`MIGRATOR`] from
+//! `migrations/` for Postgres and [`SQLITE_MIGRATOR`] from
+//! `migrations/sqlite/`. Each migration has an `.up.sql` and a `.down.sql`
+//! file; applied versions are recorded in sqlx's `_sqlx_migrations` table.
+//!
+//! Storage types run [`Database::run_migrations`] from their `ensure_schema`
+//! methods, so binaries bring the schema up to date on their own. The
+//! `migrate` binary shows the status and applies or reverts migrations by hand.
+//!
+//! The first Postgres migrations use `IF NOT EXISTS`, so a database whose
+//! tables were created before migrations existed is adopted rather than
+//! rejected.
+
+use serde::Serialize;
+use sqlx::migrate::{Migrate, MigrateError, Migrator};
+use sqlx::sqlite::{SqliteConnectOptions, SqlitePoolOptions};
+use sqlx::{PgPool, SqlitePool};
+use std::str::FromStr;
+
+/// Postgres migrations embedded from `migrations/`
+pub static MIGRATOR: Migrator = sqlx::migrate!("./migrations");
+
+/// SQLite migrations embedded from `migrations/sqlite/`
+pub static SQLITE_MIGRATOR: Migrator = sqlx::migrate!("./migrations/sqlite");
+
+/// Database backend, chosen by URL scheme
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Backend {
+    /// `postgres://` or `postgresql://`
+    Postgres,
+    /// `sqlite:`
+    Sqlite,
+}
+
+impl Backend {
+    /// Backend for a database URL
+    pub fn from_url(url: &str) -> Result<Self, sqlx::Error> {
+        match url.split_once(':').map(|(scheme, _)| scheme) {
+            Some("postgres" | "postgresql") => Ok(Self::Postgres),
+            Some("sqlite") => Ok(Self::Sqlite),
+            _ => Err(sqlx::Error::Configuration(
+                "database URL must start with postgres://, postgresql:// or sqlite:".into(),
+            )),
+        }
+    }
+}
+
+/// Connection pool for either backend
+#[derive(Debug, Clone)]
+pub enum Database {
+    /// PostgreSQL pool
+    Postgres(PgPool),
+    /// SQLite pool
+    Sqlite(SqlitePool),
+}
+
+impl From<PgPool> for Database {
+    fn from(pool: PgPool) -> Self {
+        Self::Postgres(pool)
+    }
+}
+
+impl From<SqlitePool> for Database {
+    fn from(pool: SqlitePool) -> Self {
+        Self::Sqlite(pool)
+    }
+}
+
+impl Database {
+    /// Connect to `url`, choosing the backend from its scheme
+    pub async fn connect(url: &str) -> Result<Self, sqlx::Error> {
+        match Backend::from_url(url)? {
+            Backend::Postgres => Ok(Self::Postgres(PgPool::connect(url).await?)),
+            Backend::Sqlite => {
+                let options = SqliteConnectOptions::from_str(url)?.create_if_missing(true);
+                // Each connection to an in-memory database opens a new, empty
+                // one, so the pool must keep a single connection alive
+                let pool = if url.contains(":memory:") || url.contains("mode=memory") {
+                    SqlitePoolOptions::new()
+                        .max_connections(1)
+                        .idle_timeout(None)
+                        .max_lifetime(None)
+                } else {
+                    SqlitePoolOptions::new()
+                };
+                Ok(Self::Sqlite(pool.connect_with(options).await?))
+            }
+        }
+    }
+
+    /// Backend of this pool
+    pub const fn backend(&self) -> Backend {
+        match self {
+            Self::Postgres(_) => Backend::Postgres,
+            Self::Sqlite(_) => Backend::Sqlite,
+        }
+    }
+
+    /// Migrations for this pool's backend
+    pub const fn migrator(&self) -> &'static Migrator {
+        match self {
+            Self::Postgres(_) => &MIGRATOR,
+            Self::Sqlite(_) => &SQLITE_MIGRATOR,
+        }
+    }
+
+    /// Apply all pending migrations
+    pub async fn run_migrations(&self) -> Result<(), MigrateError> {
+        match self {
+            Self::Postgres(pool) => MIGRATOR.run(pool).await,
+            Self::Sqlite(pool) => SQLITE_MIGRATOR.run(pool).await,
+        }
+    }
+
+    /// Status of every known migration, oldest first
+    pub async fn migration_status(&self) -> Result<Vec<MigrationStatus>, MigrateError> {
+        match self {
+            Self::Postgres(pool) => status_of(&mut *pool.acquire().await?, &MIGRATOR).await,
+            Self::Sqlite(pool) => status_of(&mut *pool.acquire().await?, &SQLITE_MIGRATOR).await,
+        }
+    }
+
+    /// Revert applied migrations newer than `target`
+    ///
+    /// Without a target only the latest applied migration is reverted.
+    /// Returns the reverted versions, newest first.
+    pub async fn revert_migrations(&self, target: Option<i64>) -> Result<Vec<i64>, MigrateError> {
+        let mut applied: Vec<i64> = self
+            .migration_status()
+            .await?
+            .iter()
+            .filter(|m| m.state != MigrationState::Pending)
+            .map(|m| m.version)
+            .collect();
+        applied.sort_unstable_by(|a, b| b.cmp(a));
+
+        let target = target.unwrap_or_else(|| applied.get(1).copied().unwrap_or(0));
+        match self {
+            Self::Postgres(pool) => MIGRATOR.undo(pool, target).await?,
+            Self::Sqlite(pool) => SQLITE_MIGRATOR.undo(pool, target).await?,
+        }
+
+        Ok(applied.into_iter().filter(|v| *v > target).collect())
+    }
+}
+
+/// State of one migration in a database
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum MigrationState {
+    /// Not applied yet
+    Pending,
+    /// Applied successfully
+    Applied,
+    /// Applied, but the SQL file has changed since
+    Modified,
+    /// Started but did not complete; needs manual repair
+    Dirty,
+}
+
+/// Status of one migration
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct MigrationStatus {
+    /// Migration version (file name prefix)
+    pub version: i64,
+    /// Description from the file name
+    pub description: String,
+    /// State in the database
+    pub state: MigrationState,
+}
+
+/// Compare `migrator`'s migrations with those applied on `conn`
+async fn status_of<C: Migrate + ?Sized>(
+    conn: &mut C,
+    migrator: &Migrator,
+) -> Result<Vec<MigrationStatus>, MigrateError> {
+    conn.ensure_migrations_table().await?;
+    let dirty = conn.dirty_version().await?;
+    let applied = conn.list_applied_migrations().await?;
+
+    Ok(migrator
+        .iter()
+        .filter(|m| !m.migration_type.is_down_migration())
+        .map(|migration| {
+            let state = match applied.iter().find(|a| a.version == migration.version) {
+                None => MigrationState::Pending,
+                Some(_) if dirty == Some(migration.version) => MigrationState::Dirty,
+                Some(a) if a.checksum != migration.checksum => MigrationState::Modified,
+                Some(_) => MigrationState::Applied,
+            };
+            MigrationStatus {
+                version: migration.version,
+                description: migration.description.to_string(),
+                state,
+            }
+        })
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn versions(migrator: &Migrator, down: bool) -> Vec<i64> {
+        migrator
+            .iter()
+            .filter(|m| m.migration_type.is_down_migration() == down)
+            .map(|m| m.version)
+            .collect()
+    }
+
+    #[test]
+    fn test_migrations_are_ordered_and_reversible() {
+        for migrator in [&MIGRATOR, &SQLITE_MIGRATOR] {
+            let up = versions(migrator, false);
+            assert!(!up.is_empty());
+            assert!(up.windows(2).all(|w| w[0] < w[1]));
+            assert_eq!(up, versions(migrator, true));
+        }
+    }
+
+    #[test]
+    fn test_migrations_cover_every_table() {
+        for migrator in [&MIGRATOR, &SQLITE_MIGRATOR] {
+            let sql: String = migrator
+                .iter()
+                .filter(|m| !m.migration_type.is_down_migration())
+                .map(|m| m.sql.to_string())
+                .collect();
+
+            for table in [
+                "fatsecret_oauth_pending",
+                "fatsecret_oauth_token",
+                "fatsecret_diary_sync",
+            ] {
+                assert!(
+                    sql.contains(&format!("CREATE TABLE IF NOT EXISTS {table}")),
+                    "no migration creates {table}"
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn test_migration_state_serializes_snake_case() {
+        assert_eq!(
+            serde_json::to_string(&MigrationState::Pending).unwrap(),
+            r#""pending""#
+        );
+        assert_eq!(
+            serde_json::to_string(&MigrationState::Modified).unwrap(),
+            r#""modified""#
+        );
+    }
+
+    #[test]
+    fn test_backend_from_url() {
+        assert_eq!(
+            Backend::from_url("postgres://user@localhost/meals").unwrap(),
+            Backend::Postgres
+        );
+        assert_eq!(
+            Backend::from_url("postgresql://localhost:5432/meals").unwrap(),
+            Backend::Postgres
+        );
+        assert_eq!(
+            Backend::from_url("sqlite://meal-planner.db").unwrap(),
+            Backend::Sqlite
+        );
+        assert_eq!(
+            Backend::from_url("sqlite::memory:").unwrap(),
+            Backend::Sqlite
+        );
+        assert!(Backend::from_url("mysql://localhost/meals").is_err());
+        assert!(Backend::from_url("meal-planner.db").is_err());
+    }
+
+    #[tokio::test]
+    async fn test_sqlite_migrations_apply_and_revert() {
+        let db = Database::connect("sqlite::memory:").await.unwrap();
+        assert_eq!(db.backend(), Backend::Sqlite);
+
+        db.run_migrations().await.unwrap();
+        let status = db.migration_status().await.unwrap();
+        assert!(status.iter().all(|m| m.state == MigrationState::Applied));
+
+        let reverted = db.revert_migrations(Some(0)).await.unwrap();
+        assert_eq!(reverted.len(), status.len());
+        assert!(db
+            .migration_status()
+            .await
+            .unwrap()
+            .iter()
+            .all(|m| m.state == MigrationState::Pending));
+    }
+}
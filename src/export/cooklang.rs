@@ -0,0 +1,292 @@
+//! Cooklang export
+//!
+//! Writes a [Cooklang](https://cooklang.org/docs/spec/) recipe:
+//!
+//! - Metadata goes in YAML front matter (`title`, `description`, `tags`,
+//!   `servings`, `source`, `image`, `prep time`, `cook time`, and nutrition as
+//!   `calories`, `protein`, `fat`, `carbohydrates`)
+//! - Each step is a paragraph; a step's title becomes a `== Section ==`
+//! - A step's ingredients are marked up where the step text mentions them
+//!   (`season with the @kosher salt{1/4%cup}`); the rest are listed in a
+//!   sentence before the text
+//! - A step's time becomes a timer (`~{30%minutes}`)
+//!
+//! Tandoor's header ingredients have no Cooklang equivalent and are left out.
+
+use super::{
+    format_amount, format_decimal, has_amount, keyword_names, note, positive_minutes, unit_name,
+};
+use crate::tandoor::nutrition::core::RecipeNutritionResult;
+use crate::tandoor::{RecipeDetail, RecipeIngredient, RecipeStep};
+
+/// Characters with meaning in Cooklang markup, removed from names and notes
+const MARKUP: &[char] = &['@', '#', '~', '{', '}', '(', ')', '%'];
+
+/// Recipe as a Cooklang document
+pub fn to_cooklang(recipe: &RecipeDetail, nutrition: Option<&RecipeNutritionResult>) -> String {
+    let mut doc = front_matter(recipe, nutrition);
+
+    for step in &recipe.steps {
+        let text = step_text(step);
+        if text.is_empty() {
+            continue;
+        }
+        if let Some(name) = step
+            .name
+            .as_deref()
+            .map(str::trim)
+            .filter(|n| !n.is_empty())
+        {
+            doc += &["== ", name, " ==\n\n"].concat();
+        }
+        doc.push_str(&text);
+        doc.push_str("\n\n");
+    }
+
+    format!("{}\n", doc.trim_end())
+}
+
+fn front_matter(recipe: &RecipeDetail, nutrition: Option<&RecipeNutritionResult>) -> String {
+    let mut fields = vec![("title", yaml_string(recipe.name.trim()))];
+
+    let text_fields = [
+        ("description", recipe.description.as_deref()),
+        ("source", recipe.source_url.as_deref()),
+        ("image", recipe.image.as_deref()),
+    ];
+    for (key, value) in text_fields {
+        if let Some(value) = value.map(str::trim).filter(|v| !v.is_empty()) {
+            fields.push((key, yaml_string(value)));
+        }
+    }
+
+    let tags = keyword_names(recipe);
+    if !tags.is_empty() {
+        let tags: Vec<String> = tags.into_iter().map(yaml_string).collect();
+        fields.push(("tags", format!("[{}]", tags.join(", "))));
+    }
+    if let Some(servings) = recipe.servings.filter(|s| *s > 0) {
+        fields.push(("servings", servings.to_string()));
+    }
+    if let Some(minutes) = positive_minutes(recipe.working_time) {
+        fields.push(("prep time", format!("{minutes} minutes")));
+    }
+    if let Some(minutes) = positive_minutes(recipe.waiting_time) {
+        fields.push(("cook time", format!("{minutes} minutes")));
+    }
+    if let Some(nutrition) = nutrition {
+        fields.push(("calories", format_decimal(nutrition.calories)));
+        fields.push((
+            "protein",
+            format!("{} g", format_decimal(nutrition.protein)),
+        ));
+        fields.push(("fat", format!("{} g", format_decimal(nutrition.fat))));
+        fields.push((
+            "carbohydrates",
+            format!("{} g", format_decimal(nutrition.carbohydrate)),
+        ));
+    }
+
+    let lines: Vec<String> = fields
+        .into_iter()
+        .map(|(key, value)| format!("{key}: {value}"))
+        .collect();
+    format!("---\n{}\n---\n\n", lines.join("\n"))
+}
+
+/// A YAML scalar, quoted unless it is plain text
+fn yaml_string(value: &str) -> String {
+    let plain = value.chars().all(|c| {
+        c.is_alphanumeric() || matches!(c, ' ' | '-' | '_' | '.' | '\'' | '(' | ')' | '/')
+    }) && value.chars().next().is_some_and(char::is_alphabetic)
+        && !matches!(
+            value.to_ascii_lowercase().as_str(),
+            "true" | "false" | "yes" | "no" | "on" | "off" | "null"
+        );
+
+    if plain {
+        value.to_string()
+    } else {
+        let escaped = value
+            .replace('\\', "\\\\")
+            .replace('"', "\\\"")
+            .replace('\n', "\\n");
+        format!("\"{escaped}\"")
+    }
+}
+
+/// Step text with its ingredients and timer marked up
+fn step_text(step: &RecipeStep) -> String {
+    let instruction = step.instruction.trim();
+    let lower = instruction.to_ascii_lowercase();
+
+    // Mentions of ingredients in the text: (start, end, markup)
+    let mut mentions: Vec<(usize, usize, String)> = Vec::new();
+    let mut unmentioned = Vec::new();
+
+    for ingredient in step.ingredients.iter().filter(|i| !i.is_header) {
+        let Some(name) = ingredient
+            .food
+            .as_ref()
+            .map(|f| clean(&f.name))
+            .filter(|n| !n.is_empty())
+        else {
+            continue;
+        };
+        match find_mention(&lower, &name.to_ascii_lowercase(), &mentions) {
+            Some((start, end)) => {
+                let text = instruction.get(start..end).unwrap_or(&name);
+                mentions.push((start, end, reference(text, ingredient)));
+            }
+            None => unmentioned.push(reference(&name, ingredient)),
+        }
+    }
+
+    mentions.sort_by_key(|(start, _, _)| *start);
+    let mut text = String::new();
+    let mut position = 0;
+    for (start, end, markup) in &mentions {
+        text.push_str(instruction.get(position..*start).unwrap_or_default());
+        text.push_str(markup);
+        position = *end;
+    }
+    text.push_str(instruction.get(position..).unwrap_or_default());
+
+    if !unmentioned.is_empty() {
+        let list = format!("Use {}.", unmentioned.join(", "));
+        text = if text.is_empty() {
+            list
+        } else {
+            format!("{list}\n{text}")
+        };
+    }
+    if let Some(minutes) = positive_minutes(step.time).filter(|_| !text.is_empty()) {
+        text += &[" ~{", &minutes.to_string(), "%minutes}"].concat();
+    }
+    text
+}
+
+/// Byte range of the first whole-word mention of `name` (a trailing plural
+/// "s" included) that doesn't overlap an earlier mention
+fn find_mention(
+    text: &str,
+    name: &str,
+    taken: &[(usize, usize, String)],
+) -> Option<(usize, usize)> {
+    let is_word = |c: Option<char>| c.is_some_and(char::is_alphanumeric);
+
+    text.match_indices(name).find_map(|(start, _)| {
+        let mut end = start + name.len();
+        let before = text.get(..start).and_then(|s| s.chars().next_back());
+        let mut after = text.get(end..).map(str::chars);
+        let mut next = after.as_mut().and_then(Iterator::next);
+        if next == Some('s') && !is_word(after.as_mut().and_then(Iterator::next)) {
+            end += 1;
+            next = None;
+        }
+
+        let overlaps = taken.iter().any(|(s, e, _)| start < *e && *s < end);
+        (!is_word(before) && !is_word(next) && !overlaps).then_some((start, end))
+    })
+}
+
+/// Cooklang ingredient reference (`@name{amount%unit}(note)`)
+fn reference(name: &str, ingredient: &RecipeIngredient) -> String {
+    let quantity = if has_amount(ingredient) {
+        let amount = format_amount(ingredient.amount);
+        // Cooklang has fractions but no mixed numbers
+        let amount = if amount.contains(' ') {
+            format_decimal(ingredient.amount)
+        } else {
+            amount
+        };
+        match unit_name(ingredient) {
+            Some(unit) => format!("{amount}%{}", clean(unit)),
+            None => amount,
+        }
+    } else {
+        String::new()
+    };
+
+    let note = note(ingredient)
+        .map(|note| format!("({})", clean(note)))
+        .unwrap_or_default();
+    format!("@{name}{{{quantity}}}{note}")
+}
+
+fn clean(text: &str) -> String {
+    text.replace(MARKUP, "").trim().to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::export::stored_nutrition;
+    use crate::export::tests::sample_recipe;
+
+    const EXPECTED: &str = r#"---
+title: Texas Style Brisket
+description: "Salt, pepper & smoke."
+source: "https://smokeandfire.example/texas-brisket/"
+image: "https://tandoor.example/media/brisket.jpg"
+tags: [bbq, beef]
+servings: 12
+prep time: 30 minutes
+cook time: 720 minutes
+calories: 450
+protein: 38.5 g
+fat: 30 g
+carbohydrates: 0 g
+---
+
+== Prep ==
+
+Use @packer brisket{1%whole}(12-14 lb).
+Trim the fat, then season with the @kosher salt{1/4%cup} and @black pepper{1/4%cup}(coarse). ~{30%minutes}
+
+Smoke over @post oak{} at 250°F until done. ~{720%minutes}
+"#;
+
+    #[test]
+    fn test_to_cooklang() {
+        let recipe = sample_recipe();
+        let cook = to_cooklang(&recipe, stored_nutrition(&recipe).as_ref());
+        assert_eq!(cook, EXPECTED);
+    }
+
+    #[test]
+    fn test_find_mention() {
+        assert_eq!(find_mention("add the eggs.", "egg", &[]), Some((8, 12)));
+        assert_eq!(find_mention("eggplant and egg", "egg", &[]), Some((13, 16)));
+        assert_eq!(find_mention("saltwater", "salt", &[]), None);
+        let taken = vec![(4, 8, String::new())];
+        assert_eq!(
+            find_mention("add salt, more salt", "salt", &taken),
+            Some((15, 19))
+        );
+    }
+
+    #[test]
+    fn test_yaml_string() {
+        assert_eq!(yaml_string("Texas Style Brisket"), "Texas Style Brisket");
+        assert_eq!(yaml_string("Grandma's Pie"), "Grandma's Pie");
+        assert_eq!(yaml_string("Yes"), "\"Yes\"");
+        assert_eq!(yaml_string("a: \"b\""), "\"a: \\\"b\\\"\"");
+        assert_eq!(yaml_string("7 layer dip"), "\"7 layer dip\"");
+    }
+
+    #[test]
+    fn test_mixed_amounts_are_decimals() {
+        let mut recipe = sample_recipe();
+        if let Some(salt) = recipe
+            .steps
+            .first_mut()
+            .and_then(|step| step.ingredients.get_mut(2))
+        {
+            salt.amount = 1.5;
+        }
+        let cook = to_cooklang(&recipe, None);
+        assert!(cook.contains("@kosher salt{1.5%cups}"));
+        assert!(!cook.contains("calories"));
+    }
+}
@@ -0,0 +1,201 @@
+//! schema.org `Recipe` JSON-LD export
+//!
+//! The inverse of [`scrape::schema_org`](crate::scrape::schema_org), so an
+//! exported recipe can be imported again:
+//!
+//! | Tandoor | schema.org |
+//! |---------|------------|
+//! | `working_time` / `waiting_time` | `prepTime` / `cookTime` (+ `totalTime`) |
+//! | `servings` + `servings_text` | `recipeYield` |
+//! | keywords | `keywords` (comma-separated) |
+//! | ingredients of all steps | `recipeIngredient` (one line each) |
+//! | steps | `recipeInstructions` (`HowToStep`, `name` from the step title) |
+//! | nutrition | `nutrition` (`NutritionInformation`) |
+
+use super::{format_decimal, ingredient_line, keyword_names, positive_minutes, servings_text};
+use crate::tandoor::nutrition::core::RecipeNutritionResult;
+use crate::tandoor::RecipeDetail;
+use serde_json::{json, Map, Value};
+
+/// Recipe as a schema.org `Recipe` object
+pub fn to_json_ld(recipe: &RecipeDetail, nutrition: Option<&RecipeNutritionResult>) -> Value {
+    let mut node = Map::new();
+    node.insert("@context".into(), json!("https://schema.org"));
+    node.insert("@type".into(), json!("Recipe"));
+    node.insert("name".into(), json!(recipe.name));
+
+    let optional = [
+        (
+            "description",
+            recipe.description.clone().filter(|d| !d.is_empty()),
+        ),
+        ("image", recipe.image.clone()),
+        ("url", recipe.source_url.clone().filter(|u| !u.is_empty())),
+        ("recipeYield", servings_text(recipe)),
+    ];
+    for (key, value) in optional {
+        if let Some(value) = value {
+            node.insert(key.into(), json!(value));
+        }
+    }
+
+    insert_times(&mut node, recipe);
+
+    let keywords = keyword_names(recipe);
+    if !keywords.is_empty() {
+        node.insert("keywords".into(), json!(keywords.join(", ")));
+    }
+
+    let ingredients: Vec<String> = recipe
+        .steps
+        .iter()
+        .flat_map(|step| &step.ingredients)
+        .filter_map(ingredient_line)
+        .collect();
+    node.insert("recipeIngredient".into(), json!(ingredients));
+    node.insert("recipeInstructions".into(), instructions(recipe));
+
+    if let Some(nutrition) = nutrition {
+        node.insert("nutrition".into(), nutrition_information(nutrition));
+    }
+
+    Value::Object(node)
+}
+
+/// Recipe as pretty-printed JSON-LD
+pub fn to_json_ld_string(
+    recipe: &RecipeDetail,
+    nutrition: Option<&RecipeNutritionResult>,
+) -> String {
+    let mut json = serde_json::to_string_pretty(&to_json_ld(recipe, nutrition)).unwrap_or_default();
+    json.push('\n');
+    json
+}
+
+/// Minutes as an ISO 8601 duration (`PT1H30M`)
+pub fn iso_duration(minutes: i32) -> String {
+    #[allow(clippy::integer_division)]
+    let hours = minutes / 60;
+    match (hours, minutes % 60) {
+        (0, minutes) => format!("PT{minutes}M"),
+        (hours, 0) => format!("PT{hours}H"),
+        (hours, minutes) => format!("PT{hours}H{minutes}M"),
+    }
+}
+
+fn insert_times(node: &mut Map<String, Value>, recipe: &RecipeDetail) {
+    let prep = positive_minutes(recipe.working_time);
+    let cook = positive_minutes(recipe.waiting_time);
+
+    if let Some(prep) = prep {
+        node.insert("prepTime".into(), json!(iso_duration(prep)));
+    }
+    if let Some(cook) = cook {
+        node.insert("cookTime".into(), json!(iso_duration(cook)));
+    }
+    if prep.is_some() || cook.is_some() {
+        let total = prep.unwrap_or(0) + cook.unwrap_or(0);
+        node.insert("totalTime".into(), json!(iso_duration(total)));
+    }
+}
+
+fn instructions(recipe: &RecipeDetail) -> Value {
+    recipe
+        .steps
+        .iter()
+        .filter(|step| !step.instruction.trim().is_empty())
+        .map(|step| {
+            let mut how_to = json!({
+                "@type": "HowToStep",
+                "text": step.instruction.trim(),
+            });
+            if let (Some(name), Some(object)) = (
+                step.name.as_deref().filter(|name| !name.trim().is_empty()),
+                how_to.as_object_mut(),
+            ) {
+                object.insert("name".into(), json!(name.trim()));
+            }
+            how_to
+        })
+        .collect()
+}
+
+fn nutrition_information(nutrition: &RecipeNutritionResult) -> Value {
+    json!({
+        "@type": "NutritionInformation",
+        "calories": format!("{} kcal", format_decimal(nutrition.calories)),
+        "proteinContent": format!("{} g", format_decimal(nutrition.protein)),
+        "fatContent": format!("{} g", format_decimal(nutrition.fat)),
+        "carbohydrateContent": format!("{} g", format_decimal(nutrition.carbohydrate)),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::export::stored_nutrition;
+    use crate::export::tests::sample_recipe;
+
+    #[test]
+    fn test_iso_duration() {
+        assert_eq!(iso_duration(15), "PT15M");
+        assert_eq!(iso_duration(120), "PT2H");
+        assert_eq!(iso_duration(750), "PT12H30M");
+    }
+
+    #[test]
+    fn test_to_json_ld() {
+        let recipe = sample_recipe();
+        let json = to_json_ld(&recipe, stored_nutrition(&recipe).as_ref());
+
+        let field = |pointer| json.pointer(pointer).cloned();
+        assert_eq!(field("/@type"), Some(json!("Recipe")));
+        assert_eq!(field("/name"), Some(json!("Texas Style Brisket")));
+        assert_eq!(field("/recipeYield"), Some(json!("12 servings")));
+        assert_eq!(field("/prepTime"), Some(json!("PT30M")));
+        assert_eq!(field("/cookTime"), Some(json!("PT12H")));
+        assert_eq!(field("/totalTime"), Some(json!("PT12H30M")));
+        assert_eq!(field("/keywords"), Some(json!("bbq, beef")));
+        assert_eq!(field("/nutrition/calories"), Some(json!("450 kcal")));
+        assert_eq!(field("/nutrition/proteinContent"), Some(json!("38.5 g")));
+    }
+
+    #[test]
+    fn test_ingredients_and_instructions() {
+        let json = to_json_ld(&sample_recipe(), None);
+
+        let field = |pointer| json.pointer(pointer).cloned();
+        assert_eq!(
+            field("/recipeIngredient"),
+            Some(json!([
+                "1 whole packer brisket, 12-14 lb",
+                "1/4 cup kosher salt",
+                "1/4 cup black pepper, coarse",
+                "post oak"
+            ]))
+        );
+        assert_eq!(field("/recipeInstructions/0/name"), Some(json!("Prep")));
+        assert_eq!(
+            field("/recipeInstructions/1/text"),
+            Some(json!("Smoke over post oak at 250°F until done."))
+        );
+        assert_eq!(field("/nutrition"), None);
+    }
+
+    #[test]
+    fn test_to_json_ld_without_optional_fields() {
+        let recipe: RecipeDetail =
+            serde_json::from_value(json!({"id": 1, "name": "Toast"})).unwrap();
+        let json = to_json_ld(&recipe, None);
+        assert_eq!(
+            json,
+            json!({
+                "@context": "https://schema.org",
+                "@type": "Recipe",
+                "name": "Toast",
+                "recipeIngredient": [],
+                "recipeInstructions": []
+            })
+        );
+    }
+}
@@ -0,0 +1,207 @@
+//! Markdown export
+//!
+//! One document per recipe: title, description, image, a facts list
+//! (servings, times, source, keywords), the ingredients of all steps (with
+//! Tandoor's header ingredients as sub-headings), numbered instructions and a
+//! nutrition table.
+
+use super::{
+    format_decimal, format_minutes, header_text, ingredient_line, keyword_names, positive_minutes,
+    servings_text,
+};
+use crate::tandoor::nutrition::core::RecipeNutritionResult;
+use crate::tandoor::RecipeDetail;
+
+/// Recipe as a Markdown document
+pub fn to_markdown(recipe: &RecipeDetail, nutrition: Option<&RecipeNutritionResult>) -> String {
+    let mut doc = format!("# {}\n\n", recipe.name.trim());
+
+    if let Some(description) = recipe
+        .description
+        .as_deref()
+        .map(str::trim)
+        .filter(|d| !d.is_empty())
+    {
+        doc.push_str(description);
+        doc.push_str("\n\n");
+    }
+    if let Some(image) = recipe.image.as_deref().filter(|i| !i.is_empty()) {
+        doc += &["![", recipe.name.trim(), "](", image, ")\n\n"].concat();
+    }
+
+    let facts: Vec<String> = facts(recipe)
+        .into_iter()
+        .map(|(label, value)| format!("- **{label}:** {value}\n"))
+        .collect();
+    if !facts.is_empty() {
+        doc.push_str(&facts.concat());
+        doc.push('\n');
+    }
+
+    doc.push_str(&ingredients(recipe));
+    doc.push_str(&instructions(recipe));
+    if let Some(nutrition) = nutrition {
+        doc.push_str(&nutrition_table(nutrition));
+    }
+
+    format!("{}\n", doc.trim_end())
+}
+
+fn facts(recipe: &RecipeDetail) -> Vec<(&'static str, String)> {
+    let keywords = keyword_names(recipe).join(", ");
+    [
+        ("Servings", servings_text(recipe)),
+        (
+            "Prep time",
+            positive_minutes(recipe.working_time).map(format_minutes),
+        ),
+        (
+            "Cook time",
+            positive_minutes(recipe.waiting_time).map(format_minutes),
+        ),
+        (
+            "Source",
+            recipe
+                .source_url
+                .clone()
+                .filter(|url| !url.is_empty())
+                .map(|url| format!("<{url}>")),
+        ),
+        ("Keywords", Some(keywords).filter(|k| !k.is_empty())),
+    ]
+    .into_iter()
+    .filter_map(|(label, value)| value.map(|value| (label, value)))
+    .collect()
+}
+
+fn ingredients(recipe: &RecipeDetail) -> String {
+    let section: String = recipe
+        .steps
+        .iter()
+        .flat_map(|step| &step.ingredients)
+        .filter_map(|ingredient| {
+            header_text(ingredient)
+                .map(|header| format!("\n### {header}\n\n"))
+                .or_else(|| ingredient_line(ingredient).map(|line| format!("- {line}\n")))
+        })
+        .collect();
+
+    if section.is_empty() {
+        String::new()
+    } else {
+        format!("## Ingredients\n\n{}\n\n", section.trim())
+    }
+}
+
+fn instructions(recipe: &RecipeDetail) -> String {
+    let steps: Vec<String> = recipe
+        .steps
+        .iter()
+        .filter(|step| !step.instruction.trim().is_empty())
+        .zip(1..)
+        .map(|(step, number)| {
+            // Continuation lines are indented to stay inside the list item
+            let text = step.instruction.trim().replace('\n', "\n   ");
+            step.name
+                .as_deref()
+                .map(str::trim)
+                .filter(|n| !n.is_empty())
+                .map_or_else(
+                    || format!("{number}. {text}"),
+                    |name| format!("{number}. **{name}**\n\n   {text}"),
+                )
+        })
+        .collect();
+
+    if steps.is_empty() {
+        String::new()
+    } else {
+        format!("## Instructions\n\n{}\n\n", steps.join("\n\n"))
+    }
+}
+
+fn nutrition_table(nutrition: &RecipeNutritionResult) -> String {
+    format!(
+        "## Nutrition\n\n\
+         Per serving:\n\n\
+         | Calories | Protein | Fat | Carbohydrates |\n\
+         |----------|---------|-----|---------------|\n\
+         | {} kcal | {} g | {} g | {} g |\n",
+        format_decimal(nutrition.calories),
+        format_decimal(nutrition.protein),
+        format_decimal(nutrition.fat),
+        format_decimal(nutrition.carbohydrate),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::export::stored_nutrition;
+    use crate::export::tests::sample_recipe;
+
+    const EXPECTED: &str = "\
+# Texas Style Brisket
+
+Salt, pepper & smoke.
+
+![Texas Style Brisket](https://tandoor.example/media/brisket.jpg)
+
+- **Servings:** 12 servings
+- **Prep time:** 30 min
+- **Cook time:** 12 h
+- **Source:** <https://smokeandfire.example/texas-brisket/>
+- **Keywords:** bbq, beef
+
+## Ingredients
+
+- 1 whole packer brisket, 12-14 lb
+
+### For the rub
+
+- 1/4 cup kosher salt
+- 1/4 cup black pepper, coarse
+- post oak
+
+## Instructions
+
+1. **Prep**
+
+   Trim the fat, then season with the kosher salt and black pepper.
+
+2. Smoke over post oak at 250°F until done.
+
+## Nutrition
+
+Per serving:
+
+| Calories | Protein | Fat | Carbohydrates |
+|----------|---------|-----|---------------|
+| 450 kcal | 38.5 g | 30 g | 0 g |
+";
+
+    #[test]
+    fn test_to_markdown() {
+        let recipe = sample_recipe();
+        let markdown = to_markdown(&recipe, stored_nutrition(&recipe).as_ref());
+        assert_eq!(markdown, EXPECTED);
+    }
+
+    #[test]
+    fn test_multiline_instruction_stays_in_list_item() {
+        let mut recipe = sample_recipe();
+        if let Some(step) = recipe.steps.get_mut(1) {
+            step.instruction = "Smoke.\nWrap at 165°F.".to_string();
+        }
+        let markdown = to_markdown(&recipe, None);
+        assert!(markdown.contains("2. Smoke.\n   Wrap at 165°F.\n"));
+        assert!(!markdown.contains("## Nutrition"));
+    }
+
+    #[test]
+    fn test_minimal_recipe() {
+        let recipe: RecipeDetail =
+            serde_json::from_value(serde_json::json!({"id": 1, "name": "Toast"})).unwrap();
+        assert_eq!(to_markdown(&recipe, None), "# Toast\n");
+    }
+}
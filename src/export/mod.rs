@@ -0,0 +1,464 @@
+//! Recipe export to open formats
+//!
+//! Renders a [`RecipeDetail`] from Tandoor as:
+//!
+//! - [`json_ld`] - schema.org `Recipe` JSON-LD (the format recipe sites embed)
+//! - [`markdown`] - a readable Markdown document
+//! - [`cooklang`] - a Cooklang `.cook` file
+//!
+//! Nutrition is passed as a [`RecipeNutritionResult`] from
+//! [`nutrition::core`](crate::tandoor::nutrition::core), either calculated or
+//! taken from the recipe with [`stored_nutrition`].
+//!
+//! Renderers are pure; [`export_recipes`] fetches recipes and writes one file
+//! per format into a directory:
+//!
+//! ```rust,no_run
+//! use meal_planner::export::{book_recipe_ids, export_recipes, ExportFormat};
+//! use meal_planner::tandoor::{TandoorClient, TandoorConfig};
+//! use std::path::Path;
+//!
+//! # fn main() -> Result<(), Box<dyn std::error::Error>> {
+//! let client = TandoorClient::new(&TandoorConfig {
+//!     base_url: "http://localhost:8090".to_string(),
+//!     api_token: "your-token".to_string(),
+//! })?;
+//! let ids = book_recipe_ids(&client, 3)?;
+//! let report = export_recipes(&client, &ids, Path::new("backup"), &ExportFormat::ALL)?;
+//! println!("{} exported, {} failed", report.exported.len(), report.failed.len());
+//! # Ok(())
+//! # }
+//! ```
+
+pub mod cooklang;
+pub mod json_ld;
+pub mod markdown;
+
+pub use crate::tandoor::nutrition::core::stored_nutrition;
+pub use crate::tandoor::text::{format_amount, format_decimal};
+
+use crate::tandoor::nutrition::core::RecipeNutritionResult;
+use crate::tandoor::{all_pages, RecipeDetail, RecipeIngredient, TandoorClient, TandoorError};
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+use thiserror::Error;
+
+/// Page size when listing recipes, keywords and book entries
+const PAGE_SIZE: u32 = 100;
+
+/// Export errors
+#[derive(Debug, Error)]
+pub enum ExportError {
+    #[error("Tandoor error: {0}")]
+    Tandoor(#[from] TandoorError),
+
+    #[error("I/O error: {0}")]
+    Io(#[from] std::io::Error),
+
+    #[error("Keyword not found: {0}")]
+    KeywordNotFound(String),
+}
+
+/// Export format
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ExportFormat {
+    /// schema.org JSON-LD (`.json`)
+    JsonLd,
+    /// Markdown (`.md`)
+    Markdown,
+    /// Cooklang (`.cook`)
+    Cooklang,
+}
+
+impl ExportFormat {
+    /// Every format
+    pub const ALL: [Self; 3] = [Self::JsonLd, Self::Markdown, Self::Cooklang];
+
+    /// File extension, without the dot
+    pub const fn extension(self) -> &'static str {
+        match self {
+            Self::JsonLd => "json",
+            Self::Markdown => "md",
+            Self::Cooklang => "cook",
+        }
+    }
+
+    /// Render `recipe` in this format
+    pub fn render(
+        self,
+        recipe: &RecipeDetail,
+        nutrition: Option<&RecipeNutritionResult>,
+    ) -> String {
+        match self {
+            Self::JsonLd => json_ld::to_json_ld_string(recipe, nutrition),
+            Self::Markdown => markdown::to_markdown(recipe, nutrition),
+            Self::Cooklang => cooklang::to_cooklang(recipe, nutrition),
+        }
+    }
+}
+
+// ============================================================================
+// Shared formatting
+// ============================================================================
+
+/// Unit name, plural when the amount calls for it
+pub(crate) fn unit_name(ingredient: &RecipeIngredient) -> Option<&str> {
+    let unit = ingredient.unit.as_ref()?;
+    let plural = unit
+        .plural_name
+        .as_deref()
+        .filter(|plural| !plural.is_empty() && ingredient.amount > 1.0);
+    Some(plural.unwrap_or(&unit.name)).filter(|name| !name.is_empty())
+}
+
+/// Ingredient note, if not empty
+pub(crate) fn note(ingredient: &RecipeIngredient) -> Option<&str> {
+    ingredient
+        .note
+        .as_deref()
+        .map(str::trim)
+        .filter(|note| !note.is_empty())
+}
+
+/// Whether the ingredient's amount is shown
+pub(crate) fn has_amount(ingredient: &RecipeIngredient) -> bool {
+    !ingredient.no_amount && ingredient.amount > 0.0
+}
+
+/// Ingredient as one line of text ("1 1/2 cups flour, sifted")
+///
+/// Headers have no line.
+pub fn ingredient_line(ingredient: &RecipeIngredient) -> Option<String> {
+    if ingredient.is_header {
+        return None;
+    }
+    let food = ingredient.food.as_ref().map(|food| food.name.as_str());
+    let amount = has_amount(ingredient).then(|| format_amount(ingredient.amount));
+
+    let line = [amount.as_deref(), unit_name(ingredient), food]
+        .into_iter()
+        .flatten()
+        .collect::<Vec<_>>()
+        .join(" ");
+    let line = match note(ingredient) {
+        Some(note) if line.is_empty() => note.to_string(),
+        Some(note) => format!("{line}, {note}"),
+        None => line,
+    };
+    Some(line).filter(|line| !line.is_empty())
+}
+
+/// Header text of a header ingredient
+pub(crate) fn header_text(ingredient: &RecipeIngredient) -> Option<&str> {
+    if !ingredient.is_header {
+        return None;
+    }
+    note(ingredient).or_else(|| ingredient.food.as_ref().map(|food| food.name.as_str()))
+}
+
+/// Keyword names, skipping keywords without one
+pub fn keyword_names(recipe: &RecipeDetail) -> Vec<&str> {
+    recipe
+        .keywords
+        .iter()
+        .filter_map(|keyword| keyword.name.as_deref().or(keyword.label.as_deref()))
+        .collect()
+}
+
+/// Servings with their text ("8 slices")
+pub(crate) fn servings_text(recipe: &RecipeDetail) -> Option<String> {
+    let servings = recipe.servings.filter(|servings| *servings > 0)?;
+    Some(
+        recipe
+            .servings_text
+            .as_deref()
+            .map(str::trim)
+            .filter(|text| !text.is_empty())
+            .map_or_else(|| servings.to_string(), |text| format!("{servings} {text}")),
+    )
+}
+
+/// Minutes, if more than zero
+pub(crate) fn positive_minutes(minutes: Option<i32>) -> Option<i32> {
+    minutes.filter(|minutes| *minutes > 0)
+}
+
+/// Human-readable duration ("1 h 30 min")
+pub(crate) fn format_minutes(minutes: i32) -> String {
+    #[allow(clippy::integer_division)]
+    let hours = minutes / 60;
+    match (hours, minutes % 60) {
+        (0, minutes) => format!("{minutes} min"),
+        (hours, 0) => format!("{hours} h"),
+        (hours, minutes) => format!("{hours} h {minutes} min"),
+    }
+}
+
+/// File name stem for a recipe ("42-texas-style-brisket")
+pub fn file_stem(recipe: &RecipeDetail) -> String {
+    let slug = recipe
+        .name
+        .to_lowercase()
+        .split(|c: char| !c.is_alphanumeric())
+        .filter(|word| !word.is_empty())
+        .collect::<Vec<_>>()
+        .join("-");
+    if slug.is_empty() {
+        recipe.id.to_string()
+    } else {
+        format!("{}-{slug}", recipe.id)
+    }
+}
+
+// ============================================================================
+// Bulk export
+// ============================================================================
+
+/// A recipe written to disk
+#[derive(Debug, Serialize)]
+pub struct ExportedRecipe {
+    /// Recipe ID
+    pub id: i64,
+    /// Recipe name
+    pub name: String,
+    /// Files written, one per format
+    pub files: Vec<PathBuf>,
+}
+
+/// A recipe that couldn't be exported
+#[derive(Debug, Serialize)]
+pub struct FailedExport {
+    /// Recipe ID
+    pub id: i64,
+    /// Error message
+    pub error: String,
+}
+
+/// Result of a bulk export
+#[derive(Debug, Default, Serialize)]
+pub struct ExportReport {
+    /// Recipes written
+    pub exported: Vec<ExportedRecipe>,
+    /// Recipes that failed
+    pub failed: Vec<FailedExport>,
+}
+
+/// IDs of the recipes in a recipe book, in book order
+pub fn book_recipe_ids(client: &TandoorClient, book_id: i64) -> Result<Vec<i64>, ExportError> {
+    let mut entries: Vec<_> =
+        all_pages(|page| client.list_recipe_book_entries(Some(page), Some(PAGE_SIZE)))?
+            .into_iter()
+            .filter(|entry| entry.recipe_book == book_id)
+            .collect();
+    entries.sort_by_key(|entry| entry.position);
+
+    let mut ids: Vec<i64> = Vec::new();
+    for entry in entries {
+        if !ids.contains(&entry.recipe) {
+            ids.push(entry.recipe);
+        }
+    }
+    Ok(ids)
+}
+
+/// IDs of the recipes tagged with a keyword, looked up by name
+/// (case-insensitive)
+pub fn keyword_recipe_ids(client: &TandoorClient, keyword: &str) -> Result<Vec<i64>, ExportError> {
+    let keyword_id = all_pages(|page| client.list_keywords(Some(page), Some(PAGE_SIZE)))?
+        .into_iter()
+        .find(|k| {
+            [k.name.as_deref(), k.label.as_deref()]
+                .into_iter()
+                .flatten()
+                .any(|name| name.eq_ignore_ascii_case(keyword.trim()))
+        })
+        .map(|k| k.id)
+        .ok_or_else(|| ExportError::KeywordNotFound(keyword.to_string()))?;
+
+    Ok(
+        all_pages(|page| client.list_recipes_by_keyword(keyword_id, Some(page), Some(PAGE_SIZE)))?
+            .into_iter()
+            .map(|recipe| recipe.id)
+            .collect(),
+    )
+}
+
+/// Fetch each recipe and write it into `dir` in every format
+///
+/// Files are named by [`file_stem`] plus the format's extension. A recipe
+/// that can't be fetched is reported and skipped; failing to write a file
+/// aborts the export.
+pub fn export_recipes(
+    client: &TandoorClient,
+    ids: &[i64],
+    dir: &Path,
+    formats: &[ExportFormat],
+) -> Result<ExportReport, ExportError> {
+    std::fs::create_dir_all(dir)?;
+    let mut report = ExportReport::default();
+
+    for &id in ids {
+        let recipe = match client.get_recipe_detail(id) {
+            Ok(recipe) => recipe,
+            Err(e) => {
+                report.failed.push(FailedExport {
+                    id,
+                    error: e.to_string(),
+                });
+                continue;
+            }
+        };
+        report.exported.push(write_recipe(&recipe, dir, formats)?);
+    }
+
+    Ok(report)
+}
+
+/// Write `recipe` into `dir` in each format
+pub fn write_recipe(
+    recipe: &RecipeDetail,
+    dir: &Path,
+    formats: &[ExportFormat],
+) -> Result<ExportedRecipe, ExportError> {
+    let nutrition = stored_nutrition(recipe);
+    let stem = file_stem(recipe);
+
+    let mut files = Vec::new();
+    for format in formats {
+        let path = dir.join(format!("{stem}.{}", format.extension()));
+        std::fs::write(&path, format.render(recipe, nutrition.as_ref()))?;
+        files.push(path);
+    }
+
+    Ok(ExportedRecipe {
+        id: recipe.id,
+        name: recipe.name.clone(),
+        files,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tandoor::{Food, Unit};
+
+    fn ingredient(amount: f64, unit: Option<(&str, &str)>, food: &str) -> RecipeIngredient {
+        RecipeIngredient {
+            food: Some(Food {
+                id: 1,
+                name: food.to_string(),
+                plural_name: None,
+                description: None,
+                supermarket_category: None,
+            }),
+            unit: unit.map(|(name, plural)| Unit {
+                id: 1,
+                name: name.to_string(),
+                plural_name: Some(plural.to_string()),
+            }),
+            amount,
+            note: None,
+            is_header: false,
+            no_amount: false,
+            original_text: None,
+        }
+    }
+
+    /// A Tandoor recipe detail response
+    pub(super) fn sample_recipe() -> RecipeDetail {
+        let food = |id, name: &str| serde_json::json!({"id": id, "name": name});
+        let cup = serde_json::json!({"id": 1, "name": "cup", "plural_name": "cups"});
+        serde_json::from_value(serde_json::json!({
+            "id": 42,
+            "name": "Texas Style Brisket",
+            "description": "Salt, pepper & smoke.",
+            "image": "https://tandoor.example/media/brisket.jpg",
+            "source_url": "https://smokeandfire.example/texas-brisket/",
+            "keywords": [{"id": 1, "name": "bbq", "label": "bbq"}, {"id": 2, "label": "beef"}],
+            "working_time": 30,
+            "waiting_time": 720,
+            "servings": 12,
+            "servings_text": "servings",
+            "nutrition": {"calories": 450.0, "proteins": 38.5, "fats": 30.0, "carbohydrates": 0.0, "source": "manual"},
+            "steps": [
+                {
+                    "id": 1,
+                    "name": "Prep",
+                    "instruction": "Trim the fat, then season with the kosher salt and black pepper.",
+                    "time": 30,
+                    "ingredients": [
+                        {"food": food(1, "packer brisket"), "unit": {"id": 2, "name": "whole"}, "amount": 1.0, "note": "12-14 lb"},
+                        {"food": null, "unit": null, "amount": 0.0, "note": "For the rub", "is_header": true},
+                        {"food": food(2, "kosher salt"), "unit": cup, "amount": 0.25},
+                        {"food": food(3, "black pepper"), "unit": cup, "amount": 0.25, "note": "coarse"}
+                    ]
+                },
+                {
+                    "id": 2,
+                    "name": "",
+                    "instruction": "Smoke over post oak at 250°F until done.",
+                    "time": 720,
+                    "ingredients": [
+                        {"food": food(4, "post oak"), "unit": null, "amount": 0.0, "no_amount": true}
+                    ]
+                }
+            ]
+        }))
+        .unwrap()
+    }
+
+    #[test]
+    fn test_stored_nutrition() {
+        let recipe = sample_recipe();
+        let nutrition = stored_nutrition(&recipe).unwrap();
+        assert!((nutrition.calories - 450.0).abs() < f64::EPSILON);
+        assert!((nutrition.protein - 38.5).abs() < f64::EPSILON);
+
+        let mut without = recipe;
+        without.nutrition = Some(crate::tandoor::RecipeNutrition::default());
+        assert!(stored_nutrition(&without).is_none());
+    }
+
+    #[test]
+    fn test_file_stem() {
+        let mut recipe = sample_recipe();
+        assert_eq!(file_stem(&recipe), "42-texas-style-brisket");
+        recipe.name = "Crème brûlée!".to_string();
+        assert_eq!(file_stem(&recipe), "42-crème-brûlée");
+        recipe.name = "???".to_string();
+        assert_eq!(file_stem(&recipe), "42");
+    }
+
+    #[test]
+    fn test_ingredient_line() {
+        let mut flour = ingredient(1.5, Some(("cup", "cups")), "flour");
+        flour.note = Some("sifted".to_string());
+        assert_eq!(
+            ingredient_line(&flour).as_deref(),
+            Some("1 1/2 cups flour, sifted")
+        );
+
+        let egg = ingredient(1.0, None, "egg");
+        assert_eq!(ingredient_line(&egg).as_deref(), Some("1 egg"));
+
+        let mut salt = ingredient(0.0, None, "salt");
+        salt.no_amount = true;
+        salt.note = Some("to taste".to_string());
+        assert_eq!(ingredient_line(&salt).as_deref(), Some("salt, to taste"));
+
+        let mut header = ingredient(0.0, None, "");
+        header.food = None;
+        header.is_header = true;
+        header.note = Some("For the rub".to_string());
+        assert_eq!(ingredient_line(&header), None);
+        assert_eq!(header_text(&header), Some("For the rub"));
+    }
+
+    #[test]
+    fn test_format_minutes() {
+        assert_eq!(format_minutes(45), "45 min");
+        assert_eq!(format_minutes(120), "2 h");
+        assert_eq!(format_minutes(90), "1 h 30 min");
+    }
+}
@@ -7,6 +7,8 @@ use std::env;
 
 use urlencoding::encode;
 
+use crate::fatsecret::core::oauth2::{parse_scopes, OAuth2Scope, DEFAULT_TOKEN_URL};
+
 /// Default `FatSecret` API host
 pub const DEFAULT_API_HOST: &str = "platform.fatsecret.com";
 
@@ -38,9 +40,16 @@ pub struct FatSecretConfig {
     /// The OAuth consumer secret from `FatSecret` developer account
     pub consumer_secret: String,
     /// Optional custom API host (defaults to platform.fatsecret.com)
+    ///
+    /// May include a scheme (e.g. `http://localhost:8080`), such as a local
+    /// stub server in tests.
     pub api_host: Option<String>,
     /// Optional custom authentication host (defaults to authentication.fatsecret.com)
     pub auth_host: Option<String>,
+    /// OAuth 2.0 scopes; when set, 2-legged requests use bearer auth instead of OAuth 1.0a
+    pub oauth2_scopes: Option<Vec<OAuth2Scope>>,
+    /// Optional custom OAuth 2.0 token URL (defaults to oauth.fatsecret.com)
+    pub oauth2_token_url: Option<String>,
 }
 
 impl FatSecretConfig {
@@ -66,6 +75,8 @@ impl FatSecretConfig {
             consumer_secret: secret,
             api_host: None,
             auth_host: None,
+            oauth2_scopes: None,
+            oauth2_token_url: None,
         })
     }
 
@@ -89,6 +100,11 @@ impl FatSecretConfig {
             consumer_secret,
             api_host: env::var("FATSECRET_API_HOST").ok(),
             auth_host: env::var("FATSECRET_AUTH_HOST").ok(),
+            oauth2_scopes: env::var("FATSECRET_OAUTH2_SCOPES")
+                .ok()
+                .map(|scopes| parse_scopes(&scopes))
+                .filter(|scopes| !scopes.is_empty()),
+            oauth2_token_url: env::var("FATSECRET_OAUTH2_TOKEN_URL").ok(),
         })
     }
 
@@ -112,9 +128,51 @@ impl FatSecretConfig {
         self.auth_host()
     }
 
+    /// Use OAuth 2.0 bearer auth with the given scopes for 2-legged requests
+    #[must_use]
+    pub fn with_oauth2_scopes(mut self, scopes: Vec<OAuth2Scope>) -> Self {
+        self.oauth2_scopes = Some(scopes);
+        self
+    }
+
+    /// Use a custom OAuth 2.0 token URL
+    #[must_use]
+    pub fn with_oauth2_token_url(mut self, url: impl Into<String>) -> Self {
+        self.oauth2_token_url = Some(url.into());
+        self
+    }
+
+    /// Configured OAuth 2.0 scopes plus `extra`, for endpoints that need a specific scope
+    pub fn oauth2_scopes_with(&self, extra: OAuth2Scope) -> Vec<OAuth2Scope> {
+        let mut scopes = self
+            .oauth2_scopes
+            .clone()
+            .unwrap_or_else(|| vec![OAuth2Scope::Basic]);
+        if !scopes.contains(&extra) {
+            scopes.push(extra);
+        }
+        scopes
+    }
+
+    /// Get the OAuth 2.0 token URL, using default if not configured
+    pub fn oauth2_token_url(&self) -> &str {
+        self.oauth2_token_url
+            .as_deref()
+            .unwrap_or(DEFAULT_TOKEN_URL)
+    }
+
     /// Get the full API URL
     pub fn api_url(&self) -> String {
-        format!("https://{}{}", self.api_host(), API_PATH)
+        format!("{}{}", host_base_url(self.api_host()), API_PATH)
+    }
+
+    /// Get the URL of a REST endpoint (e.g. `natural-language-processing/v1`)
+    pub fn rest_url(&self, path: &str) -> String {
+        format!(
+            "{}/rest/{}",
+            host_base_url(self.api_host()),
+            path.trim_start_matches('/')
+        )
     }
 
     /// Get the OAuth authorization URL
@@ -127,6 +185,15 @@ impl FatSecretConfig {
     }
 }
 
+/// Base URL for a host: `https://{host}` unless the host has its own scheme
+pub(crate) fn host_base_url(host: &str) -> String {
+    if host.starts_with("http://") || host.starts_with("https://") {
+        host.trim_end_matches('/').to_string()
+    } else {
+        format!("https://{host}")
+    }
+}
+
 fn validate_credential(credential: &str, is_key: bool) -> Result<(), ConfigError> {
     if credential.is_empty() {
         return Err(if is_key {
@@ -175,6 +242,22 @@ mod tests {
         assert_eq!(config.auth_host(), DEFAULT_AUTH_HOST);
     }
 
+    #[test]
+    fn test_oauth2_defaults_and_builder() {
+        let config = FatSecretConfig::new("1234567890123456", "1234567890123456").unwrap();
+        assert!(config.oauth2_scopes.is_none());
+        assert_eq!(config.oauth2_token_url(), DEFAULT_TOKEN_URL);
+
+        let config = config
+            .with_oauth2_scopes(vec![OAuth2Scope::Basic])
+            .with_oauth2_token_url("http://localhost:9000/connect/token");
+        assert_eq!(config.oauth2_scopes, Some(vec![OAuth2Scope::Basic]));
+        assert_eq!(
+            config.oauth2_token_url(),
+            "http://localhost:9000/connect/token"
+        );
+    }
+
     #[test]
     fn test_api_url() {
         let config = FatSecretConfig::new("1234567890123456", "1234567890123456").unwrap();
@@ -184,6 +267,27 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_rest_url() {
+        let config = FatSecretConfig::new("1234567890123456", "1234567890123456").unwrap();
+        assert_eq!(
+            config.rest_url("natural-language-processing/v1"),
+            "https://platform.fatsecret.com/rest/natural-language-processing/v1"
+        );
+
+        let mut local = config;
+        local.api_host = Some("http://127.0.0.1:8080/".to_string());
+        assert_eq!(
+            local.rest_url("/image-recognition/v2"),
+            "http://127.0.0.1:8080/rest/image-recognition/v2"
+        );
+        assert_eq!(local.api_url(), "http://127.0.0.1:8080/rest/server.api");
+        assert_eq!(
+            local.oauth2_scopes_with(OAuth2Scope::Nlp),
+            vec![OAuth2Scope::Basic, OAuth2Scope::Nlp]
+        );
+    }
+
     #[test]
     fn test_authorization_url() {
         let config = FatSecretConfig::new("1234567890123456", "1234567890123456").unwrap();
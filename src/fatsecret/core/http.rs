@@ -1,14 +1,19 @@
 //! `FatSecret` SDK HTTP client with OAuth signing
 //!
-//! All requests to the `FatSecret` API must be signed with OAuth 1.0a.
+//! User-delegated requests are signed with OAuth 1.0a. Public (2-legged)
+//! requests are signed the same way unless the config has OAuth 2.0 scopes,
+//! in which case they carry a client-credentials bearer token instead.
 //! This module handles signing and executing HTTP requests using reqwest.
 
 use reqwest::{Client, Method};
 use std::collections::HashMap;
+use std::future::Future;
 use std::time::Duration;
 
-use crate::fatsecret::core::errors::parse_error_response;
+use crate::fatsecret::core::config::{host_base_url, API_PATH};
+use crate::fatsecret::core::errors::{parse_error_response, ApiErrorCode};
 use crate::fatsecret::core::oauth::{build_oauth_params, oauth_encode};
+use crate::fatsecret::core::oauth2::{get_bearer_token, invalidate_bearer_token, OAuth2Scope};
 use crate::fatsecret::core::{AccessToken, FatSecretConfig, FatSecretError};
 
 /// Make signed OAuth request (2-legged or 3-legged)
@@ -25,7 +30,7 @@ pub async fn make_oauth_request(
     token: Option<&str>,
     token_secret: Option<&str>,
 ) -> Result<String, FatSecretError> {
-    let url = format!("https://{}{}", host, path);
+    let url = format!("{}{}", host_base_url(host), path);
 
     // Build OAuth parameters with signature
     let oauth_params = build_oauth_params(
@@ -89,6 +94,44 @@ pub async fn make_oauth_request(
     Ok(body)
 }
 
+/// Make request authorized with an OAuth 2.0 bearer token
+///
+/// Parameters are sent form-encoded in the POST body.
+pub async fn make_bearer_request(
+    host: &str,
+    path: &str,
+    params: &HashMap<String, String>,
+    bearer_token: &str,
+) -> Result<String, FatSecretError> {
+    let url = format!("{}{}", host_base_url(host), path);
+
+    // DOS prevention: Configure client with connection limits
+    let client = Client::builder()
+        .timeout(Duration::from_secs(30))
+        .pool_max_idle_per_host(3)
+        .pool_idle_timeout(Duration::from_secs(30))
+        .build()
+        .map_err(|e: reqwest::Error| FatSecretError::NetworkError(e.to_string()))?;
+    let response = client
+        .post(&url)
+        .bearer_auth(bearer_token)
+        .form(params)
+        .send()
+        .await?;
+
+    let status = response.status();
+    let body = response.text().await?;
+
+    if !status.is_success() {
+        return Err(FatSecretError::RequestFailed {
+            status: status.as_u16(),
+            body,
+        });
+    }
+
+    Ok(body)
+}
+
 /// Validate request body size against DOS limits
 fn validate_request_size(params: &HashMap<String, String>) -> Result<(), FatSecretError> {
     const MAX_REQUEST_SIZE: usize = 1024 * 1024; // 1MB for API requests
@@ -106,7 +149,8 @@ fn validate_request_size(params: &HashMap<String, String>) -> Result<(), FatSecr
 /// Make 2-legged API request (public data, no user token)
 ///
 /// This is used for API methods that don't require user authentication,
-/// such as `foods.search` or `food.get`.
+/// such as `foods.search` or `food.get`. Uses OAuth 2.0 bearer auth when
+/// `config.oauth2_scopes` is set, otherwise OAuth 1.0a signing.
 pub async fn make_api_request(
     config: &FatSecretConfig,
     method_name: &str,
@@ -119,6 +163,10 @@ pub async fn make_api_request(
     // DOS prevention: Validate request size before sending
     validate_request_size(&api_params)?;
 
+    if let Some(scopes) = &config.oauth2_scopes {
+        return make_bearer_api_request(config, scopes, &api_params).await;
+    }
+
     let body = make_oauth_request(
         config,
         Method::POST,
@@ -133,6 +181,108 @@ pub async fn make_api_request(
     check_api_error(body)
 }
 
+/// Send a 2-legged request with a cached bearer token
+async fn make_bearer_api_request(
+    config: &FatSecretConfig,
+    scopes: &[OAuth2Scope],
+    api_params: &HashMap<String, String>,
+) -> Result<String, FatSecretError> {
+    let host = config.api_host();
+    with_bearer_token(config, scopes, |token| async move {
+        make_bearer_request(host, API_PATH, api_params, &token).await
+    })
+    .await
+}
+
+/// POST a JSON body with a bearer token
+///
+/// Error bodies in the `FatSecret` error format are returned as `ApiError`
+/// whatever the HTTP status.
+pub async fn make_bearer_json_request(
+    url: &str,
+    body: &serde_json::Value,
+    bearer_token: &str,
+) -> Result<String, FatSecretError> {
+    // DOS prevention: Configure client with connection limits
+    let client = Client::builder()
+        .timeout(Duration::from_secs(60))
+        .pool_max_idle_per_host(3)
+        .pool_idle_timeout(Duration::from_secs(30))
+        .build()
+        .map_err(|e: reqwest::Error| FatSecretError::NetworkError(e.to_string()))?;
+    let response = client
+        .post(url)
+        .bearer_auth(bearer_token)
+        .json(body)
+        .send()
+        .await?;
+
+    let status = response.status();
+    let body = response.text().await?;
+
+    if !status.is_success() {
+        return Err(
+            parse_error_response(&body).unwrap_or(FatSecretError::RequestFailed {
+                status: status.as_u16(),
+                body,
+            }),
+        );
+    }
+
+    Ok(body)
+}
+
+/// Make a JSON request to a `FatSecret` REST endpoint (OAuth 2.0 only)
+///
+/// `path` is relative to `/rest/`, e.g. `natural-language-processing/v1`.
+/// Used by endpoints that have no `server.api` method, such as natural
+/// language processing and image recognition.
+pub async fn make_rest_request(
+    config: &FatSecretConfig,
+    scopes: &[OAuth2Scope],
+    path: &str,
+    body: &serde_json::Value,
+) -> Result<String, FatSecretError> {
+    let url = config.rest_url(path);
+    let url = url.as_str();
+    with_bearer_token(config, scopes, |token| async move {
+        make_bearer_json_request(url, body, &token).await
+    })
+    .await
+}
+
+/// Run a request with a cached bearer token
+///
+/// If the API rejects the token as invalid or expired, the cached token is
+/// dropped and the request is retried once with a fresh one.
+async fn with_bearer_token<F, Fut>(
+    config: &FatSecretConfig,
+    scopes: &[OAuth2Scope],
+    send: F,
+) -> Result<String, FatSecretError>
+where
+    F: Fn(String) -> Fut,
+    Fut: Future<Output = Result<String, FatSecretError>>,
+{
+    let token = get_bearer_token(config, scopes).await?;
+    match send(token.access_token).await.and_then(check_api_error) {
+        Err(e) if is_bearer_rejected(&e) => {
+            invalidate_bearer_token(config, scopes).await;
+            let token = get_bearer_token(config, scopes).await?;
+            send(token.access_token).await.and_then(check_api_error)
+        }
+        result => result,
+    }
+}
+
+/// True if the API rejected the bearer token itself
+fn is_bearer_rejected(error: &FatSecretError) -> bool {
+    matches!(
+        error.api_error_code(),
+        Some(ApiErrorCode::OAuth2InvalidToken | ApiErrorCode::OAuth2TokenExpired)
+    )
+}
+
 /// Make 3-legged API request (user data, requires access token)
 ///
 /// This is used for API methods that require user authentication,
@@ -1,14 +1,19 @@
 //! `FatSecret` Core module
 //!
-//! Contains configuration, error types, OAuth utilities, and HTTP client.
+//! Contains configuration, error types, OAuth 1.0a and OAuth 2.0 utilities, and HTTP client.
 
 pub mod config;
 pub mod errors;
 pub mod http;
 pub mod oauth;
+pub mod oauth2;
 pub mod serde_utils;
 
 pub use config::FatSecretConfig;
 pub use errors::{parse_error_response, ApiErrorCode, FatSecretError};
-pub use http::{make_api_request, make_authenticated_request, make_oauth_request};
+pub use http::{
+    make_api_request, make_authenticated_request, make_bearer_json_request, make_bearer_request,
+    make_oauth_request, make_rest_request,
+};
 pub use oauth::{AccessToken, RequestToken};
+pub use oauth2::{get_bearer_token, BearerToken, OAuth2Scope};
@@ -0,0 +1,368 @@
+//! OAuth 2.0 client-credentials authentication for `FatSecret` public endpoints
+//!
+//! `FatSecret` recommends OAuth 2.0 for application-only (2-legged) calls such as
+//! `foods.search` and `food.get`. The application exchanges its client ID and
+//! secret (the same consumer key/secret used for OAuth 1.0a) for a bearer token
+//! at `https://oauth.fatsecret.com/connect/token`. See docs/fatsecret/guides-oauth2.md.
+//!
+//! User-delegated (3-legged) calls still use OAuth 1.0a signing in [`super::oauth`].
+//!
+//! # Token Cache
+//!
+//! Tokens live for 24 hours. [`get_bearer_token`] keeps one token per client ID
+//! and scope set in a process-wide cache and fetches a new one
+//! [`REFRESH_MARGIN_SECS`] before the current one expires, so callers never
+//! send an expired token.
+//!
+//! # Enabling Bearer Auth
+//!
+//! Set scopes on the config (or `FATSECRET_OAUTH2_SCOPES="basic barcode"`) and
+//! [`make_api_request`](super::http::make_api_request) switches to bearer auth:
+//!
+//! ```rust,no_run
+//! use meal_planner::fatsecret::core::{FatSecretConfig, OAuth2Scope};
+//!
+//! # fn example() -> Result<(), Box<dyn std::error::Error>> {
+//! let config = FatSecretConfig::from_env()?
+//!     .with_oauth2_scopes(vec![OAuth2Scope::Basic, OAuth2Scope::Barcode]);
+//! # Ok(())
+//! # }
+//! ```
+
+use std::collections::HashMap;
+use std::time::Duration;
+
+use base64::Engine;
+use once_cell::sync::Lazy;
+use reqwest::Client;
+use serde::{Deserialize, Serialize};
+use tokio::sync::Mutex;
+
+use crate::fatsecret::core::oauth::unix_timestamp;
+use crate::fatsecret::core::{FatSecretConfig, FatSecretError};
+
+/// Default OAuth 2.0 token endpoint
+pub const DEFAULT_TOKEN_URL: &str = "https://oauth.fatsecret.com/connect/token";
+
+/// Refresh tokens this many seconds before they expire
+pub const REFRESH_MARGIN_SECS: u64 = 300;
+
+/// OAuth 2.0 scopes offered by the `FatSecret` Platform API
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum OAuth2Scope {
+    /// Basic food and nutrition data
+    Basic,
+    /// Premier features (requires subscription)
+    Premier,
+    /// Barcode lookup
+    Barcode,
+    /// Localized food data
+    Localization,
+    /// Natural language processing
+    Nlp,
+    /// Food image recognition
+    ImageRecognition,
+}
+
+impl OAuth2Scope {
+    /// Scope name as sent to the token endpoint
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Basic => "basic",
+            Self::Premier => "premier",
+            Self::Barcode => "barcode",
+            Self::Localization => "localization",
+            Self::Nlp => "nlp",
+            Self::ImageRecognition => "image-recognition",
+        }
+    }
+
+    /// Parse a scope name
+    pub fn parse(s: &str) -> Option<Self> {
+        match s.trim().to_lowercase().as_str() {
+            "basic" => Some(Self::Basic),
+            "premier" => Some(Self::Premier),
+            "barcode" => Some(Self::Barcode),
+            "localization" => Some(Self::Localization),
+            "nlp" => Some(Self::Nlp),
+            "image-recognition" | "image_recognition" => Some(Self::ImageRecognition),
+            _ => None,
+        }
+    }
+}
+
+/// Space-separated, sorted and deduplicated scope string
+pub fn scope_string(scopes: &[OAuth2Scope]) -> String {
+    let mut scopes = scopes.to_vec();
+    scopes.sort();
+    scopes.dedup();
+    scopes
+        .iter()
+        .map(|scope| scope.as_str())
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+/// Parse a space- or comma-separated scope list, skipping unknown names
+pub fn parse_scopes(s: &str) -> Vec<OAuth2Scope> {
+    s.split([' ', ','])
+        .filter(|part| !part.trim().is_empty())
+        .filter_map(OAuth2Scope::parse)
+        .collect()
+}
+
+/// OAuth 2.0 bearer token
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct BearerToken {
+    /// Token sent in the `Authorization: Bearer` header
+    pub access_token: String,
+    /// Scopes granted (space-separated)
+    pub scope: String,
+    /// Expiry as seconds since Unix epoch
+    pub expires_at: u64,
+}
+
+impl BearerToken {
+    /// True if the token expires within [`REFRESH_MARGIN_SECS`] of `now`
+    pub fn needs_refresh(&self, now: u64) -> bool {
+        now.saturating_add(REFRESH_MARGIN_SECS) >= self.expires_at
+    }
+}
+
+/// Token endpoint response
+#[derive(Debug, Deserialize)]
+struct TokenResponse {
+    access_token: String,
+    expires_in: u64,
+    #[serde(default)]
+    token_type: Option<String>,
+    #[serde(default)]
+    scope: Option<String>,
+}
+
+/// Parse a token endpoint response body, stamping expiry relative to `now`
+fn parse_token_response(
+    body: &str,
+    requested_scope: &str,
+    now: u64,
+) -> Result<BearerToken, FatSecretError> {
+    let response: TokenResponse = serde_json::from_str(body).map_err(|e| {
+        FatSecretError::ParseError(format!("Failed to parse OAuth 2.0 token response: {e}"))
+    })?;
+    if let Some(token_type) = response
+        .token_type
+        .as_deref()
+        .filter(|t| !t.eq_ignore_ascii_case("bearer"))
+    {
+        return Err(FatSecretError::OAuthError(format!(
+            "Unexpected token type: {token_type}"
+        )));
+    }
+    Ok(BearerToken {
+        access_token: response.access_token,
+        scope: response
+            .scope
+            .unwrap_or_else(|| requested_scope.to_string()),
+        expires_at: now.saturating_add(response.expires_in),
+    })
+}
+
+/// Request a new bearer token with the client-credentials grant
+///
+/// Always hits the token endpoint; use [`get_bearer_token`] to go through the cache.
+pub async fn fetch_bearer_token(
+    config: &FatSecretConfig,
+    scopes: &[OAuth2Scope],
+) -> Result<BearerToken, FatSecretError> {
+    let scope = scope_string(scopes);
+    let credentials = base64::engine::general_purpose::STANDARD.encode(format!(
+        "{}:{}",
+        config.consumer_key, config.consumer_secret
+    ));
+
+    let client = Client::builder()
+        .timeout(Duration::from_secs(30))
+        .build()
+        .map_err(|e| FatSecretError::NetworkError(e.to_string()))?;
+    let response = client
+        .post(config.oauth2_token_url())
+        .header("Authorization", format!("Basic {credentials}"))
+        .form(&[
+            ("grant_type", "client_credentials"),
+            ("scope", scope.as_str()),
+        ])
+        .send()
+        .await?;
+
+    let status = response.status();
+    let body = response.text().await?;
+    if !status.is_success() {
+        return Err(FatSecretError::RequestFailed {
+            status: status.as_u16(),
+            body,
+        });
+    }
+
+    parse_token_response(&body, &scope, unix_timestamp())
+}
+
+/// Token cache key: (token URL, client ID, scope string)
+type CacheKey = (String, String, String);
+
+/// Cached tokens, one per token URL, client ID and scope set
+static TOKEN_CACHE: Lazy<Mutex<HashMap<CacheKey, BearerToken>>> =
+    Lazy::new(|| Mutex::new(HashMap::new()));
+
+fn cache_key(config: &FatSecretConfig, scopes: &[OAuth2Scope]) -> CacheKey {
+    (
+        config.oauth2_token_url().to_string(),
+        config.consumer_key.clone(),
+        scope_string(scopes),
+    )
+}
+
+/// Get a valid bearer token, fetching a new one if none is cached or it is about to expire
+pub async fn get_bearer_token(
+    config: &FatSecretConfig,
+    scopes: &[OAuth2Scope],
+) -> Result<BearerToken, FatSecretError> {
+    let key = cache_key(config, scopes);
+    // Hold the lock while fetching so concurrent callers share one refresh
+    let mut cache = TOKEN_CACHE.lock().await;
+    if let Some(token) = cache
+        .get(&key)
+        .filter(|token| !token.needs_refresh(unix_timestamp()))
+    {
+        return Ok(token.clone());
+    }
+
+    let token = fetch_bearer_token(config, scopes).await?;
+    cache.insert(key, token.clone());
+    drop(cache);
+    Ok(token)
+}
+
+/// Drop a cached token (e.g. after the API reports it invalid or expired)
+pub async fn invalidate_bearer_token(config: &FatSecretConfig, scopes: &[OAuth2Scope]) {
+    TOKEN_CACHE.lock().await.remove(&cache_key(config, scopes));
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use wiremock::matchers::{body_string_contains, header, method, path};
+    use wiremock::{Mock, MockServer, ResponseTemplate};
+
+    fn config(server: &MockServer, key: &str) -> FatSecretConfig {
+        FatSecretConfig::new(key, "abcdefghijklmnopqrstuvwxyz")
+            .unwrap()
+            .with_oauth2_token_url(format!("{}/connect/token", server.uri()))
+    }
+
+    #[test]
+    fn test_scope_string_sorted_and_deduplicated() {
+        let scopes = [
+            OAuth2Scope::Barcode,
+            OAuth2Scope::Basic,
+            OAuth2Scope::Barcode,
+        ];
+        assert_eq!(scope_string(&scopes), "basic barcode");
+        assert_eq!(
+            parse_scopes("premier, basic image-recognition bogus"),
+            vec![
+                OAuth2Scope::Premier,
+                OAuth2Scope::Basic,
+                OAuth2Scope::ImageRecognition
+            ]
+        );
+    }
+
+    #[test]
+    fn test_needs_refresh_before_expiry() {
+        let token = BearerToken {
+            access_token: "t".to_string(),
+            scope: "basic".to_string(),
+            expires_at: 10_000,
+        };
+        assert!(!token.needs_refresh(10_000 - REFRESH_MARGIN_SECS - 1));
+        assert!(token.needs_refresh(10_000 - REFRESH_MARGIN_SECS));
+        assert!(token.needs_refresh(20_000));
+    }
+
+    #[test]
+    fn test_parse_token_response() {
+        let body = r#"{"access_token": "abc", "expires_in": 86400, "token_type": "Bearer", "scope": "basic"}"#;
+        let token = parse_token_response(body, "basic", 1_000).unwrap();
+        assert_eq!(token.access_token, "abc");
+        assert_eq!(token.expires_at, 87_400);
+
+        let mac = r#"{"access_token": "abc", "expires_in": 60, "token_type": "mac"}"#;
+        assert!(parse_token_response(mac, "basic", 0).is_err());
+        assert!(parse_token_response("not json", "basic", 0).is_err());
+    }
+
+    #[tokio::test]
+    async fn test_get_bearer_token_caches() {
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(path("/connect/token"))
+            .and(header(
+                "authorization",
+                "Basic Y2FjaGUta2V5LWFiY2RlZmdoaWo6YWJjZGVmZ2hpamtsbW5vcHFyc3R1dnd4eXo=",
+            ))
+            .and(body_string_contains("grant_type=client_credentials"))
+            .and(body_string_contains("scope=basic+barcode"))
+            .respond_with(ResponseTemplate::new(200).set_body_string(
+                r#"{"access_token": "token-1", "expires_in": 86400, "token_type": "Bearer", "scope": "basic barcode"}"#,
+            ))
+            .expect(1)
+            .mount(&server)
+            .await;
+
+        let config = config(&server, "cache-key-abcdefghij");
+        let scopes = [OAuth2Scope::Basic, OAuth2Scope::Barcode];
+        let first = get_bearer_token(&config, &scopes).await.unwrap();
+        let second = get_bearer_token(&config, &scopes).await.unwrap();
+        assert_eq!(first.access_token, "token-1");
+        assert_eq!(first, second);
+    }
+
+    #[tokio::test]
+    async fn test_refetches_expiring_token() {
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(path("/connect/token"))
+            .respond_with(ResponseTemplate::new(200).set_body_string(
+                r#"{"access_token": "short", "expires_in": 60, "token_type": "Bearer"}"#,
+            ))
+            .expect(2)
+            .mount(&server)
+            .await;
+
+        let config = config(&server, "refresh-key-abcdefghij");
+        let scopes = [OAuth2Scope::Basic];
+        get_bearer_token(&config, &scopes).await.unwrap();
+        // Expires inside the refresh margin, so the cache must not reuse it
+        get_bearer_token(&config, &scopes).await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_token_endpoint_error() {
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(path("/connect/token"))
+            .respond_with(
+                ResponseTemplate::new(400).set_body_string(r#"{"error": "invalid_client"}"#),
+            )
+            .mount(&server)
+            .await;
+
+        let config = config(&server, "error-key-abcdefghijk");
+        let result = fetch_bearer_token(&config, &[OAuth2Scope::Basic]).await;
+        assert!(matches!(
+            result,
+            Err(FatSecretError::RequestFailed { status: 400, .. })
+        ));
+    }
+}
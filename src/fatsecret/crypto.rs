@@ -10,7 +10,8 @@
 //! - **Key Management**: Requires 256-bit (32-byte) key from `OAUTH_ENCRYPTION_KEY` environment variable
 //! - **Nonce**: Uses cryptographically secure random 96-bit (12-byte) nonce per encryption
 //! - **Authentication**: GCM mode provides 128-bit authentication tag to detect tampering
-//! - **No Key Rotation**: Current implementation does not support key rotation
+//! - **Key Rotation**: Ciphertexts name the key that encrypted them; old keys stay
+//!   usable for decryption via `OAUTH_ENCRYPTION_OLD_KEYS` (see [Key Rotation](#key-rotation))
 //!
 //! ## Threat Model
 //!
@@ -86,17 +87,35 @@
 //!
 //! # Ciphertext Format
 //!
-//! Encrypted tokens are base64-encoded strings containing:
+//! Encrypted tokens are versioned strings naming the key that encrypted them:
 //!
 //! ```text
-//! [12-byte nonce][N-byte ciphertext][16-byte auth tag]
+//! v1:<key id>:base64([12-byte nonce][N-byte ciphertext][16-byte auth tag])
 //! ```
 //!
+//! - **Key ID**: First 8 hex characters of the SHA-256 of the key
 //! - **Nonce**: Random IV for this encryption operation (96 bits)
 //! - **Ciphertext**: AES-256-GCM encrypted plaintext
 //! - **Auth Tag**: GCM authentication tag (128 bits)
 //!
-//! Total overhead: 28 bytes + base64 encoding (≈37% size increase)
+//! Total overhead: 28 bytes + base64 encoding (≈37% size increase) + 12-byte prefix
+//!
+//! Ciphertexts written before key IDs existed are plain base64 without a
+//! prefix. They still decrypt: every configured key is tried in turn.
+//!
+//! # Key Rotation
+//!
+//! A [`KeyRing`] holds the current key, used for all encryption, and any
+//! number of decrypt-only old keys:
+//!
+//! ```bash
+//! export OAUTH_ENCRYPTION_KEY="new_64_character_hex_key"
+//! export OAUTH_ENCRYPTION_OLD_KEYS="previous_key,even_older_key"
+//! cargo run --bin rotate_encryption_key
+//! ```
+//!
+//! `rotate_encryption_key` re-encrypts every stored secret with the current
+//! key. Once it has run, the old keys can be removed.
 //!
 //! # Error Handling
 //!
@@ -107,6 +126,7 @@
 //! - `KeyInvalidHex`: Key contains non-hexadecimal characters
 //! - `InvalidCiphertext`: Malformed encrypted data
 //! - `DecryptionFailed`: Wrong key or tampered data
+//! - `UnknownKeyId`: Ciphertext names a key that is not configured
 //!
 //! # Performance
 //!
@@ -119,6 +139,7 @@
 //! - `aes-gcm`: AES-256-GCM implementation
 //! - `base64`: Encoding for storage
 //! - `hex`: Key parsing from environment
+//! - `ring`: SHA-256 for key IDs
 
 use aes_gcm::{
     aead::rand_core::RngCore,
@@ -126,7 +147,9 @@ use aes_gcm::{
     Aes256Gcm, Nonce,
 };
 use base64::{engine::general_purpose::STANDARD, Engine};
+use ring::digest::{digest, SHA256};
 use std::env;
+use std::fmt;
 use thiserror::Error;
 
 /// Encryption errors
@@ -147,6 +170,9 @@ pub enum CryptoError {
     /// Decryption failed due to wrong key or corrupted data
     #[error("Decryption failed (wrong key or corrupted data)")]
     DecryptionFailed,
+    /// Ciphertext was encrypted with a key that is not configured
+    #[error("No encryption key with id {0} is configured (add it to OAUTH_ENCRYPTION_OLD_KEYS)")]
+    UnknownKeyId(String),
 }
 
 /// Token storage errors
@@ -161,6 +187,9 @@ pub enum StorageError {
     /// Encryption or decryption operation failed
     #[error("Crypto error: {0}")]
     CryptoError(String),
+    /// User identifier is not acceptable
+    #[error("Invalid user: {0}")]
+    InvalidUser(String),
 }
 
 impl From<CryptoError> for StorageError {
@@ -181,6 +210,8 @@ pub enum TokenValidity {
         /// Number of days since the OAuth connection was established
         days_since_connected: i32,
     },
+    /// Token was rejected by FatSecret; the user must authorize again
+    Revoked,
 }
 
 /// Check if encryption is properly configured
@@ -192,7 +223,8 @@ pub fn encryption_configured() -> bool {
             | CryptoError::KeyInvalidLength(_)
             | CryptoError::KeyInvalidHex
             | CryptoError::InvalidCiphertext
-            | CryptoError::DecryptionFailed,
+            | CryptoError::DecryptionFailed
+            | CryptoError::UnknownKeyId(_),
         ) => false,
     }
 }
@@ -260,7 +292,9 @@ pub fn validate_encryption_at_startup() -> Result<(), CryptoError> {
                     eprintln!("Expected: 64 valid hex characters (0-9, a-f, A-F)");
                     eprintln!("To fix this: Generate a new key with: cargo run --bin generate_encryption_key");
                 }
-                CryptoError::InvalidCiphertext | CryptoError::DecryptionFailed => {
+                CryptoError::InvalidCiphertext
+                | CryptoError::DecryptionFailed
+                | CryptoError::UnknownKeyId(_) => {
                     eprintln!(
                         "SECURITY ERROR: Unexpected error validating encryption key: {}",
                         e
@@ -327,71 +361,248 @@ pub struct ValidationDetails {
 /// Expects a 64-character hex string (32 bytes for AES-256)
 fn get_encryption_key() -> Result<[u8; 32], CryptoError> {
     let key_str = env::var("OAUTH_ENCRYPTION_KEY").map_err(|_| CryptoError::KeyNotConfigured)?;
+    parse_key(&key_str)
+}
 
+/// Parse a 64-character hex key
+fn parse_key(key_str: &str) -> Result<[u8; 32], CryptoError> {
     if key_str.len() != 64 {
         return Err(CryptoError::KeyInvalidLength(key_str.len()));
     }
 
     let mut key = [0u8; 32];
-    hex::decode_to_slice(&key_str, &mut key).map_err(|_| CryptoError::KeyInvalidHex)?;
+    hex::decode_to_slice(key_str, &mut key).map_err(|_| CryptoError::KeyInvalidHex)?;
 
     Ok(key)
 }
 
-/// Encrypt plaintext using AES-256-GCM
-///
-/// Returns a base64-encoded string containing:
-/// - 12-byte nonce (IV)
-/// - Ciphertext
-/// - 16-byte authentication tag (GCM)
-///
-/// Format: [nonce (12 bytes)] || [ciphertext (N bytes)] || [tag (16 bytes)]
-pub fn encrypt(plaintext: &str) -> Result<String, CryptoError> {
-    let key_bytes = get_encryption_key()?;
-    let cipher = Aes256Gcm::new(&key_bytes.into());
+/// Version prefix of ciphertexts that carry a key ID
+const CIPHERTEXT_VERSION: &str = "v1";
 
-    // Generate nonce safely
-    let nonce = {
-        let mut rng = OsRng;
-        Aes256Gcm::generate_nonce(&mut rng)
-    };
+/// An AES-256 key together with its key ID
+#[derive(Clone)]
+pub struct EncryptionKey {
+    id: String,
+    bytes: [u8; 32],
+}
+
+impl EncryptionKey {
+    /// Parse a 64-character hex key
+    pub fn from_hex(key_str: &str) -> Result<Self, CryptoError> {
+        let bytes = parse_key(key_str)?;
+        let id = hex::encode(
+            digest(&SHA256, &bytes)
+                .as_ref()
+                .get(..4)
+                .unwrap_or_default(),
+        );
+        Ok(Self { id, bytes })
+    }
+
+    /// Key ID embedded in ciphertexts (first 8 hex chars of the key's SHA-256)
+    pub fn id(&self) -> &str {
+        &self.id
+    }
 
-    let ciphertext = cipher
-        .encrypt(&nonce, plaintext.as_bytes())
-        .map_err(|_| CryptoError::InvalidCiphertext)?;
+    fn encrypt(&self, plaintext: &str) -> Result<String, CryptoError> {
+        let cipher = Aes256Gcm::new(&self.bytes.into());
 
-    // Combine nonce + ciphertext and encode as base64
-    let mut combined = nonce.to_vec();
-    combined.extend_from_slice(&ciphertext);
+        // Generate nonce safely
+        let nonce = {
+            let mut rng = OsRng;
+            Aes256Gcm::generate_nonce(&mut rng)
+        };
 
-    Ok(STANDARD.encode(combined))
+        let ciphertext = cipher
+            .encrypt(&nonce, plaintext.as_bytes())
+            .map_err(|_| CryptoError::InvalidCiphertext)?;
+
+        // Combine nonce + ciphertext and encode as base64
+        let mut combined = nonce.to_vec();
+        combined.extend_from_slice(&ciphertext);
+
+        Ok(STANDARD.encode(combined))
+    }
+
+    fn decrypt(&self, encoded: &str) -> Result<String, CryptoError> {
+        let cipher = Aes256Gcm::new(&self.bytes.into());
+
+        // Decode base64
+        let combined = STANDARD
+            .decode(encoded)
+            .map_err(|_| CryptoError::InvalidCiphertext)?;
+
+        // Check minimum length (12-byte nonce + 16-byte GCM tag)
+        if combined.len() < 28 {
+            return Err(CryptoError::InvalidCiphertext);
+        }
+
+        // Split into nonce and ciphertext+tag
+        let (nonce_bytes, ciphertext) = combined.split_at(12);
+        let nonce = Nonce::from_slice(nonce_bytes);
+
+        cipher
+            .decrypt(nonce, ciphertext)
+            .map(|bytes| String::from_utf8(bytes).map_err(|_| CryptoError::DecryptionFailed))
+            .map_err(|_| CryptoError::DecryptionFailed)?
+    }
 }
 
-/// Decrypt base64-encoded ciphertext
+impl fmt::Debug for EncryptionKey {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("EncryptionKey")
+            .field("id", &self.id)
+            .finish_non_exhaustive()
+    }
+}
+
+/// Key ID named by a versioned ciphertext
 ///
-/// Expects format: [nonce (12 bytes)] || [ciphertext (N bytes)] || [tag (16 bytes)]
-pub fn decrypt(encrypted: &str) -> Result<String, CryptoError> {
-    let key_bytes = get_encryption_key()?;
-    let cipher = Aes256Gcm::new(&key_bytes.into());
+/// Returns `None` for ciphertexts written before key IDs existed.
+pub fn ciphertext_key_id(encrypted: &str) -> Option<&str> {
+    split_versioned(encrypted).map(|(key_id, _)| key_id)
+}
+
+/// Split `v1:<key id>:<base64>` into key ID and payload
+fn split_versioned(encrypted: &str) -> Option<(&str, &str)> {
+    let rest = encrypted
+        .strip_prefix(CIPHERTEXT_VERSION)?
+        .strip_prefix(':')?;
+    rest.split_once(':')
+}
+
+/// The current encryption key plus decrypt-only old keys
+///
+/// New ciphertexts are always written with the current key. Old keys are
+/// only used to read secrets that have not been re-encrypted yet.
+#[derive(Debug, Clone)]
+pub struct KeyRing {
+    current: EncryptionKey,
+    old: Vec<EncryptionKey>,
+}
+
+impl KeyRing {
+    /// Key ring with only a current key
+    pub fn new(current: EncryptionKey) -> Self {
+        Self {
+            current,
+            old: Vec::new(),
+        }
+    }
+
+    /// Add a decrypt-only old key
+    ///
+    /// Keys identical to one already in the ring are ignored.
+    #[must_use]
+    pub fn with_old_key(mut self, key: EncryptionKey) -> Self {
+        if self.keys().all(|existing| existing.bytes != key.bytes) {
+            self.old.push(key);
+        }
+        self
+    }
+
+    /// Load keys from the environment
+    ///
+    /// `OAUTH_ENCRYPTION_KEY` is the current key. `OAUTH_ENCRYPTION_OLD_KEYS`
+    /// optionally lists old keys, separated by commas or whitespace.
+    pub fn from_env() -> Result<Self, CryptoError> {
+        let key_str =
+            env::var("OAUTH_ENCRYPTION_KEY").map_err(|_| CryptoError::KeyNotConfigured)?;
+        let mut ring = Self::new(EncryptionKey::from_hex(&key_str)?);
+
+        if let Ok(old_keys) = env::var("OAUTH_ENCRYPTION_OLD_KEYS") {
+            for key_str in old_keys
+                .split(|c: char| c == ',' || c.is_whitespace())
+                .filter(|k| !k.is_empty())
+            {
+                ring = ring.with_old_key(EncryptionKey::from_hex(key_str)?);
+            }
+        }
+
+        Ok(ring)
+    }
+
+    /// ID of the key new ciphertexts are written with
+    pub fn current_key_id(&self) -> &str {
+        self.current.id()
+    }
 
-    // Decode base64
-    let combined = STANDARD
-        .decode(encrypted)
-        .map_err(|_| CryptoError::InvalidCiphertext)?;
+    /// IDs of the decrypt-only keys
+    pub fn old_key_ids(&self) -> Vec<&str> {
+        self.old.iter().map(EncryptionKey::id).collect()
+    }
+
+    fn keys(&self) -> impl Iterator<Item = &EncryptionKey> {
+        std::iter::once(&self.current).chain(&self.old)
+    }
+
+    /// Encrypt with the current key
+    pub fn encrypt(&self, plaintext: &str) -> Result<String, CryptoError> {
+        let payload = self.current.encrypt(plaintext)?;
+        Ok(format!(
+            "{CIPHERTEXT_VERSION}:{}:{payload}",
+            self.current.id
+        ))
+    }
+
+    /// Decrypt a ciphertext written with any key in the ring
+    ///
+    /// Unversioned ciphertexts are tried against every key, current first.
+    pub fn decrypt(&self, encrypted: &str) -> Result<String, CryptoError> {
+        if let Some((key_id, payload)) = split_versioned(encrypted) {
+            let key = self
+                .keys()
+                .find(|key| key.id == key_id)
+                .ok_or_else(|| CryptoError::UnknownKeyId(key_id.to_string()))?;
+            return key.decrypt(payload);
+        }
 
-    // Check minimum length (12-byte nonce + 16-byte GCM tag)
-    if combined.len() < 28 {
-        return Err(CryptoError::InvalidCiphertext);
+        let mut result = Err(CryptoError::DecryptionFailed);
+        for key in self.keys() {
+            result = key.decrypt(encrypted);
+            if matches!(result, Ok(_) | Err(CryptoError::InvalidCiphertext)) {
+                break;
+            }
+        }
+        result
+    }
+
+    /// Whether a ciphertext is already written with the current key
+    pub fn is_current(&self, encrypted: &str) -> bool {
+        ciphertext_key_id(encrypted) == Some(self.current.id())
+    }
+
+    /// Re-encrypt a ciphertext with the current key
+    ///
+    /// Returns `None` if it already uses the current key.
+    pub fn reencrypt(&self, encrypted: &str) -> Result<Option<String>, CryptoError> {
+        if self.is_current(encrypted) {
+            return Ok(None);
+        }
+        let plaintext = self.decrypt(encrypted)?;
+        self.encrypt(&plaintext).map(Some)
     }
+}
 
-    // Split into nonce and ciphertext+tag
-    let (nonce_bytes, ciphertext) = combined.split_at(12);
-    let nonce = Nonce::from_slice(nonce_bytes);
+/// Encrypt plaintext using AES-256-GCM with the current key
+///
+/// Returns `v1:<key id>:` followed by base64 of:
+/// - 12-byte nonce (IV)
+/// - Ciphertext
+/// - 16-byte authentication tag (GCM)
+///
+/// Keys are loaded with [`KeyRing::from_env`].
+pub fn encrypt(plaintext: &str) -> Result<String, CryptoError> {
+    KeyRing::from_env()?.encrypt(plaintext)
+}
 
-    cipher
-        .decrypt(nonce, ciphertext)
-        .map(|bytes| String::from_utf8(bytes).map_err(|_| CryptoError::DecryptionFailed))
-        .map_err(|_| CryptoError::DecryptionFailed)?
+/// Decrypt a ciphertext produced by [`encrypt`]
+///
+/// Accepts both versioned ciphertexts and the unversioned
+/// `base64([nonce] || [ciphertext] || [tag])` format. Keys are loaded with
+/// [`KeyRing::from_env`].
+pub fn decrypt(encrypted: &str) -> Result<String, CryptoError> {
+    KeyRing::from_env()?.decrypt(encrypted)
 }
 
 /// Generate a random 32-byte key encoded as 64 hex characters
@@ -536,11 +747,107 @@ mod tests {
 
         env::set_var("OAUTH_ENCRYPTION_KEY", key2);
         let result = decrypt(&encrypted);
+        assert!(matches!(result, Err(CryptoError::UnknownKeyId(_))));
+
+        // Without a key ID every key is tried; none of them fits
+        let legacy = encrypted.rsplit(':').next().unwrap();
+        let result = decrypt(legacy);
         assert!(matches!(result, Err(CryptoError::DecryptionFailed)));
 
         env::remove_var("OAUTH_ENCRYPTION_KEY");
     }
 
+    const OLD_KEY: &str = "0123456789abcdef0123456789abcdef0123456789abcdef0123456789abcdef";
+    const NEW_KEY: &str = "ffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffff";
+
+    fn ring(current: &str) -> KeyRing {
+        KeyRing::new(EncryptionKey::from_hex(current).unwrap())
+    }
+
+    #[test]
+    fn test_ciphertext_names_its_key() {
+        let keys = ring(OLD_KEY);
+        let encrypted = keys.encrypt("secret").unwrap();
+
+        assert_eq!(keys.current_key_id().len(), 8);
+        assert!(encrypted.starts_with(&format!("v1:{}:", keys.current_key_id())));
+        assert_eq!(ciphertext_key_id(&encrypted), Some(keys.current_key_id()));
+        assert_ne!(keys.current_key_id(), ring(NEW_KEY).current_key_id());
+    }
+
+    #[test]
+    fn test_key_ring_decrypts_with_old_key() {
+        let encrypted = ring(OLD_KEY).encrypt("secret").unwrap();
+
+        let rotated = ring(NEW_KEY).with_old_key(EncryptionKey::from_hex(OLD_KEY).unwrap());
+        assert_eq!(rotated.decrypt(&encrypted).unwrap(), "secret");
+        assert!(!rotated.is_current(&encrypted));
+
+        assert!(matches!(
+            ring(NEW_KEY).decrypt(&encrypted),
+            Err(CryptoError::UnknownKeyId(id)) if id == ring(OLD_KEY).current_key_id()
+        ));
+    }
+
+    #[test]
+    fn test_key_ring_decrypts_unversioned_ciphertext() {
+        let legacy = EncryptionKey::from_hex(OLD_KEY)
+            .unwrap()
+            .encrypt("secret")
+            .unwrap();
+        assert_eq!(ciphertext_key_id(&legacy), None);
+
+        assert_eq!(ring(OLD_KEY).decrypt(&legacy).unwrap(), "secret");
+        let rotated = ring(NEW_KEY).with_old_key(EncryptionKey::from_hex(OLD_KEY).unwrap());
+        assert_eq!(rotated.decrypt(&legacy).unwrap(), "secret");
+        assert!(matches!(
+            ring(NEW_KEY).decrypt(&legacy),
+            Err(CryptoError::DecryptionFailed)
+        ));
+    }
+
+    #[test]
+    fn test_reencrypt_moves_to_current_key() {
+        let rotated = ring(NEW_KEY).with_old_key(EncryptionKey::from_hex(OLD_KEY).unwrap());
+        let encrypted = ring(OLD_KEY).encrypt("secret").unwrap();
+
+        let reencrypted = rotated.reencrypt(&encrypted).unwrap().unwrap();
+        assert!(rotated.is_current(&reencrypted));
+        assert_eq!(ring(NEW_KEY).decrypt(&reencrypted).unwrap(), "secret");
+        assert_eq!(rotated.reencrypt(&reencrypted).unwrap(), None);
+    }
+
+    #[test]
+    fn test_key_ring_from_env_reads_old_keys() {
+        let _lock = CRYPTO_TEST_LOCK.lock().unwrap();
+
+        env::set_var("OAUTH_ENCRYPTION_KEY", NEW_KEY);
+        env::set_var(
+            "OAUTH_ENCRYPTION_OLD_KEYS",
+            format!("{OLD_KEY}, {NEW_KEY},"),
+        );
+        let keys = KeyRing::from_env().unwrap();
+        assert_eq!(keys.current_key_id(), ring(NEW_KEY).current_key_id());
+        assert_eq!(keys.old_key_ids(), vec![ring(OLD_KEY).current_key_id()]);
+
+        env::set_var("OAUTH_ENCRYPTION_OLD_KEYS", "abc");
+        assert!(matches!(
+            KeyRing::from_env(),
+            Err(CryptoError::KeyInvalidLength(3))
+        ));
+
+        env::remove_var("OAUTH_ENCRYPTION_OLD_KEYS");
+        env::remove_var("OAUTH_ENCRYPTION_KEY");
+    }
+
+    #[test]
+    fn test_encryption_key_debug_hides_key() {
+        let key = EncryptionKey::from_hex(OLD_KEY).unwrap();
+        let debug = format!("{key:?}");
+        assert!(debug.contains(key.id()));
+        assert!(!debug.contains("bytes"));
+    }
+
     #[test]
     fn test_encryption_configured() {
         let _lock = CRYPTO_TEST_LOCK.lock().unwrap();
@@ -642,6 +949,7 @@ mod tests {
     #[case::key_invalid_hex(CryptoError::KeyInvalidHex, "valid hex")]
     #[case::invalid_ciphertext(CryptoError::InvalidCiphertext, "invalid or corrupted")]
     #[case::decryption_failed(CryptoError::DecryptionFailed, "wrong key")]
+    #[case::unknown_key_id(CryptoError::UnknownKeyId("1a2b3c4d".to_string()), "1a2b3c4d")]
     fn test_crypto_error_display(#[case] error: CryptoError, #[case] expected_substring: &str) {
         let display = error.to_string();
         assert!(
@@ -659,6 +967,7 @@ mod tests {
     #[case::not_found(StorageError::NotFound, "not found")]
     #[case::database_error(StorageError::DatabaseError("connection failed".to_string()), "connection failed")]
     #[case::crypto_error(StorageError::CryptoError("bad key".to_string()), "bad key")]
+    #[case::invalid_user(StorageError::InvalidUser("too long".to_string()), "invalid user")]
     fn test_storage_error_display(#[case] error: StorageError, #[case] expected_substring: &str) {
         let display = error.to_string();
         assert!(
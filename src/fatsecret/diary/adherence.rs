@@ -0,0 +1,786 @@
+//! Planned vs eaten nutrition adherence reports (FUNCTIONAL CORE - PURE)
+//!
+//! Compares what Tandoor planned for a day with what the `FatSecret` diary
+//! says was actually eaten, together with exercise calories and the profile's
+//! calorie goal. The report binary fetches the data; everything here is pure.
+//!
+//! - Calories in: diary food entries (or the month summary in week mode)
+//! - Calories out: exercise diary calories
+//! - Net: calories in minus calories out, compared with the profile goal
+//! - Macro split: share of energy from carbs/protein/fat (4/4/9 kcal per gram)
+//!   against a target split
+//!
+//! Planned nutrition comes from [`plan_diary_entries`](super::sync::plan_diary_entries),
+//! so plans are interpreted exactly as the diary sync would log them.
+
+use serde::Serialize;
+
+use super::sync::{PlannedDiaryEntry, SkippedMealPlan};
+use super::types::{int_to_date, DaySummary, FoodEntry, FoodEntryInput, MealType};
+use crate::fatsecret::exercise::ExerciseDaySummary;
+
+/// Energy per gram of carbohydrate and protein (kcal)
+const KCAL_PER_GRAM_CARB_PROTEIN: f64 = 4.0;
+
+/// Energy per gram of fat (kcal)
+const KCAL_PER_GRAM_FAT: f64 = 9.0;
+
+/// A day counts as on target when net calories are within this share of the goal
+pub const ON_TARGET_TOLERANCE: f64 = 0.1;
+
+/// Default macro target (percent of energy)
+pub const DEFAULT_MACRO_TARGET: MacroSplit = MacroSplit {
+    carbohydrate_pct: 50.0,
+    protein_pct: 20.0,
+    fat_pct: 30.0,
+};
+
+/// Calories and macronutrients (grams)
+#[derive(Debug, Clone, Copy, PartialEq, Default, Serialize)]
+pub struct NutritionTotals {
+    /// Calories (kcal)
+    pub calories: f64,
+    /// Carbohydrates (g)
+    pub carbohydrate: f64,
+    /// Protein (g)
+    pub protein: f64,
+    /// Fat (g)
+    pub fat: f64,
+}
+
+impl NutritionTotals {
+    /// Add another set of totals to this one
+    pub fn add(&mut self, other: Self) {
+        self.calories += other.calories;
+        self.carbohydrate += other.carbohydrate;
+        self.protein += other.protein;
+        self.fat += other.fat;
+    }
+
+    /// Share of macronutrient energy from carbs, protein and fat
+    pub fn macro_split(&self) -> MacroSplit {
+        let carbohydrate = self.carbohydrate * KCAL_PER_GRAM_CARB_PROTEIN;
+        let protein = self.protein * KCAL_PER_GRAM_CARB_PROTEIN;
+        let fat = self.fat * KCAL_PER_GRAM_FAT;
+        let total = carbohydrate + protein + fat;
+        if total <= 0.0 {
+            return MacroSplit::default();
+        }
+        MacroSplit {
+            carbohydrate_pct: round1(carbohydrate / total * 100.0),
+            protein_pct: round1(protein / total * 100.0),
+            fat_pct: round1(fat / total * 100.0),
+        }
+    }
+}
+
+/// Macronutrient split as percent of energy
+#[derive(Debug, Clone, Copy, PartialEq, Default, Serialize, serde::Deserialize)]
+pub struct MacroSplit {
+    /// Carbohydrates (% of energy)
+    pub carbohydrate_pct: f64,
+    /// Protein (% of energy)
+    pub protein_pct: f64,
+    /// Fat (% of energy)
+    pub fat_pct: f64,
+}
+
+impl MacroSplit {
+    /// Percentage-point difference from a target split
+    #[must_use]
+    pub fn minus(&self, target: &Self) -> Self {
+        Self {
+            carbohydrate_pct: round1(self.carbohydrate_pct - target.carbohydrate_pct),
+            protein_pct: round1(self.protein_pct - target.protein_pct),
+            fat_pct: round1(self.fat_pct - target.fat_pct),
+        }
+    }
+}
+
+/// A planned meal on the report day
+#[derive(Debug, Clone, Serialize)]
+pub struct PlannedMeal {
+    /// Tandoor meal plan ID
+    pub meal_plan_id: i64,
+    /// Recipe name
+    pub name: String,
+    /// `FatSecret` meal it maps to
+    pub meal: MealType,
+    /// Planned servings
+    pub servings: f64,
+    /// Planned calories (all servings)
+    pub calories: f64,
+}
+
+/// Planned vs eaten report for a single day
+#[derive(Debug, Clone, Serialize)]
+pub struct DayAdherence {
+    /// Report date (YYYY-MM-DD)
+    pub date: String,
+    /// Nutrition of the planned meals
+    pub planned: NutritionTotals,
+    /// Nutrition logged in the diary
+    pub eaten: NutritionTotals,
+    /// Calories burned (exercise diary)
+    pub calories_out: f64,
+    /// Calories eaten minus calories burned
+    pub net_calories: f64,
+    /// Daily calorie goal from the profile
+    pub calorie_goal: Option<f64>,
+    /// Net calories minus the goal (positive = over)
+    pub goal_delta: Option<f64>,
+    /// Calories eaten minus calories planned (positive = ate more than planned)
+    pub plan_delta: f64,
+    /// Actual macro split
+    pub macro_split: MacroSplit,
+    /// Target macro split
+    pub macro_target: MacroSplit,
+    /// Actual minus target (percentage points)
+    pub macro_delta: MacroSplit,
+    /// Meals planned for the day
+    pub planned_meals: Vec<PlannedMeal>,
+    /// Plans whose nutrition could not be determined
+    pub missing_nutrition: Vec<SkippedMealPlan>,
+}
+
+/// Inputs for [`day_report`]
+#[derive(Debug, Clone, Copy)]
+pub struct DayData<'a> {
+    /// Report day (date_int)
+    pub date_int: i32,
+    /// Planned diary entries (any dates - filtered to the report day)
+    pub planned: &'a [PlannedDiaryEntry],
+    /// Meal plans without usable nutrition
+    pub skipped: &'a [SkippedMealPlan],
+    /// Diary food entries for the day
+    pub food_entries: &'a [FoodEntry],
+    /// Calories burned on the day
+    pub calories_out: f64,
+    /// Daily calorie goal
+    pub calorie_goal: Option<f64>,
+    /// Target macro split
+    pub macro_target: MacroSplit,
+}
+
+/// One day in a week report
+#[derive(Debug, Clone, Serialize)]
+pub struct WeekDay {
+    /// Date (YYYY-MM-DD)
+    pub date: String,
+    /// Calories planned
+    pub planned_calories: f64,
+    /// Nutrition logged in the diary
+    pub eaten: NutritionTotals,
+    /// Calories burned
+    pub calories_out: f64,
+    /// Calories eaten minus calories burned
+    pub net_calories: f64,
+    /// Net calories minus the goal
+    pub goal_delta: Option<f64>,
+    /// True when net calories are within [`ON_TARGET_TOLERANCE`] of the goal
+    pub on_target: Option<bool>,
+}
+
+/// Planned vs eaten report for a range of days
+#[derive(Debug, Clone, Serialize)]
+pub struct WeekAdherence {
+    /// First day (YYYY-MM-DD)
+    pub from_date: String,
+    /// Last day (YYYY-MM-DD)
+    pub to_date: String,
+    /// Daily calorie goal
+    pub calorie_goal: Option<f64>,
+    /// Per-day rows
+    pub days: Vec<WeekDay>,
+    /// Planned calories over the range
+    pub planned_calories: f64,
+    /// Eaten nutrition over the range
+    pub eaten: NutritionTotals,
+    /// Calories burned over the range
+    pub calories_out: f64,
+    /// Average daily net calories
+    pub average_net_calories: f64,
+    /// Average daily difference from the goal
+    pub average_goal_delta: Option<f64>,
+    /// Number of days within [`ON_TARGET_TOLERANCE`] of the goal
+    pub days_on_target: usize,
+    /// Macro split over the range
+    pub macro_split: MacroSplit,
+    /// Target macro split
+    pub macro_target: MacroSplit,
+    /// Actual minus target (percentage points)
+    pub macro_delta: MacroSplit,
+}
+
+/// Inputs for [`week_report`]
+#[derive(Debug, Clone, Copy)]
+pub struct WeekData<'a> {
+    /// First day (date_int)
+    pub from_date_int: i32,
+    /// Last day (date_int, inclusive)
+    pub to_date_int: i32,
+    /// Planned diary entries for the range
+    pub planned: &'a [PlannedDiaryEntry],
+    /// Diary month summary days (any months - filtered to the range)
+    pub diary_days: &'a [DaySummary],
+    /// Exercise month summary days (any months - filtered to the range)
+    pub exercise_days: &'a [ExerciseDaySummary],
+    /// Daily calorie goal
+    pub calorie_goal: Option<f64>,
+    /// Target macro split
+    pub macro_target: MacroSplit,
+}
+
+// ============================================================================
+// Calculations
+// ============================================================================
+
+/// Round to one decimal place
+fn round1(value: f64) -> f64 {
+    (value * 10.0).round() / 10.0
+}
+
+/// Nutrition of a planned entry (all servings); database foods carry none
+fn entry_nutrition(entry: &FoodEntryInput) -> NutritionTotals {
+    if let FoodEntryInput::Custom {
+        number_of_units,
+        calories,
+        carbohydrate,
+        protein,
+        fat,
+        ..
+    } = entry
+    {
+        NutritionTotals {
+            calories: calories * number_of_units,
+            carbohydrate: carbohydrate * number_of_units,
+            protein: protein * number_of_units,
+            fat: fat * number_of_units,
+        }
+    } else {
+        NutritionTotals::default()
+    }
+}
+
+/// Planned nutrition on one day
+fn planned_on(planned: &[PlannedDiaryEntry], date_int: i32) -> NutritionTotals {
+    let mut totals = NutritionTotals::default();
+    for entry in planned.iter().filter(|p| p.entry.date_int() == date_int) {
+        totals.add(entry_nutrition(&entry.entry));
+    }
+    totals
+}
+
+/// Sum diary food entries
+pub fn eaten_totals(entries: &[FoodEntry]) -> NutritionTotals {
+    let mut totals = NutritionTotals::default();
+    for entry in entries {
+        totals.add(NutritionTotals {
+            calories: entry.calories,
+            carbohydrate: entry.carbohydrate,
+            protein: entry.protein,
+            fat: entry.fat,
+        });
+    }
+    totals
+}
+
+/// Net calories minus goal, if a goal is set
+fn goal_delta(net_calories: f64, calorie_goal: Option<f64>) -> Option<f64> {
+    let goal = calorie_goal?;
+    Some(round1(net_calories - goal))
+}
+
+/// Whether a goal delta is within tolerance of the goal
+fn on_target(delta: Option<f64>, calorie_goal: Option<f64>) -> Option<bool> {
+    Some(delta?.abs() <= calorie_goal? * ON_TARGET_TOLERANCE)
+}
+
+/// Planned meals for the report day
+fn planned_meals(planned: &[PlannedDiaryEntry], date_int: i32) -> Vec<PlannedMeal> {
+    planned
+        .iter()
+        .filter(|p| p.entry.date_int() == date_int)
+        .map(|p| {
+            let (meal, servings) = match &p.entry {
+                FoodEntryInput::Custom {
+                    meal,
+                    number_of_units,
+                    ..
+                }
+                | FoodEntryInput::FromFood {
+                    meal,
+                    number_of_units,
+                    ..
+                } => (*meal, *number_of_units),
+            };
+            PlannedMeal {
+                meal_plan_id: p.meal_plan_id,
+                name: p.entry.food_entry_name().to_string(),
+                meal,
+                servings,
+                calories: round1(entry_nutrition(&p.entry).calories),
+            }
+        })
+        .collect()
+}
+
+/// Build the planned vs eaten report for one day
+pub fn day_report(data: &DayData<'_>) -> Result<DayAdherence, String> {
+    let planned = planned_on(data.planned, data.date_int);
+    let eaten = eaten_totals(data.food_entries);
+    let net_calories = round1(eaten.calories - data.calories_out);
+    let macro_split = eaten.macro_split();
+
+    Ok(DayAdherence {
+        date: int_to_date(data.date_int)?,
+        planned,
+        eaten,
+        calories_out: data.calories_out,
+        net_calories,
+        calorie_goal: data.calorie_goal,
+        goal_delta: goal_delta(net_calories, data.calorie_goal),
+        plan_delta: round1(eaten.calories - planned.calories),
+        macro_split,
+        macro_target: data.macro_target,
+        macro_delta: macro_split.minus(&data.macro_target),
+        planned_meals: planned_meals(data.planned, data.date_int),
+        missing_nutrition: data.skipped.to_vec(),
+    })
+}
+
+/// Build one row of the week report
+fn week_day(data: &WeekData<'_>, date_int: i32) -> Result<WeekDay, String> {
+    let eaten = data
+        .diary_days
+        .iter()
+        .find(|d| d.date_int == date_int)
+        .map_or_else(NutritionTotals::default, |d| NutritionTotals {
+            calories: d.calories,
+            carbohydrate: d.carbohydrate,
+            protein: d.protein,
+            fat: d.fat,
+        });
+    let calories_out = data
+        .exercise_days
+        .iter()
+        .find(|d| d.date_int == date_int)
+        .map_or(0.0, |d| d.exercise_calories);
+    let net_calories = round1(eaten.calories - calories_out);
+    let delta = goal_delta(net_calories, data.calorie_goal);
+
+    Ok(WeekDay {
+        date: int_to_date(date_int)?,
+        planned_calories: round1(planned_on(data.planned, date_int).calories),
+        eaten,
+        calories_out,
+        net_calories,
+        goal_delta: delta,
+        on_target: on_target(delta, data.calorie_goal),
+    })
+}
+
+/// Build the planned vs eaten report for a range of days (month summaries)
+pub fn week_report(data: &WeekData<'_>) -> Result<WeekAdherence, String> {
+    let days = (data.from_date_int..=data.to_date_int)
+        .map(|date_int| week_day(data, date_int))
+        .collect::<Result<Vec<_>, _>>()?;
+
+    let mut eaten = NutritionTotals::default();
+    for day in &days {
+        eaten.add(day.eaten);
+    }
+    let count = f64::from(u32::try_from(days.len().max(1)).unwrap_or(u32::MAX));
+    let sum = |f: fn(&WeekDay) -> f64| days.iter().map(f).sum::<f64>();
+    let average_net_calories = round1(sum(|d| d.net_calories) / count);
+    let macro_split = eaten.macro_split();
+
+    Ok(WeekAdherence {
+        from_date: int_to_date(data.from_date_int)?,
+        to_date: int_to_date(data.to_date_int)?,
+        calorie_goal: data.calorie_goal,
+        planned_calories: round1(sum(|d| d.planned_calories)),
+        calories_out: round1(sum(|d| d.calories_out)),
+        average_goal_delta: goal_delta(average_net_calories, data.calorie_goal),
+        days_on_target: days.iter().filter(|d| d.on_target == Some(true)).count(),
+        average_net_calories,
+        eaten,
+        macro_split,
+        macro_target: data.macro_target,
+        macro_delta: macro_split.minus(&data.macro_target),
+        days,
+    })
+}
+
+/// A calendar month touched by a report range
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RangeMonth {
+    /// First day of the month inside the range (date_int)
+    pub date_int: i32,
+    /// Four-digit year
+    pub year: i32,
+    /// Month number (1-12)
+    pub month: i32,
+}
+
+/// Calendar months touched by `[from_date_int, to_date_int]`
+///
+/// Diary month summaries are keyed by any day in the month and exercise
+/// summaries by year/month, so this is the set of requests covering a range.
+pub fn months_in_range(from_date_int: i32, to_date_int: i32) -> Result<Vec<RangeMonth>, String> {
+    let mut months: Vec<RangeMonth> = Vec::new();
+    for date_int in from_date_int..=to_date_int {
+        let date = int_to_date(date_int)?;
+        let part = |range: std::ops::Range<usize>| {
+            date.get(range)
+                .and_then(|p| p.parse::<i32>().ok())
+                .ok_or_else(|| format!("Invalid date: {date}"))
+        };
+        let (year, month) = (part(0..4)?, part(5..7)?);
+        if !matches!(months.last(), Some(last) if (last.year, last.month) == (year, month)) {
+            months.push(RangeMonth {
+                date_int,
+                year,
+                month,
+            });
+        }
+    }
+    Ok(months)
+}
+
+// ============================================================================
+// Markdown rendering
+// ============================================================================
+
+/// Format a signed difference ("+120", "-35", "0")
+fn signed(value: f64) -> String {
+    if value > 0.0 {
+        format!("+{value:.0}")
+    } else {
+        format!("{value:.0}")
+    }
+}
+
+/// Format an optional value, "–" when missing
+fn optional(value: Option<f64>, format: fn(f64) -> String) -> String {
+    value.map_or_else(|| "–".to_string(), format)
+}
+
+/// Macro split table rows
+fn macro_rows(actual: &MacroSplit, target: &MacroSplit, delta: &MacroSplit) -> String {
+    [
+        (
+            "Carbs",
+            actual.carbohydrate_pct,
+            target.carbohydrate_pct,
+            delta.carbohydrate_pct,
+        ),
+        (
+            "Protein",
+            actual.protein_pct,
+            target.protein_pct,
+            delta.protein_pct,
+        ),
+        ("Fat", actual.fat_pct, target.fat_pct, delta.fat_pct),
+    ]
+    .iter()
+    .map(|(name, a, t, d)| format!("| {name} | {a:.0}% | {t:.0}% | {} |\n", signed(*d)))
+    .collect::<Vec<_>>()
+    .concat()
+}
+
+/// Planned vs eaten nutrition table
+fn nutrition_table(planned: &NutritionTotals, eaten: &NutritionTotals) -> String {
+    let header = "| | Planned | Eaten | Difference |\n|---|---:|---:|---:|\n".to_string();
+    let rows = [
+        ("Calories (kcal)", planned.calories, eaten.calories),
+        ("Carbs (g)", planned.carbohydrate, eaten.carbohydrate),
+        ("Protein (g)", planned.protein, eaten.protein),
+        ("Fat (g)", planned.fat, eaten.fat),
+    ]
+    .iter()
+    .map(|(name, p, e)| format!("| {name} | {p:.0} | {e:.0} | {} |\n", signed(e - p)))
+    .collect::<Vec<_>>();
+    [vec![header], rows].concat().concat()
+}
+
+/// Render a day report as Markdown
+pub fn day_markdown(report: &DayAdherence) -> String {
+    let summary = format!(
+        "**Calories out:** {:.0} kcal · **Net:** {:.0} kcal · **Goal:** {} ({})\n",
+        report.calories_out,
+        report.net_calories,
+        optional(report.calorie_goal, |g| format!("{g:.0} kcal")),
+        optional(report.goal_delta, signed),
+    );
+    let meals = report
+        .planned_meals
+        .iter()
+        .map(|m| {
+            format!(
+                "- {}: {} × {} ({:.0} kcal)\n",
+                m.meal.to_api_string(),
+                m.name,
+                m.servings,
+                m.calories
+            )
+        })
+        .collect::<Vec<_>>()
+        .concat();
+    let missing = report
+        .missing_nutrition
+        .iter()
+        .map(|s| format!("- Meal plan {}: {}\n", s.meal_plan_id, s.reason))
+        .collect::<Vec<_>>()
+        .concat();
+
+    let mut sections = vec![
+        format!("# Nutrition adherence: {}\n\n", report.date),
+        nutrition_table(&report.planned, &report.eaten),
+        format!("\n{summary}\n## Macro split\n\n| Macro | Actual | Target | Difference |\n|---|---:|---:|---:|\n"),
+        macro_rows(&report.macro_split, &report.macro_target, &report.macro_delta),
+    ];
+    if !meals.is_empty() {
+        sections.push(format!("\n## Planned meals\n\n{meals}"));
+    }
+    if !missing.is_empty() {
+        sections.push(format!("\n## Missing nutrition\n\n{missing}"));
+    }
+    sections.concat()
+}
+
+/// Render a week report as Markdown
+pub fn week_markdown(report: &WeekAdherence) -> String {
+    let rows = report
+        .days
+        .iter()
+        .map(|d| {
+            format!(
+                "| {} | {:.0} | {:.0} | {:.0} | {:.0} | {} |\n",
+                d.date,
+                d.planned_calories,
+                d.eaten.calories,
+                d.calories_out,
+                d.net_calories,
+                optional(d.goal_delta, signed),
+            )
+        })
+        .collect::<Vec<_>>()
+        .concat();
+    let summary = format!(
+        "**Average net:** {:.0} kcal · **Goal:** {} ({}) · **Days on target:** {}/{}\n",
+        report.average_net_calories,
+        optional(report.calorie_goal, |g| format!("{g:.0} kcal")),
+        optional(report.average_goal_delta, signed),
+        report.days_on_target,
+        report.days.len(),
+    );
+
+    [
+        format!(
+            "# Nutrition adherence: {} to {}\n\n",
+            report.from_date, report.to_date
+        ),
+        "| Date | Planned | Eaten | Out | Net | vs Goal |\n|---|---:|---:|---:|---:|---:|\n"
+            .to_string(),
+        rows,
+        format!("\n{summary}\n## Macro split\n\n| Macro | Actual | Target | Difference |\n|---|---:|---:|---:|\n"),
+        macro_rows(&report.macro_split, &report.macro_target, &report.macro_delta),
+    ]
+    .concat()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    /// 2024-01-15
+    const DAY: i32 = 19737;
+
+    fn planned(
+        meal_plan_id: i64,
+        date_int: i32,
+        servings: f64,
+        calories: f64,
+    ) -> PlannedDiaryEntry {
+        PlannedDiaryEntry {
+            idempotency_key: format!("tandoor-meal-plan:{meal_plan_id}:{date_int}"),
+            meal_plan_id,
+            recipe_id: 1,
+            date: int_to_date(date_int).unwrap(),
+            entry: FoodEntryInput::Custom {
+                food_entry_name: "Chili".to_string(),
+                serving_description: "1 serving".to_string(),
+                number_of_units: servings,
+                meal: MealType::Dinner,
+                date_int,
+                calories,
+                carbohydrate: 50.0,
+                protein: 30.0,
+                fat: 20.0,
+            },
+        }
+    }
+
+    fn food_entry(calories: f64, carbohydrate: f64, protein: f64, fat: f64) -> FoodEntry {
+        serde_json::from_value(json!({
+            "food_entry_id": "1",
+            "food_entry_name": "Food",
+            "food_entry_description": "",
+            "food_id": "",
+            "serving_id": "",
+            "number_of_units": "1",
+            "meal": "dinner",
+            "date_int": DAY.to_string(),
+            "calories": calories.to_string(),
+            "carbohydrate": carbohydrate.to_string(),
+            "protein": protein.to_string(),
+            "fat": fat.to_string()
+        }))
+        .unwrap()
+    }
+
+    fn day_data<'a>(
+        planned: &'a [PlannedDiaryEntry],
+        food_entries: &'a [FoodEntry],
+    ) -> DayData<'a> {
+        DayData {
+            date_int: DAY,
+            planned,
+            skipped: &[],
+            food_entries,
+            calories_out: 300.0,
+            calorie_goal: Some(2000.0),
+            macro_target: DEFAULT_MACRO_TARGET,
+        }
+    }
+
+    #[test]
+    fn test_macro_split_energy_shares() {
+        let totals = NutritionTotals {
+            calories: 900.0,
+            carbohydrate: 100.0,
+            protein: 50.0,
+            fat: 200.0 / 9.0,
+        };
+        let split = totals.macro_split();
+        assert!((split.carbohydrate_pct - 50.0).abs() < 0.05);
+        assert!((split.protein_pct - 25.0).abs() < 0.05);
+        assert!((split.fat_pct - 25.0).abs() < 0.05);
+        assert_eq!(
+            NutritionTotals::default().macro_split(),
+            MacroSplit::default()
+        );
+    }
+
+    #[test]
+    fn test_day_report_compares_plan_and_diary() {
+        let plans = [planned(1, DAY, 2.0, 600.0), planned(2, DAY + 1, 1.0, 500.0)];
+        let eaten = [
+            food_entry(1500.0, 150.0, 80.0, 60.0),
+            food_entry(700.0, 70.0, 30.0, 20.0),
+        ];
+        let report = day_report(&day_data(&plans, &eaten)).unwrap();
+
+        assert_eq!(report.date, "2024-01-15");
+        assert!((report.planned.calories - 1200.0).abs() < f64::EPSILON);
+        assert!((report.eaten.calories - 2200.0).abs() < f64::EPSILON);
+        assert!((report.net_calories - 1900.0).abs() < f64::EPSILON);
+        assert_eq!(report.goal_delta, Some(-100.0));
+        assert!((report.plan_delta - 1000.0).abs() < f64::EPSILON);
+        assert_eq!(report.planned_meals.len(), 1);
+        assert!((report.planned_meals[0].servings - 2.0).abs() < f64::EPSILON);
+    }
+
+    #[test]
+    fn test_day_report_without_goal() {
+        let mut data = day_data(&[], &[]);
+        data.calorie_goal = None;
+        let report = day_report(&data).unwrap();
+        assert!(report.goal_delta.is_none());
+        assert!((report.net_calories + 300.0).abs() < f64::EPSILON);
+    }
+
+    #[test]
+    fn test_week_report_uses_month_summaries() {
+        let diary_days: Vec<DaySummary> = serde_json::from_value(json!([
+            {"date_int": DAY, "calories": "2100", "carbohydrate": "250", "protein": "100", "fat": "70"},
+            {"date_int": DAY + 1, "calories": "2600", "carbohydrate": "300", "protein": "100", "fat": "100"},
+            {"date_int": DAY - 1, "calories": "9999", "carbohydrate": "0", "protein": "0", "fat": "0"}
+        ]))
+        .unwrap();
+        let exercise_days: Vec<ExerciseDaySummary> = serde_json::from_value(json!([
+            {"date_int": DAY, "exercise_calories": "200"}
+        ]))
+        .unwrap();
+        let plans = [planned(1, DAY + 1, 1.0, 700.0)];
+
+        let report = week_report(&WeekData {
+            from_date_int: DAY,
+            to_date_int: DAY + 2,
+            planned: &plans,
+            diary_days: &diary_days,
+            exercise_days: &exercise_days,
+            calorie_goal: Some(2000.0),
+            macro_target: DEFAULT_MACRO_TARGET,
+        })
+        .unwrap();
+
+        assert_eq!(report.days.len(), 3);
+        assert_eq!(report.days[0].on_target, Some(true));
+        assert_eq!(report.days[1].on_target, Some(false));
+        assert_eq!(report.days_on_target, 1);
+        assert!((report.eaten.calories - 4700.0).abs() < f64::EPSILON);
+        assert!((report.planned_calories - 700.0).abs() < f64::EPSILON);
+        assert!((report.average_net_calories - 1500.0).abs() < f64::EPSILON);
+    }
+
+    #[test]
+    fn test_months_in_range() {
+        // 2024-01-29 .. 2024-02-04
+        let months = months_in_range(19751, 19757).unwrap();
+        assert_eq!(months.len(), 2);
+        assert_eq!(int_to_date(months[1].date_int).unwrap(), "2024-02-01");
+        assert_eq!((months[1].year, months[1].month), (2024, 2));
+        assert_eq!(
+            months_in_range(DAY, DAY + 3).unwrap(),
+            vec![RangeMonth {
+                date_int: DAY,
+                year: 2024,
+                month: 1
+            }]
+        );
+    }
+
+    #[test]
+    fn test_day_markdown() {
+        let plans = [planned(1, DAY, 1.0, 600.0)];
+        let eaten = [food_entry(1800.0, 200.0, 90.0, 60.0)];
+        let markdown = day_markdown(&day_report(&day_data(&plans, &eaten)).unwrap());
+
+        assert!(markdown.starts_with("# Nutrition adherence: 2024-01-15"));
+        assert!(markdown.contains("| Calories (kcal) | 600 | 1800 | +1200 |"));
+        assert!(markdown.contains("**Goal:** 2000 kcal (-500)"));
+        assert!(markdown.contains("- dinner: Chili × 1 (600 kcal)"));
+        assert!(!markdown.contains("Missing nutrition"));
+    }
+
+    #[test]
+    fn test_week_markdown() {
+        let report = week_report(&WeekData {
+            from_date_int: DAY,
+            to_date_int: DAY + 6,
+            planned: &[],
+            diary_days: &[],
+            exercise_days: &[],
+            calorie_goal: None,
+            macro_target: DEFAULT_MACRO_TARGET,
+        })
+        .unwrap();
+        let markdown = week_markdown(&report);
+        assert!(markdown.contains("# Nutrition adherence: 2024-01-15 to 2024-01-21"));
+        assert!(markdown.contains("| 2024-01-21 | 0 | 0 | 0 | 0 | – |"));
+        assert!(markdown.contains("**Days on target:** 0/7"));
+    }
+}
@@ -380,3 +380,129 @@ pub async fn save_template(
     make_authenticated_request(config, token, "food_entry.save_template", params).await?;
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fatsecret::diary::types::MealType;
+    use wiremock::matchers::{body_string_contains, method, path};
+    use wiremock::{Mock, MockServer, ResponseTemplate};
+
+    const SUCCESS: &str = r#"{"success": {"value": "1"}}"#;
+
+    async fn stub_server() -> (MockServer, FatSecretConfig, AccessToken) {
+        let server = MockServer::start().await;
+        let mut config =
+            FatSecretConfig::new("stub-client-abcdefg", "abcdefghijklmnopqrstuvwxyz").unwrap();
+        config.api_host = Some(server.uri());
+        (
+            server,
+            config,
+            AccessToken::new("user-token", "user-secret"),
+        )
+    }
+
+    async fn expect_call(server: &MockServer, fragments: &[&str], body: &str) {
+        let mock = fragments.iter().fold(
+            Mock::given(method("POST")).and(path("/rest/server.api")),
+            |mock, fragment| mock.and(body_string_contains(*fragment)),
+        );
+        mock.respond_with(ResponseTemplate::new(200).set_body_string(body))
+            .expect(1)
+            .mount(server)
+            .await;
+    }
+
+    #[tokio::test]
+    async fn test_copy_entries() {
+        let (server, config, token) = stub_server().await;
+        expect_call(
+            &server,
+            &[
+                "method=food_entry.copy",
+                "from_date_int=19736",
+                "to_date_int=19737",
+                "oauth_token=user-token",
+            ],
+            SUCCESS,
+        )
+        .await;
+
+        copy_entries(&config, &token, 19736, 19737).await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_copy_meal() {
+        let (server, config, token) = stub_server().await;
+        expect_call(
+            &server,
+            &[
+                "method=food_entry.copy_meal",
+                "from_meal=breakfast",
+                "to_meal=other",
+                "to_date_int=19740",
+            ],
+            SUCCESS,
+        )
+        .await;
+
+        copy_meal(
+            &config,
+            &token,
+            19736,
+            MealType::Breakfast,
+            19740,
+            MealType::Snack,
+        )
+        .await
+        .unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_commit_day_and_save_template() {
+        let (server, config, token) = stub_server().await;
+        expect_call(
+            &server,
+            &["method=food_entry.commit_day", "date_int=19736"],
+            SUCCESS,
+        )
+        .await;
+        expect_call(
+            &server,
+            &[
+                "method=food_entry.save_template",
+                "template_name=Weekday%20breakfast",
+            ],
+            SUCCESS,
+        )
+        .await;
+
+        commit_day(&config, &token, 19736).await.unwrap();
+        save_template(&config, &token, 19736, "Weekday breakfast")
+            .await
+            .unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_copy_meal_api_error() {
+        let (server, config, token) = stub_server().await;
+        expect_call(
+            &server,
+            &["method=food_entry.copy_meal"],
+            r#"{"error": {"code": 108, "message": "Invalid ID"}}"#,
+        )
+        .await;
+
+        let err = copy_meal(
+            &config,
+            &token,
+            19736,
+            MealType::Breakfast,
+            19737,
+            MealType::Breakfast,
+        )
+        .await
+        .unwrap_err();
+        assert!(matches!(err, FatSecretError::ApiError { .. }));
+    }
+}
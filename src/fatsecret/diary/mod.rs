@@ -14,6 +14,9 @@
 //! - Getting monthly nutrition summaries
 //! - Copying entries between dates
 //! - Managing meal templates
+//! - Repeating a logged meal on following days ([`repeat`])
+//! - Syncing planned Tandoor meals into the diary ([`sync`])
+//! - Planned vs eaten adherence reports ([`adherence`])
 //!
 //! # Authentication
 //!
@@ -121,7 +124,10 @@
 //!
 //! Use [`map_auth_error`] to handle specific OAuth error scenarios.
 
+pub mod adherence;
 mod client;
+pub mod repeat;
+pub mod sync;
 mod types;
 
 pub use client::{
@@ -0,0 +1,170 @@
+//! Repeat a logged meal on following days (FUNCTIONAL CORE - PURE)
+//!
+//! "Repeat yesterday's breakfast for the next 5 days" is a series of
+//! `food_entry.copy_meal` calls, one per target day. [`plan_repeat`] works out
+//! those calls and [`source_entries`] picks the entries that would be copied,
+//! so a dry run can show exactly what a real run would log.
+
+use serde::Serialize;
+
+use super::types::{int_to_date, FoodEntry, MealType};
+
+/// Most days a single repeat may cover
+pub const MAX_REPEAT_DAYS: u32 = 31;
+
+/// A meal to repeat and where to repeat it
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RepeatMeal {
+    /// Date of the logged meal (days since epoch)
+    pub from_date_int: i32,
+    /// Meal to copy
+    pub from_meal: MealType,
+    /// Meal to log the copies as (usually the same meal)
+    pub to_meal: MealType,
+    /// First target date (days since epoch)
+    pub start_date_int: i32,
+    /// Number of consecutive target days
+    pub days: u32,
+}
+
+/// One `food_entry.copy_meal` call
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct MealCopy {
+    /// Source date (days since epoch)
+    pub from_date_int: i32,
+    /// Source date (YYYY-MM-DD)
+    pub from_date: String,
+    /// Source meal
+    pub from_meal: MealType,
+    /// Target date (days since epoch)
+    pub to_date_int: i32,
+    /// Target date (YYYY-MM-DD)
+    pub to_date: String,
+    /// Target meal
+    pub to_meal: MealType,
+}
+
+/// Plan the copies for a repeat, one per target day in date order
+///
+/// Fails if `days` is outside `1..=MAX_REPEAT_DAYS` or a target day would be
+/// the source meal itself.
+pub fn plan_repeat(repeat: &RepeatMeal) -> Result<Vec<MealCopy>, String> {
+    if repeat.days == 0 || repeat.days > MAX_REPEAT_DAYS {
+        return Err(format!(
+            "days must be between 1 and {MAX_REPEAT_DAYS}, got {}",
+            repeat.days
+        ));
+    }
+    let from_date = int_to_date(repeat.from_date_int)?;
+
+    (0..repeat.days)
+        .map(|offset| {
+            let to_date_int = i32::try_from(offset)
+                .ok()
+                .and_then(|offset| repeat.start_date_int.checked_add(offset))
+                .ok_or_else(|| format!("Date overflow after {} days", offset))?;
+            if to_date_int == repeat.from_date_int && repeat.to_meal == repeat.from_meal {
+                return Err(format!(
+                    "Cannot copy {} on {from_date} onto itself",
+                    repeat.from_meal
+                ));
+            }
+            Ok(MealCopy {
+                from_date_int: repeat.from_date_int,
+                from_date: from_date.clone(),
+                from_meal: repeat.from_meal,
+                to_date_int,
+                to_date: int_to_date(to_date_int)?,
+                to_meal: repeat.to_meal,
+            })
+        })
+        .collect()
+}
+
+/// Entries of `meal` in a day's diary, i.e. what `copy_meal` would copy
+pub fn source_entries(entries: &[FoodEntry], meal: MealType) -> Vec<FoodEntry> {
+    entries
+        .iter()
+        .filter(|entry| entry.meal == meal)
+        .cloned()
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fatsecret::diary::types::date_to_int;
+
+    fn repeat(days: u32) -> RepeatMeal {
+        let from_date_int = date_to_int("2024-01-14").unwrap();
+        RepeatMeal {
+            from_date_int,
+            from_meal: MealType::Breakfast,
+            to_meal: MealType::Breakfast,
+            start_date_int: from_date_int + 1,
+            days,
+        }
+    }
+
+    fn entry(name: &str, meal: &str) -> FoodEntry {
+        serde_json::from_value(serde_json::json!({
+            "food_entry_id": name,
+            "food_entry_name": name,
+            "food_entry_description": "1 serving",
+            "food_id": "1",
+            "serving_id": "1",
+            "number_of_units": "1",
+            "meal": meal,
+            "date_int": "19736",
+            "calories": "100",
+            "carbohydrate": "10",
+            "protein": "5",
+            "fat": "2"
+        }))
+        .unwrap()
+    }
+
+    #[test]
+    fn test_plan_repeat_next_days() {
+        let copies = plan_repeat(&repeat(3)).unwrap();
+        let dates: Vec<_> = copies.iter().map(|c| c.to_date.as_str()).collect();
+        assert_eq!(dates, vec!["2024-01-15", "2024-01-16", "2024-01-17"]);
+        assert!(copies
+            .iter()
+            .all(|c| c.from_date == "2024-01-14" && c.to_meal == MealType::Breakfast));
+    }
+
+    #[test]
+    fn test_plan_repeat_rejects_bad_ranges() {
+        assert!(plan_repeat(&repeat(0)).is_err());
+        assert!(plan_repeat(&repeat(MAX_REPEAT_DAYS + 1)).is_err());
+        assert_eq!(plan_repeat(&repeat(MAX_REPEAT_DAYS)).unwrap().len(), 31);
+
+        let onto_itself = RepeatMeal {
+            start_date_int: repeat(1).from_date_int,
+            ..repeat(2)
+        };
+        assert!(plan_repeat(&onto_itself).is_err());
+
+        let as_lunch = RepeatMeal {
+            to_meal: MealType::Lunch,
+            ..onto_itself
+        };
+        assert_eq!(plan_repeat(&as_lunch).unwrap().len(), 2);
+    }
+
+    #[test]
+    fn test_source_entries_filters_by_meal() {
+        let entries = vec![
+            entry("Porridge", "breakfast"),
+            entry("Sandwich", "lunch"),
+            entry("Coffee", "breakfast"),
+        ];
+        let names: Vec<_> = source_entries(&entries, MealType::Breakfast)
+            .into_iter()
+            .map(|e| e.food_entry_name)
+            .collect();
+        assert_eq!(names, vec!["Porridge", "Coffee"]);
+        assert!(source_entries(&entries, MealType::Snack).is_empty());
+    }
+}
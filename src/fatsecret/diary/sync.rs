@@ -0,0 +1,413 @@
+//! Tandoor meal plan → `FatSecret` diary mapping (FUNCTIONAL CORE - PURE)
+//!
+//! Turns planned Tandoor meals into [`FoodEntryInput::Custom`] diary entries so
+//! cooked meals don't have to be re-entered by hand. No I/O happens here: the
+//! sync binary fetches meal plans and recipes, calls [`plan_diary_entries`],
+//! and writes the result to `FatSecret` guarded by idempotency keys.
+//!
+//! Mapping rules:
+//! - One diary entry per meal plan per day (multi-day plans log every day in range)
+//! - Nutrition values are per serving; `number_of_units` is the planned servings
+//! - Meal types map by name (breakfast/lunch/dinner), everything else is a snack
+//! - Each entry carries a stable idempotency key: `tandoor-meal-plan:{id}:{date_int}`
+
+use std::collections::HashMap;
+
+use serde::Serialize;
+use serde_json::Value;
+
+use super::types::{date_to_int, int_to_date, validate_custom_entry, FoodEntryInput, MealType};
+use crate::tandoor::MealPlan;
+
+/// Serving description used for synced entries
+pub const SERVING_DESCRIPTION: &str = "1 serving";
+
+/// Nutrition values for a single serving of a recipe
+#[derive(Debug, Clone, Copy, PartialEq, Default, Serialize)]
+pub struct ServingNutrition {
+    /// Calories (kcal)
+    pub calories: f64,
+    /// Carbohydrates (g)
+    pub carbohydrate: f64,
+    /// Protein (g)
+    pub protein: f64,
+    /// Fat (g)
+    pub fat: f64,
+}
+
+/// A diary entry ready to be created for a meal plan day
+#[derive(Debug, Clone, Serialize)]
+pub struct PlannedDiaryEntry {
+    /// Stable key preventing duplicate entries across runs
+    pub idempotency_key: String,
+    /// Source Tandoor meal plan ID
+    pub meal_plan_id: i64,
+    /// Source Tandoor recipe ID
+    pub recipe_id: i64,
+    /// Diary date (YYYY-MM-DD)
+    pub date: String,
+    /// Entry to create in `FatSecret`
+    pub entry: FoodEntryInput,
+}
+
+/// A meal plan that could not be turned into diary entries
+#[derive(Debug, Clone, Serialize)]
+pub struct SkippedMealPlan {
+    /// Tandoor meal plan ID
+    pub meal_plan_id: i64,
+    /// Why it was skipped
+    pub reason: String,
+}
+
+/// Build the idempotency key for a meal plan on a given day
+pub fn idempotency_key(meal_plan_id: i64, date_int: i32) -> String {
+    format!("tandoor-meal-plan:{meal_plan_id}:{date_int}")
+}
+
+/// Map a Tandoor meal type name to a `FatSecret` meal
+///
+/// `overrides` maps lowercase Tandoor meal type names to `FatSecret` meal
+/// strings ("breakfast", "lunch", "dinner", "other") and wins over the
+/// built-in name matching.
+pub fn meal_type_from_name(name: &str, overrides: &HashMap<String, String>) -> MealType {
+    let lower = name.trim().to_lowercase();
+    if let Some(meal) = overrides
+        .get(&lower)
+        .and_then(|meal| MealType::from_api_string(&meal.to_lowercase()))
+    {
+        return meal;
+    }
+
+    if lower.contains("breakfast") || lower.contains("brunch") {
+        MealType::Breakfast
+    } else if lower.contains("lunch") {
+        MealType::Lunch
+    } else if lower.contains("dinner") || lower.contains("supper") {
+        MealType::Dinner
+    } else {
+        MealType::Snack
+    }
+}
+
+/// Read a number that Tandoor may send as a JSON number or a string
+fn number(value: Option<&Value>) -> Option<f64> {
+    let value = value?;
+    value
+        .as_f64()
+        .or_else(|| value.as_str().and_then(|s| s.trim().parse().ok()))
+}
+
+/// Per-serving nutrition for a Tandoor recipe
+///
+/// Prefers the recipe's `nutrition` block (already per serving). Falls back to
+/// the computed `food_properties` totals, divided by the recipe's servings.
+/// Returns None when the recipe carries no calorie information.
+pub fn nutrition_per_serving(recipe: &Value) -> Option<ServingNutrition> {
+    if let Some(nutrition) = recipe.get("nutrition").filter(|n| n.is_object()) {
+        if let Some(calories) = number(nutrition.get("calories")) {
+            return Some(ServingNutrition {
+                calories,
+                carbohydrate: number(nutrition.get("carbohydrates")).unwrap_or(0.0),
+                protein: number(nutrition.get("proteins")).unwrap_or(0.0),
+                fat: number(nutrition.get("fats")).unwrap_or(0.0),
+            });
+        }
+    }
+
+    let properties = recipe.get("food_properties")?.as_object()?;
+    let total = |names: &[&str]| {
+        properties.values().find_map(|property| {
+            let name = property.get("name")?.as_str()?.to_lowercase();
+            names
+                .contains(&name.as_str())
+                .then(|| number(property.get("total_value")))
+                .flatten()
+        })
+    };
+
+    let calories = total(&["calories", "energy", "energy (kcal)"])?;
+    let servings = number(recipe.get("servings"))
+        .filter(|s| *s > 0.0)
+        .unwrap_or(1.0);
+
+    Some(ServingNutrition {
+        calories: calories / servings,
+        carbohydrate: total(&["carbohydrates", "carbohydrate", "carbs"]).unwrap_or(0.0) / servings,
+        protein: total(&["proteins", "protein"]).unwrap_or(0.0) / servings,
+        fat: total(&["fats", "fat"]).unwrap_or(0.0) / servings,
+    })
+}
+
+/// Day range (as date_ints) a meal plan covers
+fn plan_days(plan: &MealPlan) -> Result<(i32, i32), String> {
+    let day = |date: &str| {
+        date.get(..10)
+            .map_or_else(|| Err(format!("Invalid date: {date}")), date_to_int)
+    };
+    let from = day(&plan.from_date)?;
+    let to = day(&plan.to_date).unwrap_or(from);
+    Ok((from, to.max(from)))
+}
+
+/// Build diary entries for every meal plan day inside `[from_date_int, to_date_int]`
+///
+/// `recipes` maps recipe IDs to full recipe JSON (as returned by Tandoor's
+/// recipe detail endpoint). Plans without a recipe, without nutrition, or with
+/// invalid values are reported as skipped instead of failing the whole sync.
+pub fn plan_diary_entries(
+    plans: &[MealPlan],
+    recipes: &HashMap<i64, Value>,
+    meal_type_overrides: &HashMap<String, String>,
+    from_date_int: i32,
+    to_date_int: i32,
+) -> (Vec<PlannedDiaryEntry>, Vec<SkippedMealPlan>) {
+    let mut planned = Vec::new();
+    let mut skipped = Vec::new();
+
+    for plan in plans {
+        match plan_entries_for(
+            plan,
+            recipes,
+            meal_type_overrides,
+            from_date_int,
+            to_date_int,
+        ) {
+            Ok(entries) => planned.extend(entries),
+            Err(reason) => skipped.push(SkippedMealPlan {
+                meal_plan_id: plan.id,
+                reason,
+            }),
+        }
+    }
+
+    planned.sort_by(|a, b| {
+        a.date
+            .cmp(&b.date)
+            .then(a.meal_plan_id.cmp(&b.meal_plan_id))
+    });
+    (planned, skipped)
+}
+
+/// Diary entries for a single meal plan
+fn plan_entries_for(
+    plan: &MealPlan,
+    recipes: &HashMap<i64, Value>,
+    meal_type_overrides: &HashMap<String, String>,
+    from_date_int: i32,
+    to_date_int: i32,
+) -> Result<Vec<PlannedDiaryEntry>, String> {
+    let recipe_id = plan
+        .recipe
+        .get("id")
+        .and_then(Value::as_i64)
+        .ok_or("Meal plan has no recipe")?;
+    let recipe = recipes
+        .get(&recipe_id)
+        .ok_or_else(|| format!("Recipe {recipe_id} was not fetched"))?;
+    let nutrition = nutrition_per_serving(recipe)
+        .ok_or_else(|| format!("Recipe {recipe_id} has no nutrition data"))?;
+
+    let name = recipe
+        .get("name")
+        .and_then(Value::as_str)
+        .filter(|n| !n.is_empty())
+        .unwrap_or(&plan.recipe_name)
+        .to_string();
+    let meal_type_name = Some(plan.meal_type_name.as_str())
+        .filter(|n| !n.is_empty())
+        .or_else(|| plan.meal_type.get("name").and_then(Value::as_str))
+        .unwrap_or_default();
+    let meal = meal_type_from_name(meal_type_name, meal_type_overrides);
+
+    validate_custom_entry(
+        &name,
+        SERVING_DESCRIPTION,
+        plan.servings,
+        nutrition.calories,
+        nutrition.carbohydrate,
+        nutrition.protein,
+        nutrition.fat,
+    )
+    .map_err(|e| format!("Invalid diary entry for recipe {recipe_id}: {e}"))?;
+
+    let (plan_from, plan_to) = plan_days(plan)?;
+    (plan_from.max(from_date_int)..=plan_to.min(to_date_int))
+        .map(|date_int| {
+            Ok(PlannedDiaryEntry {
+                idempotency_key: idempotency_key(plan.id, date_int),
+                meal_plan_id: plan.id,
+                recipe_id,
+                date: int_to_date(date_int)?,
+                entry: custom_entry(&name, meal, plan.servings, nutrition, date_int),
+            })
+        })
+        .collect()
+}
+
+/// Custom diary entry for `servings` servings of a recipe
+fn custom_entry(
+    name: &str,
+    meal: MealType,
+    servings: f64,
+    nutrition: ServingNutrition,
+    date_int: i32,
+) -> FoodEntryInput {
+    FoodEntryInput::Custom {
+        food_entry_name: name.to_string(),
+        serving_description: SERVING_DESCRIPTION.to_string(),
+        number_of_units: servings,
+        meal,
+        date_int,
+        calories: nutrition.calories,
+        carbohydrate: nutrition.carbohydrate,
+        protein: nutrition.protein,
+        fat: nutrition.fat,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn plan(id: i64, recipe_id: i64, meal_type: &str, from: &str, to: &str) -> MealPlan {
+        serde_json::from_value(json!({
+            "id": id,
+            "title": "",
+            "recipe": {"id": recipe_id, "name": "ignored"},
+            "servings": 2.0,
+            "from_date": from,
+            "to_date": to,
+            "meal_type": {"id": 1, "name": meal_type},
+            "created_by": 1,
+            "recipe_name": "ignored",
+            "meal_type_name": meal_type
+        }))
+        .unwrap()
+    }
+
+    fn recipes() -> HashMap<i64, Value> {
+        HashMap::from([(
+            10,
+            json!({
+                "id": 10,
+                "name": "Chili",
+                "servings": 4,
+                "nutrition": {"calories": "450", "carbohydrates": 40.0, "proteins": 30.0, "fats": 15.0}
+            }),
+        )])
+    }
+
+    #[test]
+    fn test_meal_type_from_name() {
+        let none = HashMap::new();
+        assert_eq!(meal_type_from_name("Breakfast", &none), MealType::Breakfast);
+        assert_eq!(
+            meal_type_from_name("Sunday Brunch", &none),
+            MealType::Breakfast
+        );
+        assert_eq!(meal_type_from_name("lunch", &none), MealType::Lunch);
+        assert_eq!(meal_type_from_name("Supper", &none), MealType::Dinner);
+        assert_eq!(meal_type_from_name("Dessert", &none), MealType::Snack);
+    }
+
+    #[test]
+    fn test_meal_type_override_wins() {
+        let overrides = HashMap::from([("dessert".to_string(), "Dinner".to_string())]);
+        assert_eq!(meal_type_from_name("Dessert", &overrides), MealType::Dinner);
+    }
+
+    #[test]
+    fn test_nutrition_from_nutrition_block() {
+        let recipes = recipes();
+        let nutrition = nutrition_per_serving(&recipes[&10]).unwrap();
+        assert!((nutrition.calories - 450.0).abs() < f64::EPSILON);
+        assert!((nutrition.protein - 30.0).abs() < f64::EPSILON);
+    }
+
+    #[test]
+    fn test_nutrition_from_food_properties_divides_by_servings() {
+        let recipe = json!({
+            "servings": 4,
+            "nutrition": null,
+            "food_properties": {
+                "1": {"name": "Calories", "total_value": 2000.0},
+                "2": {"name": "Proteins", "total_value": 100.0},
+                "3": {"name": "Fats", "total_value": 40.0}
+            }
+        });
+        let nutrition = nutrition_per_serving(&recipe).unwrap();
+        assert!((nutrition.calories - 500.0).abs() < f64::EPSILON);
+        assert!((nutrition.protein - 25.0).abs() < f64::EPSILON);
+        assert!((nutrition.fat - 10.0).abs() < f64::EPSILON);
+        assert!(nutrition.carbohydrate.abs() < f64::EPSILON);
+    }
+
+    #[test]
+    fn test_nutrition_missing() {
+        assert!(nutrition_per_serving(&json!({"name": "Toast"})).is_none());
+    }
+
+    #[test]
+    fn test_plan_diary_entries_single_day() {
+        let from = date_to_int("2024-01-15").unwrap();
+        let plans = [plan(
+            1,
+            10,
+            "Dinner",
+            "2024-01-15T00:00:00+01:00",
+            "2024-01-15",
+        )];
+        let (planned, skipped) =
+            plan_diary_entries(&plans, &recipes(), &HashMap::new(), from, from);
+
+        assert!(skipped.is_empty());
+        assert_eq!(planned.len(), 1);
+        assert_eq!(planned[0].date, "2024-01-15");
+        assert_eq!(
+            planned[0].idempotency_key,
+            format!("tandoor-meal-plan:1:{from}")
+        );
+        match &planned[0].entry {
+            FoodEntryInput::Custom {
+                food_entry_name,
+                number_of_units,
+                meal,
+                ..
+            } => {
+                assert_eq!(food_entry_name, "Chili");
+                assert!((number_of_units - 2.0).abs() < f64::EPSILON);
+                assert_eq!(*meal, MealType::Dinner);
+            }
+            FoodEntryInput::FromFood { .. } => panic!("expected custom entry"),
+        }
+    }
+
+    #[test]
+    fn test_plan_diary_entries_clamps_multi_day_plan_to_range() {
+        let from = date_to_int("2024-01-16").unwrap();
+        let to = date_to_int("2024-01-17").unwrap();
+        let plans = [plan(1, 10, "Lunch", "2024-01-15", "2024-01-20")];
+        let (planned, _) = plan_diary_entries(&plans, &recipes(), &HashMap::new(), from, to);
+
+        let dates: Vec<_> = planned.iter().map(|p| p.date.as_str()).collect();
+        assert_eq!(dates, vec!["2024-01-16", "2024-01-17"]);
+    }
+
+    #[test]
+    fn test_plan_diary_entries_skips_unknown_recipe() {
+        let day = date_to_int("2024-01-15").unwrap();
+        let plans = [plan(7, 99, "Dinner", "2024-01-15", "2024-01-15")];
+        let (planned, skipped) = plan_diary_entries(&plans, &recipes(), &HashMap::new(), day, day);
+
+        assert!(planned.is_empty());
+        assert_eq!(skipped.len(), 1);
+        assert_eq!(skipped[0].meal_plan_id, 7);
+    }
+
+    #[test]
+    fn test_idempotency_key_is_stable() {
+        assert_eq!(idempotency_key(42, 19723), idempotency_key(42, 19723));
+        assert_ne!(idempotency_key(42, 19723), idempotency_key(42, 19724));
+    }
+}
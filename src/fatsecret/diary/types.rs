@@ -449,6 +449,15 @@ impl FoodEntryInput {
             } => food_entry_name,
         }
     }
+
+    /// Returns the diary date as days since Unix epoch
+    pub fn date_int(&self) -> i32 {
+        match self {
+            FoodEntryInput::FromFood { date_int, .. } | FoodEntryInput::Custom { date_int, .. } => {
+                *date_int
+            }
+        }
+    }
 }
 
 /// Update for an existing food entry
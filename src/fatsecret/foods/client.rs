@@ -26,6 +26,10 @@
 //! | [`autocomplete_foods`] | `foods.autocomplete.v2` | Get autocomplete suggestions |
 //! | [`autocomplete_foods_with_options`] | `foods.autocomplete.v2` | Autocomplete with `max_results` |
 //! | [`find_food_by_barcode`] | `food.find_id_for_barcode.v2` | Lookup food by barcode |
+//! | [`search_foods_with_sub_categories`] | `foods.search.v3` | Search with sub-categories on each result |
+//! | [`get_food_brands`] | `food_brands.get.v2` | List brands by starting characters |
+//! | [`get_food_categories`] | `food_categories.get.v2` | List top-level food categories |
+//! | [`get_food_sub_categories`] | `food_sub_categories.get` | List sub-categories of a category |
 //!
 //! # Examples
 //!
@@ -110,8 +114,12 @@ use std::collections::HashMap;
 
 use serde::Deserialize;
 
+use crate::fatsecret::core::serde_utils::{deserialize_flexible_int, deserialize_single_or_vec};
 use crate::fatsecret::core::{make_api_request, FatSecretConfig, FatSecretError};
-use crate::fatsecret::foods::types::{Food, FoodAutocompleteResponse, FoodId, FoodSearchResponse};
+use crate::fatsecret::foods::types::{
+    BrandListResponse, BrandType, Food, FoodAutocompleteResponse, FoodCategoriesResponse,
+    FoodCategoryId, FoodId, FoodSearchResponse, FoodSearchResult, FoodSubCategoriesResponse,
+};
 
 // ============================================================================
 // Response Wrappers
@@ -132,6 +140,58 @@ struct AutocompleteWrapper {
     suggestions: FoodAutocompleteResponse,
 }
 
+#[derive(Deserialize)]
+struct FoodsSearchV3Wrapper {
+    foods_search: FoodsSearchV3,
+}
+
+#[derive(Deserialize)]
+struct FoodsSearchV3 {
+    #[serde(deserialize_with = "deserialize_flexible_int")]
+    max_results: i32,
+    #[serde(deserialize_with = "deserialize_flexible_int")]
+    total_results: i32,
+    #[serde(deserialize_with = "deserialize_flexible_int")]
+    page_number: i32,
+    #[serde(default)]
+    results: Option<FoodsSearchV3Results>,
+}
+
+#[derive(Deserialize)]
+struct FoodsSearchV3Results {
+    #[serde(default, deserialize_with = "deserialize_single_or_vec")]
+    food: Vec<FoodSearchResult>,
+}
+
+impl From<FoodsSearchV3> for FoodSearchResponse {
+    fn from(search: FoodsSearchV3) -> Self {
+        Self {
+            foods: search.results.map(|r| r.food).unwrap_or_default(),
+            max_results: search.max_results,
+            total_results: search.total_results,
+            page_number: search.page_number,
+        }
+    }
+}
+
+#[derive(Deserialize)]
+struct BrandsWrapper {
+    #[serde(default)]
+    brands: BrandListResponse,
+}
+
+#[derive(Deserialize)]
+struct CategoriesWrapper {
+    #[serde(default)]
+    food_categories: FoodCategoriesResponse,
+}
+
+#[derive(Deserialize)]
+struct SubCategoriesWrapper {
+    #[serde(default)]
+    food_sub_categories: FoodSubCategoriesResponse,
+}
+
 #[derive(Deserialize)]
 struct BarcodeWrapper {
     food_id: BarcodeValue,
@@ -208,6 +268,34 @@ pub async fn search_foods_simple(
     list_foods_with_options(config, query, None, None).await
 }
 
+/// Search for foods using `foods.search.v3`, including each result's sub-categories
+///
+/// This is a 2-legged OAuth request (no user token required). Use this when
+/// results will be filtered by category (see [`crate::fatsecret::foods::filter`]).
+pub async fn search_foods_with_sub_categories(
+    config: &FatSecretConfig,
+    query: &str,
+    page: Option<u32>,
+    max_results: Option<u32>,
+) -> Result<FoodSearchResponse, FatSecretError> {
+    let mut params = HashMap::new();
+    params.insert("search_expression".to_string(), query.to_string());
+    params.insert("include_sub_categories".to_string(), "true".to_string());
+
+    if let Some(p) = page {
+        params.insert("page_number".to_string(), p.to_string());
+    }
+
+    if let Some(m) = max_results {
+        params.insert("max_results".to_string(), m.to_string());
+    }
+
+    let response_json = make_api_request(config, "foods.search.v3", params).await?;
+
+    let wrapper: FoodsSearchV3Wrapper = serde_json::from_str(&response_json)?;
+    Ok(wrapper.foods_search.into())
+}
+
 // ============================================================================
 // Food Barcode Lookup API (food.find_id_for_barcode.v2)
 // ============================================================================
@@ -267,3 +355,173 @@ pub async fn autocomplete_foods(
 ) -> Result<FoodAutocompleteResponse, FatSecretError> {
     autocomplete_foods_with_options(config, expression, None).await
 }
+
+// ============================================================================
+// Food Brands API (food_brands.get.v2)
+// ============================================================================
+
+/// Get brands starting with the given characters using `food_brands.get.v2`
+///
+/// This is a 2-legged OAuth request (no user token required). Requires the
+/// `premier` scope.
+pub async fn get_food_brands(
+    config: &FatSecretConfig,
+    starts_with: &str,
+    brand_type: Option<BrandType>,
+    region: Option<&str>,
+    language: Option<&str>,
+) -> Result<BrandListResponse, FatSecretError> {
+    let mut params = HashMap::new();
+    params.insert("starts_with".to_string(), starts_with.to_string());
+
+    if let Some(bt) = brand_type {
+        params.insert("brand_type".to_string(), bt.to_api_string().to_string());
+    }
+
+    insert_locale(&mut params, region, language);
+
+    let response_json = make_api_request(config, "food_brands.get.v2", params).await?;
+
+    let wrapper: BrandsWrapper = serde_json::from_str(&response_json)?;
+    Ok(wrapper.brands)
+}
+
+/// Brand lookup with no type or locale filters
+pub async fn search_brands_simple(
+    config: &FatSecretConfig,
+    starts_with: &str,
+) -> Result<BrandListResponse, FatSecretError> {
+    get_food_brands(config, starts_with, None, None, None).await
+}
+
+// ============================================================================
+// Food Categories API (food_categories.get.v2, food_sub_categories.get)
+// ============================================================================
+
+/// Get all top-level food categories using `food_categories.get.v2`
+///
+/// This is a 2-legged OAuth request (no user token required). Requires the
+/// `premier` scope.
+pub async fn get_food_categories(
+    config: &FatSecretConfig,
+    region: Option<&str>,
+    language: Option<&str>,
+) -> Result<FoodCategoriesResponse, FatSecretError> {
+    let mut params = HashMap::new();
+    insert_locale(&mut params, region, language);
+
+    let response_json = make_api_request(config, "food_categories.get.v2", params).await?;
+
+    let wrapper: CategoriesWrapper = serde_json::from_str(&response_json)?;
+    Ok(wrapper.food_categories)
+}
+
+/// Get the sub-categories of a food category using `food_sub_categories.get`
+///
+/// This is a 2-legged OAuth request (no user token required). Requires the
+/// `premier` scope.
+pub async fn get_food_sub_categories(
+    config: &FatSecretConfig,
+    food_category_id: &FoodCategoryId,
+    region: Option<&str>,
+    language: Option<&str>,
+) -> Result<FoodSubCategoriesResponse, FatSecretError> {
+    let mut params = HashMap::new();
+    params.insert(
+        "food_category_id".to_string(),
+        food_category_id.as_str().to_string(),
+    );
+    insert_locale(&mut params, region, language);
+
+    let response_json = make_api_request(config, "food_sub_categories.get", params).await?;
+
+    let wrapper: SubCategoriesWrapper = serde_json::from_str(&response_json)?;
+    Ok(wrapper.food_sub_categories)
+}
+
+/// Add optional `region`/`language` parameters
+fn insert_locale(
+    params: &mut HashMap<String, String>,
+    region: Option<&str>,
+    language: Option<&str>,
+) {
+    if let Some(r) = region {
+        params.insert("region".to_string(), r.to_string());
+    }
+
+    if let Some(l) = language {
+        params.insert("language".to_string(), l.to_string());
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_brands_wrapper_single_and_empty() {
+        let single = r#"{"brands": {"brand": {"brand_id": "1234", "brand_name": "Kellogg's", "brand_type": "manufacturer"}}}"#;
+        let wrapper: BrandsWrapper = serde_json::from_str(single).unwrap();
+        assert_eq!(wrapper.brands.brands.len(), 1);
+        assert_eq!(
+            wrapper.brands.brands[0].brand_type,
+            Some(BrandType::Manufacturer)
+        );
+
+        let empty: BrandsWrapper = serde_json::from_str(r#"{"brands": {}}"#).unwrap();
+        assert!(empty.brands.brands.is_empty());
+    }
+
+    #[test]
+    fn test_categories_wrapper() {
+        let json = r#"{"food_categories": {"food_category": [
+            {"food_category_id": "1", "food_category_name": "Fruits", "food_category_description": "Fresh and dried fruits"},
+            {"food_category_id": "2", "food_category_name": "Vegetables"}
+        ]}}"#;
+        let wrapper: CategoriesWrapper = serde_json::from_str(json).unwrap();
+        let categories = wrapper.food_categories.categories;
+        assert_eq!(categories.len(), 2);
+        assert_eq!(categories[0].food_category_id.as_str(), "1");
+        assert_eq!(categories[1].food_category_description, "");
+    }
+
+    #[test]
+    fn test_sub_categories_wrapper_objects_or_names() {
+        let objects = r#"{"food_sub_categories": {"food_sub_category": [
+            {"food_sub_category_id": "101", "food_sub_category_name": "Citrus Fruits"}
+        ]}}"#;
+        let wrapper: SubCategoriesWrapper = serde_json::from_str(objects).unwrap();
+        assert_eq!(
+            wrapper.food_sub_categories.sub_categories[0].name(),
+            "Citrus Fruits"
+        );
+
+        let names =
+            r#"{"food_sub_categories": {"food_sub_category": ["Berries", "Citrus Fruits"]}}"#;
+        let wrapper: SubCategoriesWrapper = serde_json::from_str(names).unwrap();
+        assert_eq!(
+            wrapper.food_sub_categories.sub_categories[0].name(),
+            "Berries"
+        );
+    }
+
+    #[test]
+    fn test_foods_search_v3_wrapper() {
+        let json = r#"{"foods_search": {"max_results": "20", "total_results": "1", "page_number": "0",
+            "results": {"food": {"food_id": "3092", "food_name": "White Bread", "food_type": "Generic",
+            "food_url": "https://www.fatsecret.com/calories-nutrition/generic/bread-white",
+            "food_sub_categories": {"food_sub_category": ["Bread", "White Bread"]}}}}}"#;
+        let wrapper: FoodsSearchV3Wrapper = serde_json::from_str(json).unwrap();
+        let response: FoodSearchResponse = wrapper.foods_search.into();
+        assert_eq!(response.total_results, 1);
+        let sub_categories = response.foods[0].food_sub_categories.as_ref().unwrap();
+        assert_eq!(sub_categories.names, vec!["Bread", "White Bread"]);
+
+        let empty =
+            r#"{"foods_search": {"max_results": 20, "total_results": 0, "page_number": 0}}"#;
+        let wrapper: FoodsSearchV3Wrapper = serde_json::from_str(empty).unwrap();
+        assert!(FoodSearchResponse::from(wrapper.foods_search)
+            .foods
+            .is_empty());
+    }
+}
@@ -0,0 +1,190 @@
+//! Food search result filtering (FUNCTIONAL CORE - PURE)
+//!
+//! `foods.search` mixes generic foods with branded products. These helpers
+//! narrow results by brand or category and rank generic foods first, so
+//! ingredient resolution picks "Chicken Breast" over a specific deli brand.
+//!
+//! Category filtering needs sub-categories on each result, which only
+//! [`search_foods_with_sub_categories`](super::client::search_foods_with_sub_categories)
+//! returns. Results without sub-categories never match a category filter.
+
+use serde::{Deserialize, Serialize};
+
+use crate::fatsecret::foods::types::FoodSearchResult;
+
+/// Filter applied to food search results
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct FoodSearchFilter {
+    /// Keep only foods whose brand name contains this text (case-insensitive)
+    #[serde(default)]
+    pub brand: Option<String>,
+    /// Keep only foods with a sub-category containing this text (case-insensitive)
+    #[serde(default)]
+    pub category: Option<String>,
+    /// Drop branded foods
+    #[serde(default)]
+    pub generic_only: bool,
+}
+
+impl FoodSearchFilter {
+    /// True if no criteria are set
+    pub fn is_empty(&self) -> bool {
+        self.brand.is_none() && self.category.is_none() && !self.generic_only
+    }
+
+    /// True if the result satisfies every criterion
+    pub fn matches(&self, result: &FoodSearchResult) -> bool {
+        if self.generic_only && !is_generic(result) {
+            return false;
+        }
+        if let Some(brand) = &self.brand {
+            if !result
+                .brand_name
+                .as_deref()
+                .is_some_and(|name| contains_ignore_case(name, brand))
+            {
+                return false;
+            }
+        }
+        if let Some(category) = &self.category {
+            return result.food_sub_categories.as_ref().is_some_and(|subs| {
+                subs.names
+                    .iter()
+                    .any(|name| contains_ignore_case(name, category))
+            });
+        }
+        true
+    }
+}
+
+fn contains_ignore_case(haystack: &str, needle: &str) -> bool {
+    haystack
+        .to_lowercase()
+        .contains(&needle.trim().to_lowercase())
+}
+
+/// True for generic (unbranded) foods
+pub fn is_generic(result: &FoodSearchResult) -> bool {
+    result.food_type.eq_ignore_ascii_case("generic")
+}
+
+/// Results matching the filter, generic foods first (otherwise in API order)
+pub fn filter_results(
+    results: &[FoodSearchResult],
+    filter: &FoodSearchFilter,
+) -> Vec<FoodSearchResult> {
+    let mut matching: Vec<FoodSearchResult> = results
+        .iter()
+        .filter(|result| filter.matches(result))
+        .cloned()
+        .collect();
+    matching.sort_by_key(|result| !is_generic(result));
+    matching
+}
+
+/// Best result for an ingredient name
+///
+/// Prefers a generic food with the exact name, then any generic food, then
+/// the first result.
+pub fn best_match<'a>(results: &'a [FoodSearchResult], name: &str) -> Option<&'a FoodSearchResult> {
+    let exact = |r: &&FoodSearchResult| r.food_name.eq_ignore_ascii_case(name.trim());
+
+    results
+        .iter()
+        .find(|r| is_generic(r) && exact(r))
+        .or_else(|| results.iter().find(|r| is_generic(r)))
+        .or_else(|| results.first())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn result(name: &str, brand: Option<&str>, sub_categories: &[&str]) -> FoodSearchResult {
+        serde_json::from_value(serde_json::json!({
+            "food_id": name,
+            "food_name": name,
+            "food_type": if brand.is_some() { "Brand" } else { "Generic" },
+            "brand_name": brand,
+            "food_url": "",
+            "food_sub_categories": {"food_sub_category": sub_categories},
+        }))
+        .unwrap()
+    }
+
+    fn results() -> Vec<FoodSearchResult> {
+        vec![
+            result(
+                "Wholemeal Bread",
+                Some("Hovis"),
+                &["Bread", "Whole Wheat Bread"],
+            ),
+            result("White Bread", None, &["Bread", "White Bread"]),
+            result("Peanut Butter", Some("Skippy"), &["Nut Butters"]),
+            result("Wholemeal Bread", None, &["Bread"]),
+        ]
+    }
+
+    #[test]
+    fn test_empty_filter_ranks_generic_first() {
+        let filter = FoodSearchFilter::default();
+        assert!(filter.is_empty());
+        let names: Vec<_> = filter_results(&results(), &filter)
+            .into_iter()
+            .map(|r| (r.food_name, r.brand_name))
+            .collect();
+        assert_eq!(
+            names,
+            vec![
+                ("White Bread".to_string(), None),
+                ("Wholemeal Bread".to_string(), None),
+                ("Wholemeal Bread".to_string(), Some("Hovis".to_string())),
+                ("Peanut Butter".to_string(), Some("Skippy".to_string())),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_filter_by_brand_and_category() {
+        let by_brand = FoodSearchFilter {
+            brand: Some("hovis".to_string()),
+            ..FoodSearchFilter::default()
+        };
+        let filtered = filter_results(&results(), &by_brand);
+        assert_eq!(filtered.len(), 1);
+        assert_eq!(filtered[0].brand_name.as_deref(), Some("Hovis"));
+
+        let by_category = FoodSearchFilter {
+            category: Some("bread".to_string()),
+            generic_only: true,
+            ..FoodSearchFilter::default()
+        };
+        let filtered = filter_results(&results(), &by_category);
+        assert_eq!(filtered.len(), 2);
+        assert!(filtered.iter().all(is_generic));
+    }
+
+    #[test]
+    fn test_category_filter_needs_sub_categories() {
+        let mut plain = result("Rice", None, &[]);
+        plain.food_sub_categories = None;
+        let filter = FoodSearchFilter {
+            category: Some("grains".to_string()),
+            ..FoodSearchFilter::default()
+        };
+        assert!(!filter.matches(&plain));
+    }
+
+    #[test]
+    fn test_best_match_prefers_generic_exact_name() {
+        let results = results();
+        let best = best_match(&results, "wholemeal bread").unwrap();
+        assert!(is_generic(best));
+        assert_eq!(best.food_name, "Wholemeal Bread");
+
+        let best = best_match(&results, "toast").unwrap();
+        assert_eq!(best.food_name, "White Bread");
+
+        assert!(best_match(&[], "toast").is_none());
+    }
+}
@@ -8,6 +8,7 @@
 //!
 //! - [`client`] - API client functions for interacting with `FatSecret` foods endpoints
 //! - [`types`] - Type definitions for foods, servings, and nutrition information
+//! - [`filter`] - Brand/category filtering and generic-first ranking of search results
 //!
 //! # Key Types
 //!
@@ -26,6 +27,10 @@
 //! - `foods.search` - Search foods by text query with pagination
 //! - `foods.autocomplete.v2` - Get food suggestions for autocomplete
 //! - `food.find_id_for_barcode.v2` - Lookup food by barcode (UPC/EAN)
+//! - `foods.search.v3` - Search with sub-categories (for category filtering)
+//! - `food_brands.get.v2` - List brands by starting characters (Premier)
+//! - `food_categories.get.v2` - List food categories (Premier)
+//! - `food_sub_categories.get` - List sub-categories of a category (Premier)
 //!
 //! All endpoints use 2-legged OAuth (no user token required).
 //!
@@ -102,13 +107,18 @@
 //! - [`crate::fatsecret::diary`] for logging food consumption
 
 pub mod client;
+pub mod filter;
 pub mod types;
 
 pub use client::{
     autocomplete_foods, autocomplete_foods_with_options, find_food_by_barcode, get_food,
-    list_foods_with_options, search_foods, search_foods_simple,
+    get_food_brands, get_food_categories, get_food_sub_categories, list_foods_with_options,
+    search_brands_simple, search_foods, search_foods_simple, search_foods_with_sub_categories,
 };
+pub use filter::{best_match, filter_results, FoodSearchFilter};
 pub use types::{
-    Food, FoodAutocompleteResponse, FoodId, FoodSearchResponse, FoodSearchResult, FoodSuggestion,
-    Nutrition, Serving, ServingId,
+    Brand, BrandId, BrandListResponse, BrandType, Food, FoodAutocompleteResponse,
+    FoodCategoriesResponse, FoodCategory, FoodCategoryId, FoodId, FoodSearchResponse,
+    FoodSearchResult, FoodSubCategoriesResponse, FoodSubCategory, FoodSubCategoryEntry,
+    FoodSuggestion, Nutrition, Serving, ServingId,
 };
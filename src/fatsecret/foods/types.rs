@@ -52,6 +52,12 @@
 //! - [`FoodAutocompleteResponse`] - Autocomplete suggestions
 //! - [`FoodSuggestion`] - Single suggestion (id + name only)
 //!
+//! ## Brands & Categories
+//!
+//! - [`Brand`], [`BrandId`], [`BrandType`] - Food brands from `food_brands.get.v2`
+//! - [`FoodCategory`], [`FoodCategoryId`] - Top-level categories from `food_categories.get.v2`
+//! - [`FoodSubCategory`] - Sub-categories from `food_sub_categories.get`
+//!
 //! # API Response Mapping
 //!
 //! `FatSecret` returns inconsistent JSON shapes. This module handles:
@@ -437,13 +443,29 @@ pub struct FoodSearchResult {
     pub food_name: String,
     /// Type of food (e.g., "Generic", "Brand")
     pub food_type: String,
-    /// Brief description including nutrition summary
+    /// Brief description including nutrition summary (absent in `foods.search.v3`)
+    #[serde(default)]
     pub food_description: String,
     /// Brand name for branded foods
     #[serde(skip_serializing_if = "Option::is_none")]
     pub brand_name: Option<String>,
     /// URL to the food details on `FatSecret`
     pub food_url: String,
+    /// Sub-categories the food belongs to (`foods.search.v3` with `include_sub_categories`)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub food_sub_categories: Option<FoodSearchSubCategories>,
+}
+
+/// Sub-category names attached to a search result
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct FoodSearchSubCategories {
+    /// Sub-category names (e.g., "Bread", "Whole Wheat Bread")
+    #[serde(
+        rename = "food_sub_category",
+        default,
+        deserialize_with = "deserialize_single_or_vec"
+    )]
+    pub names: Vec<String>,
 }
 
 /// Response from `foods.search` API
@@ -487,3 +509,227 @@ pub struct FoodAutocompleteResponse {
     #[serde(rename = "suggestion", deserialize_with = "deserialize_single_or_vec")]
     pub suggestions: Vec<FoodSuggestion>,
 }
+
+// ============================================================================
+// Brands
+// ============================================================================
+
+/// Opaque brand ID from `FatSecret` API
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct BrandId(String);
+
+impl BrandId {
+    /// Creates a new `BrandId` from the given value
+    pub fn new(id: impl Into<String>) -> Self {
+        Self(id.into())
+    }
+
+    /// Returns the brand ID as a string slice
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+}
+
+impl From<String> for BrandId {
+    fn from(s: String) -> Self {
+        Self(s)
+    }
+}
+
+impl From<&str> for BrandId {
+    fn from(s: &str) -> Self {
+        Self(s.to_string())
+    }
+}
+
+impl std::fmt::Display for BrandId {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+/// Kind of food brand
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum BrandType {
+    /// Food manufacturers and packaged goods brands
+    Manufacturer,
+    /// Restaurant and fast food chains
+    Restaurant,
+    /// Supermarket and store brands
+    Supermarket,
+}
+
+impl BrandType {
+    /// Value sent as the `brand_type` API parameter
+    pub fn to_api_string(self) -> &'static str {
+        match self {
+            Self::Manufacturer => "manufacturer",
+            Self::Restaurant => "restaurant",
+            Self::Supermarket => "supermarket",
+        }
+    }
+}
+
+impl std::fmt::Display for BrandType {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.to_api_string())
+    }
+}
+
+/// A food brand
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Brand {
+    /// Unique identifier for the brand
+    pub brand_id: BrandId,
+    /// Display name of the brand
+    pub brand_name: String,
+    /// Type of brand (not always returned)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub brand_type: Option<BrandType>,
+}
+
+/// Response from `food_brands.get.v2` API
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct BrandListResponse {
+    /// Matching brands (may be empty)
+    #[serde(
+        rename = "brand",
+        default,
+        deserialize_with = "deserialize_single_or_vec"
+    )]
+    pub brands: Vec<Brand>,
+}
+
+// ============================================================================
+// Categories
+// ============================================================================
+
+/// Opaque food category ID from `FatSecret` API
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct FoodCategoryId(String);
+
+impl FoodCategoryId {
+    /// Creates a new `FoodCategoryId` from the given value
+    pub fn new(id: impl Into<String>) -> Self {
+        Self(id.into())
+    }
+
+    /// Returns the category ID as a string slice
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+}
+
+impl From<String> for FoodCategoryId {
+    fn from(s: String) -> Self {
+        Self(s)
+    }
+}
+
+impl From<&str> for FoodCategoryId {
+    fn from(s: &str) -> Self {
+        Self(s.to_string())
+    }
+}
+
+impl std::fmt::Display for FoodCategoryId {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+/// A top-level food category
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FoodCategory {
+    /// Unique identifier for this category
+    pub food_category_id: FoodCategoryId,
+    /// Display name of the category (e.g., "Fruits")
+    pub food_category_name: String,
+    /// Description of what the category contains
+    #[serde(default)]
+    pub food_category_description: String,
+}
+
+/// Response from `food_categories.get.v2` API
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct FoodCategoriesResponse {
+    /// List of food categories
+    #[serde(
+        rename = "food_category",
+        default,
+        deserialize_with = "deserialize_single_or_vec"
+    )]
+    pub categories: Vec<FoodCategory>,
+}
+
+/// A sub-category within a food category
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FoodSubCategory {
+    /// Unique identifier for this sub-category
+    pub food_sub_category_id: String,
+    /// Display name of the sub-category (e.g., "Citrus Fruits")
+    pub food_sub_category_name: String,
+    /// Description of what the sub-category contains
+    #[serde(default)]
+    pub food_sub_category_description: String,
+}
+
+/// Response from `food_sub_categories.get` API
+///
+/// The API returns either full sub-category objects or bare names depending on
+/// version, so both shapes are accepted.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct FoodSubCategoriesResponse {
+    /// List of sub-categories
+    #[serde(
+        rename = "food_sub_category",
+        default,
+        deserialize_with = "deserialize_sub_categories"
+    )]
+    pub sub_categories: Vec<FoodSubCategoryEntry>,
+}
+
+/// Like `deserialize_single_or_vec`, but tries the sequence first: serde would
+/// otherwise read a list of names as a single [`FoodSubCategory`] struct
+fn deserialize_sub_categories<'de, D>(
+    deserializer: D,
+) -> Result<Vec<FoodSubCategoryEntry>, D::Error>
+where
+    D: serde::Deserializer<'de>,
+{
+    #[derive(Deserialize)]
+    #[serde(untagged)]
+    enum VecOrSingle {
+        Vec(Vec<FoodSubCategoryEntry>),
+        Single(FoodSubCategoryEntry),
+    }
+
+    Ok(match Option::<VecOrSingle>::deserialize(deserializer)? {
+        Some(VecOrSingle::Vec(entries)) => entries,
+        Some(VecOrSingle::Single(entry)) => vec![entry],
+        None => Vec::new(),
+    })
+}
+
+/// Sub-category as returned by `food_sub_categories.get`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum FoodSubCategoryEntry {
+    /// Full sub-category object
+    Detailed(FoodSubCategory),
+    /// Sub-category name only
+    Name(String),
+}
+
+impl FoodSubCategoryEntry {
+    /// Display name of the sub-category
+    pub fn name(&self) -> &str {
+        match self {
+            Self::Detailed(sub) => &sub.food_sub_category_name,
+            Self::Name(name) => name,
+        }
+    }
+}
@@ -0,0 +1,156 @@
+//! Access token health checks
+//!
+//! FatSecret can revoke an access token at any time (the user disconnects the
+//! app, or the token is reset on their side). Scripts then fail with an opaque
+//! auth error on their next call. [`check_token_health`] probes the stored
+//! token with a cheap authenticated call (`profile.get`), classifies the
+//! outcome and records it in storage:
+//!
+//! - success → [`TokenHealth::Healthy`], `last_used_at` is updated
+//! - an auth-related API error ([`ApiErrorCode::is_auth_related`]) →
+//!   [`TokenHealth::Revoked`], the token is marked revoked so that
+//!   `check_token_validity` reports [`TokenValidity::Revoked`](super::TokenValidity::Revoked)
+//! - anything else (network, rate limit, …) → [`TokenHealth::Unknown`]; the
+//!   stored state is left alone
+//!
+//! The `fatsecret_token_health` binary wraps this and, when re-authorization
+//! is needed, starts a new OAuth flow and returns the authorization URL.
+//!
+//! [`ApiErrorCode::is_auth_related`]: super::core::ApiErrorCode::is_auth_related
+
+use super::core::{AccessToken, FatSecretConfig, FatSecretError};
+use super::profile::get_profile;
+use super::storage::TokenStore;
+use super::StorageError;
+use serde::Serialize;
+
+/// Outcome of probing a user's access token
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(tag = "status", rename_all = "snake_case")]
+pub enum TokenHealth {
+    /// The token works
+    Healthy,
+    /// No token is stored for the user
+    NotConnected,
+    /// FatSecret rejected the token with an auth-related error
+    Revoked {
+        /// FatSecret API error code
+        code: i32,
+        /// Error message from FatSecret
+        message: String,
+    },
+    /// The probe failed for a reason unrelated to the token
+    Unknown {
+        /// Error description
+        error: String,
+    },
+}
+
+impl TokenHealth {
+    /// Classify the result of an authenticated API call
+    pub fn from_probe<T>(result: &Result<T, FatSecretError>) -> Self {
+        match result {
+            Ok(_) => Self::Healthy,
+            Err(FatSecretError::ApiError { code, message }) if code.is_auth_related() => {
+                Self::Revoked {
+                    code: code.to_code(),
+                    message: message.clone(),
+                }
+            }
+            Err(e) => Self::Unknown {
+                error: e.to_string(),
+            },
+        }
+    }
+
+    /// Whether the user must go through the OAuth flow again
+    #[must_use]
+    pub const fn reauth_required(&self) -> bool {
+        matches!(self, Self::NotConnected | Self::Revoked { .. })
+    }
+}
+
+/// Probe `token` with `profile.get`
+pub async fn probe_token(config: &FatSecretConfig, token: &AccessToken) -> TokenHealth {
+    TokenHealth::from_probe(&get_profile(config, token).await)
+}
+
+/// Probe the stored token of `storage`'s user and record the result
+///
+/// Only storage failures are returned as errors; API failures are part of
+/// the [`TokenHealth`].
+pub async fn check_token_health(
+    config: &FatSecretConfig,
+    storage: &impl TokenStore,
+) -> Result<TokenHealth, StorageError> {
+    let Some(token) = storage.get_access_token().await? else {
+        return Ok(TokenHealth::NotConnected);
+    };
+
+    let health = probe_token(config, &token).await;
+    match health {
+        TokenHealth::Healthy => storage.update_last_used().await?,
+        TokenHealth::Revoked { .. } => storage.mark_token_revoked().await?,
+        TokenHealth::NotConnected | TokenHealth::Unknown { .. } => {}
+    }
+
+    Ok(health)
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod tests {
+    use super::*;
+
+    fn probe(result: &Result<(), FatSecretError>) -> TokenHealth {
+        TokenHealth::from_probe(result)
+    }
+
+    #[test]
+    fn test_success_is_healthy() {
+        assert_eq!(probe(&Ok(())), TokenHealth::Healthy);
+        assert!(!TokenHealth::Healthy.reauth_required());
+    }
+
+    #[test]
+    fn test_invalid_access_token_is_revoked() {
+        let health = probe(&Err(FatSecretError::api_error(9, "Invalid access token")));
+        assert_eq!(
+            health,
+            TokenHealth::Revoked {
+                code: 9,
+                message: "Invalid access token".to_string(),
+            }
+        );
+        assert!(health.reauth_required());
+    }
+
+    #[test]
+    fn test_other_errors_are_unknown() {
+        for err in [
+            FatSecretError::api_error(22, "Rate limit exceeded"),
+            FatSecretError::NetworkError("connection reset".to_string()),
+        ] {
+            let health = probe(&Err(err));
+            assert!(matches!(health, TokenHealth::Unknown { .. }));
+            assert!(!health.reauth_required());
+        }
+    }
+
+    #[test]
+    fn test_serializes_with_status_tag() {
+        assert_eq!(
+            serde_json::to_value(TokenHealth::NotConnected).unwrap(),
+            serde_json::json!({"status": "not_connected"})
+        );
+        assert_eq!(
+            serde_json::to_value(TokenHealth::Revoked {
+                code: 13,
+                message: "Invalid token".to_string(),
+            })
+            .unwrap(),
+            serde_json::json!({"status": "revoked", "code": 13, "message": "Invalid token"})
+        );
+        assert!(TokenHealth::NotConnected.reauth_required());
+    }
+}
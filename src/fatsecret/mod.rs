@@ -22,6 +22,7 @@
 //! - [`core`] - OAuth client, HTTP utilities, error types, configuration
 //! - [`crypto`] - Encryption/decryption for secure token storage
 //! - [`storage`] - SQLx-based persistent OAuth token storage
+//! - [`health`] - Access token probing and revocation tracking
 //!
 //! ## API Domains
 //! - [`diary`] - Food diary entries and daily/monthly summaries
@@ -29,6 +30,7 @@
 //! - [`exercise`] - Exercise entries and activity tracking
 //! - [`favorites`] - Favorite foods, recipes, most/recently eaten
 //! - [`recipes`] - Recipe search, details, ingredients, directions
+//! - [`recognition`] - Natural language and photo food logging
 //! - [`weight`] - Weight entries and historical tracking
 //! - [`profile`] - User profile and authentication
 //! - [`saved_meals`] - Meal templates for quick food logging
@@ -47,10 +49,11 @@
 //!
 //! ## Token Storage and Encryption
 //!
-//! Tokens are stored securely in `PostgreSQL` with AES-256-GCM encryption:
+//! Tokens are stored securely in `PostgreSQL` or SQLite with AES-256-GCM encryption:
 //!
 //! - Requires `OAUTH_ENCRYPTION_KEY` environment variable (32-byte base64)
-//! - Use [TokenStorage] for persistent token management
+//! - Use [TokenStorage] for persistent token management, one token per user
+//!   (`TokenStorage::new(db).with_user("alex")`)
 //! - Use [`crypto`] module for encryption/decryption primitives
 //!
 //! # Example Usage
@@ -61,7 +64,7 @@
 //! use meal_planner::fatsecret::{
 //!     core::{FatSecretConfig, AccessToken},
 //!     diary::{get_food_entries, FoodEntryInput, MealType, create_food_entry},
-//!     storage::TokenStorage,
+//!     storage::{TokenStorage, TokenStore},
 //! };
 //! use sqlx::PgPool;
 //!
@@ -101,7 +104,7 @@
 //! ```rust,no_run
 //! use meal_planner::fatsecret::{
 //!     core::{FatSecretConfig, oauth::{get_request_token, get_access_token}},
-//!     storage::TokenStorage,
+//!     storage::{TokenStorage, TokenStore},
 //! };
 //! use sqlx::PgPool;
 //!
@@ -186,7 +189,7 @@
 //! - FATSECRET_CONSUMER_KEY - OAuth consumer key (required)
 //! - FATSECRET_CONSUMER_SECRET - OAuth consumer secret (required)
 //! - `OAUTH_ENCRYPTION_KEY` - 32-byte base64 key for token encryption (required for storage)
-//! - DATABASE_URL - `PostgreSQL` connection string (required for token storage)
+//! - DATABASE_URL - `PostgreSQL` or SQLite connection string (required for token storage)
 //!
 //! # Further Reading
 //!
@@ -204,6 +207,9 @@ pub mod crypto;
 /// Persistent token storage with encryption support
 pub mod storage;
 
+/// Access token health checks and revocation tracking
+pub mod health;
+
 /// Food diary entries and daily/monthly summaries
 pub mod diary;
 
@@ -222,6 +228,9 @@ pub mod profile;
 /// Recipe search and details
 pub mod recipes;
 
+/// Food recognition from natural language descriptions and meal photos
+pub mod recognition;
+
 /// Saved meal templates for quick food logging
 pub mod saved_meals;
 
@@ -274,11 +283,18 @@ pub use diary::{
 
 // Re-export crypto types
 pub use crypto::{
-    decrypt, encrypt, encryption_configured, generate_key, CryptoError, StorageError, TokenValidity,
+    decrypt, encrypt, encryption_configured, generate_key, CryptoError, EncryptionKey, KeyRing,
+    StorageError, TokenValidity,
 };
 
 // Re-export OAuth types
 pub use core::{AccessToken, RequestToken};
 
 // Re-export storage
-pub use storage::TokenStorage;
+pub use storage::{
+    validate_user_id, DiarySyncStorage, DiarySyncStore, RotationProgress, RotationReport,
+    TableRotation, TokenStorage, TokenStore, DEFAULT_USER,
+};
+
+// Re-export token health checks
+pub use health::{check_token_health, probe_token, TokenHealth};
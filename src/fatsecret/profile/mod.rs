@@ -42,7 +42,12 @@
 //!
 //! # async fn example() -> Result<(), Box<dyn std::error::Error>> {
 //! let config = FatSecretConfig::from_env()?;
-//! let app_token = AccessToken::from_client_credentials(&config).await?;
+//! // profile.create is signed with OAuth 1.0a; bearer tokens from
+//! // `core::oauth2` only cover public endpoints
+//! let app_token = AccessToken::new(
+//!     std::env::var("FATSECRET_ACCESS_TOKEN")?,
+//!     std::env::var("FATSECRET_ACCESS_SECRET")?,
+//! );
 //!
 //! // Create a new user profile
 //! let profile_auth = create_profile(
@@ -54,10 +59,10 @@
 //! println!("Profile created! OAuth token: {}", profile_auth.auth_token);
 //!
 //! // Convert ProfileAuth to AccessToken for user-specific API calls
-//! let user_token = AccessToken {
-//!     token: profile_auth.auth_token.clone(),
-//!     secret: profile_auth.auth_secret.clone(),
-//! };
+//! let user_token = AccessToken::new(
+//!     profile_auth.auth_token.clone(),
+//!     profile_auth.auth_secret.clone(),
+//! );
 //!
 //! // Get user's profile data
 //! let profile = get_profile(&config, &user_token).await?;
@@ -83,7 +88,7 @@
 //! # use meal_planner::fatsecret::profile::create_profile;
 //! # async fn example() -> Result<(), Box<dyn std::error::Error>> {
 //! # let config = FatSecretConfig::from_env()?;
-//! # let app_token = AccessToken::from_client_credentials(&config).await?;
+//! # let app_token = AccessToken::new("app-token", "app-secret");
 //! // On user signup
 //! let profile_auth = create_profile(&config, &app_token, "user-456").await?;
 //! // Store profile_auth.auth_token and profile_auth.auth_secret in your DB
@@ -91,10 +96,7 @@
 //!
 //! // On subsequent requests
 //! // let stored_auth = db.get_profile_auth("user-456");
-//! // let user_token = AccessToken {
-//! //     token: stored_auth.auth_token,
-//! //     secret: stored_auth.auth_secret,
-//! // };
+//! // let user_token = AccessToken::new(stored_auth.auth_token, stored_auth.auth_secret);
 //! // Now use user_token for all API calls on behalf of this user
 //! # Ok(())
 //! # }
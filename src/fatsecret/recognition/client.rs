@@ -0,0 +1,191 @@
+//! `FatSecret` food recognition client functions
+//!
+//! These endpoints are REST-only and require OAuth 2.0; requests go through
+//! [`make_rest_request`] with the endpoint's scope added to the configured ones.
+
+use crate::fatsecret::core::{
+    make_rest_request, ApiErrorCode, FatSecretConfig, FatSecretError, OAuth2Scope,
+};
+use crate::fatsecret::recognition::types::{
+    ImageRecognitionRequest, NlpRequest, RecognitionResponse,
+};
+
+/// REST path of the natural language processing endpoint
+pub const NLP_PATH: &str = "natural-language-processing/v1";
+
+/// REST path of the image recognition endpoint
+pub const IMAGE_RECOGNITION_PATH: &str = "image-recognition/v1";
+
+/// API error code for "no food item detected"
+///
+/// The recognition endpoints reuse code 211 (elsewhere "maximum limit
+/// reached"), so it is matched by number rather than [`ApiErrorCode`] variant.
+pub const NO_FOOD_DETECTED_CODE: i32 = 211;
+
+/// Turn a free-text meal description into foods with suggested servings
+///
+/// Requires the `nlp` scope. Input that mentions no recognizable food returns
+/// an empty response rather than an error.
+pub async fn parse_natural_language(
+    config: &FatSecretConfig,
+    request: &NlpRequest,
+) -> Result<RecognitionResponse, FatSecretError> {
+    let body = serde_json::to_value(request)?;
+    let scopes = config.oauth2_scopes_with(OAuth2Scope::Nlp);
+    recognition_response(make_rest_request(config, &scopes, NLP_PATH, &body).await)
+}
+
+/// Detect foods in a base64-encoded meal photo
+///
+/// Requires the `image-recognition` scope. An image with no recognizable food
+/// returns an empty response rather than an error.
+pub async fn recognize_image(
+    config: &FatSecretConfig,
+    request: &ImageRecognitionRequest,
+) -> Result<RecognitionResponse, FatSecretError> {
+    let body = serde_json::to_value(request)?;
+    let scopes = config.oauth2_scopes_with(OAuth2Scope::ImageRecognition);
+    recognition_response(make_rest_request(config, &scopes, IMAGE_RECOGNITION_PATH, &body).await)
+}
+
+/// Parse a recognition response, mapping "no food detected" to an empty result
+pub(crate) fn recognition_response(
+    result: Result<String, FatSecretError>,
+) -> Result<RecognitionResponse, FatSecretError> {
+    match result {
+        Ok(body) => Ok(serde_json::from_str(&body)?),
+        Err(e) if e.api_error_code().map(ApiErrorCode::to_code) == Some(NO_FOOD_DETECTED_CODE) => {
+            Ok(RecognitionResponse::default())
+        }
+        Err(e) => Err(e),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fatsecret::recognition::types::encode_image;
+    use wiremock::matchers::{body_partial_json, header, method, path};
+    use wiremock::{Mock, MockServer, ResponseTemplate};
+
+    async fn stub_server() -> (MockServer, FatSecretConfig) {
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(path("/connect/token"))
+            .respond_with(ResponseTemplate::new(200).set_body_string(
+                r#"{"access_token": "stub-token", "expires_in": 86400, "token_type": "Bearer"}"#,
+            ))
+            .mount(&server)
+            .await;
+        let mut config = FatSecretConfig::new("stub-client-abcdefg", "abcdefghijklmnopqrstuvwxyz")
+            .unwrap()
+            .with_oauth2_token_url(format!("{}/connect/token", server.uri()));
+        config.api_host = Some(server.uri());
+        (server, config)
+    }
+
+    #[tokio::test]
+    async fn test_parse_natural_language() {
+        let (server, config) = stub_server().await;
+        Mock::given(method("POST"))
+            .and(path("/rest/natural-language-processing/v1"))
+            .and(header("authorization", "Bearer stub-token"))
+            .and(body_partial_json(serde_json::json!({"user_input": "2 eggs"})))
+            .respond_with(ResponseTemplate::new(200).set_body_string(
+                r#"{"food_response": {"food_id": 3094, "food_entry_name": "Egg",
+                    "eaten": {"total_nutritional_content": {"calories": 143, "carbohydrate": 0.7, "protein": 12.6, "fat": 9.5}},
+                    "suggested_serving": {"serving_id": 11783, "serving_description": "1 large", "number_of_units": 2}}}"#,
+            ))
+            .mount(&server)
+            .await;
+
+        let request = NlpRequest {
+            user_input: "2 eggs".to_string(),
+            ..NlpRequest::default()
+        };
+        let response = parse_natural_language(&config, &request).await.unwrap();
+        assert_eq!(response.food_response.len(), 1);
+        assert_eq!(response.food_response[0].food_entry_name, "Egg");
+    }
+
+    #[tokio::test]
+    async fn test_no_food_detected_is_empty() {
+        let (server, config) = stub_server().await;
+        Mock::given(method("POST"))
+            .and(path("/rest/natural-language-processing/v1"))
+            .respond_with(
+                ResponseTemplate::new(400).set_body_string(
+                    r#"{"error": {"code": 211, "message": "No food item detected"}}"#,
+                ),
+            )
+            .mount(&server)
+            .await;
+
+        let request = NlpRequest {
+            user_input: "went for a walk".to_string(),
+            ..NlpRequest::default()
+        };
+        let response = parse_natural_language(&config, &request).await.unwrap();
+        assert!(response.food_response.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_recognize_image() {
+        let (server, config) = stub_server().await;
+        let image_b64 = encode_image(b"not really a jpeg").unwrap();
+        Mock::given(method("POST"))
+            .and(path("/rest/image-recognition/v1"))
+            .and(header("authorization", "Bearer stub-token"))
+            .and(body_partial_json(serde_json::json!({"image_b64": image_b64.clone(), "include_food_data": true})))
+            .respond_with(ResponseTemplate::new(200).set_body_string(
+                r#"{"food_response": [
+                    {"food_id": 4881, "food_entry_name": "Spaghetti Bolognese",
+                     "eaten": {"food_names": ["Spaghetti Bolognese"], "metric_info": {"metric_serving_amount": 350, "metric_serving_unit": "g"},
+                               "total_nutritional_content": {"calories": 480, "carbohydrate": 55, "protein": 24, "fat": 17}},
+                     "suggested_serving": {"serving_id": 20034, "serving_description": "1 cup", "number_of_units": 1.5},
+                     "food": {"food_id": "4881", "food_name": "Spaghetti Bolognese"}},
+                    {"food_id": 36774, "food_entry_name": "Parmesan Cheese",
+                     "eaten": {"total_nutritional_content": {"calories": 22, "carbohydrate": 0.2, "protein": 1.9, "fat": 1.4}},
+                     "suggested_serving": {"serving_id": 51290, "serving_description": "1 tbsp", "number_of_units": 1}}
+                ]}"#,
+            ))
+            .expect(1)
+            .mount(&server)
+            .await;
+
+        let request = ImageRecognitionRequest {
+            image_b64,
+            include_food_data: true,
+            ..ImageRecognitionRequest::default()
+        };
+        let response = recognize_image(&config, &request).await.unwrap();
+        assert_eq!(response.food_response.len(), 2);
+        assert!(response.food_response[0].food.is_some());
+        let total = response.total_nutrition();
+        assert!((total.calories - 502.0).abs() < 1e-9);
+    }
+
+    #[tokio::test]
+    async fn test_recognize_image_api_error() {
+        let (server, config) = stub_server().await;
+        Mock::given(method("POST"))
+            .and(path("/rest/image-recognition/v1"))
+            .respond_with(
+                ResponseTemplate::new(400).set_body_string(
+                    r#"{"error": {"code": 12, "message": "Method not accessible"}}"#,
+                ),
+            )
+            .mount(&server)
+            .await;
+
+        let request = ImageRecognitionRequest {
+            image_b64: encode_image(b"photo").unwrap(),
+            ..ImageRecognitionRequest::default()
+        };
+        let error = recognize_image(&config, &request).await.unwrap_err();
+        assert_eq!(
+            error.api_error_code(),
+            Some(&ApiErrorCode::MethodNotAccessible)
+        );
+    }
+}
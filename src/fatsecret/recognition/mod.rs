@@ -0,0 +1,52 @@
+//! `FatSecret` food recognition (natural language processing and image recognition)
+//!
+//! Turns a description of a meal ("2 eggs and a slice of toast") or a meal
+//! photo into `FatSecret` foods with suggested servings and nutrition, ready
+//! to log.
+//!
+//! # Key Types
+//!
+//! - [`NlpRequest`] - Free text plus optional previously eaten foods as context
+//! - [`ImageRecognitionRequest`] - Base64 photo (see [`encode_image`]) plus the same context
+//! - [`RecognitionResponse`] - Detected foods ([`RecognizedFood`])
+//! - [`SuggestedServing`] - Serving and number of units to log
+//!
+//! # Logging Results
+//!
+//! [`RecognitionResponse::to_food_entries`] maps each detected food to a
+//! `FoodEntryInput::FromFood` for [`crate::fatsecret::diary::create_food_entry`].
+//! Show the results to the user before logging; recognition is a best guess.
+//!
+//! # Authentication
+//!
+//! OAuth 2.0 only, with the `nlp` or `image-recognition` scope. The scope is
+//! added to the config's scopes automatically.
+//!
+//! ```no_run
+//! use meal_planner::fatsecret::core::FatSecretConfig;
+//! use meal_planner::fatsecret::diary::MealType;
+//! use meal_planner::fatsecret::recognition::{parse_natural_language, NlpRequest};
+//!
+//! # async fn example() -> Result<(), Box<dyn std::error::Error>> {
+//! let config = FatSecretConfig::from_env()?;
+//! let request = NlpRequest {
+//!     user_input: "2 eggs and a slice of toast".to_string(),
+//!     ..NlpRequest::default()
+//! };
+//! let response = parse_natural_language(&config, &request).await?;
+//! for food in &response.food_response {
+//!     println!("{}: {}", food.food_entry_name, food.suggested_serving.serving_description);
+//! }
+//! let entries = response.to_food_entries(MealType::Breakfast, 20_000);
+//! # Ok(())
+//! # }
+//! ```
+
+pub mod client;
+pub mod types;
+
+pub use client::{parse_natural_language, recognize_image};
+pub use types::{
+    encode_image, validate_user_input, EatenFood, ImageRecognitionRequest, MetricInfo, NlpRequest,
+    RecognitionResponse, RecognizedFood, RecognizedNutrition, SuggestedServing,
+};
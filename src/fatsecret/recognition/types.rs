@@ -0,0 +1,359 @@
+//! `FatSecret` food recognition types
+//!
+//! Natural language processing and image recognition take similar requests
+//! (input plus optional eaten-food context) and return the same
+//! `food_response` shape: each detected food with what was eaten, its total
+//! nutrition and a suggested serving to log.
+
+use base64::Engine;
+use serde::{Deserialize, Serialize};
+
+use crate::fatsecret::core::serde_utils::{
+    deserialize_flexible_float, deserialize_flexible_i64, deserialize_optional_flexible_float,
+    deserialize_single_or_vec,
+};
+use crate::fatsecret::diary::{FoodEntryInput, MealType};
+
+/// Maximum `user_input` length accepted by the NLP endpoint
+pub const MAX_USER_INPUT_CHARS: usize = 1000;
+
+/// Maximum `image_b64` length accepted by the image recognition endpoint
+pub const MAX_IMAGE_B64_CHARS: usize = 999_982;
+
+// ============================================================================
+// Requests
+// ============================================================================
+
+/// A previously eaten food, sent as context to improve matching
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct EatenFood {
+    /// `FatSecret` food ID
+    #[serde(deserialize_with = "deserialize_flexible_i64")]
+    pub food_id: i64,
+    /// Name of the food
+    pub food_name: String,
+    /// Brand name (if applicable)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub food_brand: Option<String>,
+    /// Description of the serving size (e.g., "1 slice")
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub serving_description: Option<String>,
+    /// Size of the serving
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub serving_size: Option<f64>,
+}
+
+/// Request body for natural-language-processing/v1
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct NlpRequest {
+    /// What was eaten, e.g. "2 eggs and a slice of toast"
+    pub user_input: String,
+    /// Include full food data for each detected food
+    #[serde(default)]
+    pub include_food_data: bool,
+    /// Previously eaten foods, used as matching context
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub eaten_foods: Vec<EatenFood>,
+    /// Region filter (Premier)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub region: Option<String>,
+    /// Response language (Premier)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub language: Option<String>,
+}
+
+/// Request body for image-recognition/v1
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ImageRecognitionRequest {
+    /// Base64-encoded meal photo (see [`encode_image`])
+    pub image_b64: String,
+    /// Include full food data for each detected food
+    #[serde(default)]
+    pub include_food_data: bool,
+    /// Previously eaten foods, used as matching context
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub eaten_foods: Vec<EatenFood>,
+    /// Region filter (Premier)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub region: Option<String>,
+    /// Response language (Premier)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub language: Option<String>,
+}
+
+/// Base64-encode image bytes, checking the result fits [`MAX_IMAGE_B64_CHARS`]
+pub fn encode_image(bytes: &[u8]) -> Result<String, String> {
+    if bytes.is_empty() {
+        return Err("image is empty".to_string());
+    }
+    let encoded = base64::engine::general_purpose::STANDARD.encode(bytes);
+    if encoded.len() > MAX_IMAGE_B64_CHARS {
+        return Err(format!(
+            "image is {} bytes ({} base64 characters); the limit is {MAX_IMAGE_B64_CHARS} \
+             characters, so resize or compress it to under about 730 KB",
+            bytes.len(),
+            encoded.len()
+        ));
+    }
+    Ok(encoded)
+}
+
+/// Check `user_input` before sending it (non-empty, at most [`MAX_USER_INPUT_CHARS`])
+pub fn validate_user_input(user_input: &str) -> Result<(), String> {
+    let chars = user_input.trim().chars().count();
+    if chars == 0 {
+        return Err("user_input must not be empty".to_string());
+    }
+    if chars > MAX_USER_INPUT_CHARS {
+        return Err(format!(
+            "user_input is {chars} characters; the limit is {MAX_USER_INPUT_CHARS}"
+        ));
+    }
+    Ok(())
+}
+
+// ============================================================================
+// Responses
+// ============================================================================
+
+/// Nutrition for the detected amount
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct RecognizedNutrition {
+    /// Calories in kcal
+    #[serde(default, deserialize_with = "deserialize_flexible_float")]
+    pub calories: f64,
+    /// Carbohydrate in grams
+    #[serde(default, deserialize_with = "deserialize_flexible_float")]
+    pub carbohydrate: f64,
+    /// Protein in grams
+    #[serde(default, deserialize_with = "deserialize_flexible_float")]
+    pub protein: f64,
+    /// Fat in grams
+    #[serde(default, deserialize_with = "deserialize_flexible_float")]
+    pub fat: f64,
+    /// Fiber in grams
+    #[serde(
+        default,
+        skip_serializing_if = "Option::is_none",
+        deserialize_with = "deserialize_optional_flexible_float"
+    )]
+    pub fiber: Option<f64>,
+    /// Sugar in grams
+    #[serde(
+        default,
+        skip_serializing_if = "Option::is_none",
+        deserialize_with = "deserialize_optional_flexible_float"
+    )]
+    pub sugar: Option<f64>,
+}
+
+/// Metric size of the detected amount
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct MetricInfo {
+    /// Amount (e.g., 30)
+    #[serde(deserialize_with = "deserialize_flexible_float")]
+    pub metric_serving_amount: f64,
+    /// Unit (e.g., "g")
+    pub metric_serving_unit: String,
+}
+
+/// What the recognizer thinks was eaten
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct Eaten {
+    /// Possible names for the food
+    #[serde(default, deserialize_with = "deserialize_single_or_vec")]
+    pub food_names: Vec<String>,
+    /// Descriptions of the eaten amount (e.g., "1 slice of toast")
+    #[serde(default, deserialize_with = "deserialize_single_or_vec")]
+    pub descriptions: Vec<String>,
+    /// Units the amount can be measured in
+    #[serde(default, deserialize_with = "deserialize_single_or_vec")]
+    pub units: Vec<String>,
+    /// Metric size of the eaten amount
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub metric_info: Option<MetricInfo>,
+    /// Nutrition for the eaten amount
+    #[serde(default)]
+    pub total_nutritional_content: RecognizedNutrition,
+}
+
+/// Serving the recognizer suggests logging
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SuggestedServing {
+    /// `FatSecret` serving ID
+    #[serde(deserialize_with = "deserialize_flexible_i64")]
+    pub serving_id: i64,
+    /// Human-readable serving (e.g., "1 slice")
+    pub serving_description: String,
+    /// Number of servings eaten
+    #[serde(deserialize_with = "deserialize_flexible_float")]
+    pub number_of_units: f64,
+}
+
+/// A food detected in text or an image
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RecognizedFood {
+    /// `FatSecret` food ID
+    #[serde(deserialize_with = "deserialize_flexible_i64")]
+    pub food_id: i64,
+    /// Display name for the diary entry
+    pub food_entry_name: String,
+    /// What was eaten
+    #[serde(default)]
+    pub eaten: Eaten,
+    /// Serving to log
+    pub suggested_serving: SuggestedServing,
+    /// Full food details (only with `include_food_data`)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub food: Option<serde_json::Value>,
+}
+
+impl RecognizedFood {
+    /// Diary entry for the suggested serving
+    pub fn to_food_entry(&self, meal: MealType, date_int: i32) -> FoodEntryInput {
+        FoodEntryInput::FromFood {
+            food_id: self.food_id.to_string(),
+            food_entry_name: self.food_entry_name.clone(),
+            serving_id: self.suggested_serving.serving_id.to_string(),
+            number_of_units: self.suggested_serving.number_of_units,
+            meal,
+            date_int,
+        }
+    }
+}
+
+/// Response from the NLP and image recognition endpoints
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct RecognitionResponse {
+    /// Detected foods (empty when nothing was recognized)
+    #[serde(default, deserialize_with = "deserialize_single_or_vec")]
+    pub food_response: Vec<RecognizedFood>,
+}
+
+impl RecognitionResponse {
+    /// Total nutrition across all detected foods
+    pub fn total_nutrition(&self) -> RecognizedNutrition {
+        self.food_response
+            .iter()
+            .map(|food| &food.eaten.total_nutritional_content)
+            .fold(RecognizedNutrition::default(), |total, n| {
+                RecognizedNutrition {
+                    calories: total.calories + n.calories,
+                    carbohydrate: total.carbohydrate + n.carbohydrate,
+                    protein: total.protein + n.protein,
+                    fat: total.fat + n.fat,
+                    fiber: sum_optional(total.fiber, n.fiber),
+                    sugar: sum_optional(total.sugar, n.sugar),
+                }
+            })
+    }
+
+    /// Diary entries for every detected food
+    pub fn to_food_entries(&self, meal: MealType, date_int: i32) -> Vec<FoodEntryInput> {
+        self.food_response
+            .iter()
+            .map(|food| food.to_food_entry(meal, date_int))
+            .collect()
+    }
+}
+
+fn sum_optional(a: Option<f64>, b: Option<f64>) -> Option<f64> {
+    match (a, b) {
+        (None, None) => None,
+        (a, b) => Some(a.unwrap_or(0.0) + b.unwrap_or(0.0)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const TOAST_AND_BUTTER: &str = r#"{"food_response": [
+        {"food_id": 33099, "food_entry_name": "Toast",
+         "eaten": {"food_names": ["Toast", "White Toast"], "descriptions": ["1 slice of toast"],
+                   "units": ["slice"], "metric_info": {"metric_serving_amount": 30, "metric_serving_unit": "g"},
+                   "total_nutritional_content": {"calories": 79, "carbohydrate": 15, "protein": 2.7, "fat": 1, "fiber": 0.8}},
+         "suggested_serving": {"serving_id": 47512, "serving_description": "1 slice", "number_of_units": 1}},
+        {"food_id": "36774", "food_entry_name": "Butter",
+         "eaten": {"food_names": ["Butter"], "total_nutritional_content": {"calories": "36", "carbohydrate": 0, "protein": 0, "fat": 4.1}},
+         "suggested_serving": {"serving_id": "51284", "serving_description": "1 pat", "number_of_units": "1.5"}}
+    ]}"#;
+
+    #[test]
+    fn test_parse_response() {
+        let response: RecognitionResponse = serde_json::from_str(TOAST_AND_BUTTER).unwrap();
+        assert_eq!(response.food_response.len(), 2);
+        let butter = &response.food_response[1];
+        assert_eq!(butter.food_id, 36774);
+        assert_eq!(butter.suggested_serving.serving_id, 51284);
+        assert!((butter.suggested_serving.number_of_units - 1.5).abs() < f64::EPSILON);
+        assert!(butter.eaten.metric_info.is_none());
+
+        let empty: RecognitionResponse = serde_json::from_str("{}").unwrap();
+        assert!(empty.food_response.is_empty());
+    }
+
+    #[test]
+    fn test_total_nutrition() {
+        let response: RecognitionResponse = serde_json::from_str(TOAST_AND_BUTTER).unwrap();
+        let total = response.total_nutrition();
+        assert!((total.calories - 115.0).abs() < 1e-9);
+        assert!((total.fat - 5.1).abs() < 1e-9);
+        assert_eq!(total.fiber, Some(0.8));
+        assert_eq!(total.sugar, None);
+    }
+
+    #[test]
+    fn test_to_food_entries() {
+        let response: RecognitionResponse = serde_json::from_str(TOAST_AND_BUTTER).unwrap();
+        let entries = response.to_food_entries(MealType::Breakfast, 19_737);
+        assert_eq!(entries.len(), 2);
+        match &entries[1] {
+            FoodEntryInput::FromFood {
+                food_id,
+                serving_id,
+                number_of_units,
+                meal,
+                date_int,
+                ..
+            } => {
+                assert_eq!(food_id, "36774");
+                assert_eq!(serving_id, "51284");
+                assert!((number_of_units - 1.5).abs() < f64::EPSILON);
+                assert_eq!(*meal, MealType::Breakfast);
+                assert_eq!(*date_int, 19_737);
+            }
+            FoodEntryInput::Custom { .. } => panic!("expected FromFood entry"),
+        }
+    }
+
+    #[test]
+    fn test_validate_user_input() {
+        assert!(validate_user_input("2 eggs and a slice of toast").is_ok());
+        assert!(validate_user_input("   ").is_err());
+        assert!(validate_user_input(&"a".repeat(MAX_USER_INPUT_CHARS)).is_ok());
+        assert!(validate_user_input(&"a".repeat(MAX_USER_INPUT_CHARS + 1)).is_err());
+    }
+
+    #[test]
+    fn test_encode_image() {
+        assert_eq!(encode_image(b"jpeg").unwrap(), "anBlZw==");
+        assert!(encode_image(&[]).is_err());
+        // 3 bytes encode to 4 characters: 749,985 bytes -> 999,980 characters
+        let limit_bytes = 749_985;
+        assert!(encode_image(&vec![0; limit_bytes]).is_ok());
+        assert!(encode_image(&vec![0; limit_bytes + 1]).is_err());
+    }
+
+    #[test]
+    fn test_nlp_request_skips_empty_context() {
+        let request = NlpRequest {
+            user_input: "2 eggs".to_string(),
+            ..NlpRequest::default()
+        };
+        assert_eq!(
+            serde_json::to_value(&request).unwrap(),
+            serde_json::json!({"user_input": "2 eggs", "include_food_data": false})
+        );
+    }
+}
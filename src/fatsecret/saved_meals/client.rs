@@ -6,8 +6,8 @@ use crate::fatsecret::core::http::make_authenticated_request;
 use crate::fatsecret::core::oauth::AccessToken;
 use crate::fatsecret::core::serde_utils::SuccessResponse;
 use crate::fatsecret::saved_meals::types::{
-    MealType, SavedMeal, SavedMealId, SavedMealItem, SavedMealItemsResponseWrapper,
-    SavedMealsResponseWrapper,
+    MealType, SavedMeal, SavedMealId, SavedMealItem, SavedMealItemAdd, SavedMealItemId,
+    SavedMealItemsResponseWrapper, SavedMealsResponseWrapper,
 };
 use std::collections::HashMap;
 
@@ -180,3 +180,123 @@ pub async fn edit_saved_meal(
 
     Ok(())
 }
+
+/// Add a food to a saved meal (`saved_meal_item.add.v2` - 3-legged)
+pub async fn add_saved_meal_item(
+    config: &FatSecretConfig,
+    access_token: &AccessToken,
+    saved_meal_id: &SavedMealId,
+    item: &SavedMealItemAdd,
+) -> Result<SavedMealItemId, FatSecretError> {
+    let mut params = HashMap::new();
+    params.insert(
+        "saved_meal_id".to_string(),
+        saved_meal_id.as_str().to_string(),
+    );
+    params.insert("food_id".to_string(), item.food_id.clone());
+    params.insert("food_entry_name".to_string(), item.food_entry_name.clone());
+    params.insert("serving_id".to_string(), item.serving_id.clone());
+    params.insert(
+        "number_of_units".to_string(),
+        item.number_of_units.to_string(),
+    );
+
+    let body =
+        make_authenticated_request(config, access_token, "saved_meal_item.add.v2", params).await?;
+
+    #[derive(serde::Deserialize)]
+    #[serde(untagged)]
+    enum ItemIdValue {
+        Wrapped { value: String },
+        Plain(String),
+    }
+
+    #[derive(serde::Deserialize)]
+    struct AddResponse {
+        saved_meal_item_id: ItemIdValue,
+    }
+
+    let response: AddResponse = serde_json::from_str(&body).map_err(|e| {
+        FatSecretError::ParseError(format!(
+            "Failed to parse add item response: {}. Body: {}",
+            e, body
+        ))
+    })?;
+
+    Ok(match response.saved_meal_item_id {
+        ItemIdValue::Wrapped { value } | ItemIdValue::Plain(value) => SavedMealItemId::new(value),
+    })
+}
+
+/// Edit a saved meal item (`saved_meal_item.edit.v2` - 3-legged)
+pub async fn edit_saved_meal_item(
+    config: &FatSecretConfig,
+    access_token: &AccessToken,
+    saved_meal_item_id: &SavedMealItemId,
+    item_name: Option<&str>,
+    number_of_units: Option<f64>,
+) -> Result<(), FatSecretError> {
+    let mut params = HashMap::new();
+    params.insert(
+        "saved_meal_item_id".to_string(),
+        saved_meal_item_id.as_str().to_string(),
+    );
+
+    if let Some(n) = item_name {
+        params.insert("saved_meal_item_name".to_string(), n.to_string());
+    }
+
+    if let Some(units) = number_of_units {
+        params.insert("number_of_units".to_string(), units.to_string());
+    }
+
+    let body =
+        make_authenticated_request(config, access_token, "saved_meal_item.edit.v2", params).await?;
+    let response: SuccessResponse = serde_json::from_str(&body).map_err(|e| {
+        FatSecretError::ParseError(format!(
+            "Failed to parse edit item response: {}. Body: {}",
+            e, body
+        ))
+    })?;
+
+    if !response.is_success() {
+        return Err(FatSecretError::RequestFailed {
+            status: 400,
+            body: "Edit item operation did not return success".to_string(),
+        });
+    }
+
+    Ok(())
+}
+
+/// Remove an item from a saved meal (`saved_meal_item.delete.v2` - 3-legged)
+pub async fn delete_saved_meal_item(
+    config: &FatSecretConfig,
+    access_token: &AccessToken,
+    saved_meal_item_id: &SavedMealItemId,
+) -> Result<(), FatSecretError> {
+    let mut params = HashMap::new();
+    params.insert(
+        "saved_meal_item_id".to_string(),
+        saved_meal_item_id.as_str().to_string(),
+    );
+
+    let body =
+        make_authenticated_request(config, access_token, "saved_meal_item.delete.v2", params)
+            .await?;
+    let response: SuccessResponse = serde_json::from_str(&body).map_err(|e| {
+        FatSecretError::ParseError(format!(
+            "Failed to parse delete item response: {}. Body: {}",
+            e, body
+        ))
+    })?;
+
+    if !response.is_success() {
+        return Err(FatSecretError::RequestFailed {
+            status: 400,
+            body: "Delete item operation did not return success".to_string(),
+        });
+    }
+
+    Ok(())
+}
@@ -50,6 +50,11 @@
 //! - [`client::create_saved_meal_item`] - Add item to existing meal
 //! - [`client::delete_saved_meal_item`] - Remove item from meal
 //!
+//! ## Publishing Tandoor Recipes
+//!
+//! - [`publish::publish_recipe`] - Create or update a saved meal from a Tandoor recipe,
+//!   matching each ingredient to a `FatSecret` food and serving
+//!
 //! ## Quick Logging
 //!
 //! - [`client::log_saved_meal`] - Log entire saved meal to food diary
@@ -188,6 +193,7 @@
 
 pub mod client;
 pub mod flow_validation;
+pub mod publish;
 pub mod types;
 
 pub use client::*;
@@ -0,0 +1,649 @@
+//! Publish Tandoor recipes as `FatSecret` saved meals
+//!
+//! Resolves each recipe ingredient to a `FatSecret` food and serving, then
+//! creates (or updates in place) a saved meal holding those items so the
+//! recipe can be logged with one tap.
+//!
+//! The matching and diffing logic is PURE and unit tested here; only
+//! [`publish_recipe`] talks to the API.
+//!
+//! Published meals are recognised on re-run by a marker in their description
+//! (`tandoor-recipe:{id}`), so the same saved meal is edited rather than a new
+//! one created. Items are reconciled by food and serving: changed amounts are
+//! edited, new ingredients added and removed ingredients deleted.
+
+use std::collections::HashMap;
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use crate::fatsecret::core::{AccessToken, FatSecretConfig, FatSecretError};
+use crate::fatsecret::foods::filter::best_match;
+use crate::fatsecret::foods::{get_food, search_foods, FoodId, Serving};
+use crate::fatsecret::saved_meals::client::{
+    add_saved_meal_item, create_saved_meal, delete_saved_meal_item, edit_saved_meal,
+    edit_saved_meal_item, get_saved_meal_items, get_saved_meals,
+};
+use crate::fatsecret::saved_meals::types::{
+    MealType, SavedMeal, SavedMealId, SavedMealItem, SavedMealItemAdd, SavedMealItemId,
+};
+use crate::tandoor::nutrition::extract_ingredient_info;
+
+/// Number of search results considered when resolving an ingredient
+const SEARCH_RESULTS: u32 = 10;
+
+/// Units treated as "one of the thing" (matched against the default serving)
+const COUNT_UNITS: &[&str] = &["", "piece", "pc", "whole", "each", "item", "serving"];
+
+/// A single ingredient line from a Tandoor recipe
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct RecipeIngredient {
+    /// Food name
+    pub name: String,
+    /// Amount in `unit`
+    pub amount: f64,
+    /// Unit name (empty for counted items)
+    pub unit: String,
+}
+
+/// Manually chosen `FatSecret` food for an ingredient
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct FoodOverride {
+    /// `FatSecret` food ID
+    pub food_id: String,
+    /// Serving ID (optional - otherwise chosen from the ingredient's unit)
+    #[serde(default)]
+    pub serving_id: Option<String>,
+}
+
+/// Options for [`publish_recipe`]
+#[derive(Debug, Clone)]
+pub struct PublishOptions {
+    /// Number of recipe servings one logged meal represents
+    pub portions: f64,
+    /// Meals the saved meal is offered for
+    pub meals: Vec<MealType>,
+    /// Lowercase ingredient name → food override
+    pub food_overrides: HashMap<String, FoodOverride>,
+}
+
+impl Default for PublishOptions {
+    fn default() -> Self {
+        Self {
+            portions: 1.0,
+            meals: vec![
+                MealType::Breakfast,
+                MealType::Lunch,
+                MealType::Dinner,
+                MealType::Snack,
+            ],
+            food_overrides: HashMap::new(),
+        }
+    }
+}
+
+/// An ingredient matched to a `FatSecret` food and serving
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct ResolvedItem {
+    /// Tandoor ingredient name
+    pub ingredient: String,
+    /// Item to add to the saved meal
+    pub item: SavedMealItemAdd,
+}
+
+/// An ingredient that could not be matched
+#[derive(Debug, Clone, Serialize)]
+pub struct UnresolvedIngredient {
+    /// Tandoor ingredient name
+    pub ingredient: String,
+    /// Why it could not be matched
+    pub reason: String,
+}
+
+/// Changes needed to bring a saved meal's items in line with the recipe
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ItemChanges {
+    /// Items to add
+    pub add: Vec<SavedMealItemAdd>,
+    /// Existing items whose amount changed
+    pub update: Vec<(SavedMealItemId, f64)>,
+    /// Existing items no longer in the recipe
+    pub remove: Vec<SavedMealItemId>,
+    /// Number of items already up to date
+    pub unchanged: usize,
+}
+
+/// Outcome of publishing a recipe
+#[derive(Debug, Clone, Serialize)]
+pub struct PublishReport {
+    /// Saved meal ID
+    pub saved_meal_id: SavedMealId,
+    /// Saved meal name
+    pub saved_meal_name: String,
+    /// True if the saved meal was newly created (false if updated in place)
+    pub created: bool,
+    /// Ingredients matched to `FatSecret` foods
+    pub resolved: Vec<ResolvedItem>,
+    /// Ingredients left out of the saved meal
+    pub unresolved: Vec<UnresolvedIngredient>,
+    /// Items added
+    pub items_added: usize,
+    /// Items whose amount was edited
+    pub items_updated: usize,
+    /// Items removed
+    pub items_removed: usize,
+    /// Items already up to date
+    pub items_unchanged: usize,
+}
+
+// ============================================================================
+// Pure helpers
+// ============================================================================
+
+/// Description marker identifying the saved meal published for a recipe
+pub fn recipe_marker(recipe_id: i64) -> String {
+    format!("tandoor-recipe:{recipe_id}")
+}
+
+/// Find a saved meal previously published for this recipe
+pub fn find_published(meals: &[SavedMeal], recipe_id: i64) -> Option<&SavedMeal> {
+    let marker = recipe_marker(recipe_id);
+    meals.iter().find(|meal| {
+        meal.saved_meal_description
+            .as_deref()
+            .is_some_and(|d| d.split_whitespace().any(|word| word == marker))
+    })
+}
+
+/// Ingredient lines from a Tandoor recipe (section headers are skipped)
+pub fn recipe_ingredients(recipe: &Value) -> Vec<RecipeIngredient> {
+    recipe
+        .get("steps")
+        .and_then(Value::as_array)
+        .into_iter()
+        .flatten()
+        .filter_map(|step| step.get("ingredients").and_then(Value::as_array))
+        .flatten()
+        .filter(|ingredient| {
+            !ingredient
+                .get("is_header")
+                .and_then(Value::as_bool)
+                .unwrap_or(false)
+        })
+        .map(extract_ingredient_info)
+        .filter(|(name, _, _)| !name.trim().is_empty())
+        .map(|(name, amount, unit)| RecipeIngredient {
+            name: name.trim().to_string(),
+            amount,
+            unit,
+        })
+        .collect()
+}
+
+/// Normalise a unit name ("Tablespoons" → "tablespoon", "g." → "g")
+fn normalize_unit(unit: &str) -> String {
+    let lower = unit.trim().trim_end_matches('.').to_lowercase();
+    match lower.as_str() {
+        "gram" | "grams" | "gr" => "g".to_string(),
+        "tbsp" | "tbs" | "tablespoons" => "tablespoon".to_string(),
+        "tsp" | "teaspoons" => "teaspoon".to_string(),
+        "cups" => "cup".to_string(),
+        "milliliter" | "milliliters" | "millilitre" | "millilitres" => "ml".to_string(),
+        "ounce" | "ounces" => "oz".to_string(),
+        "pieces" | "pcs" => "piece".to_string(),
+        _ => lower,
+    }
+}
+
+/// Convert an amount to grams or millilitres when the unit allows it
+fn to_metric(amount: f64, unit: &str) -> Option<(f64, &'static str)> {
+    match unit {
+        "g" => Some((amount, "g")),
+        "kg" => Some((amount * 1000.0, "g")),
+        "oz" => Some((amount * 28.3495, "g")),
+        "lb" | "lbs" | "pound" | "pounds" => Some((amount * 453.592, "g")),
+        "ml" => Some((amount, "ml")),
+        "l" | "liter" | "liters" | "litre" | "litres" => Some((amount * 1000.0, "ml")),
+        "cup" => Some((amount * 240.0, "ml")),
+        "tablespoon" => Some((amount * 15.0, "ml")),
+        "teaspoon" => Some((amount * 5.0, "ml")),
+        _ => None,
+    }
+}
+
+/// Choose a serving for an ingredient and compute its number of units
+///
+/// Tries, in order: a serving named after the unit ("cup", "tbsp"), a metric
+/// serving in grams/millilitres, then the default serving for counted items.
+pub fn choose_serving<'a>(
+    servings: &'a [Serving],
+    amount: f64,
+    unit: &str,
+) -> Option<(&'a Serving, f64)> {
+    let unit = normalize_unit(unit);
+
+    let by_name = servings.iter().find(|s| {
+        !COUNT_UNITS.contains(&unit.as_str())
+            && s.number_of_units > 0.0
+            && normalize_unit(&s.measurement_description) == unit
+    });
+    if let Some(serving) = by_name {
+        return Some((serving, amount / serving.number_of_units));
+    }
+
+    if let Some((quantity, metric_unit)) = to_metric(amount, &unit) {
+        return servings.iter().find_map(|s| {
+            let serving_amount = s.metric_serving_amount.filter(|a| *a > 0.0)?;
+            let serving_unit = normalize_unit(s.metric_serving_unit.as_deref()?);
+            (serving_unit == metric_unit).then_some((s, quantity / serving_amount))
+        });
+    }
+
+    if COUNT_UNITS.contains(&unit.as_str()) {
+        let serving = servings
+            .iter()
+            .find(|s| s.is_default == Some(1))
+            .or_else(|| servings.first())?;
+        let per_serving = if serving.number_of_units > 0.0 {
+            serving.number_of_units
+        } else {
+            1.0
+        };
+        return Some((serving, amount / per_serving));
+    }
+
+    None
+}
+
+/// Round units to FatSecret's precision so re-runs compare cleanly
+fn round_units(units: f64) -> f64 {
+    (units * 1000.0).round() / 1000.0
+}
+
+/// Merge resolved items that share a food and serving
+fn merge_items(resolved: &[ResolvedItem]) -> Vec<SavedMealItemAdd> {
+    let mut merged: Vec<SavedMealItemAdd> = Vec::new();
+    for ResolvedItem { item, .. } in resolved {
+        match merged
+            .iter_mut()
+            .find(|m| m.food_id == item.food_id && m.serving_id == item.serving_id)
+        {
+            Some(existing) => existing.number_of_units += item.number_of_units,
+            None => merged.push(item.clone()),
+        }
+    }
+    for item in &mut merged {
+        item.number_of_units = round_units(item.number_of_units);
+    }
+    merged
+}
+
+/// Work out which items to add, edit and delete on an existing saved meal
+pub fn plan_item_changes(existing: &[SavedMealItem], resolved: &[ResolvedItem]) -> ItemChanges {
+    let mut remaining: Vec<&SavedMealItem> = existing.iter().collect();
+    let mut changes = ItemChanges::default();
+
+    for item in merge_items(resolved) {
+        let position = remaining
+            .iter()
+            .position(|e| e.food_id == item.food_id && e.serving_id == item.serving_id);
+        match position.map(|index| remaining.swap_remove(index)) {
+            Some(current) if (current.number_of_units - item.number_of_units).abs() < 1e-3 => {
+                changes.unchanged += 1;
+            }
+            Some(current) => changes
+                .update
+                .push((current.saved_meal_item_id.clone(), item.number_of_units)),
+            None => changes.add.push(item),
+        }
+    }
+
+    changes.remove = remaining
+        .into_iter()
+        .map(|e| e.saved_meal_item_id.clone())
+        .collect();
+    changes
+}
+
+// ============================================================================
+// API orchestration
+// ============================================================================
+
+/// Resolve one ingredient to a saved meal item
+async fn resolve_ingredient(
+    config: &FatSecretConfig,
+    ingredient: &RecipeIngredient,
+    scale: f64,
+    food_override: Option<&FoodOverride>,
+) -> Result<ResolvedItem, String> {
+    if ingredient.amount <= 0.0 {
+        return Err("No amount given".to_string());
+    }
+
+    let food_id = if let Some(o) = food_override {
+        FoodId::new(o.food_id.clone())
+    } else {
+        let results = search_foods(config, &ingredient.name, 0, SEARCH_RESULTS)
+            .await
+            .map_err(|e| format!("Search failed: {e}"))?;
+        best_match(&results.foods, &ingredient.name)
+            .ok_or("No FatSecret food found")?
+            .food_id
+            .clone()
+    };
+    let food = get_food(config, &food_id)
+        .await
+        .map_err(|e| format!("Failed to load food {food_id}: {e}"))?;
+
+    let servings = &food.servings.serving;
+    let chosen = food_override
+        .and_then(|o| o.serving_id.as_deref())
+        .map_or_else(
+            || choose_serving(servings, ingredient.amount, &ingredient.unit),
+            |serving_id| {
+                servings
+                    .iter()
+                    .find(|s| s.serving_id.as_str() == serving_id)
+                    .map(|s| (s, ingredient.amount / s.number_of_units.max(f64::EPSILON)))
+            },
+        );
+    let (serving, units) = chosen.ok_or_else(|| {
+        format!(
+            "No serving of '{}' matches unit '{}'",
+            food.food_name, ingredient.unit
+        )
+    })?;
+
+    Ok(ResolvedItem {
+        ingredient: ingredient.name.clone(),
+        item: SavedMealItemAdd {
+            food_id: food.food_id.as_str().to_string(),
+            food_entry_name: food.food_name.clone(),
+            serving_id: serving.serving_id.as_str().to_string(),
+            number_of_units: round_units(units * scale),
+        },
+    })
+}
+
+/// Resolve every ingredient, splitting matches from failures
+async fn resolve_ingredients(
+    config: &FatSecretConfig,
+    ingredients: &[RecipeIngredient],
+    scale: f64,
+    options: &PublishOptions,
+) -> (Vec<ResolvedItem>, Vec<UnresolvedIngredient>) {
+    let mut resolved = Vec::new();
+    let mut unresolved = Vec::new();
+    for ingredient in ingredients {
+        let food_override = options.food_overrides.get(&ingredient.name.to_lowercase());
+        match resolve_ingredient(config, ingredient, scale, food_override).await {
+            Ok(item) => resolved.push(item),
+            Err(reason) => unresolved.push(UnresolvedIngredient {
+                ingredient: ingredient.name.clone(),
+                reason,
+            }),
+        }
+    }
+    (resolved, unresolved)
+}
+
+/// Apply item changes to a saved meal
+async fn apply_item_changes(
+    config: &FatSecretConfig,
+    token: &AccessToken,
+    saved_meal_id: &SavedMealId,
+    changes: &ItemChanges,
+) -> Result<(), FatSecretError> {
+    for item_id in &changes.remove {
+        delete_saved_meal_item(config, token, item_id).await?;
+    }
+    for (item_id, units) in &changes.update {
+        edit_saved_meal_item(config, token, item_id, None, Some(*units)).await?;
+    }
+    for item in &changes.add {
+        add_saved_meal_item(config, token, saved_meal_id, item).await?;
+    }
+    Ok(())
+}
+
+/// Update the saved meal published for a recipe, or create it
+///
+/// Returns the saved meal ID, whether it was created, and its current items.
+async fn upsert_saved_meal(
+    config: &FatSecretConfig,
+    token: &AccessToken,
+    recipe_id: i64,
+    name: &str,
+    meals: &[MealType],
+) -> Result<(SavedMealId, bool, Vec<SavedMealItem>), FatSecretError> {
+    let description = format!("From Tandoor {}", recipe_marker(recipe_id));
+    let saved_meals = get_saved_meals(config, token, None).await?;
+
+    if let Some(meal) = find_published(&saved_meals, recipe_id) {
+        let id = meal.saved_meal_id.clone();
+        edit_saved_meal(
+            config,
+            token,
+            &id,
+            Some(name),
+            Some(&description),
+            Some(meals),
+        )
+        .await?;
+        let items = get_saved_meal_items(config, token, &id).await?;
+        Ok((id, false, items))
+    } else {
+        let id = create_saved_meal(config, token, name, Some(&description), meals).await?;
+        Ok((id, true, Vec::new()))
+    }
+}
+
+/// Publish a Tandoor recipe (full recipe JSON) as a `FatSecret` saved meal
+///
+/// Creates the saved meal on first run and updates it in place afterwards.
+/// Ingredients that can't be matched are reported and left out.
+pub async fn publish_recipe(
+    config: &FatSecretConfig,
+    token: &AccessToken,
+    recipe: &Value,
+    options: &PublishOptions,
+) -> Result<PublishReport, FatSecretError> {
+    let recipe_id = recipe
+        .get("id")
+        .and_then(Value::as_i64)
+        .ok_or_else(|| FatSecretError::ParseError("Recipe has no id".to_string()))?;
+    let name = recipe
+        .get("name")
+        .and_then(Value::as_str)
+        .unwrap_or("Tandoor recipe")
+        .to_string();
+    let servings = recipe
+        .get("servings")
+        .and_then(Value::as_f64)
+        .filter(|s| *s > 0.0)
+        .unwrap_or(1.0);
+    let (resolved, unresolved) = resolve_ingredients(
+        config,
+        &recipe_ingredients(recipe),
+        options.portions / servings,
+        options,
+    )
+    .await;
+
+    let (saved_meal_id, created, existing) =
+        upsert_saved_meal(config, token, recipe_id, &name, &options.meals).await?;
+
+    let changes = plan_item_changes(&existing, &resolved);
+    apply_item_changes(config, token, &saved_meal_id, &changes).await?;
+
+    Ok(PublishReport {
+        saved_meal_id,
+        saved_meal_name: name,
+        created,
+        resolved,
+        unresolved,
+        items_added: changes.add.len(),
+        items_updated: changes.update.len(),
+        items_removed: changes.remove.len(),
+        items_unchanged: changes.unchanged,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fatsecret::foods::FoodSearchResult;
+    use serde_json::json;
+
+    fn serving(id: &str, description: &str, units: f64, metric: Option<(f64, &str)>) -> Serving {
+        let mut value = json!({
+            "serving_id": id,
+            "serving_description": description,
+            "serving_url": "",
+            "number_of_units": units,
+            "measurement_description": description,
+            "calories": "100",
+            "carbohydrate": "10",
+            "protein": "5",
+            "fat": "2"
+        });
+        if let Some((amount, unit)) = metric {
+            value["metric_serving_amount"] = json!(amount);
+            value["metric_serving_unit"] = json!(unit);
+        }
+        serde_json::from_value(value).unwrap()
+    }
+
+    fn saved_item(id: &str, food_id: &str, serving_id: &str, units: f64) -> SavedMealItem {
+        serde_json::from_value(json!({
+            "saved_meal_item_id": id,
+            "food_id": food_id,
+            "food_entry_name": "Food",
+            "serving_id": serving_id,
+            "number_of_units": units,
+            "calories": 0, "carbohydrate": 0, "protein": 0, "fat": 0
+        }))
+        .unwrap()
+    }
+
+    fn resolved(food_id: &str, serving_id: &str, units: f64) -> ResolvedItem {
+        ResolvedItem {
+            ingredient: food_id.to_string(),
+            item: SavedMealItemAdd {
+                food_id: food_id.to_string(),
+                food_entry_name: "Food".to_string(),
+                serving_id: serving_id.to_string(),
+                number_of_units: units,
+            },
+        }
+    }
+
+    #[test]
+    fn test_recipe_ingredients_skips_headers() {
+        let recipe = json!({"steps": [{"ingredients": [
+            {"food": {"name": "Flour"}, "unit": {"name": "g"}, "amount": 200},
+            {"food": {"name": "For the sauce"}, "is_header": true, "amount": 0},
+            {"food": null, "amount": 1}
+        ]}]});
+        let ingredients = recipe_ingredients(&recipe);
+        assert_eq!(ingredients.len(), 1);
+        assert_eq!(ingredients[0].name, "Flour");
+        assert_eq!(ingredients[0].unit, "g");
+    }
+
+    #[test]
+    fn test_choose_serving_by_unit_name() {
+        let servings = [
+            serving("1", "cup", 1.0, Some((240.0, "ml"))),
+            serving("2", "tbsp", 1.0, Some((15.0, "ml"))),
+        ];
+        let (chosen, units) = choose_serving(&servings, 3.0, "Tablespoons").unwrap();
+        assert_eq!(chosen.serving_id.as_str(), "2");
+        assert!((units - 3.0).abs() < f64::EPSILON);
+    }
+
+    #[test]
+    fn test_choose_serving_metric_grams() {
+        let servings = [
+            serving("1", "medium", 1.0, Some((118.0, "g"))),
+            serving("2", "g", 100.0, Some((100.0, "g"))),
+        ];
+        let (chosen, units) = choose_serving(&servings, 0.25, "kg").unwrap();
+        assert_eq!(chosen.serving_id.as_str(), "1");
+        assert!((units - 250.0 / 118.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_choose_serving_counted_uses_default() {
+        let mut default = serving("2", "large", 1.0, Some((50.0, "g")));
+        default.is_default = Some(1);
+        let servings = [serving("1", "medium", 1.0, None), default];
+        let (chosen, units) = choose_serving(&servings, 3.0, "").unwrap();
+        assert_eq!(chosen.serving_id.as_str(), "2");
+        assert!((units - 3.0).abs() < f64::EPSILON);
+    }
+
+    #[test]
+    fn test_choose_serving_unknown_unit() {
+        let servings = [serving("1", "medium", 1.0, None)];
+        assert!(choose_serving(&servings, 1.0, "pinch").is_none());
+    }
+
+    #[test]
+    fn test_best_match_prefers_generic_exact() {
+        let results: Vec<FoodSearchResult> = serde_json::from_value(json!([
+            {"food_id": "1", "food_name": "Banana Chips", "food_type": "Brand", "food_description": "", "food_url": ""},
+            {"food_id": "2", "food_name": "Banana Bread", "food_type": "Generic", "food_description": "", "food_url": ""},
+            {"food_id": "3", "food_name": "Banana", "food_type": "Generic", "food_description": "", "food_url": ""}
+        ]))
+        .unwrap();
+        let picked = best_match(&results, "banana").unwrap();
+        assert_eq!(picked.food_id.as_str(), "3");
+        assert!(best_match(&[], "banana").is_none());
+    }
+
+    #[test]
+    fn test_find_published_by_marker() {
+        let meals: Vec<SavedMeal> = serde_json::from_value(json!([
+            {"saved_meal_id": "1", "saved_meal_name": "A", "saved_meal_description": "From Tandoor tandoor-recipe:12",
+             "meals": "Dinner", "calories": 0, "carbohydrate": 0, "protein": 0, "fat": 0},
+            {"saved_meal_id": "2", "saved_meal_name": "B", "saved_meal_description": "From Tandoor tandoor-recipe:1",
+             "meals": "Dinner", "calories": 0, "carbohydrate": 0, "protein": 0, "fat": 0}
+        ]))
+        .unwrap();
+        assert_eq!(
+            find_published(&meals, 1).unwrap().saved_meal_id.as_str(),
+            "2"
+        );
+        assert!(find_published(&meals, 99).is_none());
+    }
+
+    #[test]
+    fn test_plan_item_changes_reconciles() {
+        let existing = [
+            saved_item("a", "10", "100", 2.0),
+            saved_item("b", "20", "200", 1.0),
+            saved_item("c", "30", "300", 1.0),
+        ];
+        let wanted = [
+            resolved("10", "100", 2.0),
+            resolved("20", "200", 1.0),
+            resolved("20", "200", 0.5),
+            resolved("40", "400", 1.0),
+        ];
+        let changes = plan_item_changes(&existing, &wanted);
+
+        assert_eq!(changes.unchanged, 1);
+        assert_eq!(changes.update, vec![(SavedMealItemId::new("b"), 1.5)]);
+        assert_eq!(changes.add.len(), 1);
+        assert_eq!(changes.add[0].food_id, "40");
+        assert_eq!(changes.remove, vec![SavedMealItemId::new("c")]);
+    }
+
+    #[test]
+    fn test_plan_item_changes_fresh_meal_adds_everything() {
+        let changes = plan_item_changes(&[], &[resolved("10", "100", 1.0)]);
+        assert_eq!(changes.add.len(), 1);
+        assert!(changes.update.is_empty() && changes.remove.is_empty());
+    }
+}
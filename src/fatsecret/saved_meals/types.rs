@@ -196,6 +196,19 @@ pub enum SavedMealItemInput {
     },
 }
 
+/// A `FatSecret` food to add to a saved meal (`saved_meal_item.add.v2`)
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SavedMealItemAdd {
+    /// `FatSecret` food ID
+    pub food_id: String,
+    /// Display name for the item
+    pub food_entry_name: String,
+    /// Serving size ID
+    pub serving_id: String,
+    /// Number of serving units
+    pub number_of_units: f64,
+}
+
 // ============================================================================
 // Response Wrappers
 // ============================================================================
@@ -0,0 +1,622 @@
+//! Token storage for OAuth authentication
+//!
+//! Handles storing and retrieving OAuth tokens with encryption using SQLx.
+//! Tokens are keyed by a user/profile identifier so several `FatSecret`
+//! accounts can be connected side by side; [`DEFAULT_USER`] is used when no
+//! user is given. Secrets can be re-encrypted under a new key with
+//! [`TokenStore::reencrypt_secrets`]. Also holds the idempotency ledger
+//! used by the meal plan → diary sync.
+//!
+//! # Backends
+//!
+//! Persistence is described by the [`TokenStore`] and [`DiarySyncStore`]
+//! traits, implemented for PostgreSQL ([`postgres`]) and SQLite ([`sqlite`]).
+//! [`TokenStorage`] and [`DiarySyncStorage`] pick the implementation from the
+//! [`Database`] they are given, which [`Database::connect`] chooses from the
+//! `DATABASE_URL` scheme:
+//!
+//! ```rust,no_run
+//! use meal_planner::db::Database;
+//! use meal_planner::fatsecret::storage::{TokenStorage, TokenStore};
+//!
+//! # async fn example() -> Result<(), Box<dyn std::error::Error>> {
+//! let db = Database::connect("sqlite://meal-planner.db").await?;
+//! let storage = TokenStorage::new(db).with_user("alex");
+//! storage.ensure_schema().await?;
+//! let token = storage.get_access_token().await?;
+//! # Ok(())
+//! # }
+//! ```
+
+pub mod postgres;
+pub mod sqlite;
+
+pub use postgres::{PgDiarySyncStore, PgTokenStore};
+pub use sqlite::{SqliteDiarySyncStore, SqliteTokenStore};
+
+use super::core::{AccessToken, RequestToken};
+use super::crypto::{decrypt, KeyRing};
+use super::StorageError;
+use super::TokenValidity;
+use crate::db::Database;
+use chrono::{DateTime, Duration, Utc};
+use serde::Serialize;
+use std::future::Future;
+
+/// User that tokens belong to when none is given
+///
+/// Rows from the single-user schema are migrated to this user. The value is
+/// also the column default in `migrations/0002_key_fatsecret_tokens_by_user.up.sql`.
+pub const DEFAULT_USER: &str = "default";
+
+/// Longest accepted user identifier
+pub const MAX_USER_ID_LEN: usize = 64;
+
+/// Check a user identifier
+///
+/// Identifiers are 1-64 characters of ASCII letters, digits, `.`, `_`, `-`
+/// or `@`, so an email address or a short name both work.
+pub fn validate_user_id(user: &str) -> Result<(), StorageError> {
+    if user.is_empty() || user.len() > MAX_USER_ID_LEN {
+        return Err(StorageError::InvalidUser(format!(
+            "user must be 1-{MAX_USER_ID_LEN} characters"
+        )));
+    }
+    if let Some(c) = user
+        .chars()
+        .find(|c| !(c.is_ascii_alphanumeric() || matches!(c, '.' | '_' | '-' | '@')))
+    {
+        return Err(StorageError::InvalidUser(format!(
+            "user contains invalid character {c:?}"
+        )));
+    }
+    Ok(())
+}
+
+/// Tables holding encrypted secrets, with the column identifying each row
+const SECRET_TABLES: [(&str, &str); 2] = [
+    ("fatsecret_oauth_token", "user_id"),
+    ("fatsecret_oauth_pending", "oauth_token"),
+];
+
+/// Progress of [`TokenStore::reencrypt_secrets`] through one table
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RotationProgress {
+    /// Table being re-encrypted
+    pub table: &'static str,
+    /// Rows processed so far
+    pub done: usize,
+    /// Rows in the table
+    pub total: usize,
+}
+
+/// Outcome of re-encrypting one table
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct TableRotation {
+    /// Table name
+    pub table: &'static str,
+    /// Rows in the table
+    pub total: usize,
+    /// Rows rewritten with the current key
+    pub reencrypted: usize,
+    /// Rows that already used the current key
+    pub already_current: usize,
+}
+
+/// Outcome of [`TokenStore::reencrypt_secrets`]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct RotationReport {
+    /// Key ID every secret is now encrypted with
+    pub key_id: String,
+    /// Whether the changes were rolled back
+    pub dry_run: bool,
+    /// Per-table counts
+    pub tables: Vec<TableRotation>,
+}
+
+/// Token storage for OAuth 3-legged flow
+///
+/// Manages both pending tokens (temporary, during auth flow)
+/// and access tokens (persistent, after successful auth) for one user.
+pub trait TokenStore: Clone + Send + Sync {
+    /// User the tokens belong to
+    fn user(&self) -> &str;
+
+    /// Scope the storage to another user
+    ///
+    /// Check untrusted identifiers with [`validate_user_id`] first.
+    #[must_use]
+    fn with_user(self, user: impl Into<String>) -> Self;
+
+    /// Create the token tables, migrating the single-user schema if present
+    ///
+    /// Runs the pending [`crate::db`] migrations. The old access token table
+    /// was a singleton keyed by `id = 1`; its row becomes the [`DEFAULT_USER`]
+    /// token. Pending tokens without a user are assigned to [`DEFAULT_USER`]
+    /// as well. Safe to run repeatedly.
+    fn ensure_schema(&self) -> impl Future<Output = Result<(), StorageError>> + Send;
+
+    /// Store a pending OAuth request token
+    ///
+    /// These tokens are temporary and used during 3-legged OAuth flow.
+    /// They expire after 15 minutes and belong to the user who started the flow.
+    fn store_pending_token(
+        &self,
+        token: &RequestToken,
+    ) -> impl Future<Output = Result<(), StorageError>> + Send;
+
+    /// Get a pending OAuth request token
+    ///
+    /// Returns None if token doesn't exist, has expired, or belongs to
+    /// another user.
+    fn get_pending_token(
+        &self,
+        oauth_token: &str,
+    ) -> impl Future<Output = Result<Option<RequestToken>, StorageError>> + Send;
+
+    /// Get the user who started the flow for a pending request token
+    ///
+    /// Looks across all users, for callbacks that only know the token.
+    /// Returns None if the token doesn't exist or has expired.
+    fn get_pending_token_user(
+        &self,
+        oauth_token: &str,
+    ) -> impl Future<Output = Result<Option<String>, StorageError>> + Send;
+
+    /// Get the user's most recent pending OAuth request token
+    ///
+    /// Returns None if no pending tokens exist or all have expired.
+    /// Useful for OOB flows where user doesn't have the token ID.
+    fn get_latest_pending_token(
+        &self,
+    ) -> impl Future<Output = Result<Option<RequestToken>, StorageError>> + Send;
+
+    /// Delete a pending token (after exchange for access token)
+    fn delete_pending_token(
+        &self,
+        oauth_token: &str,
+    ) -> impl Future<Output = Result<(), StorageError>> + Send;
+
+    /// Store an access token after successful OAuth flow
+    ///
+    /// Replaces any token the user already has.
+    fn store_access_token(
+        &self,
+        token: &AccessToken,
+    ) -> impl Future<Output = Result<(), StorageError>> + Send;
+
+    /// Get the user's stored access token
+    ///
+    /// Returns None if no token is stored.
+    fn get_access_token(
+        &self,
+    ) -> impl Future<Output = Result<Option<AccessToken>, StorageError>> + Send;
+
+    /// Update the `last_used_at` timestamp for the user's access token
+    ///
+    /// A token that is used successfully is no longer revoked, so this also
+    /// clears any revocation recorded by [`mark_token_revoked`](Self::mark_token_revoked).
+    fn update_last_used(&self) -> impl Future<Output = Result<(), StorageError>> + Send;
+
+    /// Record that FatSecret rejected the user's access token
+    ///
+    /// `check_token_validity` reports [`TokenValidity::Revoked`] until a new
+    /// token is stored or the token is used successfully again.
+    fn mark_token_revoked(&self) -> impl Future<Output = Result<(), StorageError>> + Send;
+
+    /// Check if the user has a token and whether it is valid
+    ///
+    /// Returns a `TokenValidity` enum indicating the status.
+    fn check_token_validity(
+        &self,
+    ) -> impl Future<Output = Result<TokenValidity, StorageError>> + Send;
+
+    /// Delete the user's stored access token (disconnect account)
+    fn delete_access_token(&self) -> impl Future<Output = Result<(), StorageError>> + Send;
+
+    /// List users with a stored access token
+    fn list_users(&self) -> impl Future<Output = Result<Vec<String>, StorageError>> + Send;
+
+    /// Re-encrypt every stored secret, for all users, with the current key
+    ///
+    /// Both token tables are rewritten in one transaction: if any secret
+    /// cannot be decrypted with `keys`, nothing is changed. With `dry_run`
+    /// every secret is still decrypted and re-encrypted, but the transaction
+    /// is rolled back. `on_progress` is called after each row.
+    fn reencrypt_secrets(
+        &self,
+        keys: &KeyRing,
+        dry_run: bool,
+        on_progress: impl FnMut(&RotationProgress) + Send,
+    ) -> impl Future<Output = Result<RotationReport, StorageError>> + Send;
+
+    /// Cleanup expired pending tokens for all users
+    ///
+    /// Returns the number of tokens deleted.
+    fn cleanup_expired_tokens(&self) -> impl Future<Output = Result<u64, StorageError>> + Send;
+}
+
+/// Idempotency ledger for diary entries created by the meal plan sync
+///
+/// Each synced entry is recorded under a stable key (see
+/// [`crate::fatsecret::diary::sync::idempotency_key`]). A key is claimed
+/// *before* the diary entry is created, so a crash between the two steps
+/// never results in a duplicate entry on the next run.
+pub trait DiarySyncStore: Clone + Send + Sync {
+    /// Create the ledger table if it doesn't exist yet
+    ///
+    /// Runs the pending [`crate::db`] migrations.
+    fn ensure_schema(&self) -> impl Future<Output = Result<(), StorageError>> + Send;
+
+    /// Check whether a key has already been claimed (synced or in progress)
+    fn is_synced(
+        &self,
+        idempotency_key: &str,
+    ) -> impl Future<Output = Result<bool, StorageError>> + Send;
+
+    /// Claim a key before creating its diary entry
+    ///
+    /// Returns false if the key was already claimed by an earlier run.
+    fn claim(
+        &self,
+        idempotency_key: &str,
+        meal_plan_id: i64,
+        date_int: i32,
+    ) -> impl Future<Output = Result<bool, StorageError>> + Send;
+
+    /// Record the `FatSecret` entry created for a claimed key
+    fn complete(
+        &self,
+        idempotency_key: &str,
+        food_entry_id: &str,
+    ) -> impl Future<Output = Result<(), StorageError>> + Send;
+
+    /// Release a claim after the diary entry could not be created
+    fn release(
+        &self,
+        idempotency_key: &str,
+    ) -> impl Future<Output = Result<(), StorageError>> + Send;
+}
+
+/// Store for one of the supported backends
+#[derive(Debug, Clone)]
+enum Store<P, S> {
+    Postgres(P),
+    Sqlite(S),
+}
+
+/// Forward a call to the backend store in use
+macro_rules! dispatch {
+    ($store:expr, $inner:ident => $call:expr) => {
+        match $store {
+            Store::Postgres($inner) => $call,
+            Store::Sqlite($inner) => $call,
+        }
+    };
+}
+
+/// [`TokenStore`] for whichever backend a [`Database`] uses
+#[derive(Debug, Clone)]
+pub struct TokenStorage {
+    store: Store<PgTokenStore, SqliteTokenStore>,
+}
+
+impl TokenStorage {
+    /// Create new token storage for [`DEFAULT_USER`]
+    pub fn new(db: impl Into<Database>) -> Self {
+        let store = match db.into() {
+            Database::Postgres(pool) => Store::Postgres(PgTokenStore::new(pool)),
+            Database::Sqlite(pool) => Store::Sqlite(SqliteTokenStore::new(pool)),
+        };
+        Self { store }
+    }
+}
+
+impl TokenStore for TokenStorage {
+    fn user(&self) -> &str {
+        dispatch!(&self.store, s => s.user())
+    }
+
+    fn with_user(self, user: impl Into<String>) -> Self {
+        let store = match self.store {
+            Store::Postgres(s) => Store::Postgres(s.with_user(user)),
+            Store::Sqlite(s) => Store::Sqlite(s.with_user(user)),
+        };
+        Self { store }
+    }
+
+    async fn ensure_schema(&self) -> Result<(), StorageError> {
+        dispatch!(&self.store, s => s.ensure_schema().await)
+    }
+
+    async fn store_pending_token(&self, token: &RequestToken) -> Result<(), StorageError> {
+        dispatch!(&self.store, s => s.store_pending_token(token).await)
+    }
+
+    async fn get_pending_token(
+        &self,
+        oauth_token: &str,
+    ) -> Result<Option<RequestToken>, StorageError> {
+        dispatch!(&self.store, s => s.get_pending_token(oauth_token).await)
+    }
+
+    async fn get_pending_token_user(
+        &self,
+        oauth_token: &str,
+    ) -> Result<Option<String>, StorageError> {
+        dispatch!(&self.store, s => s.get_pending_token_user(oauth_token).await)
+    }
+
+    async fn get_latest_pending_token(&self) -> Result<Option<RequestToken>, StorageError> {
+        dispatch!(&self.store, s => s.get_latest_pending_token().await)
+    }
+
+    async fn delete_pending_token(&self, oauth_token: &str) -> Result<(), StorageError> {
+        dispatch!(&self.store, s => s.delete_pending_token(oauth_token).await)
+    }
+
+    async fn store_access_token(&self, token: &AccessToken) -> Result<(), StorageError> {
+        dispatch!(&self.store, s => s.store_access_token(token).await)
+    }
+
+    async fn get_access_token(&self) -> Result<Option<AccessToken>, StorageError> {
+        dispatch!(&self.store, s => s.get_access_token().await)
+    }
+
+    async fn update_last_used(&self) -> Result<(), StorageError> {
+        dispatch!(&self.store, s => s.update_last_used().await)
+    }
+
+    async fn mark_token_revoked(&self) -> Result<(), StorageError> {
+        dispatch!(&self.store, s => s.mark_token_revoked().await)
+    }
+
+    async fn check_token_validity(&self) -> Result<TokenValidity, StorageError> {
+        dispatch!(&self.store, s => s.check_token_validity().await)
+    }
+
+    async fn delete_access_token(&self) -> Result<(), StorageError> {
+        dispatch!(&self.store, s => s.delete_access_token().await)
+    }
+
+    async fn list_users(&self) -> Result<Vec<String>, StorageError> {
+        dispatch!(&self.store, s => s.list_users().await)
+    }
+
+    async fn reencrypt_secrets(
+        &self,
+        keys: &KeyRing,
+        dry_run: bool,
+        on_progress: impl FnMut(&RotationProgress) + Send,
+    ) -> Result<RotationReport, StorageError> {
+        dispatch!(&self.store, s => s.reencrypt_secrets(keys, dry_run, on_progress).await)
+    }
+
+    async fn cleanup_expired_tokens(&self) -> Result<u64, StorageError> {
+        dispatch!(&self.store, s => s.cleanup_expired_tokens().await)
+    }
+}
+
+/// [`DiarySyncStore`] for whichever backend a [`Database`] uses
+#[derive(Debug, Clone)]
+pub struct DiarySyncStorage {
+    store: Store<PgDiarySyncStore, SqliteDiarySyncStore>,
+}
+
+impl DiarySyncStorage {
+    /// Create new diary sync storage
+    pub fn new(db: impl Into<Database>) -> Self {
+        let store = match db.into() {
+            Database::Postgres(pool) => Store::Postgres(PgDiarySyncStore::new(pool)),
+            Database::Sqlite(pool) => Store::Sqlite(SqliteDiarySyncStore::new(pool)),
+        };
+        Self { store }
+    }
+}
+
+impl DiarySyncStore for DiarySyncStorage {
+    async fn ensure_schema(&self) -> Result<(), StorageError> {
+        dispatch!(&self.store, s => s.ensure_schema().await)
+    }
+
+    async fn is_synced(&self, idempotency_key: &str) -> Result<bool, StorageError> {
+        dispatch!(&self.store, s => s.is_synced(idempotency_key).await)
+    }
+
+    async fn claim(
+        &self,
+        idempotency_key: &str,
+        meal_plan_id: i64,
+        date_int: i32,
+    ) -> Result<bool, StorageError> {
+        dispatch!(&self.store, s => s.claim(idempotency_key, meal_plan_id, date_int).await)
+    }
+
+    async fn complete(
+        &self,
+        idempotency_key: &str,
+        food_entry_id: &str,
+    ) -> Result<(), StorageError> {
+        dispatch!(&self.store, s => s.complete(idempotency_key, food_entry_id).await)
+    }
+
+    async fn release(&self, idempotency_key: &str) -> Result<(), StorageError> {
+        dispatch!(&self.store, s => s.release(idempotency_key).await)
+    }
+}
+
+/// When a pending token stored now expires
+fn pending_token_expiry() -> DateTime<Utc> {
+    Utc::now() + Duration::minutes(15)
+}
+
+/// Request token from a stored row
+fn request_token(
+    oauth_token: String,
+    encrypted_secret: &str,
+) -> Result<RequestToken, StorageError> {
+    let oauth_token_secret =
+        decrypt(encrypted_secret).map_err(|e| StorageError::CryptoError(e.to_string()))?;
+
+    Ok(RequestToken {
+        oauth_token,
+        oauth_token_secret,
+        oauth_callback_confirmed: true,
+    })
+}
+
+/// Access token from a stored row
+fn access_token(oauth_token: String, encrypted_secret: &str) -> Result<AccessToken, StorageError> {
+    let oauth_token_secret =
+        decrypt(encrypted_secret).map_err(|e| StorageError::CryptoError(e.to_string()))?;
+
+    Ok(AccessToken {
+        oauth_token,
+        oauth_token_secret,
+    })
+}
+
+/// Validity of an access token connected at `connected_at`
+fn token_validity(connected_at: Option<DateTime<Utc>>) -> TokenValidity {
+    connected_at.map_or(TokenValidity::NotFound, |connected_at| {
+        let days_since = Utc::now().signed_duration_since(connected_at).num_days();
+
+        if days_since < 365 {
+            TokenValidity::Valid
+        } else {
+            // Safe: days_since is bounded by realistic dates (won't overflow i32)
+            #[allow(clippy::cast_possible_truncation)]
+            TokenValidity::Old {
+                days_since_connected: days_since as i32,
+            }
+        }
+    })
+}
+
+/// Validity of a stored token row of `(connected_at, revoked)`
+fn stored_token_validity(row: Option<(DateTime<Utc>, bool)>) -> TokenValidity {
+    match row {
+        Some((_, true)) => TokenValidity::Revoked,
+        row => token_validity(row.map(|(connected_at, _)| connected_at)),
+    }
+}
+
+/// Re-encrypt one row's secret, counting it in `rotation`
+///
+/// Returns the new ciphertext, or None if the secret already uses the
+/// current key.
+fn rotate_secret(
+    keys: &KeyRing,
+    rotation: &mut TableRotation,
+    id: &str,
+    secret: &str,
+) -> Result<Option<String>, StorageError> {
+    let reencrypted = keys
+        .reencrypt(secret)
+        .map_err(|e| StorageError::CryptoError(format!("{} row {id}: {e}", rotation.table)))?;
+
+    if reencrypted.is_some() {
+        rotation.reencrypted += 1;
+    } else {
+        rotation.already_current += 1;
+    }
+    Ok(reencrypted)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_validate_user_id() {
+        assert!(validate_user_id(DEFAULT_USER).is_ok());
+        assert!(validate_user_id("alex.smith@example.com").is_ok());
+        assert!(validate_user_id("kid_2").is_ok());
+        assert!(validate_user_id("").is_err());
+        assert!(validate_user_id("two words").is_err());
+        assert!(validate_user_id("robert'; DROP TABLE").is_err());
+        assert!(validate_user_id(&"x".repeat(MAX_USER_ID_LEN + 1)).is_err());
+    }
+
+    #[test]
+    fn test_token_validity_new() {
+        assert_eq!(TokenValidity::Valid, TokenValidity::Valid);
+    }
+
+    #[test]
+    fn test_token_validity_not_found() {
+        assert_eq!(TokenValidity::NotFound, TokenValidity::NotFound);
+    }
+
+    #[test]
+    fn test_token_validity_old() {
+        let v1 = TokenValidity::Old {
+            days_since_connected: 400,
+        };
+        let v2 = TokenValidity::Old {
+            days_since_connected: 400,
+        };
+
+        assert_eq!(v1, v2);
+    }
+
+    fn connected_days_ago(days: i64) -> DateTime<Utc> {
+        Utc::now() - Duration::days(days)
+    }
+
+    #[test]
+    fn test_token_age_not_connected() {
+        assert_eq!(token_validity(None), TokenValidity::NotFound);
+    }
+
+    #[test]
+    fn test_token_age_valid_fresh() {
+        assert_eq!(
+            token_validity(Some(connected_days_ago(0))),
+            TokenValidity::Valid
+        );
+    }
+
+    #[test]
+    fn test_token_age_valid_almost_year() {
+        assert_eq!(
+            token_validity(Some(connected_days_ago(364))),
+            TokenValidity::Valid
+        );
+    }
+
+    #[test]
+    fn test_token_age_old_exactly_one_year() {
+        assert!(matches!(
+            token_validity(Some(connected_days_ago(365))),
+            TokenValidity::Old {
+                days_since_connected: 365
+            }
+        ));
+    }
+
+    #[test]
+    fn test_stored_token_validity_revoked() {
+        assert_eq!(stored_token_validity(None), TokenValidity::NotFound);
+        assert_eq!(
+            stored_token_validity(Some((connected_days_ago(1), false))),
+            TokenValidity::Valid
+        );
+        assert_eq!(
+            stored_token_validity(Some((connected_days_ago(1), true))),
+            TokenValidity::Revoked
+        );
+        assert_eq!(
+            stored_token_validity(Some((connected_days_ago(400), true))),
+            TokenValidity::Revoked
+        );
+    }
+
+    #[test]
+    fn test_pending_token_expiry() {
+        let minutes = pending_token_expiry()
+            .signed_duration_since(Utc::now())
+            .num_minutes();
+        assert!((14..=15).contains(&minutes));
+    }
+}
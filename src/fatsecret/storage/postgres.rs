@@ -0,0 +1,413 @@
+//! PostgreSQL implementations of the storage traits
+
+use super::{
+    access_token, pending_token_expiry, request_token, rotate_secret, stored_token_validity,
+    DiarySyncStore, RotationProgress, RotationReport, TableRotation, TokenStore, DEFAULT_USER,
+    SECRET_TABLES,
+};
+use crate::db::Database;
+use crate::fatsecret::core::{AccessToken, RequestToken};
+use crate::fatsecret::crypto::{encrypt, KeyRing};
+use crate::fatsecret::{StorageError, TokenValidity};
+use chrono::{DateTime, Utc};
+use sqlx::Row;
+use sqlx::{PgConnection, PgPool};
+
+/// [`TokenStore`] backed by PostgreSQL
+#[derive(Debug, Clone)]
+pub struct PgTokenStore {
+    db: PgPool,
+    user: String,
+}
+
+impl PgTokenStore {
+    /// Create new token storage for [`DEFAULT_USER`]
+    pub fn new(db: PgPool) -> Self {
+        Self {
+            db,
+            user: DEFAULT_USER.to_string(),
+        }
+    }
+}
+
+impl TokenStore for PgTokenStore {
+    fn user(&self) -> &str {
+        &self.user
+    }
+
+    fn with_user(mut self, user: impl Into<String>) -> Self {
+        self.user = user.into();
+        self
+    }
+
+    async fn ensure_schema(&self) -> Result<(), StorageError> {
+        Database::Postgres(self.db.clone())
+            .run_migrations()
+            .await
+            .map_err(|e| StorageError::DatabaseError(e.to_string()))
+    }
+
+    async fn store_pending_token(&self, token: &RequestToken) -> Result<(), StorageError> {
+        // Encrypt the secret for storage
+        let encrypted_secret = encrypt(&token.oauth_token_secret)
+            .map_err(|e| StorageError::CryptoError(e.to_string()))?;
+
+        sqlx::query(
+            r"
+            INSERT INTO fatsecret_oauth_pending (oauth_token, oauth_token_secret, user_id, expires_at)
+            VALUES ($1, $2, $3, $4)
+            ON CONFLICT (oauth_token) DO UPDATE SET
+                oauth_token_secret = EXCLUDED.oauth_token_secret,
+                user_id = EXCLUDED.user_id,
+                expires_at = EXCLUDED.expires_at
+            ",
+        )
+        .bind(&token.oauth_token)
+        .bind(&encrypted_secret)
+        .bind(&self.user)
+        .bind(pending_token_expiry())
+        .execute(&self.db)
+        .await
+        .map_err(|e| StorageError::DatabaseError(e.to_string()))?;
+
+        Ok(())
+    }
+
+    async fn get_pending_token(
+        &self,
+        oauth_token: &str,
+    ) -> Result<Option<RequestToken>, StorageError> {
+        let result = sqlx::query(
+            r"
+            SELECT oauth_token, oauth_token_secret
+            FROM fatsecret_oauth_pending
+            WHERE oauth_token = $1 AND user_id = $2 AND expires_at > NOW()
+            ",
+        )
+        .bind(oauth_token)
+        .bind(&self.user)
+        .fetch_optional(&self.db)
+        .await
+        .map_err(|e| StorageError::DatabaseError(e.to_string()))?;
+
+        result
+            .map(|row| request_token(row.get("oauth_token"), row.get("oauth_token_secret")))
+            .transpose()
+    }
+
+    async fn get_pending_token_user(
+        &self,
+        oauth_token: &str,
+    ) -> Result<Option<String>, StorageError> {
+        let result = sqlx::query(
+            r"
+            SELECT user_id
+            FROM fatsecret_oauth_pending
+            WHERE oauth_token = $1 AND expires_at > NOW()
+            ",
+        )
+        .bind(oauth_token)
+        .fetch_optional(&self.db)
+        .await
+        .map_err(|e| StorageError::DatabaseError(e.to_string()))?;
+
+        Ok(result.map(|row| row.get("user_id")))
+    }
+
+    async fn get_latest_pending_token(&self) -> Result<Option<RequestToken>, StorageError> {
+        let result = sqlx::query(
+            r"
+            SELECT oauth_token, oauth_token_secret
+            FROM fatsecret_oauth_pending
+            WHERE user_id = $1 AND expires_at > NOW()
+            ORDER BY created_at DESC
+            LIMIT 1
+            ",
+        )
+        .bind(&self.user)
+        .fetch_optional(&self.db)
+        .await
+        .map_err(|e| StorageError::DatabaseError(e.to_string()))?;
+
+        result
+            .map(|row| request_token(row.get("oauth_token"), row.get("oauth_token_secret")))
+            .transpose()
+    }
+
+    async fn delete_pending_token(&self, oauth_token: &str) -> Result<(), StorageError> {
+        sqlx::query("DELETE FROM fatsecret_oauth_pending WHERE oauth_token = $1 AND user_id = $2")
+            .bind(oauth_token)
+            .bind(&self.user)
+            .execute(&self.db)
+            .await
+            .map_err(|e| StorageError::DatabaseError(e.to_string()))?;
+
+        Ok(())
+    }
+
+    async fn store_access_token(&self, token: &AccessToken) -> Result<(), StorageError> {
+        // Encrypt the secret for storage
+        let encrypted_secret = encrypt(&token.oauth_token_secret)
+            .map_err(|e| StorageError::CryptoError(e.to_string()))?;
+
+        sqlx::query(
+            r"
+            INSERT INTO fatsecret_oauth_token (user_id, oauth_token, oauth_token_secret, connected_at, last_used_at)
+            VALUES ($1, $2, $3, NOW(), NOW())
+            ON CONFLICT (user_id) DO UPDATE SET
+                oauth_token = EXCLUDED.oauth_token,
+                oauth_token_secret = EXCLUDED.oauth_token_secret,
+                connected_at = EXCLUDED.connected_at,
+                last_used_at = EXCLUDED.last_used_at,
+                revoked_at = NULL
+            ",
+        )
+        .bind(&self.user)
+        .bind(&token.oauth_token)
+        .bind(&encrypted_secret)
+        .execute(&self.db)
+        .await
+        .map_err(|e| StorageError::DatabaseError(e.to_string()))?;
+
+        Ok(())
+    }
+
+    async fn get_access_token(&self) -> Result<Option<AccessToken>, StorageError> {
+        let result = sqlx::query(
+            r"
+            SELECT oauth_token, oauth_token_secret
+            FROM fatsecret_oauth_token
+            WHERE user_id = $1
+            ",
+        )
+        .bind(&self.user)
+        .fetch_optional(&self.db)
+        .await
+        .map_err(|e| StorageError::DatabaseError(e.to_string()))?;
+
+        result
+            .map(|row| access_token(row.get("oauth_token"), row.get("oauth_token_secret")))
+            .transpose()
+    }
+
+    async fn update_last_used(&self) -> Result<(), StorageError> {
+        sqlx::query(
+            "UPDATE fatsecret_oauth_token SET last_used_at = NOW(), revoked_at = NULL WHERE user_id = $1",
+        )
+        .bind(&self.user)
+        .execute(&self.db)
+        .await
+        .map_err(|e| StorageError::DatabaseError(e.to_string()))?;
+
+        Ok(())
+    }
+
+    async fn mark_token_revoked(&self) -> Result<(), StorageError> {
+        sqlx::query("UPDATE fatsecret_oauth_token SET revoked_at = NOW() WHERE user_id = $1")
+            .bind(&self.user)
+            .execute(&self.db)
+            .await
+            .map_err(|e| StorageError::DatabaseError(e.to_string()))?;
+
+        Ok(())
+    }
+
+    async fn check_token_validity(&self) -> Result<TokenValidity, StorageError> {
+        let row: Option<(DateTime<Utc>, bool)> = sqlx::query_as(
+            "SELECT connected_at, revoked_at IS NOT NULL FROM fatsecret_oauth_token WHERE user_id = $1",
+        )
+        .bind(&self.user)
+        .fetch_optional(&self.db)
+        .await
+        .map_err(|e| StorageError::DatabaseError(e.to_string()))?;
+
+        Ok(stored_token_validity(row))
+    }
+
+    async fn delete_access_token(&self) -> Result<(), StorageError> {
+        sqlx::query("DELETE FROM fatsecret_oauth_token WHERE user_id = $1")
+            .bind(&self.user)
+            .execute(&self.db)
+            .await
+            .map_err(|e| StorageError::DatabaseError(e.to_string()))?;
+
+        Ok(())
+    }
+
+    async fn list_users(&self) -> Result<Vec<String>, StorageError> {
+        sqlx::query_scalar("SELECT user_id FROM fatsecret_oauth_token ORDER BY user_id")
+            .fetch_all(&self.db)
+            .await
+            .map_err(|e| StorageError::DatabaseError(e.to_string()))
+    }
+
+    async fn reencrypt_secrets(
+        &self,
+        keys: &KeyRing,
+        dry_run: bool,
+        mut on_progress: impl FnMut(&RotationProgress) + Send,
+    ) -> Result<RotationReport, StorageError> {
+        let mut tx = self
+            .db
+            .begin()
+            .await
+            .map_err(|e| StorageError::DatabaseError(e.to_string()))?;
+
+        let mut tables = Vec::with_capacity(SECRET_TABLES.len());
+        for (table, key_column) in SECRET_TABLES {
+            tables.push(reencrypt_table(&mut tx, keys, table, key_column, &mut on_progress).await?);
+        }
+
+        if dry_run {
+            tx.rollback().await
+        } else {
+            tx.commit().await
+        }
+        .map_err(|e| StorageError::DatabaseError(e.to_string()))?;
+
+        Ok(RotationReport {
+            key_id: keys.current_key_id().to_string(),
+            dry_run,
+            tables,
+        })
+    }
+
+    async fn cleanup_expired_tokens(&self) -> Result<u64, StorageError> {
+        let result = sqlx::query("DELETE FROM fatsecret_oauth_pending WHERE expires_at < NOW()")
+            .execute(&self.db)
+            .await
+            .map_err(|e| StorageError::DatabaseError(e.to_string()))?;
+
+        Ok(result.rows_affected())
+    }
+}
+
+/// Re-encrypt the secrets of one table inside an open transaction
+async fn reencrypt_table(
+    conn: &mut PgConnection,
+    keys: &KeyRing,
+    table: &'static str,
+    key_column: &str,
+    on_progress: &mut (impl FnMut(&RotationProgress) + Send),
+) -> Result<TableRotation, StorageError> {
+    let rows = sqlx::query(&format!(
+        "SELECT {key_column}, oauth_token_secret FROM {table} ORDER BY {key_column} FOR UPDATE"
+    ))
+    .fetch_all(&mut *conn)
+    .await
+    .map_err(|e| StorageError::DatabaseError(e.to_string()))?;
+
+    let mut rotation = TableRotation {
+        table,
+        total: rows.len(),
+        reencrypted: 0,
+        already_current: 0,
+    };
+    for (done, row) in rows.iter().enumerate() {
+        let id: String = row.get(key_column);
+        let secret: String = row.get("oauth_token_secret");
+
+        if let Some(reencrypted) = rotate_secret(keys, &mut rotation, &id, &secret)? {
+            sqlx::query(&format!(
+                "UPDATE {table} SET oauth_token_secret = $1 WHERE {key_column} = $2"
+            ))
+            .bind(&reencrypted)
+            .bind(&id)
+            .execute(&mut *conn)
+            .await
+            .map_err(|e| StorageError::DatabaseError(e.to_string()))?;
+        }
+
+        on_progress(&RotationProgress {
+            table,
+            done: done + 1,
+            total: rotation.total,
+        });
+    }
+
+    Ok(rotation)
+}
+
+/// [`DiarySyncStore`] backed by PostgreSQL
+#[derive(Debug, Clone)]
+pub struct PgDiarySyncStore {
+    db: PgPool,
+}
+
+impl PgDiarySyncStore {
+    /// Create new diary sync storage
+    pub fn new(db: PgPool) -> Self {
+        Self { db }
+    }
+}
+
+impl DiarySyncStore for PgDiarySyncStore {
+    async fn ensure_schema(&self) -> Result<(), StorageError> {
+        Database::Postgres(self.db.clone())
+            .run_migrations()
+            .await
+            .map_err(|e| StorageError::DatabaseError(e.to_string()))
+    }
+
+    async fn is_synced(&self, idempotency_key: &str) -> Result<bool, StorageError> {
+        let result = sqlx::query("SELECT 1 FROM fatsecret_diary_sync WHERE idempotency_key = $1")
+            .bind(idempotency_key)
+            .fetch_optional(&self.db)
+            .await
+            .map_err(|e| StorageError::DatabaseError(e.to_string()))?;
+
+        Ok(result.is_some())
+    }
+
+    async fn claim(
+        &self,
+        idempotency_key: &str,
+        meal_plan_id: i64,
+        date_int: i32,
+    ) -> Result<bool, StorageError> {
+        let result = sqlx::query(
+            r"
+            INSERT INTO fatsecret_diary_sync (idempotency_key, meal_plan_id, date_int)
+            VALUES ($1, $2, $3)
+            ON CONFLICT (idempotency_key) DO NOTHING
+            ",
+        )
+        .bind(idempotency_key)
+        .bind(meal_plan_id)
+        .bind(date_int)
+        .execute(&self.db)
+        .await
+        .map_err(|e| StorageError::DatabaseError(e.to_string()))?;
+
+        Ok(result.rows_affected() == 1)
+    }
+
+    async fn complete(
+        &self,
+        idempotency_key: &str,
+        food_entry_id: &str,
+    ) -> Result<(), StorageError> {
+        sqlx::query(
+            "UPDATE fatsecret_diary_sync SET food_entry_id = $2 WHERE idempotency_key = $1",
+        )
+        .bind(idempotency_key)
+        .bind(food_entry_id)
+        .execute(&self.db)
+        .await
+        .map_err(|e| StorageError::DatabaseError(e.to_string()))?;
+
+        Ok(())
+    }
+
+    async fn release(&self, idempotency_key: &str) -> Result<(), StorageError> {
+        sqlx::query(
+            "DELETE FROM fatsecret_diary_sync WHERE idempotency_key = $1 AND food_entry_id IS NULL",
+        )
+        .bind(idempotency_key)
+        .execute(&self.db)
+        .await
+        .map_err(|e| StorageError::DatabaseError(e.to_string()))?;
+
+        Ok(())
+    }
+}
@@ -16,6 +16,7 @@
 //! - [`update_weight`] - Record a new weight measurement for a specific date
 //! - [`get_weight_by_date`] - Retrieve weight measurement for a specific date
 //! - [`get_weight_month_summary`] - Get all weight measurements for a month
+//! - [`trend::analyze`] - Smoothed trend, weekly rate, energy balance and goal projection
 //!
 //! # Date Format
 //!
@@ -53,6 +54,7 @@
 //! ```
 
 pub mod client;
+pub mod trend;
 pub mod types;
 
 pub use client::*;
@@ -0,0 +1,390 @@
+//! Weight trend analytics (FUNCTIONAL CORE - PURE)
+//!
+//! Interprets raw daily weigh-ins from [`get_weight_month_summary`](super::get_weight_month_summary)
+//! the way The Hacker's Diet does: daily scale readings are noisy, so the
+//! useful signal is an exponentially smoothed trend line.
+//!
+//! - Trend: `trend = previous + 10% × (weight − previous)`, one step per day.
+//!   Days without a weigh-in are linearly interpolated between readings.
+//! - Weekly rate: least-squares slope of the trend over a recent window
+//! - Energy balance: weekly rate × 7700 kcal/kg ÷ 7 (negative = deficit)
+//! - Goal projection: days until the trend reaches the goal at the current rate
+//!
+//! The [`WeightTrend::chart`] series are aligned arrays ready for plotting.
+
+use chrono::{Duration, Months, NaiveDate};
+use serde::Serialize;
+
+use super::types::WeightDaySummary;
+
+/// Share of each day's deviation folded into the trend
+pub const SMOOTHING: f64 = 0.1;
+
+/// Approximate energy content of one kilogram of body weight (kcal)
+pub const KCAL_PER_KG: f64 = 7700.0;
+
+/// Default number of days used to estimate the rate of change
+pub const DEFAULT_RATE_WINDOW_DAYS: usize = 28;
+
+/// Unix epoch date (1970-01-01) - constant for date calculations
+const UNIX_EPOCH_DATE: (i32, u32, u32) = (1970, 1, 1);
+
+/// One day on the trend line
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct TrendPoint {
+    /// Date (YYYY-MM-DD)
+    pub date: String,
+    /// Date as days since Unix epoch
+    pub date_int: i32,
+    /// Scale reading, if one was logged that day
+    pub weight_kg: Option<f64>,
+    /// Smoothed trend weight
+    pub trend_kg: f64,
+}
+
+/// Aligned series for plotting weight against trend
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct TrendChart {
+    /// X axis labels (YYYY-MM-DD)
+    pub labels: Vec<String>,
+    /// Scale readings (null on days without a weigh-in)
+    pub weight_kg: Vec<Option<f64>>,
+    /// Trend weight
+    pub trend_kg: Vec<f64>,
+    /// Goal weight repeated per label (empty without a goal)
+    pub goal_kg: Vec<f64>,
+}
+
+/// When the goal weight will be reached at the current rate
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct GoalProjection {
+    /// Goal weight (kg)
+    pub goal_weight_kg: f64,
+    /// Trend weight minus goal (positive = above goal)
+    pub remaining_kg: f64,
+    /// Projected date the trend reaches the goal (YYYY-MM-DD)
+    pub projected_date: Option<String>,
+    /// Days from the last weigh-in to the projected date
+    pub days_to_goal: Option<i64>,
+    /// Why no date could be projected
+    pub note: Option<String>,
+}
+
+/// Trend analysis of a series of weigh-ins
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct WeightTrend {
+    /// Most recent scale reading (kg)
+    pub latest_weight_kg: f64,
+    /// Current trend weight (kg)
+    pub trend_weight_kg: f64,
+    /// Trend change per week (kg, negative = losing)
+    pub weekly_rate_kg: f64,
+    /// Estimated daily energy balance (kcal, negative = deficit)
+    pub daily_energy_balance_kcal: f64,
+    /// Goal projection, if a goal weight is known
+    pub goal: Option<GoalProjection>,
+    /// Day-by-day trend
+    pub points: Vec<TrendPoint>,
+    /// Chart-ready series
+    pub chart: TrendChart,
+}
+
+fn epoch() -> Result<NaiveDate, String> {
+    NaiveDate::from_ymd_opt(UNIX_EPOCH_DATE.0, UNIX_EPOCH_DATE.1, UNIX_EPOCH_DATE.2)
+        .ok_or_else(|| "Invalid epoch date".to_string())
+}
+
+fn to_date(date_int: i32) -> Result<NaiveDate, String> {
+    epoch()?
+        .checked_add_signed(Duration::days(i64::from(date_int)))
+        .ok_or_else(|| format!("Date calculation overflow: {date_int}"))
+}
+
+fn to_date_int(date: NaiveDate) -> Result<i32, String> {
+    let days = (date - epoch()?).num_days();
+    i32::try_from(days).map_err(|_| format!("Date too far from epoch: {days} days"))
+}
+
+/// Round to two decimal places
+fn round2(value: f64) -> f64 {
+    (value * 100.0).round() / 100.0
+}
+
+/// One date_int per month for the `months` months ending with `end_date_int`
+///
+/// Oldest month first; each value can be passed to `get_weight_month_summary`.
+pub fn month_dates(end_date_int: i32, months: u32) -> Result<Vec<i32>, String> {
+    let end = to_date(end_date_int)?;
+    (0..months)
+        .rev()
+        .map(|back| {
+            end.checked_sub_months(Months::new(back))
+                .ok_or_else(|| format!("Date out of range: {back} months before {end}"))
+                .and_then(to_date_int)
+        })
+        .collect()
+}
+
+/// Daily readings sorted by date, deduplicated (last reading per day wins)
+fn daily_readings(days: &[WeightDaySummary]) -> Vec<(i32, f64)> {
+    let mut readings: Vec<(i32, f64)> = days
+        .iter()
+        .filter(|d| d.weight_kg > 0.0)
+        .map(|d| (d.date_int, d.weight_kg))
+        .collect();
+    readings.sort_by_key(|(date_int, _)| *date_int);
+    readings.reverse();
+    readings.dedup_by_key(|(date_int, _)| *date_int);
+    readings.reverse();
+    readings
+}
+
+/// Smoothed trend for every day from the first to the last reading
+///
+/// Missing days are linearly interpolated so the trend advances one step per day.
+pub fn trend_points(days: &[WeightDaySummary]) -> Result<Vec<TrendPoint>, String> {
+    let readings = daily_readings(days);
+    let Some(&(first_day, first_weight)) = readings.first() else {
+        return Ok(Vec::new());
+    };
+
+    let mut points = Vec::new();
+    let mut trend = first_weight;
+    let mut previous = (first_day, first_weight);
+    for &(date_int, weight) in &readings {
+        let gap = date_int - previous.0;
+        for step in 1..=gap {
+            let day = previous.0 + step;
+            let estimate =
+                previous.1 + (weight - previous.1) * f64::from(step) / f64::from(gap.max(1));
+            trend += SMOOTHING * (estimate - trend);
+            points.push(trend_point(
+                day,
+                (day == date_int).then_some(weight),
+                trend,
+            )?);
+        }
+        if gap == 0 {
+            points.push(trend_point(date_int, Some(weight), trend)?);
+        }
+        previous = (date_int, weight);
+    }
+    Ok(points)
+}
+
+fn trend_point(date_int: i32, weight_kg: Option<f64>, trend_kg: f64) -> Result<TrendPoint, String> {
+    Ok(TrendPoint {
+        date: to_date(date_int)?.format("%Y-%m-%d").to_string(),
+        date_int,
+        weight_kg,
+        trend_kg: round2(trend_kg),
+    })
+}
+
+/// Least-squares slope of the trend over the last `window_days` points (kg/day)
+pub fn daily_rate(points: &[TrendPoint], window_days: usize) -> f64 {
+    let window = points
+        .get(points.len().saturating_sub(window_days.max(2))..)
+        .unwrap_or_default();
+    let n = f64::from(u32::try_from(window.len()).unwrap_or(u32::MAX));
+    if window.len() < 2 {
+        return 0.0;
+    }
+
+    let xs = window.iter().map(|p| f64::from(p.date_int));
+    let mean_x = xs.clone().sum::<f64>() / n;
+    let mean_y = window.iter().map(|p| p.trend_kg).sum::<f64>() / n;
+    let (covariance, variance) =
+        xs.zip(window.iter().map(|p| p.trend_kg))
+            .fold((0.0, 0.0), |(cov, var), (x, y)| {
+                (
+                    (x - mean_x).mul_add(y - mean_y, cov),
+                    (x - mean_x).mul_add(x - mean_x, var),
+                )
+            });
+    if variance > 0.0 {
+        covariance / variance
+    } else {
+        0.0
+    }
+}
+
+/// Project when the trend reaches `goal_weight_kg` at `daily_rate` kg/day
+pub fn project_goal(
+    last: &TrendPoint,
+    daily_rate: f64,
+    goal_weight_kg: f64,
+) -> Result<GoalProjection, String> {
+    let remaining_kg = round2(last.trend_kg - goal_weight_kg);
+    let mut projection = GoalProjection {
+        goal_weight_kg,
+        remaining_kg,
+        projected_date: None,
+        days_to_goal: None,
+        note: None,
+    };
+
+    if remaining_kg.abs() < 0.05 {
+        projection.projected_date = Some(last.date.clone());
+        projection.days_to_goal = Some(0);
+    } else if daily_rate == 0.0 || remaining_kg.signum() == daily_rate.signum() {
+        projection.note = Some("Trend is not moving towards the goal".to_string());
+    } else {
+        let days = (remaining_kg / -daily_rate).ceil();
+        // Anything beyond ~27 years is not a useful projection
+        if days < 10_000.0 {
+            #[allow(clippy::cast_possible_truncation)]
+            let days = days as i64;
+            let date = to_date(last.date_int)?
+                .checked_add_signed(Duration::days(days))
+                .ok_or("Projected date out of range")?;
+            projection.projected_date = Some(date.format("%Y-%m-%d").to_string());
+            projection.days_to_goal = Some(days);
+        } else {
+            projection.note = Some("Goal is too far away at the current rate".to_string());
+        }
+    }
+    Ok(projection)
+}
+
+/// Chart series for a trend
+fn chart(points: &[TrendPoint], goal_weight_kg: Option<f64>) -> TrendChart {
+    TrendChart {
+        labels: points.iter().map(|p| p.date.clone()).collect(),
+        weight_kg: points.iter().map(|p| p.weight_kg).collect(),
+        trend_kg: points.iter().map(|p| p.trend_kg).collect(),
+        goal_kg: goal_weight_kg
+            .map(|goal| vec![goal; points.len()])
+            .unwrap_or_default(),
+    }
+}
+
+/// Analyse weigh-ins: trend, weekly rate, energy balance and goal projection
+///
+/// Returns None when there are no readings.
+pub fn analyze(
+    days: &[WeightDaySummary],
+    goal_weight_kg: Option<f64>,
+    rate_window_days: usize,
+) -> Result<Option<WeightTrend>, String> {
+    let points = trend_points(days)?;
+    let Some(last) = points.last() else {
+        return Ok(None);
+    };
+    let latest_weight_kg = points
+        .iter()
+        .rev()
+        .find_map(|p| p.weight_kg)
+        .unwrap_or(last.trend_kg);
+
+    let rate = daily_rate(&points, rate_window_days);
+    let goal = goal_weight_kg
+        .map(|goal| project_goal(last, rate, goal))
+        .transpose()?;
+
+    Ok(Some(WeightTrend {
+        latest_weight_kg,
+        trend_weight_kg: last.trend_kg,
+        weekly_rate_kg: round2(rate * 7.0),
+        daily_energy_balance_kcal: (rate * KCAL_PER_KG).round(),
+        goal,
+        chart: chart(&points, goal_weight_kg),
+        points,
+    }))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// 2024-01-01
+    const START: i32 = 19723;
+
+    fn day(date_int: i32, weight_kg: f64) -> WeightDaySummary {
+        WeightDaySummary {
+            date_int,
+            weight_kg,
+        }
+    }
+
+    #[test]
+    fn test_trend_smooths_towards_readings() {
+        let points = trend_points(&[day(START, 80.0), day(START + 1, 81.0)]).unwrap();
+        assert_eq!(points.len(), 2);
+        assert!((points[0].trend_kg - 80.0).abs() < f64::EPSILON);
+        assert!((points[1].trend_kg - 80.1).abs() < 1e-9);
+        assert_eq!(points[1].date, "2024-01-02");
+    }
+
+    #[test]
+    fn test_trend_interpolates_gaps() {
+        let points = trend_points(&[day(START + 3, 77.0), day(START, 80.0)]).unwrap();
+        assert_eq!(points.len(), 4);
+        assert_eq!(points[1].weight_kg, None);
+        assert_eq!(points[3].weight_kg, Some(77.0));
+        // 80 → 79 → 78 → 77 interpolated, each step folds in 10%
+        assert!((points[1].trend_kg - 79.9).abs() < 1e-9);
+        assert!(points[3].trend_kg < points[2].trend_kg);
+    }
+
+    #[test]
+    fn test_trend_ignores_duplicates_and_empty() {
+        assert!(trend_points(&[]).unwrap().is_empty());
+        let points = trend_points(&[day(START, 80.0), day(START, 0.0)]).unwrap();
+        assert_eq!(points.len(), 1);
+    }
+
+    #[test]
+    fn test_daily_rate_linear_loss() {
+        let points: Vec<TrendPoint> = (0..30)
+            .map(|i| trend_point(START + i, None, (-0.1f64).mul_add(f64::from(i), 90.0)).unwrap())
+            .collect();
+        assert!((daily_rate(&points, 28) + 0.1).abs() < 1e-3);
+        assert!(daily_rate(&points[..1], 28).abs() < f64::EPSILON);
+    }
+
+    #[test]
+    fn test_project_goal() {
+        let last = trend_point(START, Some(80.0), 80.0).unwrap();
+        let projection = project_goal(&last, -0.1, 75.0).unwrap();
+        assert_eq!(projection.days_to_goal, Some(50));
+        assert_eq!(projection.projected_date.as_deref(), Some("2024-02-20"));
+
+        let wrong_way = project_goal(&last, 0.1, 75.0).unwrap();
+        assert!(wrong_way.projected_date.is_none());
+        assert!(wrong_way.note.is_some());
+
+        let reached = project_goal(&last, 0.0, 80.0).unwrap();
+        assert_eq!(reached.days_to_goal, Some(0));
+    }
+
+    #[test]
+    fn test_analyze_builds_report_and_chart() {
+        let days: Vec<WeightDaySummary> = (0..28)
+            .step_by(2)
+            .map(|i| day(START + i, (-0.05f64).mul_add(f64::from(i), 85.0)))
+            .collect();
+        let trend = analyze(&days, Some(80.0), DEFAULT_RATE_WINDOW_DAYS)
+            .unwrap()
+            .unwrap();
+
+        assert!((trend.latest_weight_kg - 83.7).abs() < 1e-9);
+        assert!(trend.weekly_rate_kg < 0.0);
+        assert!(trend.daily_energy_balance_kcal < 0.0);
+        assert!(trend.goal.as_ref().unwrap().projected_date.is_some());
+        assert_eq!(trend.chart.labels.len(), trend.points.len());
+        assert_eq!(trend.chart.goal_kg.len(), trend.points.len());
+        assert!(analyze(&[], None, 28).unwrap().is_none());
+    }
+
+    #[test]
+    fn test_month_dates() {
+        // 2024-03-15, three months back
+        let dates = month_dates(19797, 3).unwrap();
+        let formatted: Vec<String> = dates
+            .iter()
+            .map(|d| to_date(*d).unwrap().format("%Y-%m-%d").to_string())
+            .collect();
+        assert_eq!(formatted, vec!["2024-01-15", "2024-02-15", "2024-03-15"]);
+    }
+}
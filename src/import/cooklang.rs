@@ -0,0 +1,649 @@
+//! Cooklang import
+//!
+//! Parses a [Cooklang](https://cooklang.org/docs/spec/) recipe:
+//!
+//! - Metadata from YAML front matter or `>> key: value` lines; nested keys
+//!   are joined with dots (`source.url`) and lists with commas
+//! - Steps are paragraphs; `== Section ==` (or `= Section`) starts a section
+//! - Ingredients: `@salt`, `@ground beef{1%lb}`, `@onion{1}(diced)`
+//! - Cookware: `#pot`, `#dutch oven{}`
+//! - Timers: `~{25%minutes}`, `~rest{10%min}`
+//! - Comments: `-- to end of line` and `[- block -]`
+//! - Notes: `> text`
+//!
+//! [`CookRecipe::to_create_request`] maps the recipe onto Tandoor:
+//!
+//! | Cooklang | Tandoor |
+//! |----------|---------|
+//! | `title` (else the file name) | `name` |
+//! | `description` (else the notes) | `description` |
+//! | `source` / `source.url` | `source_url` |
+//! | `servings` / `serves` / `yield` | `servings` |
+//! | `prep time` | `working_time` |
+//! | `cook time` (else the sum of the timers) | `waiting_time` |
+//! | `tags` | keywords |
+//! | a section's name | a `## Section` heading on its first step |
+//!
+//! Cookware has no Tandoor equivalent and stays in the step text.
+
+use super::{parse_minutes, parse_quantity, parse_servings, ImportError};
+use crate::tandoor::{
+    CreateFoodRequest, CreateIngredientRequest, CreateKeywordRequest, CreateRecipeRequest,
+    CreateStepRequest, CreateUnitRequest,
+};
+use std::collections::{BTreeMap, HashSet};
+
+/// Characters that can't appear in a multi-word name
+const NOT_IN_NAME: &[char] = &[
+    '@', '#', '~', '{', '}', '(', ')', '\n', '.', ',', ';', ':', '!', '?',
+];
+
+/// Ingredient modifiers (`@?optional`, `@-hidden`, `@&reference`, ...)
+const MODIFIERS: &[char] = &['@', '&', '?', '+', '-', '='];
+
+/// A parsed Cooklang recipe
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct CookRecipe {
+    /// Metadata by lowercased key
+    pub metadata: BTreeMap<String, String>,
+    /// Notes (`> text` lines)
+    pub notes: Vec<String>,
+    /// Steps, in order
+    pub steps: Vec<CookStep>,
+}
+
+/// A step (paragraph) of a Cooklang recipe
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct CookStep {
+    /// Name of the section this step begins
+    pub section: Option<String>,
+    /// Step text with the markup replaced by names and durations
+    pub text: String,
+    /// Ingredients, in order of appearance
+    pub ingredients: Vec<CookIngredient>,
+    /// Cookware names
+    pub cookware: Vec<String>,
+    /// Timers
+    pub timers: Vec<CookTimer>,
+}
+
+/// An ingredient reference (`@name{quantity%unit}(note)`)
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CookIngredient {
+    /// Ingredient name
+    pub name: String,
+    /// Quantity as written (`1/2`, `some`)
+    pub quantity: Option<String>,
+    /// Unit
+    pub unit: Option<String>,
+    /// Preparation note
+    pub note: Option<String>,
+}
+
+/// A timer (`~name{quantity%unit}`)
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CookTimer {
+    /// Timer name
+    pub name: Option<String>,
+    /// Duration as written
+    pub quantity: String,
+    /// Duration unit
+    pub unit: Option<String>,
+}
+
+impl CookTimer {
+    /// Duration in minutes
+    pub fn minutes(&self) -> Option<i32> {
+        parse_minutes(&format!(
+            "{} {}",
+            self.quantity,
+            self.unit.as_deref().unwrap_or("minutes")
+        ))
+    }
+
+    /// Duration as shown in the step text (`25 minutes`)
+    fn text(&self) -> String {
+        self.unit.as_ref().map_or_else(
+            || self.quantity.clone(),
+            |unit| format!("{} {unit}", self.quantity),
+        )
+    }
+}
+
+/// Parse a Cooklang document
+///
+/// Markup that doesn't parse (a lone `@`, an unclosed `{`) is kept as text;
+/// only invalid front matter is an error.
+pub fn parse_cooklang(source: &str) -> Result<CookRecipe, ImportError> {
+    let source = source.trim_start_matches('\u{feff}');
+    let (front_matter, body) = split_front_matter(source);
+
+    let mut recipe = CookRecipe::default();
+    if let Some(front_matter) = front_matter {
+        recipe.metadata = parse_front_matter(front_matter)?;
+    }
+
+    let mut section = None;
+    let mut paragraph: Vec<&str> = Vec::new();
+    let body = strip_comments(body);
+    for line in body.lines().map(str::trim) {
+        let is_text = !line.is_empty() && !line.starts_with('>') && !line.starts_with('=');
+        if is_text {
+            paragraph.push(line);
+            continue;
+        }
+        push_step(&mut recipe, &mut paragraph, &mut section);
+
+        if let Some(entry) = line.strip_prefix(">>") {
+            if let Some((key, value)) = entry.split_once(':') {
+                recipe
+                    .metadata
+                    .insert(key.trim().to_lowercase(), value.trim().to_string());
+            }
+        } else if let Some(note) = line.strip_prefix('>') {
+            recipe.notes.push(note.trim().to_string());
+        } else if line.starts_with('=') {
+            section = Some(line.trim_matches('=').trim().to_string()).filter(|s| !s.is_empty());
+        }
+    }
+    push_step(&mut recipe, &mut paragraph, &mut section);
+
+    Ok(recipe)
+}
+
+/// Parse a Cooklang document into a Tandoor recipe request
+pub fn recipe_from_cooklang(
+    source: &str,
+    fallback_name: &str,
+) -> Result<CreateRecipeRequest, ImportError> {
+    Ok(parse_cooklang(source)?.to_create_request(fallback_name))
+}
+
+fn push_step(recipe: &mut CookRecipe, paragraph: &mut Vec<&str>, section: &mut Option<String>) {
+    if paragraph.is_empty() {
+        return;
+    }
+    let mut step = parse_step(&paragraph.join("\n"));
+    step.section = section.take();
+    recipe.steps.push(step);
+    paragraph.clear();
+}
+
+/// Split `---` delimited front matter from the body
+fn split_front_matter(source: &str) -> (Option<&str>, &str) {
+    let Some(rest) = source
+        .strip_prefix("---\n")
+        .or_else(|| source.strip_prefix("---\r\n"))
+    else {
+        return (None, source);
+    };
+
+    let mut offset = 0;
+    for line in rest.split_inclusive('\n') {
+        if line.trim_end() == "---" {
+            return (
+                rest.get(..offset),
+                rest.get(offset + line.len()..).unwrap_or_default(),
+            );
+        }
+        offset += line.len();
+    }
+    (None, source)
+}
+
+fn parse_front_matter(yaml: &str) -> Result<BTreeMap<String, String>, ImportError> {
+    let value: serde_yaml::Value =
+        serde_yaml::from_str(yaml).map_err(|e| ImportError::Parse(format!("front matter: {e}")))?;
+    let mut metadata = BTreeMap::new();
+    flatten_yaml("", &value, &mut metadata);
+    Ok(metadata)
+}
+
+fn flatten_yaml(key: &str, value: &serde_yaml::Value, metadata: &mut BTreeMap<String, String>) {
+    use serde_yaml::Value;
+
+    match value {
+        Value::Mapping(mapping) => {
+            for (child, value) in mapping {
+                if let Some(child) = yaml_scalar(child) {
+                    let child = child.to_lowercase();
+                    let key = if key.is_empty() {
+                        child
+                    } else {
+                        format!("{key}.{child}")
+                    };
+                    flatten_yaml(&key, value, metadata);
+                }
+            }
+        }
+        Value::Sequence(items) => {
+            let items: Vec<String> = items.iter().filter_map(yaml_scalar).collect();
+            metadata.insert(key.to_string(), items.join(", "));
+        }
+        Value::Tagged(tagged) => flatten_yaml(key, &tagged.value, metadata),
+        Value::Null | Value::Bool(_) | Value::Number(_) | Value::String(_) => {
+            if let Some(text) = yaml_scalar(value) {
+                metadata.insert(key.to_string(), text);
+            }
+        }
+    }
+}
+
+fn yaml_scalar(value: &serde_yaml::Value) -> Option<String> {
+    use serde_yaml::Value;
+
+    match value {
+        Value::String(s) => Some(s.trim().to_string()),
+        Value::Number(n) => Some(n.to_string()),
+        Value::Bool(b) => Some(b.to_string()),
+        Value::Tagged(tagged) => yaml_scalar(&tagged.value),
+        Value::Null | Value::Sequence(_) | Value::Mapping(_) => None,
+    }
+}
+
+/// Remove `[- block -]` and `-- line` comments
+fn strip_comments(text: &str) -> String {
+    let mut uncommented = String::new();
+    let mut rest = text;
+    while let Some(start) = rest.find("[-") {
+        uncommented.push_str(rest.get(..start).unwrap_or_default());
+        let comment = rest.get(start + 2..).unwrap_or_default();
+        rest = comment
+            .find("-]")
+            .and_then(|end| comment.get(end + 2..))
+            .unwrap_or_default();
+    }
+    uncommented.push_str(rest);
+
+    uncommented
+        .lines()
+        .map(|line| {
+            line.find("--")
+                .map_or(line, |i| line.get(..i).unwrap_or_default())
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+/// A component's name, `{...}` contents and `(...)` note, and its length
+struct Component<'a> {
+    name: &'a str,
+    braces: Option<&'a str>,
+    note: Option<&'a str>,
+    len: usize,
+}
+
+/// Parse the component after a sigil
+fn component(text: &str) -> Component<'_> {
+    // Multi-word name: everything up to `{`, if it is name-like
+    let braced = text.find('{').and_then(|open| {
+        let name = text.get(..open)?;
+        let inner = text.get(open + 1..)?;
+        let close = inner.find('}')?;
+        let plain = !name.contains(NOT_IN_NAME) && !name.starts_with(char::is_whitespace);
+        plain.then(|| (name.trim_end(), inner.get(..close), open + close + 2))
+    });
+    let (name, braces, mut len) = braced.unwrap_or_else(|| {
+        let len = text
+            .find(|c: char| !(c.is_alphanumeric() || c == '_'))
+            .unwrap_or(text.len());
+        (text.get(..len).unwrap_or_default(), None, len)
+    });
+
+    let rest = text.get(len..).unwrap_or_default();
+    let note = rest
+        .strip_prefix('(')
+        .and_then(|note| note.find([')', '\n']).map(|end| (note, end)))
+        .filter(|(note, end)| note.get(*end..).is_some_and(|r| r.starts_with(')')))
+        .and_then(|(note, end)| note.get(..end));
+    if let Some(note) = note {
+        len += note.len() + 2;
+    }
+
+    Component {
+        name,
+        braces,
+        note,
+        len,
+    }
+}
+
+/// `quantity%unit` split into its (non-empty) parts
+fn quantity_unit(braces: &str) -> (Option<String>, Option<String>) {
+    let (quantity, unit) = braces.split_once('%').unwrap_or((braces, ""));
+    let non_empty =
+        |s: &str| Some(s.trim().trim_end_matches('*').trim().to_string()).filter(|s| !s.is_empty());
+    (non_empty(quantity), non_empty(unit))
+}
+
+/// Parse a step's text, collecting its ingredients, cookware and timers
+fn parse_step(text: &str) -> CookStep {
+    let mut step = CookStep::default();
+    let mut rest = text;
+
+    while let Some(position) = rest.find(['@', '#', '~']) {
+        step.text.push_str(rest.get(..position).unwrap_or_default());
+        let sigil = rest.get(position..).and_then(|s| s.chars().next());
+        let after = rest.get(position + 1..).unwrap_or_default();
+        let modifiers = if sigil == Some('@') {
+            after.len() - after.trim_start_matches(MODIFIERS).len()
+        } else {
+            0
+        };
+
+        let consumed = after
+            .get(modifiers..)
+            .map(component)
+            .and_then(|c| add_component(&mut step, sigil, &c).then_some(modifiers + c.len));
+        if let Some(len) = consumed {
+            rest = after.get(len..).unwrap_or_default();
+        } else {
+            step.text.extend(sigil);
+            rest = after;
+        }
+    }
+    step.text.push_str(rest);
+    step.text = step.text.trim().to_string();
+    step
+}
+
+/// Record a component and write its text; false if it isn't valid markup
+fn add_component(step: &mut CookStep, sigil: Option<char>, c: &Component<'_>) -> bool {
+    let (quantity, unit) = c.braces.map(quantity_unit).unwrap_or_default();
+    let name = c.name.trim();
+
+    match sigil {
+        Some('@') if !name.is_empty() => {
+            step.text.push_str(name);
+            step.ingredients.push(CookIngredient {
+                name: name.to_string(),
+                quantity,
+                unit,
+                note: c
+                    .note
+                    .map(|n| n.trim().to_string())
+                    .filter(|n| !n.is_empty()),
+            });
+        }
+        Some('#') if !name.is_empty() => {
+            step.text.push_str(name);
+            if let Some(note) = c.note {
+                step.text.push_str(&["(", note, ")"].concat());
+            }
+            step.cookware.push(name.to_string());
+        }
+        Some('~') if c.braces.is_some() => {
+            let timer = CookTimer {
+                name: Some(name.to_string()).filter(|n| !n.is_empty()),
+                quantity: quantity.unwrap_or_default(),
+                unit,
+            };
+            let text = if timer.quantity.is_empty() {
+                name.to_string()
+            } else {
+                timer.text()
+            };
+            step.text.push_str(&text);
+            if let Some(note) = c.note {
+                step.text.push_str(&["(", note, ")"].concat());
+            }
+            step.timers.push(timer);
+        }
+        _ => return false,
+    }
+    true
+}
+
+impl CookRecipe {
+    /// The first non-empty metadata value among `keys`
+    pub fn meta(&self, keys: &[&str]) -> Option<&str> {
+        keys.iter()
+            .filter_map(|key| self.metadata.get(*key))
+            .map(|value| value.trim())
+            .find(|value| !value.is_empty())
+    }
+
+    /// Tags, split on commas and deduplicated case-insensitively
+    pub fn tags(&self) -> Vec<String> {
+        let mut seen = HashSet::new();
+        self.meta(&["tags", "tag", "keywords"])
+            .unwrap_or_default()
+            .split(',')
+            .map(|tag| tag.trim().to_string())
+            .filter(|tag| !tag.is_empty() && seen.insert(tag.to_lowercase()))
+            .collect()
+    }
+
+    /// Map onto a Tandoor recipe request; `fallback_name` is used without a
+    /// `title`
+    pub fn to_create_request(&self, fallback_name: &str) -> CreateRecipeRequest {
+        let description = self.meta(&["description", "introduction"]).map_or_else(
+            || Some(self.notes.join("\n\n")).filter(|notes| !notes.is_empty()),
+            |description| Some(description.to_string()),
+        );
+        let timers: i32 = self
+            .steps
+            .iter()
+            .flat_map(|step| &step.timers)
+            .filter_map(CookTimer::minutes)
+            .sum();
+        let keywords: Vec<CreateKeywordRequest> = self
+            .tags()
+            .into_iter()
+            .map(|name| CreateKeywordRequest { name })
+            .collect();
+
+        CreateRecipeRequest {
+            name: self
+                .meta(&["title", "name"])
+                .unwrap_or(fallback_name)
+                .to_string(),
+            description,
+            source_url: self
+                .meta(&["source", "source.url", "url"])
+                .map(str::to_string),
+            servings: self
+                .meta(&["servings", "serves", "yield"])
+                .and_then(parse_servings),
+            working_time: self
+                .meta(&["prep time", "prep_time", "time.prep"])
+                .and_then(parse_minutes),
+            waiting_time: self
+                .meta(&["cook time", "cook_time", "time.cook"])
+                .and_then(parse_minutes)
+                .or_else(|| Some(timers).filter(|minutes| *minutes > 0)),
+            keywords: Some(keywords).filter(|k| !k.is_empty()),
+            steps: Some(self.steps.iter().map(step_request).collect()),
+        }
+    }
+}
+
+fn step_request(step: &CookStep) -> CreateStepRequest {
+    let instruction = step.section.as_ref().map_or_else(
+        || step.text.clone(),
+        |section| format!("## {section}\n\n{}", step.text),
+    );
+    CreateStepRequest {
+        instruction,
+        ingredients: Some(step.ingredients.iter().map(ingredient_request).collect()),
+    }
+}
+
+fn ingredient_request(ingredient: &CookIngredient) -> CreateIngredientRequest {
+    let amount = ingredient.quantity.as_deref().and_then(parse_quantity);
+    // A quantity that isn't a number ("some", "to taste") goes in the note
+    let loose = ingredient.quantity.clone().filter(|_| amount.is_none());
+    let note: Vec<String> = [loose, ingredient.note.clone()]
+        .into_iter()
+        .flatten()
+        .collect();
+
+    CreateIngredientRequest {
+        amount,
+        food: CreateFoodRequest {
+            name: ingredient.name.clone(),
+        },
+        unit: ingredient
+            .unit
+            .clone()
+            .map(|name| CreateUnitRequest { name }),
+        note: Some(note.join(", ")).filter(|note| !note.is_empty()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const CHILI: &str = "\
+---
+title: Weeknight Chili
+tags: [chili, weeknight]
+servings: 6 bowls
+prep time: 15 minutes
+source:
+  name: Quick Eats
+  url: https://quickeats.example/chili
+---
+
+> One pot, thirty minutes.
+
+-- shopping: get the good chili powder
+== Brown ==
+
+Brown the @ground beef{1%lb} with the @onion{1}(diced) in a #dutch oven{}.
+
+== Simmer ==
+
+Add the @crushed tomatoes{28%oz}, @chili powder{2%tbsp} and @salt.
+Simmer for ~{20%minutes}. [- longer is better -]
+
+Serve with @?sour cream{some}.
+";
+
+    fn ingredient(name: &str, quantity: Option<&str>, unit: Option<&str>) -> CookIngredient {
+        CookIngredient {
+            name: name.to_string(),
+            quantity: quantity.map(str::to_string),
+            unit: unit.map(str::to_string),
+            note: None,
+        }
+    }
+
+    #[test]
+    fn test_parse_metadata_and_notes() {
+        let recipe = parse_cooklang(CHILI).unwrap();
+        assert_eq!(recipe.meta(&["title"]), Some("Weeknight Chili"));
+        assert_eq!(recipe.meta(&["tags"]), Some("chili, weeknight"));
+        assert_eq!(
+            recipe.meta(&["source", "source.url"]),
+            Some("https://quickeats.example/chili")
+        );
+        assert_eq!(recipe.notes, ["One pot, thirty minutes."]);
+    }
+
+    #[test]
+    fn test_parse_steps() {
+        let recipe = parse_cooklang(CHILI).unwrap();
+        assert_eq!(recipe.steps.len(), 3);
+
+        let brown = recipe.steps.first().unwrap();
+        assert_eq!(brown.section.as_deref(), Some("Brown"));
+        assert_eq!(
+            brown.text,
+            "Brown the ground beef with the onion in a dutch oven."
+        );
+        let mut onion = ingredient("onion", Some("1"), None);
+        onion.note = Some("diced".to_string());
+        assert_eq!(
+            brown.ingredients,
+            [ingredient("ground beef", Some("1"), Some("lb")), onion]
+        );
+        assert_eq!(brown.cookware, ["dutch oven"]);
+    }
+
+    #[test]
+    fn test_parse_step_sections() {
+        let recipe = parse_cooklang(CHILI).unwrap();
+        let simmer = recipe.steps.get(1).unwrap();
+        assert_eq!(simmer.section.as_deref(), Some("Simmer"));
+        assert_eq!(
+            simmer.text,
+            "Add the crushed tomatoes, chili powder and salt.\nSimmer for 20 minutes."
+        );
+        assert_eq!(
+            simmer.ingredients.get(2),
+            Some(&ingredient("salt", None, None))
+        );
+        assert_eq!(simmer.timers.first().and_then(CookTimer::minutes), Some(20));
+
+        let serve = recipe.steps.get(2).unwrap();
+        assert_eq!(serve.section, None);
+        assert_eq!(serve.text, "Serve with sour cream.");
+    }
+
+    #[test]
+    fn test_markup_that_is_text() {
+        let step = parse_step("Serve @ the table, # of guests: 4, ~ 2 {cups");
+        assert_eq!(step.text, "Serve @ the table, # of guests: 4, ~ 2 {cups");
+        assert!(step.ingredients.is_empty());
+        assert!(step.cookware.is_empty());
+        assert!(step.timers.is_empty());
+    }
+
+    #[test]
+    fn test_to_create_request() {
+        let request = recipe_from_cooklang(CHILI, "chili").unwrap();
+        assert_eq!(request.name, "Weeknight Chili");
+        assert_eq!(
+            request.description.as_deref(),
+            Some("One pot, thirty minutes.")
+        );
+        assert_eq!(
+            request.source_url.as_deref(),
+            Some("https://quickeats.example/chili")
+        );
+        assert_eq!(request.servings, Some(6));
+        assert_eq!(request.working_time, Some(15));
+        assert_eq!(request.waiting_time, Some(20));
+    }
+
+    #[test]
+    fn test_to_create_request_steps() {
+        let request = recipe_from_cooklang(CHILI, "chili").unwrap();
+        let json = serde_json::to_value(&request).unwrap();
+        assert_eq!(
+            json.pointer("/keywords"),
+            Some(&serde_json::json!([{"name": "chili"}, {"name": "weeknight"}]))
+        );
+        assert_eq!(
+            json.pointer("/steps/1/instruction")
+                .and_then(|v| v.as_str()),
+            Some("## Simmer\n\nAdd the crushed tomatoes, chili powder and salt.\nSimmer for 20 minutes.")
+        );
+        assert_eq!(
+            json.pointer("/steps/0/ingredients/1"),
+            Some(&serde_json::json!({"amount": 1.0, "food": {"name": "onion"}, "note": "diced"}))
+        );
+        assert_eq!(
+            json.pointer("/steps/2/ingredients/0"),
+            Some(&serde_json::json!({"food": {"name": "sour cream"}, "note": "some"}))
+        );
+    }
+
+    #[test]
+    fn test_legacy_metadata_and_fallback_name() {
+        let source = ">> servings: 2\n>> cook time: 1h 30m\n\nToast the @bread{2%slices}.";
+        let request = recipe_from_cooklang(source, "toast").unwrap();
+        assert_eq!(request.name, "toast");
+        assert_eq!(request.servings, Some(2));
+        assert_eq!(request.waiting_time, Some(90));
+        assert!(request.keywords.is_none());
+    }
+
+    #[test]
+    fn test_invalid_front_matter() {
+        let result = parse_cooklang("---\ntitle: [unclosed\n---\nStep.");
+        assert!(matches!(result, Err(ImportError::Parse(_))));
+    }
+}
@@ -0,0 +1,441 @@
+//! Markdown import
+//!
+//! Reads documents laid out like the [Markdown export](crate::export::markdown),
+//! and hand-written ones that follow the same shape:
+//!
+//! - `# Title` names the recipe (else the file name does)
+//! - Text before the first `##` heading is the description; a
+//!   `- **Label:** value` list there sets servings, `Prep time`, `Cook time`,
+//!   `Source` and `Keywords`
+//! - `## Ingredients` lists one ingredient per item (`- 1 1/2 cups flour,
+//!   sifted`); `###` sub-headings are skipped
+//! - `## Instructions` (or `Directions`, `Method`, `Steps`) holds numbered
+//!   or bulleted steps, or one paragraph per step; a step starting with a
+//!   `**Name**` line, or following a `### Name`, gets a `## Name` heading
+//! - Other sections (`## Nutrition`, `## Notes`) are skipped
+//!
+//! Like the schema.org scraper, all ingredients go on the first step.
+
+use super::{parse_minutes, parse_servings, steps_with_ingredients, ImportError};
+use crate::tandoor::ingredient::parse_ingredient;
+use crate::tandoor::{CreateIngredientRequest, CreateKeywordRequest, CreateRecipeRequest};
+
+/// Section of the document being read
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Section {
+    Preamble,
+    Ingredients,
+    Instructions,
+    Other,
+}
+
+/// A step being collected: heading and text lines
+#[derive(Debug, Default)]
+struct Step {
+    heading: Option<String>,
+    lines: Vec<String>,
+}
+
+/// Everything collected from the document
+#[derive(Debug, Default)]
+struct Document {
+    title: Option<String>,
+    description: Vec<String>,
+    facts: Vec<(String, String)>,
+    ingredients: Vec<CreateIngredientRequest>,
+    steps: Vec<Step>,
+}
+
+/// Parse a Markdown recipe into a Tandoor recipe request
+///
+/// `fallback_name` is used when there is no `# Title`. A document with
+/// neither ingredients nor instructions is an error.
+pub fn recipe_from_markdown(
+    source: &str,
+    fallback_name: &str,
+) -> Result<CreateRecipeRequest, ImportError> {
+    let document = read_document(source);
+    if document.ingredients.is_empty() && document.steps.is_empty() {
+        return Err(ImportError::Parse(
+            "no ingredients or instructions found".to_string(),
+        ));
+    }
+
+    let mut request = CreateRecipeRequest {
+        name: document
+            .title
+            .clone()
+            .unwrap_or_else(|| fallback_name.to_string()),
+        description: Some(document.description.join("\n").trim().to_string())
+            .filter(|d| !d.is_empty()),
+        source_url: None,
+        servings: None,
+        working_time: None,
+        waiting_time: None,
+        keywords: None,
+        steps: None,
+    };
+    for (label, value) in &document.facts {
+        apply_fact(&mut request, label, value);
+    }
+
+    let instructions = document
+        .steps
+        .into_iter()
+        .map(|step| {
+            let text = step.lines.join("\n").trim().to_string();
+            match step.heading {
+                Some(heading) => format!("## {heading}\n\n{text}"),
+                None => text,
+            }
+        })
+        .collect();
+    let steps = steps_with_ingredients(instructions, document.ingredients);
+    request.steps = Some(steps);
+
+    Ok(request)
+}
+
+fn read_document(source: &str) -> Document {
+    let mut document = Document::default();
+    let mut section = Section::Preamble;
+    let mut pending_heading: Option<String> = None;
+    let mut after_blank = true;
+
+    for line in source.lines() {
+        let trimmed = line.trim();
+        if let Some(heading) = heading(trimmed) {
+            match heading {
+                (1, title) if document.title.is_none() => document.title = Some(title.to_string()),
+                (2, name) => section = section_of(name),
+                (_, name) if section == Section::Instructions => {
+                    pending_heading = Some(name.to_string());
+                }
+                _ => {}
+            }
+            after_blank = true;
+            continue;
+        }
+
+        match section {
+            Section::Preamble => read_preamble_line(&mut document, trimmed),
+            Section::Ingredients => {
+                if let Some(item) = list_item(trimmed) {
+                    document.ingredients.push(parse_ingredient_line(item));
+                }
+            }
+            Section::Instructions => {
+                read_instruction_line(&mut document.steps, &mut pending_heading, line, after_blank);
+            }
+            Section::Other => {}
+        }
+        after_blank = trimmed.is_empty();
+    }
+
+    document
+}
+
+/// `(level, text)` of an ATX heading
+fn heading(line: &str) -> Option<(usize, &str)> {
+    let level = line.len() - line.trim_start_matches('#').len();
+    let text = line.get(level..)?;
+    (level > 0 && text.starts_with(' ')).then(|| (level, text.trim().trim_end_matches('#').trim()))
+}
+
+fn section_of(heading: &str) -> Section {
+    match heading.to_lowercase().as_str() {
+        "ingredients" => Section::Ingredients,
+        "instructions" | "directions" | "method" | "steps" | "preparation" => Section::Instructions,
+        _ => Section::Other,
+    }
+}
+
+/// Text of a `-`, `*` or `+` list item
+fn list_item(line: &str) -> Option<&str> {
+    line.strip_prefix("- ")
+        .or_else(|| line.strip_prefix("* "))
+        .or_else(|| line.strip_prefix("+ "))
+        .map(str::trim)
+}
+
+/// Text of a numbered list item (`1.` or `1)`)
+fn numbered_item(line: &str) -> Option<&str> {
+    let digits = line.len() - line.trim_start_matches(|c: char| c.is_ascii_digit()).len();
+    let rest = line.get(digits..)?;
+    let text = rest
+        .strip_prefix(". ")
+        .or_else(|| rest.strip_prefix(") "))?;
+    (digits > 0).then(|| text.trim())
+}
+
+fn read_preamble_line(document: &mut Document, line: &str) {
+    if let Some((label, value)) = list_item(line).and_then(fact) {
+        document.facts.push((label, value));
+    } else if !line.starts_with("![") && (!line.is_empty() || !document.description.is_empty()) {
+        document.description.push(line.to_string());
+    }
+}
+
+/// `**Label:** value` or `**Label**: value`
+fn fact(item: &str) -> Option<(String, String)> {
+    let (label, value) = item.strip_prefix("**")?.split_once("**")?;
+    Some((
+        label.trim().trim_end_matches(':').trim().to_lowercase(),
+        value.trim_start_matches(':').trim().to_string(),
+    ))
+}
+
+fn read_instruction_line(
+    steps: &mut Vec<Step>,
+    pending_heading: &mut Option<String>,
+    line: &str,
+    after_blank: bool,
+) {
+    let trimmed = line.trim();
+    if trimmed.is_empty() {
+        return;
+    }
+
+    let item = numbered_item(trimmed).or_else(|| list_item(trimmed));
+    let continues = steps.last().is_some()
+        && item.is_none()
+        && (line.starts_with(char::is_whitespace) || !after_blank);
+    if continues {
+        if let Some(step) = steps.last_mut() {
+            step.lines.push(trimmed.to_string());
+        }
+        return;
+    }
+
+    let text = item.unwrap_or(trimmed);
+    let mut step = Step {
+        heading: pending_heading.take(),
+        lines: Vec::new(),
+    };
+    match text
+        .strip_prefix("**")
+        .and_then(|t| t.strip_suffix("**"))
+        .filter(|_| step.heading.is_none())
+    {
+        Some(name) => step.heading = Some(name.trim().to_string()),
+        None => step.lines.push(text.to_string()),
+    }
+    steps.push(step);
+}
+
+fn apply_fact(request: &mut CreateRecipeRequest, label: &str, value: &str) {
+    match label {
+        "servings" | "serves" | "yield" => request.servings = parse_servings(value),
+        "prep time" => request.working_time = parse_minutes(value),
+        "cook time" => request.waiting_time = parse_minutes(value),
+        "source" => {
+            request.source_url = Some(value.trim_start_matches('<').trim_end_matches('>'))
+                .filter(|url| !url.is_empty())
+                .map(str::to_string);
+        }
+        "keywords" | "tags" => {
+            let keywords: Vec<CreateKeywordRequest> = value
+                .split(',')
+                .map(str::trim)
+                .filter(|name| !name.is_empty())
+                .map(|name| CreateKeywordRequest {
+                    name: name.to_string(),
+                })
+                .collect();
+            request.keywords = Some(keywords).filter(|k| !k.is_empty());
+        }
+        _ => {}
+    }
+}
+
+/// Split an ingredient line into amount, unit, food and note
+///
+/// `1 1/2 cups flour, sifted` is 1.5 cups of flour with the note "sifted";
+/// see [`parse_ingredient`] for what is recognized. Units are kept as
+/// written.
+pub fn parse_ingredient_line(line: &str) -> CreateIngredientRequest {
+    parse_ingredient(line).to_create_request()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Output of the Markdown export
+    const BRISKET: &str = "\
+# Texas Style Brisket
+
+Salt, pepper & smoke.
+
+![Texas Style Brisket](https://tandoor.example/media/brisket.jpg)
+
+- **Servings:** 12 servings
+- **Prep time:** 30 min
+- **Cook time:** 12 h
+- **Source:** <https://smokeandfire.example/texas-brisket/>
+- **Keywords:** bbq, beef
+
+## Ingredients
+
+- 1 whole packer brisket, 12-14 lb
+
+### For the rub
+
+- 1/4 cup kosher salt
+- 1/4 cup black pepper, coarse
+- post oak
+
+## Instructions
+
+1. **Prep**
+
+   Trim the fat, then season with the kosher salt and black pepper.
+
+2. Smoke over post oak at 250°F until done.
+   Wrap at 165°F.
+
+## Nutrition
+
+| Calories | Protein | Fat | Carbohydrates |
+|----------|---------|-----|---------------|
+| 450 kcal | 38.5 g | 30 g | 0 g |
+";
+
+    #[test]
+    fn test_exported_markdown() {
+        let request = recipe_from_markdown(BRISKET, "brisket").unwrap();
+        assert_eq!(request.name, "Texas Style Brisket");
+        assert_eq!(
+            request.description.as_deref(),
+            Some("Salt, pepper & smoke.")
+        );
+        assert_eq!(request.servings, Some(12));
+        assert_eq!(request.working_time, Some(30));
+        assert_eq!(request.waiting_time, Some(720));
+        assert_eq!(
+            request.source_url.as_deref(),
+            Some("https://smokeandfire.example/texas-brisket/")
+        );
+    }
+
+    #[test]
+    fn test_exported_markdown_steps() {
+        let request = recipe_from_markdown(BRISKET, "brisket").unwrap();
+        let json = serde_json::to_value(&request).unwrap();
+        assert_eq!(
+            json.pointer("/keywords"),
+            Some(&serde_json::json!([{"name": "bbq"}, {"name": "beef"}]))
+        );
+        assert_eq!(
+            json.pointer("/steps/0/instruction")
+                .and_then(|v| v.as_str()),
+            Some("## Prep\n\nTrim the fat, then season with the kosher salt and black pepper.")
+        );
+        assert_eq!(
+            json.pointer("/steps/1/instruction")
+                .and_then(|v| v.as_str()),
+            Some("Smoke over post oak at 250°F until done.\nWrap at 165°F.")
+        );
+        assert_eq!(
+            json.pointer("/steps/0/ingredients")
+                .and_then(|v| v.as_array())
+                .map(Vec::len),
+            Some(4)
+        );
+        assert_eq!(
+            json.pointer("/steps/1/ingredients"),
+            Some(&serde_json::json!([]))
+        );
+    }
+
+    #[test]
+    fn test_paragraph_steps_and_fallback_name() {
+        let source = "\
+Grandma's version.
+
+## Ingredients
+
+* 2 eggs
+* salt (to taste)
+
+## Directions
+
+Whisk the eggs with the salt.
+
+### Cook
+
+Cook gently,
+stirring often.
+";
+        let request = recipe_from_markdown(source, "scrambled-eggs").unwrap();
+        assert_eq!(request.name, "scrambled-eggs");
+        assert_eq!(request.description.as_deref(), Some("Grandma's version."));
+
+        let json = serde_json::to_value(&request).unwrap();
+        assert_eq!(
+            json.pointer("/steps/1/instruction")
+                .and_then(|v| v.as_str()),
+            Some("## Cook\n\nCook gently,\nstirring often.")
+        );
+        assert_eq!(
+            json.pointer("/steps/0/ingredients/1"),
+            Some(&serde_json::json!({"food": {"name": "salt"}, "note": "to taste"}))
+        );
+    }
+
+    #[test]
+    fn test_no_recipe() {
+        let result = recipe_from_markdown("# Shopping\n\nJust a note.", "note");
+        assert!(matches!(result, Err(ImportError::Parse(_))));
+    }
+
+    /// Line, amount, unit, food, note
+    type Case<'a> = (
+        &'a str,
+        Option<f64>,
+        Option<&'a str>,
+        &'a str,
+        Option<&'a str>,
+    );
+
+    #[test]
+    fn test_parse_ingredient_line() {
+        let cases: &[Case] = &[
+            (
+                "1 1/2 cups flour, sifted",
+                Some(1.5),
+                Some("cups"),
+                "flour",
+                Some("sifted"),
+            ),
+            ("2 eggs", Some(2.0), None, "eggs", None),
+            (
+                "28 oz. crushed tomatoes",
+                Some(28.0),
+                Some("oz"),
+                "crushed tomatoes",
+                None,
+            ),
+            (
+                "1 whole packer brisket, 12-14 lb",
+                Some(1.0),
+                Some("whole"),
+                "packer brisket",
+                Some("12-14 lb"),
+            ),
+            ("salt (to taste)", None, None, "salt", Some("to taste")),
+            ("cup of tea", None, None, "cup of tea", None),
+        ];
+        for (line, amount, unit, food, note) in cases {
+            let parsed = parse_ingredient_line(line);
+            assert_eq!(parsed.amount, *amount, "{line}");
+            assert_eq!(
+                parsed.unit.as_ref().map(|u| u.name.as_str()),
+                *unit,
+                "{line}"
+            );
+            assert_eq!(parsed.food.name, *food, "{line}");
+            assert_eq!(parsed.note.as_deref(), *note, "{line}");
+        }
+    }
+}
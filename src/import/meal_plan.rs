@@ -0,0 +1,887 @@
+//! Meal plans from calendars and spreadsheets
+//!
+//! [`PlanFormat::read`] turns an iCalendar file or a CSV into
+//! [`PlannedMeal`]s, which [`import_meal_plans`](super::plan_sync::import_meal_plans)
+//! resolves against Tandoor and writes as meal plans.
+//!
+//! CSV columns (headers ignore case, spaces and underscores): `date`
+//! (YYYY-MM-DD), `recipe` (a name or an ID), and optionally `meal_type`,
+//! `servings`, `note` and `meal_plan_id`.
+//!
+//! Calendar events: the day (and time) comes from `DTSTART`, the recipe from
+//! a Tandoor recipe link in `URL`, else the `SUMMARY`. The meal type is the
+//! first of `CATEGORIES`, and `Servings: 4` in the description sets the
+//! servings. Events exported by `tandoor_meal_plan_calendar` carry their
+//! meal plan's ID in their UID, with the instance's host as its domain.
+//! UTC times (`…T000000Z`, as Google Calendar exports them) and times in a
+//! `TZID` zone the file defines are moved to the local time zone (`TZ`)
+//! before the day and meal type are taken;
+//! floating times are read as written. Recurring events count once, on
+//! their first day.
+
+use super::migrate::Unreadable;
+use super::{parse_quantity, ImportError};
+use chrono::{
+    Datelike, Days, Local, NaiveDate, NaiveDateTime, NaiveTime, TimeDelta, TimeZone, Weekday,
+};
+use csv::StringRecord;
+use serde::Serialize;
+use std::collections::HashMap;
+use std::path::Path;
+
+/// UID prefix of events exported by `tandoor_meal_plan_calendar`
+const UID_PREFIX: &str = "tandoor-meal-plan-";
+
+/// Path of a Tandoor recipe page
+const RECIPE_PATH: &str = "/view/recipe/";
+
+/// A recipe as given: by ID or by name
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum RecipeRef {
+    Id(i64),
+    Name(String),
+}
+
+impl std::fmt::Display for RecipeRef {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Id(id) => write!(f, "#{id}"),
+            Self::Name(name) => f.write_str(name),
+        }
+    }
+}
+
+impl RecipeRef {
+    /// An ID if `text` is a number, else a name
+    fn parse(text: &str) -> Self {
+        let text = text.trim();
+        text.trim_start_matches('#')
+            .parse()
+            .map_or_else(|_| Self::Name(text.to_string()), Self::Id)
+    }
+}
+
+/// A meal read from a calendar or spreadsheet
+#[derive(Debug, Clone, PartialEq)]
+pub struct PlannedMeal {
+    /// Where it was read, for the report (`row 3`, `event 2`)
+    pub source: String,
+    pub date: NaiveDate,
+    /// Start time, for calendar events that have one
+    pub time: Option<NaiveTime>,
+    /// Meal type name, as written
+    pub meal_type: Option<String>,
+    pub recipe: RecipeRef,
+    pub servings: Option<f64>,
+    pub note: Option<String>,
+    /// Meal plan this meal was exported from
+    pub meal_plan_id: Option<i64>,
+    /// Host of the Tandoor instance that `meal_plan_id` and a linked recipe
+    /// ID belong to: the event's recipe link's host, else its UID's domain
+    pub origin: Option<String>,
+    /// The event's summary, when the recipe is a linked ID: it is matched by
+    /// name instead if the link is to another instance
+    pub summary: Option<String>,
+}
+
+/// Meals read from a file
+#[derive(Debug, Default)]
+pub struct PlanFile {
+    /// Meals, in file order
+    pub meals: Vec<PlannedMeal>,
+    /// Rows or events that could not be read
+    pub unreadable: Vec<Unreadable>,
+}
+
+/// Meal plan file format
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, serde::Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum PlanFormat {
+    /// iCalendar (`.ics`)
+    Ics,
+    /// CSV with a header row (`.csv`)
+    Csv,
+}
+
+impl PlanFormat {
+    /// Format of a file, by extension
+    pub fn of(path: &Path) -> Option<Self> {
+        let extension = path.extension()?.to_str()?.to_ascii_lowercase();
+        match extension.as_str() {
+            "ics" | "ical" | "ifb" => Some(Self::Ics),
+            "csv" => Some(Self::Csv),
+            _ => None,
+        }
+    }
+
+    /// Read the meals of a file in this format
+    pub fn read(self, bytes: &[u8]) -> Result<PlanFile, ImportError> {
+        match self {
+            Self::Ics => read_meal_plan_ics(&String::from_utf8_lossy(bytes)),
+            Self::Csv => read_meal_plan_csv(bytes),
+        }
+    }
+}
+
+/// A content line: `NAME;PARAM=VALUE:value`
+#[derive(Debug)]
+struct Property {
+    name: String,
+    /// Parameters, with names uppercased and quotes removed
+    params: Vec<(String, String)>,
+    value: String,
+}
+
+impl Property {
+    fn parse(line: &str) -> Option<Self> {
+        // The value starts at the first colon outside a quoted parameter
+        let mut quoted = false;
+        let colon = line.char_indices().find_map(|(index, c)| {
+            if c == '"' {
+                quoted = !quoted;
+            }
+            (c == ':' && !quoted).then_some(index)
+        })?;
+        let (head, value) = line.split_at(colon);
+        let mut head = head.split(';');
+        let name = head.next().unwrap_or_default().to_ascii_uppercase();
+        let params = head
+            .filter_map(|param| {
+                let (key, value) = param.split_once('=')?;
+                Some((
+                    key.trim().to_ascii_uppercase(),
+                    value.trim().trim_matches('"').to_string(),
+                ))
+            })
+            .collect();
+        Some(Self {
+            name,
+            params,
+            value: value.get(1..).unwrap_or_default().to_string(),
+        })
+    }
+
+    fn param(&self, name: &str) -> Option<&str> {
+        self.params
+            .iter()
+            .find(|(key, _)| key == name)
+            .map(|(_, value)| value.as_str())
+    }
+}
+
+/// A component (`BEGIN:VEVENT` ... `END:VEVENT`) and those nested in it
+#[derive(Debug, Default)]
+struct Component {
+    name: String,
+    properties: Vec<Property>,
+    children: Vec<Component>,
+}
+
+impl Component {
+    /// First property named `name` with a non-empty value
+    fn property(&self, name: &str) -> Option<&Property> {
+        self.properties
+            .iter()
+            .find(|property| property.name == name && !property.value.trim().is_empty())
+    }
+
+    /// Trimmed, non-empty value of a property
+    fn get(&self, name: &str) -> Option<&str> {
+        self.property(name).map(|property| property.value.trim())
+    }
+
+    /// Nested components named `name`, not looking inside the ones found
+    fn find<'a>(&'a self, name: &str, found: &mut Vec<&'a Self>) {
+        for child in &self.children {
+            if child.name == name {
+                found.push(child);
+            } else {
+                child.find(name, found);
+            }
+        }
+    }
+}
+
+/// The components of a file under a nameless root; unterminated ones are
+/// dropped
+fn parse_components(lines: &[String]) -> Component {
+    let mut stack = vec![Component::default()];
+    for property in lines
+        .iter()
+        .filter_map(|line| Property::parse(line.trim_end()))
+    {
+        match property.name.as_str() {
+            "BEGIN" => stack.push(Component {
+                name: property.value.trim().to_ascii_uppercase(),
+                ..Component::default()
+            }),
+            "END" if stack.len() > 1 => {
+                let done = stack.pop();
+                if let (Some(done), Some(parent)) = (done, stack.last_mut()) {
+                    parent.children.push(done);
+                }
+            }
+            "END" => {}
+            _ => {
+                if let Some(current) = stack.last_mut() {
+                    current.properties.push(property);
+                }
+            }
+        }
+    }
+    stack.truncate(1);
+    stack.pop().unwrap_or_default()
+}
+
+/// Lines with folded continuations joined back (RFC 5545 §3.1)
+fn unfold(text: &str) -> Vec<String> {
+    let mut lines: Vec<String> = Vec::new();
+    for line in text.lines() {
+        match (line.strip_prefix([' ', '\t']), lines.last_mut()) {
+            (Some(continuation), Some(last)) => last.push_str(continuation),
+            _ => lines.push(line.to_string()),
+        }
+    }
+    lines
+}
+
+/// Undo TEXT escaping
+fn unescape(text: &str) -> String {
+    let mut unescaped = String::with_capacity(text.len());
+    let mut chars = text.chars();
+    while let Some(c) = chars.next() {
+        if c != '\\' {
+            unescaped.push(c);
+            continue;
+        }
+        match chars.next() {
+            Some('n' | 'N') => unescaped.push('\n'),
+            Some(other) => unescaped.push(other),
+            None => unescaped.push('\\'),
+        }
+    }
+    unescaped
+}
+
+/// Read the events of an iCalendar file, with times in the local time zone
+///
+/// Properties of components inside an event (its alarms) are ignored.
+pub fn read_meal_plan_ics(text: &str) -> Result<PlanFile, ImportError> {
+    read_meal_plan_ics_in(text, &Local)
+}
+
+/// Read the events of an iCalendar file, with times in `zone`
+pub fn read_meal_plan_ics_in<Tz: TimeZone>(text: &str, zone: &Tz) -> Result<PlanFile, ImportError> {
+    let lines = unfold(text);
+    if !lines
+        .iter()
+        .any(|line| line.trim().eq_ignore_ascii_case("BEGIN:VCALENDAR"))
+    {
+        return Err(ImportError::Parse("not an iCalendar file".to_string()));
+    }
+
+    let root = parse_components(&lines);
+    let mut timezones = Vec::new();
+    root.find("VTIMEZONE", &mut timezones);
+    let clock = Clock {
+        zones: timezones
+            .into_iter()
+            .filter_map(|timezone| Some((timezone.get("TZID")?, timezone)))
+            .collect(),
+        local: zone,
+    };
+    let mut events = Vec::new();
+    root.find("VEVENT", &mut events);
+
+    let mut file = PlanFile::default();
+    for (index, event) in events.into_iter().enumerate() {
+        let source = format!("event {}", index + 1);
+        match read_event(event, &clock, &source) {
+            Ok(meal) => file.meals.push(meal),
+            Err(error) => file.unreadable.push(Unreadable {
+                entry: source,
+                error,
+            }),
+        }
+    }
+    Ok(file)
+}
+
+/// Moves event times into the local zone
+struct Clock<'a, Tz> {
+    /// `VTIMEZONE`s of the file, by `TZID`
+    zones: HashMap<&'a str, &'a Component>,
+    local: &'a Tz,
+}
+
+impl<Tz: TimeZone> Clock<'_, Tz> {
+    /// Local day and time of a `DTSTART`
+    ///
+    /// UTC times and times in a zone the file defines are converted; floating
+    /// times, and those in a zone it doesn't define, are taken as written.
+    fn start(&self, start: &Property) -> Result<(NaiveDate, Option<NaiveTime>), String> {
+        let value = start.value.trim();
+        let date = value
+            .get(..8)
+            .and_then(|date| NaiveDate::parse_from_str(date, "%Y%m%d").ok())
+            .ok_or_else(|| format!("invalid start date: {value}"))?;
+        let Some(time) = value
+            .get(9..15)
+            .and_then(|time| NaiveTime::parse_from_str(time, "%H%M%S").ok())
+        else {
+            return Ok((date, None));
+        };
+        let written = date.and_time(time);
+        let offset = if value.ends_with(['Z', 'z']) {
+            Some(TimeDelta::zero())
+        } else {
+            start
+                .param("TZID")
+                .and_then(|tzid| self.zones.get(tzid))
+                .and_then(|zone| zone_offset(zone, written))
+        };
+        let local = offset
+            .and_then(|offset| written.checked_sub_signed(offset))
+            .map_or(written, |utc| {
+                self.local.from_utc_datetime(&utc).naive_local()
+            });
+        Ok((local.date(), Some(local.time())))
+    }
+}
+
+/// UTC offset of a `VTIMEZONE` at a time in it: that of the `STANDARD` or
+/// `DAYLIGHT` observance that began last
+fn zone_offset(zone: &Component, at: NaiveDateTime) -> Option<TimeDelta> {
+    zone.children
+        .iter()
+        .filter_map(|observance| {
+            let start = parse_date_time(observance.get("DTSTART")?)?;
+            let offset = parse_offset(observance.get("TZOFFSETTO")?)?;
+            let onset = observance.get("RRULE").map_or_else(
+                || Some(start).filter(|start| *start <= at),
+                |rule| last_onset(rule, start, at),
+            )?;
+            Some((onset, offset))
+        })
+        .max_by_key(|(onset, _)| *onset)
+        .map(|(_, offset)| offset)
+}
+
+/// Latest onset of a yearly observance rule (`FREQ=YEARLY;BYMONTH=3;BYDAY=2SU`)
+/// by `at`; other rules aren't followed
+fn last_onset(rule: &str, start: NaiveDateTime, at: NaiveDateTime) -> Option<NaiveDateTime> {
+    let parts: HashMap<&str, &str> = rule
+        .split(';')
+        .filter_map(|part| part.split_once('='))
+        .collect();
+    if parts.get("FREQ") != Some(&"YEARLY") {
+        return None;
+    }
+    let month = parts
+        .get("BYMONTH")
+        .and_then(|month| month.parse().ok())
+        .unwrap_or_else(|| start.month());
+    let until = parts.get("UNTIL").and_then(|until| parse_date_time(until));
+    [at.year(), at.year() - 1]
+        .into_iter()
+        .filter_map(|year| {
+            let day = parts.get("BYDAY").map_or_else(
+                || NaiveDate::from_ymd_opt(year, month, start.day()),
+                |by_day| nth_weekday(year, month, by_day),
+            )?;
+            Some(day.and_time(start.time()))
+        })
+        .find(|onset| {
+            *onset <= at && *onset >= start && until.map_or(true, |until| *onset <= until)
+        })
+}
+
+/// Day of a `BYDAY` rule in a month: `2SU` is the second Sunday, `-1SU`
+/// the last
+fn nth_weekday(year: i32, month: u32, by_day: &str) -> Option<NaiveDate> {
+    let split = by_day.len().checked_sub(2)?;
+    let weekday = match by_day.get(split..)? {
+        "MO" => Weekday::Mon,
+        "TU" => Weekday::Tue,
+        "WE" => Weekday::Wed,
+        "TH" => Weekday::Thu,
+        "FR" => Weekday::Fri,
+        "SA" => Weekday::Sat,
+        "SU" => Weekday::Sun,
+        _ => return None,
+    };
+    let n: i64 = match by_day.get(..split)? {
+        "" => 1,
+        n => n.trim_start_matches('+').parse().ok()?,
+    };
+    if n > 0 {
+        return NaiveDate::from_weekday_of_month_opt(year, month, weekday, u8::try_from(n).ok()?);
+    }
+    let last = NaiveDate::from_ymd_opt(year, month + 1, 1)
+        .or_else(|| NaiveDate::from_ymd_opt(year + 1, 1, 1))?
+        .pred_opt()?;
+    let back = (7 + last.weekday().num_days_from_monday() - weekday.num_days_from_monday()) % 7;
+    let weeks = u64::try_from(-n - 1).ok()?;
+    last.checked_sub_days(Days::new(u64::from(back) + 7 * weeks))
+}
+
+/// A `DATE` or `DATE-TIME` value, ignoring any `Z`
+fn parse_date_time(value: &str) -> Option<NaiveDateTime> {
+    let date = NaiveDate::parse_from_str(value.get(..8)?, "%Y%m%d").ok()?;
+    let time = value
+        .get(9..15)
+        .and_then(|time| NaiveTime::parse_from_str(time, "%H%M%S").ok())
+        .unwrap_or(NaiveTime::MIN);
+    Some(date.and_time(time))
+}
+
+/// A UTC offset (`-0500`, `+0530`, `+053000`)
+fn parse_offset(text: &str) -> Option<TimeDelta> {
+    let text = text.trim();
+    let (sign, digits) = text
+        .strip_prefix('+')
+        .map(|digits| (1, digits))
+        .or_else(|| text.strip_prefix('-').map(|digits| (-1, digits)))?;
+    let hours: i64 = digits.get(..2)?.parse().ok()?;
+    let minutes: i64 = digits.get(2..4)?.parse().ok()?;
+    let seconds: i64 = digits.get(4..6).map_or(Some(0), |s| s.parse().ok())?;
+    Some(TimeDelta::seconds(
+        sign * (hours * 3600 + minutes * 60 + seconds),
+    ))
+}
+
+fn read_event<Tz: TimeZone>(
+    event: &Component,
+    clock: &Clock<'_, Tz>,
+    source: &str,
+) -> Result<PlannedMeal, String> {
+    let start = event.property("DTSTART").ok_or("no start date")?;
+    let (date, time) = clock.start(start)?;
+
+    let link = event.get("URL").and_then(recipe_link);
+    let summary = event.get("SUMMARY").map(unescape);
+    let (recipe, summary) = match (&link, summary) {
+        (Some((_, id)), summary) => (RecipeRef::Id(*id), summary),
+        (None, Some(summary)) => (RecipeRef::Name(summary), None),
+        (None, None) => return Err("no summary".to_string()),
+    };
+
+    let meal_type = event
+        .get("CATEGORIES")
+        .and_then(first_category)
+        .filter(|category| !category.is_empty());
+    let servings = event.get("DESCRIPTION").and_then(description_servings);
+    let exported = event.get("UID").and_then(|uid| {
+        let (local, domain) = uid.split_once('@')?;
+        Some((local.strip_prefix(UID_PREFIX)?.parse().ok()?, domain))
+    });
+    let origin = link
+        .and_then(|(host, _)| host)
+        .or_else(|| exported.map(|(_, domain)| domain.to_ascii_lowercase()));
+    // An event whose UID and link name different instances keeps neither ID
+    let meal_plan_id = exported
+        .filter(|(_, domain)| origin.as_deref() == Some(&domain.to_ascii_lowercase()))
+        .map(|(id, _)| id);
+
+    Ok(PlannedMeal {
+        source: source.to_string(),
+        date,
+        time,
+        meal_type,
+        recipe,
+        servings,
+        note: None,
+        meal_plan_id,
+        origin,
+        summary,
+    })
+}
+
+/// Host and recipe ID of a Tandoor recipe link
+fn recipe_link(url: &str) -> Option<(Option<String>, i64)> {
+    let (_, path) = url.split_once(RECIPE_PATH)?;
+    let id = path.split(['/', '?', '#']).next()?.parse().ok()?;
+    let host = url::Url::parse(url)
+        .ok()
+        .and_then(|url| url.host_str().map(str::to_ascii_lowercase));
+    Some((host, id))
+}
+
+/// Servings from a `Servings: 4` line of a description
+fn description_servings(description: &str) -> Option<f64> {
+    unescape(description).lines().find_map(|line| {
+        let (label, value) = line.split_once(':')?;
+        label
+            .trim()
+            .eq_ignore_ascii_case("servings")
+            .then(|| parse_quantity(value))
+            .flatten()
+    })
+}
+
+/// First value of a comma-separated list, honouring `\,` escapes
+fn first_category(categories: &str) -> Option<String> {
+    let mut end = categories.len();
+    let mut escaped = false;
+    for (index, c) in categories.char_indices() {
+        match c {
+            ',' if !escaped => {
+                end = index;
+                break;
+            }
+            '\\' => escaped = !escaped,
+            _ => escaped = false,
+        }
+    }
+    categories
+        .get(..end)
+        .map(|category| unescape(category).trim().to_string())
+}
+
+/// Header key: lowercase, underscores as spaces
+fn header_key(header: &str) -> String {
+    header
+        .replace('_', " ")
+        .split_whitespace()
+        .collect::<Vec<_>>()
+        .join(" ")
+        .to_lowercase()
+}
+
+/// Column of the first header matching one of `names`
+fn column(headers: &StringRecord, names: &[&str]) -> Option<usize> {
+    headers
+        .iter()
+        .position(|header| names.contains(&header_key(header).as_str()))
+}
+
+/// Trimmed, non-empty field of a row
+fn field(record: &StringRecord, column: Option<usize>) -> Option<&str> {
+    column
+        .and_then(|column| record.get(column))
+        .map(str::trim)
+        .filter(|value| !value.is_empty())
+}
+
+struct PlanColumns {
+    date: usize,
+    recipe: usize,
+    meal_type: Option<usize>,
+    servings: Option<usize>,
+    note: Option<usize>,
+    meal_plan_id: Option<usize>,
+}
+
+/// Read a meal plan spreadsheet
+///
+/// Rows that can't be read are listed in [`PlanFile::unreadable`]; a file
+/// without `date` and `recipe` columns is an error.
+pub fn read_meal_plan_csv(bytes: &[u8]) -> Result<PlanFile, ImportError> {
+    let mut reader = csv::ReaderBuilder::new()
+        .flexible(true)
+        .trim(csv::Trim::All)
+        .from_reader(bytes);
+    let headers = reader
+        .headers()
+        .cloned()
+        .map_err(|e| ImportError::Parse(format!("not a meal plan CSV: {e}")))?;
+    let required = |names: &[&str]| {
+        column(&headers, names).ok_or_else(|| {
+            ImportError::Parse(format!(
+                "not a meal plan CSV: no {} column",
+                names.first().unwrap_or(&"")
+            ))
+        })
+    };
+    let columns = PlanColumns {
+        date: required(&["date", "day"])?,
+        recipe: required(&["recipe", "recipe name", "recipe id"])?,
+        meal_type: column(&headers, &["meal type", "meal", "type"]),
+        servings: column(&headers, &["servings", "serves"]),
+        note: column(&headers, &["note", "notes"]),
+        meal_plan_id: column(&headers, &["meal plan id", "meal plan"]),
+    };
+
+    let mut file = PlanFile::default();
+    for (index, record) in reader.records().enumerate() {
+        let source = format!("row {}", index + 2);
+        let meal = record
+            .map_err(|e| e.to_string())
+            .and_then(|record| read_row(&record, &columns, &source));
+        match meal {
+            Ok(Some(meal)) => file.meals.push(meal),
+            Ok(None) => {}
+            Err(error) => file.unreadable.push(Unreadable {
+                entry: source,
+                error,
+            }),
+        }
+    }
+    Ok(file)
+}
+
+/// A row's meal; blank rows are skipped
+fn read_row(
+    record: &StringRecord,
+    columns: &PlanColumns,
+    source: &str,
+) -> Result<Option<PlannedMeal>, String> {
+    if record.iter().all(|value| value.trim().is_empty()) {
+        return Ok(None);
+    }
+    let date = field(record, Some(columns.date)).ok_or("no date")?;
+    let date = date
+        .get(..10)
+        .and_then(|day| NaiveDate::parse_from_str(day, "%Y-%m-%d").ok())
+        .ok_or_else(|| format!("invalid date: {date} (expected YYYY-MM-DD)"))?;
+    let recipe = field(record, Some(columns.recipe)).ok_or("no recipe")?;
+    let servings = field(record, columns.servings)
+        .map(|servings| {
+            parse_quantity(servings)
+                .filter(|servings| *servings > 0.0)
+                .ok_or_else(|| format!("invalid servings: {servings}"))
+        })
+        .transpose()?;
+    let meal_plan_id = field(record, columns.meal_plan_id)
+        .map(|id| {
+            id.parse()
+                .map_err(|_| format!("invalid meal plan ID: {id}"))
+        })
+        .transpose()?;
+
+    Ok(Some(PlannedMeal {
+        source: source.to_string(),
+        date,
+        time: None,
+        meal_type: field(record, columns.meal_type).map(str::to_string),
+        recipe: RecipeRef::parse(recipe),
+        servings,
+        note: field(record, columns.note).map(str::to_string),
+        meal_plan_id,
+        origin: None,
+        summary: None,
+    }))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::FixedOffset;
+
+    const CALENDAR: &str = "BEGIN:VCALENDAR\r\n\
+        VERSION:2.0\r\n\
+        BEGIN:VEVENT\r\n\
+        UID:tandoor-meal-plan-42@tandoor.example\r\n\
+        DTSTART:20261020T183000\r\n\
+        SUMMARY:Dinner: Chicken Tikka\r\n\
+        DESCRIPTION:Servings: 4\\nWorking time: 30 min\\, waiting time: 15 min\r\n\
+        CATEGORIES:Dinner\r\n\
+        URL:https://tandoor.example/view/recipe/12\r\n\
+        BEGIN:VALARM\r\n\
+        TRIGGER:-PT12H\r\n\
+        DESCRIPTION:Servings: 9\r\n\
+        END:VALARM\r\n\
+        END:VEVENT\r\n\
+        BEGIN:VEVENT\r\n\
+        UID:0f3c@google.com\r\n\
+        DTSTART;VALUE=DATE:20261021\r\n\
+        SUMMARY:Lentil soup with a very long name that a calendar app has fol\r\n \
+        ded\r\n\
+        END:VEVENT\r\n\
+        BEGIN:VEVENT\r\n\
+        SUMMARY:No date\r\n\
+        END:VEVENT\r\n\
+        END:VCALENDAR\r\n";
+
+    #[test]
+    fn test_read_exported_event() {
+        let file = read_meal_plan_ics(CALENDAR).unwrap();
+        assert_eq!(file.meals.len(), 2);
+        assert_eq!(file.unreadable.len(), 1);
+        assert_eq!(file.unreadable[0].entry, "event 3");
+
+        let exported = &file.meals[0];
+        assert_eq!(
+            exported.date,
+            NaiveDate::from_ymd_opt(2026, 10, 20).unwrap()
+        );
+        assert_eq!(exported.time, NaiveTime::from_hms_opt(18, 30, 0));
+        assert_eq!(exported.recipe, RecipeRef::Id(12));
+        assert_eq!(exported.meal_type.as_deref(), Some("Dinner"));
+        assert_eq!(exported.servings, Some(4.0));
+        assert_eq!(exported.meal_plan_id, Some(42));
+    }
+
+    #[test]
+    fn test_read_other_event() {
+        let file = read_meal_plan_ics(CALENDAR).unwrap();
+        let google = &file.meals[1];
+        assert_eq!(google.time, None);
+        assert_eq!(
+            google.recipe,
+            RecipeRef::Name(
+                "Lentil soup with a very long name that a calendar app has folded".to_string()
+            )
+        );
+        assert_eq!(
+            (google.meal_type.as_deref(), google.meal_plan_id),
+            (None, None)
+        );
+    }
+
+    /// Google-style UTC times, and New York times with the zone defined
+    const ZONED: &str = "BEGIN:VCALENDAR\r\n\
+        BEGIN:VTIMEZONE\r\n\
+        TZID:America/New_York\r\n\
+        BEGIN:DAYLIGHT\r\n\
+        TZOFFSETFROM:-0500\r\n\
+        TZOFFSETTO:-0400\r\n\
+        DTSTART:20070311T020000\r\n\
+        RRULE:FREQ=YEARLY;BYMONTH=3;BYDAY=2SU\r\n\
+        END:DAYLIGHT\r\n\
+        BEGIN:STANDARD\r\n\
+        TZOFFSETFROM:-0400\r\n\
+        TZOFFSETTO:-0500\r\n\
+        DTSTART:20071104T020000\r\n\
+        RRULE:FREQ=YEARLY;BYMONTH=11;BYDAY=1SU\r\n\
+        END:STANDARD\r\n\
+        END:VTIMEZONE\r\n\
+        BEGIN:VEVENT\r\n\
+        DTSTART:20261021T000000Z\r\n\
+        SUMMARY:Chili\r\n\
+        END:VEVENT\r\n\
+        BEGIN:VEVENT\r\n\
+        DTSTART;TZID=America/New_York:20261020T190000\r\n\
+        SUMMARY:Chili\r\n\
+        END:VEVENT\r\n\
+        BEGIN:VEVENT\r\n\
+        DTSTART;TZID=America/New_York:20261220T190000\r\n\
+        SUMMARY:Chili\r\n\
+        END:VEVENT\r\n\
+        BEGIN:VEVENT\r\n\
+        DTSTART;TZID=Europe/Berlin:20261220T190000\r\n\
+        SUMMARY:Chili\r\n\
+        END:VEVENT\r\n\
+        END:VCALENDAR\r\n";
+
+    fn starts(zone: &FixedOffset) -> Vec<String> {
+        read_meal_plan_ics_in(ZONED, zone)
+            .unwrap()
+            .meals
+            .iter()
+            .map(|meal| format!("{} {}", meal.date, meal.time.unwrap()))
+            .collect()
+    }
+
+    #[test]
+    fn test_read_zoned_times() {
+        // A 19:00 dinner in UTC-5, exported in UTC, lands on its own day
+        let new_york = FixedOffset::west_opt(5 * 3600).unwrap();
+        assert_eq!(starts(&new_york)[0], "2026-10-20 19:00:00");
+        // Daylight saving time in October, standard time in December;
+        // a zone the file doesn't define is taken as written
+        let utc = FixedOffset::east_opt(0).unwrap();
+        assert_eq!(
+            starts(&utc),
+            [
+                "2026-10-21 00:00:00",
+                "2026-10-20 23:00:00",
+                "2026-12-21 00:00:00",
+                "2026-12-20 19:00:00"
+            ]
+        );
+    }
+
+    #[test]
+    fn test_observance_rules() {
+        let day = |y, m, d| NaiveDate::from_ymd_opt(y, m, d);
+        assert_eq!(nth_weekday(2026, 3, "2SU"), day(2026, 3, 8));
+        assert_eq!(nth_weekday(2026, 10, "-1SU"), day(2026, 10, 25));
+        assert_eq!(nth_weekday(2026, 12, "-1TH"), day(2026, 12, 31));
+        assert_eq!(nth_weekday(2026, 12, "XX"), None);
+        assert_eq!(parse_offset("+0530"), Some(TimeDelta::minutes(330)));
+        assert_eq!(parse_offset("-0500"), Some(TimeDelta::hours(-5)));
+    }
+
+    #[test]
+    fn test_exported_ids_name_their_instance() {
+        let file = read_meal_plan_ics(CALENDAR).unwrap();
+        let exported = &file.meals[0];
+        assert_eq!(exported.origin.as_deref(), Some("tandoor.example"));
+        assert_eq!(exported.summary.as_deref(), Some("Dinner: Chicken Tikka"));
+        assert_eq!(file.meals[1].origin, None);
+
+        // A UID from one instance and a link to another: neither is trusted
+        // for the meal plan
+        let mixed = "BEGIN:VCALENDAR\r\nBEGIN:VEVENT\r\n\
+            UID:tandoor-meal-plan-42@tandoor.example\r\n\
+            DTSTART:20261020T183000\r\n\
+            URL:https://other.example/view/recipe/12\r\n\
+            END:VEVENT\r\nEND:VCALENDAR\r\n";
+        let meal = &read_meal_plan_ics(mixed).unwrap().meals[0];
+        assert_eq!(meal.origin.as_deref(), Some("other.example"));
+        assert_eq!(
+            (&meal.recipe, meal.meal_plan_id),
+            (&RecipeRef::Id(12), None)
+        );
+    }
+
+    #[test]
+    fn test_ics_text_values() {
+        assert_eq!(
+            unescape("Salt\\, pepper\\; oil\\\\vinegar\\nServe"),
+            "Salt, pepper; oil\\vinegar\nServe"
+        );
+        assert_eq!(
+            first_category("Dinner\\, late,Family"),
+            Some("Dinner, late".to_string())
+        );
+        let property = Property::parse("DTSTART;TZID=\"Europe/Berlin:x\":20261020T183000").unwrap();
+        assert_eq!(
+            (property.name.as_str(), property.value.as_str()),
+            ("DTSTART", "20261020T183000")
+        );
+        assert!(read_meal_plan_ics("date,recipe").is_err());
+    }
+
+    #[test]
+    fn test_read_csv() {
+        let csv = "Date,Meal Type,Recipe,Servings,Notes\n\
+            2026-10-19,Dinner,Chili con Carne,4,Double batch\n\
+            2026-10-20,lunch,12,,\n\
+            ,,,,\n\
+            19/10/2026,Dinner,Chili,2,\n\
+            2026-10-21,Dinner,,2,\n\
+            2026-10-22,Dinner,Chili,lots,\n";
+        let file = read_meal_plan_csv(csv.as_bytes()).unwrap();
+
+        assert_eq!(file.meals.len(), 2);
+        let chili = &file.meals[0];
+        assert_eq!(chili.source, "row 2");
+        assert_eq!(chili.recipe, RecipeRef::Name("Chili con Carne".to_string()));
+        assert_eq!(chili.meal_type.as_deref(), Some("Dinner"));
+        assert_eq!(chili.servings, Some(4.0));
+        assert_eq!(chili.note.as_deref(), Some("Double batch"));
+        let by_id = &file.meals[1];
+        assert_eq!(
+            (&by_id.recipe, by_id.servings, &by_id.note),
+            (&RecipeRef::Id(12), None, &None)
+        );
+
+        let unreadable: Vec<_> = file.unreadable.iter().map(|u| u.entry.as_str()).collect();
+        assert_eq!(unreadable, ["row 5", "row 6", "row 7"]);
+    }
+
+    #[test]
+    fn test_csv_needs_date_and_recipe() {
+        let error = read_meal_plan_csv(b"day,meal\n2026-10-19,Dinner\n").unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "Invalid recipe file: not a meal plan CSV: no recipe column"
+        );
+        let file = read_meal_plan_csv(b"recipe_id,day,meal_plan_id\n7,2026-10-19,40\n").unwrap();
+        assert_eq!(file.meals[0].recipe, RecipeRef::Id(7));
+        assert_eq!(file.meals[0].meal_plan_id, Some(40));
+        assert_eq!(PlanFormat::of(Path::new("plan.ICS")), Some(PlanFormat::Ics));
+    }
+}
@@ -0,0 +1,304 @@
+//! Mealie import
+//!
+//! Reads Mealie recipe JSON: a single recipe, a list of recipes, a page from
+//! the `/api/recipes` endpoint (`{"items": [...]}`), or a zip export holding
+//! one `.json` file per recipe.
+//!
+//! Structured ingredients keep their quantity, unit, food and note; ones
+//! Mealie never parsed fall back to [`parse_ingredient_line`] on their text.
+//! Titled instructions get a `## Title` heading, as in the Markdown import.
+
+use super::markdown::parse_ingredient_line;
+use super::migrate::{AppExport, Unreadable};
+use super::{parse_minutes, parse_servings, steps_with_ingredients, ImportError};
+use crate::tandoor::{
+    CreateFoodRequest, CreateIngredientRequest, CreateKeywordRequest, CreateRecipeRequest,
+    CreateUnitRequest,
+};
+use serde::Deserialize;
+use serde_json::Value;
+use std::io::{Cursor, Read};
+use std::path::Path;
+
+/// A recipe as Mealie exports it (the fields that map onto Tandoor)
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MealieRecipe {
+    pub name: Option<String>,
+    pub slug: Option<String>,
+    pub description: Option<String>,
+    /// Free text, like `4 servings` (older exports)
+    pub recipe_yield: Option<String>,
+    pub recipe_servings: Option<f64>,
+    /// Free text, like `15 minutes` or `PT15M`
+    pub prep_time: Option<String>,
+    pub perform_time: Option<String>,
+    #[serde(rename = "orgURL")]
+    pub org_url: Option<String>,
+    pub recipe_ingredient: Option<Vec<MealieIngredient>>,
+    pub recipe_instructions: Option<Vec<MealieInstruction>>,
+    pub tags: Option<Vec<MealieName>>,
+    pub recipe_category: Option<Vec<MealieName>>,
+}
+
+/// A Mealie ingredient, parsed (`food` set) or plain text
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MealieIngredient {
+    pub quantity: Option<f64>,
+    pub unit: Option<MealieName>,
+    pub food: Option<MealieName>,
+    pub note: Option<String>,
+    pub original_text: Option<String>,
+    pub display: Option<String>,
+}
+
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct MealieInstruction {
+    pub title: Option<String>,
+    pub text: Option<String>,
+}
+
+/// A unit, food, tag or category reference
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct MealieName {
+    pub name: String,
+}
+
+/// Trimmed text, if any
+fn non_empty(text: Option<&str>) -> Option<String> {
+    text.map(str::trim)
+        .filter(|text| !text.is_empty())
+        .map(str::to_string)
+}
+
+impl MealieIngredient {
+    /// The ingredient request, if there is anything to import
+    pub fn to_request(&self) -> Option<CreateIngredientRequest> {
+        let Some(food) = self.food.as_ref().and_then(|f| non_empty(Some(&f.name))) else {
+            let text = [&self.original_text, &self.note, &self.display]
+                .into_iter()
+                .find_map(|text| non_empty(text.as_deref()))?;
+            return Some(parse_ingredient_line(&text));
+        };
+        Some(CreateIngredientRequest {
+            amount: self.quantity.filter(|quantity| *quantity > 0.0),
+            food: CreateFoodRequest { name: food },
+            unit: self
+                .unit
+                .as_ref()
+                .and_then(|unit| non_empty(Some(&unit.name)))
+                .map(|name| CreateUnitRequest { name }),
+            note: non_empty(self.note.as_deref()),
+        })
+    }
+}
+
+impl MealieRecipe {
+    /// The Tandoor recipe request; `fallback_name` names an untitled recipe
+    pub fn to_create_request(&self, fallback_name: &str) -> CreateRecipeRequest {
+        let keywords: Vec<CreateKeywordRequest> = [&self.tags, &self.recipe_category]
+            .into_iter()
+            .flatten()
+            .flatten()
+            .filter_map(|tag| non_empty(Some(&tag.name)))
+            .map(|name| CreateKeywordRequest { name })
+            .collect();
+        let ingredients = self
+            .recipe_ingredient
+            .iter()
+            .flatten()
+            .filter_map(MealieIngredient::to_request)
+            .collect();
+        let instructions = self
+            .recipe_instructions
+            .iter()
+            .flatten()
+            .filter_map(|step| {
+                let text = non_empty(step.text.as_deref())?;
+                Some(match non_empty(step.title.as_deref()) {
+                    Some(title) => format!("## {title}\n\n{text}"),
+                    None => text,
+                })
+            })
+            .collect();
+
+        #[allow(clippy::cast_possible_truncation)]
+        let servings = self
+            .recipe_servings
+            .filter(|servings| *servings >= 1.0)
+            .map(|servings| servings.round() as i32)
+            .or_else(|| self.recipe_yield.as_deref().and_then(parse_servings));
+
+        CreateRecipeRequest {
+            name: [&self.name, &self.slug]
+                .into_iter()
+                .find_map(|name| non_empty(name.as_deref()))
+                .unwrap_or_else(|| fallback_name.to_string()),
+            description: non_empty(self.description.as_deref()),
+            source_url: non_empty(self.org_url.as_deref()),
+            servings,
+            working_time: self.prep_time.as_deref().and_then(parse_minutes),
+            waiting_time: self.perform_time.as_deref().and_then(parse_minutes),
+            keywords: Some(keywords).filter(|k| !k.is_empty()),
+            steps: Some(steps_with_ingredients(instructions, ingredients)),
+        }
+    }
+}
+
+/// Read a Mealie JSON export, or a zip of them
+///
+/// A recipe (or zip entry) that can't be read is listed in
+/// [`AppExport::unreadable`]; a file that is neither JSON nor zip is an error.
+pub fn read_mealie(bytes: &[u8]) -> Result<AppExport, ImportError> {
+    let mut export = AppExport::default();
+    if bytes.starts_with(b"PK") {
+        let mut archive = zip::ZipArchive::new(Cursor::new(bytes))
+            .map_err(|e| ImportError::Parse(format!("not a Mealie export: {e}")))?;
+        for index in 0..archive.len() {
+            let mut file = archive
+                .by_index(index)
+                .map_err(|e| ImportError::Parse(format!("Mealie archive: {e}")))?;
+            let entry = file.name().to_string();
+            let is_json = Path::new(&entry)
+                .extension()
+                .is_some_and(|ext| ext.eq_ignore_ascii_case("json"));
+            if file.is_dir() || !is_json {
+                continue;
+            }
+            let mut json = Vec::new();
+            match file
+                .read_to_end(&mut json)
+                .map_err(ImportError::from)
+                .and_then(|_| {
+                    serde_json::from_slice(&json).map_err(|e| ImportError::Parse(e.to_string()))
+                }) {
+                Ok(document) => read_values(document, &entry, &mut export),
+                Err(e) => export.unreadable.push(Unreadable {
+                    entry,
+                    error: e.to_string(),
+                }),
+            }
+        }
+        return Ok(export);
+    }
+
+    let document = serde_json::from_slice(bytes)
+        .map_err(|e| ImportError::Parse(format!("not a Mealie export: {e}")))?;
+    read_values(document, "export", &mut export);
+    Ok(export)
+}
+
+/// A Mealie JSON document
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum Document {
+    /// A page from `/api/recipes`
+    Page {
+        items: Vec<Value>,
+    },
+    List(Vec<Value>),
+    Recipe(Value),
+}
+
+/// Add the recipes in a JSON document
+fn read_values(document: Document, entry: &str, export: &mut AppExport) {
+    let recipes = match document {
+        Document::Page { items } => items,
+        Document::List(recipes) => recipes,
+        Document::Recipe(recipe) => vec![recipe],
+    };
+
+    for (index, recipe) in recipes.into_iter().enumerate() {
+        let fallback = format!("{entry} #{}", index + 1);
+        match serde_json::from_value::<MealieRecipe>(recipe) {
+            Ok(recipe) => export.recipes.push(recipe.to_create_request(&fallback)),
+            Err(e) => export.unreadable.push(Unreadable {
+                entry: fallback,
+                error: e.to_string(),
+            }),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const RECIPE: &str = r#"{
+        "id": "5b0c9a36-7d84-4a57-a2a1-0d1c0ad6b1d3",
+        "name": "Sheet Pan Gnocchi",
+        "slug": "sheet-pan-gnocchi",
+        "description": "Crispy gnocchi with roasted vegetables.",
+        "recipeServings": 4,
+        "recipeYield": "4 servings",
+        "prepTime": "10 minutes",
+        "performTime": "PT25M",
+        "orgURL": "https://weeknight.example/gnocchi",
+        "recipeIngredient": [
+            {"quantity": 1, "unit": {"name": "pound"}, "food": {"name": "gnocchi"}, "note": "shelf-stable", "originalText": "1 lb gnocchi"},
+            {"quantity": 0, "unit": null, "food": null, "note": "2 cups cherry tomatoes", "display": "2 cups cherry tomatoes"},
+            {"quantity": 0, "unit": null, "food": null, "note": "", "title": "Garnish"}
+        ],
+        "recipeInstructions": [
+            {"title": "Roast", "text": "Toss everything with oil and roast for 25 minutes."},
+            {"title": "", "text": "Serve hot."}
+        ],
+        "tags": [{"name": "Weeknight"}],
+        "recipeCategory": [{"name": "Dinner"}]
+    }"#;
+
+    #[test]
+    fn test_to_create_request() {
+        let recipe: MealieRecipe = serde_json::from_str(RECIPE).unwrap();
+        let request = recipe.to_create_request("fallback");
+        assert_eq!(request.name, "Sheet Pan Gnocchi");
+        assert_eq!(request.servings, Some(4));
+        assert_eq!(request.working_time, Some(10));
+        assert_eq!(request.waiting_time, Some(25));
+        assert_eq!(
+            request.source_url.as_deref(),
+            Some("https://weeknight.example/gnocchi")
+        );
+
+        let json = serde_json::to_value(&request).unwrap();
+        assert_eq!(
+            json.pointer("/keywords"),
+            Some(&serde_json::json!([{"name": "Weeknight"}, {"name": "Dinner"}]))
+        );
+        assert_eq!(
+            json.pointer("/steps/0/instruction"),
+            Some(&serde_json::json!(
+                "## Roast\n\nToss everything with oil and roast for 25 minutes."
+            ))
+        );
+        assert_eq!(
+            json.pointer("/steps/0/ingredients"),
+            Some(&serde_json::json!([
+                {"amount": 1.0, "food": {"name": "gnocchi"}, "unit": {"name": "pound"}, "note": "shelf-stable"},
+                {"amount": 2.0, "food": {"name": "cherry tomatoes"}, "unit": {"name": "cups"}}
+            ]))
+        );
+    }
+
+    #[test]
+    fn test_read_list_and_page() {
+        let list = format!("[{RECIPE}, {{\"name\": 7}}]");
+        let export = read_mealie(list.as_bytes()).unwrap();
+        assert_eq!(export.recipes.len(), 1);
+        assert_eq!(export.unreadable.len(), 1);
+        assert_eq!(
+            export.unreadable.first().map(|u| u.entry.as_str()),
+            Some("export #2")
+        );
+
+        let page = format!("{{\"page\": 1, \"items\": [{RECIPE}, {RECIPE}]}}");
+        assert_eq!(read_mealie(page.as_bytes()).unwrap().recipes.len(), 2);
+    }
+
+    #[test]
+    fn test_not_an_export() {
+        let result = read_mealie(b"Date,Meal,Calories\n");
+        assert!(matches!(result, Err(ImportError::Parse(_))));
+    }
+}
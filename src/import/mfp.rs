@@ -0,0 +1,509 @@
+//! MyFitnessPal import
+//!
+//! Two CSV exports are read, matching columns by header name (ignoring case
+//! and unit suffixes like `(g)`):
+//!
+//! - Recipes: one row per ingredient, with `Recipe`, `Ingredient` and
+//!   optional `Servings` and `Directions` columns. Rows with the same recipe
+//!   name make up one recipe; ingredient text is split with
+//!   [`parse_ingredient_line`].
+//! - Diary ("Nutrition Summary"): one row per meal per day, with `Date`,
+//!   `Meal`, `Calories` and optional `Fat`, `Carbohydrates` and `Protein`
+//!   columns. Each row becomes one custom `FatSecret` diary entry named
+//!   after the meal ("MyFitnessPal Breakfast"); meals other than breakfast,
+//!   lunch and dinner are logged as snacks.
+//!
+//! [`import_diary`] skips meals already logged under the same name, so a
+//! rerun doesn't log them twice.
+
+use super::markdown::parse_ingredient_line;
+use super::migrate::{AppExport, Unreadable};
+use super::{parse_servings, steps_with_ingredients, ImportError};
+use crate::fatsecret::core::{AccessToken, FatSecretConfig};
+use crate::fatsecret::diary::{
+    create_food_entry, date_to_int, get_food_entries, validate_custom_entry, FoodEntry,
+    FoodEntryInput, MealType,
+};
+use crate::tandoor::CreateRecipeRequest;
+use csv::StringRecord;
+use serde::Serialize;
+use std::collections::btree_map::Entry;
+use std::collections::BTreeMap;
+
+/// Serving description of an imported meal
+const MEAL_SERVING: &str = "1 meal";
+
+/// Column of the first header matching one of `names`
+fn column(headers: &StringRecord, names: &[&str]) -> Option<usize> {
+    headers.iter().position(|header| {
+        let key = header.split('(').next().unwrap_or_default().trim();
+        names.iter().any(|name| key.eq_ignore_ascii_case(name))
+    })
+}
+
+fn required_column(headers: &StringRecord, names: &[&str]) -> Result<usize, ImportError> {
+    column(headers, names).ok_or_else(|| {
+        ImportError::Parse(format!(
+            "not a MyFitnessPal export: no {} column",
+            names.first().unwrap_or(&"")
+        ))
+    })
+}
+
+/// Trimmed, non-empty field of a row
+fn field(record: &StringRecord, column: Option<usize>) -> Option<&str> {
+    column
+        .and_then(|column| record.get(column))
+        .map(str::trim)
+        .filter(|value| !value.is_empty())
+}
+
+fn reader(bytes: &[u8]) -> csv::Reader<&[u8]> {
+    csv::ReaderBuilder::new().flexible(true).from_reader(bytes)
+}
+
+fn headers(reader: &mut csv::Reader<&[u8]>) -> Result<StringRecord, ImportError> {
+    reader
+        .headers()
+        .cloned()
+        .map_err(|e| ImportError::Parse(format!("not a MyFitnessPal export: {e}")))
+}
+
+/// Rows of a recipe, gathered by name
+#[derive(Debug, Default)]
+struct MfpRecipe {
+    name: String,
+    servings: Option<i32>,
+    ingredients: Vec<String>,
+    directions: Vec<String>,
+}
+
+impl MfpRecipe {
+    fn to_create_request(&self) -> CreateRecipeRequest {
+        let ingredients = self
+            .ingredients
+            .iter()
+            .map(|line| parse_ingredient_line(line))
+            .collect();
+        CreateRecipeRequest {
+            name: self.name.clone(),
+            description: None,
+            source_url: None,
+            servings: self.servings,
+            working_time: None,
+            waiting_time: None,
+            keywords: None,
+            steps: Some(steps_with_ingredients(self.directions.clone(), ingredients)),
+        }
+    }
+}
+
+/// The recipe called `name`, added if this is its first row
+fn recipe_named<'a>(recipes: &'a mut Vec<MfpRecipe>, name: &str) -> Option<&'a mut MfpRecipe> {
+    let index = recipes
+        .iter()
+        .position(|recipe| recipe.name == name)
+        .unwrap_or_else(|| {
+            recipes.push(MfpRecipe {
+                name: name.to_string(),
+                ..MfpRecipe::default()
+            });
+            recipes.len() - 1
+        });
+    recipes.get_mut(index)
+}
+
+/// Read a MyFitnessPal recipe CSV
+///
+/// Rows without a recipe name are listed in [`AppExport::unreadable`]; a
+/// file without `Recipe` and `Ingredient` columns is an error.
+pub fn read_mfp_recipes(bytes: &[u8]) -> Result<AppExport, ImportError> {
+    let mut reader = reader(bytes);
+    let headers = headers(&mut reader)?;
+    let recipe_column = required_column(&headers, &["Recipe", "Recipe Name"])?;
+    let ingredient_column = required_column(&headers, &["Ingredient", "Ingredients"])?;
+    let servings_column = column(&headers, &["Servings"]);
+    let directions_column = column(&headers, &["Directions", "Instructions"]);
+
+    let mut recipes: Vec<MfpRecipe> = Vec::new();
+    let mut unreadable = Vec::new();
+    for (index, record) in reader.records().enumerate() {
+        let entry = format!("row {}", index + 2);
+        let record = match record {
+            Ok(record) => record,
+            Err(e) => {
+                unreadable.push(Unreadable {
+                    entry,
+                    error: e.to_string(),
+                });
+                continue;
+            }
+        };
+        let Some(name) = field(&record, Some(recipe_column)) else {
+            unreadable.push(Unreadable {
+                entry,
+                error: "no recipe name".to_string(),
+            });
+            continue;
+        };
+
+        let Some(recipe) = recipe_named(&mut recipes, name) else {
+            continue;
+        };
+        add_row(
+            recipe,
+            &record,
+            ingredient_column,
+            servings_column,
+            directions_column,
+        );
+    }
+
+    Ok(AppExport {
+        recipes: recipes.iter().map(MfpRecipe::to_create_request).collect(),
+        unreadable,
+    })
+}
+
+fn add_row(
+    recipe: &mut MfpRecipe,
+    record: &StringRecord,
+    ingredient: usize,
+    servings: Option<usize>,
+    directions: Option<usize>,
+) {
+    if let Some(line) = field(record, Some(ingredient)) {
+        recipe.ingredients.push(line.to_string());
+    }
+    if recipe.servings.is_none() {
+        recipe.servings = field(record, servings).and_then(parse_servings);
+    }
+    if recipe.directions.is_empty() {
+        recipe.directions = field(record, directions)
+            .unwrap_or_default()
+            .lines()
+            .map(str::trim)
+            .filter(|line| !line.is_empty())
+            .map(str::to_string)
+            .collect();
+    }
+}
+
+/// One meal of a MyFitnessPal diary day
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct MfpMeal {
+    /// Day (YYYY-MM-DD)
+    pub date: String,
+    /// Day as days since epoch
+    pub date_int: i32,
+    /// Meal name in MyFitnessPal ("Breakfast", "Snacks", "Meal 5")
+    pub mfp_meal: String,
+    /// `FatSecret` meal to log it as
+    pub meal: MealType,
+    pub calories: f64,
+    pub carbohydrate: f64,
+    pub protein: f64,
+    pub fat: f64,
+}
+
+impl MfpMeal {
+    /// Diary entry name
+    pub fn food_entry_name(&self) -> String {
+        format!("MyFitnessPal {}", self.mfp_meal)
+    }
+
+    /// The custom diary entry to create
+    pub fn to_entry_input(&self) -> FoodEntryInput {
+        FoodEntryInput::Custom {
+            food_entry_name: self.food_entry_name(),
+            serving_description: MEAL_SERVING.to_string(),
+            number_of_units: 1.0,
+            meal: self.meal,
+            date_int: self.date_int,
+            calories: self.calories,
+            carbohydrate: self.carbohydrate,
+            protein: self.protein,
+            fat: self.fat,
+        }
+    }
+}
+
+/// Meals read from a diary export
+#[derive(Debug, Default)]
+pub struct MfpDiary {
+    pub meals: Vec<MfpMeal>,
+    /// Rows that could not be read, with the reason
+    pub unreadable: Vec<Unreadable>,
+}
+
+/// Columns of a Nutrition Summary export
+struct DiaryColumns {
+    date: usize,
+    meal: usize,
+    calories: usize,
+    carbohydrate: Option<usize>,
+    protein: Option<usize>,
+    fat: Option<usize>,
+}
+
+/// A `FatSecret` meal for a MyFitnessPal meal name
+fn meal_type(mfp_meal: &str) -> MealType {
+    match mfp_meal.to_ascii_lowercase().as_str() {
+        "breakfast" => MealType::Breakfast,
+        "lunch" => MealType::Lunch,
+        "dinner" => MealType::Dinner,
+        _ => MealType::Snack,
+    }
+}
+
+/// A day as `YYYY-MM-DD` and days since epoch; `MM/DD/YYYY` is accepted too
+fn parse_date(text: &str) -> Result<(String, i32), String> {
+    let date = chrono::NaiveDate::parse_from_str(text, "%Y-%m-%d")
+        .or_else(|_| chrono::NaiveDate::parse_from_str(text, "%m/%d/%Y"))
+        .map_err(|e| format!("invalid date {text:?}: {e}"))?
+        .format("%Y-%m-%d")
+        .to_string();
+    let date_int = date_to_int(&date)?;
+    Ok((date, date_int))
+}
+
+/// A nutrient amount; a missing column or empty field is zero
+fn amount(record: &StringRecord, column: Option<usize>, label: &str) -> Result<f64, String> {
+    field(record, column).map_or(Ok(0.0), |value| {
+        value
+            .replace(',', "")
+            .parse()
+            .map_err(|_| format!("invalid {label} {value:?}"))
+    })
+}
+
+fn read_meal(record: &StringRecord, columns: &DiaryColumns) -> Result<MfpMeal, String> {
+    let date = field(record, Some(columns.date)).ok_or("no date")?;
+    let mfp_meal = field(record, Some(columns.meal)).ok_or("no meal")?;
+    let (date, date_int) = parse_date(date)?;
+    let meal = MfpMeal {
+        date,
+        date_int,
+        mfp_meal: mfp_meal.to_string(),
+        meal: meal_type(mfp_meal),
+        calories: amount(record, Some(columns.calories), "calories")?,
+        carbohydrate: amount(record, columns.carbohydrate, "carbohydrates")?,
+        protein: amount(record, columns.protein, "protein")?,
+        fat: amount(record, columns.fat, "fat")?,
+    };
+    validate_custom_entry(
+        &meal.food_entry_name(),
+        MEAL_SERVING,
+        1.0,
+        meal.calories,
+        meal.carbohydrate,
+        meal.protein,
+        meal.fat,
+    )
+    .map_err(|e| e.to_string())?;
+    Ok(meal)
+}
+
+/// Read a MyFitnessPal Nutrition Summary CSV
+///
+/// Rows that can't be read are listed in [`MfpDiary::unreadable`]; a file
+/// without `Date`, `Meal` and `Calories` columns is an error.
+pub fn read_mfp_diary(bytes: &[u8]) -> Result<MfpDiary, ImportError> {
+    let mut reader = reader(bytes);
+    let headers = headers(&mut reader)?;
+    let columns = DiaryColumns {
+        date: required_column(&headers, &["Date"])?,
+        meal: required_column(&headers, &["Meal"])?,
+        calories: required_column(&headers, &["Calories"])?,
+        carbohydrate: column(&headers, &["Carbohydrates", "Carbs"]),
+        protein: column(&headers, &["Protein"]),
+        fat: column(&headers, &["Fat", "Total Fat"]),
+    };
+
+    let mut diary = MfpDiary::default();
+    for (index, record) in reader.records().enumerate() {
+        let meal = record
+            .map_err(|e| e.to_string())
+            .and_then(|record| read_meal(&record, &columns));
+        match meal {
+            Ok(meal) => diary.meals.push(meal),
+            Err(error) => diary.unreadable.push(Unreadable {
+                entry: format!("row {}", index + 2),
+                error,
+            }),
+        }
+    }
+    Ok(diary)
+}
+
+/// What happened to a diary meal
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum DiaryAction {
+    /// Diary entry created (or, in a dry run, to be created)
+    Created,
+    /// An entry with the same name is already logged for that meal
+    AlreadyLogged,
+    Failed,
+}
+
+/// Outcome for one meal
+#[derive(Debug, Clone, Serialize)]
+pub struct ImportedMeal {
+    pub date: String,
+    pub meal: MealType,
+    pub food_entry_name: String,
+    pub calories: f64,
+    pub action: DiaryAction,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub food_entry_id: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+/// Diary import outcome
+#[derive(Debug, Default, Serialize)]
+pub struct DiaryReport {
+    pub dry_run: bool,
+    /// One entry per meal, in export order
+    pub meals: Vec<ImportedMeal>,
+    /// Rows of the export that could not be read
+    pub unreadable: Vec<Unreadable>,
+}
+
+/// Log MyFitnessPal meals to the `FatSecret` diary
+///
+/// Each day's diary is read first, and meals already logged there (same
+/// meal, same entry name) are skipped. With `dry_run` nothing is created,
+/// but the report says what would be.
+pub async fn import_diary(
+    config: &FatSecretConfig,
+    token: &AccessToken,
+    diary: MfpDiary,
+    dry_run: bool,
+) -> Result<DiaryReport, ImportError> {
+    let mut logged: BTreeMap<i32, Vec<FoodEntry>> = BTreeMap::new();
+    for meal in &diary.meals {
+        if let Entry::Vacant(slot) = logged.entry(meal.date_int) {
+            slot.insert(get_food_entries(config, token, meal.date_int).await?);
+        }
+    }
+
+    let mut meals = Vec::new();
+    for meal in diary.meals {
+        let name = meal.food_entry_name();
+        let already_logged = logged.get(&meal.date_int).is_some_and(|entries| {
+            entries
+                .iter()
+                .any(|entry| entry.meal == meal.meal && entry.food_entry_name == name)
+        });
+        let (action, food_entry_id, error) = if already_logged {
+            (DiaryAction::AlreadyLogged, None, None)
+        } else if dry_run {
+            (DiaryAction::Created, None, None)
+        } else {
+            match create_food_entry(config, token, meal.to_entry_input()).await {
+                Ok(id) => (DiaryAction::Created, Some(id.to_string()), None),
+                Err(e) => (DiaryAction::Failed, None, Some(e.to_string())),
+            }
+        };
+        meals.push(ImportedMeal {
+            date: meal.date,
+            meal: meal.meal,
+            food_entry_name: name,
+            calories: meal.calories,
+            action,
+            food_entry_id,
+            error,
+        });
+    }
+
+    Ok(DiaryReport {
+        dry_run,
+        meals,
+        unreadable: diary.unreadable,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const DIARY: &str = "\
+Date,Meal,Calories,Fat (g),Saturated Fat,Sodium (mg),Carbohydrates (g),Fiber,Sugar,Protein (g),Note
+2024-01-15,Breakfast,412.5,14,4,320,52,8,12,18,
+2024-01-15,Snacks,\"1,050\",40,10,900,120,6,60,30,movie night
+2024-01-16,Lunch,-5,0,0,0,0,0,0,0,
+not a date,Dinner,600,20,5,700,60,5,5,40,
+";
+
+    const RECIPES: &str = "\
+Recipe,Servings,Ingredient,Directions
+Turkey Chili,6,1 lb ground turkey,\"Brown the turkey.
+Simmer everything for 30 minutes.\"
+Turkey Chili,,2 cups kidney beans,
+Protein Pancakes,2 servings,1 cup oat flour,
+,,1 egg,
+";
+
+    #[test]
+    fn test_read_mfp_diary() {
+        let diary = read_mfp_diary(DIARY.as_bytes()).unwrap();
+        assert_eq!(diary.meals.len(), 2);
+
+        let breakfast = diary.meals.first().unwrap();
+        assert_eq!(breakfast.date, "2024-01-15");
+        assert_eq!(breakfast.date_int, 19737);
+        assert_eq!(breakfast.meal, MealType::Breakfast);
+        assert_eq!(breakfast.food_entry_name(), "MyFitnessPal Breakfast");
+        assert_eq!(
+            (
+                breakfast.calories,
+                breakfast.carbohydrate,
+                breakfast.protein,
+                breakfast.fat
+            ),
+            (412.5, 52.0, 18.0, 14.0)
+        );
+
+        let snacks = diary.meals.get(1).unwrap();
+        assert_eq!(snacks.meal, MealType::Snack);
+        assert!((snacks.calories - 1050.0).abs() < f64::EPSILON);
+
+        let rows: Vec<&str> = diary.unreadable.iter().map(|u| u.entry.as_str()).collect();
+        assert_eq!(rows, ["row 4", "row 5"]);
+    }
+
+    #[test]
+    fn test_read_mfp_recipes() {
+        let export = read_mfp_recipes(RECIPES.as_bytes()).unwrap();
+        let names: Vec<&str> = export.recipes.iter().map(|r| r.name.as_str()).collect();
+        assert_eq!(names, ["Turkey Chili", "Protein Pancakes"]);
+        assert_eq!(export.unreadable.len(), 1);
+
+        let chili = serde_json::to_value(export.recipes.first().unwrap()).unwrap();
+        assert_eq!(chili.pointer("/servings"), Some(&serde_json::json!(6)));
+        assert_eq!(
+            chili.pointer("/steps/1/instruction"),
+            Some(&serde_json::json!("Simmer everything for 30 minutes."))
+        );
+        assert_eq!(
+            chili.pointer("/steps/0/ingredients/1"),
+            Some(
+                &serde_json::json!({"amount": 2.0, "food": {"name": "kidney beans"}, "unit": {"name": "cups"}})
+            )
+        );
+    }
+
+    #[test]
+    fn test_wrong_export() {
+        assert!(matches!(
+            read_mfp_diary(RECIPES.as_bytes()),
+            Err(ImportError::Parse(_))
+        ));
+        assert!(matches!(
+            read_mfp_recipes(DIARY.as_bytes()),
+            Err(ImportError::Parse(_))
+        ));
+    }
+}
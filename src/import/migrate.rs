@@ -0,0 +1,167 @@
+//! Migration from other recipe apps
+//!
+//! [`App::read`] turns an export from Paprika, Mealie or MyFitnessPal into
+//! recipe requests, and [`import_recipes`] creates them in Tandoor. Run it
+//! with `dry_run` first: the report lists what would happen to every recipe,
+//! and the foods and units that would be created, without writing anything.
+//!
+//! A recipe whose name matches an existing Tandoor recipe (ignoring case) is
+//! left alone, so a migration can be rerun after fixing failures.
+
+use super::resolve::{normalize, Catalog, Resolution};
+use super::{mealie, mfp, paprika, ImportError};
+use crate::tandoor::{all_pages, CreateRecipeRequest, TandoorClient};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+/// Page size when listing existing recipes
+const PAGE_SIZE: u32 = 100;
+
+/// App an export comes from
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum App {
+    /// `.paprikarecipes` archive (or one `.paprikarecipe`)
+    Paprika,
+    /// Mealie recipe JSON, or a zip of it
+    Mealie,
+    /// MyFitnessPal recipe CSV
+    MyFitnessPal,
+}
+
+impl App {
+    /// Read the recipes of an export from this app
+    pub fn read(self, bytes: &[u8]) -> Result<AppExport, ImportError> {
+        match self {
+            Self::Paprika => paprika::read_paprika(bytes),
+            Self::Mealie => mealie::read_mealie(bytes),
+            Self::MyFitnessPal => mfp::read_mfp_recipes(bytes),
+        }
+    }
+}
+
+/// Part of an export that could not be read
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct Unreadable {
+    /// Archive entry, recipe or row
+    pub entry: String,
+    pub error: String,
+}
+
+/// Recipes read from another app's export
+#[derive(Debug, Default)]
+pub struct AppExport {
+    /// Recipes, in export order
+    pub recipes: Vec<CreateRecipeRequest>,
+    pub unreadable: Vec<Unreadable>,
+}
+
+/// What happened to a recipe
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum MigrationAction {
+    /// Recipe created (or, in a dry run, to be created)
+    Created,
+    /// A recipe with the same name is already in Tandoor
+    Exists,
+    Failed,
+}
+
+/// Outcome for one recipe
+#[derive(Debug, Clone, Serialize)]
+pub struct MigratedRecipe {
+    pub name: String,
+    pub action: MigrationAction,
+    /// Created or existing recipe (none in a dry run)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub recipe_id: Option<i64>,
+    pub ingredients: usize,
+    pub steps: usize,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+/// Migration outcome
+#[derive(Debug, Default, Serialize)]
+pub struct MigrationReport {
+    pub dry_run: bool,
+    /// One entry per recipe, in export order
+    pub recipes: Vec<MigratedRecipe>,
+    /// Foods and units created for the ingredients
+    #[serde(flatten)]
+    pub resolution: Resolution,
+    /// Parts of the export that could not be read
+    pub unreadable: Vec<Unreadable>,
+}
+
+/// Create the export's recipes in Tandoor
+///
+/// Recipes already in Tandoor (by name) are skipped, and a recipe that
+/// fails is reported without stopping the rest. With `dry_run` nothing is
+/// written, but the report says what would be.
+pub fn import_recipes(
+    client: &TandoorClient,
+    export: AppExport,
+    dry_run: bool,
+) -> Result<MigrationReport, ImportError> {
+    let mut existing: HashMap<String, Option<i64>> =
+        all_pages(|page| client.list_recipes(Some(page), Some(PAGE_SIZE)))?
+            .into_iter()
+            .map(|recipe| (normalize(&recipe.name), Some(recipe.id)))
+            .collect();
+    let mut catalog = Catalog::load(client)?;
+    let mut report = MigrationReport {
+        dry_run,
+        unreadable: export.unreadable,
+        ..MigrationReport::default()
+    };
+
+    for mut recipe in export.recipes {
+        let steps = recipe.steps.as_ref().map_or(0, Vec::len);
+        let ingredients = recipe
+            .steps
+            .iter()
+            .flatten()
+            .map(|step| step.ingredients.as_ref().map_or(0, Vec::len))
+            .sum();
+        let key = normalize(&recipe.name);
+
+        let (action, recipe_id, error) = if let Some(id) = existing.get(&key) {
+            (MigrationAction::Exists, *id, None)
+        } else {
+            match create(client, &mut catalog, &mut recipe, dry_run) {
+                Ok((id, resolution)) => {
+                    report.resolution.extend(resolution);
+                    existing.insert(key, id);
+                    (MigrationAction::Created, id, None)
+                }
+                Err(e) => (MigrationAction::Failed, None, Some(e.to_string())),
+            }
+        };
+        report.recipes.push(MigratedRecipe {
+            name: recipe.name,
+            action,
+            recipe_id,
+            ingredients,
+            steps,
+            error,
+        });
+    }
+
+    Ok(report)
+}
+
+/// Resolve a recipe's foods and units and create it (unless `dry_run`)
+fn create(
+    client: &TandoorClient,
+    catalog: &mut Catalog,
+    recipe: &mut CreateRecipeRequest,
+    dry_run: bool,
+) -> Result<(Option<i64>, Resolution), ImportError> {
+    let resolution = catalog.resolve(client, recipe, dry_run)?;
+    if dry_run {
+        return Ok((None, resolution));
+    }
+    let created = client.create_recipe(recipe)?;
+    Ok((Some(created.id), resolution))
+}
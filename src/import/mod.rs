@@ -0,0 +1,306 @@
+//! Recipe import from text files
+//!
+//! Parses recipes kept as plain text into a [`CreateRecipeRequest`]:
+//!
+//! - [`cooklang`] - Cooklang `.cook` files (`@ingredient{qty%unit}`,
+//!   `#cookware{}`, `~timer{}`, metadata)
+//! - [`markdown`] - Markdown documents laid out like the
+//!   [Markdown export](crate::export::markdown)
+//! - [`paprika`], [`mealie`], [`mfp`] - exports from other apps, read
+//!   through [`App`] and created by [`import_recipes`] (MyFitnessPal diary
+//!   exports go to the `FatSecret` diary via [`mfp::import_diary`])
+//! - [`meal_plan`] - meal plans from an iCalendar file or a spreadsheet,
+//!   written as Tandoor meal plans by [`import_meal_plans`]
+//!
+//! Parsers are pure and keep food and unit names as written. [`Catalog`]
+//! maps them onto existing Tandoor foods and units (creating the missing
+//! ones), and [`sync_directory`] keeps Tandoor in step with a directory of
+//! recipe files:
+//!
+//! ```rust,no_run
+//! use meal_planner::import::{sync_directory, SyncOptions};
+//! use meal_planner::tandoor::{TandoorClient, TandoorConfig};
+//!
+//! # fn main() -> Result<(), Box<dyn std::error::Error>> {
+//! let client = TandoorClient::new(&TandoorConfig {
+//!     base_url: "http://localhost:8090".to_string(),
+//!     api_token: "your-token".to_string(),
+//! })?;
+//! let report = sync_directory(&client, &SyncOptions::new("recipes"))?;
+//! println!("{} files synced", report.files.len());
+//! # Ok(())
+//! # }
+//! ```
+
+pub mod cooklang;
+pub mod markdown;
+pub mod meal_plan;
+pub mod mealie;
+pub mod mfp;
+pub mod migrate;
+pub mod paprika;
+pub mod plan_sync;
+pub mod resolve;
+pub mod sync;
+
+pub use cooklang::{parse_cooklang, recipe_from_cooklang, CookRecipe};
+pub use markdown::recipe_from_markdown;
+pub use meal_plan::{PlanFile, PlanFormat, PlannedMeal, RecipeRef};
+pub use migrate::{import_recipes, App, AppExport, MigrationReport};
+pub use plan_sync::{import_meal_plans, MealPlanImportOptions, MealPlanImportReport};
+pub use resolve::Catalog;
+pub use sync::{sync_directory, SyncOptions, SyncReport, SyncState};
+
+use crate::fatsecret::core::FatSecretError;
+use crate::tandoor::{
+    CreateIngredientRequest, CreateRecipeRequest, CreateStepRequest, TandoorError,
+};
+use std::path::Path;
+use thiserror::Error;
+
+/// Import errors
+#[derive(Debug, Error)]
+pub enum ImportError {
+    #[error("Tandoor error: {0}")]
+    Tandoor(#[from] TandoorError),
+
+    #[error("FatSecret error: {0}")]
+    FatSecret(#[from] FatSecretError),
+
+    #[error("I/O error: {0}")]
+    Io(#[from] std::io::Error),
+
+    #[error("Invalid recipe file: {0}")]
+    Parse(String),
+
+    #[error("Sync state error: {0}")]
+    StateError(String),
+}
+
+/// Recipe file format, by extension
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FileFormat {
+    /// Cooklang (`.cook`)
+    Cooklang,
+    /// Markdown (`.md`, `.markdown`)
+    Markdown,
+}
+
+impl FileFormat {
+    /// Format of a file, if it is a recipe file
+    pub fn of(path: &Path) -> Option<Self> {
+        let extension = path.extension()?.to_str()?.to_ascii_lowercase();
+        match extension.as_str() {
+            "cook" => Some(Self::Cooklang),
+            "md" | "markdown" => Some(Self::Markdown),
+            _ => None,
+        }
+    }
+
+    /// Parse `source` in this format; `fallback_name` names an untitled recipe
+    pub fn parse(
+        self,
+        source: &str,
+        fallback_name: &str,
+    ) -> Result<CreateRecipeRequest, ImportError> {
+        match self {
+            Self::Cooklang => recipe_from_cooklang(source, fallback_name),
+            Self::Markdown => recipe_from_markdown(source, fallback_name),
+        }
+    }
+}
+
+/// A quantity as a number: `2`, `0.5`, `1/2`, `1 1/2` or `1,5`
+///
+/// A range (`2-3`) counts as its lower end.
+pub fn parse_quantity(text: &str) -> Option<f64> {
+    let text = text.trim();
+    let text = text
+        .split_once('-')
+        .filter(|(low, _)| !low.trim().is_empty())
+        .map_or(text, |(low, _)| low.trim());
+
+    let mut total = 0.0;
+    let mut parts = 0;
+    for part in text.split_whitespace() {
+        let value = match part.split_once('/') {
+            Some((numerator, denominator)) => {
+                let denominator: f64 = denominator.parse().ok()?;
+                (denominator != 0.0).then_some(numerator.parse::<f64>().ok()? / denominator)?
+            }
+            None => part.replace(',', ".").parse().ok()?,
+        };
+        total += value;
+        parts += 1;
+    }
+
+    (parts > 0 && total.is_finite() && total >= 0.0).then_some(total)
+}
+
+/// A duration in minutes: `90`, `45 min`, `1 hour 30 minutes`, `1h30m`,
+/// `2 hrs`, `1 1/2 hours`, `1,5 h`
+pub fn parse_minutes(text: &str) -> Option<i32> {
+    let text = text.trim().to_ascii_lowercase();
+    let mut minutes = 0.0;
+    // Numbers since the last unit, read together (`1 1/2`)
+    let mut number: Vec<String> = Vec::new();
+    let mut found = false;
+
+    for token in split_number_words(&text) {
+        if token.starts_with(|c: char| c.is_ascii_digit() || matches!(c, '.' | '/')) {
+            number.push(token);
+            continue;
+        }
+        let factor = match token.trim_end_matches('.') {
+            "d" | "day" | "days" => 24.0 * 60.0,
+            "h" | "hr" | "hrs" | "hour" | "hours" => 60.0,
+            "m" | "min" | "mins" | "minute" | "minutes" => 1.0,
+            "s" | "sec" | "secs" | "second" | "seconds" => 1.0 / 60.0,
+            _ => continue,
+        };
+        if let Some(value) = take_quantity(&mut number) {
+            minutes += value * factor;
+            found = true;
+        }
+    }
+    // A number without a unit is minutes
+    if let Some(value) = take_quantity(&mut number) {
+        minutes += value;
+        found = true;
+    }
+
+    #[allow(clippy::cast_possible_truncation)]
+    found.then_some(minutes.round() as i32)
+}
+
+/// The pending number tokens as one quantity, clearing them
+fn take_quantity(number: &mut Vec<String>) -> Option<f64> {
+    let quantity = parse_quantity(&number.join(" "));
+    number.clear();
+    quantity
+}
+
+/// Split text into number and word tokens (`1h30m` -> `1`, `h`, `30`, `m`)
+///
+/// A comma between digits is a decimal comma (`1,5`); elsewhere it
+/// separates, like whitespace.
+fn split_number_words(text: &str) -> Vec<String> {
+    let mut tokens: Vec<String> = Vec::new();
+    let mut previous_numeric = None;
+    let mut chars = text.chars().peekable();
+    while let Some(c) = chars.next() {
+        let decimal_comma = c == ','
+            && previous_numeric == Some(true)
+            && chars.peek().is_some_and(char::is_ascii_digit);
+        if c.is_whitespace() || (c == ',' && !decimal_comma) {
+            previous_numeric = None;
+            continue;
+        }
+        let numeric = decimal_comma || c.is_ascii_digit() || matches!(c, '.' | '/');
+        match tokens.last_mut() {
+            Some(token) if previous_numeric == Some(numeric) => token.push(c),
+            _ => tokens.push(c.to_string()),
+        }
+        previous_numeric = Some(numeric);
+    }
+    tokens
+}
+
+/// Servings from text like `4`, `4-6` or `6 servings`
+pub fn parse_servings(text: &str) -> Option<i32> {
+    let digits: String = text
+        .trim()
+        .chars()
+        .take_while(char::is_ascii_digit)
+        .collect();
+    digits.parse().ok().filter(|servings| *servings > 0)
+}
+
+/// Steps for instructions whose ingredients are listed separately
+///
+/// Like the schema.org scraper, all ingredients go on the first step; a
+/// recipe without instructions gets one empty step to hold them.
+pub(crate) fn steps_with_ingredients(
+    instructions: Vec<String>,
+    ingredients: Vec<CreateIngredientRequest>,
+) -> Vec<CreateStepRequest> {
+    let mut steps: Vec<CreateStepRequest> = instructions
+        .into_iter()
+        .map(|instruction| CreateStepRequest {
+            instruction,
+            ingredients: Some(Vec::new()),
+        })
+        .collect();
+    if steps.is_empty() {
+        steps.push(CreateStepRequest {
+            instruction: String::new(),
+            ingredients: None,
+        });
+    }
+    if let Some(first) = steps.first_mut() {
+        first.ingredients = Some(ingredients);
+    }
+    steps
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_quantity() {
+        assert_eq!(parse_quantity("2"), Some(2.0));
+        assert_eq!(parse_quantity("0.5"), Some(0.5));
+        assert_eq!(parse_quantity("1,5"), Some(1.5));
+        assert_eq!(parse_quantity("1/4"), Some(0.25));
+        assert_eq!(parse_quantity("1 1/2"), Some(1.5));
+        assert_eq!(parse_quantity("2-3"), Some(2.0));
+        assert_eq!(parse_quantity("some"), None);
+        assert_eq!(parse_quantity("1/0"), None);
+        assert_eq!(parse_quantity(""), None);
+    }
+
+    #[test]
+    fn test_parse_minutes() {
+        assert_eq!(parse_minutes("90"), Some(90));
+        assert_eq!(parse_minutes("45 min"), Some(45));
+        assert_eq!(parse_minutes("1 hour 30 minutes"), Some(90));
+        assert_eq!(parse_minutes("1h30m"), Some(90));
+        assert_eq!(parse_minutes("2 hrs"), Some(120));
+        assert_eq!(parse_minutes("1.5 hours"), Some(90));
+        assert_eq!(parse_minutes("1 1/2 hours"), Some(90));
+        assert_eq!(parse_minutes("1,5 hours"), Some(90));
+        assert_eq!(parse_minutes("overnight"), None);
+    }
+
+    #[test]
+    fn test_split_number_words() {
+        assert_eq!(split_number_words("1h30m"), ["1", "h", "30", "m"]);
+        assert_eq!(split_number_words("1,5 hours"), ["1,5", "hours"]);
+        assert_eq!(
+            split_number_words("1 hour, 30 min"),
+            ["1", "hour", "30", "min"]
+        );
+    }
+
+    #[test]
+    fn test_parse_servings() {
+        assert_eq!(parse_servings("4"), Some(4));
+        assert_eq!(parse_servings("4-6"), Some(4));
+        assert_eq!(parse_servings("6 servings"), Some(6));
+        assert_eq!(parse_servings("a crowd"), None);
+    }
+
+    #[test]
+    fn test_file_format() {
+        assert_eq!(
+            FileFormat::of(Path::new("mains/Chili.cook")),
+            Some(FileFormat::Cooklang)
+        );
+        assert_eq!(
+            FileFormat::of(Path::new("README.MD")),
+            Some(FileFormat::Markdown)
+        );
+        assert_eq!(FileFormat::of(Path::new("notes.txt")), None);
+    }
+}
@@ -0,0 +1,209 @@
+//! Paprika import
+//!
+//! A `.paprikarecipes` export is a zip archive holding one `.paprikarecipe`
+//! file per recipe, each a gzipped JSON object. A single `.paprikarecipe`
+//! file is read too.
+//!
+//! Ingredients and directions are plain text, one per line; ingredient lines
+//! are split with [`parse_ingredient_line`], and each direction line becomes
+//! a step. Categories become keywords and notes follow the description.
+
+use super::markdown::parse_ingredient_line;
+use super::migrate::{AppExport, Unreadable};
+use super::{parse_minutes, parse_servings, steps_with_ingredients, ImportError};
+use crate::tandoor::{CreateKeywordRequest, CreateRecipeRequest};
+use flate2::read::GzDecoder;
+use serde::Deserialize;
+use std::io::{Cursor, Read};
+
+/// Leading bytes of a gzip stream
+const GZIP_MAGIC: &[u8] = &[0x1f, 0x8b];
+
+/// A recipe as Paprika exports it (the fields that map onto Tandoor)
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct PaprikaRecipe {
+    pub name: Option<String>,
+    /// Ingredient lines, newline-separated
+    pub ingredients: Option<String>,
+    /// Direction lines, newline-separated
+    pub directions: Option<String>,
+    pub description: Option<String>,
+    pub notes: Option<String>,
+    /// Free text, like `4` or `4 servings`
+    pub servings: Option<String>,
+    /// Free text, like `15 mins`
+    pub prep_time: Option<String>,
+    pub cook_time: Option<String>,
+    pub source_url: Option<String>,
+    #[serde(default)]
+    pub categories: Vec<String>,
+}
+
+/// Non-blank, trimmed lines of an optional text
+fn lines(text: Option<&str>) -> impl Iterator<Item = &str> {
+    text.unwrap_or_default()
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty())
+}
+
+/// Trimmed text, if any
+fn non_empty(text: Option<&str>) -> Option<String> {
+    text.map(str::trim)
+        .filter(|text| !text.is_empty())
+        .map(str::to_string)
+}
+
+impl PaprikaRecipe {
+    /// The Tandoor recipe request; `fallback_name` names an untitled recipe
+    pub fn to_create_request(&self, fallback_name: &str) -> CreateRecipeRequest {
+        let description: Vec<String> = [self.description.as_deref(), self.notes.as_deref()]
+            .into_iter()
+            .filter_map(non_empty)
+            .collect();
+        let keywords: Vec<CreateKeywordRequest> = self
+            .categories
+            .iter()
+            .filter_map(|category| non_empty(Some(category)))
+            .map(|name| CreateKeywordRequest { name })
+            .collect();
+        let ingredients = lines(self.ingredients.as_deref())
+            .map(parse_ingredient_line)
+            .collect();
+        let instructions = lines(self.directions.as_deref())
+            .map(str::to_string)
+            .collect();
+
+        CreateRecipeRequest {
+            name: non_empty(self.name.as_deref()).unwrap_or_else(|| fallback_name.to_string()),
+            description: Some(description.join("\n\n")).filter(|d| !d.is_empty()),
+            source_url: non_empty(self.source_url.as_deref()),
+            servings: self.servings.as_deref().and_then(parse_servings),
+            working_time: self.prep_time.as_deref().and_then(parse_minutes),
+            waiting_time: self.cook_time.as_deref().and_then(parse_minutes),
+            keywords: Some(keywords).filter(|k| !k.is_empty()),
+            steps: Some(steps_with_ingredients(instructions, ingredients)),
+        }
+    }
+}
+
+/// Read a `.paprikarecipes` archive, or a single `.paprikarecipe` file
+///
+/// An archive entry that can't be read is listed in
+/// [`AppExport::unreadable`]; a file that is neither is an error.
+pub fn read_paprika(bytes: &[u8]) -> Result<AppExport, ImportError> {
+    if bytes.starts_with(GZIP_MAGIC) {
+        return Ok(AppExport {
+            recipes: vec![read_recipe(bytes, "Untitled")?],
+            unreadable: Vec::new(),
+        });
+    }
+
+    let mut archive = zip::ZipArchive::new(Cursor::new(bytes))
+        .map_err(|e| ImportError::Parse(format!("not a Paprika export: {e}")))?;
+    let mut export = AppExport::default();
+    for index in 0..archive.len() {
+        let mut file = archive
+            .by_index(index)
+            .map_err(|e| ImportError::Parse(format!("Paprika archive: {e}")))?;
+        if file.is_dir() {
+            continue;
+        }
+        let entry = file.name().to_string();
+        let stem = entry.rsplit('/').next().unwrap_or(&entry);
+        let fallback = stem.strip_suffix(".paprikarecipe").unwrap_or(stem);
+
+        let mut data = Vec::new();
+        let recipe = file
+            .read_to_end(&mut data)
+            .map_err(ImportError::from)
+            .and_then(|_| read_recipe(&data, fallback));
+        match recipe {
+            Ok(recipe) => export.recipes.push(recipe),
+            Err(e) => export.unreadable.push(Unreadable {
+                entry,
+                error: e.to_string(),
+            }),
+        }
+    }
+    Ok(export)
+}
+
+/// One recipe: gzipped JSON (or, leniently, plain JSON)
+fn read_recipe(bytes: &[u8], fallback_name: &str) -> Result<CreateRecipeRequest, ImportError> {
+    let mut json = Vec::new();
+    if bytes.starts_with(GZIP_MAGIC) {
+        GzDecoder::new(bytes).read_to_end(&mut json)?;
+    } else {
+        json.extend_from_slice(bytes);
+    }
+    let recipe: PaprikaRecipe =
+        serde_json::from_slice(&json).map_err(|e| ImportError::Parse(e.to_string()))?;
+    Ok(recipe.to_create_request(fallback_name))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const RECIPE: &str = r#"{
+        "uid": "6D3B7A2E",
+        "name": "Overnight Oats",
+        "ingredients": "1 cup rolled oats\n\n1 cup milk\n2 tbsp chia seeds\nhoney, to taste",
+        "directions": "Stir everything together.\nRefrigerate overnight.",
+        "description": "",
+        "notes": "Keeps for three days.",
+        "servings": "2 servings",
+        "prep_time": "5 mins",
+        "cook_time": "8 hrs",
+        "source": "Family",
+        "source_url": null,
+        "categories": ["Breakfast", " "],
+        "rating": 5,
+        "photo_data": null
+    }"#;
+
+    #[test]
+    fn test_to_create_request() {
+        let recipe: PaprikaRecipe = serde_json::from_str(RECIPE).unwrap();
+        let request = recipe.to_create_request("oats");
+        assert_eq!(request.name, "Overnight Oats");
+        assert_eq!(
+            request.description.as_deref(),
+            Some("Keeps for three days.")
+        );
+        assert_eq!(request.source_url, None);
+        assert_eq!(request.servings, Some(2));
+        assert_eq!(request.working_time, Some(5));
+        assert_eq!(request.waiting_time, Some(480));
+
+        let json = serde_json::to_value(&request).unwrap();
+        assert_eq!(
+            json.pointer("/keywords"),
+            Some(&serde_json::json!([{"name": "Breakfast"}]))
+        );
+        assert_eq!(
+            json.pointer("/steps/1/instruction"),
+            Some(&serde_json::json!("Refrigerate overnight."))
+        );
+        assert_eq!(
+            json.pointer("/steps/0/ingredients/3"),
+            Some(&serde_json::json!({"food": {"name": "honey"}, "note": "to taste"}))
+        );
+    }
+
+    #[test]
+    fn test_read_single_gzipped_recipe() {
+        let mut encoder = flate2::write::GzEncoder::new(Vec::new(), flate2::Compression::default());
+        std::io::Write::write_all(&mut encoder, RECIPE.as_bytes()).unwrap();
+        let export = read_paprika(&encoder.finish().unwrap()).unwrap();
+        assert_eq!(export.recipes.len(), 1);
+        assert!(export.unreadable.is_empty());
+    }
+
+    #[test]
+    fn test_not_an_export() {
+        let result = read_paprika(b"name,servings\n");
+        assert!(matches!(result, Err(ImportError::Parse(_))));
+    }
+}
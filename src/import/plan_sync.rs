@@ -0,0 +1,605 @@
+//! Meal plan import
+//!
+//! [`import_meal_plans`] resolves the meals of a [`PlanFile`] against
+//! Tandoor and writes them as meal plans:
+//!
+//! - Recipes given by name are matched with
+//!   [`name_similarity`]. One exact match, or a best match clearly ahead of
+//!   the rest, resolves; otherwise the meal is reported as ambiguous with its
+//!   closest candidates and left for manual review.
+//! - Meal types are matched by name. Without one, a `Dinner: Chili` summary
+//!   prefix, the meal type whose time is closest to the event, or the
+//!   default meal type is used.
+//! - A meal replaces the plan it was exported from, or else an existing plan
+//!   of the same recipe in the same slot (day and meal type), so importing
+//!   the same file twice changes nothing. A different recipe already in the
+//!   slot is kept, and the meal is added beside it. Meal plan and recipe IDs
+//!   of events exported from another Tandoor instance (by the UID's domain
+//!   or the recipe link's host) are ignored.
+//!
+//! Like [`import_recipes`](super::import_recipes), run it with `dry_run`
+//! first: the report says what would happen to every meal without writing.
+
+use super::meal_plan::{PlanFile, PlannedMeal, RecipeRef};
+use super::migrate::Unreadable;
+use super::ImportError;
+use crate::scrape::crawler::name_similarity;
+use crate::tandoor::{
+    all_pages, CreateMealPlanRequest, MealPlan, MealType, RecipeSummary, TandoorClient,
+    TandoorError, UpdateMealPlanRequest,
+};
+use chrono::{NaiveDate, NaiveTime, Timelike};
+use serde::Serialize;
+use serde_json::Value;
+use std::borrow::Cow;
+use std::collections::HashSet;
+
+/// Page size when listing recipes and meal types
+const PAGE_SIZE: u32 = 100;
+
+/// How far the best recipe match must lead the next one to be picked
+const MATCH_MARGIN: f64 = 0.1;
+
+/// Candidates listed for an ambiguous recipe
+const MAX_CANDIDATES: usize = 3;
+
+/// Meal plan import options
+#[derive(Debug, Clone)]
+pub struct MealPlanImportOptions {
+    /// Report what would happen without writing (default true)
+    pub dry_run: bool,
+    /// Lowest name similarity for a recipe match (default 0.8)
+    pub min_similarity: f64,
+    /// Meal type for meals that don't name one (default: Tandoor's default)
+    pub default_meal_type: Option<String>,
+}
+
+impl Default for MealPlanImportOptions {
+    fn default() -> Self {
+        Self {
+            dry_run: true,
+            min_similarity: 0.8,
+            default_meal_type: None,
+        }
+    }
+}
+
+/// What happened to a meal
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum MealPlanAction {
+    /// Meal plan created (or, in a dry run, to be created)
+    Created,
+    /// Existing meal plan changed to match
+    Updated,
+    /// Existing meal plan already matches
+    Unchanged,
+    /// Several recipes match the name; see the candidates
+    Ambiguous,
+    /// No recipe matches
+    Unresolved,
+    Failed,
+}
+
+/// A recipe that might be the one meant
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct RecipeCandidate {
+    pub id: i64,
+    pub name: String,
+    /// Name similarity, 0.0 to 1.0
+    pub similarity: f64,
+}
+
+/// Outcome for one meal
+#[derive(Debug, Clone, Serialize)]
+pub struct ImportedMealPlan {
+    /// Row or event it was read from
+    pub source: String,
+    /// Day planned (YYYY-MM-DD)
+    pub date: String,
+    /// Resolved meal type
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub meal_type: Option<String>,
+    /// Recipe as written in the file
+    pub recipe: String,
+    pub action: MealPlanAction,
+    /// Created, updated or unchanged meal plan (none for a dry-run create)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub meal_plan_id: Option<i64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub recipe_id: Option<i64>,
+    /// Name of the matched recipe
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub matched_recipe: Option<String>,
+    /// Closest recipes, for ambiguous names
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub candidates: Vec<RecipeCandidate>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+/// Meal plan import outcome
+#[derive(Debug, Default, Serialize)]
+pub struct MealPlanImportReport {
+    pub dry_run: bool,
+    /// One entry per meal, in file order
+    pub meals: Vec<ImportedMealPlan>,
+    /// Rows or events that could not be read
+    pub unreadable: Vec<Unreadable>,
+}
+
+impl MealPlanImportReport {
+    /// Meals left for manual review (ambiguous or unresolved recipes)
+    pub fn needs_review(&self) -> impl Iterator<Item = &ImportedMealPlan> {
+        self.meals.iter().filter(|meal| {
+            matches!(
+                meal.action,
+                MealPlanAction::Ambiguous | MealPlanAction::Unresolved
+            )
+        })
+    }
+}
+
+/// Result of matching a recipe name
+#[derive(Debug)]
+enum RecipeMatch<'a> {
+    Found(&'a RecipeSummary),
+    Ambiguous(Vec<RecipeCandidate>),
+    Missing,
+}
+
+/// Match a recipe name against Tandoor's recipes
+fn match_recipe<'a>(
+    name: &str,
+    recipes: &'a [RecipeSummary],
+    min_similarity: f64,
+) -> RecipeMatch<'a> {
+    let mut scored: Vec<(f64, &RecipeSummary)> = recipes
+        .iter()
+        .map(|recipe| (name_similarity(name, &recipe.name), recipe))
+        .filter(|(similarity, _)| *similarity >= min_similarity)
+        .collect();
+    scored.sort_by(|a, b| b.0.total_cmp(&a.0));
+    let exact = scored
+        .iter()
+        .take_while(|(similarity, _)| *similarity >= 1.0)
+        .count();
+
+    match scored.as_slice() {
+        [] => RecipeMatch::Missing,
+        [(_, only)] => RecipeMatch::Found(only),
+        [(best, recipe), (second, _), ..]
+            if exact == 1 || (exact == 0 && best - second >= MATCH_MARGIN) =>
+        {
+            RecipeMatch::Found(recipe)
+        }
+        _ => RecipeMatch::Ambiguous(
+            scored
+                .iter()
+                .take(MAX_CANDIDATES)
+                .map(|(similarity, recipe)| RecipeCandidate {
+                    id: recipe.id,
+                    name: recipe.name.clone(),
+                    similarity: (similarity * 100.0).round() / 100.0,
+                })
+                .collect(),
+        ),
+    }
+}
+
+/// Meal type named `name`, ignoring case
+fn meal_type_named<'a>(types: &'a [MealType], name: &str) -> Option<&'a MealType> {
+    types
+        .iter()
+        .find(|meal_type| meal_type.name.trim().eq_ignore_ascii_case(name.trim()))
+}
+
+/// Meal type whose time of day is closest to `time`
+fn meal_type_at(types: &[MealType], time: NaiveTime) -> Option<&MealType> {
+    let seconds = |time: NaiveTime| i64::from(time.num_seconds_from_midnight());
+    types
+        .iter()
+        .filter_map(|meal_type| {
+            let at = meal_type.time.as_deref()?;
+            let at = NaiveTime::parse_from_str(at, "%H:%M:%S")
+                .or_else(|_| NaiveTime::parse_from_str(at, "%H:%M"))
+                .ok()?;
+            Some(((seconds(at) - seconds(time)).abs(), meal_type))
+        })
+        .min_by_key(|(distance, _)| *distance)
+        .map(|(_, meal_type)| meal_type)
+}
+
+/// Split a `Dinner: Chili` summary into a known meal type and the recipe
+fn split_meal_type<'a, 'n>(
+    types: &'a [MealType],
+    name: &'n str,
+) -> Option<(&'a MealType, &'n str)> {
+    let (prefix, rest) = name.split_once(':')?;
+    let meal_type = meal_type_named(types, prefix)?;
+    Some((meal_type, rest.trim()))
+}
+
+/// ID inside an embedded object (`{"id": 3, ...}`) or a bare ID
+fn embedded_id(value: &Value) -> Option<i64> {
+    value
+        .get("id")
+        .and_then(Value::as_i64)
+        .or_else(|| value.as_i64())
+}
+
+/// Day a meal plan starts
+fn plan_date(plan: &MealPlan) -> Option<NaiveDate> {
+    plan.from_date
+        .get(..10)
+        .and_then(|date| NaiveDate::parse_from_str(date, "%Y-%m-%d").ok())
+}
+
+/// Whether a plan already says what the meal says
+///
+/// Servings and note are only compared when the file gives them.
+fn is_unchanged(plan: &MealPlan, meal: &PlannedMeal, recipe_id: i64, meal_type_id: i64) -> bool {
+    embedded_id(&plan.recipe) == Some(recipe_id)
+        && embedded_id(&plan.meal_type) == Some(meal_type_id)
+        && plan_date(plan) == Some(meal.date)
+        && meal.servings.map_or(true, |servings| {
+            (servings - plan.servings).abs() < f64::EPSILON
+        })
+        && meal.note.as_ref().map_or(true, |note| *note == plan.note)
+}
+
+/// Tandoor state the meals are resolved against
+struct Planner<'a> {
+    client: &'a TandoorClient,
+    options: &'a MealPlanImportOptions,
+    recipes: Vec<RecipeSummary>,
+    meal_types: Vec<MealType>,
+    existing: Vec<MealPlan>,
+    /// Host of the Tandoor instance, which exported events must name
+    host: String,
+    /// Existing plans already claimed by a meal
+    claimed: HashSet<i64>,
+}
+
+/// A meal's resolved recipe and meal type
+struct Resolved {
+    recipe_id: i64,
+    recipe_servings: Option<i32>,
+    meal_type_id: i64,
+}
+
+impl Planner<'_> {
+    /// Meal type of a meal, and the recipe name without a meal type prefix
+    fn meal_type<'m>(&self, meal: &'m PlannedMeal) -> Result<(&MealType, Option<&'m str>), String> {
+        let name = match &meal.recipe {
+            RecipeRef::Name(name) => Some(name.as_str()),
+            RecipeRef::Id(_) => None,
+        };
+        let prefixed = name.and_then(|name| split_meal_type(&self.meal_types, name));
+        if let Some(wanted) = &meal.meal_type {
+            let meal_type = meal_type_named(&self.meal_types, wanted)
+                .ok_or_else(|| format!("no meal type named {wanted}"))?;
+            let name = prefixed
+                .filter(|(prefix, _)| prefix.id == meal_type.id)
+                .map_or(name, |(_, rest)| Some(rest));
+            return Ok((meal_type, name));
+        }
+        if let Some((meal_type, rest)) = prefixed {
+            return Ok((meal_type, Some(rest)));
+        }
+        let meal_type = match (&self.options.default_meal_type, meal.time) {
+            (_, Some(time)) if meal_type_at(&self.meal_types, time).is_some() => {
+                meal_type_at(&self.meal_types, time)
+            }
+            (Some(default), _) => meal_type_named(&self.meal_types, default),
+            (None, _) => self.meal_types.iter().find(|meal_type| meal_type.default),
+        };
+        meal_type
+            .map(|meal_type| (meal_type, name))
+            .ok_or_else(|| "no meal type given and no default meal type".to_string())
+    }
+
+    /// Resolve a meal's recipe and meal type, filling in the report entry
+    fn resolve(&self, meal: &PlannedMeal, imported: &mut ImportedMealPlan) -> Option<Resolved> {
+        let (meal_type, name) = match self.meal_type(meal) {
+            Ok(resolved) => resolved,
+            Err(error) => {
+                imported.error = Some(error);
+                return None;
+            }
+        };
+        imported.meal_type = Some(meal_type.name.clone());
+
+        let found = match (&meal.recipe, name) {
+            (RecipeRef::Id(id), _) => self.recipes.iter().find(|recipe| recipe.id == *id),
+            (RecipeRef::Name(_), Some(name)) => {
+                match match_recipe(name, &self.recipes, self.options.min_similarity) {
+                    RecipeMatch::Found(recipe) => Some(recipe),
+                    RecipeMatch::Ambiguous(candidates) => {
+                        imported.action = MealPlanAction::Ambiguous;
+                        imported.candidates = candidates;
+                        return None;
+                    }
+                    RecipeMatch::Missing => None,
+                }
+            }
+            (RecipeRef::Name(_), None) => None,
+        };
+        let Some(recipe) = found else {
+            imported.action = MealPlanAction::Unresolved;
+            imported.error = Some(format!("no recipe matches {}", meal.recipe));
+            return None;
+        };
+        imported.recipe_id = Some(recipe.id);
+        imported.matched_recipe = Some(recipe.name.clone());
+        Some(Resolved {
+            recipe_id: recipe.id,
+            recipe_servings: recipe.servings,
+            meal_type_id: meal_type.id,
+        })
+    }
+
+    /// Existing plan a meal replaces: the one it was exported from, else an
+    /// unclaimed plan of the same recipe in its slot. Other plans in the slot
+    /// are left alone; the meal is added next to them.
+    fn claim(&mut self, meal: &PlannedMeal, resolved: &Resolved) -> Option<MealPlan> {
+        let open = |plan: &&MealPlan| !self.claimed.contains(&plan.id);
+        let plan = meal
+            .meal_plan_id
+            .and_then(|id| self.existing.iter().filter(open).find(|plan| plan.id == id))
+            .or_else(|| {
+                self.existing.iter().filter(open).find(|plan| {
+                    plan_date(plan) == Some(meal.date)
+                        && embedded_id(&plan.meal_type) == Some(resolved.meal_type_id)
+                        && embedded_id(&plan.recipe) == Some(resolved.recipe_id)
+                })
+            })?
+            .clone();
+        self.claimed.insert(plan.id);
+        Some(plan)
+    }
+
+    /// The meal, without the IDs of an event exported from another Tandoor
+    /// instance: its linked recipe is matched by the event's summary instead
+    fn localize<'m>(&self, meal: &'m PlannedMeal) -> Result<Cow<'m, PlannedMeal>, String> {
+        let Some(origin) = meal.origin.as_deref().filter(|origin| *origin != self.host) else {
+            return Ok(Cow::Borrowed(meal));
+        };
+        let recipe = match (&meal.recipe, &meal.summary) {
+            (RecipeRef::Id(_), Some(summary)) => RecipeRef::Name(summary.clone()),
+            (RecipeRef::Id(_), None) => {
+                return Err(format!(
+                    "recipe link is to another Tandoor instance ({origin})"
+                ))
+            }
+            (RecipeRef::Name(name), _) => RecipeRef::Name(name.clone()),
+        };
+        Ok(Cow::Owned(PlannedMeal {
+            recipe,
+            meal_plan_id: None,
+            origin: None,
+            ..meal.clone()
+        }))
+    }
+
+    /// Create or update the meal plan for one meal
+    fn import(&mut self, meal: &PlannedMeal) -> ImportedMealPlan {
+        let mut imported = ImportedMealPlan {
+            source: meal.source.clone(),
+            date: meal.date.to_string(),
+            meal_type: None,
+            recipe: meal.recipe.to_string(),
+            action: MealPlanAction::Failed,
+            meal_plan_id: None,
+            recipe_id: None,
+            matched_recipe: None,
+            candidates: Vec::new(),
+            error: None,
+        };
+        let meal = match self.localize(meal) {
+            Ok(meal) => meal,
+            Err(error) => {
+                imported.action = MealPlanAction::Unresolved;
+                imported.error = Some(error);
+                return imported;
+            }
+        };
+        let Some(resolved) = self.resolve(&meal, &mut imported) else {
+            return imported;
+        };
+
+        let outcome = match self.claim(&meal, &resolved) {
+            Some(plan) if is_unchanged(&plan, &meal, resolved.recipe_id, resolved.meal_type_id) => {
+                Ok((MealPlanAction::Unchanged, Some(plan.id)))
+            }
+            Some(plan) => self
+                .update(plan.id, &meal, &resolved)
+                .map(|()| (MealPlanAction::Updated, Some(plan.id))),
+            None => self
+                .create(&meal, &resolved)
+                .map(|id| (MealPlanAction::Created, id)),
+        };
+        match outcome {
+            Ok((action, meal_plan_id)) => {
+                imported.action = action;
+                imported.meal_plan_id = meal_plan_id;
+            }
+            Err(error) => imported.error = Some(error.to_string()),
+        }
+        imported
+    }
+
+    fn update(&self, id: i64, meal: &PlannedMeal, resolved: &Resolved) -> Result<(), TandoorError> {
+        if self.options.dry_run {
+            return Ok(());
+        }
+        let date = meal.date.to_string();
+        self.client.update_meal_plan(
+            id,
+            &UpdateMealPlanRequest {
+                recipe: Some(resolved.recipe_id),
+                meal_type: Some(resolved.meal_type_id),
+                from_date: Some(date.clone()),
+                to_date: Some(date),
+                servings: meal.servings,
+                title: None,
+                note: meal.note.clone(),
+            },
+        )?;
+        Ok(())
+    }
+
+    fn create(&self, meal: &PlannedMeal, resolved: &Resolved) -> Result<Option<i64>, TandoorError> {
+        if self.options.dry_run {
+            return Ok(None);
+        }
+        let servings = meal
+            .servings
+            .or_else(|| resolved.recipe_servings.map(f64::from))
+            .unwrap_or(1.0);
+        let created = self.client.create_meal_plan(&CreateMealPlanRequest {
+            recipe: resolved.recipe_id,
+            meal_type: resolved.meal_type_id,
+            from_date: meal.date.to_string(),
+            to_date: None,
+            servings,
+            title: None,
+            note: meal.note.clone(),
+        })?;
+        Ok(Some(created.id))
+    }
+}
+
+/// Create or update a meal plan for every meal in the file
+///
+/// A meal that can't be resolved or written is reported without stopping
+/// the rest. With `dry_run` nothing is written, but the report says what
+/// would be.
+pub fn import_meal_plans(
+    client: &TandoorClient,
+    file: PlanFile,
+    options: &MealPlanImportOptions,
+) -> Result<MealPlanImportReport, ImportError> {
+    let dates = file.meals.iter().map(|meal| meal.date);
+    let existing = match (dates.clone().min(), dates.max()) {
+        (Some(from), Some(to)) => {
+            client.list_all_meal_plans_by_date(&from.to_string(), &to.to_string())?
+        }
+        _ => Vec::new(),
+    };
+    let mut planner = Planner {
+        client,
+        options,
+        recipes: all_pages(|page| client.list_recipes(Some(page), Some(PAGE_SIZE)))?,
+        meal_types: all_pages(|page| client.list_meal_types(Some(page), Some(PAGE_SIZE)))?,
+        existing,
+        host: url::Url::parse(client.base_url())
+            .ok()
+            .and_then(|url| url.host_str().map(str::to_ascii_lowercase))
+            .unwrap_or_default(),
+        claimed: HashSet::new(),
+    };
+
+    let meals = file.meals.iter().map(|meal| planner.import(meal)).collect();
+    Ok(MealPlanImportReport {
+        dry_run: options.dry_run,
+        meals,
+        unreadable: file.unreadable,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn recipe(id: i64, name: &str) -> RecipeSummary {
+        serde_json::from_value(serde_json::json!({"id": id, "name": name})).unwrap()
+    }
+
+    fn meal_type(id: i64, name: &str, time: Option<&str>) -> MealType {
+        serde_json::from_value(serde_json::json!({"id": id, "name": name, "time": time})).unwrap()
+    }
+
+    #[test]
+    fn test_match_recipe() {
+        let recipes = [
+            recipe(1, "Chili con Carne"),
+            recipe(2, "Chicken Curry"),
+            recipe(3, "Chicken Curry"),
+            recipe(4, "Lentil Soup"),
+            recipe(5, "Red Lentil Soup"),
+            recipe(6, "Tomato Soup"),
+            recipe(7, "Tomato Soups"),
+        ];
+        let found = |name| match match_recipe(name, &recipes, 0.6) {
+            RecipeMatch::Found(recipe) => Some(recipe.id),
+            RecipeMatch::Ambiguous(_) | RecipeMatch::Missing => None,
+        };
+        assert_eq!(found("chili con carne"), Some(1));
+        assert_eq!(found("Chilli con Carne"), Some(1));
+        assert_eq!(found("Lentil soup"), Some(4));
+        assert!(matches!(
+            match_recipe("Pancakes", &recipes, 0.6),
+            RecipeMatch::Missing
+        ));
+
+        let RecipeMatch::Ambiguous(candidates) = match_recipe("chicken curry", &recipes, 0.6)
+        else {
+            panic!("two recipes share the name");
+        };
+        let ids: Vec<_> = candidates.iter().map(|c| c.id).collect();
+        assert_eq!(ids, [2, 3]);
+        assert!(matches!(
+            match_recipe("tomato soupe", &recipes, 0.6),
+            RecipeMatch::Ambiguous(_)
+        ));
+    }
+
+    #[test]
+    fn test_meal_type_resolution() {
+        let types = [
+            meal_type(1, "Breakfast", Some("08:00:00")),
+            meal_type(2, "Lunch", Some("12:30:00")),
+            meal_type(3, "Dinner", Some("19:00")),
+            meal_type(4, "Snack", None),
+        ];
+        assert_eq!(meal_type_named(&types, " dinner").map(|t| t.id), Some(3));
+        let at =
+            |h, m| meal_type_at(&types, NaiveTime::from_hms_opt(h, m, 0).unwrap()).map(|t| t.id);
+        assert_eq!(at(7, 0), Some(1));
+        assert_eq!(at(13, 0), Some(2));
+        assert_eq!(at(18, 30), Some(3));
+        let (meal_type, rest) = split_meal_type(&types, "Lunch: Tomato soup").unwrap();
+        assert_eq!((meal_type.id, rest), (2, "Tomato soup"));
+        assert!(split_meal_type(&types, "Soup: the best").is_none());
+    }
+
+    #[test]
+    fn test_is_unchanged() {
+        let plan: MealPlan = serde_json::from_value(serde_json::json!({
+            "id": 9, "recipe": {"id": 1, "name": "Chili"}, "servings": 4.0, "note": "",
+            "from_date": "2026-10-19T00:00:00+02:00", "to_date": "2026-10-19T00:00:00+02:00",
+            "meal_type": {"id": 3, "name": "Dinner"}, "created_by": 1,
+            "recipe_name": "Chili", "meal_type_name": "Dinner"
+        }))
+        .unwrap();
+        let mut meal = PlannedMeal {
+            source: "row 2".to_string(),
+            date: NaiveDate::from_ymd_opt(2026, 10, 19).unwrap(),
+            time: None,
+            meal_type: Some("Dinner".to_string()),
+            recipe: RecipeRef::Name("Chili".to_string()),
+            servings: None,
+            note: None,
+            meal_plan_id: None,
+            origin: None,
+            summary: None,
+        };
+        assert!(is_unchanged(&plan, &meal, 1, 3));
+        assert!(!is_unchanged(&plan, &meal, 2, 3));
+        meal.servings = Some(4.0);
+        assert!(is_unchanged(&plan, &meal, 1, 3));
+        meal.servings = Some(6.0);
+        assert!(!is_unchanged(&plan, &meal, 1, 3));
+    }
+}
@@ -0,0 +1,229 @@
+//! Food and unit resolution
+//!
+//! Parsers keep names as written ("Onions", "Tbsp"). A [`Catalog`] of the
+//! space's foods and units maps each name onto an existing object, matching
+//! its name or plural name regardless of case, so an import reuses
+//! "onion" instead of adding "Onions". Names with no match are created.
+
+use super::ImportError;
+use crate::tandoor::{
+    all_pages, CreateFoodRequestData, CreateRecipeRequest, CreateUnitRequestData, Food,
+    TandoorClient, Unit,
+};
+use serde::Serialize;
+use std::collections::HashMap;
+
+/// Page size when listing foods and units
+const PAGE_SIZE: u32 = 100;
+
+/// Existing foods and units, by normalized name
+#[derive(Debug, Clone, Default)]
+pub struct Catalog {
+    foods: HashMap<String, String>,
+    units: HashMap<String, String>,
+}
+
+/// Foods and units created (or, in a dry run, to be created)
+#[derive(Debug, Default, Serialize)]
+pub struct Resolution {
+    /// Food names created
+    pub created_foods: Vec<String>,
+    /// Unit names created
+    pub created_units: Vec<String>,
+}
+
+impl Resolution {
+    /// Add another resolution's created names
+    pub fn extend(&mut self, other: Self) {
+        self.created_foods.extend(other.created_foods);
+        self.created_units.extend(other.created_units);
+    }
+}
+
+/// Lowercase with single spaces, for matching
+pub(super) fn normalize(name: &str) -> String {
+    name.split_whitespace()
+        .collect::<Vec<_>>()
+        .join(" ")
+        .to_lowercase()
+}
+
+fn index<'a>(entries: impl Iterator<Item = (&'a str, Option<&'a str>)>) -> HashMap<String, String> {
+    let mut index = HashMap::new();
+    for (name, plural) in entries {
+        for key in std::iter::once(name).chain(plural) {
+            index
+                .entry(normalize(key))
+                .or_insert_with(|| name.to_string());
+        }
+    }
+    index
+}
+
+/// The indexed name for `name`, or the name `create` gives it
+///
+/// A created name is indexed under both spellings and added to `created`.
+fn resolve_name(
+    index: &mut HashMap<String, String>,
+    name: &str,
+    created: &mut Vec<String>,
+    create: impl FnOnce(String) -> Result<String, ImportError>,
+) -> Result<String, ImportError> {
+    if let Some(existing) = index.get(&normalize(name)) {
+        return Ok(existing.clone());
+    }
+    let new_name = create(name.trim().to_string())?;
+    index.insert(normalize(name), new_name.clone());
+    index.insert(normalize(&new_name), new_name.clone());
+    created.push(new_name.clone());
+    Ok(new_name)
+}
+
+impl Catalog {
+    /// Catalog of the given foods and units
+    pub fn new(foods: &[Food], units: &[Unit]) -> Self {
+        Self {
+            foods: index(
+                foods
+                    .iter()
+                    .map(|f| (f.name.as_str(), f.plural_name.as_deref())),
+            ),
+            units: index(
+                units
+                    .iter()
+                    .map(|u| (u.name.as_str(), u.plural_name.as_deref())),
+            ),
+        }
+    }
+
+    /// Load every food and unit of the space
+    pub fn load(client: &TandoorClient) -> Result<Self, ImportError> {
+        let foods = all_pages(|page| client.list_foods(Some(page), Some(PAGE_SIZE)))?;
+        let units = all_pages(|page| client.list_units(Some(page), Some(PAGE_SIZE)))?;
+        Ok(Self::new(&foods, &units))
+    }
+
+    /// Name of the existing food matching `name`
+    pub fn food(&self, name: &str) -> Option<&str> {
+        self.foods.get(&normalize(name)).map(String::as_str)
+    }
+
+    /// Name of the existing unit matching `name`
+    pub fn unit(&self, name: &str) -> Option<&str> {
+        self.units.get(&normalize(name)).map(String::as_str)
+    }
+
+    /// Point the request's ingredients at existing foods and units
+    ///
+    /// Missing ones are created, unless `dry_run`, in which case they are
+    /// only reported (and remembered, so they are reported once).
+    pub fn resolve(
+        &mut self,
+        client: &TandoorClient,
+        request: &mut CreateRecipeRequest,
+        dry_run: bool,
+    ) -> Result<Resolution, ImportError> {
+        let mut resolution = Resolution::default();
+        let ingredients = request
+            .steps
+            .iter_mut()
+            .flatten()
+            .flat_map(|step| step.ingredients.iter_mut().flatten());
+
+        for ingredient in ingredients {
+            ingredient.food.name = resolve_name(
+                &mut self.foods,
+                &ingredient.food.name,
+                &mut resolution.created_foods,
+                |name| {
+                    if dry_run {
+                        return Ok(name);
+                    }
+                    let request = CreateFoodRequestData {
+                        name,
+                        description: None,
+                    };
+                    Ok(client.create_food(&request)?.name)
+                },
+            )?;
+
+            if let Some(unit) = ingredient.unit.as_mut() {
+                unit.name = resolve_name(
+                    &mut self.units,
+                    &unit.name,
+                    &mut resolution.created_units,
+                    |name| {
+                        if dry_run {
+                            return Ok(name);
+                        }
+                        let request = CreateUnitRequestData {
+                            name,
+                            plural_name: None,
+                        };
+                        Ok(client.create_unit(&request)?.name)
+                    },
+                )?;
+            }
+        }
+
+        Ok(resolution)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn catalog() -> Catalog {
+        let foods: Vec<Food> = serde_json::from_value(serde_json::json!([
+            {"id": 1, "name": "onion", "plural_name": "onions"},
+            {"id": 2, "name": "Ground Beef"}
+        ]))
+        .unwrap();
+        let units: Vec<Unit> = serde_json::from_value(serde_json::json!([
+            {"id": 1, "name": "tbsp", "plural_name": "tbsp"},
+            {"id": 2, "name": "cup", "plural_name": "cups"}
+        ]))
+        .unwrap();
+        Catalog::new(&foods, &units)
+    }
+
+    #[test]
+    fn test_matches_name_and_plural_ignoring_case() {
+        let catalog = catalog();
+        assert_eq!(catalog.food("Onions"), Some("onion"));
+        assert_eq!(catalog.food("ground  beef"), Some("Ground Beef"));
+        assert_eq!(catalog.food("shallot"), None);
+        assert_eq!(catalog.unit("Cups"), Some("cup"));
+        assert_eq!(catalog.unit("TBSP"), Some("tbsp"));
+    }
+
+    #[test]
+    fn test_dry_run_resolution() {
+        let mut catalog = catalog();
+        let mut request = crate::import::recipe_from_markdown(
+            "## Ingredients\n\n- 2 Onions\n- 1 cup shallots\n- 2 Shallots\n- 1 pinch salt",
+            "test",
+        )
+        .unwrap();
+        let client = TandoorClient::new(&crate::tandoor::TandoorConfig {
+            base_url: "http://localhost:1".to_string(),
+            api_token: "unused".to_string(),
+        })
+        .unwrap();
+
+        let resolution = catalog.resolve(&client, &mut request, true).unwrap();
+        assert_eq!(resolution.created_foods, ["shallots", "salt"]);
+        assert_eq!(resolution.created_units, ["pinch"]);
+
+        let json = serde_json::to_value(&request).unwrap();
+        assert_eq!(
+            json.pointer("/steps/0/ingredients/0/food/name"),
+            Some(&serde_json::json!("onion"))
+        );
+        assert_eq!(
+            json.pointer("/steps/0/ingredients/1/unit/name"),
+            Some(&serde_json::json!("cup"))
+        );
+    }
+}
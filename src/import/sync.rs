@@ -0,0 +1,358 @@
+//! Directory sync
+//!
+//! Keeps Tandoor in step with a directory of recipe files. Each file is one
+//! recipe, keyed by its path relative to the directory; the sync state maps
+//! that path to the Tandoor recipe and a hash of the file's content, so a
+//! rerun creates new files, updates edited ones and skips the rest.
+
+use super::resolve::{Catalog, Resolution};
+use super::{FileFormat, ImportError};
+use crate::tandoor::{CreateRecipeRequest, TandoorClient, TandoorError};
+use ring::digest::{digest, SHA256};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+
+/// Default state file name, inside the synced directory
+pub const STATE_FILE: &str = ".tandoor-sync.json";
+
+/// The Tandoor recipe a file was synced to
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SyncedRecipe {
+    /// Tandoor recipe ID
+    pub recipe_id: i64,
+    /// SHA-256 of the file content when it was last synced
+    pub hash: String,
+}
+
+/// Synced files, by path relative to the directory (`/`-separated)
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SyncState {
+    pub recipes: BTreeMap<String, SyncedRecipe>,
+}
+
+impl SyncState {
+    /// Load the state from `path`; a missing file is a first sync
+    pub fn load(path: &Path) -> Result<Self, ImportError> {
+        match std::fs::read_to_string(path) {
+            Ok(json) => serde_json::from_str(&json)
+                .map_err(|e| ImportError::StateError(format!("{}: {e}", path.display()))),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(Self::default()),
+            Err(e) => Err(ImportError::StateError(format!("{}: {e}", path.display()))),
+        }
+    }
+
+    /// Save the state to `path`
+    ///
+    /// Written to a temporary file first, so an interrupted save keeps the
+    /// previous state.
+    pub fn save(&self, path: &Path) -> Result<(), ImportError> {
+        let error =
+            |e: &dyn std::fmt::Display| ImportError::StateError(format!("{}: {e}", path.display()));
+        let json = serde_json::to_string_pretty(self).map_err(|e| error(&e))?;
+        let tmp = path.with_extension("tmp");
+        std::fs::write(&tmp, json).map_err(|e| error(&e))?;
+        std::fs::rename(&tmp, path).map_err(|e| error(&e))
+    }
+}
+
+/// Sync settings
+#[derive(Debug, Clone)]
+pub struct SyncOptions {
+    /// Directory of recipe files (searched recursively)
+    pub dir: PathBuf,
+    /// Sync state file
+    pub state_path: PathBuf,
+    /// Parse and resolve, but don't write to Tandoor or save the state
+    pub dry_run: bool,
+    /// Sync files even if unchanged since the last sync
+    pub force: bool,
+}
+
+impl SyncOptions {
+    /// Sync `dir`, keeping the state in [`STATE_FILE`] inside it
+    pub fn new(dir: impl Into<PathBuf>) -> Self {
+        let dir = dir.into();
+        Self {
+            state_path: dir.join(STATE_FILE),
+            dir,
+            dry_run: false,
+            force: false,
+        }
+    }
+}
+
+/// What happened to a file
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SyncAction {
+    /// New recipe created
+    Created,
+    /// Existing recipe updated
+    Updated,
+    /// File unchanged since the last sync
+    Unchanged,
+    /// File could not be parsed or synced
+    Failed,
+}
+
+/// Outcome for one file
+#[derive(Debug, Clone, Serialize)]
+pub struct SyncedFile {
+    /// Path relative to the directory
+    pub path: String,
+    pub action: SyncAction,
+    /// Tandoor recipe ID (none for a failure, or a creation in a dry run)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub recipe_id: Option<i64>,
+    /// Recipe name, for created and updated recipes
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+/// Sync outcome
+#[derive(Debug, Default, Serialize)]
+pub struct SyncReport {
+    /// One entry per recipe file, in path order
+    pub files: Vec<SyncedFile>,
+    /// Foods and units created for the ingredients
+    #[serde(flatten)]
+    pub resolution: Resolution,
+    /// Synced paths whose file is gone (their recipes are kept)
+    pub missing: Vec<String>,
+}
+
+/// Sync every recipe file under `options.dir` to Tandoor
+///
+/// A file that fails is reported and the sync goes on; the state is saved
+/// after every file, so an interrupted sync resumes where it stopped.
+pub fn sync_directory(
+    client: &TandoorClient,
+    options: &SyncOptions,
+) -> Result<SyncReport, ImportError> {
+    let mut syncer = Syncer {
+        client,
+        options,
+        state: SyncState::load(&options.state_path)?,
+        catalog: None,
+        resolution: Resolution::default(),
+    };
+
+    let mut files = Vec::new();
+    for path in recipe_files(&options.dir)? {
+        let key = relative_key(&options.dir, &path);
+        files.push(syncer.sync_file(&path, key)?);
+    }
+
+    let missing = syncer
+        .state
+        .recipes
+        .keys()
+        .filter(|key| !files.iter().any(|file| &file.path == *key))
+        .cloned()
+        .collect();
+
+    Ok(SyncReport {
+        files,
+        resolution: syncer.resolution,
+        missing,
+    })
+}
+
+struct Syncer<'a> {
+    client: &'a TandoorClient,
+    options: &'a SyncOptions,
+    state: SyncState,
+    catalog: Option<Catalog>,
+    resolution: Resolution,
+}
+
+impl Syncer<'_> {
+    /// Sync one file; only a state save failure stops the sync
+    fn sync_file(&mut self, path: &Path, key: String) -> Result<SyncedFile, ImportError> {
+        match self.try_sync(path, &key) {
+            Ok(file) => Ok(file),
+            Err(e @ ImportError::StateError(_)) => Err(e),
+            Err(e) => Ok(SyncedFile {
+                path: key,
+                action: SyncAction::Failed,
+                recipe_id: None,
+                name: None,
+                error: Some(e.to_string()),
+            }),
+        }
+    }
+
+    fn try_sync(&mut self, path: &Path, key: &str) -> Result<SyncedFile, ImportError> {
+        let source = std::fs::read_to_string(path)?;
+        let hash = hex::encode(digest(&SHA256, source.as_bytes()));
+        let previous = self.state.recipes.get(key).map(|synced| synced.recipe_id);
+
+        if !self.options.force
+            && self
+                .state
+                .recipes
+                .get(key)
+                .is_some_and(|synced| synced.hash == hash)
+        {
+            return Ok(SyncedFile {
+                path: key.to_string(),
+                action: SyncAction::Unchanged,
+                recipe_id: previous,
+                name: None,
+                error: None,
+            });
+        }
+
+        let format = FileFormat::of(path)
+            .ok_or_else(|| ImportError::Parse(format!("{key}: not a recipe file")))?;
+        let fallback = path.file_stem().and_then(|s| s.to_str()).unwrap_or(key);
+        let mut request = format.parse(&source, fallback)?;
+
+        self.resolve(&mut request)?;
+
+        let (action, recipe_id) = if self.options.dry_run {
+            let action = previous.map_or(SyncAction::Created, |_| SyncAction::Updated);
+            (action, previous)
+        } else {
+            let (action, id) = self.upsert(previous, &request)?;
+            self.state.recipes.insert(
+                key.to_string(),
+                SyncedRecipe {
+                    recipe_id: id,
+                    hash,
+                },
+            );
+            self.state.save(&self.options.state_path)?;
+            (action, Some(id))
+        };
+
+        Ok(SyncedFile {
+            path: key.to_string(),
+            action,
+            recipe_id,
+            name: Some(request.name),
+            error: None,
+        })
+    }
+
+    /// Resolve foods and units, loading the catalog on first use
+    fn resolve(&mut self, request: &mut CreateRecipeRequest) -> Result<(), ImportError> {
+        let catalog = match self.catalog.take() {
+            Some(catalog) => catalog,
+            None => Catalog::load(self.client)?,
+        };
+        let resolution =
+            self.catalog
+                .insert(catalog)
+                .resolve(self.client, request, self.options.dry_run)?;
+        self.resolution.extend(resolution);
+        Ok(())
+    }
+
+    /// Update the synced recipe, or create one if there is none (or it was
+    /// deleted in Tandoor)
+    fn upsert(
+        &self,
+        existing: Option<i64>,
+        request: &CreateRecipeRequest,
+    ) -> Result<(SyncAction, i64), ImportError> {
+        if let Some(id) = existing {
+            let body =
+                serde_json::to_value(request).map_err(|e| ImportError::Parse(e.to_string()))?;
+            match self.client.update_recipe(id, &body) {
+                Ok(_) => return Ok((SyncAction::Updated, id)),
+                Err(TandoorError::ApiError { status: 404, .. }) => {}
+                Err(e) => return Err(e.into()),
+            }
+        }
+        let created = self.client.create_recipe(request)?;
+        Ok((SyncAction::Created, created.id))
+    }
+}
+
+/// Recipe files under `dir`, recursively, sorted by path
+///
+/// Hidden files and directories (like the state file) are skipped.
+fn recipe_files(dir: &Path) -> Result<Vec<PathBuf>, ImportError> {
+    let mut files = Vec::new();
+    let mut pending = vec![dir.to_path_buf()];
+    while let Some(current) = pending.pop() {
+        for entry in std::fs::read_dir(&current)? {
+            let entry = entry?;
+            let path = entry.path();
+            if entry.file_name().to_string_lossy().starts_with('.') {
+                continue;
+            }
+            if entry.file_type()?.is_dir() {
+                pending.push(path);
+            } else if FileFormat::of(&path).is_some() {
+                files.push(path);
+            }
+        }
+    }
+    files.sort();
+    Ok(files)
+}
+
+/// `path` relative to `dir`, with `/` separators on every platform
+fn relative_key(dir: &Path, path: &Path) -> String {
+    path.strip_prefix(dir)
+        .unwrap_or(path)
+        .components()
+        .map(|c| c.as_os_str().to_string_lossy())
+        .collect::<Vec<_>>()
+        .join("/")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_recipe_files_and_keys() {
+        let dir = std::env::temp_dir().join(format!("sync-files-{}", std::process::id()));
+        std::fs::create_dir_all(dir.join("mains")).unwrap();
+        std::fs::create_dir_all(dir.join(".git")).unwrap();
+        for file in [
+            "mains/chili.cook",
+            "bread.md",
+            "notes.txt",
+            ".git/x.md",
+            STATE_FILE,
+        ] {
+            std::fs::write(dir.join(file), "").unwrap();
+        }
+
+        let keys: Vec<String> = recipe_files(&dir)
+            .unwrap()
+            .iter()
+            .map(|path| relative_key(&dir, path))
+            .collect();
+        std::fs::remove_dir_all(&dir).unwrap();
+
+        assert_eq!(keys, ["bread.md", "mains/chili.cook"]);
+    }
+
+    #[test]
+    fn test_state_round_trip() {
+        let path = std::env::temp_dir().join(format!("sync-state-{}.json", std::process::id()));
+        assert_eq!(SyncState::load(&path).unwrap(), SyncState::default());
+
+        let mut state = SyncState::default();
+        state.recipes.insert(
+            "mains/chili.cook".to_string(),
+            SyncedRecipe {
+                recipe_id: 7,
+                hash: "abc".to_string(),
+            },
+        );
+        state.save(&path).unwrap();
+        let loaded = SyncState::load(&path).unwrap();
+        std::fs::remove_file(&path).unwrap();
+
+        assert_eq!(loaded, state);
+    }
+}
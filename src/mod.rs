@@ -6,6 +6,11 @@
 //!
 //! - `fatsecret` - `FatSecret` API client (nutrition tracking)
 //! - `tandoor` - Tandoor Recipes API client (recipe management)
+//! - `scrape` - schema.org recipe scraping from web pages
+//! - `export` - recipe export to JSON-LD, Markdown and Cooklang
+//! - `import` - recipe import from Cooklang and Markdown files
+//! - `backup` - full Tandoor backup to a versioned archive, and restore
+//! - `db` - Postgres/SQLite connections and embedded SQL migrations
 
 // =============================================================================
 // NIGHTLY FEATURES - Maximum safety with latest Rust
@@ -36,6 +41,15 @@
 pub mod fatsecret;
 pub mod tandoor;
 
+// Recipe scraping, export and import
+pub mod backup;
+pub mod export;
+pub mod import;
+pub mod scrape;
+
+// Database schema
+pub mod db;
+
 // Test utilities - only compiled for tests
 #[cfg(test)]
 pub mod test_helpers;
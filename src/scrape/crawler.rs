@@ -0,0 +1,1047 @@
+//! Site crawler for bulk recipe import
+//!
+//! [`crawl`] imports every recipe of a site into Tandoor:
+//!
+//! 1. **Discover** recipe URLs from sitemaps (following sitemap indexes),
+//!    category listing pages, or the sitemaps a site's robots.txt names
+//! 2. **Dedupe** each URL against the existing Tandoor recipes, by
+//!    `source_url` and by fuzzy name (the sitemap's image title, the listing's
+//!    link text, or the URL slug)
+//! 3. **Import** the rest with
+//!    [`TandoorClient::import_recipe_from_url`]
+//!
+//! Requests to the site honor its robots.txt and a politeness delay
+//! ([`PoliteFetcher`]); Tandoor fetches each recipe page itself, so imports
+//! wait out the delay too.
+//!
+//! Progress is saved to a JSON [`CrawlState`] file after every URL. Running
+//! again with the same file skips discovery and every URL that already has an
+//! outcome, so an interrupted crawl (or one capped by `max_imports`) resumes
+//! where it stopped.
+
+use super::robots::RobotsTxt;
+use super::{ScrapeError, USER_AGENT};
+use crate::tandoor::TandoorClient;
+use reqwest::blocking::Client;
+use scraper::{ElementRef, Html};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::path::Path;
+use std::thread;
+use std::time::{Duration, Instant};
+use url::{Position, Url};
+
+/// Default delay between requests to a site
+pub const DEFAULT_DELAY: Duration = Duration::from_secs(2);
+
+/// Default minimum name similarity (0.0-1.0) for a fuzzy duplicate
+pub const DEFAULT_MIN_NAME_SIMILARITY: f64 = 0.9;
+
+/// How many levels of sitemap indexes are followed
+const MAX_SITEMAP_DEPTH: usize = 3;
+
+/// Page size when listing existing Tandoor recipes
+const RECIPE_PAGE_SIZE: u32 = 100;
+
+/// Words that don't help tell recipe names apart
+const NAME_STOP_WORDS: &[&str] = &["recipe", "recipes", "the", "a", "an", "and", "with"];
+
+// ============================================================================
+// Fetching
+// ============================================================================
+
+/// HTTP fetcher that honors robots.txt and waits between requests to a site
+///
+/// The delay per site is the larger of the configured delay and the site's
+/// `Crawl-delay`.
+pub struct PoliteFetcher {
+    client: Client,
+    delay: Duration,
+    robots: HashMap<String, RobotsTxt>,
+    last_request: HashMap<String, Instant>,
+}
+
+impl PoliteFetcher {
+    /// Create a fetcher waiting at least `delay` between requests to a site
+    pub fn new(client: Client, delay: Duration) -> Self {
+        Self {
+            client,
+            delay,
+            robots: HashMap::new(),
+            last_request: HashMap::new(),
+        }
+    }
+
+    /// robots.txt of the URL's site, fetched on first use
+    ///
+    /// A missing robots.txt (4xx) allows everything; an unreachable one
+    /// (5xx, network error) disallows everything.
+    pub fn robots(&mut self, url: &Url) -> &RobotsTxt {
+        let origin = url.origin().ascii_serialization();
+        let client = &self.client;
+        self.robots
+            .entry(origin)
+            .or_insert_with_key(|origin| fetch_robots(client, origin))
+    }
+
+    /// Whether robots.txt allows fetching `url`
+    pub fn is_allowed(&mut self, url: &Url) -> bool {
+        let path = &url[Position::BeforePath..Position::AfterQuery];
+        self.robots(url).is_allowed(path)
+    }
+
+    /// Sleep until the next request to the URL's site is allowed
+    pub fn wait(&mut self, url: &Url) {
+        let delay = self
+            .robots(url)
+            .crawl_delay()
+            .map_or(self.delay, |crawl_delay| crawl_delay.max(self.delay));
+        let origin = url.origin().ascii_serialization();
+
+        if let Some(remaining) = self
+            .last_request
+            .get(&origin)
+            .and_then(|last| delay.checked_sub(last.elapsed()))
+        {
+            thread::sleep(remaining);
+        }
+        self.last_request.insert(origin, Instant::now());
+    }
+
+    /// Fetch a page's text
+    pub fn get_text(&mut self, url: &str) -> Result<String, ScrapeError> {
+        let parsed = parse_url(url)?;
+        if !self.is_allowed(&parsed) {
+            return Err(ScrapeError::Disallowed(url.to_string()));
+        }
+        self.wait(&parsed);
+
+        let response = self.client.get(parsed).send()?;
+        let status = response.status();
+        if !status.is_success() {
+            return Err(ScrapeError::StatusError {
+                status: status.as_u16(),
+                url: url.to_string(),
+            });
+        }
+        Ok(response.text()?)
+    }
+}
+
+fn fetch_robots(client: &Client, origin: &str) -> RobotsTxt {
+    match client.get(format!("{origin}/robots.txt")).send() {
+        Ok(response) if response.status().is_success() => response.text().map_or_else(
+            |_| RobotsTxt::disallow_all(),
+            |text| RobotsTxt::parse(&text, USER_AGENT),
+        ),
+        Ok(response) if response.status().is_client_error() => RobotsTxt::allow_all(),
+        Ok(_) | Err(_) => RobotsTxt::disallow_all(),
+    }
+}
+
+fn parse_url(url: &str) -> Result<Url, ScrapeError> {
+    Url::parse(url).map_err(|e| ScrapeError::InvalidUrl(format!("{url}: {e}")))
+}
+
+// ============================================================================
+// Discovery
+// ============================================================================
+
+/// Where to look for recipe URLs
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Source {
+    /// A sitemap or sitemap index
+    Sitemap(String),
+    /// A category or archive page linking to recipes
+    Listing(String),
+    /// A site's root URL: the sitemaps named in its robots.txt, or
+    /// `/sitemap.xml`
+    Site(String),
+}
+
+/// A discovered recipe URL
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Candidate {
+    /// Page URL
+    pub url: String,
+    /// Title from the sitemap or listing link, if any
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub title: Option<String>,
+}
+
+impl Candidate {
+    /// Best guess at the recipe's name: its title, or the URL slug
+    pub fn name(&self) -> String {
+        self.title
+            .clone()
+            .unwrap_or_else(|| name_from_url(&self.url))
+    }
+}
+
+/// Contents of a sitemap
+#[derive(Debug, Default, PartialEq, Eq)]
+pub struct Sitemap {
+    /// Page URLs (`<url>` entries)
+    pub pages: Vec<Candidate>,
+    /// Nested sitemaps (`<sitemap>` entries of a sitemap index)
+    pub sitemaps: Vec<String>,
+}
+
+/// Parse a sitemap or sitemap index
+///
+/// A page's `<image:title>` is kept as its title.
+pub fn parse_sitemap(xml: &str) -> Sitemap {
+    let document = Html::parse_document(xml);
+    let mut sitemap = Sitemap::default();
+
+    for element in document
+        .root_element()
+        .descendants()
+        .filter_map(ElementRef::wrap)
+    {
+        match element.value().name() {
+            "url" => {
+                if let Some(url) = child_text(element, "loc") {
+                    sitemap.pages.push(Candidate {
+                        url,
+                        title: child_text(element, "image:title"),
+                    });
+                }
+            }
+            "sitemap" => sitemap.sitemaps.extend(child_text(element, "loc")),
+            _ => {}
+        }
+    }
+
+    sitemap
+}
+
+/// Text of the first descendant element called `name`
+fn child_text(element: ElementRef<'_>, name: &str) -> Option<String> {
+    element
+        .descendants()
+        .filter_map(ElementRef::wrap)
+        .find(|child| child.value().name() == name)
+        .map(|child| collapse_whitespace(&child.text().collect::<String>()))
+        .filter(|text| !text.is_empty())
+}
+
+/// Links on a listing page that look like recipes
+///
+/// Only links to the same host are kept. With `pattern`, a link must contain
+/// it (e.g. `/blogs/recipes/`); without, it must be below the listing's own
+/// path. When several links point at one URL, the longest link text is its
+/// title.
+pub fn listing_links(html: &str, page_url: &str, pattern: Option<&str>) -> Vec<Candidate> {
+    let Ok(base) = Url::parse(page_url) else {
+        return Vec::new();
+    };
+    let below = format!("{}/", base.path().trim_end_matches('/'));
+    let page_key = url_key(page_url);
+
+    let document = Html::parse_document(html);
+    let mut candidates: Vec<Candidate> = Vec::new();
+    let mut index: HashMap<String, usize> = HashMap::new();
+
+    for link in document
+        .root_element()
+        .descendants()
+        .filter_map(ElementRef::wrap)
+    {
+        if link.value().name() != "a" {
+            continue;
+        }
+        let Some(mut url) = link
+            .value()
+            .attr("href")
+            .and_then(|href| base.join(href).ok())
+        else {
+            continue;
+        };
+        url.set_fragment(None);
+
+        let wanted = url.host_str() == base.host_str()
+            && pattern.map_or_else(
+                || url.path().starts_with(&below) && url.path().len() > below.len(),
+                |pattern| url.as_str().contains(pattern),
+            );
+        let key = url_key(url.as_str());
+        if !wanted || key == page_key {
+            continue;
+        }
+
+        let text = collapse_whitespace(&link.text().collect::<String>());
+        let title = Some(text).filter(|text| !text.is_empty());
+        if let Some(existing) = index.get(&key).and_then(|i| candidates.get_mut(*i)) {
+            if title.as_ref().map_or(0, String::len)
+                > existing.title.as_ref().map_or(0, String::len)
+            {
+                existing.title = title;
+            }
+        } else {
+            index.insert(key, candidates.len());
+            candidates.push(Candidate {
+                url: url.into(),
+                title,
+            });
+        }
+    }
+
+    candidates
+}
+
+/// A sitemap listed in an index that couldn't be read
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct FailedSitemap {
+    pub url: String,
+    pub error: String,
+}
+
+/// What [`discover`] found
+#[derive(Debug, Default)]
+pub struct Discovery {
+    /// Recipe URLs, deduplicated and in order
+    pub candidates: Vec<Candidate>,
+    /// Nested sitemaps skipped because they couldn't be read
+    pub failed_sitemaps: Vec<FailedSitemap>,
+}
+
+/// Discover recipe URLs from `sources`, deduplicated and in order
+///
+/// `pattern` filters sitemap pages the same way as [`listing_links`]. A
+/// source that can't be read is an error; a sitemap listed in an index that
+/// can't be read (missing, or disallowed by robots.txt) is recorded in
+/// [`Discovery::failed_sitemaps`] and the rest are crawled.
+pub fn discover(
+    fetcher: &mut PoliteFetcher,
+    sources: &[Source],
+    pattern: Option<&str>,
+) -> Result<Discovery, ScrapeError> {
+    let mut discovery = Discovery::default();
+
+    for source in sources {
+        match source {
+            Source::Sitemap(url) => crawl_sitemap(fetcher, url, pattern, &mut discovery)?,
+            Source::Listing(url) => {
+                let html = fetcher.get_text(url)?;
+                discovery
+                    .candidates
+                    .extend(listing_links(&html, url, pattern));
+            }
+            Source::Site(url) => {
+                let site = parse_url(url)?;
+                let mut sitemaps = fetcher.robots(&site).sitemaps().to_vec();
+                if sitemaps.is_empty() {
+                    sitemaps.push(
+                        site.join("/sitemap.xml")
+                            .map_err(|e| ScrapeError::InvalidUrl(format!("{url}: {e}")))?
+                            .into(),
+                    );
+                }
+                for sitemap in &sitemaps {
+                    crawl_sitemap(fetcher, sitemap, pattern, &mut discovery)?;
+                }
+            }
+        }
+    }
+
+    let mut seen = HashSet::new();
+    discovery
+        .candidates
+        .retain(|candidate| seen.insert(url_key(&candidate.url)));
+    Ok(discovery)
+}
+
+/// Collect the pages of a sitemap, following sitemap indexes
+fn crawl_sitemap(
+    fetcher: &mut PoliteFetcher,
+    url: &str,
+    pattern: Option<&str>,
+    discovery: &mut Discovery,
+) -> Result<(), ScrapeError> {
+    let mut queue = vec![(url.to_string(), 0)];
+    let mut visited = HashSet::new();
+
+    while let Some((url, depth)) = queue.pop() {
+        if depth > MAX_SITEMAP_DEPTH || !visited.insert(url.clone()) {
+            continue;
+        }
+        let text = match fetcher.get_text(&url) {
+            Ok(text) => text,
+            Err(e) if depth == 0 => return Err(e),
+            Err(e) => {
+                discovery.failed_sitemaps.push(FailedSitemap {
+                    url,
+                    error: e.to_string(),
+                });
+                continue;
+            }
+        };
+        let sitemap = parse_sitemap(&text);
+
+        discovery.candidates.extend(
+            sitemap
+                .pages
+                .into_iter()
+                .filter(|page| pattern.map_or(true, |pattern| page.url.contains(pattern))),
+        );
+        // Reversed so nested sitemaps are crawled in document order
+        queue.extend(sitemap.sitemaps.into_iter().rev().map(|s| (s, depth + 1)));
+    }
+
+    Ok(())
+}
+
+// ============================================================================
+// Deduplication
+// ============================================================================
+
+/// Comparable form of a URL: host without `www.`, path without trailing
+/// slash, and query; scheme and fragment are ignored
+pub fn url_key(url: &str) -> String {
+    Url::parse(url).map_or_else(
+        |_| url.trim().trim_end_matches('/').to_lowercase(),
+        |url| {
+            let host = url.host_str().unwrap_or_default();
+            let host = host.strip_prefix("www.").unwrap_or(host);
+            let path = url.path().trim_end_matches('/');
+            let query = url
+                .query()
+                .map_or_else(String::new, |query| format!("?{query}"));
+            format!("{host}{path}{query}")
+        },
+    )
+}
+
+/// Recipe name guessed from a URL's last path segment
+/// (`/recipes/texas-style-brisket.html` → "texas style brisket")
+pub fn name_from_url(url: &str) -> String {
+    let path = Url::parse(url).map_or_else(|_| url.to_string(), |url| url.path().to_string());
+    let slug = path
+        .split('/')
+        .rfind(|segment| !segment.is_empty())
+        .unwrap_or_default();
+    let slug = slug.rsplit_once('.').map_or(slug, |(stem, _)| stem);
+    collapse_whitespace(&slug.replace(['-', '_', '+'], " "))
+}
+
+/// Similarity of two recipe names from 0.0 to 1.0
+///
+/// Names are compared case-insensitively, without punctuation and common
+/// filler words ("recipe", "the", "with", …), as the Sørensen–Dice
+/// coefficient of their character bigrams.
+pub fn name_similarity(a: &str, b: &str) -> f64 {
+    let (a, b) = (normalize_name(a), normalize_name(b));
+    if a.is_empty() || b.is_empty() {
+        return 0.0;
+    }
+    if a == b {
+        return 1.0;
+    }
+
+    let (a, b) = (bigrams(&a), bigrams(&b));
+    let total = a.len() + b.len();
+    let mut remaining = b;
+    let shared = a
+        .iter()
+        .filter(|bigram| {
+            remaining
+                .iter()
+                .position(|other| other == *bigram)
+                .map(|i| remaining.swap_remove(i))
+                .is_some()
+        })
+        .count();
+
+    // Safe: bigram counts are far below f64's exact integer range
+    #[allow(clippy::cast_precision_loss)]
+    let similarity = (2 * shared) as f64 / total.max(1) as f64;
+    similarity
+}
+
+fn normalize_name(name: &str) -> String {
+    name.to_lowercase()
+        .split(|c: char| !c.is_alphanumeric())
+        .filter(|word| !word.is_empty() && !NAME_STOP_WORDS.contains(word))
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+fn bigrams(s: &str) -> Vec<(char, char)> {
+    s.chars().zip(s.chars().skip(1)).collect()
+}
+
+fn collapse_whitespace(s: &str) -> String {
+    s.split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+/// Why a URL counts as already imported
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "by", rename_all = "snake_case")]
+pub enum DuplicateMatch {
+    /// A recipe has the same source URL
+    SourceUrl,
+    /// A recipe has a similar name
+    Name {
+        /// Name of the existing recipe
+        name: String,
+        /// Name similarity (0.0-1.0)
+        similarity: f64,
+    },
+}
+
+/// Names and source URLs of the recipes already in Tandoor
+#[derive(Debug, Default)]
+pub struct ExistingRecipes {
+    by_url: HashMap<String, i64>,
+    names: Vec<(i64, String)>,
+}
+
+impl ExistingRecipes {
+    /// Load every recipe from Tandoor
+    ///
+    /// The recipe list has no source URLs, so each recipe is fetched once;
+    /// `source_cache` (kept in the crawl state) remembers the answers by
+    /// recipe ID across runs.
+    pub fn load(
+        client: &TandoorClient,
+        source_cache: &mut BTreeMap<i64, Option<String>>,
+    ) -> Result<Self, ScrapeError> {
+        let mut existing = Self::default();
+        let mut listed = HashSet::new();
+
+        for page in 1.. {
+            let response = client.list_recipes(Some(page), Some(RECIPE_PAGE_SIZE))?;
+            for recipe in &response.results {
+                let source_url = if let Some(cached) = source_cache.get(&recipe.id) {
+                    cached.clone()
+                } else {
+                    let detail = client.get_recipe(recipe.id)?;
+                    let source_url = detail
+                        .get("source_url")
+                        .and_then(serde_json::Value::as_str)
+                        .filter(|url| !url.is_empty())
+                        .map(str::to_string);
+                    source_cache.insert(recipe.id, source_url.clone());
+                    source_url
+                };
+                existing.add(recipe.id, &recipe.name, source_url.as_deref());
+                listed.insert(recipe.id);
+            }
+            if response.next.is_none() || response.results.is_empty() {
+                break;
+            }
+        }
+
+        // Forget recipes that were deleted
+        source_cache.retain(|id, _| listed.contains(id));
+        Ok(existing)
+    }
+
+    /// Record a recipe
+    pub fn add(&mut self, id: i64, name: &str, source_url: Option<&str>) {
+        if let Some(url) = source_url {
+            self.by_url.insert(url_key(url), id);
+        }
+        self.names.push((id, name.to_string()));
+    }
+
+    /// The existing recipe `candidate` duplicates, if any
+    pub fn find(
+        &self,
+        candidate: &Candidate,
+        min_similarity: f64,
+    ) -> Option<(i64, DuplicateMatch)> {
+        if let Some(id) = self.by_url.get(&url_key(&candidate.url)) {
+            return Some((*id, DuplicateMatch::SourceUrl));
+        }
+
+        let name = candidate.name();
+        self.names
+            .iter()
+            .map(|(id, existing)| (*id, existing, name_similarity(&name, existing)))
+            .filter(|(_, _, similarity)| *similarity >= min_similarity)
+            .max_by(|a, b| a.2.total_cmp(&b.2))
+            .map(|(id, existing, similarity)| {
+                (
+                    id,
+                    DuplicateMatch::Name {
+                        name: existing.clone(),
+                        similarity,
+                    },
+                )
+            })
+    }
+}
+
+// ============================================================================
+// State and crawling
+// ============================================================================
+
+/// What happened to a discovered URL
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "status", rename_all = "snake_case")]
+pub enum UrlOutcome {
+    /// Imported into Tandoor
+    Imported {
+        /// New recipe ID
+        recipe_id: Option<i64>,
+        /// New recipe name
+        name: Option<String>,
+    },
+    /// Already in Tandoor
+    Duplicate {
+        /// Existing recipe ID
+        recipe_id: i64,
+        /// How the duplicate was found
+        matched: DuplicateMatch,
+    },
+    /// robots.txt disallows the page
+    Disallowed,
+    /// Import failed
+    Failed {
+        /// Error message
+        error: String,
+    },
+    /// Would be imported (dry run; never saved to the state)
+    WouldImport {
+        /// Guessed recipe name
+        name: String,
+    },
+}
+
+/// Crawl progress, saved between runs
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct CrawlState {
+    /// Discovered recipe URLs, in crawl order
+    #[serde(default)]
+    pub discovered: Vec<Candidate>,
+    /// Outcome of each processed URL
+    #[serde(default)]
+    pub outcomes: BTreeMap<String, UrlOutcome>,
+    /// Source URLs of existing Tandoor recipes, by recipe ID
+    #[serde(default)]
+    pub recipe_sources: BTreeMap<i64, Option<String>>,
+}
+
+impl CrawlState {
+    /// Load the state from `path`; a missing file is a fresh crawl
+    pub fn load(path: &Path) -> Result<Self, ScrapeError> {
+        match std::fs::read_to_string(path) {
+            Ok(json) => serde_json::from_str(&json)
+                .map_err(|e| ScrapeError::StateError(format!("{}: {e}", path.display()))),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(Self::default()),
+            Err(e) => Err(ScrapeError::StateError(format!("{}: {e}", path.display()))),
+        }
+    }
+
+    /// Save the state to `path`
+    ///
+    /// Written to a temporary file first, so an interrupted save keeps the
+    /// previous state.
+    pub fn save(&self, path: &Path) -> Result<(), ScrapeError> {
+        let error =
+            |e: &dyn std::fmt::Display| ScrapeError::StateError(format!("{}: {e}", path.display()));
+        let json = serde_json::to_string_pretty(self).map_err(|e| error(&e))?;
+        let tmp = path.with_extension("tmp");
+        std::fs::write(&tmp, json).map_err(|e| error(&e))?;
+        std::fs::rename(&tmp, path).map_err(|e| error(&e))
+    }
+
+    /// Whether `url` needs no further work
+    pub fn is_done(&self, url: &str, retry_failed: bool) -> bool {
+        match self.outcomes.get(url) {
+            Some(UrlOutcome::Failed { .. }) => !retry_failed,
+            Some(_) => true,
+            None => false,
+        }
+    }
+}
+
+/// Crawl settings
+#[derive(Debug, Clone)]
+pub struct CrawlOptions {
+    /// Where to discover recipe URLs
+    pub sources: Vec<Source>,
+    /// Substring recipe URLs must contain
+    pub url_pattern: Option<String>,
+    /// Minimum name similarity for a fuzzy duplicate
+    pub min_name_similarity: f64,
+    /// Stop after this many imports (the rest stays pending)
+    pub max_imports: Option<usize>,
+    /// Discover and dedupe, but don't import or record outcomes
+    pub dry_run: bool,
+    /// Try previously failed URLs again
+    pub retry_failed: bool,
+    /// Discover URLs again even if the state has them
+    pub rediscover: bool,
+    /// Keywords added to every imported recipe
+    pub keywords: Vec<String>,
+}
+
+impl Default for CrawlOptions {
+    fn default() -> Self {
+        Self {
+            sources: Vec::new(),
+            url_pattern: None,
+            min_name_similarity: DEFAULT_MIN_NAME_SIMILARITY,
+            max_imports: None,
+            dry_run: false,
+            retry_failed: false,
+            rediscover: false,
+            keywords: Vec::new(),
+        }
+    }
+}
+
+/// Outcome of one URL in this run
+#[derive(Debug, Clone, Serialize)]
+pub struct UrlReport {
+    /// Page URL
+    pub url: String,
+    /// What happened
+    #[serde(flatten)]
+    pub outcome: UrlOutcome,
+}
+
+/// Summary of a crawl run
+#[derive(Debug, Default, Serialize)]
+pub struct CrawlReport {
+    /// URLs discovered in total
+    pub discovered: usize,
+    /// Imported in this run (or would be, in a dry run)
+    pub imported: usize,
+    /// Found to be duplicates in this run
+    pub duplicates: usize,
+    /// Disallowed by robots.txt in this run
+    pub disallowed: usize,
+    /// Failed in this run
+    pub failed: usize,
+    /// Skipped because an earlier run handled them
+    pub already_done: usize,
+    /// Left for the next run by `max_imports`
+    pub remaining: usize,
+    /// Nested sitemaps that couldn't be read when discovering in this run
+    pub failed_sitemaps: Vec<FailedSitemap>,
+    /// Per-URL outcomes of this run
+    pub results: Vec<UrlReport>,
+}
+
+impl CrawlReport {
+    fn record(&mut self, url: &str, outcome: UrlOutcome) {
+        match outcome {
+            UrlOutcome::Imported { .. } | UrlOutcome::WouldImport { .. } => self.imported += 1,
+            UrlOutcome::Duplicate { .. } => self.duplicates += 1,
+            UrlOutcome::Disallowed => self.disallowed += 1,
+            UrlOutcome::Failed { .. } => self.failed += 1,
+        }
+        self.results.push(UrlReport {
+            url: url.to_string(),
+            outcome,
+        });
+    }
+}
+
+/// Discover, dedupe and import, saving progress to `state_path`
+///
+/// Failed imports and unreadable nested sitemaps are recorded and the crawl
+/// goes on; only source, Tandoor listing and state file errors abort it.
+pub fn crawl(
+    fetcher: &mut PoliteFetcher,
+    tandoor: &TandoorClient,
+    options: &CrawlOptions,
+    state_path: &Path,
+) -> Result<CrawlReport, ScrapeError> {
+    let mut state = CrawlState::load(state_path)?;
+    // A dry run leaves the state file as it was
+    let save = |state: &CrawlState| {
+        if options.dry_run {
+            Ok(())
+        } else {
+            state.save(state_path)
+        }
+    };
+
+    let mut failed_sitemaps = Vec::new();
+    if state.discovered.is_empty() || options.rediscover {
+        let discovery = discover(fetcher, &options.sources, options.url_pattern.as_deref())?;
+        state.discovered = discovery.candidates;
+        failed_sitemaps = discovery.failed_sitemaps;
+        save(&state)?;
+    }
+    let mut existing = ExistingRecipes::load(tandoor, &mut state.recipe_sources)?;
+    save(&state)?;
+
+    let mut report = CrawlReport {
+        discovered: state.discovered.len(),
+        failed_sitemaps,
+        ..CrawlReport::default()
+    };
+
+    for candidate in state.discovered.clone() {
+        if state.is_done(&candidate.url, options.retry_failed) {
+            report.already_done += 1;
+            continue;
+        }
+        if options
+            .max_imports
+            .is_some_and(|max| report.imported >= max)
+        {
+            report.remaining += 1;
+            continue;
+        }
+
+        let outcome = process(fetcher, tandoor, options, &mut existing, &candidate);
+        if !options.dry_run {
+            state
+                .outcomes
+                .insert(candidate.url.clone(), outcome.clone());
+            save(&state)?;
+        }
+        report.record(&candidate.url, outcome);
+    }
+
+    Ok(report)
+}
+
+/// Dedupe and import one URL
+fn process(
+    fetcher: &mut PoliteFetcher,
+    tandoor: &TandoorClient,
+    options: &CrawlOptions,
+    existing: &mut ExistingRecipes,
+    candidate: &Candidate,
+) -> UrlOutcome {
+    let url = match parse_url(&candidate.url) {
+        Ok(url) => url,
+        Err(e) => {
+            return UrlOutcome::Failed {
+                error: e.to_string(),
+            }
+        }
+    };
+
+    if let Some((recipe_id, matched)) = existing.find(candidate, options.min_name_similarity) {
+        return UrlOutcome::Duplicate { recipe_id, matched };
+    }
+    if !fetcher.is_allowed(&url) {
+        return UrlOutcome::Disallowed;
+    }
+    if options.dry_run {
+        return UrlOutcome::WouldImport {
+            name: candidate.name(),
+        };
+    }
+
+    // Tandoor fetches the page, so it counts as a request to the site
+    fetcher.wait(&url);
+    let keywords = Some(options.keywords.clone()).filter(|k| !k.is_empty());
+    match tandoor.import_recipe_from_url(&candidate.url, keywords) {
+        Ok(result) if result.success => {
+            if let Some(id) = result.recipe_id {
+                let name = result
+                    .recipe_name
+                    .clone()
+                    .unwrap_or_else(|| candidate.name());
+                existing.add(id, &name, Some(&candidate.url));
+            }
+            UrlOutcome::Imported {
+                recipe_id: result.recipe_id,
+                name: result.recipe_name,
+            }
+        }
+        Ok(result) => UrlOutcome::Failed {
+            error: result.message,
+        },
+        Err(e) => UrlOutcome::Failed {
+            error: e.to_string(),
+        },
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SITEMAP_INDEX: &str = r#"<?xml version="1.0" encoding="UTF-8"?>
+<sitemapindex xmlns="http://www.sitemaps.org/schemas/sitemap/0.9">
+  <sitemap><loc>https://example.com/sitemap_pages_1.xml</loc></sitemap>
+  <sitemap><loc>https://example.com/sitemap_blogs_1.xml?from=1&amp;to=99</loc></sitemap>
+</sitemapindex>"#;
+
+    const SITEMAP: &str = r#"<?xml version="1.0" encoding="UTF-8"?>
+<urlset xmlns="http://www.sitemaps.org/schemas/sitemap/0.9" xmlns:image="http://www.google.com/schemas/sitemap-image/1.1">
+  <url>
+    <loc>https://example.com/blogs/recipes/texas-style-brisket</loc>
+    <lastmod>2024-01-02T10:00:00Z</lastmod>
+    <image:image>
+      <image:loc>https://cdn.example.com/brisket.jpg</image:loc>
+      <image:title>Texas Style Brisket</image:title>
+    </image:image>
+  </url>
+  <url><loc>https://example.com/blogs/recipes/smoked-queso</loc></url>
+</urlset>"#;
+
+    #[test]
+    fn test_parse_sitemap_index() {
+        let sitemap = parse_sitemap(SITEMAP_INDEX);
+        assert!(sitemap.pages.is_empty());
+        assert_eq!(
+            sitemap.sitemaps,
+            [
+                "https://example.com/sitemap_pages_1.xml",
+                "https://example.com/sitemap_blogs_1.xml?from=1&to=99"
+            ]
+        );
+    }
+
+    #[test]
+    fn test_parse_sitemap_pages_with_image_titles() {
+        let sitemap = parse_sitemap(SITEMAP);
+        assert_eq!(
+            sitemap.pages,
+            [
+                Candidate {
+                    url: "https://example.com/blogs/recipes/texas-style-brisket".to_string(),
+                    title: Some("Texas Style Brisket".to_string()),
+                },
+                Candidate {
+                    url: "https://example.com/blogs/recipes/smoked-queso".to_string(),
+                    title: None,
+                },
+            ]
+        );
+        assert!(sitemap.sitemaps.is_empty());
+    }
+
+    #[test]
+    fn test_listing_links() {
+        let html = r##"
+            <nav><a href="/">Home</a><a href="/blogs/recipes">Recipes</a></nav>
+            <a href="/blogs/recipes/brisket-flat"><img src="flat.jpg"></a>
+            <a href="/blogs/recipes/brisket-flat#comments">Brisket Flat</a>
+            <a href="https://www.example.com/blogs/recipes/cottage-pie">Cottage Pie</a>
+            <a href="https://other.example.org/blogs/recipes/ribs">Ribs</a>
+            <a href="/blogs/recipes?page=2">Next</a>
+        "##;
+        let links = listing_links(html, "https://www.example.com/blogs/recipes", None);
+        assert_eq!(
+            links,
+            [
+                Candidate {
+                    url: "https://www.example.com/blogs/recipes/brisket-flat".to_string(),
+                    title: Some("Brisket Flat".to_string()),
+                },
+                Candidate {
+                    url: "https://www.example.com/blogs/recipes/cottage-pie".to_string(),
+                    title: Some("Cottage Pie".to_string()),
+                },
+            ]
+        );
+
+        let links = listing_links(html, "https://www.example.com/blogs/recipes", Some("pie"));
+        assert_eq!(links.len(), 1);
+    }
+
+    #[test]
+    fn test_url_key() {
+        assert_eq!(
+            url_key("https://www.example.com/recipes/ribs/"),
+            url_key("http://example.com/recipes/ribs")
+        );
+        assert_eq!(
+            url_key("https://example.com/recipes/ribs#step-2"),
+            "example.com/recipes/ribs"
+        );
+        assert_ne!(
+            url_key("https://example.com/recipe?id=1"),
+            url_key("https://example.com/recipe?id=2")
+        );
+    }
+
+    #[test]
+    fn test_name_from_url() {
+        assert_eq!(
+            name_from_url("https://example.com/blogs/recipes/texas-style-brisket/"),
+            "texas style brisket"
+        );
+        assert_eq!(
+            name_from_url("https://example.com/recipes/smoked_queso.html"),
+            "smoked queso"
+        );
+    }
+
+    #[test]
+    fn test_name_similarity() {
+        assert!((name_similarity("Texas Style Brisket", "texas-style brisket") - 1.0).abs() < 1e-9);
+        assert!((name_similarity("Smoked Queso Recipe", "Smoked Queso") - 1.0).abs() < 1e-9);
+        assert!(name_similarity("Texas Style Brisket", "Texas-Style Briskett") > 0.9);
+        assert!(name_similarity("Brisket Flat", "Brisket Cheesesteak") < 0.6);
+        assert!(name_similarity("", "Ribs") < f64::EPSILON);
+    }
+
+    #[test]
+    fn test_existing_recipes_find() {
+        let mut existing = ExistingRecipes::default();
+        existing.add(1, "Texas Style Brisket", None);
+        existing.add(
+            2,
+            "Cottage Pie",
+            Some("https://example.com/blogs/recipes/cottage-pie"),
+        );
+
+        let by_url = Candidate {
+            url: "https://www.example.com/blogs/recipes/cottage-pie/".to_string(),
+            title: None,
+        };
+        assert_eq!(
+            existing.find(&by_url, DEFAULT_MIN_NAME_SIMILARITY),
+            Some((2, DuplicateMatch::SourceUrl))
+        );
+
+        let by_name = Candidate {
+            url: "https://example.com/blogs/recipes/texas-style-brisket-recipe".to_string(),
+            title: None,
+        };
+        assert!(matches!(
+            existing.find(&by_name, DEFAULT_MIN_NAME_SIMILARITY),
+            Some((1, DuplicateMatch::Name { .. }))
+        ));
+
+        let new = Candidate {
+            url: "https://example.com/blogs/recipes/brisket-flat".to_string(),
+            title: Some("Brisket Flat".to_string()),
+        };
+        assert_eq!(existing.find(&new, DEFAULT_MIN_NAME_SIMILARITY), None);
+    }
+
+    #[test]
+    fn test_state_round_trip() {
+        let path = std::env::temp_dir().join(format!("crawl-state-{}.json", std::process::id()));
+        assert!(CrawlState::load(&path).unwrap().discovered.is_empty());
+
+        let mut state = CrawlState::default();
+        state.discovered.push(Candidate {
+            url: "https://example.com/a".to_string(),
+            title: None,
+        });
+        state.outcomes.insert(
+            "https://example.com/a".to_string(),
+            UrlOutcome::Failed {
+                error: "timeout".to_string(),
+            },
+        );
+        state.recipe_sources.insert(7, None);
+        state.save(&path).unwrap();
+
+        let loaded = CrawlState::load(&path).unwrap();
+        std::fs::remove_file(&path).unwrap();
+        assert_eq!(loaded.discovered, state.discovered);
+        assert_eq!(loaded.outcomes, state.outcomes);
+        assert_eq!(loaded.recipe_sources, state.recipe_sources);
+        assert!(loaded.is_done("https://example.com/a", false));
+        assert!(!loaded.is_done("https://example.com/a", true));
+        assert!(!loaded.is_done("https://example.com/b", false));
+    }
+}
@@ -0,0 +1,104 @@
+//! Recipe scraping without Tandoor
+//!
+//! [`TandoorClient::scrape_recipe_from_url`](crate::tandoor::TandoorClient::scrape_recipe_from_url)
+//! asks Tandoor's `/api/recipe-from-source/` to do the scraping. This module
+//! does the same locally: it fetches a page and extracts the schema.org
+//! `Recipe` it describes, as JSON-LD or microdata, into a
+//! [`SourceImportRecipe`] that `tandoor_create_recipe` accepts.
+//!
+//! Parsing is separate from fetching, so saved pages can be parsed without
+//! network access:
+//!
+//! ```rust,no_run
+//! use meal_planner::scrape::{fetch_recipe, parse_recipe_html};
+//!
+//! # fn main() -> Result<(), Box<dyn std::error::Error>> {
+//! let recipe = fetch_recipe("https://example.com/brisket")?;
+//! println!("{}: {} steps", recipe.name, recipe.steps.len());
+//!
+//! let html = std::fs::read_to_string("brisket.html")?;
+//! let recipe = parse_recipe_html(&html, Some("https://example.com/brisket"))?;
+//! # Ok(())
+//! # }
+//! ```
+//!
+//! See [`schema_org`] for how schema.org properties map onto the import types.
+//! [`crawler`] imports whole sites into Tandoor, honoring [`robots`] rules.
+
+pub mod crawler;
+pub mod robots;
+pub mod schema_org;
+
+pub use crawler::{crawl, CrawlOptions, CrawlReport, PoliteFetcher, Source};
+pub use robots::RobotsTxt;
+pub use schema_org::{find_recipe_json, parse_iso_duration, parse_recipe_html, recipe_from_schema};
+
+use crate::tandoor::{SourceImportRecipe, TandoorError};
+use reqwest::blocking::Client;
+use std::time::Duration;
+use thiserror::Error;
+
+/// User agent sent with scraping requests
+pub const USER_AGENT: &str = concat!("meal-planner/", env!("CARGO_PKG_VERSION"));
+
+/// Request timeout for fetching a page
+const FETCH_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// Scraping errors
+#[derive(Debug, Error)]
+pub enum ScrapeError {
+    #[error("HTTP request failed: {0}")]
+    HttpError(#[from] reqwest::Error),
+
+    #[error("HTTP {status} fetching {url}")]
+    StatusError { status: u16, url: String },
+
+    #[error("No schema.org Recipe found in page")]
+    NoRecipe,
+
+    #[error("Invalid recipe data: {0}")]
+    InvalidRecipe(String),
+
+    #[error("Invalid URL: {0}")]
+    InvalidUrl(String),
+
+    #[error("Disallowed by robots.txt: {0}")]
+    Disallowed(String),
+
+    #[error("Crawl state error: {0}")]
+    StateError(String),
+
+    #[error("Tandoor error: {0}")]
+    Tandoor(#[from] TandoorError),
+}
+
+/// HTTP client configured for scraping
+pub fn http_client() -> Result<Client, ScrapeError> {
+    Ok(Client::builder()
+        .user_agent(USER_AGENT)
+        .timeout(FETCH_TIMEOUT)
+        .build()?)
+}
+
+/// Fetch `url` and extract its schema.org recipe
+pub fn fetch_recipe(url: &str) -> Result<SourceImportRecipe, ScrapeError> {
+    fetch_recipe_with(&http_client()?, url)
+}
+
+/// Fetch `url` with `client` and extract its schema.org recipe
+///
+/// The recipe's `source_url` is the page URL after redirects.
+pub fn fetch_recipe_with(client: &Client, url: &str) -> Result<SourceImportRecipe, ScrapeError> {
+    let response = client.get(url).send()?;
+    let status = response.status();
+    if !status.is_success() {
+        return Err(ScrapeError::StatusError {
+            status: status.as_u16(),
+            url: url.to_string(),
+        });
+    }
+
+    let page_url = response.url().to_string();
+    let html = response.text()?;
+    parse_recipe_html(&html, Some(&page_url))
+}
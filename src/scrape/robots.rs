@@ -0,0 +1,266 @@
+//! robots.txt rules (RFC 9309)
+//!
+//! Only the group for our user agent applies, or the `*` group if no group
+//! names it. The longest matching `Allow`/`Disallow` pattern wins, with
+//! `Allow` winning ties; `*` matches any characters and a trailing `$`
+//! anchors the pattern at the end of the path. `Crawl-delay` and `Sitemap`
+//! lines are kept as well.
+
+use std::time::Duration;
+
+/// Longest `Crawl-delay` honoured; larger values are capped to it
+const MAX_CRAWL_DELAY: Duration = Duration::from_secs(600);
+
+/// One `Allow` or `Disallow` rule
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct Rule {
+    allow: bool,
+    pattern: String,
+}
+
+/// Parsed robots.txt for one user agent
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct RobotsTxt {
+    rules: Vec<Rule>,
+    crawl_delay: Option<Duration>,
+    sitemaps: Vec<String>,
+}
+
+/// A user-agent group while parsing
+#[derive(Default)]
+struct Group {
+    agents: Vec<String>,
+    rules: Vec<Rule>,
+    crawl_delay: Option<Duration>,
+}
+
+impl RobotsTxt {
+    /// Rules that allow everything (robots.txt missing)
+    pub fn allow_all() -> Self {
+        Self::default()
+    }
+
+    /// Rules that disallow everything (robots.txt unreachable)
+    pub fn disallow_all() -> Self {
+        Self {
+            rules: vec![Rule {
+                allow: false,
+                pattern: "/".to_string(),
+            }],
+            ..Self::default()
+        }
+    }
+
+    /// Parse robots.txt, keeping the rules that apply to `user_agent`
+    ///
+    /// `user_agent` is matched case-insensitively against the product token
+    /// (`meal-planner` for `meal-planner/0.1.0`).
+    pub fn parse(text: &str, user_agent: &str) -> Self {
+        let token = user_agent
+            .split('/')
+            .next()
+            .unwrap_or(user_agent)
+            .to_ascii_lowercase();
+
+        let mut groups: Vec<Group> = Vec::new();
+        let mut sitemaps = Vec::new();
+        let mut current = Group::default();
+        let mut in_rules = false;
+
+        for line in text.lines() {
+            let line = line.split('#').next().unwrap_or_default().trim();
+            let Some((key, value)) = line.split_once(':') else {
+                continue;
+            };
+            let value = value.trim();
+
+            match key.trim().to_ascii_lowercase().as_str() {
+                "user-agent" => {
+                    // A user-agent line after rules starts a new group
+                    if in_rules {
+                        groups.push(std::mem::take(&mut current));
+                        in_rules = false;
+                    }
+                    current.agents.push(value.to_ascii_lowercase());
+                }
+                "allow" | "disallow" if !value.is_empty() => {
+                    in_rules = true;
+                    current.rules.push(Rule {
+                        allow: key.trim().eq_ignore_ascii_case("allow"),
+                        pattern: value.to_string(),
+                    });
+                }
+                "allow" | "disallow" => in_rules = true,
+                "crawl-delay" => {
+                    in_rules = true;
+                    current.crawl_delay = parse_delay(value);
+                }
+                "sitemap" => sitemaps.push(value.to_string()),
+                _ => {}
+            }
+        }
+        groups.push(current);
+        let applicable = applicable_groups(&groups, &token);
+
+        Self {
+            rules: applicable
+                .iter()
+                .flat_map(|g| g.rules.iter().cloned())
+                .collect(),
+            crawl_delay: applicable.iter().filter_map(|g| g.crawl_delay).max(),
+            sitemaps,
+        }
+    }
+
+    /// Whether `path` (path and query of a URL) may be fetched
+    pub fn is_allowed(&self, path: &str) -> bool {
+        self.rules
+            .iter()
+            .filter(|rule| pattern_matches(&rule.pattern, path))
+            .max_by_key(|rule| (rule.pattern.len(), rule.allow))
+            .map_or(true, |rule| rule.allow)
+    }
+
+    /// Requested delay between requests
+    pub const fn crawl_delay(&self) -> Option<Duration> {
+        self.crawl_delay
+    }
+
+    /// Sitemap URLs listed in the file
+    pub fn sitemaps(&self) -> &[String] {
+        &self.sitemaps
+    }
+}
+
+/// `Crawl-delay` value in seconds, possibly fractional, at most
+/// [`MAX_CRAWL_DELAY`]
+fn parse_delay(value: &str) -> Option<Duration> {
+    let secs = value.parse::<f64>().ok().filter(|secs| *secs >= 0.0)?;
+    Some(
+        Duration::try_from_secs_f64(secs)
+            .map_or(MAX_CRAWL_DELAY, |delay| delay.min(MAX_CRAWL_DELAY)),
+    )
+}
+
+/// Groups naming `token`, or the `*` groups if none does
+fn applicable_groups<'a>(groups: &'a [Group], token: &str) -> Vec<&'a Group> {
+    let named: Vec<&Group> = groups
+        .iter()
+        .filter(|g| g.agents.iter().any(|a| a == token))
+        .collect();
+    if named.is_empty() {
+        groups
+            .iter()
+            .filter(|g| g.agents.iter().any(|a| a == "*"))
+            .collect()
+    } else {
+        named
+    }
+}
+
+/// Match a robots.txt path pattern against a path
+fn pattern_matches(pattern: &str, path: &str) -> bool {
+    let (pattern, anchored) = pattern
+        .strip_suffix('$')
+        .map_or((pattern, false), |p| (p, true));
+
+    let segments: Vec<&str> = pattern.split('*').collect();
+    let Some((first, others)) = segments.split_first() else {
+        return false;
+    };
+    let Some(mut rest) = path.strip_prefix(first) else {
+        return false;
+    };
+    let Some((last, middle)) = others.split_last() else {
+        return !anchored || rest.is_empty();
+    };
+
+    for segment in middle {
+        let Some(pos) = rest.find(segment) else {
+            return false;
+        };
+        rest = rest.get(pos + segment.len()..).unwrap_or_default();
+    }
+
+    if anchored {
+        rest.ends_with(last)
+    } else {
+        rest.contains(last)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const ROBOTS: &str = "
+# Example robots.txt
+User-agent: *
+Disallow: /admin
+Disallow: /search
+Allow: /search/recipes
+Disallow: /*.pdf$
+Crawl-delay: 5
+
+User-agent: BadBot
+User-agent: meal-planner
+Disallow: /cart
+Crawl-delay: 2.5
+
+Sitemap: https://example.com/sitemap.xml
+";
+
+    #[test]
+    fn test_named_group_replaces_wildcard_group() {
+        let robots = RobotsTxt::parse(ROBOTS, "meal-planner/0.1.0");
+        assert!(!robots.is_allowed("/cart"));
+        assert!(robots.is_allowed("/admin"));
+        assert_eq!(robots.crawl_delay(), Some(Duration::from_millis(2500)));
+        assert_eq!(robots.sitemaps(), ["https://example.com/sitemap.xml"]);
+    }
+
+    #[test]
+    fn test_wildcard_group() {
+        let robots = RobotsTxt::parse(ROBOTS, "other-agent/1.0");
+        assert!(robots.is_allowed("/blogs/recipes/brisket"));
+        assert!(!robots.is_allowed("/admin/login"));
+        assert!(!robots.is_allowed("/search?q=ribs"));
+        // Longer Allow beats shorter Disallow
+        assert!(robots.is_allowed("/search/recipes?page=2"));
+        assert!(!robots.is_allowed("/files/menu.pdf"));
+        assert!(robots.is_allowed("/files/menu.pdf?download=1"));
+        assert_eq!(robots.crawl_delay(), Some(Duration::from_secs(5)));
+    }
+
+    #[test]
+    fn test_crawl_delay_is_capped() {
+        let delay = |value| {
+            RobotsTxt::parse(&format!("User-agent: *\nCrawl-delay: {value}\n"), "bot").crawl_delay()
+        };
+        assert_eq!(delay("1e20"), Some(MAX_CRAWL_DELAY));
+        assert_eq!(delay("inf"), Some(MAX_CRAWL_DELAY));
+        assert_eq!(delay("700"), Some(MAX_CRAWL_DELAY));
+        assert_eq!(delay("0.5"), Some(Duration::from_millis(500)));
+        assert_eq!(delay("NaN"), None);
+        assert_eq!(delay("-1"), None);
+    }
+
+    #[test]
+    fn test_empty_disallow_allows_everything() {
+        let robots = RobotsTxt::parse("User-agent: *\nDisallow:\n", "meal-planner");
+        assert!(robots.is_allowed("/anything"));
+        assert!(RobotsTxt::allow_all().is_allowed("/"));
+        assert!(!RobotsTxt::disallow_all().is_allowed("/recipes"));
+    }
+
+    #[test]
+    fn test_pattern_matches() {
+        assert!(pattern_matches("/recipes", "/recipes/ribs"));
+        assert!(pattern_matches("/*/print", "/recipes/ribs/print"));
+        assert!(pattern_matches("/recipes$", "/recipes"));
+        assert!(!pattern_matches("/recipes$", "/recipes/ribs"));
+        assert!(pattern_matches("/*?sort=", "/recipes?sort=new"));
+        assert!(pattern_matches("*", "/"));
+        assert!(!pattern_matches("/cart", "/blog/cart"));
+    }
+}
@@ -0,0 +1,684 @@
+//! schema.org `Recipe` extraction
+//!
+//! A page describes its recipe either as JSON-LD (`<script
+//! type="application/ld+json">`) or as microdata (`itemscope
+//! itemtype="https://schema.org/Recipe"`). JSON-LD is preferred; microdata is
+//! converted to the same JSON shape so both go through [`recipe_from_schema`].
+//!
+//! The recipe may be the top-level node, one entry of an array or of an
+//! `@graph`, or nested in another node (e.g. a `WebPage`'s `mainEntity`).
+//!
+//! Property mapping onto [`SourceImportRecipe`]:
+//!
+//! - `prepTime` → `working_time`, `cookTime` → `waiting_time` (ISO-8601
+//!   durations in minutes); with only `totalTime`, the part not covered by
+//!   `cookTime` is working time
+//! - `recipeYield` → `servings` (first number, default 1) and `servings_text`
+//!   (the words after it, e.g. "cookies" from "Makes 12 cookies")
+//! - `recipeInstructions` → one step per string, `HowToStep` or line of text;
+//!   a `HowToSection`'s name becomes a heading on its first step
+//! - `recipeIngredient` → ingredients of the first step, each line split
+//!   into amount, unit, food and note with [`parse_ingredient`] and kept
+//!   whole as `original_text`
+//! - `keywords`, `recipeCategory` and `recipeCuisine` → keywords
+
+use super::ScrapeError;
+use crate::tandoor::ingredient::parse_ingredient;
+use crate::tandoor::{
+    SourceImportIngredient, SourceImportKeyword, SourceImportRecipe, SourceImportStep,
+};
+use scraper::{ElementRef, Html, Node};
+use serde_json::{Map, Value};
+use std::collections::HashSet;
+use url::Url;
+
+/// Elements that start a new line of text
+const BLOCK_ELEMENTS: &[&str] = &[
+    "br", "div", "h1", "h2", "h3", "h4", "h5", "h6", "li", "ol", "p", "tr", "ul",
+];
+
+/// Extract the schema.org recipe from a page
+///
+/// `page_url` is used as the recipe's `source_url` and to resolve relative
+/// image URLs.
+pub fn parse_recipe_html(
+    html: &str,
+    page_url: Option<&str>,
+) -> Result<SourceImportRecipe, ScrapeError> {
+    let node = find_recipe_json(html).ok_or(ScrapeError::NoRecipe)?;
+    recipe_from_schema(&node, page_url)
+}
+
+/// Find the schema.org `Recipe` node in a page, from JSON-LD or microdata
+pub fn find_recipe_json(html: &str) -> Option<Value> {
+    let document = Html::parse_document(html);
+
+    json_ld_blocks(&document)
+        .iter()
+        .find_map(find_recipe_node)
+        .cloned()
+        .or_else(|| microdata_recipe(&document))
+}
+
+/// Convert a schema.org `Recipe` node into an import recipe
+pub fn recipe_from_schema(
+    node: &Value,
+    page_url: Option<&str>,
+) -> Result<SourceImportRecipe, ScrapeError> {
+    let name = node
+        .get("name")
+        .and_then(text_of)
+        .ok_or_else(|| ScrapeError::InvalidRecipe("recipe has no name".to_string()))?;
+
+    let source_url = page_url.map(str::to_string).or_else(|| {
+        node.get("url")
+            .and_then(text_of)
+            .filter(|url| Url::parse(url).is_ok())
+    });
+    let image = node
+        .get("image")
+        .and_then(image_url)
+        .and_then(|image| resolve_url(&image, page_url));
+    let (servings, servings_text) =
+        parse_yield(node.get("recipeYield").or_else(|| node.get("yield")));
+    let (working_time, waiting_time) = times(node);
+
+    Ok(SourceImportRecipe {
+        name,
+        description: node
+            .get("description")
+            .and_then(text_of)
+            .unwrap_or_default(),
+        source_url,
+        image,
+        servings,
+        servings_text,
+        working_time,
+        waiting_time,
+        internal: true,
+        steps: steps(node),
+        keywords: keywords(node),
+    })
+}
+
+/// Parse an ISO-8601 duration (`PT1H30M`, `P0DT0H20M`, `PT90S`) into minutes
+///
+/// Seconds are rounded to the nearest minute. Returns None for anything else,
+/// including durations in years or months.
+pub fn parse_iso_duration(duration: &str) -> Option<i32> {
+    let duration = duration.trim().to_ascii_uppercase();
+    let rest = duration.strip_prefix('P')?;
+
+    let mut seconds = 0.0;
+    let mut in_time = false;
+    let mut number = String::new();
+    let mut any = false;
+
+    for c in rest.chars() {
+        match c {
+            'T' if number.is_empty() && !in_time => in_time = true,
+            '0'..='9' | '.' => number.push(c),
+            ',' => number.push('.'),
+            unit => {
+                let value: f64 = number.parse().ok()?;
+                number.clear();
+                let factor = match (unit, in_time) {
+                    ('W', false) => 7.0 * 86_400.0,
+                    ('D', false) => 86_400.0,
+                    ('H', true) => 3_600.0,
+                    ('M', true) => 60.0,
+                    ('S', true) => 1.0,
+                    _ => return None,
+                };
+                seconds += value * factor;
+                any = true;
+            }
+        }
+    }
+
+    if !any || !number.is_empty() {
+        return None;
+    }
+
+    let minutes = (seconds / 60.0).round();
+    if !(0.0..=f64::from(i32::MAX)).contains(&minutes) {
+        return None;
+    }
+    // Safe: range checked above
+    #[allow(clippy::cast_possible_truncation)]
+    Some(minutes as i32)
+}
+
+// ============================================================================
+// JSON-LD
+// ============================================================================
+
+/// Parsed contents of every JSON-LD script in the document
+fn json_ld_blocks(document: &Html) -> Vec<Value> {
+    document
+        .root_element()
+        .descendants()
+        .filter_map(ElementRef::wrap)
+        .filter(|el| {
+            el.value().name() == "script"
+                && el
+                    .value()
+                    .attr("type")
+                    .is_some_and(|t| t.trim().eq_ignore_ascii_case("application/ld+json"))
+        })
+        .filter_map(|el| parse_json_ld(&el.text().collect::<String>()))
+        .collect()
+}
+
+/// Parse one JSON-LD script, tolerating HTML comment wrappers and raw
+/// control characters inside strings
+fn parse_json_ld(script: &str) -> Option<Value> {
+    let script = script
+        .trim()
+        .trim_start_matches("<!--")
+        .trim_end_matches("-->")
+        .trim();
+
+    serde_json::from_str(script).ok().or_else(|| {
+        let sanitized: String = script
+            .chars()
+            .map(|c| if c.is_control() { ' ' } else { c })
+            .collect();
+        serde_json::from_str(&sanitized).ok()
+    })
+}
+
+/// Depth-first search for a node whose `@type` is `Recipe`
+fn find_recipe_node(value: &Value) -> Option<&Value> {
+    match value {
+        Value::Array(items) => items.iter().find_map(find_recipe_node),
+        Value::Object(object) => {
+            if has_type(value, "Recipe") {
+                Some(value)
+            } else {
+                object.values().find_map(find_recipe_node)
+            }
+        }
+        Value::Null | Value::Bool(_) | Value::Number(_) | Value::String(_) => None,
+    }
+}
+
+/// Whether a node's `@type` is (or includes) `type_name`
+///
+/// Accepts full IRIs (`http://schema.org/Recipe`) and prefixed names
+/// (`schema:Recipe`).
+fn has_type(node: &Value, type_name: &str) -> bool {
+    let matches = |t: &Value| {
+        t.as_str().is_some_and(|t| {
+            t == type_name
+                || t.strip_suffix(type_name)
+                    .is_some_and(|prefix| prefix.ends_with('/') || prefix.ends_with(':'))
+        })
+    };
+
+    match node.get("@type") {
+        Some(Value::Array(types)) => types.iter().any(matches),
+        Some(t) => matches(t),
+        None => false,
+    }
+}
+
+// ============================================================================
+// Microdata
+// ============================================================================
+
+/// First microdata item of type `Recipe`, as a JSON-LD-shaped object
+fn microdata_recipe(document: &Html) -> Option<Value> {
+    document
+        .root_element()
+        .descendants()
+        .filter_map(ElementRef::wrap)
+        .find(|el| {
+            el.value().attr("itemscope").is_some()
+                && el.value().attr("itemtype").is_some_and(|types| {
+                    types
+                        .split_whitespace()
+                        .any(|t| has_type(&serde_json::json!({ "@type": t }), "Recipe"))
+                })
+        })
+        .map(microdata_item)
+}
+
+/// Properties of a microdata item
+fn microdata_item(scope: ElementRef<'_>) -> Value {
+    let mut properties = Map::new();
+    if let Some(item_type) = scope.value().attr("itemtype") {
+        let first = item_type.split_whitespace().next().unwrap_or_default();
+        let name = first.rsplit(['/', ':']).next().unwrap_or(first);
+        properties.insert("@type".to_string(), Value::String(name.to_string()));
+    }
+    collect_properties(scope, &mut properties);
+    Value::Object(properties)
+}
+
+/// Collect `itemprop`s below `element` that belong to its item
+///
+/// Nested items own the properties inside them, so their subtrees are
+/// not searched.
+fn collect_properties(element: ElementRef<'_>, properties: &mut Map<String, Value>) {
+    for child in element.children().filter_map(ElementRef::wrap) {
+        if let Some(names) = child.value().attr("itemprop") {
+            let value = microdata_value(child);
+            for name in names.split_whitespace() {
+                add_property(properties, name, value.clone());
+            }
+        }
+        if child.value().attr("itemscope").is_none() {
+            collect_properties(child, properties);
+        }
+    }
+}
+
+/// Add a property value; repeated properties become arrays
+fn add_property(properties: &mut Map<String, Value>, name: &str, value: Value) {
+    match properties.get_mut(name) {
+        Some(Value::Array(values)) => values.push(value),
+        Some(existing) => *existing = Value::Array(vec![existing.take(), value]),
+        None => {
+            properties.insert(name.to_string(), value);
+        }
+    }
+}
+
+/// Value of an `itemprop` element, per the microdata spec
+fn microdata_value(element: ElementRef<'_>) -> Value {
+    if element.value().attr("itemscope").is_some() {
+        return microdata_item(element);
+    }
+
+    let attr = |name| element.value().attr(name).map(str::to_string);
+    let value = attr("content").or_else(|| match element.value().name() {
+        "a" | "area" | "link" => attr("href"),
+        "audio" | "embed" | "iframe" | "img" | "source" | "video" => attr("src"),
+        "object" => attr("data"),
+        "data" | "meter" => attr("value"),
+        "time" => attr("datetime"),
+        _ => None,
+    });
+
+    Value::String(value.unwrap_or_else(|| text_lines(element).join("\n")))
+}
+
+// ============================================================================
+// Property mapping
+// ============================================================================
+
+/// Text of a property value: a string, a number, or an object's `text`,
+/// `name` or `@value`; the first non-empty entry of an array
+fn text_of(value: &Value) -> Option<String> {
+    match value {
+        Value::String(s) => Some(clean_text(s)).filter(|s| !s.is_empty()),
+        Value::Number(n) => Some(n.to_string()),
+        Value::Array(items) => items.iter().find_map(text_of),
+        Value::Object(object) => ["text", "name", "@value"]
+            .iter()
+            .filter_map(|key| object.get(*key))
+            .find_map(text_of),
+        Value::Null | Value::Bool(_) => None,
+    }
+}
+
+/// Texts of a property that may be a single value or an array
+fn texts_of(value: &Value) -> Vec<String> {
+    match value {
+        Value::Array(items) => items.iter().flat_map(texts_of).collect(),
+        Value::Null | Value::Bool(_) | Value::Number(_) | Value::String(_) | Value::Object(_) => {
+            text_of(value).into_iter().collect()
+        }
+    }
+}
+
+/// Lines of text in an HTML fragment or element
+fn text_lines(element: ElementRef<'_>) -> Vec<String> {
+    let mut text = String::new();
+    for node in element.descendants() {
+        match node.value() {
+            // Line breaks in HTML text are just whitespace
+            Node::Text(t) => text.extend(t.chars().map(|c| if c == '\n' { ' ' } else { c })),
+            Node::Element(e) if BLOCK_ELEMENTS.contains(&e.name()) => text.push('\n'),
+            Node::Document
+            | Node::Fragment
+            | Node::Doctype(_)
+            | Node::Comment(_)
+            | Node::Element(_)
+            | Node::ProcessingInstruction(_) => {}
+        }
+    }
+
+    text.lines()
+        .map(collapse_whitespace)
+        .filter(|line| !line.is_empty())
+        .collect()
+}
+
+/// Lines of a string that may contain HTML markup or entities
+///
+/// Markup decides the lines if there is any; otherwise each line of the
+/// string is one line of text.
+fn html_lines(s: &str) -> Vec<String> {
+    if s.contains('<') {
+        return text_lines(Html::parse_fragment(s).root_element());
+    }
+
+    s.lines()
+        .map(|line| {
+            if line.contains('&') {
+                text_lines(Html::parse_fragment(line).root_element()).join(" ")
+            } else {
+                collapse_whitespace(line)
+            }
+        })
+        .filter(|line| !line.is_empty())
+        .collect()
+}
+
+/// A string's text on one line, without markup
+fn clean_text(s: &str) -> String {
+    html_lines(s).join(" ")
+}
+
+fn collapse_whitespace(s: &str) -> String {
+    s.split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+/// URL of an `image` property: a URL, an `ImageObject`, or an array of either
+fn image_url(value: &Value) -> Option<String> {
+    match value {
+        Value::String(url) => Some(url.trim().to_string()).filter(|url| !url.is_empty()),
+        Value::Array(items) => items.iter().find_map(image_url),
+        Value::Object(object) => object
+            .get("url")
+            .or_else(|| object.get("contentUrl"))
+            .and_then(image_url),
+        Value::Null | Value::Bool(_) | Value::Number(_) => None,
+    }
+}
+
+/// Make `url` absolute relative to the page
+fn resolve_url(url: &str, page_url: Option<&str>) -> Option<String> {
+    Url::parse(url)
+        .or_else(|_| Url::parse(page_url.unwrap_or_default())?.join(url))
+        .ok()
+        .map(String::from)
+}
+
+/// Servings and servings text from `recipeYield`
+fn parse_yield(value: Option<&Value>) -> (i32, String) {
+    let candidates = value.map(texts_of).unwrap_or_default();
+
+    let servings = candidates
+        .iter()
+        .find_map(|c| leading_number(c))
+        .unwrap_or(1);
+    let text = candidates
+        .iter()
+        .map(|c| yield_text(c))
+        .find(|t| !t.is_empty())
+        .unwrap_or_default();
+
+    (servings, text)
+}
+
+/// First positive whole number in `s`
+fn leading_number(s: &str) -> Option<i32> {
+    let start = s.find(|c: char| c.is_ascii_digit())?;
+    let digits: String = s
+        .get(start..)?
+        .chars()
+        .take_while(char::is_ascii_digit)
+        .collect();
+    digits.parse().ok().filter(|n| *n > 0)
+}
+
+/// Words after the yield's number (and range): "cookies" from "Makes 12
+/// cookies", "people" from "4-6 people"; the whole string if it has no number
+fn yield_text(s: &str) -> String {
+    let Some(start) = s.find(|c: char| c.is_ascii_digit()) else {
+        return collapse_whitespace(s);
+    };
+    fn after_number(s: &str) -> &str {
+        s.trim_start_matches(|c: char| c.is_ascii_digit() || c == '.')
+    }
+
+    let rest = after_number(s.get(start..).unwrap_or_default()).trim_start();
+    let rest = ["-", "–", "to "]
+        .iter()
+        .find_map(|sep| rest.strip_prefix(sep))
+        .map(str::trim_start)
+        .filter(|r| r.starts_with(|c: char| c.is_ascii_digit()))
+        .map_or(rest, after_number);
+
+    collapse_whitespace(rest)
+}
+
+/// Working and waiting time in minutes
+fn times(node: &Value) -> (i32, i32) {
+    let minutes = |key| {
+        node.get(key)
+            .and_then(text_of)
+            .and_then(|d| parse_iso_duration(&d))
+    };
+
+    match (
+        minutes("prepTime"),
+        minutes("cookTime"),
+        minutes("totalTime"),
+    ) {
+        (None, cook, Some(total)) => {
+            let cook = cook.unwrap_or(0);
+            ((total - cook).max(0), cook)
+        }
+        (prep, cook, _) => (prep.unwrap_or(0), cook.unwrap_or(0)),
+    }
+}
+
+/// Steps from `recipeInstructions`, with all ingredients on the first step
+fn steps(node: &Value) -> Vec<SourceImportStep> {
+    let mut instructions = Vec::new();
+    if let Some(value) = node.get("recipeInstructions") {
+        collect_instructions(value, &mut instructions);
+    }
+
+    let ingredients: Vec<SourceImportIngredient> = node
+        .get("recipeIngredient")
+        .or_else(|| node.get("ingredients"))
+        .map(texts_of)
+        .unwrap_or_default()
+        .iter()
+        .map(|line| parse_ingredient(line).to_source_ingredient())
+        .collect();
+
+    if instructions.is_empty() && !ingredients.is_empty() {
+        instructions.push(String::new());
+    }
+
+    let mut ingredients = Some(ingredients);
+    instructions
+        .into_iter()
+        .map(|instruction| SourceImportStep {
+            instruction,
+            ingredients: ingredients.take().unwrap_or_default(),
+            show_ingredients_table: true,
+        })
+        .collect()
+}
+
+/// Flatten instructions: strings (one step per line), `HowToStep`s, and
+/// `HowToSection`s or `ItemList`s of them
+fn collect_instructions(value: &Value, instructions: &mut Vec<String>) {
+    match value {
+        Value::String(s) => instructions.extend(html_lines(s)),
+        Value::Array(items) => {
+            for item in items {
+                collect_instructions(item, instructions);
+            }
+        }
+        Value::Object(object) => {
+            if let Some(items) = object.get("itemListElement") {
+                let first = instructions.len();
+                collect_instructions(items, instructions);
+                let heading = object.get("name").and_then(text_of);
+                if let (Some(heading), Some(step)) = (heading, instructions.get_mut(first)) {
+                    *step = format!("## {heading}\n\n{step}");
+                }
+            } else if let Some(text) = object.get("text").or_else(|| object.get("name")) {
+                collect_instructions(text, instructions);
+            }
+        }
+        Value::Null | Value::Bool(_) | Value::Number(_) => {}
+    }
+}
+
+/// Keywords from `keywords`, `recipeCategory` and `recipeCuisine`,
+/// split on commas and deduplicated case-insensitively
+fn keywords(node: &Value) -> Vec<SourceImportKeyword> {
+    let mut seen = HashSet::new();
+
+    ["keywords", "recipeCategory", "recipeCuisine"]
+        .iter()
+        .filter_map(|key| node.get(*key))
+        .flat_map(texts_of)
+        .flat_map(|text| {
+            text.split(',')
+                .map(|k| k.trim().to_string())
+                .collect::<Vec<_>>()
+        })
+        .filter(|name| !name.is_empty() && seen.insert(name.to_lowercase()))
+        .map(|name| SourceImportKeyword {
+            id: None,
+            label: Some(name.clone()),
+            name,
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_parse_iso_duration() {
+        assert_eq!(parse_iso_duration("PT20M"), Some(20));
+        assert_eq!(parse_iso_duration("PT1H30M"), Some(90));
+        assert_eq!(parse_iso_duration("P0DT0H45M"), Some(45));
+        assert_eq!(parse_iso_duration("P1DT2H"), Some(1560));
+        assert_eq!(parse_iso_duration("PT1.5H"), Some(90));
+        assert_eq!(parse_iso_duration("PT90S"), Some(2));
+        assert_eq!(parse_iso_duration("pt10m"), Some(10));
+        assert_eq!(parse_iso_duration(" PT5M "), Some(5));
+    }
+
+    #[test]
+    fn test_parse_iso_duration_rejects_invalid() {
+        for invalid in [
+            "",
+            "PT",
+            "P",
+            "20 minutes",
+            "PT20",
+            "P1M",
+            "P1Y",
+            "PTT5M",
+            "PT5D",
+        ] {
+            assert_eq!(parse_iso_duration(invalid), None, "{invalid}");
+        }
+    }
+
+    #[test]
+    fn test_parse_yield() {
+        let cases = [
+            (json!("4 servings"), 4, "servings"),
+            (json!("Makes 12 cookies"), 12, "cookies"),
+            (json!("4-6 people"), 4, "people"),
+            (json!("6 to 8 servings"), 6, "servings"),
+            (json!(8), 8, ""),
+            (json!(["4", "4 servings"]), 4, "servings"),
+            (json!("Serves 2"), 2, ""),
+            (json!("one loaf"), 1, "one loaf"),
+        ];
+        for (value, servings, text) in cases {
+            assert_eq!(
+                parse_yield(Some(&value)),
+                (servings, text.to_string()),
+                "{value}"
+            );
+        }
+        assert_eq!(parse_yield(None), (1, String::new()));
+    }
+
+    #[test]
+    fn test_times_from_total_time() {
+        let node = json!({"totalTime": "PT1H", "cookTime": "PT40M"});
+        assert_eq!(times(&node), (20, 40));
+        let node = json!({"totalTime": "PT1H"});
+        assert_eq!(times(&node), (60, 0));
+        let node = json!({"prepTime": "PT10M", "cookTime": "PT40M", "totalTime": "PT2H"});
+        assert_eq!(times(&node), (10, 40));
+    }
+
+    #[test]
+    fn test_find_recipe_node_in_graph() {
+        let graph = json!({
+            "@context": "https://schema.org",
+            "@graph": [
+                {"@type": "WebSite", "name": "Blog"},
+                {"@type": "WebPage", "mainEntity": {"@type": ["Recipe", "NewsArticle"], "name": "Ribs"}}
+            ]
+        });
+        let node = find_recipe_node(&graph).unwrap();
+        assert_eq!(node["name"], "Ribs");
+        assert!(find_recipe_node(&json!({"@type": "Article", "name": "No"})).is_none());
+    }
+
+    #[test]
+    fn test_has_type_accepts_iris() {
+        assert!(has_type(&json!({"@type": "Recipe"}), "Recipe"));
+        assert!(has_type(
+            &json!({"@type": "http://schema.org/Recipe"}),
+            "Recipe"
+        ));
+        assert!(has_type(&json!({"@type": "schema:Recipe"}), "Recipe"));
+        assert!(!has_type(&json!({"@type": "NotARecipe"}), "Recipe"));
+    }
+
+    #[test]
+    fn test_instructions_from_html_string() {
+        let mut instructions = Vec::new();
+        collect_instructions(
+            &json!("<p>Preheat &amp; oil.</p><p>Bake\n  20 minutes.</p>"),
+            &mut instructions,
+        );
+        assert_eq!(instructions, ["Preheat & oil.", "Bake 20 minutes."]);
+
+        let mut instructions = Vec::new();
+        collect_instructions(
+            &json!("Mix salt &amp; pepper.\nRub the ribs."),
+            &mut instructions,
+        );
+        assert_eq!(instructions, ["Mix salt & pepper.", "Rub the ribs."]);
+    }
+
+    #[test]
+    fn test_keywords_are_split_and_deduplicated() {
+        let node = json!({
+            "keywords": "bbq, Smoked,ribs",
+            "recipeCategory": ["Main Course", "BBQ"],
+            "recipeCuisine": "American"
+        });
+        let names: Vec<String> = keywords(&node).into_iter().map(|k| k.name).collect();
+        assert_eq!(names, ["bbq", "Smoked", "ribs", "Main Course", "American"]);
+    }
+
+    #[test]
+    fn test_recipe_requires_name() {
+        let result = recipe_from_schema(&json!({"@type": "Recipe"}), None);
+        assert!(matches!(result, Err(ScrapeError::InvalidRecipe(_))));
+    }
+}
@@ -0,0 +1,661 @@
+//! Meal plan → iCalendar (FUNCTIONAL CORE - PURE)
+//!
+//! Builds an iCalendar feed from Tandoor meal plans locally, rather than
+//! asking Tandoor to render one meal plan at a time, so a whole date range
+//! fits in one file and events carry reminders. No I/O happens here: the
+//! calendar binary fetches meal plans and recipes and calls
+//! [`meal_plan_calendar`].
+//!
+//! Event rules:
+//! - One event per meal plan, on its first day
+//! - Starts at the meal type's `time`; meal types without one give all-day
+//!   events
+//! - Lasts the recipe's `working_time` plus `waiting_time`
+//!   ([`DEFAULT_DURATION_MINUTES`] when neither is set)
+//! - UID `tandoor-meal-plan-{id}@{host}` never changes, so calendar clients
+//!   update events on re-import instead of duplicating them
+//! - Steps that need a head start ("marinate overnight") become reminders
+//!   that far before the event, see [`prep_reminders`]
+//! - Times are floating (no time zone), so calendars show them as local time
+
+use std::collections::HashMap;
+
+use chrono::{DateTime, NaiveDate, NaiveTime, Utc};
+use serde::Serialize;
+use serde_json::Value;
+
+use crate::import::parse_quantity;
+use crate::tandoor::text::{format_lead, sentences, whole_hours, words};
+use crate::tandoor::MealPlan;
+
+/// Event length when the recipe has no working or waiting time
+pub const DEFAULT_DURATION_MINUTES: u32 = 30;
+
+/// Shortest lead worth a prep reminder; shorter waits fit in the recipe's
+/// waiting time
+pub const MIN_PREP_LEAD_MINUTES: u32 = 120;
+
+/// Lead for "overnight"
+const OVERNIGHT_MINUTES: u32 = 12 * 60;
+
+const PRODID: &str = "-//meal-planner//Tandoor meal plan//EN";
+
+/// Content lines longer than this are folded (RFC 5545 §3.1)
+const MAX_LINE_OCTETS: usize = 75;
+
+/// A step that takes a head start, recognised by its verb
+struct PrepAction {
+    words: &'static [&'static str],
+    /// What to start, for the reminder text
+    label: &'static str,
+}
+
+const PREP_ACTIONS: &[PrepAction] = &[
+    PrepAction {
+        words: &["marinate", "marinated", "marinating", "marinade"],
+        label: "marinating",
+    },
+    PrepAction {
+        words: &["soak", "soaked", "soaking"],
+        label: "soaking",
+    },
+    PrepAction {
+        words: &["brine", "brined", "brining"],
+        label: "brining",
+    },
+    PrepAction {
+        words: &[
+            "thaw",
+            "thawed",
+            "thawing",
+            "defrost",
+            "defrosted",
+            "defrosting",
+        ],
+        label: "thawing",
+    },
+    PrepAction {
+        words: &[
+            "chill",
+            "chilled",
+            "chilling",
+            "refrigerate",
+            "refrigerated",
+        ],
+        label: "chilling",
+    },
+    PrepAction {
+        words: &[
+            "proof", "proofed", "proofing", "prove", "proved", "rise", "risen", "rising",
+        ],
+        label: "proofing",
+    },
+    PrepAction {
+        words: &["cure", "cured", "curing"],
+        label: "curing",
+    },
+    PrepAction {
+        words: &["ferment", "fermented", "fermenting"],
+        label: "fermenting",
+    },
+];
+
+/// Options for [`meal_plan_calendar`]
+#[derive(Debug, Clone)]
+pub struct CalendarOptions {
+    /// Tandoor base URL, for recipe links and the UID domain
+    pub base_url: String,
+    /// Remind this many minutes before each meal
+    pub reminder_minutes: Option<u32>,
+    /// Add [`prep_reminders`] from recipe steps
+    pub prep_reminders: bool,
+}
+
+/// A reminder to start a step well before the meal
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct PrepReminder {
+    /// How long before the event starts
+    pub lead_minutes: u32,
+    /// What to start ("marinating")
+    pub action: &'static str,
+    /// The sentence it comes from
+    pub instruction: String,
+}
+
+/// A meal plan left out of the calendar
+#[derive(Debug, Clone, Serialize)]
+pub struct SkippedMealPlan {
+    /// Tandoor meal plan ID
+    pub meal_plan_id: i64,
+    /// Why it was skipped
+    pub reason: String,
+}
+
+/// A generated calendar
+#[derive(Debug, Clone, Serialize)]
+pub struct MealPlanCalendar {
+    /// iCalendar text (CRLF line endings)
+    pub ical: String,
+    /// Events written
+    pub events: usize,
+    /// Meal plans left out
+    pub skipped: Vec<SkippedMealPlan>,
+}
+
+/// Stable event UID for a meal plan
+pub fn meal_plan_uid(meal_plan_id: i64, domain: &str) -> String {
+    format!("tandoor-meal-plan-{meal_plan_id}@{domain}")
+}
+
+/// Build a calendar with one event per meal plan
+///
+/// `recipes` maps recipe IDs to full recipe JSON (as returned by Tandoor's
+/// recipe detail endpoint); plans whose recipe is missing fall back to the
+/// recipe summary in the meal plan, which has times but no steps.
+/// `generated_at` is written as every event's `DTSTAMP`.
+pub fn meal_plan_calendar(
+    plans: &[MealPlan],
+    recipes: &HashMap<i64, Value>,
+    options: &CalendarOptions,
+    generated_at: DateTime<Utc>,
+) -> MealPlanCalendar {
+    let domain = url::Url::parse(&options.base_url)
+        .ok()
+        .and_then(|url| url.host_str().map(str::to_string))
+        .unwrap_or_else(|| "tandoor".to_string());
+    let stamp = generated_at.format("%Y%m%dT%H%M%SZ").to_string();
+
+    let mut sorted: Vec<&MealPlan> = plans.iter().collect();
+    sorted.sort_by(|a, b| a.from_date.cmp(&b.from_date).then(a.id.cmp(&b.id)));
+
+    let mut lines = vec![
+        "BEGIN:VCALENDAR".to_string(),
+        "VERSION:2.0".to_string(),
+        format!("PRODID:{PRODID}"),
+        "CALSCALE:GREGORIAN".to_string(),
+        "METHOD:PUBLISH".to_string(),
+        "X-WR-CALNAME:Meal plan".to_string(),
+    ];
+    let mut events = 0;
+    let mut skipped = Vec::new();
+    for plan in sorted {
+        match event_lines(plan, recipes, options, &domain, &stamp) {
+            Ok(event) => {
+                lines.extend(event);
+                events += 1;
+            }
+            Err(reason) => skipped.push(SkippedMealPlan {
+                meal_plan_id: plan.id,
+                reason,
+            }),
+        }
+    }
+    lines.push("END:VCALENDAR".to_string());
+
+    let ical = lines.iter().map(|line| fold(line) + "\r\n").collect();
+    MealPlanCalendar {
+        ical,
+        events,
+        skipped,
+    }
+}
+
+/// The `VEVENT` for one meal plan
+fn event_lines(
+    plan: &MealPlan,
+    recipes: &HashMap<i64, Value>,
+    options: &CalendarOptions,
+    domain: &str,
+    stamp: &str,
+) -> Result<Vec<String>, String> {
+    let date = plan
+        .from_date
+        .get(..10)
+        .and_then(|date| NaiveDate::parse_from_str(date, "%Y-%m-%d").ok())
+        .ok_or_else(|| format!("Invalid date: {}", plan.from_date))?;
+    let recipe_id = plan.recipe.get("id").and_then(Value::as_i64);
+    let recipe = recipe_id
+        .and_then(|id| recipes.get(&id))
+        .unwrap_or(&plan.recipe);
+    let meal_type = Some(plan.meal_type_name.as_str())
+        .filter(|name| !name.is_empty())
+        .or_else(|| plan.meal_type.get("name").and_then(Value::as_str))
+        .unwrap_or_default();
+    let summary = event_summary(plan, recipe, meal_type);
+
+    let mut lines = vec![
+        "BEGIN:VEVENT".to_string(),
+        format!("UID:{}", meal_plan_uid(plan.id, domain)),
+        format!("DTSTAMP:{stamp}"),
+    ];
+    lines.extend(timing_lines(plan, recipe, date));
+    lines.push(format!("SUMMARY:{}", escape(&summary)));
+    let recipe_url = recipe_id.map(|id| {
+        format!(
+            "{}/view/recipe/{id}",
+            options.base_url.trim_end_matches('/')
+        )
+    });
+    let description = event_description(plan, recipe, recipe_url.as_deref());
+    if !description.is_empty() {
+        lines.push(format!("DESCRIPTION:{}", escape(&description)));
+    }
+    if !meal_type.is_empty() {
+        lines.push(format!("CATEGORIES:{}", escape(meal_type)));
+    }
+    lines.extend(recipe_url.map(|url| format!("URL:{url}")));
+    lines.extend(alarm_lines(recipe, &summary, options));
+    lines.push("END:VEVENT".to_string());
+    Ok(lines)
+}
+
+/// `Dinner: Chicken Tikka`; the plan's title wins over the recipe name
+fn event_summary(plan: &MealPlan, recipe: &Value, meal_type: &str) -> String {
+    let name = Some(plan.title.trim())
+        .filter(|title| !title.is_empty())
+        .or_else(|| recipe.get("name").and_then(Value::as_str))
+        .or_else(|| Some(plan.recipe_name.as_str()).filter(|name| !name.is_empty()));
+    match (meal_type.is_empty(), name) {
+        (false, Some(name)) => format!("{meal_type}: {name}"),
+        (true, Some(name)) => name.to_string(),
+        (false, None) => meal_type.to_string(),
+        (true, None) => "Meal".to_string(),
+    }
+}
+
+/// Start and length: timed at the meal type's time, or all day
+fn timing_lines(plan: &MealPlan, recipe: &Value, date: NaiveDate) -> Vec<String> {
+    let time = plan
+        .meal_type
+        .get("time")
+        .and_then(Value::as_str)
+        .and_then(|time| {
+            NaiveTime::parse_from_str(time, "%H:%M:%S")
+                .or_else(|_| NaiveTime::parse_from_str(time, "%H:%M"))
+                .ok()
+        });
+    let Some(time) = time else {
+        let next_day = date.succ_opt().unwrap_or(date);
+        return vec![
+            format!("DTSTART;VALUE=DATE:{}", date.format("%Y%m%d")),
+            format!("DTEND;VALUE=DATE:{}", next_day.format("%Y%m%d")),
+        ];
+    };
+    let minutes =
+        Some(recipe_minutes(recipe, "working_time") + recipe_minutes(recipe, "waiting_time"))
+            .filter(|minutes| *minutes > 0)
+            .unwrap_or(DEFAULT_DURATION_MINUTES);
+    vec![
+        format!(
+            "DTSTART:{}T{}",
+            date.format("%Y%m%d"),
+            time.format("%H%M%S")
+        ),
+        format!("DURATION:PT{minutes}M"),
+    ]
+}
+
+fn recipe_minutes(recipe: &Value, field: &str) -> u32 {
+    recipe
+        .get(field)
+        .and_then(Value::as_u64)
+        .and_then(|minutes| u32::try_from(minutes).ok())
+        .unwrap_or(0)
+}
+
+/// Servings, times, the plan's note and a link to the recipe
+fn event_description(plan: &MealPlan, recipe: &Value, recipe_url: Option<&str>) -> String {
+    let mut parts = Vec::new();
+    if plan.servings > 0.0 {
+        parts.push(format!("Servings: {}", plan.servings));
+    }
+    let (working, waiting) = (
+        recipe_minutes(recipe, "working_time"),
+        recipe_minutes(recipe, "waiting_time"),
+    );
+    if working > 0 || waiting > 0 {
+        parts.push(format!(
+            "Working time: {working} min, waiting time: {waiting} min"
+        ));
+    }
+    let note = plan.note.trim();
+    if !note.is_empty() {
+        parts.push(note.to_string());
+    }
+    parts.extend(recipe_url.map(str::to_string));
+    parts.join("\n")
+}
+
+/// The meal reminder and prep reminders for an event
+fn alarm_lines(recipe: &Value, summary: &str, options: &CalendarOptions) -> Vec<String> {
+    let mut lines = Vec::new();
+    if let Some(minutes) = options.reminder_minutes {
+        lines.extend(alarm(minutes, summary));
+    }
+    if options.prep_reminders {
+        for reminder in prep_reminders(recipe) {
+            let description = format!(
+                "Start {} {} before {summary}: {}",
+                reminder.action,
+                format_lead(reminder.lead_minutes),
+                reminder.instruction
+            );
+            lines.extend(alarm(reminder.lead_minutes, &description));
+        }
+    }
+    lines
+}
+
+fn alarm(lead_minutes: u32, description: &str) -> [String; 5] {
+    let trigger = whole_hours(lead_minutes).map_or_else(
+        || format!("-PT{lead_minutes}M"),
+        |hours| format!("-PT{hours}H"),
+    );
+    [
+        "BEGIN:VALARM".to_string(),
+        "ACTION:DISPLAY".to_string(),
+        format!("TRIGGER:{trigger}"),
+        format!("DESCRIPTION:{}", escape(description)),
+        "END:VALARM".to_string(),
+    ]
+}
+
+/// Steps that need starting well before the meal
+///
+/// Looks for a prep verb (marinate, soak, brine, thaw, chill, proof, cure,
+/// ferment) in each sentence of each step, with a duration in the same
+/// sentence ("12 hours", "8h", "overnight" as 12 hours; the longest counts,
+/// ranges count from their low end) or, failing that, the step's `time`.
+/// Leads under [`MIN_PREP_LEAD_MINUTES`] are left out. Longest lead first.
+pub fn prep_reminders(recipe: &Value) -> Vec<PrepReminder> {
+    let steps = recipe
+        .get("steps")
+        .and_then(Value::as_array)
+        .map_or(&[][..], Vec::as_slice);
+    let mut reminders: Vec<PrepReminder> = Vec::new();
+    for step in steps {
+        let instruction = step
+            .get("instruction")
+            .and_then(Value::as_str)
+            .unwrap_or_default();
+        let step_time = step
+            .get("time")
+            .and_then(Value::as_u64)
+            .and_then(|time| u32::try_from(time).ok());
+        for sentence in sentences(instruction) {
+            let words = words(sentence);
+            let Some(action) = PREP_ACTIONS.iter().find(|action| {
+                words
+                    .iter()
+                    .any(|word| action.words.contains(&word.as_str()))
+            }) else {
+                continue;
+            };
+            let Some(lead) = lead_minutes(&words)
+                .or(step_time)
+                .filter(|lead| *lead >= MIN_PREP_LEAD_MINUTES)
+            else {
+                continue;
+            };
+            let reminder = PrepReminder {
+                lead_minutes: lead,
+                action: action.label,
+                instruction: sentence.to_string(),
+            };
+            if !reminders.contains(&reminder) {
+                reminders.push(reminder);
+            }
+        }
+    }
+    reminders.sort_by(|a, b| b.lead_minutes.cmp(&a.lead_minutes));
+    reminders
+}
+
+/// Longest duration in a sentence: `12 hours`, `8h`, `a day`, `overnight`
+fn lead_minutes(words: &[String]) -> Option<u32> {
+    let spelled = words
+        .iter()
+        .zip(words.iter().skip(1))
+        .filter_map(|(amount, unit)| duration(amount, unit));
+    let glued = words.iter().filter_map(|word| {
+        let (amount, unit) = word.split_at(word.find(char::is_alphabetic)?);
+        duration(amount, unit)
+    });
+    let overnight = words
+        .iter()
+        .any(|word| word == "overnight")
+        .then_some(OVERNIGHT_MINUTES);
+    spelled.chain(glued).chain(overnight).max()
+}
+
+fn duration(amount: &str, unit: &str) -> Option<u32> {
+    let amount = match amount {
+        "a" | "an" | "one" => 1.0,
+        _ => parse_quantity(amount)?,
+    };
+    let factor = match unit {
+        "d" | "day" | "days" => 24.0 * 60.0,
+        "h" | "hr" | "hrs" | "hour" | "hours" => 60.0,
+        "m" | "min" | "mins" | "minute" | "minutes" => 1.0,
+        _ => return None,
+    };
+    #[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+    Some((amount * factor).round() as u32)
+}
+
+/// Escape a TEXT value (RFC 5545 §3.3.11)
+fn escape(text: &str) -> String {
+    text.replace('\\', "\\\\")
+        .replace(';', "\\;")
+        .replace(',', "\\,")
+        .replace("\r\n", "\\n")
+        .replace('\n', "\\n")
+}
+
+/// Fold a content line at [`MAX_LINE_OCTETS`], never inside a character
+fn fold(line: &str) -> String {
+    let mut folded = String::with_capacity(line.len());
+    let mut octets = 0;
+    for c in line.chars() {
+        if octets + c.len_utf8() > MAX_LINE_OCTETS {
+            folded.push_str("\r\n ");
+            octets = 1;
+        }
+        folded.push(c);
+        octets += c.len_utf8();
+    }
+    folded
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+    use serde_json::json;
+
+    fn plan(id: i64, from: &str, meal_type: &Value, recipe: &Value) -> MealPlan {
+        serde_json::from_value(json!({
+            "id": id,
+            "title": "",
+            "recipe": recipe,
+            "servings": 2.0,
+            "note": "",
+            "from_date": from,
+            "to_date": from,
+            "meal_type": meal_type,
+            "created_by": 1,
+            "recipe_name": "",
+            "meal_type_name": meal_type["name"]
+        }))
+        .unwrap()
+    }
+
+    fn tikka() -> Value {
+        json!({
+            "id": 12,
+            "name": "Chicken Tikka",
+            "working_time": 30,
+            "waiting_time": 15,
+            "steps": [
+                {"instruction": "Mix the yogurt and spices. Marinate the chicken overnight.", "time": 0},
+                {"instruction": "Grill for 15 minutes.", "time": 15}
+            ]
+        })
+    }
+
+    fn options() -> CalendarOptions {
+        CalendarOptions {
+            base_url: "https://tandoor.example:8090/".to_string(),
+            reminder_minutes: None,
+            prep_reminders: false,
+        }
+    }
+
+    fn calendar(
+        plans: &[MealPlan],
+        recipes: &HashMap<i64, Value>,
+        options: &CalendarOptions,
+    ) -> MealPlanCalendar {
+        let generated_at = Utc.with_ymd_and_hms(2026, 10, 19, 8, 0, 0).unwrap();
+        meal_plan_calendar(plans, recipes, options, generated_at)
+    }
+
+    #[test]
+    fn test_timed_event() {
+        let dinner = json!({"id": 3, "name": "Dinner", "time": "18:30:00"});
+        let plans = [plan(
+            42,
+            "2026-10-20T00:00:00+02:00",
+            &dinner,
+            &json!({"id": 12}),
+        )];
+        let recipes = HashMap::from([(12, tikka())]);
+        let result = calendar(&plans, &recipes, &options());
+
+        assert_eq!(result.events, 1);
+        let lines: Vec<&str> = result.ical.split("\r\n").collect();
+        for expected in [
+            "BEGIN:VCALENDAR",
+            "UID:tandoor-meal-plan-42@tandoor.example",
+            "DTSTAMP:20261019T080000Z",
+            "DTSTART:20261020T183000",
+            "DURATION:PT45M",
+            "SUMMARY:Dinner: Chicken Tikka",
+            "CATEGORIES:Dinner",
+            "URL:https://tandoor.example:8090/view/recipe/12",
+        ] {
+            assert!(lines.contains(&expected), "missing {expected}");
+        }
+        assert!(result.ical.ends_with("END:VCALENDAR\r\n"));
+        assert!(!result.ical.contains("VALARM"));
+    }
+
+    #[test]
+    fn test_all_day_event_without_meal_type_time() {
+        let snack = json!({"id": 4, "name": "Snack", "time": null});
+        let mut titled = plan(7, "2026-10-31", &snack, &Value::Null);
+        titled.title = "Leftovers".to_string();
+        let result = calendar(&[titled], &HashMap::new(), &options());
+
+        assert!(result.ical.contains("DTSTART;VALUE=DATE:20261031\r\n"));
+        assert!(result.ical.contains("DTEND;VALUE=DATE:20261101\r\n"));
+        assert!(result.ical.contains("SUMMARY:Snack: Leftovers\r\n"));
+        assert!(!result.ical.contains("DURATION"));
+        assert!(!result.ical.contains("URL:"));
+    }
+
+    #[test]
+    fn test_uid_is_stable_and_invalid_dates_skipped() {
+        let dinner = json!({"id": 3, "name": "Dinner", "time": "18:30"});
+        let plans = [
+            plan(
+                42,
+                "2026-10-20",
+                &dinner,
+                &json!({"id": 12, "name": "Chicken Tikka"}),
+            ),
+            plan(
+                43,
+                "soon",
+                &dinner,
+                &json!({"id": 12, "name": "Chicken Tikka"}),
+            ),
+        ];
+        let first = calendar(&plans, &HashMap::new(), &options());
+        let second = calendar(&plans, &HashMap::new(), &options());
+        assert_eq!(first.ical, second.ical);
+        assert_eq!(first.events, 1);
+        assert_eq!(first.skipped.len(), 1);
+        assert_eq!(first.skipped[0].meal_plan_id, 43);
+        // The summary in the plan has no times
+        assert!(first.ical.contains("DURATION:PT30M\r\n"));
+    }
+
+    #[test]
+    fn test_reminders() {
+        let dinner = json!({"id": 3, "name": "Dinner", "time": "18:30:00"});
+        let plans = [plan(42, "2026-10-20", &dinner, &json!({"id": 12}))];
+        let recipes = HashMap::from([(12, tikka())]);
+        let options = CalendarOptions {
+            reminder_minutes: Some(45),
+            prep_reminders: true,
+            ..options()
+        };
+        let result = calendar(&plans, &recipes, &options);
+
+        assert_eq!(result.ical.matches("BEGIN:VALARM").count(), 2);
+        assert!(result.ical.contains("TRIGGER:-PT45M\r\n"));
+        assert!(result.ical.contains("TRIGGER:-PT12H\r\n"));
+        let unfolded = result.ical.replace("\r\n ", "");
+        assert!(unfolded.contains(
+            "DESCRIPTION:Start marinating 12h before Dinner: Chicken Tikka: Marinate the chicken overnight."
+        ));
+    }
+
+    type PrepCase = (&'static str, u64, Option<(u32, &'static str)>);
+
+    #[test]
+    fn test_prep_reminders() {
+        let cases: [PrepCase; 8] = [
+            (
+                "Marinate the chicken overnight.",
+                0,
+                Some((720, "marinating")),
+            ),
+            ("Soak the beans for 8-10 hours", 0, Some((480, "soaking"))),
+            ("Let the dough rise for 1.5 hours", 0, None),
+            ("Cover and let rise 2h", 0, Some((120, "proofing"))),
+            (
+                "Thaw the turkey in the fridge",
+                2880,
+                Some((2880, "thawing")),
+            ),
+            ("Brine the pork for a day", 0, Some((1440, "brining"))),
+            ("Chill for 30 minutes", 0, None),
+            ("Add 2 tsp chilli flakes and simmer 3 hours", 0, None),
+        ];
+        for (instruction, time, expected) in cases {
+            let recipe = json!({"steps": [{"instruction": instruction, "time": time}]});
+            let found = prep_reminders(&recipe)
+                .first()
+                .map(|reminder| (reminder.lead_minutes, reminder.action));
+            assert_eq!(found, expected, "{instruction}");
+        }
+    }
+
+    #[test]
+    fn test_escape_and_fold() {
+        assert_eq!(
+            escape("Salt, pepper; oil\\vinegar\nServe"),
+            "Salt\\, pepper\\; oil\\\\vinegar\\nServe"
+        );
+
+        let line = format!("DESCRIPTION:{}", "é".repeat(60));
+        let folded = fold(&line);
+        assert!(folded
+            .split("\r\n")
+            .all(|part| part.len() <= MAX_LINE_OCTETS));
+        assert_eq!(folded.replace("\r\n ", ""), line);
+        assert_eq!(fold("SUMMARY:Dinner"), "SUMMARY:Dinner");
+    }
+}
@@ -76,8 +76,13 @@
 use crate::tandoor::types::*;
 use reqwest::blocking::Client;
 use reqwest::header::{HeaderMap, HeaderValue, AUTHORIZATION, CONTENT_TYPE};
+use std::collections::hash_map::Entry;
+use std::collections::HashMap;
 use thiserror::Error;
 
+/// Page size used when walking a whole listing
+const PAGE_SIZE: u32 = 100;
+
 #[derive(Error, Debug)]
 #[allow(clippy::enum_variant_names)]
 pub enum TandoorError {
@@ -152,6 +157,11 @@ impl TandoorClient {
         })
     }
 
+    /// Base URL of the Tandoor instance, without a trailing slash
+    pub fn base_url(&self) -> &str {
+        &self.base_url
+    }
+
     /// Validate request body size against DOS limits
     #[allow(clippy::unused_self)]
     fn validate_request_size(&self, body: &[u8]) -> Result<(), TandoorError> {
@@ -260,6 +270,33 @@ impl TandoorClient {
             .map_err(|e| TandoorError::ParseError(e.to_string()))
     }
 
+    /// List recipes tagged with a keyword, with pagination
+    pub fn list_recipes_by_keyword(
+        &self,
+        keyword_id: i64,
+        page: Option<u32>,
+        page_size: Option<u32>,
+    ) -> Result<PaginatedResponse<RecipeSummary>, TandoorError> {
+        let mut params = vec![format!("keywords={}", keyword_id)];
+        if let Some(p) = page {
+            params.push(format!("page={}", p));
+        }
+        if let Some(ps) = page_size {
+            params.push(format!("page_size={}", ps));
+        }
+        let url = format!("{}/api/recipe/?{}", self.base_url, params.join("&"));
+        let response = self.client.get(&url).send()?;
+        if !response.status().is_success() {
+            return Err(TandoorError::ApiError {
+                status: response.status().as_u16(),
+                message: response.text().unwrap_or_default(),
+            });
+        }
+        response
+            .json()
+            .map_err(|e| TandoorError::ParseError(e.to_string()))
+    }
+
     /// Import a recipe from a URL using Tandoor's scraper
     ///
     /// This calls the /api/recipe-from-source/ endpoint which scrapes
@@ -809,6 +846,21 @@ impl TandoorClient {
             .map_err(|e| TandoorError::ParseError(e.to_string()))
     }
 
+    /// Get a single recipe by ID with typed steps, ingredients and nutrition
+    pub fn get_recipe_detail(&self, id: i64) -> Result<RecipeDetail, TandoorError> {
+        let url = format!("{}/api/recipe/{}/", self.base_url, id);
+        let response = self.client.get(&url).send()?;
+        if !response.status().is_success() {
+            return Err(TandoorError::ApiError {
+                status: response.status().as_u16(),
+                message: response.text().unwrap_or_default(),
+            });
+        }
+        response
+            .json()
+            .map_err(|e| TandoorError::ParseError(e.to_string()))
+    }
+
     /// Delete a recipe by ID
     pub fn delete_recipe(&self, id: i64) -> Result<(), TandoorError> {
         let url = format!("{}/api/recipe/{}/", self.base_url, id);
@@ -966,6 +1018,70 @@ impl TandoorClient {
             .map_err(|e| TandoorError::ParseError(e.to_string()))
     }
 
+    /// List meal plans overlapping a date range (dates as YYYY-MM-DD, inclusive)
+    pub fn list_meal_plans_by_date(
+        &self,
+        from_date: &str,
+        to_date: &str,
+        page: Option<u32>,
+        page_size: Option<u32>,
+    ) -> Result<PaginatedMealPlanResponse, TandoorError> {
+        let mut url = format!(
+            "{}/api/meal-plan/?from_date={}&to_date={}",
+            self.base_url,
+            urlencoding::encode(from_date),
+            urlencoding::encode(to_date)
+        );
+        if let Some(p) = page {
+            url = format!("{}&page={}", url, p);
+        }
+        if let Some(ps) = page_size {
+            url = format!("{}&page_size={}", url, ps);
+        }
+        let response = self.client.get(&url).send()?;
+        if !response.status().is_success() {
+            return Err(TandoorError::ApiError {
+                status: response.status().as_u16(),
+                message: response.text().unwrap_or_default(),
+            });
+        }
+        response
+            .json()
+            .map_err(|e| TandoorError::ParseError(e.to_string()))
+    }
+
+    /// Every meal plan overlapping a date range (dates as YYYY-MM-DD, inclusive)
+    pub fn list_all_meal_plans_by_date(
+        &self,
+        from_date: &str,
+        to_date: &str,
+    ) -> Result<Vec<MealPlan>, TandoorError> {
+        all_pages(|page| {
+            self.list_meal_plans_by_date(from_date, to_date, Some(page), Some(PAGE_SIZE))
+                .map(PaginatedResponse::from)
+        })
+    }
+
+    /// Every meal plan overlapping a date range, plus the full recipe (with
+    /// steps and nutrition) of each planned recipe, fetched once per recipe
+    pub fn get_meal_plans_with_recipes(
+        &self,
+        from_date: &str,
+        to_date: &str,
+    ) -> Result<MealPlansWithRecipes, TandoorError> {
+        let plans = self.list_all_meal_plans_by_date(from_date, to_date)?;
+        let mut recipes = HashMap::new();
+        for recipe_id in plans
+            .iter()
+            .filter_map(|plan| plan.recipe.get("id").and_then(serde_json::Value::as_i64))
+        {
+            if let Entry::Vacant(slot) = recipes.entry(recipe_id) {
+                slot.insert(self.get_recipe(recipe_id)?);
+            }
+        }
+        Ok(MealPlansWithRecipes { plans, recipes })
+    }
+
     /// Get a single meal plan by ID
     pub fn get_meal_plan(&self, id: i64) -> Result<MealPlan, TandoorError> {
         let url = format!("{}/api/meal-plan/{}/", self.base_url, id);
@@ -1944,18 +2060,7 @@ impl TandoorClient {
         file.read_to_end(&mut buffer)
             .map_err(|e| TandoorError::FileError(format!("Failed to read file: {}", e)))?;
 
-        // Detect MIME type from extension
-        let mime_type = match path.extension().and_then(|e| e.to_str()) {
-            Some("jpg" | "jpeg") => "image/jpeg",
-            Some("png") => "image/png",
-            Some("gif") => "image/gif",
-            Some("webp") => "image/webp",
-            Some("pdf") => "application/pdf",
-            _ => "application/octet-stream",
-        }
-        .to_string();
-
-        Ok((buffer, file_name, mime_type))
+        Ok((buffer, file_name, mime_type(path).to_string()))
     }
 
     /// Upload an image for a recipe
@@ -1966,18 +2071,29 @@ impl TandoorClient {
         &self,
         recipe_id: i64,
         image_path: &str,
+    ) -> Result<RecipeImage, TandoorError> {
+        // Read file and detect MIME type using helper
+        let (buffer, file_name, _) = self.read_file_with_mime(image_path)?;
+        self.upload_recipe_image_bytes(recipe_id, &file_name, buffer)
+    }
+
+    /// Upload an image for a recipe from memory
+    ///
+    /// The MIME type is taken from the extension of `file_name`.
+    pub fn upload_recipe_image_bytes(
+        &self,
+        recipe_id: i64,
+        file_name: &str,
+        image: Vec<u8>,
     ) -> Result<RecipeImage, TandoorError> {
         use reqwest::blocking::multipart::{Form, Part};
 
         let url = format!("{}/api/recipe/{}/image/", self.base_url, recipe_id);
 
-        // Read file and detect MIME type using helper
-        let (buffer, file_name, mime_type) = self.read_file_with_mime(image_path)?;
-
         // Create multipart form with the image
-        let part = Part::bytes(buffer)
-            .file_name(file_name)
-            .mime_str(&mime_type)
+        let part = Part::bytes(image)
+            .file_name(file_name.to_string())
+            .mime_str(mime_type(std::path::Path::new(file_name)))
             .map_err(|e| TandoorError::ParseError(format!("Invalid MIME type: {}", e)))?;
 
         let form = Form::new().part("image", part);
@@ -2267,6 +2383,117 @@ impl TandoorClient {
         Ok(())
     }
 
+    // ========================================================================
+    // Raw Resource Methods (backup and restore)
+    // ========================================================================
+
+    /// List any API resource as raw JSON, e.g. `unit-conversion`
+    ///
+    /// Returns the response as sent: a paginated object, or a plain array
+    /// for endpoints that don't paginate.
+    pub fn list_resource(
+        &self,
+        resource: &str,
+        page: Option<u32>,
+        page_size: Option<u32>,
+    ) -> Result<serde_json::Value, TandoorError> {
+        let mut url = format!("{}/api/{}/", self.base_url, resource);
+        let mut params = Vec::new();
+        if let Some(p) = page {
+            params.push(format!("page={}", p));
+        }
+        if let Some(ps) = page_size {
+            params.push(format!("page_size={}", ps));
+        }
+        if !params.is_empty() {
+            url = format!("{}?{}", url, params.join("&"));
+        }
+        let response = self.client.get(&url).send()?;
+        if response.status().as_u16() == 401 || response.status().as_u16() == 403 {
+            return Err(TandoorError::AuthError(response.text().unwrap_or_default()));
+        }
+        if !response.status().is_success() {
+            return Err(TandoorError::ApiError {
+                status: response.status().as_u16(),
+                message: response.text().unwrap_or_default(),
+            });
+        }
+        response
+            .json()
+            .map_err(|e| TandoorError::ParseError(e.to_string()))
+    }
+
+    /// Create any API resource from raw JSON
+    pub fn create_resource(
+        &self,
+        resource: &str,
+        request: &serde_json::Value,
+    ) -> Result<serde_json::Value, TandoorError> {
+        let url = format!("{}/api/{}/", self.base_url, resource);
+        let response = self.post_request(&url, request)?;
+        if !response.status().is_success() {
+            return Err(TandoorError::ApiError {
+                status: response.status().as_u16(),
+                message: response.text().unwrap_or_default(),
+            });
+        }
+        response
+            .json()
+            .map_err(|e| TandoorError::ParseError(e.to_string()))
+    }
+
+    /// Partially update any API resource from raw JSON
+    pub fn update_resource(
+        &self,
+        resource: &str,
+        id: i64,
+        request: &serde_json::Value,
+    ) -> Result<serde_json::Value, TandoorError> {
+        let url = format!("{}/api/{}/{}/", self.base_url, resource, id);
+        let response = self.patch_request(&url, request)?;
+        if !response.status().is_success() {
+            return Err(TandoorError::ApiError {
+                status: response.status().as_u16(),
+                message: response.text().unwrap_or_default(),
+            });
+        }
+        response
+            .json()
+            .map_err(|e| TandoorError::ParseError(e.to_string()))
+    }
+
+    /// Download a media file, such as a recipe image
+    ///
+    /// `url` is absolute, or a path on the Tandoor server (`/media/...`).
+    /// Media on another host (S3, a CDN) is fetched without the API token
+    /// or Tandoor's `Host` header.
+    pub fn download_media(&self, url: &str) -> Result<Vec<u8>, TandoorError> {
+        let request = if !(url.starts_with("http://") || url.starts_with("https://")) {
+            self.client
+                .get(format!("{}/{}", self.base_url, url.trim_start_matches('/')))
+        } else if self.is_own_url(url) {
+            self.client.get(url)
+        } else {
+            Client::builder().build()?.get(url)
+        };
+        let response = request.timeout(std::time::Duration::from_secs(60)).send()?;
+        if !response.status().is_success() {
+            return Err(TandoorError::ApiError {
+                status: response.status().as_u16(),
+                message: response.text().unwrap_or_default(),
+            });
+        }
+        Ok(response.bytes()?.to_vec())
+    }
+
+    /// Whether an absolute URL has the same origin as `base_url`
+    fn is_own_url(&self, url: &str) -> bool {
+        match (url::Url::parse(url), url::Url::parse(&self.base_url)) {
+            (Ok(url), Ok(base)) => url.origin() == base.origin(),
+            _ => false,
+        }
+    }
+
     /// Get the API token (helper for multipart requests that need a fresh client)
     fn get_token(&self) -> String {
         self.headers
@@ -2277,6 +2504,34 @@ impl TandoorClient {
     }
 }
 
+/// Collect every page of a paginated listing
+pub fn all_pages<T>(
+    mut fetch: impl FnMut(u32) -> Result<PaginatedResponse<T>, TandoorError>,
+) -> Result<Vec<T>, TandoorError> {
+    let mut items = Vec::new();
+    for page in 1.. {
+        let response = fetch(page)?;
+        let done = response.next.is_none() || response.results.is_empty();
+        items.extend(response.results);
+        if done {
+            break;
+        }
+    }
+    Ok(items)
+}
+
+/// MIME type of a file, from its extension
+fn mime_type(path: &std::path::Path) -> &'static str {
+    match path.extension().and_then(|e| e.to_str()) {
+        Some("jpg" | "jpeg") => "image/jpeg",
+        Some("png") => "image/png",
+        Some("gif") => "image/gif",
+        Some("webp") => "image/webp",
+        Some("pdf") => "application/pdf",
+        _ => "application/octet-stream",
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -0,0 +1,943 @@
+//! Ingredient line parsing (FUNCTIONAL CORE - PURE)
+//!
+//! [`parse_ingredient`] splits a free-text line like `1 ½ cups flour, sifted`
+//! into amount, unit, food and note without a round trip to Tandoor's
+//! parser ([`TandoorClient::ingredient_from_string`](super::TandoorClient::ingredient_from_string)):
+//!
+//! - Amounts: `2`, `0.5`, `1,5`, `1/2`, `1 1/2`, `1½`, ranges (`2-3`,
+//!   `2 to 3`), and `a`/`an` before a unit
+//! - Units: metric and imperial (US customary) measures plus counts like
+//!   `clove` or `can`, by name, plural or abbreviation (`Tbsp.`, `T`,
+//!   `fl oz`), also when glued to the amount (`250g`)
+//! - Notes: parentheses anywhere (`(finely chopped)`), text after the first
+//!   comma (`, divided`) and an alternative measure (`200 g / 7 oz`)
+//!
+//! The result converts to a [`ParsedIngredient`] or a
+//! [`CreateIngredientRequest`] and carries a confidence between 0 and 1, so
+//! callers can send doubtful lines to the server instead.
+
+use super::nutrition::convert_to_grams;
+use super::{
+    CreateFoodRequest, CreateIngredientRequest, CreateUnitRequest, ParsedIngredient,
+    SourceImportFood, SourceImportIngredient, SourceImportUnit,
+};
+use serde::Serialize;
+use serde_json::json;
+
+/// Measurement system of a unit
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum UnitSystem {
+    Metric,
+    /// Imperial and US customary measures (cups are US cups)
+    Imperial,
+}
+
+/// What a unit measures
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Dimension {
+    /// Converts to grams
+    Mass,
+    /// Converts to millilitres
+    Volume,
+    /// Pinches, cloves, cans: no fixed size
+    Count,
+}
+
+/// A known unit
+#[derive(Debug, PartialEq)]
+pub struct Unit {
+    /// Singular name (`tablespoon`)
+    pub name: &'static str,
+    pub plural_name: &'static str,
+    /// Abbreviations and other spellings, lowercase (`tbsp`, `tbs`)
+    pub aliases: &'static [&'static str],
+    /// None for counts
+    pub system: Option<UnitSystem>,
+    pub dimension: Dimension,
+    /// Grams (mass) or millilitres (volume) in one unit; 0 for counts
+    pub size: f64,
+}
+
+const fn metric(
+    name: &'static str,
+    plural_name: &'static str,
+    aliases: &'static [&'static str],
+    dimension: Dimension,
+    size: f64,
+) -> Unit {
+    Unit {
+        name,
+        plural_name,
+        aliases,
+        system: Some(UnitSystem::Metric),
+        dimension,
+        size,
+    }
+}
+
+const fn imperial(
+    name: &'static str,
+    plural_name: &'static str,
+    aliases: &'static [&'static str],
+    dimension: Dimension,
+    size: f64,
+) -> Unit {
+    Unit {
+        name,
+        plural_name,
+        aliases,
+        system: Some(UnitSystem::Imperial),
+        dimension,
+        size,
+    }
+}
+
+const fn count(
+    name: &'static str,
+    plural_name: &'static str,
+    aliases: &'static [&'static str],
+) -> Unit {
+    Unit {
+        name,
+        plural_name,
+        aliases,
+        system: None,
+        dimension: Dimension::Count,
+        size: 0.0,
+    }
+}
+
+/// Units recognized after an amount
+pub static UNITS: &[Unit] = &[
+    metric(
+        "milligram",
+        "milligrams",
+        &["mg", "mgs"],
+        Dimension::Mass,
+        0.001,
+    ),
+    metric(
+        "gram",
+        "grams",
+        &["g", "gr", "grs", "gramme", "grammes"],
+        Dimension::Mass,
+        1.0,
+    ),
+    metric(
+        "kilogram",
+        "kilograms",
+        &["kg", "kgs", "kilo", "kilos"],
+        Dimension::Mass,
+        1000.0,
+    ),
+    metric(
+        "milliliter",
+        "milliliters",
+        &["ml", "mls", "millilitre", "millilitres"],
+        Dimension::Volume,
+        1.0,
+    ),
+    metric(
+        "centiliter",
+        "centiliters",
+        &["cl", "centilitre", "centilitres"],
+        Dimension::Volume,
+        10.0,
+    ),
+    metric(
+        "deciliter",
+        "deciliters",
+        &["dl", "decilitre", "decilitres"],
+        Dimension::Volume,
+        100.0,
+    ),
+    metric(
+        "liter",
+        "liters",
+        &["l", "lt", "ltr", "litre", "litres"],
+        Dimension::Volume,
+        1000.0,
+    ),
+    imperial("ounce", "ounces", &["oz"], Dimension::Mass, 28.3495),
+    imperial("pound", "pounds", &["lb", "lbs"], Dimension::Mass, 453.592),
+    imperial(
+        "teaspoon",
+        "teaspoons",
+        &["t", "tsp", "tsps", "tspn"],
+        Dimension::Volume,
+        4.928_92,
+    ),
+    imperial(
+        "tablespoon",
+        "tablespoons",
+        &["tb", "tbl", "tbls", "tbs", "tbsp", "tbsps"],
+        Dimension::Volume,
+        14.7868,
+    ),
+    imperial(
+        "fluid ounce",
+        "fluid ounces",
+        &["fl oz", "floz", "fluid oz"],
+        Dimension::Volume,
+        29.5735,
+    ),
+    imperial("cup", "cups", &["c"], Dimension::Volume, 236.588),
+    imperial("pint", "pints", &["pt", "pts"], Dimension::Volume, 473.176),
+    imperial(
+        "quart",
+        "quarts",
+        &["qt", "qts"],
+        Dimension::Volume,
+        946.353,
+    ),
+    imperial(
+        "gallon",
+        "gallons",
+        &["gal", "gals"],
+        Dimension::Volume,
+        3785.41,
+    ),
+    count("bag", "bags", &[]),
+    count("bottle", "bottles", &[]),
+    count("bunch", "bunches", &[]),
+    count("can", "cans", &["tin", "tins"]),
+    count("clove", "cloves", &[]),
+    count("dash", "dashes", &[]),
+    count("drop", "drops", &[]),
+    count("handful", "handfuls", &[]),
+    count("head", "heads", &[]),
+    count("jar", "jars", &[]),
+    count("package", "packages", &["pkg", "pkgs", "packet", "packets"]),
+    count("piece", "pieces", &["pc", "pcs"]),
+    count("pinch", "pinches", &[]),
+    count("slice", "slices", &[]),
+    count("sprig", "sprigs", &[]),
+    count("stalk", "stalks", &[]),
+    count("stick", "sticks", &[]),
+    count("whole", "whole", &[]),
+];
+
+/// Look up a unit by name, plural or abbreviation, ignoring case and periods
+///
+/// The one case-sensitive spelling is the cook's shorthand `T` for
+/// tablespoon (`t` is teaspoon).
+pub fn find_unit(text: &str) -> Option<&'static Unit> {
+    let words: Vec<&str> = text
+        .split_whitespace()
+        .map(|word| word.trim_end_matches('.'))
+        .collect();
+    let text = words.join(" ");
+    if text == "T" {
+        return UNITS.iter().find(|unit| unit.name == "tablespoon");
+    }
+    let text = text.to_lowercase();
+    UNITS.iter().find(|unit| {
+        unit.name == text || unit.plural_name == text || unit.aliases.contains(&text.as_str())
+    })
+}
+
+/// A unit as written, and the unit it stands for
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct ParsedUnit {
+    /// As written, without periods (`Tbsp`)
+    pub name: String,
+    #[serde(skip)]
+    pub unit: &'static Unit,
+}
+
+/// A food name
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct ParsedFood {
+    pub name: String,
+}
+
+/// A parsed ingredient line
+///
+/// Serializes like Tandoor's [`ParsedIngredient`], plus `amount_max` for
+/// ranges and `confidence`.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct IngredientLine {
+    /// Amount, or the low end of a range
+    pub amount: Option<f64>,
+    /// High end of a range (`2-3`)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub amount_max: Option<f64>,
+    pub unit: Option<ParsedUnit>,
+    pub food: Option<ParsedFood>,
+    pub note: Option<String>,
+    pub original_text: String,
+    /// How sure the parser is, from 0 (no food found) to 1
+    pub confidence: f64,
+}
+
+impl IngredientLine {
+    /// Food name, empty if none was found
+    pub fn food_name(&self) -> &str {
+        self.food.as_ref().map_or("", |food| food.name.as_str())
+    }
+
+    /// Amount in grams (mass units) or millilitres (volume units)
+    pub fn base_amount(&self) -> Option<f64> {
+        let unit = self.unit.as_ref()?.unit;
+        (unit.dimension != Dimension::Count).then_some(self.amount? * unit.size)
+    }
+
+    /// Amount in grams; volumes are converted with [`convert_to_grams`]
+    pub fn grams(&self) -> Option<f64> {
+        let base = self.base_amount()?;
+        match self.unit.as_ref()?.unit.dimension {
+            Dimension::Mass => Some(base),
+            Dimension::Volume => Some(convert_to_grams(base, "ml", self.food_name())),
+            Dimension::Count => None,
+        }
+    }
+
+    /// Ingredient for a recipe request, keeping the unit as written
+    pub fn to_create_request(&self) -> CreateIngredientRequest {
+        CreateIngredientRequest {
+            amount: self.amount,
+            food: CreateFoodRequest {
+                name: self.food_name().to_string(),
+            },
+            unit: self.unit.as_ref().map(|unit| CreateUnitRequest {
+                name: unit.name.clone(),
+            }),
+            note: self.note.clone(),
+        }
+    }
+
+    /// Ingredient in Tandoor's import shape, keeping the unit as written;
+    /// a line without a recognizable food keeps its text as the food name
+    pub fn to_source_ingredient(&self) -> SourceImportIngredient {
+        let food = Some(self.food_name())
+            .filter(|name| !name.is_empty())
+            .unwrap_or(&self.original_text);
+        SourceImportIngredient {
+            amount: self.amount,
+            food: Some(SourceImportFood {
+                name: food.to_string(),
+            }),
+            unit: self.unit.as_ref().map(|unit| SourceImportUnit {
+                name: unit.name.clone(),
+            }),
+            note: self.note.clone().unwrap_or_default(),
+            original_text: self.original_text.clone(),
+        }
+    }
+}
+
+impl From<IngredientLine> for ParsedIngredient {
+    fn from(line: IngredientLine) -> Self {
+        Self {
+            amount: line.amount,
+            unit: line.unit.map(|unit| json!({ "name": unit.name })),
+            food: line.food.map(|food| json!({ "name": food.name })),
+            note: line.note,
+            original_text: Some(line.original_text),
+        }
+    }
+}
+
+/// Leading amount of a line: a number, range, or `a`/`an`
+#[derive(Debug, Clone, Copy)]
+struct Amount {
+    low: f64,
+    high: Option<f64>,
+    /// Tokens read
+    len: usize,
+    /// `a` or `an` rather than a number
+    article: bool,
+}
+
+/// Parse an ingredient line
+///
+/// Never fails: a line it can't make sense of is all food, with a lower
+/// [`IngredientLine::confidence`].
+pub fn parse_ingredient(text: &str) -> IngredientLine {
+    let original_text = text.trim().to_string();
+    let normalized = normalize(&original_text);
+    let (rest, mut notes, balanced) = take_parentheses(&normalized);
+    let (head, comma_note) = split_comma(&rest);
+    let tokens = tokenize(head);
+
+    let amount = amount(&tokens)
+        .filter(|amount| !amount.article || unit_after(&tokens, amount.len).is_some());
+    let mut position = amount.map_or(0, |amount| amount.len);
+    let unit = amount.and_then(|_| unit_after(&tokens, position));
+    if let Some((_, len)) = &unit {
+        position += len;
+        if let Some((alternative, len)) = alternative_measure(&tokens, position) {
+            notes.insert(0, alternative);
+            position += len;
+        }
+        if tokens
+            .get(position)
+            .is_some_and(|word| word.eq_ignore_ascii_case("of"))
+            && tokens.len() > position + 1
+        {
+            position += 1;
+        }
+    }
+    notes.extend(comma_note);
+
+    let food = tokens.get(position..).unwrap_or_default().join(" ");
+    let mut line = IngredientLine {
+        amount: amount.map(|amount| amount.low),
+        amount_max: amount.and_then(|amount| amount.high),
+        unit: unit.map(|(unit, _)| unit),
+        food: Some(ParsedFood { name: food }).filter(|food| !food.name.is_empty()),
+        note: Some(notes.join(", ")).filter(|note| !note.is_empty()),
+        original_text,
+        confidence: 1.0,
+    };
+    line.confidence = confidence(&line, amount.is_some_and(|amount| amount.article), balanced);
+    line
+}
+
+/// How sure we are of a parse, from 0 to 1
+fn confidence(line: &IngredientLine, article: bool, balanced: bool) -> f64 {
+    let food = line.food_name();
+    if food.is_empty() {
+        return 0.0;
+    }
+    let ambiguous_unit = line
+        .unit
+        .as_ref()
+        .is_some_and(|unit| unit.name.chars().count() == 1);
+    let factors = [
+        (line.amount.is_none(), 0.7),
+        (line.amount.is_some() && line.unit.is_none(), 0.9),
+        (article, 0.9),
+        (ambiguous_unit, 0.9),
+        // Numbers left in the food are measures we didn't understand
+        (food.contains(|c: char| c.is_ascii_digit()), 0.6),
+        (!balanced, 0.8),
+    ];
+    let confidence: f64 = factors
+        .iter()
+        .filter(|(applies, _)| *applies)
+        .map(|(_, factor)| factor)
+        .product();
+    (confidence * 100.0).round() / 100.0
+}
+
+/// Spell out unicode fractions (`1½` is `1 1/2`) and dashes
+fn normalize(text: &str) -> String {
+    let mut normalized = String::with_capacity(text.len());
+    for c in text.chars() {
+        if let Some(fraction) = vulgar_fraction(c) {
+            normalized.push(' ');
+            normalized.push_str(fraction);
+            normalized.push(' ');
+        } else {
+            normalized.push(match c {
+                '–' | '—' | '‒' | '−' => '-',
+                '⁄' => '/',
+                _ => c,
+            });
+        }
+    }
+    normalized
+}
+
+fn vulgar_fraction(c: char) -> Option<&'static str> {
+    let fraction = match c {
+        '½' => "1/2",
+        '⅓' => "1/3",
+        '⅔' => "2/3",
+        '¼' => "1/4",
+        '¾' => "3/4",
+        '⅕' => "1/5",
+        '⅖' => "2/5",
+        '⅗' => "3/5",
+        '⅘' => "4/5",
+        '⅙' => "1/6",
+        '⅚' => "5/6",
+        '⅛' => "1/8",
+        '⅜' => "3/8",
+        '⅝' => "5/8",
+        '⅞' => "7/8",
+        _ => return None,
+    };
+    Some(fraction)
+}
+
+/// Remove parenthesized text, returning the rest, the parenthesized notes
+/// and whether the parentheses balanced
+fn take_parentheses(text: &str) -> (String, Vec<String>, bool) {
+    let mut rest = String::with_capacity(text.len());
+    let mut notes = Vec::new();
+    let mut note = String::new();
+    let mut depth = 0_usize;
+    let mut balanced = true;
+    for c in text.chars() {
+        match (c, depth) {
+            ('(', _) => {
+                if depth > 0 {
+                    note.push(c);
+                }
+                depth += 1;
+            }
+            (')', 0) => balanced = false,
+            (')', 1) => {
+                depth = 0;
+                notes.push(std::mem::take(&mut note));
+                rest.push(' ');
+            }
+            (')', _) => {
+                depth -= 1;
+                note.push(c);
+            }
+            (_, 0) => rest.push(c),
+            (_, _) => note.push(c),
+        }
+    }
+    if depth > 0 {
+        balanced = false;
+        notes.push(note);
+    }
+    let notes = notes
+        .iter()
+        .map(|note| note.trim().to_string())
+        .filter(|note| !note.is_empty())
+        .collect();
+    (rest, notes, balanced)
+}
+
+/// Split at the first comma that isn't a decimal comma (`1,5 kg`)
+fn split_comma(text: &str) -> (&str, Option<String>) {
+    let chars: Vec<char> = text.chars().collect();
+    let decimal = |index: usize| {
+        let digit = |i: Option<usize>| {
+            i.and_then(|i| chars.get(i))
+                .is_some_and(char::is_ascii_digit)
+        };
+        digit(index.checked_sub(1)) && digit(Some(index + 1))
+    };
+    let comma = text
+        .char_indices()
+        .enumerate()
+        .find(|(index, (_, c))| *c == ',' && !decimal(*index))
+        .map(|(_, (byte, _))| byte);
+
+    comma.map_or_else(
+        || (text.trim(), None),
+        |byte| {
+            let (head, note) = text.split_at(byte);
+            let note = note.trim_start_matches(',').trim();
+            (
+                head.trim(),
+                Some(note.to_string()).filter(|n| !n.is_empty()),
+            )
+        },
+    )
+}
+
+/// Words of a line, with glued forms pulled apart: `200g/7oz` is
+/// `200 g / 7 oz` and `2-3` is `2 - 3`
+fn tokenize(text: &str) -> Vec<String> {
+    let mut tokens = Vec::new();
+    for word in text.split_whitespace() {
+        for piece in split_alternative(word) {
+            let (number, unit) = split_glued_unit(piece);
+            tokens.extend(split_range(number));
+            tokens.extend(unit.map(str::to_string));
+        }
+    }
+    tokens
+}
+
+/// Split a word at slashes that aren't fraction bars
+fn split_alternative(word: &str) -> Vec<&str> {
+    let mut pieces = Vec::new();
+    let mut start = 0;
+    let bytes = word.as_bytes();
+    for (index, byte) in bytes.iter().enumerate() {
+        let fraction = index
+            .checked_sub(1)
+            .and_then(|i| bytes.get(i))
+            .is_some_and(u8::is_ascii_digit)
+            && bytes.get(index + 1).is_some_and(u8::is_ascii_digit);
+        if *byte == b'/' && !fraction {
+            pieces.extend(word.get(start..index).filter(|piece| !piece.is_empty()));
+            pieces.push("/");
+            start = index + 1;
+        }
+    }
+    pieces.extend(word.get(start..).filter(|piece| !piece.is_empty()));
+    pieces
+}
+
+/// `250g` is `250` and `g`, when the letters are a unit
+fn split_glued_unit(word: &str) -> (&str, Option<&str>) {
+    let split = word
+        .find(|c: char| c.is_alphabetic())
+        .filter(|&index| index > 0)
+        .map(|index| word.split_at(index))
+        .filter(|(number, unit)| {
+            number.starts_with(|c: char| c.is_ascii_digit()) && find_unit(unit).is_some()
+        });
+    split.map_or((word, None), |(number, unit)| (number, Some(unit)))
+}
+
+/// `2-3` is `2`, `-`, `3`
+fn split_range(word: &str) -> Vec<String> {
+    if let Some((low, high)) = word
+        .split_once('-')
+        .filter(|(low, high)| number(low).is_some() && number(high).is_some())
+    {
+        vec![low.to_string(), "-".to_string(), high.to_string()]
+    } else {
+        vec![word.to_string()]
+    }
+}
+
+/// A number: `2`, `0.5`, `1,5` or `1/2`
+fn number(token: &str) -> Option<f64> {
+    if !token.starts_with(|c: char| c.is_ascii_digit() || c == '.') {
+        return None;
+    }
+    let value = match token.split_once('/') {
+        Some((numerator, denominator)) => {
+            let denominator: f64 = denominator.parse().ok()?;
+            (denominator != 0.0).then_some(numerator.parse::<f64>().ok()? / denominator)?
+        }
+        None => token.replace(',', ".").parse().ok()?,
+    };
+    (value.is_finite() && value >= 0.0).then_some(value)
+}
+
+/// A number at `position`, with a following fraction (`1 1/2`)
+fn quantity(tokens: &[String], position: usize) -> Option<(f64, usize)> {
+    let whole_token = tokens.get(position)?;
+    let whole = number(whole_token)?;
+    let fraction = tokens
+        .get(position + 1)
+        .filter(|_| whole_token.chars().all(|c| c.is_ascii_digit()))
+        .filter(|token| token.contains('/'))
+        .and_then(|token| number(token))
+        .filter(|fraction| *fraction < 1.0);
+    Some(fraction.map_or((whole, 1), |fraction| (whole + fraction, 2)))
+}
+
+/// The amount at the start of `tokens`
+fn amount(tokens: &[String]) -> Option<Amount> {
+    if tokens
+        .first()
+        .is_some_and(|word| word.eq_ignore_ascii_case("a") || word.eq_ignore_ascii_case("an"))
+    {
+        return Some(Amount {
+            low: 1.0,
+            high: None,
+            len: 1,
+            article: true,
+        });
+    }
+
+    let (low, len) = quantity(tokens, 0)?;
+    let range = tokens
+        .get(len)
+        .filter(|word| *word == "-" || word.eq_ignore_ascii_case("to"))
+        .and_then(|_| quantity(tokens, len + 1))
+        .filter(|(high, _)| *high > low);
+    Some(Amount {
+        low,
+        high: range.map(|(high, _)| high),
+        len: len + range.map_or(0, |(_, range_len)| range_len + 1),
+        article: false,
+    })
+}
+
+/// A unit at `position`, if words follow it (`2 cloves` is 2 of the food
+/// "cloves")
+fn unit_after(tokens: &[String], position: usize) -> Option<(ParsedUnit, usize)> {
+    (1..=2).rev().find_map(|len| {
+        let words = tokens.get(position..position + len)?;
+        if tokens.len() <= position + len {
+            return None;
+        }
+        let name = words
+            .iter()
+            .map(|word| word.trim_end_matches('.'))
+            .collect::<Vec<_>>()
+            .join(" ");
+        find_unit(&name).map(|unit| (ParsedUnit { name, unit }, len))
+    })
+}
+
+/// An alternative measure after the unit (`/ 7 oz`), as text
+fn alternative_measure(tokens: &[String], position: usize) -> Option<(String, usize)> {
+    if tokens.get(position).map(String::as_str) != Some("/") {
+        return None;
+    }
+    let rest = tokens.get(position + 1..)?;
+    let amount = amount(rest).filter(|amount| !amount.article)?;
+    let (_, unit_len) = unit_after(rest, amount.len)?;
+    let len = amount.len + unit_len;
+    let text = rest.get(..len)?.join(" ");
+    Some((text, len + 1))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Line, amount, amount_max
+    type AmountCase<'a> = (&'a str, Option<f64>, Option<f64>);
+
+    const AMOUNTS: &[AmountCase] = &[
+        ("2 eggs", Some(2.0), None),
+        ("0.5 cup milk", Some(0.5), None),
+        (".5 cup milk", Some(0.5), None),
+        ("1,5 kg flour", Some(1.5), None),
+        ("1/2 tsp salt", Some(0.5), None),
+        ("1 1/2 cups flour", Some(1.5), None),
+        ("1½ cups flour", Some(1.5), None),
+        ("1 ½ cups flour", Some(1.5), None),
+        ("½ cup sugar", Some(0.5), None),
+        ("¾ cup sugar", Some(0.75), None),
+        ("1⁄3 cup oil", Some(1.0 / 3.0), None),
+        ("2-3 cloves garlic", Some(2.0), Some(3.0)),
+        ("2 - 3 cloves garlic", Some(2.0), Some(3.0)),
+        ("2–3 cloves garlic", Some(2.0), Some(3.0)),
+        ("2 to 3 cloves garlic", Some(2.0), Some(3.0)),
+        ("1/2-1 tsp chili flakes", Some(0.5), Some(1.0)),
+        ("¼-½ tsp cayenne", Some(0.25), Some(0.5)),
+        ("1 1/2 - 2 lb chicken", Some(1.5), Some(2.0)),
+        ("a pinch of salt", Some(1.0), None),
+        ("An 8 oz block cream cheese", None, None),
+        ("salt to taste", None, None),
+        ("1/0 cup water", None, None),
+    ];
+
+    #[test]
+    fn test_amounts() {
+        for (line, amount, amount_max) in AMOUNTS {
+            let parsed = parse_ingredient(line);
+            assert_eq!(parsed.amount, *amount, "{line}");
+            assert_eq!(parsed.amount_max, *amount_max, "{line}");
+        }
+    }
+
+    /// Line, unit as written, unit name, system
+    const UNIT_CASES: &[(&str, &str, &str, Option<UnitSystem>)] = &[
+        (
+            "2 Tbsp. olive oil",
+            "Tbsp",
+            "tablespoon",
+            Some(UnitSystem::Imperial),
+        ),
+        ("1 T sugar", "T", "tablespoon", Some(UnitSystem::Imperial)),
+        ("1 t salt", "t", "teaspoon", Some(UnitSystem::Imperial)),
+        (
+            "2 tsps vanilla",
+            "tsps",
+            "teaspoon",
+            Some(UnitSystem::Imperial),
+        ),
+        ("3 c flour", "c", "cup", Some(UnitSystem::Imperial)),
+        ("2 Cups flour", "Cups", "cup", Some(UnitSystem::Imperial)),
+        (
+            "1 fl. oz. rum",
+            "fl oz",
+            "fluid ounce",
+            Some(UnitSystem::Imperial),
+        ),
+        (
+            "2 fluid ounces cream",
+            "fluid ounces",
+            "fluid ounce",
+            Some(UnitSystem::Imperial),
+        ),
+        (
+            "3 lbs chicken thighs",
+            "lbs",
+            "pound",
+            Some(UnitSystem::Imperial),
+        ),
+        ("4 oz cheddar", "oz", "ounce", Some(UnitSystem::Imperial)),
+        ("1 qt stock", "qt", "quart", Some(UnitSystem::Imperial)),
+        ("250g butter", "g", "gram", Some(UnitSystem::Metric)),
+        ("1.5kg potatoes", "kg", "kilogram", Some(UnitSystem::Metric)),
+        ("100 ml cream", "ml", "milliliter", Some(UnitSystem::Metric)),
+        (
+            "2 litres stock",
+            "litres",
+            "liter",
+            Some(UnitSystem::Metric),
+        ),
+        ("1 L water", "L", "liter", Some(UnitSystem::Metric)),
+        ("5 dl milk", "dl", "deciliter", Some(UnitSystem::Metric)),
+        (
+            "2 grammes saffron",
+            "grammes",
+            "gram",
+            Some(UnitSystem::Metric),
+        ),
+        ("2 pinches salt", "pinches", "pinch", None),
+        ("3 cloves garlic", "cloves", "clove", None),
+        ("1 (14 oz) can tomatoes", "can", "can", None),
+        ("2 tins chickpeas", "tins", "can", None),
+        ("1 bunch cilantro", "bunch", "bunch", None),
+        ("2 cups of flour", "cups", "cup", Some(UnitSystem::Imperial)),
+    ];
+
+    #[test]
+    fn test_units() {
+        for (line, written, name, system) in UNIT_CASES {
+            let parsed = parse_ingredient(line);
+            assert_eq!(
+                parsed
+                    .unit
+                    .map(|unit| (unit.name, unit.unit.name, unit.unit.system)),
+                Some(((*written).to_string(), *name, *system)),
+                "{line}"
+            );
+        }
+    }
+
+    /// Line, unit as written, food, note
+    type FoodCase<'a> = (&'a str, Option<&'a str>, &'a str, Option<&'a str>);
+
+    const FOODS: &[FoodCase] = &[
+        (
+            "1 onion, finely chopped",
+            None,
+            "onion",
+            Some("finely chopped"),
+        ),
+        (
+            "1 onion (finely chopped)",
+            None,
+            "onion",
+            Some("finely chopped"),
+        ),
+        (
+            "2 cups flour (sifted), divided",
+            Some("cups"),
+            "flour",
+            Some("sifted, divided"),
+        ),
+        ("1 cup (240 ml) milk", Some("cup"), "milk", Some("240 ml")),
+        (
+            "1 (14 oz) can tomatoes",
+            Some("can"),
+            "tomatoes",
+            Some("14 oz"),
+        ),
+        ("200 g / 7 oz butter", Some("g"), "butter", Some("7 oz")),
+        (
+            "200g/7oz butter, softened",
+            Some("g"),
+            "butter",
+            Some("7 oz, softened"),
+        ),
+        ("1,5 kg flour", Some("kg"), "flour", None),
+        (
+            "Salt and pepper, to taste",
+            None,
+            "Salt and pepper",
+            Some("to taste"),
+        ),
+        ("3 large eggs, beaten", None, "large eggs", Some("beaten")),
+        ("2 cloves", None, "cloves", None),
+        ("a pinch of salt", Some("pinch"), "salt", None),
+        ("an onion", None, "an onion", None),
+        ("cup of tea", None, "cup of tea", None),
+        ("  1 tsp cumin  ", Some("tsp"), "cumin", None),
+    ];
+
+    #[test]
+    fn test_food_and_note() {
+        for (line, unit, food, note) in FOODS {
+            let parsed = parse_ingredient(line);
+            assert_eq!(
+                parsed.unit.as_ref().map(|u| u.name.as_str()),
+                *unit,
+                "{line}"
+            );
+            assert_eq!(parsed.food_name(), *food, "{line}");
+            assert_eq!(parsed.note.as_deref(), *note, "{line}");
+            assert_eq!(parsed.original_text, line.trim());
+        }
+    }
+
+    /// Line, confidence
+    const CONFIDENCE: &[(&str, f64)] = &[
+        ("2 cups flour", 1.0),
+        ("2-3 cloves garlic, minced", 1.0),
+        ("2 eggs", 0.9),
+        ("a pinch of salt", 0.9),
+        ("1 t salt", 0.9),
+        ("salt", 0.7),
+        ("2 cups flour (sifted", 0.8),
+        ("1 x 400 g tin tomatoes", 0.54),
+        ("2", 0.0),
+        ("(optional)", 0.0),
+        ("", 0.0),
+    ];
+
+    #[test]
+    fn test_confidence() {
+        for (line, confidence) in CONFIDENCE {
+            let parsed = parse_ingredient(line);
+            assert!(
+                (parsed.confidence - confidence).abs() < 1e-9,
+                "{line}: {}",
+                parsed.confidence
+            );
+        }
+    }
+
+    #[test]
+    fn test_conversions() {
+        let grams = |line: &str| parse_ingredient(line).grams();
+        let close = |actual: Option<f64>, expected: f64| {
+            actual.is_some_and(|actual| (actual - expected).abs() < 0.01)
+        };
+        assert!(close(grams("250g butter"), 250.0));
+        assert!(close(grams("8 oz ground beef"), 226.796));
+        assert!(close(grams("1 lb pasta"), 453.592));
+        assert!(close(grams("2 cups water"), 473.176));
+        assert!(close(grams("1 l milk"), 1030.0));
+        assert!(close(parse_ingredient("2 tbsp oil").base_amount(), 29.5736));
+        assert_eq!(grams("3 cloves garlic"), None);
+        assert_eq!(grams("3 eggs"), None);
+    }
+
+    #[test]
+    fn test_units_table() {
+        for unit in UNITS {
+            assert_eq!(find_unit(unit.name), Some(unit));
+            assert_eq!(find_unit(unit.plural_name), Some(unit));
+            for alias in unit.aliases {
+                assert_eq!(find_unit(alias), Some(unit), "{alias}");
+            }
+            assert_eq!(unit.system.is_none(), unit.dimension == Dimension::Count);
+        }
+        assert_eq!(find_unit("Tbsp.").map(|u| u.name), Some("tablespoon"));
+        assert_eq!(find_unit("T").map(|u| u.name), Some("tablespoon"));
+        assert_eq!(find_unit("large"), None);
+    }
+
+    #[test]
+    fn test_to_parsed_ingredient() {
+        let line = parse_ingredient("1 1/2 cups flour, sifted");
+        assert_eq!(
+            serde_json::to_value(&line).unwrap(),
+            json!({
+                "amount": 1.5, "unit": {"name": "cups"}, "food": {"name": "flour"},
+                "note": "sifted", "original_text": "1 1/2 cups flour, sifted", "confidence": 1.0
+            })
+        );
+        let request = serde_json::to_value(line.to_create_request()).unwrap();
+        assert_eq!(request["unit"]["name"], "cups");
+
+        let parsed = ParsedIngredient::from(line);
+        assert_eq!(parsed.amount, Some(1.5));
+        assert_eq!(parsed.food, Some(json!({"name": "flour"})));
+        assert_eq!(parsed.note.as_deref(), Some("sifted"));
+    }
+}
@@ -68,13 +68,17 @@
 //!
 //! See [`ARCHITECTURE.md`](../../../docs/ARCHITECTURE.md) for design principles.
 
+pub mod calendar;
 mod client;
+pub mod ingredient;
 pub mod nutrition;
 pub mod shopping;
+pub mod text;
 mod types;
+pub mod weekly;
 
 #[cfg(test)]
 mod tests;
 
-pub use client::TandoorClient;
+pub use client::{all_pages, TandoorClient, TandoorError};
 pub use types::*;
@@ -11,6 +11,8 @@
 
 use serde_json::Value;
 
+use crate::tandoor::RecipeDetail;
+
 /// Ingredient nutrition data from FatSecret
 #[derive(Debug, Clone)]
 pub struct IngredientNutrition {
@@ -31,6 +33,25 @@ pub struct RecipeNutritionResult {
     pub failed_ingredients: Vec<String>,
 }
 
+/// The recipe's stored nutrition, if it has any
+pub fn stored_nutrition(recipe: &RecipeDetail) -> Option<RecipeNutritionResult> {
+    let stored = recipe.nutrition.as_ref()?;
+    if stored.calories.is_none()
+        && stored.proteins.is_none()
+        && stored.fats.is_none()
+        && stored.carbohydrates.is_none()
+    {
+        return None;
+    }
+    Some(RecipeNutritionResult {
+        calories: stored.calories.unwrap_or(0.0),
+        protein: stored.proteins.unwrap_or(0.0),
+        fat: stored.fats.unwrap_or(0.0),
+        carbohydrate: stored.carbohydrates.unwrap_or(0.0),
+        failed_ingredients: Vec::new(),
+    })
+}
+
 /// Calculate nutrition for a recipe from its ingredients
 ///
 /// # Arguments
@@ -0,0 +1,469 @@
+//! Supermarket aisle ordering for shopping lists (FUNCTIONAL CORE - PURE)
+//!
+//! Groups shopping list entries by supermarket category and orders the
+//! groups in the walking order configured for a Tandoor supermarket.
+//! Rendering to Markdown, plain text and printable HTML lives here too,
+//! so binaries only fetch data and print the result.
+//!
+//! Ordering rules:
+//! - Categories configured on the supermarket come first, in their `order`
+//! - Categories unknown to the supermarket follow, alphabetically
+//! - Foods without a category end up in a final "Other" section
+
+use crate::tandoor::types::{Food, ShoppingListEntry, Supermarket};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
+
+/// Section name for foods without a supermarket category
+pub const UNCATEGORIZED_SECTION: &str = "Other";
+
+/// Output format for a rendered shopping list
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Deserialize, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ShoppingListFormat {
+    /// GitHub-flavoured Markdown task list
+    #[default]
+    Markdown,
+    /// Plain text with `[ ]` checkboxes
+    Text,
+    /// Standalone printable HTML page
+    Html,
+}
+
+/// Category walking order for a single store
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct AisleLayout {
+    /// Store name (used as the list title)
+    pub supermarket_name: Option<String>,
+    /// Category names in walking order
+    pub categories: Vec<String>,
+}
+
+impl AisleLayout {
+    /// Build the walking order from a Tandoor supermarket
+    pub fn from_supermarket(supermarket: &Supermarket) -> Self {
+        let mut relations: Vec<_> = supermarket.category_to_supermarket.iter().collect();
+        relations.sort_by_key(|relation| relation.order);
+
+        Self {
+            supermarket_name: Some(supermarket.name.clone()),
+            categories: relations
+                .into_iter()
+                .map(|relation| relation.category.name.clone())
+                .collect(),
+        }
+    }
+
+    /// Position of a category in this store (None if the store doesn't list it)
+    fn position(&self, category: &str) -> Option<usize> {
+        self.categories
+            .iter()
+            .position(|c| c.eq_ignore_ascii_case(category))
+    }
+}
+
+/// A single line on the shopping list (entries for the same food and unit merged)
+#[derive(Debug, Clone, Serialize)]
+pub struct ShoppingItem {
+    /// Food name
+    pub food: String,
+    /// Total amount (None if no entry carried an amount)
+    pub amount: Option<f64>,
+    /// Unit name
+    pub unit: Option<String>,
+    /// True only when every merged entry is checked off
+    pub checked: bool,
+}
+
+/// All items shelved in one supermarket category
+#[derive(Debug, Clone, Serialize)]
+pub struct AisleSection {
+    /// Category name
+    pub category: String,
+    /// Items in this category, sorted by food name
+    pub items: Vec<ShoppingItem>,
+}
+
+/// Build a lowercase food name → category name lookup from Tandoor foods
+pub fn food_category_map(foods: &[Food]) -> HashMap<String, String> {
+    foods
+        .iter()
+        .filter_map(|food| {
+            food.supermarket_category
+                .as_ref()
+                .map(|category| (food.name.to_lowercase(), category.name.clone()))
+        })
+        .collect()
+}
+
+/// Group shopping list entries into aisle sections ordered for a store
+///
+/// Entries without a food name are skipped. `food_categories` keys must be
+/// lowercase food names (see [`food_category_map`]).
+pub fn group_by_aisle(
+    entries: &[ShoppingListEntry],
+    layout: &AisleLayout,
+    food_categories: &HashMap<String, String>,
+) -> Vec<AisleSection> {
+    let mut by_category: BTreeMap<String, Vec<ShoppingItem>> = BTreeMap::new();
+
+    for entry in entries {
+        let Some(food) = entry
+            .food
+            .as_deref()
+            .map(str::trim)
+            .filter(|f| !f.is_empty())
+        else {
+            continue;
+        };
+        let category = food_categories
+            .get(&food.to_lowercase())
+            .cloned()
+            .unwrap_or_else(|| UNCATEGORIZED_SECTION.to_string());
+        merge_item(by_category.entry(category).or_default(), food, entry);
+    }
+
+    let mut sections: Vec<AisleSection> = by_category
+        .into_iter()
+        .map(|(category, mut items)| {
+            items.sort_by_key(|item| item.food.to_lowercase());
+            AisleSection { category, items }
+        })
+        .collect();
+
+    sections.sort_by_key(|section| section_rank(&section.category, layout));
+    sections
+}
+
+/// Merge an entry into an existing item with the same food and unit, or append it
+fn merge_item(items: &mut Vec<ShoppingItem>, food: &str, entry: &ShoppingListEntry) {
+    let existing = items
+        .iter_mut()
+        .find(|item| item.food.eq_ignore_ascii_case(food) && item.unit == entry.unit);
+
+    if let Some(item) = existing {
+        item.amount = match (item.amount, entry.amount) {
+            (Some(a), Some(b)) => Some(a + b),
+            (a, b) => a.or(b),
+        };
+        item.checked = item.checked && entry.checked;
+    } else {
+        items.push(ShoppingItem {
+            food: food.to_string(),
+            amount: entry.amount,
+            unit: entry.unit.clone(),
+            checked: entry.checked,
+        });
+    }
+}
+
+/// Sort key: (known-to-store first, store position, name), "Other" always last
+fn section_rank(category: &str, layout: &AisleLayout) -> (u8, usize, String) {
+    if category == UNCATEGORIZED_SECTION {
+        return (2, 0, String::new());
+    }
+    layout.position(category).map_or_else(
+        || (1, 0, category.to_lowercase()),
+        |position| (0, position, String::new()),
+    )
+}
+
+/// Format an amount without trailing zeros ("2", "1.5", "0.25")
+fn format_amount(amount: f64) -> String {
+    let rounded = format!("{:.2}", amount);
+    rounded
+        .trim_end_matches('0')
+        .trim_end_matches('.')
+        .to_string()
+}
+
+/// Human-readable item label ("2 cup flour", "eggs")
+fn item_label(item: &ShoppingItem) -> String {
+    let quantity = [
+        item.amount.filter(|a| *a > 0.0).map(format_amount),
+        item.unit.clone().filter(|u| !u.is_empty()),
+    ]
+    .into_iter()
+    .flatten()
+    .collect::<Vec<_>>()
+    .join(" ");
+
+    if quantity.is_empty() {
+        item.food.clone()
+    } else {
+        format!("{} {}", quantity, item.food)
+    }
+}
+
+/// Checkbox marker for an item ("x" when checked off)
+fn check_mark(item: &ShoppingItem) -> &'static str {
+    if item.checked {
+        "x"
+    } else {
+        " "
+    }
+}
+
+/// Render grouped sections as a Markdown checklist
+pub fn render_markdown(title: &str, sections: &[AisleSection]) -> String {
+    let body = sections
+        .iter()
+        .map(|section| {
+            let items = section
+                .items
+                .iter()
+                .map(|item| format!("- [{}] {}\n", check_mark(item), item_label(item)))
+                .collect::<Vec<_>>()
+                .concat();
+            format!("\n## {}\n\n{}", section.category, items)
+        })
+        .collect::<Vec<_>>()
+        .concat();
+    format!("# {}\n{}", title, body)
+}
+
+/// Render grouped sections as plain text
+pub fn render_text(title: &str, sections: &[AisleSection]) -> String {
+    let body = sections
+        .iter()
+        .map(|section| {
+            let items = section
+                .items
+                .iter()
+                .map(|item| format!("  [{}] {}\n", check_mark(item), item_label(item)))
+                .collect::<Vec<_>>()
+                .concat();
+            format!("\n{}\n{}", section.category.to_uppercase(), items)
+        })
+        .collect::<Vec<_>>()
+        .concat();
+    format!("{}\n{}\n{}", title, "=".repeat(title.chars().count()), body)
+}
+
+/// Render grouped sections as a standalone, printable HTML checklist
+pub fn render_html(title: &str, sections: &[AisleSection]) -> String {
+    let body = sections
+        .iter()
+        .map(|section| {
+            let items = section
+                .items
+                .iter()
+                .map(|item| {
+                    let checked = if item.checked { " checked" } else { "" };
+                    format!(
+                        "<li><label><input type=\"checkbox\"{}> {}</label></li>\n",
+                        checked,
+                        escape_html(&item_label(item))
+                    )
+                })
+                .collect::<Vec<_>>()
+                .concat();
+            format!(
+                "<section>\n<h2>{}</h2>\n<ul>\n{}</ul>\n</section>\n",
+                escape_html(&section.category),
+                items
+            )
+        })
+        .collect::<Vec<_>>()
+        .concat();
+
+    format!(
+        "<!DOCTYPE html>\n<html lang=\"en\">\n<head>\n<meta charset=\"utf-8\">\n\
+         <title>{title}</title>\n<style>\n\
+         body {{ font-family: sans-serif; margin: 1.5em; }}\n\
+         h2 {{ border-bottom: 1px solid #999; margin-top: 1.2em; }}\n\
+         ul {{ list-style: none; padding-left: 0; }}\n\
+         li {{ padding: 0.15em 0; }}\n\
+         section {{ break-inside: avoid; }}\n\
+         @media print {{ body {{ margin: 0; }} }}\n\
+         </style>\n</head>\n<body>\n<h1>{title}</h1>\n{body}</body>\n</html>\n",
+        title = escape_html(title),
+        body = body
+    )
+}
+
+/// Render grouped sections in the requested format
+pub fn render(format: ShoppingListFormat, title: &str, sections: &[AisleSection]) -> String {
+    match format {
+        ShoppingListFormat::Markdown => render_markdown(title, sections),
+        ShoppingListFormat::Text => render_text(title, sections),
+        ShoppingListFormat::Html => render_html(title, sections),
+    }
+}
+
+/// Escape text for safe inclusion in HTML
+pub(crate) fn escape_html(text: &str) -> String {
+    text.chars()
+        .fold(String::with_capacity(text.len()), |mut out, c| {
+            match c {
+                '&' => out.push_str("&amp;"),
+                '<' => out.push_str("&lt;"),
+                '>' => out.push_str("&gt;"),
+                '"' => out.push_str("&quot;"),
+                '\'' => out.push_str("&#39;"),
+                other => out.push(other),
+            }
+            out
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tandoor::types::{SupermarketCategory, SupermarketCategoryRelation};
+
+    fn entry(food: &str, amount: Option<f64>, unit: Option<&str>) -> ShoppingListEntry {
+        ShoppingListEntry {
+            id: 1,
+            list: 1,
+            ingredient: None,
+            unit: unit.map(str::to_string),
+            amount,
+            food: Some(food.to_string()),
+            checked: false,
+            order: None,
+        }
+    }
+
+    fn category(id: i64, name: &str) -> SupermarketCategory {
+        SupermarketCategory {
+            id,
+            name: name.to_string(),
+            description: None,
+        }
+    }
+
+    fn store() -> Supermarket {
+        Supermarket {
+            id: 1,
+            name: "Corner Store".to_string(),
+            description: None,
+            category_to_supermarket: vec![
+                SupermarketCategoryRelation {
+                    id: Some(1),
+                    category: category(2, "Dairy"),
+                    order: 1,
+                },
+                SupermarketCategoryRelation {
+                    id: Some(2),
+                    category: category(1, "Produce"),
+                    order: 0,
+                },
+            ],
+        }
+    }
+
+    fn categories() -> HashMap<String, String> {
+        HashMap::from([
+            ("milk".to_string(), "Dairy".to_string()),
+            ("apples".to_string(), "Produce".to_string()),
+            ("bread".to_string(), "Bakery".to_string()),
+        ])
+    }
+
+    #[test]
+    fn test_layout_sorted_by_order() {
+        let layout = AisleLayout::from_supermarket(&store());
+        assert_eq!(layout.categories, vec!["Produce", "Dairy"]);
+        assert_eq!(layout.supermarket_name.as_deref(), Some("Corner Store"));
+    }
+
+    #[test]
+    fn test_group_follows_store_order() {
+        let layout = AisleLayout::from_supermarket(&store());
+        let entries = vec![
+            entry("salt", None, None),
+            entry("Milk", Some(1.0), Some("l")),
+            entry("bread", Some(1.0), None),
+            entry("apples", Some(6.0), None),
+        ];
+        let sections = group_by_aisle(&entries, &layout, &categories());
+        let names: Vec<_> = sections.iter().map(|s| s.category.as_str()).collect();
+        assert_eq!(names, vec!["Produce", "Dairy", "Bakery", "Other"]);
+    }
+
+    #[test]
+    fn test_group_merges_same_food_and_unit() {
+        let entries = vec![
+            entry("milk", Some(0.5), Some("l")),
+            entry("Milk", Some(1.0), Some("l")),
+            entry("milk", Some(2.0), Some("cup")),
+        ];
+        let sections = group_by_aisle(&entries, &AisleLayout::default(), &categories());
+        assert_eq!(sections.len(), 1);
+        assert_eq!(sections[0].items.len(), 2);
+        let litres = sections[0]
+            .items
+            .iter()
+            .find(|i| i.unit.as_deref() == Some("l"))
+            .unwrap();
+        assert!((litres.amount.unwrap() - 1.5).abs() < f64::EPSILON);
+    }
+
+    #[test]
+    fn test_group_skips_entries_without_food() {
+        let mut blank = entry("", None, None);
+        blank.food = None;
+        let sections = group_by_aisle(&[blank], &AisleLayout::default(), &categories());
+        assert!(sections.is_empty());
+    }
+
+    #[test]
+    fn test_food_category_map_lowercases_names() {
+        let foods = vec![Food {
+            id: 1,
+            name: "Greek Yogurt".to_string(),
+            plural_name: None,
+            description: None,
+            supermarket_category: Some(category(2, "Dairy")),
+        }];
+        let map = food_category_map(&foods);
+        assert_eq!(map.get("greek yogurt").map(String::as_str), Some("Dairy"));
+    }
+
+    #[test]
+    fn test_render_markdown() {
+        let layout = AisleLayout::from_supermarket(&store());
+        let sections = group_by_aisle(
+            &[
+                entry("apples", Some(6.0), None),
+                entry("milk", Some(1.5), Some("l")),
+            ],
+            &layout,
+            &categories(),
+        );
+        let md = render_markdown("Weekly Shop", &sections);
+        assert!(md.starts_with("# Weekly Shop\n"));
+        assert!(md.contains("## Produce\n\n- [ ] 6 apples\n"));
+        assert!(md.contains("## Dairy\n\n- [ ] 1.5 l milk\n"));
+        assert!(md.find("Produce") < md.find("Dairy"));
+    }
+
+    #[test]
+    fn test_render_text_marks_checked() {
+        let mut done = entry("apples", None, None);
+        done.checked = true;
+        let sections = group_by_aisle(&[done], &AisleLayout::default(), &categories());
+        let text = render_text("List", &sections);
+        assert!(text.contains("PRODUCE\n  [x] apples\n"));
+    }
+
+    #[test]
+    fn test_render_html_escapes() {
+        let sections = group_by_aisle(
+            &[entry("mac & cheese", None, None)],
+            &AisleLayout::default(),
+            &HashMap::new(),
+        );
+        let html = render(ShoppingListFormat::Html, "<List>", &sections);
+        assert!(html.contains("<title>&lt;List&gt;</title>"));
+        assert!(html.contains("<input type=\"checkbox\"> mac &amp; cheese"));
+    }
+
+    #[test]
+    fn test_format_amount_trims_zeros() {
+        assert_eq!(format_amount(2.0), "2");
+        assert_eq!(format_amount(1.5), "1.5");
+        assert_eq!(format_amount(0.25), "0.25");
+    }
+}
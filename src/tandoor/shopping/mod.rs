@@ -9,6 +9,8 @@
 //! These functions form the FUNCTIONAL CORE.
 //! The IMPERATIVE SHELL (binaries) handles all I/O.
 
+pub mod aisle;
+
 use crate::tandoor::types::ShoppingListRecipe;
 use serde::{Deserialize, Serialize};
 
@@ -0,0 +1,101 @@
+//! Text helpers shared by the recipe and meal plan renderers (FUNCTIONAL CORE - PURE)
+//!
+//! Amount and duration formatting for people to read, and the sentence and
+//! word splitting used to find prep steps in recipe instructions.
+
+/// Common fractions shown instead of decimals
+const FRACTIONS: &[(f64, &str)] = &[
+    (0.125, "1/8"),
+    (0.25, "1/4"),
+    (1.0 / 3.0, "1/3"),
+    (0.5, "1/2"),
+    (2.0 / 3.0, "2/3"),
+    (0.75, "3/4"),
+];
+
+/// Amount as a mixed fraction where one fits ("1 1/2", "1/3"), else a
+/// decimal ("0.15")
+pub fn format_amount(amount: f64) -> String {
+    let whole = amount.trunc();
+    let fraction = FRACTIONS
+        .iter()
+        .find(|(value, _)| (amount - whole - value).abs() < 0.01)
+        .map(|(_, text)| *text);
+
+    match fraction {
+        Some(fraction) if whole < 1.0 => fraction.to_string(),
+        Some(fraction) => format!("{whole:.0} {fraction}"),
+        None => format_decimal(amount),
+    }
+}
+
+/// Amount as a decimal with at most two places ("1.5", "2")
+pub fn format_decimal(amount: f64) -> String {
+    let text = format!("{amount:.2}");
+    text.trim_end_matches('0').trim_end_matches('.').to_string()
+}
+
+/// `12h` or `90 min`
+pub fn format_lead(minutes: u32) -> String {
+    whole_hours(minutes).map_or_else(|| format!("{minutes} min"), |hours| format!("{hours}h"))
+}
+
+/// Minutes as whole hours, when they are
+pub fn whole_hours(minutes: u32) -> Option<u32> {
+    (minutes > 0 && minutes % 60 == 0).then(|| minutes.div_euclid(60))
+}
+
+/// Sentences of an instruction (`1.5 hours` stays whole)
+pub fn sentences(text: &str) -> impl Iterator<Item = &str> {
+    text.split(['\n', '!', '?'])
+        .flat_map(|line| line.split(". "))
+        .map(str::trim)
+        .filter(|sentence| !sentence.is_empty())
+}
+
+/// Lowercase words, without surrounding punctuation
+pub fn words(sentence: &str) -> Vec<String> {
+    sentence
+        .split(|c: char| c.is_whitespace() || matches!(c, ',' | ';' | ':' | '(' | ')'))
+        .map(|word| {
+            word.trim_matches(|c: char| !c.is_alphanumeric())
+                .to_lowercase()
+        })
+        .filter(|word| !word.is_empty())
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_format_amount() {
+        assert_eq!(format_amount(2.0), "2");
+        assert_eq!(format_amount(0.5), "1/2");
+        assert_eq!(format_amount(1.5), "1 1/2");
+        assert_eq!(format_amount(0.333), "1/3");
+        assert_eq!(format_amount(2.75), "2 3/4");
+        assert_eq!(format_amount(0.15), "0.15");
+        assert_eq!(format_amount(250.0), "250");
+        assert_eq!(format_decimal(1.5), "1.5");
+        assert_eq!(format_decimal(1.0 / 3.0), "0.33");
+    }
+
+    #[test]
+    fn test_format_lead() {
+        assert_eq!(format_lead(720), "12h");
+        assert_eq!(format_lead(90), "90 min");
+        assert_eq!(whole_hours(0), None);
+    }
+
+    #[test]
+    fn test_sentences_and_words() {
+        let found: Vec<_> = sentences("Soak the beans. Simmer 1.5 hours!\nServe").collect();
+        assert_eq!(found, ["Soak the beans", "Simmer 1.5 hours", "Serve"]);
+        assert_eq!(
+            words("Marinate (overnight), then grill."),
+            ["marinate", "overnight", "then", "grill"]
+        );
+    }
+}
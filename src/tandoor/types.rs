@@ -17,6 +17,11 @@
 //! - [`RecipeSummary`] - Recipe metadata from `/api/recipe/` (GET)
 //! - [`Keyword`] - Recipe tags/keywords
 //!
+//! ## Recipe Detail
+//! - [`RecipeDetail`] - Full recipe from `/api/recipe/{id}/` (GET)
+//! - [`RecipeStep`], [`RecipeIngredient`] - Steps with their ingredients
+//! - [`RecipeNutrition`] - Nutrition stored on the recipe
+//!
 //! ## Recipe Import (URL Scraping)
 //! - [`RecipeFromSourceRequest`] - Request to `/api/recipe-from-source/` (POST)
 //! - [`RecipeFromSourceResponse`] - Scraped recipe data
@@ -106,10 +111,12 @@
 //! | Endpoint | Request Type | Response Type |
 //! |----------|--------------|---------------|
 //! | `GET /api/recipe/` | N/A | `PaginatedResponse<RecipeSummary>` |
+//! | `GET /api/recipe/{id}/` | N/A | `RecipeDetail` |
 //! | `POST /api/recipe/` | `CreateRecipeRequest` | `CreatedRecipe` |
 //! | `POST /api/recipe-from-source/` | `RecipeFromSourceRequest` | `RecipeFromSourceResponse` |
 
 use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
 
 /// Configuration for Tandoor API client
 #[derive(Debug, Clone, Deserialize, Serialize)]
@@ -167,10 +174,112 @@ pub struct RecipeSummary {
     pub servings: Option<i32>,
 }
 
+/// Full recipe (detail view) with steps, ingredients and nutrition
+#[derive(Debug, Deserialize, Serialize, Clone)]
+pub struct RecipeDetail {
+    /// Recipe ID
+    pub id: i64,
+    /// Recipe name
+    pub name: String,
+    /// Recipe description
+    #[serde(default)]
+    pub description: Option<String>,
+    /// Image URL
+    #[serde(default)]
+    pub image: Option<String>,
+    /// Original source URL
+    #[serde(default)]
+    pub source_url: Option<String>,
+    /// Keywords/tags
+    #[serde(default)]
+    pub keywords: Vec<Keyword>,
+    /// Steps in order
+    #[serde(default)]
+    pub steps: Vec<RecipeStep>,
+    /// Active cooking time in minutes
+    #[serde(default)]
+    pub working_time: Option<i32>,
+    /// Passive time (marinating, resting) in minutes
+    #[serde(default)]
+    pub waiting_time: Option<i32>,
+    /// Number of servings
+    #[serde(default)]
+    pub servings: Option<i32>,
+    /// Servings text (e.g., "slices")
+    #[serde(default)]
+    pub servings_text: Option<String>,
+    /// Nutrition per serving, if recorded
+    #[serde(default)]
+    pub nutrition: Option<RecipeNutrition>,
+}
+
+/// Step of a [`RecipeDetail`]
+#[derive(Debug, Deserialize, Serialize, Clone)]
+pub struct RecipeStep {
+    /// Step ID
+    #[serde(default)]
+    pub id: Option<i64>,
+    /// Step title
+    #[serde(default)]
+    pub name: Option<String>,
+    /// Step instructions (Markdown)
+    #[serde(default)]
+    pub instruction: String,
+    /// Ingredients used in this step
+    #[serde(default)]
+    pub ingredients: Vec<RecipeIngredient>,
+    /// Step time in minutes
+    #[serde(default)]
+    pub time: Option<i32>,
+}
+
+/// Ingredient of a [`RecipeStep`]
+#[derive(Debug, Deserialize, Serialize, Clone)]
+pub struct RecipeIngredient {
+    /// Food (absent for section headers)
+    #[serde(default)]
+    pub food: Option<Food>,
+    /// Unit of measurement
+    #[serde(default)]
+    pub unit: Option<Unit>,
+    /// Quantity amount
+    #[serde(default)]
+    pub amount: f64,
+    /// Additional notes (e.g., "finely chopped")
+    #[serde(default)]
+    pub note: Option<String>,
+    /// Is this a section header (the note is the header text)
+    #[serde(default)]
+    pub is_header: bool,
+    /// Amount is not shown (e.g., "salt to taste")
+    #[serde(default)]
+    pub no_amount: bool,
+    /// Original text from import
+    #[serde(default)]
+    pub original_text: Option<String>,
+}
+
+/// Nutrition stored on a recipe, per serving
+#[derive(Debug, Deserialize, Serialize, Clone, Default)]
+pub struct RecipeNutrition {
+    /// Calories (kcal)
+    #[serde(default)]
+    pub calories: Option<f64>,
+    /// Protein in grams
+    #[serde(default)]
+    pub proteins: Option<f64>,
+    /// Fat in grams
+    #[serde(default)]
+    pub fats: Option<f64>,
+    /// Carbohydrates in grams
+    #[serde(default)]
+    pub carbohydrates: Option<f64>,
+}
+
 /// Keyword/tag
 /// Note: In recipe list responses, keywords only have `id` and `label`.
 /// In keyword list responses, they have full details including `name`.
-#[derive(Debug, Deserialize, Serialize)]
+#[derive(Debug, Deserialize, Serialize, Clone)]
 pub struct Keyword {
     /// Keyword ID
     pub id: i64,
@@ -533,6 +642,26 @@ pub struct PaginatedMealPlanResponse {
     pub results: Vec<MealPlan>,
 }
 
+impl From<PaginatedMealPlanResponse> for PaginatedResponse<MealPlan> {
+    fn from(response: PaginatedMealPlanResponse) -> Self {
+        Self {
+            count: response.count,
+            next: response.next,
+            previous: response.previous,
+            results: response.results,
+        }
+    }
+}
+
+/// Meal plans in a date range plus the full recipe JSON for each planned recipe
+#[derive(Debug, Default)]
+pub struct MealPlansWithRecipes {
+    /// Meal plans overlapping the range
+    pub plans: Vec<MealPlan>,
+    /// Full recipes by ID
+    pub recipes: HashMap<i64, serde_json::Value>,
+}
+
 /// Request to create a meal plan
 #[derive(Debug, Serialize)]
 pub struct CreateMealPlanRequest {
@@ -954,9 +1083,15 @@ pub struct Food {
     pub id: i64,
     /// Food name
     pub name: String,
+    /// Plural form (optional)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub plural_name: Option<String>,
     /// Food description
     #[serde(default)]
     pub description: Option<String>,
+    /// Supermarket category (aisle) this food is shelved in
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub supermarket_category: Option<SupermarketCategory>,
 }
 
 /// Request to create a food
@@ -1233,6 +1368,34 @@ pub struct Supermarket {
     /// Supermarket description
     #[serde(default)]
     pub description: Option<String>,
+    /// Categories in this store, with their aisle order
+    #[serde(default)]
+    pub category_to_supermarket: Vec<SupermarketCategoryRelation>,
+}
+
+/// Supermarket category (e.g., "Produce", "Dairy")
+#[derive(Debug, Deserialize, Serialize, Clone)]
+pub struct SupermarketCategory {
+    /// Category ID
+    pub id: i64,
+    /// Category name
+    pub name: String,
+    /// Category description
+    #[serde(default)]
+    pub description: Option<String>,
+}
+
+/// Link between a supermarket and a category, with walking order
+#[derive(Debug, Deserialize, Serialize, Clone)]
+pub struct SupermarketCategoryRelation {
+    /// Relation ID
+    #[serde(default)]
+    pub id: Option<i64>,
+    /// The category placed in this store
+    pub category: SupermarketCategory,
+    /// Position of the category in the store's walking order
+    #[serde(default)]
+    pub order: i32,
 }
 
 /// Request to create a supermarket
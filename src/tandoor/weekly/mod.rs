@@ -0,0 +1,393 @@
+//! Printable weekly menu and prep sheet (FUNCTIONAL CORE - PURE)
+//!
+//! [`weekly_menu`] lays a week of meal plans out as a grid, one row per day
+//! and one column per meal type, with each day's nutrition, and gathers the
+//! [`PrepSheet`] for a prep session before the week starts. [`render`] turns
+//! it into printable HTML (grid in landscape, prep sheet on its own page,
+//! ready to print to PDF), Markdown or plain text. No I/O happens here: the
+//! `tandoor_weekly_menu` binary fetches meal plans and recipes.
+//!
+//! Nutrition is per person: one serving of each meal, from the nutrition
+//! stored on the recipe. A day's totals add up the meals that have it and
+//! count the ones that don't.
+
+pub mod prep;
+pub mod render;
+
+pub use prep::{PrepKind, PrepSheet, PrepTask};
+pub use render::{render, MenuFormat};
+
+use std::collections::HashMap;
+
+use chrono::{NaiveDate, NaiveTime};
+use serde::Serialize;
+use serde_json::Value;
+
+use crate::tandoor::calendar::SkippedMealPlan;
+use crate::tandoor::nutrition::core::stored_nutrition;
+use crate::tandoor::{MealPlan, RecipeDetail};
+
+/// Column for meal plans without a meal type
+const UNTYPED_MEAL: &str = "Meal";
+
+/// Nutrition of one serving, or a day of them
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize)]
+pub struct NutritionTotals {
+    /// kcal
+    pub calories: f64,
+    /// Grams
+    pub protein: f64,
+    /// Grams
+    pub fat: f64,
+    /// Grams
+    pub carbohydrate: f64,
+}
+
+impl NutritionTotals {
+    fn of(recipe: &RecipeDetail) -> Option<Self> {
+        stored_nutrition(recipe).map(|nutrition| Self {
+            calories: nutrition.calories,
+            protein: nutrition.protein,
+            fat: nutrition.fat,
+            carbohydrate: nutrition.carbohydrate,
+        })
+    }
+
+    fn add(&mut self, other: &Self) {
+        self.calories += other.calories;
+        self.protein += other.protein;
+        self.fat += other.fat;
+        self.carbohydrate += other.carbohydrate;
+    }
+}
+
+/// One meal in the grid
+#[derive(Debug, Clone, Serialize)]
+pub struct MenuMeal {
+    pub meal_plan_id: i64,
+    /// Column it sits in
+    pub meal_type: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub recipe_id: Option<i64>,
+    /// The plan's title, else the recipe name
+    pub name: String,
+    pub servings: f64,
+    /// Per serving
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub nutrition: Option<NutritionTotals>,
+}
+
+/// One row of the grid
+#[derive(Debug, Clone, Serialize)]
+pub struct MenuDay {
+    /// YYYY-MM-DD
+    pub date: String,
+    /// `Mon`
+    pub weekday: String,
+    /// In meal type order
+    pub meals: Vec<MenuMeal>,
+    /// One serving of each meal that has nutrition
+    pub nutrition: NutritionTotals,
+    /// Meals without nutrition, left out of the totals
+    pub missing_nutrition: usize,
+}
+
+impl MenuDay {
+    /// Meals in one column
+    pub fn meals_of<'a>(&'a self, meal_type: &'a str) -> impl Iterator<Item = &'a MenuMeal> {
+        self.meals
+            .iter()
+            .filter(move |meal| meal.meal_type == meal_type)
+    }
+}
+
+/// A week laid out for printing
+#[derive(Debug, Clone, Serialize)]
+pub struct WeeklyMenu {
+    pub title: String,
+    /// Columns, in meal type order
+    pub meal_types: Vec<String>,
+    /// Every day in range, planned or not
+    pub days: Vec<MenuDay>,
+    pub prep: PrepSheet,
+    /// Meal plans left out
+    pub skipped: Vec<SkippedMealPlan>,
+}
+
+/// Options for [`weekly_menu`]
+#[derive(Debug, Clone)]
+pub struct WeeklyMenuOptions {
+    /// First day
+    pub from: NaiveDate,
+    /// Last day
+    pub to: NaiveDate,
+    /// Day of the prep session (default: the day before `from`)
+    pub prep_date: Option<NaiveDate>,
+    /// Heading (default: `Menu for <from> to <to>`)
+    pub title: Option<String>,
+}
+
+/// A meal plan with what the menu and prep sheet need from it
+pub(crate) struct Planned<'a> {
+    pub(crate) plan: &'a MealPlan,
+    pub(crate) date: NaiveDate,
+    pub(crate) meal_type: String,
+    pub(crate) order: i64,
+    pub(crate) time: Option<NaiveTime>,
+    /// Recipe JSON with steps, or the plan's recipe summary
+    pub(crate) raw: &'a Value,
+    pub(crate) recipe: Option<RecipeDetail>,
+}
+
+impl Planned<'_> {
+    /// `Mon dinner`
+    pub(crate) fn label(&self) -> String {
+        format!(
+            "{} {}",
+            self.date.format("%a"),
+            self.meal_type.to_lowercase()
+        )
+    }
+
+    /// Planned servings over the recipe's servings
+    pub(crate) fn scale(&self) -> f64 {
+        let servings = self.recipe.as_ref().and_then(|recipe| recipe.servings);
+        match servings {
+            Some(servings) if servings > 0 && self.plan.servings > 0.0 => {
+                self.plan.servings / f64::from(servings)
+            }
+            Some(_) | None => 1.0,
+        }
+    }
+}
+
+/// Lay out the meal plans from `options.from` to `options.to`
+///
+/// `recipes` maps recipe IDs to full recipe JSON (as returned by Tandoor's
+/// recipe detail endpoint); plans whose recipe is missing show without
+/// nutrition and add nothing to the prep sheet. Plans outside the range are
+/// skipped.
+pub fn weekly_menu(
+    plans: &[MealPlan],
+    recipes: &HashMap<i64, Value>,
+    options: &WeeklyMenuOptions,
+) -> WeeklyMenu {
+    let mut planned = Vec::new();
+    let mut skipped = Vec::new();
+    for plan in plans {
+        match planned_meal(plan, recipes, options) {
+            Ok(meal) => planned.push(meal),
+            Err(reason) => skipped.push(SkippedMealPlan {
+                meal_plan_id: plan.id,
+                reason,
+            }),
+        }
+    }
+    planned.sort_by(|a, b| {
+        (a.date, a.order, &a.meal_type, a.plan.id).cmp(&(b.date, b.order, &b.meal_type, b.plan.id))
+    });
+
+    let mut meal_types: Vec<(i64, String)> = planned
+        .iter()
+        .map(|meal| (meal.order, meal.meal_type.clone()))
+        .collect();
+    meal_types.sort();
+    let mut columns: Vec<String> = Vec::new();
+    for (_, name) in meal_types {
+        if !columns.contains(&name) {
+            columns.push(name);
+        }
+    }
+
+    let days = options
+        .from
+        .iter_days()
+        .take_while(|date| *date <= options.to)
+        .map(|date| menu_day(date, &planned))
+        .collect();
+    let prep_date = options
+        .prep_date
+        .or_else(|| options.from.pred_opt())
+        .unwrap_or(options.from);
+
+    WeeklyMenu {
+        title: options
+            .title
+            .clone()
+            .unwrap_or_else(|| format!("Menu for {} to {}", options.from, options.to)),
+        meal_types: columns,
+        days,
+        prep: prep::prep_sheet(&planned, prep_date),
+        skipped,
+    }
+}
+
+fn planned_meal<'a>(
+    plan: &'a MealPlan,
+    recipes: &'a HashMap<i64, Value>,
+    options: &WeeklyMenuOptions,
+) -> Result<Planned<'a>, String> {
+    let date = plan
+        .from_date
+        .get(..10)
+        .and_then(|date| NaiveDate::parse_from_str(date, "%Y-%m-%d").ok())
+        .ok_or_else(|| format!("Invalid date: {}", plan.from_date))?;
+    if date < options.from || date > options.to {
+        return Err(format!("Outside {} to {}", options.from, options.to));
+    }
+    let meal_type = Some(plan.meal_type_name.trim())
+        .filter(|name| !name.is_empty())
+        .or_else(|| plan.meal_type.get("name").and_then(Value::as_str))
+        .unwrap_or(UNTYPED_MEAL)
+        .to_string();
+    let time = plan
+        .meal_type
+        .get("time")
+        .and_then(Value::as_str)
+        .and_then(|time| {
+            NaiveTime::parse_from_str(time, "%H:%M:%S")
+                .or_else(|_| NaiveTime::parse_from_str(time, "%H:%M"))
+                .ok()
+        });
+    let raw = plan
+        .recipe
+        .get("id")
+        .and_then(Value::as_i64)
+        .and_then(|id| recipes.get(&id))
+        .unwrap_or(&plan.recipe);
+
+    Ok(Planned {
+        plan,
+        date,
+        meal_type,
+        order: plan
+            .meal_type
+            .get("order")
+            .and_then(Value::as_i64)
+            .unwrap_or(0),
+        time,
+        raw,
+        recipe: serde_json::from_value(raw.clone()).ok(),
+    })
+}
+
+fn menu_day(date: NaiveDate, planned: &[Planned<'_>]) -> MenuDay {
+    let mut nutrition = NutritionTotals::default();
+    let mut missing_nutrition = 0;
+    let meals = planned
+        .iter()
+        .filter(|meal| meal.date == date)
+        .map(|meal| {
+            let per_serving = meal.recipe.as_ref().and_then(NutritionTotals::of);
+            match &per_serving {
+                Some(per_serving) => nutrition.add(per_serving),
+                None => missing_nutrition += 1,
+            }
+            MenuMeal {
+                meal_plan_id: meal.plan.id,
+                meal_type: meal.meal_type.clone(),
+                recipe_id: meal.plan.recipe.get("id").and_then(Value::as_i64),
+                name: meal_name(meal),
+                servings: meal.plan.servings,
+                nutrition: per_serving,
+            }
+        })
+        .collect();
+    MenuDay {
+        date: date.to_string(),
+        weekday: date.format("%a").to_string(),
+        meals,
+        nutrition,
+        missing_nutrition,
+    }
+}
+
+/// The plan's title, else the recipe's name
+fn meal_name(meal: &Planned<'_>) -> String {
+    Some(meal.plan.title.trim())
+        .filter(|title| !title.is_empty())
+        .or_else(|| meal.recipe.as_ref().map(|recipe| recipe.name.as_str()))
+        .or_else(|| Some(meal.plan.recipe_name.as_str()).filter(|name| !name.is_empty()))
+        .or_else(|| meal.raw.get("name").and_then(Value::as_str))
+        .unwrap_or(UNTYPED_MEAL)
+        .to_string()
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use super::*;
+    use serde_json::json;
+
+    pub fn plan(id: i64, date: &str, meal_type: (&str, i64), recipe_id: i64) -> MealPlan {
+        serde_json::from_value(json!({
+            "id": id, "recipe": {"id": recipe_id, "name": format!("Recipe {recipe_id}")},
+            "servings": 4.0, "from_date": format!("{date}T00:00:00"), "to_date": format!("{date}T00:00:00"),
+            "meal_type": {"id": meal_type.1, "name": meal_type.0, "order": meal_type.1, "time": "18:00:00"},
+            "created_by": 1, "recipe_name": format!("Recipe {recipe_id}"), "meal_type_name": meal_type.0
+        }))
+        .unwrap()
+    }
+
+    pub fn options(from: &str, to: &str) -> WeeklyMenuOptions {
+        WeeklyMenuOptions {
+            from: from.parse().unwrap(),
+            to: to.parse().unwrap(),
+            prep_date: None,
+            title: None,
+        }
+    }
+
+    fn menu() -> WeeklyMenu {
+        let plans = [
+            plan(1, "2026-10-19", ("Dinner", 3), 10),
+            plan(2, "2026-10-19", ("Breakfast", 1), 11),
+            plan(3, "2026-10-21", ("Dinner", 3), 12),
+            plan(4, "2026-10-30", ("Dinner", 3), 10),
+        ];
+        let recipes = HashMap::from([
+            (
+                10,
+                json!({"id": 10, "name": "Chili", "servings": 4, "nutrition": {"calories": 650.0, "proteins": 40.0}}),
+            ),
+            (
+                11,
+                json!({"id": 11, "name": "Oats", "nutrition": {"calories": 350.5, "carbohydrates": 60.0}}),
+            ),
+        ]);
+        weekly_menu(&plans, &recipes, &options("2026-10-19", "2026-10-25"))
+    }
+
+    #[test]
+    fn test_range_defaults() {
+        let menu = menu();
+        assert_eq!(menu.title, "Menu for 2026-10-19 to 2026-10-25");
+        assert_eq!(menu.days.len(), 7);
+        assert_eq!(menu.skipped.len(), 1);
+        assert_eq!(menu.skipped[0].meal_plan_id, 4);
+        assert_eq!(menu.prep.prep_date, "2026-10-18");
+    }
+
+    #[test]
+    fn test_grid() {
+        let menu = menu();
+        assert_eq!(menu.meal_types, ["Breakfast", "Dinner"]);
+        assert_eq!(menu.days[0].weekday, "Mon");
+        let names: Vec<_> = menu.days[0].meals.iter().map(|m| m.name.as_str()).collect();
+        assert_eq!(names, ["Oats", "Chili"]);
+        assert_eq!(menu.days[2].meals_of("Dinner").count(), 1);
+        assert_eq!(menu.days[2].meals[0].name, "Recipe 12");
+        assert!(menu.days[1].meals.is_empty());
+    }
+
+    #[test]
+    fn test_nutrition_totals() {
+        let menu = menu();
+        let monday = &menu.days[0];
+        assert!((monday.nutrition.calories - 1000.5).abs() < 1e-9);
+        assert!((monday.nutrition.carbohydrate - 60.0).abs() < 1e-9);
+        assert_eq!(monday.missing_nutrition, 0);
+
+        let wednesday = &menu.days[2];
+        assert_eq!(wednesday.missing_nutrition, 1);
+    }
+}
@@ -0,0 +1,454 @@
+//! Prep sheet: one prep session for the week ahead
+//!
+//! Tasks come from the week's recipes:
+//! - Head starts: steps that need hours before the meal (marinate, soak,
+//!   thaw, ...; see [`prep_reminders`]). Those that must start by the prep
+//!   day go on the sheet; later ones are listed with when to start them.
+//! - Batch prep: a food that two or more meals cook or cut the same way is
+//!   done once ("Cook rice for Mon+Wed"), with the amounts added up for the
+//!   planned servings when they share a unit.
+//!
+//! The sheet runs in dependency order: head starts first, longest first,
+//! since everything else happens while they wait; then cooking, to get the
+//! stove and oven going; then knife work, which fits in while things cook.
+//! Within each, tasks for earlier meals come first.
+
+use std::collections::BTreeMap;
+
+use chrono::{NaiveDate, NaiveDateTime, NaiveTime};
+use serde::Serialize;
+
+use super::Planned;
+use crate::tandoor::calendar::prep_reminders;
+use crate::tandoor::text::{format_amount, format_lead, sentences, words};
+use crate::tandoor::RecipeIngredient;
+
+/// Kind of prep task, in the order the sheet runs them
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum PrepKind {
+    /// Marinate, soak, thaw, ... well before the meal
+    HeadStart,
+    /// Cook once for several meals
+    Cook,
+    /// Cut once for several meals
+    Cut,
+}
+
+/// One line on the prep sheet
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct PrepTask {
+    pub kind: PrepKind,
+    /// `Cook rice for Mon+Wed (3 cup)`
+    pub task: String,
+    /// Meals it is for (`Mon dinner`)
+    pub meals: Vec<String>,
+    /// Total for batch prep, when the meals share a unit
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub amount: Option<String>,
+    /// The step it comes from
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub instruction: Option<String>,
+    /// When to start a head start after the prep day (`Wed 06:00`)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub start: Option<String>,
+}
+
+/// Tasks for the prep session, and head starts due later in the week
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct PrepSheet {
+    /// YYYY-MM-DD
+    pub prep_date: String,
+    /// In the order to do them
+    pub tasks: Vec<PrepTask>,
+    /// Head starts that can't happen on the prep day, by start time
+    pub later: Vec<PrepTask>,
+}
+
+/// A way of prepping a food ahead, recognised by its verb
+struct BatchAction {
+    words: &'static [&'static str],
+    label: &'static str,
+    kind: PrepKind,
+}
+
+const BATCH_ACTIONS: &[BatchAction] = &[
+    BatchAction {
+        words: &["cook", "cooked", "cooking"],
+        label: "Cook",
+        kind: PrepKind::Cook,
+    },
+    BatchAction {
+        words: &["boil", "boiled", "boiling"],
+        label: "Boil",
+        kind: PrepKind::Cook,
+    },
+    BatchAction {
+        words: &["simmer", "simmered"],
+        label: "Simmer",
+        kind: PrepKind::Cook,
+    },
+    BatchAction {
+        words: &["roast", "roasted"],
+        label: "Roast",
+        kind: PrepKind::Cook,
+    },
+    BatchAction {
+        words: &["bake", "baked"],
+        label: "Bake",
+        kind: PrepKind::Cook,
+    },
+    BatchAction {
+        words: &["steam", "steamed"],
+        label: "Steam",
+        kind: PrepKind::Cook,
+    },
+    BatchAction {
+        words: &["toast", "toasted"],
+        label: "Toast",
+        kind: PrepKind::Cook,
+    },
+    BatchAction {
+        words: &["blanch", "blanched"],
+        label: "Blanch",
+        kind: PrepKind::Cook,
+    },
+    BatchAction {
+        words: &["chop", "chopped"],
+        label: "Chop",
+        kind: PrepKind::Cut,
+    },
+    BatchAction {
+        words: &["dice", "diced"],
+        label: "Dice",
+        kind: PrepKind::Cut,
+    },
+    BatchAction {
+        words: &["mince", "minced"],
+        label: "Mince",
+        kind: PrepKind::Cut,
+    },
+    BatchAction {
+        words: &["slice", "sliced"],
+        label: "Slice",
+        kind: PrepKind::Cut,
+    },
+    BatchAction {
+        words: &["grate", "grated"],
+        label: "Grate",
+        kind: PrepKind::Cut,
+    },
+    BatchAction {
+        words: &["shred", "shredded"],
+        label: "Shred",
+        kind: PrepKind::Cut,
+    },
+    BatchAction {
+        words: &["peel", "peeled"],
+        label: "Peel",
+        kind: PrepKind::Cut,
+    },
+];
+
+/// A food one meal preps a given way
+struct Usage<'a> {
+    /// Index into the planned meals (date order)
+    meal: usize,
+    /// Scaled to the planned servings
+    amount: f64,
+    unit: Option<String>,
+    instruction: &'a str,
+}
+
+/// Tasks sorted for the sheet, with a key for "earlier meals first"
+type Sortable = (PrepKind, u32, usize, PrepTask);
+
+/// Meals using each (food, action), with the action's verbs
+type Usages<'a> = BTreeMap<(String, &'static str), (&'static BatchAction, Vec<Usage<'a>>)>;
+
+/// Build the prep sheet for meals in date order
+pub(crate) fn prep_sheet(planned: &[Planned<'_>], prep_date: NaiveDate) -> PrepSheet {
+    let (mut tasks, later) = head_starts(planned, prep_date);
+    tasks.extend(batch_tasks(planned));
+    tasks.sort_by(|a, b| {
+        (a.0, std::cmp::Reverse(a.1), a.2, &a.3.task).cmp(&(
+            b.0,
+            std::cmp::Reverse(b.1),
+            b.2,
+            &b.3.task,
+        ))
+    });
+    PrepSheet {
+        prep_date: prep_date.to_string(),
+        tasks: tasks.into_iter().map(|(_, _, _, task)| task).collect(),
+        later,
+    }
+}
+
+/// Head starts on the prep day, and those that start later
+///
+/// Meals without a time count from the start of their day, so the start
+/// errs early.
+fn head_starts(planned: &[Planned<'_>], prep_date: NaiveDate) -> (Vec<Sortable>, Vec<PrepTask>) {
+    let prep_end = prep_date
+        .succ_opt()
+        .map_or(NaiveDateTime::MAX, |next| next.and_time(NaiveTime::MIN));
+    let mut session = Vec::new();
+    let mut later: Vec<(NaiveDateTime, PrepTask)> = Vec::new();
+    for (index, meal) in planned.iter().enumerate() {
+        let served = meal.date.and_time(meal.time.unwrap_or(NaiveTime::MIN));
+        for reminder in prep_reminders(meal.raw) {
+            let start = served - chrono::Duration::minutes(i64::from(reminder.lead_minutes));
+            let mut task = PrepTask {
+                kind: PrepKind::HeadStart,
+                task: format!(
+                    "Start {} for {} ({} ahead)",
+                    reminder.action,
+                    meal.label(),
+                    format_lead(reminder.lead_minutes)
+                ),
+                meals: vec![meal.label()],
+                amount: None,
+                instruction: Some(reminder.instruction),
+                start: None,
+            };
+            if start < prep_end {
+                session.push((PrepKind::HeadStart, reminder.lead_minutes, index, task));
+            } else {
+                task.start = Some(start.format("%a %H:%M").to_string());
+                later.push((start, task));
+            }
+        }
+    }
+    later.sort_by(|a, b| a.0.cmp(&b.0));
+    (session, later.into_iter().map(|(_, task)| task).collect())
+}
+
+/// Foods that two or more meals prep the same way
+fn batch_tasks(planned: &[Planned<'_>]) -> Vec<Sortable> {
+    let mut usages = Usages::new();
+    for (index, meal) in planned.iter().enumerate() {
+        let Some(recipe) = &meal.recipe else {
+            continue;
+        };
+        for step in &recipe.steps {
+            for ingredient in step.ingredients.iter().filter(|i| !i.is_header) {
+                let Some(food) = &ingredient.food else {
+                    continue;
+                };
+                let Some(action) = batch_action(ingredient, &step.instruction) else {
+                    continue;
+                };
+                let usage = Usage {
+                    meal: index,
+                    amount: ingredient.amount * meal.scale(),
+                    unit: ingredient.unit.as_ref().map(|unit| unit.name.clone()),
+                    instruction: &step.instruction,
+                };
+                usages
+                    .entry((food.name.to_lowercase(), action.label))
+                    .or_insert_with(|| (action, Vec::new()))
+                    .1
+                    .push(usage);
+            }
+        }
+    }
+
+    usages
+        .into_iter()
+        .filter_map(|((food, _), (action, usages))| batch_task(planned, &food, action, &usages))
+        .collect()
+}
+
+/// How an ingredient is prepped: its note ("diced") or a sentence of its
+/// step that names the food ("Cook the rice")
+fn batch_action(ingredient: &RecipeIngredient, instruction: &str) -> Option<&'static BatchAction> {
+    let food = ingredient.food.as_ref()?;
+    let names: Vec<String> = [Some(&food.name), food.plural_name.as_ref()]
+        .into_iter()
+        .flatten()
+        .filter_map(|name| words(name).pop())
+        .collect();
+    let action_in = |words: &[String]| {
+        BATCH_ACTIONS.iter().find(|action| {
+            words
+                .iter()
+                .any(|word| action.words.contains(&word.as_str()))
+        })
+    };
+
+    let note = ingredient.note.as_deref().map(words).unwrap_or_default();
+    action_in(&note).or_else(|| {
+        sentences(instruction)
+            .map(words)
+            .filter(|words| words.iter().any(|word| names.contains(word)))
+            .find_map(|words| action_in(&words))
+    })
+}
+
+fn batch_task(
+    planned: &[Planned<'_>],
+    food: &str,
+    action: &BatchAction,
+    usages: &[Usage<'_>],
+) -> Option<Sortable> {
+    let mut meals: Vec<usize> = usages.iter().map(|usage| usage.meal).collect();
+    meals.dedup();
+    let first = *meals.first()?;
+    if meals.len() < 2 {
+        return None;
+    }
+    let mut days: Vec<String> = Vec::new();
+    for meal in meals.iter().filter_map(|index| planned.get(*index)) {
+        let day = meal.date.format("%a").to_string();
+        if !days.contains(&day) {
+            days.push(day);
+        }
+    }
+
+    let unit = usages.first().and_then(|usage| usage.unit.clone());
+    let total: f64 = usages.iter().map(|usage| usage.amount).sum();
+    let amount = (total > 0.0
+        && usages
+            .iter()
+            .all(|usage| usage.amount > 0.0 && usage.unit == unit))
+    .then(|| {
+        unit.map_or_else(
+            || format_amount(total),
+            |unit| format!("{} {unit}", format_amount(total)),
+        )
+    });
+    let task = format!("{} {food} for {}", action.label, days.join("+"));
+
+    Some((
+        action.kind,
+        0,
+        first,
+        PrepTask {
+            kind: action.kind,
+            task: amount
+                .as_ref()
+                .map_or_else(|| task.clone(), |amount| format!("{task} ({amount})")),
+            meals: meals
+                .iter()
+                .filter_map(|index| planned.get(*index).map(Planned::label))
+                .collect(),
+            amount,
+            instruction: usages
+                .first()
+                .map(|usage| usage.instruction.trim().to_string()),
+            start: None,
+        },
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::tests::{options, plan};
+    use super::super::weekly_menu;
+    use serde_json::{json, Value};
+    use std::collections::HashMap;
+
+    fn ingredient(food: &str, amount: f64, unit: &str, note: &str) -> Value {
+        json!({"food": {"id": 1, "name": food}, "unit": {"id": 1, "name": unit}, "amount": amount, "note": note})
+    }
+
+    fn recipes() -> HashMap<i64, Value> {
+        HashMap::from([
+            (
+                10,
+                json!({"id": 10, "name": "Chili", "servings": 4, "steps": [
+                    {"instruction": "Cook the rice. Marinate the beef overnight.", "ingredients": [
+                        ingredient("rice", 1.0, "cup", ""), ingredient("beef", 500.0, "g", ""),
+                        ingredient("onion", 1.0, "piece", "diced")]}
+                ]}),
+            ),
+            (
+                11,
+                json!({"id": 11, "name": "Stir Fry", "servings": 2, "steps": [
+                    {"instruction": "Cook rice until tender.", "ingredients": [
+                        ingredient("rice", 1.0, "cup", ""), ingredient("onion", 1.0, "piece", "sliced")]}
+                ]}),
+            ),
+            (
+                12,
+                json!({"id": 12, "name": "Curry", "servings": 4, "steps": [
+                    {"instruction": "Soak the chickpeas for 8 hours. Simmer with the onion.", "ingredients": [
+                        ingredient("chickpeas", 200.0, "g", ""), ingredient("onion", 2.0, "piece", "chopped")]}
+                ]}),
+            ),
+        ])
+    }
+
+    #[test]
+    fn test_batch_prep_across_meals() {
+        let plans = [
+            plan(1, "2026-10-19", ("Dinner", 3), 10),
+            plan(2, "2026-10-21", ("Dinner", 3), 11),
+            plan(3, "2026-10-22", ("Dinner", 3), 12),
+        ];
+        let menu = weekly_menu(&plans, &recipes(), &options("2026-10-19", "2026-10-25"));
+        let tasks: Vec<_> = menu
+            .prep
+            .tasks
+            .iter()
+            .map(|task| task.task.as_str())
+            .collect();
+        assert_eq!(tasks, ["Cook rice for Mon+Wed (3 cup)"]);
+        let rice = &menu.prep.tasks[0];
+        assert_eq!(rice.meals, ["Mon dinner", "Wed dinner"]);
+        assert_eq!(
+            rice.instruction.as_deref(),
+            Some("Cook the rice. Marinate the beef overnight.")
+        );
+
+        let later: Vec<_> = menu
+            .prep
+            .later
+            .iter()
+            .map(|task| (task.task.as_str(), task.start.as_deref()))
+            .collect();
+        assert_eq!(
+            later,
+            [
+                (
+                    "Start marinating for Mon dinner (12h ahead)",
+                    Some("Mon 06:00")
+                ),
+                ("Start soaking for Thu dinner (8h ahead)", Some("Thu 10:00")),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_prep_order() {
+        let plans = [
+            plan(1, "2026-10-19", ("Dinner", 3), 12),
+            plan(2, "2026-10-20", ("Dinner", 3), 12),
+            plan(3, "2026-10-21", ("Dinner", 3), 10),
+            plan(4, "2026-10-22", ("Dinner", 3), 11),
+        ];
+        let mut options = options("2026-10-19", "2026-10-25");
+        options.prep_date = "2026-10-19".parse().ok();
+        let menu = weekly_menu(&plans, &recipes(), &options);
+        let tasks: Vec<_> = menu
+            .prep
+            .tasks
+            .iter()
+            .map(|task| (task.kind, task.task.as_str()))
+            .collect();
+        assert_eq!(
+            tasks,
+            [
+                (
+                    super::PrepKind::HeadStart,
+                    "Start soaking for Mon dinner (8h ahead)"
+                ),
+                (super::PrepKind::Cook, "Cook rice for Wed+Thu (3 cup)"),
+                (super::PrepKind::Cut, "Chop onion for Mon+Tue (4 piece)"),
+            ]
+        );
+        assert_eq!(menu.prep.later.len(), 2);
+        assert_eq!(menu.prep.later[0].start.as_deref(), Some("Tue 10:00"));
+    }
+}
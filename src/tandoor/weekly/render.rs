@@ -0,0 +1,334 @@
+//! Weekly menu rendering: printable HTML, Markdown and plain text
+
+use serde::{Deserialize, Serialize};
+
+use super::{MenuDay, MenuMeal, PrepSheet, PrepTask, WeeklyMenu};
+use crate::tandoor::shopping::aisle::escape_html;
+use crate::tandoor::text::format_decimal;
+
+/// Output format for a rendered menu
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Deserialize, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum MenuFormat {
+    /// Standalone printable HTML page (print to PDF from a browser)
+    #[default]
+    Html,
+    /// GitHub-flavoured Markdown
+    Markdown,
+    /// Plain text
+    Text,
+}
+
+/// Render the menu and its prep sheet in the requested format
+pub fn render(format: MenuFormat, menu: &WeeklyMenu) -> String {
+    match format {
+        MenuFormat::Html => render_html(menu),
+        MenuFormat::Markdown => render_markdown(menu),
+        MenuFormat::Text => render_text(menu),
+    }
+}
+
+/// `Chili (×4)`
+fn meal_label(meal: &MenuMeal) -> String {
+    format!("{} (×{})", meal.name, format_decimal(meal.servings))
+}
+
+/// `1850 kcal, 90 g protein, 60 g fat, 200 g carbs (1 meal without data)`
+fn nutrition_label(day: &MenuDay) -> String {
+    let missing = match day.missing_nutrition {
+        0 => String::new(),
+        1 => " (1 meal without data)".to_string(),
+        n => format!(" ({n} meals without data)"),
+    };
+    if day.meals.len() == day.missing_nutrition {
+        return if day.meals.is_empty() {
+            String::new()
+        } else {
+            missing.trim().to_string()
+        };
+    }
+    let n = &day.nutrition;
+    format!(
+        "{:.0} kcal, {:.0} g protein, {:.0} g fat, {:.0} g carbs{missing}",
+        n.calories, n.protein, n.fat, n.carbohydrate
+    )
+}
+
+/// `Prep sheet: Sun 2026-10-18`
+fn prep_heading(prep: &PrepSheet) -> String {
+    let weekday = prep
+        .prep_date
+        .parse::<chrono::NaiveDate>()
+        .map(|date| date.format("%a ").to_string())
+        .unwrap_or_default();
+    format!("Prep sheet: {weekday}{}", prep.prep_date)
+}
+
+/// `Wed 06:00: Start marinating for Wed dinner (12h ahead)`
+fn later_label(task: &PrepTask) -> String {
+    task.start.as_ref().map_or_else(
+        || task.task.clone(),
+        |start| format!("{start}: {}", task.task),
+    )
+}
+
+/// Render as a standalone, printable HTML page: the grid in landscape and
+/// the prep sheet on its own page
+pub fn render_html(menu: &WeeklyMenu) -> String {
+    let header = menu
+        .meal_types
+        .iter()
+        .map(|meal_type| format!("<th>{}</th>", escape_html(meal_type)))
+        .collect::<Vec<_>>()
+        .concat();
+    let rows = menu
+        .days
+        .iter()
+        .map(|day| row_html(&menu.meal_types, day))
+        .collect::<Vec<_>>()
+        .concat();
+
+    format!(
+        "<!DOCTYPE html>\n<html lang=\"en\">\n<head>\n<meta charset=\"utf-8\">\n\
+         <title>{title}</title>\n<style>\n\
+         @page {{ size: A4 landscape; margin: 12mm; }}\n\
+         body {{ font-family: sans-serif; margin: 1.5em; }}\n\
+         table {{ border-collapse: collapse; width: 100%; table-layout: fixed; }}\n\
+         th, td {{ border: 1px solid #999; padding: 0.4em; text-align: left; vertical-align: top; }}\n\
+         td.nutrition {{ font-size: 0.85em; color: #444; }}\n\
+         .prep {{ break-before: page; }}\n\
+         .prep ul {{ list-style: none; padding-left: 0; }}\n\
+         .prep li {{ padding: 0.2em 0; }}\n\
+         .step {{ font-size: 0.85em; color: #555; margin-left: 1.6em; }}\n\
+         @media print {{ body {{ margin: 0; }} }}\n\
+         </style>\n</head>\n<body>\n<h1>{title}</h1>\n\
+         <table>\n<thead><tr><th>Day</th>{header}<th>Nutrition (per person)</th></tr></thead>\n\
+         <tbody>\n{rows}</tbody>\n</table>\n{prep}</body>\n</html>\n",
+        title = escape_html(&menu.title),
+        prep = prep_html(&menu.prep),
+    )
+}
+
+/// One day of the grid: a cell per meal type, then the day's nutrition
+fn row_html(meal_types: &[String], day: &MenuDay) -> String {
+    let cells = meal_types
+        .iter()
+        .map(|meal_type| {
+            let meals = day
+                .meals_of(meal_type)
+                .map(|meal| format!("<div>{}</div>", escape_html(&meal_label(meal))))
+                .collect::<Vec<_>>()
+                .concat();
+            format!("<td>{meals}</td>")
+        })
+        .collect::<Vec<_>>()
+        .concat();
+    format!(
+        "<tr><th>{} {}</th>{cells}<td class=\"nutrition\">{}</td></tr>\n",
+        day.weekday,
+        day.date,
+        escape_html(&nutrition_label(day))
+    )
+}
+
+fn prep_html(prep: &PrepSheet) -> String {
+    let tasks = prep
+        .tasks
+        .iter()
+        .map(|task| {
+            let step = task
+                .instruction
+                .as_ref()
+                .map(|step| format!("<div class=\"step\">{}</div>", escape_html(step)))
+                .unwrap_or_default();
+            format!(
+                "<li><label><input type=\"checkbox\"> {}</label>{step}</li>\n",
+                escape_html(&task.task)
+            )
+        })
+        .collect::<Vec<_>>()
+        .concat();
+    let later = if prep.later.is_empty() {
+        String::new()
+    } else {
+        let items = prep
+            .later
+            .iter()
+            .map(|task| format!("<li>{}</li>\n", escape_html(&later_label(task))))
+            .collect::<Vec<_>>()
+            .concat();
+        format!("<h2>Later this week</h2>\n<ul>\n{items}</ul>\n")
+    };
+    let empty = if prep.tasks.is_empty() {
+        "<p>Nothing to prep ahead.</p>\n"
+    } else {
+        ""
+    };
+    format!(
+        "<section class=\"prep\">\n<h1>{}</h1>\n{empty}<ul>\n{tasks}</ul>\n{later}</section>\n",
+        escape_html(&prep_heading(prep))
+    )
+}
+
+/// Escape a Markdown table cell
+fn cell(text: &str) -> String {
+    text.replace('|', "\\|")
+}
+
+/// Render as Markdown: a table, then the prep sheet as a checklist
+pub fn render_markdown(menu: &WeeklyMenu) -> String {
+    let columns = menu.meal_types.len() + 2;
+    let header = std::iter::once("Day")
+        .chain(menu.meal_types.iter().map(String::as_str))
+        .chain(std::iter::once("Nutrition (per person)"))
+        .map(cell)
+        .collect::<Vec<_>>()
+        .join(" | ");
+    let rows = menu
+        .days
+        .iter()
+        .map(|day| {
+            let meals = menu.meal_types.iter().map(|meal_type| {
+                day.meals_of(meal_type)
+                    .map(meal_label)
+                    .collect::<Vec<_>>()
+                    .join("<br>")
+            });
+            let row = std::iter::once(format!("{} {}", day.weekday, day.date))
+                .chain(meals)
+                .chain(std::iter::once(nutrition_label(day)))
+                .map(|text| cell(&text))
+                .collect::<Vec<_>>()
+                .join(" | ");
+            format!("| {row} |\n")
+        })
+        .collect::<Vec<_>>()
+        .concat();
+
+    let tasks = prep_lines(&menu.prep, "- [ ] ", "  ");
+    format!(
+        "# {}\n\n| {header} |\n|{}\n{rows}\n## {}\n\n{tasks}{}",
+        menu.title,
+        "---|".repeat(columns),
+        prep_heading(&menu.prep),
+        later_lines(&menu.prep, "\n### Later this week\n\n", "- ")
+    )
+}
+
+/// Render as plain text: one block per day, then the prep sheet
+pub fn render_text(menu: &WeeklyMenu) -> String {
+    let days = menu
+        .days
+        .iter()
+        .map(|day| {
+            let meals = day
+                .meals
+                .iter()
+                .map(|meal| format!("  {}: {}\n", meal.meal_type, meal_label(meal)))
+                .collect::<Vec<_>>()
+                .concat();
+            let nutrition = Some(nutrition_label(day))
+                .filter(|label| !label.is_empty())
+                .map(|label| format!("  Nutrition: {label}\n"))
+                .unwrap_or_default();
+            format!("\n{} {}\n{meals}{nutrition}", day.weekday, day.date)
+        })
+        .collect::<Vec<_>>()
+        .concat();
+    let heading = prep_heading(&menu.prep).to_uppercase();
+    format!(
+        "{title}\n{underline}\n{days}\n{heading}\n{tasks}{later}",
+        title = menu.title,
+        underline = "=".repeat(menu.title.chars().count()),
+        tasks = prep_lines(&menu.prep, "  [ ] ", "      "),
+        later = later_lines(&menu.prep, "\nLATER THIS WEEK\n", "  "),
+    )
+}
+
+/// Prep tasks as a checklist, each with its step indented below
+fn prep_lines(prep: &PrepSheet, bullet: &str, indent: &str) -> String {
+    if prep.tasks.is_empty() {
+        return "Nothing to prep ahead.\n".to_string();
+    }
+    prep.tasks
+        .iter()
+        .map(|task| {
+            let step = task
+                .instruction
+                .as_ref()
+                .map(|step| format!("{indent}{step}\n"))
+                .unwrap_or_default();
+            format!("{bullet}{}\n{step}", task.task)
+        })
+        .collect::<Vec<_>>()
+        .concat()
+}
+
+fn later_lines(prep: &PrepSheet, heading: &str, bullet: &str) -> String {
+    if prep.later.is_empty() {
+        return String::new();
+    }
+    let items = prep
+        .later
+        .iter()
+        .map(|task| format!("{bullet}{}\n", later_label(task)))
+        .collect::<Vec<_>>()
+        .concat();
+    format!("{heading}{items}")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::tests::{options, plan};
+    use super::super::weekly_menu;
+    use super::*;
+    use serde_json::json;
+    use std::collections::HashMap;
+
+    fn menu() -> WeeklyMenu {
+        let plans = [
+            plan(1, "2026-10-19", ("Dinner", 3), 10),
+            plan(2, "2026-10-19", ("Breakfast", 1), 11),
+        ];
+        let recipes = HashMap::from([
+            (
+                10,
+                json!({"id": 10, "name": "Mac & <Cheese> | Peas", "nutrition": {"calories": 700.0, "proteins": 30.0}}),
+            ),
+            (
+                11,
+                json!({"id": 11, "name": "Oats", "steps": [{"instruction": "Soak the oats overnight."}]}),
+            ),
+        ]);
+        weekly_menu(&plans, &recipes, &options("2026-10-19", "2026-10-20"))
+    }
+
+    #[test]
+    fn test_render_html() {
+        let html = render(MenuFormat::Html, &menu());
+        assert!(html.contains("<th>Day</th><th>Breakfast</th><th>Dinner</th>"));
+        assert!(html.contains("<tr><th>Mon 2026-10-19</th><td><div>Oats (×4)</div></td><td><div>Mac &amp; &lt;Cheese&gt; | Peas (×4)</div></td>"));
+        assert!(html.contains("700 kcal, 30 g protein, 0 g fat, 0 g carbs (1 meal without data)"));
+        assert!(html.contains(
+            "<tr><th>Tue 2026-10-20</th><td></td><td></td><td class=\"nutrition\"></td></tr>"
+        ));
+        assert!(html.contains("<h1>Prep sheet: Sun 2026-10-18</h1>\n<p>Nothing to prep ahead.</p>"));
+        assert!(html.contains("<li>Mon 06:00: Start soaking for Mon breakfast (12h ahead)</li>"));
+        assert!(html.contains("break-before: page"));
+    }
+
+    #[test]
+    fn test_render_markdown_and_text() {
+        let menu = menu();
+        let markdown = render(MenuFormat::Markdown, &menu);
+        assert!(markdown.starts_with("# Menu for 2026-10-19 to 2026-10-20\n\n| Day | Breakfast | Dinner | Nutrition (per person) |\n|---|---|---|---|\n"));
+        assert!(markdown
+            .contains("| Mon 2026-10-19 | Oats (×4) | Mac & <Cheese> \\| Peas (×4) | 700 kcal"));
+        assert!(markdown.contains("\n### Later this week\n\n- Mon 06:00: Start soaking"));
+
+        let text = render(MenuFormat::Text, &menu);
+        assert!(text.contains("\nMon 2026-10-19\n  Breakfast: Oats (×4)\n  Dinner: Mac & <Cheese> | Peas (×4)\n  Nutrition: 700 kcal"));
+        assert!(text
+            .contains("\nTue 2026-10-20\n\nPREP SHEET: SUN 2026-10-18\nNothing to prep ahead.\n"));
+    }
+}
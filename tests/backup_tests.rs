@@ -0,0 +1,337 @@
+//! Tests for full Tandoor backup and restore
+//!
+//! Covers:
+//! - Backing up every resource from a mock instance, with recipe details
+//!   and images, and reading the archive back
+//! - Fetching images stored on another host without the Tandoor token
+//! - Restoring into a second mock instance: new IDs are remapped into meal
+//!   plans, book entries, step links and food links, and existing units are
+//!   reused
+//! - Refusing to restore into an instance that already has recipes
+
+#![allow(clippy::unwrap_used, clippy::indexing_slicing)]
+
+use meal_planner::backup::{
+    create_backup, restore_backup, Backup, BackupError, Resource, RestoreOptions, RestoreReport,
+};
+use meal_planner::tandoor::{TandoorClient, TandoorConfig};
+use serde_json::{json, Value};
+use std::io::Cursor;
+use wiremock::matchers::{body_string_contains, method, path};
+use wiremock::{Mock, MockServer, ResponseTemplate};
+
+const IMAGE: &[u8] = b"\x89PNG\r\n\x1a\nimage";
+
+fn page(results: &Value) -> Value {
+    json!({"count": results.as_array().map_or(0, Vec::len), "next": null, "previous": null, "results": results})
+}
+
+fn client(uri: String) -> TandoorClient {
+    TandoorClient::new(&TandoorConfig {
+        base_url: uri,
+        api_token: "test_token".to_string(),
+    })
+    .unwrap()
+}
+
+async fn mount(server: &MockServer, verb: &str, url_path: &str, status: u16, body: Value) {
+    Mock::given(method(verb))
+        .and(path(url_path))
+        .respond_with(ResponseTemplate::new(status).set_body_json(body))
+        .mount(server)
+        .await;
+}
+
+fn pancakes() -> Value {
+    json!({
+        "id": 5, "name": "Pancakes", "image": "/media/recipes/5.png", "servings": 4,
+        "keywords": [{"id": 1, "name": "breakfast"}],
+        "steps": [{"id": 50, "instruction": "Whisk and fry", "step_recipe": null, "ingredients": [
+            {"id": 500, "food": {"id": 4, "name": "Pancake Mix"}, "unit": {"id": 2, "name": "gram"}, "amount": 200}
+        ]}]
+    })
+}
+
+fn full_breakfast() -> Value {
+    json!({
+        "id": 6, "name": "Full Breakfast", "image": null, "servings": 2,
+        "steps": [{"id": 60, "instruction": "Plate up", "step_recipe": 5, "ingredients": []}]
+    })
+}
+
+/// List responses with one record (or so) of every resource
+fn source_lists() -> Vec<(&'static str, Value)> {
+    vec![
+        ("keyword", page(&json!([{"id": 1, "name": "breakfast"}]))),
+        ("unit", page(&json!([{"id": 2, "name": "gram"}]))),
+        ("property-type", page(&json!([]))),
+        (
+            "supermarket",
+            page(&json!([{"id": 3, "name": "Corner Shop"}])),
+        ),
+        (
+            "food",
+            page(
+                &json!([{"id": 4, "name": "Pancake Mix", "recipe": {"id": 5, "name": "Pancakes"}}]),
+            ),
+        ),
+        // Answered as a plain array
+        ("unit-conversion", json!([])),
+        (
+            "meal-type",
+            page(&json!([{"id": 2, "name": "Breakfast", "time": "08:00:00"}])),
+        ),
+        (
+            "recipe",
+            page(&json!([{"id": 5, "name": "Pancakes"}, {"id": 6, "name": "Full Breakfast"}])),
+        ),
+        (
+            "meal-plan",
+            page(&json!([{
+                "id": 9, "title": "", "servings": 2, "from_date": "2026-10-19T08:00:00Z",
+                "recipe": {"id": 6, "name": "Full Breakfast"},
+                "meal_type": {"id": 2, "name": "Breakfast"}, "shared": []
+            }])),
+        ),
+        (
+            "recipe-book",
+            page(&json!([{"id": 3, "name": "Weekend", "filter": null}])),
+        ),
+        (
+            "recipe-book-entry",
+            page(&json!([{"id": 11, "book": 3, "recipe": 5}])),
+        ),
+        (
+            "shopping-list-entry",
+            page(
+                &json!([{"id": 12, "food": {"id": 4, "name": "Pancake Mix"}, "amount": 1, "list_recipe": 7}]),
+            ),
+        ),
+    ]
+}
+
+/// An instance with one record (or so) of every resource
+async fn mount_source(server: &MockServer) {
+    for (endpoint, body) in source_lists() {
+        mount(server, "GET", &format!("/api/{endpoint}/"), 200, body).await;
+    }
+    mount(server, "GET", "/api/recipe/5/", 200, pancakes()).await;
+    mount(server, "GET", "/api/recipe/6/", 200, full_breakfast()).await;
+    Mock::given(method("GET"))
+        .and(path("/media/recipes/5.png"))
+        .respond_with(ResponseTemplate::new(200).set_body_bytes(IMAGE))
+        .mount(server)
+        .await;
+}
+
+/// An empty instance that already has a "gram" unit
+async fn mount_target(server: &MockServer) {
+    for endpoint in [
+        "keyword",
+        "property-type",
+        "supermarket",
+        "food",
+        "meal-type",
+        "recipe",
+        "recipe-book",
+    ] {
+        mount(
+            server,
+            "GET",
+            &format!("/api/{endpoint}/"),
+            200,
+            page(&json!([])),
+        )
+        .await;
+    }
+    mount(
+        server,
+        "GET",
+        "/api/unit/",
+        200,
+        page(&json!([{"id": 50, "name": "Gram"}])),
+    )
+    .await;
+    mount_created(server).await;
+}
+
+/// Creates answer with the old ID plus 100
+async fn mount_created(server: &MockServer) {
+    let created = [
+        ("keyword", 101),
+        ("supermarket", 103),
+        ("food", 104),
+        ("meal-type", 102),
+        ("meal-plan", 109),
+        ("recipe-book", 103),
+        ("recipe-book-entry", 111),
+        ("shopping-list-entry", 112),
+    ];
+    for (endpoint, id) in created {
+        mount(
+            server,
+            "POST",
+            &format!("/api/{endpoint}/"),
+            201,
+            json!({"id": id}),
+        )
+        .await;
+    }
+    for (name, id) in [("\"Pancakes\"", 105), ("\"Full Breakfast\"", 106)] {
+        Mock::given(method("POST"))
+            .and(path("/api/recipe/"))
+            .and(body_string_contains(name))
+            .respond_with(ResponseTemplate::new(201).set_body_json(json!({"id": id})))
+            .mount(server)
+            .await;
+    }
+    mount(server, "PATCH", "/api/food/104/", 200, json!({"id": 104})).await;
+    mount(
+        server,
+        "PUT",
+        "/api/recipe/105/image/",
+        200,
+        json!({"image": "/media/recipes/105.png", "image_url": null}),
+    )
+    .await;
+}
+
+async fn backup_from(server: &MockServer) -> Backup {
+    let uri = server.uri();
+    let (backup, report) =
+        tokio::task::spawn_blocking(move || create_backup(&client(uri), true).unwrap())
+            .await
+            .unwrap();
+    assert!(report.failed.is_empty(), "{:?}", report.failed);
+    assert_eq!(report.images, 1);
+    backup
+}
+
+async fn restore_into(server: &MockServer, backup: Backup) -> Result<RestoreReport, BackupError> {
+    let uri = server.uri();
+    tokio::task::spawn_blocking(move || {
+        restore_backup(&client(uri), &backup, &RestoreOptions::default())
+    })
+    .await
+    .unwrap()
+}
+
+/// JSON bodies of the requests sent to `url_path`
+async fn bodies(server: &MockServer, verb: &str, url_path: &str) -> Vec<Value> {
+    server
+        .received_requests()
+        .await
+        .unwrap()
+        .iter()
+        .filter(|r| r.method.as_str() == verb && r.url.path() == url_path)
+        .map(|r| serde_json::from_slice(&r.body).unwrap())
+        .collect()
+}
+
+#[tokio::test]
+async fn test_backup_round_trip() {
+    let source = MockServer::start().await;
+    mount_source(&source).await;
+
+    let backup = backup_from(&source).await;
+    let bytes = backup.write(Cursor::new(Vec::new())).unwrap().into_inner();
+    let restored = Backup::read(Cursor::new(bytes)).unwrap();
+
+    assert_eq!(restored.manifest.source, source.uri());
+    assert_eq!(restored.manifest.counts.len(), 12);
+    assert_eq!(restored.records, backup.records);
+    // Recipes are stored in full, with their steps
+    assert_eq!(
+        restored.records(Resource::Recipe),
+        [pancakes(), full_breakfast()]
+    );
+    let image = &restored.images[&5];
+    assert_eq!(
+        (image.file_name.as_str(), image.data.as_slice()),
+        ("5.png", IMAGE)
+    );
+}
+
+#[tokio::test]
+async fn test_backup_image_on_other_host() {
+    let source = MockServer::start().await;
+    let cdn = MockServer::start().await;
+    let mut recipe = pancakes();
+    recipe["image"] = json!(format!("{}/bucket/5.png?X-Amz-Signature=abc", cdn.uri()));
+    mount(&source, "GET", "/api/recipe/5/", 200, recipe).await;
+    mount_source(&source).await;
+    Mock::given(method("GET"))
+        .and(path("/bucket/5.png"))
+        .respond_with(ResponseTemplate::new(200).set_body_bytes(IMAGE))
+        .mount(&cdn)
+        .await;
+
+    let backup = backup_from(&source).await;
+    assert_eq!(backup.images[&5].data, IMAGE);
+
+    let requests = cdn.received_requests().await.unwrap();
+    assert_eq!(requests.len(), 1);
+    assert!(requests[0].headers.get("authorization").is_none());
+    let host = requests[0].headers.get("host").unwrap().to_str().unwrap();
+    assert!(cdn.uri().ends_with(host));
+}
+
+#[tokio::test]
+async fn test_restore_remaps_ids() {
+    let source = MockServer::start().await;
+    mount_source(&source).await;
+    let target = MockServer::start().await;
+    mount_target(&target).await;
+
+    let report = restore_into(&target, backup_from(&source).await)
+        .await
+        .unwrap();
+    assert!(report.failed.is_empty(), "{:?}", report.failed);
+    assert!(report.warnings.is_empty(), "{:?}", report.warnings);
+    assert_eq!(report.images, 1);
+    let units = report
+        .resources
+        .iter()
+        .find(|r| r.resource == Resource::Unit);
+    assert_eq!(units.map(|r| (r.created, r.existing)), Some((0, 1)));
+
+    let recipes = bodies(&target, "POST", "/api/recipe/").await;
+    let names: Vec<_> = recipes
+        .iter()
+        .map(|r| r["name"].as_str().unwrap())
+        .collect();
+    assert_eq!(names, ["Pancakes", "Full Breakfast"]);
+    assert_eq!(recipes[1]["steps"][0]["step_recipe"], 105);
+    assert!(recipes[0]["steps"][0].get("id").is_none());
+
+    let plans = bodies(&target, "POST", "/api/meal-plan/").await;
+    assert_eq!(
+        (&plans[0]["recipe"], &plans[0]["meal_type"]),
+        (&json!(106), &json!(102))
+    );
+    let entries = bodies(&target, "POST", "/api/recipe-book-entry/").await;
+    assert_eq!(entries, [json!({"book": 103, "recipe": 105})]);
+    let food_links = bodies(&target, "PATCH", "/api/food/104/").await;
+    assert_eq!(
+        food_links,
+        [json!({"recipe": {"id": 105, "name": "Pancakes"}})]
+    );
+}
+
+#[tokio::test]
+async fn test_restore_refuses_non_empty_instance() {
+    let target = MockServer::start().await;
+    mount(
+        &target,
+        "GET",
+        "/api/recipe/",
+        200,
+        json!({"count": 3, "next": null, "previous": null, "results": []}),
+    )
+    .await;
+
+    let result = restore_into(&target, Backup::default()).await;
+    assert!(matches!(result, Err(BackupError::NotEmpty(3))));
+    let posts = target.received_requests().await.unwrap();
+    assert!(posts.iter().all(|r| r.method.as_str() == "GET"));
+}
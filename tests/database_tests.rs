@@ -1,13 +1,22 @@
 //! Database Layer Integration Tests
 //!
 //! Comprehensive tests for database operations including:
-//! - `TokenStorage` CRUD operations
+//! - `TokenStorage` and `DiarySyncStorage` CRUD operations
 //! - Encryption/decryption round-trips
 //! - Error handling for missing database
 //! - Concurrent access patterns
 //!
 //! Test database configuration:
 //! - Uses TEST_DATABASE_URL environment variable (falls back to `DATABASE_URL`)
+//! - Runs against Postgres or SQLite, picked by the URL scheme; run the suite
+//!   once per backend:
+//!
+//!   ```bash
+//!   TEST_DATABASE_URL=postgres://localhost/meal_test cargo test --test database_tests -- --ignored --test-threads=1
+//!   TEST_DATABASE_URL=sqlite::memory: cargo test --test database_tests -- --ignored --test-threads=1
+//!   ```
+//!
+//! - Creates the schema from the embedded migrations (`meal_planner::db`)
 //! - Creates isolated test data for each test
 //! - Cleans up test data after each test
 
@@ -20,13 +29,16 @@
 #![allow(clippy::wildcard_enum_match_arm)]
 #![allow(clippy::inefficient_to_string)]
 
-use chrono::{Duration, Utc};
+use chrono::{DateTime, Duration, Utc};
+use meal_planner::db::{Backend, Database, MigrationState};
 use meal_planner::fatsecret::{
     core::{AccessToken, RequestToken},
-    generate_key, StorageError, TokenStorage, TokenValidity,
+    encrypt, generate_key, DiarySyncStorage, DiarySyncStore, EncryptionKey, KeyRing,
+    RotationReport, StorageError, TableRotation, TokenStorage, TokenStore, TokenValidity,
+    DEFAULT_USER,
 };
 use serial_test::serial;
-use sqlx::{PgPool, Row};
+use sqlx::Row;
 use std::env;
 use std::sync::Arc;
 use tokio::sync::Semaphore;
@@ -45,12 +57,36 @@ fn get_test_database_url() -> String {
         .expect("TEST_DATABASE_URL or DATABASE_URL must be set for integration tests")
 }
 
-/// Create a test database pool
-async fn create_test_pool() -> PgPool {
+/// Connect to the test database and migrate the schema to the latest version
+async fn create_test_pool() -> Database {
     let url = get_test_database_url();
-    PgPool::connect(&url)
+    let db = Database::connect(&url)
         .await
-        .expect("Failed to connect to test database")
+        .expect("Failed to connect to test database");
+    db.run_migrations().await.expect("Failed to run migrations");
+    db
+}
+
+/// Run a statement on the test database, whichever backend it uses
+macro_rules! execute {
+    ($db:expr, $sql:expr $(, $bind:expr)* $(,)?) => {
+        match &$db {
+            Database::Postgres(pool) => sqlx::query($sql)$(.bind($bind))*.execute(pool).await.map(|_| ()),
+            Database::Sqlite(pool) => sqlx::query($sql)$(.bind($bind))*.execute(pool).await.map(|_| ()),
+        }
+        .expect("Failed to execute statement")
+    };
+}
+
+/// Fetch one value from the test database, whichever backend it uses
+macro_rules! fetch_scalar {
+    ($db:expr, $ty:ty, $sql:expr $(, $bind:expr)* $(,)?) => {
+        match &$db {
+            Database::Postgres(pool) => sqlx::query_scalar::<_, $ty>($sql)$(.bind($bind))*.fetch_one(pool).await,
+            Database::Sqlite(pool) => sqlx::query_scalar::<_, $ty>($sql)$(.bind($bind))*.fetch_one(pool).await,
+        }
+        .expect("Failed to fetch value")
+    };
 }
 
 /// Setup encryption key for tests
@@ -66,17 +102,11 @@ fn cleanup_encryption() {
 }
 
 /// Clear all OAuth tokens from test database
-async fn cleanup_test_data(pool: &PgPool) {
-    // Delete all tokens (both pending and access)
-    sqlx::query("DELETE FROM fatsecret_oauth_pending")
-        .execute(pool)
-        .await
-        .expect("Failed to cleanup pending tokens");
-
-    sqlx::query("DELETE FROM fatsecret_oauth_token")
-        .execute(pool)
-        .await
-        .expect("Failed to cleanup access tokens");
+async fn cleanup_test_data(db: &Database) {
+    // Delete all tokens (both pending and access) and the sync ledger
+    execute!(db, "DELETE FROM fatsecret_oauth_pending");
+    execute!(db, "DELETE FROM fatsecret_oauth_token");
+    execute!(db, "DELETE FROM fatsecret_diary_sync");
 }
 
 // =============================================================================
@@ -126,10 +156,10 @@ mod fixtures {
 #[serial]
 async fn test_store_and_get_pending_token() {
     let _key = setup_encryption();
-    let pool = create_test_pool().await;
-    cleanup_test_data(&pool).await;
+    let db = create_test_pool().await;
+    cleanup_test_data(&db).await;
 
-    let storage = TokenStorage::new(pool.clone());
+    let storage = TokenStorage::new(db.clone());
     let token = fixtures::test_request_token();
 
     // Store pending token
@@ -150,7 +180,7 @@ async fn test_store_and_get_pending_token() {
     assert_eq!(retrieved.oauth_token_secret, token.oauth_token_secret);
     assert!(retrieved.oauth_callback_confirmed);
 
-    cleanup_test_data(&pool).await;
+    cleanup_test_data(&db).await;
     cleanup_encryption();
 }
 
@@ -159,10 +189,10 @@ async fn test_store_and_get_pending_token() {
 #[serial]
 async fn test_get_nonexistent_pending_token() {
     let _key = setup_encryption();
-    let pool = create_test_pool().await;
-    cleanup_test_data(&pool).await;
+    let db = create_test_pool().await;
+    cleanup_test_data(&db).await;
 
-    let storage = TokenStorage::new(pool.clone());
+    let storage = TokenStorage::new(db.clone());
 
     // Try to get a token that doesn't exist
     let result = storage
@@ -172,7 +202,7 @@ async fn test_get_nonexistent_pending_token() {
 
     assert!(result.is_none());
 
-    cleanup_test_data(&pool).await;
+    cleanup_test_data(&db).await;
     cleanup_encryption();
 }
 
@@ -181,10 +211,10 @@ async fn test_get_nonexistent_pending_token() {
 #[serial]
 async fn test_update_pending_token_on_conflict() {
     let _key = setup_encryption();
-    let pool = create_test_pool().await;
-    cleanup_test_data(&pool).await;
+    let db = create_test_pool().await;
+    cleanup_test_data(&db).await;
 
-    let storage = TokenStorage::new(pool.clone());
+    let storage = TokenStorage::new(db.clone());
     let token = fixtures::test_request_token();
 
     // Store first version
@@ -214,7 +244,7 @@ async fn test_update_pending_token_on_conflict() {
 
     assert_eq!(retrieved.oauth_token_secret, "updated_secret_999");
 
-    cleanup_test_data(&pool).await;
+    cleanup_test_data(&db).await;
     cleanup_encryption();
 }
 
@@ -223,10 +253,10 @@ async fn test_update_pending_token_on_conflict() {
 #[serial]
 async fn test_delete_pending_token() {
     let _key = setup_encryption();
-    let pool = create_test_pool().await;
-    cleanup_test_data(&pool).await;
+    let db = create_test_pool().await;
+    cleanup_test_data(&db).await;
 
-    let storage = TokenStorage::new(pool.clone());
+    let storage = TokenStorage::new(db.clone());
     let token = fixtures::test_request_token();
 
     // Store and verify
@@ -244,7 +274,7 @@ async fn test_delete_pending_token() {
     let deleted = storage.get_pending_token(&token.oauth_token).await.unwrap();
     assert!(deleted.is_none());
 
-    cleanup_test_data(&pool).await;
+    cleanup_test_data(&db).await;
     cleanup_encryption();
 }
 
@@ -253,10 +283,10 @@ async fn test_delete_pending_token() {
 #[serial]
 async fn test_get_latest_pending_token() {
     let _key = setup_encryption();
-    let pool = create_test_pool().await;
-    cleanup_test_data(&pool).await;
+    let db = create_test_pool().await;
+    cleanup_test_data(&db).await;
 
-    let storage = TokenStorage::new(pool.clone());
+    let storage = TokenStorage::new(db.clone());
 
     // Store first token
     let token1 = fixtures::test_request_token();
@@ -278,7 +308,7 @@ async fn test_get_latest_pending_token() {
 
     assert_eq!(latest.oauth_token, token2.oauth_token);
 
-    cleanup_test_data(&pool).await;
+    cleanup_test_data(&db).await;
     cleanup_encryption();
 }
 
@@ -287,10 +317,10 @@ async fn test_get_latest_pending_token() {
 #[serial]
 async fn test_get_latest_pending_token_when_none_exist() {
     let _key = setup_encryption();
-    let pool = create_test_pool().await;
-    cleanup_test_data(&pool).await;
+    let db = create_test_pool().await;
+    cleanup_test_data(&db).await;
 
-    let storage = TokenStorage::new(pool.clone());
+    let storage = TokenStorage::new(db.clone());
 
     let result = storage
         .get_latest_pending_token()
@@ -299,7 +329,7 @@ async fn test_get_latest_pending_token_when_none_exist() {
 
     assert!(result.is_none());
 
-    cleanup_test_data(&pool).await;
+    cleanup_test_data(&db).await;
     cleanup_encryption();
 }
 
@@ -312,10 +342,10 @@ async fn test_get_latest_pending_token_when_none_exist() {
 #[serial]
 async fn test_store_and_get_access_token() {
     let _key = setup_encryption();
-    let pool = create_test_pool().await;
-    cleanup_test_data(&pool).await;
+    let db = create_test_pool().await;
+    cleanup_test_data(&db).await;
 
-    let storage = TokenStorage::new(pool.clone());
+    let storage = TokenStorage::new(db.clone());
     let token = fixtures::test_access_token();
 
     // Store access token
@@ -335,7 +365,7 @@ async fn test_store_and_get_access_token() {
     assert_eq!(retrieved.oauth_token, token.oauth_token);
     assert_eq!(retrieved.oauth_token_secret, token.oauth_token_secret);
 
-    cleanup_test_data(&pool).await;
+    cleanup_test_data(&db).await;
     cleanup_encryption();
 }
 
@@ -344,10 +374,10 @@ async fn test_store_and_get_access_token() {
 #[serial]
 async fn test_get_access_token_when_none_exists() {
     let _key = setup_encryption();
-    let pool = create_test_pool().await;
-    cleanup_test_data(&pool).await;
+    let db = create_test_pool().await;
+    cleanup_test_data(&db).await;
 
-    let storage = TokenStorage::new(pool.clone());
+    let storage = TokenStorage::new(db.clone());
 
     let result = storage
         .get_access_token()
@@ -356,7 +386,7 @@ async fn test_get_access_token_when_none_exists() {
 
     assert!(result.is_none());
 
-    cleanup_test_data(&pool).await;
+    cleanup_test_data(&db).await;
     cleanup_encryption();
 }
 
@@ -365,10 +395,10 @@ async fn test_get_access_token_when_none_exists() {
 #[serial]
 async fn test_update_access_token_on_conflict() {
     let _key = setup_encryption();
-    let pool = create_test_pool().await;
-    cleanup_test_data(&pool).await;
+    let db = create_test_pool().await;
+    cleanup_test_data(&db).await;
 
-    let storage = TokenStorage::new(pool.clone());
+    let storage = TokenStorage::new(db.clone());
     let token = fixtures::test_access_token();
 
     // Store first token
@@ -388,7 +418,7 @@ async fn test_update_access_token_on_conflict() {
     assert_eq!(retrieved.oauth_token, new_token.oauth_token);
     assert_eq!(retrieved.oauth_token_secret, new_token.oauth_token_secret);
 
-    cleanup_test_data(&pool).await;
+    cleanup_test_data(&db).await;
     cleanup_encryption();
 }
 
@@ -397,10 +427,10 @@ async fn test_update_access_token_on_conflict() {
 #[serial]
 async fn test_delete_access_token() {
     let _key = setup_encryption();
-    let pool = create_test_pool().await;
-    cleanup_test_data(&pool).await;
+    let db = create_test_pool().await;
+    cleanup_test_data(&db).await;
 
-    let storage = TokenStorage::new(pool.clone());
+    let storage = TokenStorage::new(db.clone());
     let token = fixtures::test_access_token();
 
     // Store and verify
@@ -418,7 +448,7 @@ async fn test_delete_access_token() {
     let deleted = storage.get_access_token().await.unwrap();
     assert!(deleted.is_none());
 
-    cleanup_test_data(&pool).await;
+    cleanup_test_data(&db).await;
     cleanup_encryption();
 }
 
@@ -427,21 +457,21 @@ async fn test_delete_access_token() {
 #[serial]
 async fn test_update_last_used() {
     let _key = setup_encryption();
-    let pool = create_test_pool().await;
-    cleanup_test_data(&pool).await;
+    let db = create_test_pool().await;
+    cleanup_test_data(&db).await;
 
-    let storage = TokenStorage::new(pool.clone());
+    let storage = TokenStorage::new(db.clone());
     let token = fixtures::test_access_token();
 
     // Store token
     storage.store_access_token(&token).await.unwrap();
 
     // Get initial last_used_at
-    let row = sqlx::query("SELECT last_used_at FROM fatsecret_oauth_token WHERE id = 1")
-        .fetch_one(&pool)
-        .await
-        .unwrap();
-    let initial_last_used: chrono::DateTime<Utc> = row.get("last_used_at");
+    let initial_last_used = fetch_scalar!(
+        db,
+        DateTime<Utc>,
+        "SELECT last_used_at FROM fatsecret_oauth_token WHERE user_id = 'default'"
+    );
 
     // Wait a moment
     tokio::time::sleep(tokio::time::Duration::from_millis(100)).await;
@@ -450,16 +480,16 @@ async fn test_update_last_used() {
     storage.update_last_used().await.unwrap();
 
     // Get updated last_used_at
-    let row = sqlx::query("SELECT last_used_at FROM fatsecret_oauth_token WHERE id = 1")
-        .fetch_one(&pool)
-        .await
-        .unwrap();
-    let updated_last_used: chrono::DateTime<Utc> = row.get("last_used_at");
+    let updated_last_used = fetch_scalar!(
+        db,
+        DateTime<Utc>,
+        "SELECT last_used_at FROM fatsecret_oauth_token WHERE user_id = 'default'"
+    );
 
     // Verify it was updated
     assert!(updated_last_used > initial_last_used);
 
-    cleanup_test_data(&pool).await;
+    cleanup_test_data(&db).await;
     cleanup_encryption();
 }
 
@@ -472,10 +502,10 @@ async fn test_update_last_used() {
 #[serial]
 async fn test_check_token_validity_not_found() {
     let _key = setup_encryption();
-    let pool = create_test_pool().await;
-    cleanup_test_data(&pool).await;
+    let db = create_test_pool().await;
+    cleanup_test_data(&db).await;
 
-    let storage = TokenStorage::new(pool.clone());
+    let storage = TokenStorage::new(db.clone());
 
     let validity = storage
         .check_token_validity()
@@ -484,7 +514,7 @@ async fn test_check_token_validity_not_found() {
 
     assert_eq!(validity, TokenValidity::NotFound);
 
-    cleanup_test_data(&pool).await;
+    cleanup_test_data(&db).await;
     cleanup_encryption();
 }
 
@@ -493,10 +523,10 @@ async fn test_check_token_validity_not_found() {
 #[serial]
 async fn test_check_token_validity_fresh() {
     let _key = setup_encryption();
-    let pool = create_test_pool().await;
-    cleanup_test_data(&pool).await;
+    let db = create_test_pool().await;
+    cleanup_test_data(&db).await;
 
-    let storage = TokenStorage::new(pool.clone());
+    let storage = TokenStorage::new(db.clone());
     let token = fixtures::test_access_token();
 
     // Store fresh token
@@ -509,7 +539,7 @@ async fn test_check_token_validity_fresh() {
 
     assert_eq!(validity, TokenValidity::Valid);
 
-    cleanup_test_data(&pool).await;
+    cleanup_test_data(&db).await;
     cleanup_encryption();
 }
 
@@ -518,10 +548,10 @@ async fn test_check_token_validity_fresh() {
 #[serial]
 async fn test_check_token_validity_old() {
     let _key = setup_encryption();
-    let pool = create_test_pool().await;
-    cleanup_test_data(&pool).await;
+    let db = create_test_pool().await;
+    cleanup_test_data(&db).await;
 
-    let storage = TokenStorage::new(pool.clone());
+    let storage = TokenStorage::new(db.clone());
     let token = fixtures::test_access_token();
 
     // Store token
@@ -529,11 +559,11 @@ async fn test_check_token_validity_old() {
 
     // Manually set connected_at to 400 days ago
     let old_date = Utc::now() - Duration::days(400);
-    sqlx::query("UPDATE fatsecret_oauth_token SET connected_at = $1 WHERE id = 1")
-        .bind(old_date)
-        .execute(&pool)
-        .await
-        .unwrap();
+    execute!(
+        db,
+        "UPDATE fatsecret_oauth_token SET connected_at = $1 WHERE user_id = 'default'",
+        old_date
+    );
 
     let validity = storage
         .check_token_validity()
@@ -549,7 +579,76 @@ async fn test_check_token_validity_old() {
         _ => panic!("Expected Old validity status"),
     }
 
-    cleanup_test_data(&pool).await;
+    cleanup_test_data(&db).await;
+    cleanup_encryption();
+}
+
+#[tokio::test]
+#[ignore = "requires database connection"]
+#[serial]
+async fn test_check_token_validity_revoked() {
+    let _key = setup_encryption();
+    let db = create_test_pool().await;
+    cleanup_test_data(&db).await;
+
+    let storage = TokenStorage::new(db.clone());
+    let token = fixtures::test_access_token();
+    storage.store_access_token(&token).await.unwrap();
+
+    storage
+        .mark_token_revoked()
+        .await
+        .expect("Should mark token revoked");
+    assert_eq!(
+        storage.check_token_validity().await.unwrap(),
+        TokenValidity::Revoked
+    );
+
+    // A successful use clears the revocation
+    storage.update_last_used().await.unwrap();
+    assert_eq!(
+        storage.check_token_validity().await.unwrap(),
+        TokenValidity::Valid
+    );
+
+    // So does storing a new token
+    storage.mark_token_revoked().await.unwrap();
+    storage.store_access_token(&token).await.unwrap();
+    assert_eq!(
+        storage.check_token_validity().await.unwrap(),
+        TokenValidity::Valid
+    );
+
+    cleanup_test_data(&db).await;
+    cleanup_encryption();
+}
+
+#[tokio::test]
+#[ignore = "requires database connection"]
+#[serial]
+async fn test_mark_token_revoked_is_per_user() {
+    let _key = setup_encryption();
+    let db = create_test_pool().await;
+    cleanup_test_data(&db).await;
+
+    let alex = TokenStorage::new(db.clone()).with_user("alex");
+    let sam = TokenStorage::new(db.clone()).with_user("sam");
+    let token = fixtures::test_access_token();
+    alex.store_access_token(&token).await.unwrap();
+    sam.store_access_token(&token).await.unwrap();
+
+    alex.mark_token_revoked().await.unwrap();
+
+    assert_eq!(
+        alex.check_token_validity().await.unwrap(),
+        TokenValidity::Revoked
+    );
+    assert_eq!(
+        sam.check_token_validity().await.unwrap(),
+        TokenValidity::Valid
+    );
+
+    cleanup_test_data(&db).await;
     cleanup_encryption();
 }
 
@@ -562,10 +661,10 @@ async fn test_check_token_validity_old() {
 #[serial]
 async fn test_pending_token_expires() {
     let _key = setup_encryption();
-    let pool = create_test_pool().await;
-    cleanup_test_data(&pool).await;
+    let db = create_test_pool().await;
+    cleanup_test_data(&db).await;
 
-    let storage = TokenStorage::new(pool.clone());
+    let storage = TokenStorage::new(db.clone());
     let token = fixtures::test_request_token();
 
     // Store token
@@ -573,18 +672,18 @@ async fn test_pending_token_expires() {
 
     // Manually set expires_at to the past
     let past = Utc::now() - Duration::minutes(1);
-    sqlx::query("UPDATE fatsecret_oauth_pending SET expires_at = $1 WHERE oauth_token = $2")
-        .bind(past)
-        .bind(&token.oauth_token)
-        .execute(&pool)
-        .await
-        .unwrap();
+    execute!(
+        db,
+        "UPDATE fatsecret_oauth_pending SET expires_at = $1 WHERE oauth_token = $2",
+        past,
+        &token.oauth_token
+    );
 
     // Try to retrieve - should return None
     let result = storage.get_pending_token(&token.oauth_token).await.unwrap();
     assert!(result.is_none());
 
-    cleanup_test_data(&pool).await;
+    cleanup_test_data(&db).await;
     cleanup_encryption();
 }
 
@@ -593,10 +692,10 @@ async fn test_pending_token_expires() {
 #[serial]
 async fn test_cleanup_expired_tokens() {
     let _key = setup_encryption();
-    let pool = create_test_pool().await;
-    cleanup_test_data(&pool).await;
+    let db = create_test_pool().await;
+    cleanup_test_data(&db).await;
 
-    let storage = TokenStorage::new(pool.clone());
+    let storage = TokenStorage::new(db.clone());
 
     // Store two tokens
     let token1 = fixtures::test_request_token();
@@ -607,12 +706,12 @@ async fn test_cleanup_expired_tokens() {
 
     // Expire token1
     let past = Utc::now() - Duration::minutes(1);
-    sqlx::query("UPDATE fatsecret_oauth_pending SET expires_at = $1 WHERE oauth_token = $2")
-        .bind(past)
-        .bind(&token1.oauth_token)
-        .execute(&pool)
-        .await
-        .unwrap();
+    execute!(
+        db,
+        "UPDATE fatsecret_oauth_pending SET expires_at = $1 WHERE oauth_token = $2",
+        past,
+        &token1.oauth_token
+    );
 
     // Cleanup
     let deleted = storage
@@ -635,7 +734,7 @@ async fn test_cleanup_expired_tokens() {
     assert!(result1.is_none());
     assert!(result2.is_some());
 
-    cleanup_test_data(&pool).await;
+    cleanup_test_data(&db).await;
     cleanup_encryption();
 }
 
@@ -644,10 +743,10 @@ async fn test_cleanup_expired_tokens() {
 #[serial]
 async fn test_cleanup_expired_tokens_when_none() {
     let _key = setup_encryption();
-    let pool = create_test_pool().await;
-    cleanup_test_data(&pool).await;
+    let db = create_test_pool().await;
+    cleanup_test_data(&db).await;
 
-    let storage = TokenStorage::new(pool.clone());
+    let storage = TokenStorage::new(db.clone());
 
     let deleted = storage
         .cleanup_expired_tokens()
@@ -656,7 +755,7 @@ async fn test_cleanup_expired_tokens_when_none() {
 
     assert_eq!(deleted, 0);
 
-    cleanup_test_data(&pool).await;
+    cleanup_test_data(&db).await;
     cleanup_encryption();
 }
 
@@ -669,10 +768,10 @@ async fn test_cleanup_expired_tokens_when_none() {
 #[serial]
 async fn test_encryption_roundtrip_pending_token() {
     let _key = setup_encryption();
-    let pool = create_test_pool().await;
-    cleanup_test_data(&pool).await;
+    let db = create_test_pool().await;
+    cleanup_test_data(&db).await;
 
-    let storage = TokenStorage::new(pool.clone());
+    let storage = TokenStorage::new(db.clone());
     let original_secret = "super_secret_pending_token_456";
 
     let token = RequestToken {
@@ -685,14 +784,12 @@ async fn test_encryption_roundtrip_pending_token() {
     storage.store_pending_token(&token).await.unwrap();
 
     // Verify it's encrypted in the database
-    let row = sqlx::query(
+    let encrypted = fetch_scalar!(
+        db,
+        String,
         "SELECT oauth_token_secret FROM fatsecret_oauth_pending WHERE oauth_token = $1",
-    )
-    .bind(&token.oauth_token)
-    .fetch_one(&pool)
-    .await
-    .unwrap();
-    let encrypted: String = row.get("oauth_token_secret");
+        &token.oauth_token
+    );
 
     // Should NOT be plaintext
     assert_ne!(encrypted, original_secret);
@@ -707,7 +804,7 @@ async fn test_encryption_roundtrip_pending_token() {
     // Should match original
     assert_eq!(retrieved.oauth_token_secret, original_secret);
 
-    cleanup_test_data(&pool).await;
+    cleanup_test_data(&db).await;
     cleanup_encryption();
 }
 
@@ -716,10 +813,10 @@ async fn test_encryption_roundtrip_pending_token() {
 #[serial]
 async fn test_encryption_roundtrip_access_token() {
     let _key = setup_encryption();
-    let pool = create_test_pool().await;
-    cleanup_test_data(&pool).await;
+    let db = create_test_pool().await;
+    cleanup_test_data(&db).await;
 
-    let storage = TokenStorage::new(pool.clone());
+    let storage = TokenStorage::new(db.clone());
     let original_secret = "super_secret_access_token_789";
 
     let token = AccessToken {
@@ -731,11 +828,11 @@ async fn test_encryption_roundtrip_access_token() {
     storage.store_access_token(&token).await.unwrap();
 
     // Verify it's encrypted in the database
-    let row = sqlx::query("SELECT oauth_token_secret FROM fatsecret_oauth_token WHERE id = 1")
-        .fetch_one(&pool)
-        .await
-        .unwrap();
-    let encrypted: String = row.get("oauth_token_secret");
+    let encrypted = fetch_scalar!(
+        db,
+        String,
+        "SELECT oauth_token_secret FROM fatsecret_oauth_token WHERE user_id = 'default'"
+    );
 
     // Should NOT be plaintext
     assert_ne!(encrypted, original_secret);
@@ -746,7 +843,7 @@ async fn test_encryption_roundtrip_access_token() {
     // Should match original
     assert_eq!(retrieved.oauth_token_secret, original_secret);
 
-    cleanup_test_data(&pool).await;
+    cleanup_test_data(&db).await;
     cleanup_encryption();
 }
 
@@ -755,10 +852,10 @@ async fn test_encryption_roundtrip_access_token() {
 #[serial]
 async fn test_encryption_different_ciphertexts() {
     let _key = setup_encryption();
-    let pool = create_test_pool().await;
-    cleanup_test_data(&pool).await;
+    let db = create_test_pool().await;
+    cleanup_test_data(&db).await;
 
-    let storage = TokenStorage::new(pool.clone());
+    let storage = TokenStorage::new(db.clone());
 
     // Store same secret twice
     let token1 = RequestToken {
@@ -777,23 +874,19 @@ async fn test_encryption_different_ciphertexts() {
     storage.store_pending_token(&token2).await.unwrap();
 
     // Get encrypted values from database
-    let row1 = sqlx::query(
+    let encrypted1 = fetch_scalar!(
+        db,
+        String,
         "SELECT oauth_token_secret FROM fatsecret_oauth_pending WHERE oauth_token = $1",
-    )
-    .bind(&token1.oauth_token)
-    .fetch_one(&pool)
-    .await
-    .unwrap();
-    let encrypted1: String = row1.get("oauth_token_secret");
+        &token1.oauth_token
+    );
 
-    let row2 = sqlx::query(
+    let encrypted2 = fetch_scalar!(
+        db,
+        String,
         "SELECT oauth_token_secret FROM fatsecret_oauth_pending WHERE oauth_token = $1",
-    )
-    .bind(&token2.oauth_token)
-    .fetch_one(&pool)
-    .await
-    .unwrap();
-    let encrypted2: String = row2.get("oauth_token_secret");
+        &token2.oauth_token
+    );
 
     // Should be different (due to random nonces)
     assert_ne!(encrypted1, encrypted2);
@@ -813,7 +906,7 @@ async fn test_encryption_different_ciphertexts() {
     assert_eq!(retrieved1.oauth_token_secret, "same_secret");
     assert_eq!(retrieved2.oauth_token_secret, "same_secret");
 
-    cleanup_test_data(&pool).await;
+    cleanup_test_data(&db).await;
     cleanup_encryption();
 }
 
@@ -827,9 +920,14 @@ async fn test_encryption_different_ciphertexts() {
 async fn test_invalid_database_url() {
     let _key = setup_encryption();
 
-    // Try to connect to invalid database
-    let result = PgPool::connect("postgresql://invalid:5432/nonexistent").await;
+    // Try to connect to invalid databases
+    let result = Database::connect("postgresql://invalid:5432/nonexistent").await;
+    assert!(result.is_err());
 
+    let result = Database::connect("sqlite:///nonexistent/dir/meal-planner.db").await;
+    assert!(result.is_err());
+
+    let result = Database::connect("mysql://localhost/meals").await;
     assert!(result.is_err());
 
     cleanup_encryption();
@@ -841,10 +939,10 @@ async fn test_invalid_database_url() {
 async fn test_store_pending_token_without_encryption() {
     cleanup_encryption(); // Ensure no key is set
 
-    let pool = create_test_pool().await;
-    cleanup_test_data(&pool).await;
+    let db = create_test_pool().await;
+    cleanup_test_data(&db).await;
 
-    let storage = TokenStorage::new(pool.clone());
+    let storage = TokenStorage::new(db.clone());
     let token = fixtures::test_request_token();
 
     // Should fail with crypto error
@@ -856,7 +954,7 @@ async fn test_store_pending_token_without_encryption() {
         _ => panic!("Expected CryptoError"),
     }
 
-    cleanup_test_data(&pool).await;
+    cleanup_test_data(&db).await;
 }
 
 #[tokio::test]
@@ -864,21 +962,21 @@ async fn test_store_pending_token_without_encryption() {
 #[serial]
 async fn test_get_pending_token_with_corrupted_data() {
     let _key = setup_encryption();
-    let pool = create_test_pool().await;
-    cleanup_test_data(&pool).await;
+    let db = create_test_pool().await;
+    cleanup_test_data(&db).await;
 
-    let storage = TokenStorage::new(pool.clone());
+    let storage = TokenStorage::new(db.clone());
 
     // Insert corrupted encrypted data directly
-    sqlx::query(
-        "INSERT INTO fatsecret_oauth_pending (oauth_token, oauth_token_secret, expires_at) 
-         VALUES ($1, $2, NOW() + INTERVAL '15 minutes')",
-    )
-    .bind("corrupted_token")
-    .bind("not_valid_encrypted_data!!!")
-    .execute(&pool)
-    .await
-    .unwrap();
+    execute!(
+        db,
+        "INSERT INTO fatsecret_oauth_pending (oauth_token, oauth_token_secret, created_at, expires_at)
+         VALUES ($1, $2, $3, $4)",
+        "corrupted_token",
+        "not_valid_encrypted_data!!!",
+        Utc::now(),
+        Utc::now() + Duration::minutes(15)
+    );
 
     // Try to retrieve - should fail with crypto error
     let result = storage.get_pending_token("corrupted_token").await;
@@ -889,7 +987,7 @@ async fn test_get_pending_token_with_corrupted_data() {
         _ => panic!("Expected CryptoError"),
     }
 
-    cleanup_test_data(&pool).await;
+    cleanup_test_data(&db).await;
     cleanup_encryption();
 }
 
@@ -898,10 +996,10 @@ async fn test_get_pending_token_with_corrupted_data() {
 #[serial]
 async fn test_get_access_token_with_wrong_decryption_key() {
     let _key = setup_encryption();
-    let pool = create_test_pool().await;
-    cleanup_test_data(&pool).await;
+    let db = create_test_pool().await;
+    cleanup_test_data(&db).await;
 
-    let storage = TokenStorage::new(pool.clone());
+    let storage = TokenStorage::new(db.clone());
     let token = fixtures::test_access_token();
 
     // Store with first key
@@ -920,7 +1018,7 @@ async fn test_get_access_token_with_wrong_decryption_key() {
         _ => panic!("Expected CryptoError"),
     }
 
-    cleanup_test_data(&pool).await;
+    cleanup_test_data(&db).await;
     cleanup_encryption();
 }
 
@@ -933,10 +1031,10 @@ async fn test_get_access_token_with_wrong_decryption_key() {
 #[serial]
 async fn test_concurrent_pending_token_writes() {
     let _key = setup_encryption();
-    let pool = create_test_pool().await;
-    cleanup_test_data(&pool).await;
+    let db = create_test_pool().await;
+    cleanup_test_data(&db).await;
 
-    let storage = Arc::new(TokenStorage::new(pool.clone()));
+    let storage = Arc::new(TokenStorage::new(db.clone()));
 
     // Create 10 concurrent write tasks
     let mut handles = vec![];
@@ -972,7 +1070,7 @@ async fn test_concurrent_pending_token_writes() {
         assert!(result.is_some());
     }
 
-    cleanup_test_data(&pool).await;
+    cleanup_test_data(&db).await;
     cleanup_encryption();
 }
 
@@ -981,10 +1079,10 @@ async fn test_concurrent_pending_token_writes() {
 #[serial]
 async fn test_concurrent_access_token_updates() {
     let _key = setup_encryption();
-    let pool = create_test_pool().await;
-    cleanup_test_data(&pool).await;
+    let db = create_test_pool().await;
+    cleanup_test_data(&db).await;
 
-    let storage = Arc::new(TokenStorage::new(pool.clone()));
+    let storage = Arc::new(TokenStorage::new(db.clone()));
 
     // Create 10 concurrent update tasks (all updating same singleton)
     let mut handles = vec![];
@@ -1014,7 +1112,7 @@ async fn test_concurrent_access_token_updates() {
     let result = storage.get_access_token().await.unwrap();
     assert!(result.is_some());
 
-    cleanup_test_data(&pool).await;
+    cleanup_test_data(&db).await;
     cleanup_encryption();
 }
 
@@ -1023,10 +1121,10 @@ async fn test_concurrent_access_token_updates() {
 #[serial]
 async fn test_concurrent_reads_and_writes() {
     let _key = setup_encryption();
-    let pool = create_test_pool().await;
-    cleanup_test_data(&pool).await;
+    let db = create_test_pool().await;
+    cleanup_test_data(&db).await;
 
-    let storage = Arc::new(TokenStorage::new(pool.clone()));
+    let storage = Arc::new(TokenStorage::new(db.clone()));
 
     // Store initial token
     let token = fixtures::test_request_token();
@@ -1072,7 +1170,7 @@ async fn test_concurrent_reads_and_writes() {
         handle.await.expect("Task should complete");
     }
 
-    cleanup_test_data(&pool).await;
+    cleanup_test_data(&db).await;
     cleanup_encryption();
 }
 
@@ -1081,10 +1179,10 @@ async fn test_concurrent_reads_and_writes() {
 #[serial]
 async fn test_rate_limited_concurrent_access() {
     let _key = setup_encryption();
-    let pool = create_test_pool().await;
-    cleanup_test_data(&pool).await;
+    let db = create_test_pool().await;
+    cleanup_test_data(&db).await;
 
-    let storage = Arc::new(TokenStorage::new(pool.clone()));
+    let storage = Arc::new(TokenStorage::new(db.clone()));
     let semaphore = Arc::new(Semaphore::new(3)); // Max 3 concurrent operations
 
     let mut handles = vec![];
@@ -1125,10 +1223,475 @@ async fn test_rate_limited_concurrent_access() {
         assert!(result.is_some());
     }
 
-    cleanup_test_data(&pool).await;
+    cleanup_test_data(&db).await;
+    cleanup_encryption();
+}
+
+// =============================================================================
+// Multi-User Token Storage Tests
+// =============================================================================
+
+#[tokio::test]
+#[ignore = "requires database connection"]
+#[serial]
+async fn test_access_tokens_are_per_user() {
+    let _key = setup_encryption();
+    let db = create_test_pool().await;
+    cleanup_test_data(&db).await;
+
+    let alex = TokenStorage::new(db.clone()).with_user("alex");
+    let sam = TokenStorage::new(db.clone()).with_user("sam");
+    alex.store_access_token(&fixtures::test_access_token())
+        .await
+        .unwrap();
+    sam.store_access_token(&fixtures::alternate_access_token())
+        .await
+        .unwrap();
+
+    let alex_token = alex.get_access_token().await.unwrap().unwrap();
+    let sam_token = sam.get_access_token().await.unwrap().unwrap();
+    assert_eq!(alex_token.oauth_token, "test_access_token_789");
+    assert_eq!(sam_token.oauth_token, "alternate_access_ghi");
+
+    // The default user is separate from named users
+    let default = TokenStorage::new(db.clone());
+    assert_eq!(default.user(), DEFAULT_USER);
+    assert!(default.get_access_token().await.unwrap().is_none());
+    assert_eq!(
+        default.list_users().await.unwrap(),
+        vec!["alex".to_string(), "sam".to_string()]
+    );
+
+    // Disconnecting one user leaves the other connected
+    alex.delete_access_token().await.unwrap();
+    assert!(alex.get_access_token().await.unwrap().is_none());
+    assert!(sam.get_access_token().await.unwrap().is_some());
+
+    cleanup_test_data(&db).await;
+    cleanup_encryption();
+}
+
+#[tokio::test]
+#[ignore = "requires database connection"]
+#[serial]
+async fn test_pending_tokens_belong_to_initiating_user() {
+    let _key = setup_encryption();
+    let db = create_test_pool().await;
+    cleanup_test_data(&db).await;
+
+    let alex = TokenStorage::new(db.clone()).with_user("alex");
+    let sam = TokenStorage::new(db.clone()).with_user("sam");
+    let token = fixtures::test_request_token();
+    alex.store_pending_token(&token).await.unwrap();
+
+    assert_eq!(
+        sam.get_pending_token_user(&token.oauth_token)
+            .await
+            .unwrap()
+            .as_deref(),
+        Some("alex")
+    );
+    assert!(alex
+        .get_pending_token(&token.oauth_token)
+        .await
+        .unwrap()
+        .is_some());
+    assert!(sam
+        .get_pending_token(&token.oauth_token)
+        .await
+        .unwrap()
+        .is_none());
+    assert!(sam.get_latest_pending_token().await.unwrap().is_none());
+
+    // Another user can't delete the pending token
+    sam.delete_pending_token(&token.oauth_token).await.unwrap();
+    assert!(alex.get_latest_pending_token().await.unwrap().is_some());
+
+    cleanup_test_data(&db).await;
+    cleanup_encryption();
+}
+
+#[tokio::test]
+#[ignore = "requires database connection"]
+#[serial]
+async fn test_ensure_schema_migrates_single_user_rows() {
+    let _key = setup_encryption();
+    let db = Database::connect(&get_test_database_url())
+        .await
+        .expect("Failed to connect to test database");
+    // SQLite databases never had the single-user schema
+    let Database::Postgres(pool) = &db else {
+        return;
+    };
+
+    // Recreate the single-user schema, as created before migrations existed
+    for statement in [
+        "DROP TABLE IF EXISTS _sqlx_migrations",
+        "DROP TABLE IF EXISTS fatsecret_diary_sync",
+        "DROP TABLE IF EXISTS fatsecret_oauth_token",
+        "DROP TABLE IF EXISTS fatsecret_oauth_pending",
+        "CREATE TABLE fatsecret_oauth_token (
+            id INTEGER PRIMARY KEY DEFAULT 1 CHECK (id = 1),
+            oauth_token TEXT NOT NULL,
+            oauth_token_secret TEXT NOT NULL,
+            connected_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+            last_used_at TIMESTAMPTZ
+        )",
+        "CREATE TABLE fatsecret_oauth_pending (
+            oauth_token TEXT PRIMARY KEY,
+            oauth_token_secret TEXT NOT NULL,
+            created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+            expires_at TIMESTAMPTZ NOT NULL
+        )",
+    ] {
+        sqlx::query(statement).execute(pool).await.unwrap();
+    }
+    sqlx::query(
+        "INSERT INTO fatsecret_oauth_token (id, oauth_token, oauth_token_secret) VALUES (1, $1, $2)",
+    )
+    .bind("legacy_token")
+    .bind(encrypt("legacy_secret").unwrap())
+    .execute(pool)
+    .await
+    .unwrap();
+    sqlx::query(
+        "INSERT INTO fatsecret_oauth_pending (oauth_token, oauth_token_secret, expires_at) VALUES ($1, $2, $3)",
+    )
+    .bind("legacy_pending")
+    .bind(encrypt("legacy_pending_secret").unwrap())
+    .bind(Utc::now() + Duration::minutes(10))
+    .execute(pool)
+    .await
+    .unwrap();
+
+    let storage = TokenStorage::new(db.clone());
+    storage.ensure_schema().await.unwrap();
+    // Running it again is a no-op
+    storage.ensure_schema().await.unwrap();
+
+    let token = storage.get_access_token().await.unwrap().unwrap();
+    assert_eq!(token.oauth_token, "legacy_token");
+    assert_eq!(token.oauth_token_secret, "legacy_secret");
+    assert_eq!(
+        storage
+            .get_pending_token_user("legacy_pending")
+            .await
+            .unwrap()
+            .as_deref(),
+        Some(DEFAULT_USER)
+    );
+
+    // Other users can now be connected alongside the migrated row
+    let sam = storage.clone().with_user("sam");
+    sam.store_access_token(&fixtures::alternate_access_token())
+        .await
+        .unwrap();
+    assert_eq!(storage.list_users().await.unwrap().len(), 2);
+
+    cleanup_test_data(&db).await;
+    cleanup_encryption();
+}
+
+/// Rotation counts for one table
+fn rotated<'a>(report: &'a RotationReport, table: &str) -> &'a TableRotation {
+    report.tables.iter().find(|t| t.table == table).unwrap()
+}
+
+#[tokio::test]
+#[ignore = "requires database connection"]
+#[serial]
+async fn test_reencrypt_secrets_moves_all_rows_to_new_key() {
+    let old_key = setup_encryption();
+    let db = create_test_pool().await;
+    cleanup_test_data(&db).await;
+
+    let storage = TokenStorage::new(db.clone());
+    storage
+        .store_access_token(&fixtures::test_access_token())
+        .await
+        .unwrap();
+    storage
+        .clone()
+        .with_user("sam")
+        .store_access_token(&fixtures::alternate_access_token())
+        .await
+        .unwrap();
+    storage
+        .store_pending_token(&fixtures::test_request_token())
+        .await
+        .unwrap();
+
+    let new_key = generate_key();
+    let keys = KeyRing::new(EncryptionKey::from_hex(&new_key).unwrap())
+        .with_old_key(EncryptionKey::from_hex(&old_key).unwrap());
+
+    // A dry run reads everything but leaves the rows alone
+    let report = storage
+        .reencrypt_secrets(&keys, true, |_| {})
+        .await
+        .unwrap();
+    assert!(report.dry_run);
+    assert_eq!(rotated(&report, "fatsecret_oauth_token").reencrypted, 2);
+    assert!(storage.get_access_token().await.unwrap().is_some());
+
+    let mut progress = Vec::new();
+    let report = storage
+        .reencrypt_secrets(&keys, false, |p| progress.push((p.table, p.done, p.total)))
+        .await
+        .unwrap();
+    assert_eq!(report.key_id, keys.current_key_id());
+    assert_eq!(rotated(&report, "fatsecret_oauth_token").reencrypted, 2);
+    assert_eq!(rotated(&report, "fatsecret_oauth_pending").reencrypted, 1);
+    assert_eq!(
+        progress,
+        vec![
+            ("fatsecret_oauth_token", 1, 2),
+            ("fatsecret_oauth_token", 2, 2),
+            ("fatsecret_oauth_pending", 1, 1),
+        ]
+    );
+
+    // The old key is no longer needed
+    env::set_var("OAUTH_ENCRYPTION_KEY", &new_key);
+    let token = storage.get_access_token().await.unwrap().unwrap();
+    assert_eq!(
+        token.oauth_token_secret,
+        fixtures::test_access_token().oauth_token_secret
+    );
+    let pending = storage
+        .get_pending_token(&fixtures::test_request_token().oauth_token)
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(
+        pending.oauth_token_secret,
+        fixtures::test_request_token().oauth_token_secret
+    );
+
+    // Running again finds nothing left to do
+    let report = storage
+        .reencrypt_secrets(&keys, false, |_| {})
+        .await
+        .unwrap();
+    assert_eq!(rotated(&report, "fatsecret_oauth_token").already_current, 2);
+    assert_eq!(rotated(&report, "fatsecret_oauth_token").reencrypted, 0);
+
+    cleanup_test_data(&db).await;
+    cleanup_encryption();
+}
+
+#[tokio::test]
+#[ignore = "requires database connection"]
+#[serial]
+async fn test_reencrypt_secrets_is_all_or_nothing() {
+    let old_key = setup_encryption();
+    let db = create_test_pool().await;
+    cleanup_test_data(&db).await;
+
+    let storage = TokenStorage::new(db.clone());
+    storage
+        .store_access_token(&fixtures::test_access_token())
+        .await
+        .unwrap();
+    execute!(
+        db,
+        "INSERT INTO fatsecret_oauth_pending (oauth_token, oauth_token_secret, user_id, created_at, expires_at) VALUES ($1, $2, $3, $4, $5)",
+        "unreadable",
+        KeyRing::new(EncryptionKey::from_hex(&generate_key()).unwrap())
+            .encrypt("lost")
+            .unwrap(),
+        DEFAULT_USER,
+        Utc::now(),
+        Utc::now() + Duration::minutes(10)
+    );
+
+    let keys = KeyRing::new(EncryptionKey::from_hex(&generate_key()).unwrap())
+        .with_old_key(EncryptionKey::from_hex(&old_key).unwrap());
+    let result = storage.reencrypt_secrets(&keys, false, |_| {}).await;
+    assert!(
+        matches!(&result, Err(StorageError::CryptoError(msg)) if msg.contains("fatsecret_oauth_pending row unreadable")),
+        "unexpected result: {result:?}"
+    );
+
+    // The access token was rolled back to the old key
+    let token = storage.get_access_token().await.unwrap().unwrap();
+    assert_eq!(
+        token.oauth_token_secret,
+        fixtures::test_access_token().oauth_token_secret
+    );
+
+    cleanup_test_data(&db).await;
+    cleanup_encryption();
+}
+
+// =============================================================================
+// Schema Migration Tests
+// =============================================================================
+
+/// Whether a table exists in the current schema
+async fn table_exists(db: &Database, table: &str) -> bool {
+    let sql = match db.backend() {
+        Backend::Postgres => "SELECT COUNT(*) FROM information_schema.tables WHERE table_schema = current_schema() AND table_name = $1",
+        Backend::Sqlite => "SELECT COUNT(*) FROM sqlite_master WHERE type = 'table' AND name = $1",
+    };
+    fetch_scalar!(db, i64, sql, table) > 0
+}
+
+#[tokio::test]
+#[ignore = "requires database connection"]
+#[serial]
+async fn test_migrations_are_all_applied() {
+    let db = create_test_pool().await;
+
+    let status = db.migration_status().await.unwrap();
+    assert!(!status.is_empty());
+    assert!(status.iter().all(|m| m.state == MigrationState::Applied));
+
+    // Running them again is a no-op
+    db.run_migrations().await.unwrap();
+    for table in [
+        "fatsecret_oauth_pending",
+        "fatsecret_oauth_token",
+        "fatsecret_diary_sync",
+    ] {
+        assert!(table_exists(&db, table).await, "{table} missing");
+    }
+}
+
+#[tokio::test]
+#[ignore = "requires database connection"]
+#[serial]
+async fn test_migrations_revert_and_reapply() {
+    let db = create_test_pool().await;
+    let latest = db.migration_status().await.unwrap().last().unwrap().version;
+
+    // Down without a target reverts only the latest migration
+    let reverted = db.revert_migrations(None).await.unwrap();
+    assert_eq!(reverted, vec![latest]);
+    let status = db.migration_status().await.unwrap();
+    assert_eq!(status.last().unwrap().state, MigrationState::Pending);
+    assert!(status
+        .iter()
+        .rev()
+        .skip(1)
+        .all(|m| m.state == MigrationState::Applied));
+
+    // Down to 0 removes every table
+    db.revert_migrations(Some(0)).await.unwrap();
+    assert!(db
+        .migration_status()
+        .await
+        .unwrap()
+        .iter()
+        .all(|m| m.state == MigrationState::Pending));
+    assert!(!table_exists(&db, "fatsecret_oauth_token").await);
+    assert!(!table_exists(&db, "fatsecret_diary_sync").await);
+
+    db.run_migrations().await.unwrap();
+    assert!(table_exists(&db, "fatsecret_oauth_token").await);
+}
+
+#[tokio::test]
+#[ignore = "requires database connection"]
+#[serial]
+async fn test_reverting_per_user_tokens_keeps_default_token() {
+    let _key = setup_encryption();
+    let db = create_test_pool().await;
+    // The per-user migration only exists for Postgres
+    let Database::Postgres(pool) = &db else {
+        return;
+    };
+    cleanup_test_data(&db).await;
+
+    let storage = TokenStorage::new(db.clone());
+    storage
+        .store_access_token(&fixtures::test_access_token())
+        .await
+        .unwrap();
+    storage
+        .clone()
+        .with_user("sam")
+        .store_access_token(&fixtures::alternate_access_token())
+        .await
+        .unwrap();
+
+    // Back to the single-user schema
+    db.revert_migrations(Some(1)).await.unwrap();
+    let rows = sqlx::query("SELECT id, oauth_token FROM fatsecret_oauth_token")
+        .fetch_all(pool)
+        .await
+        .unwrap();
+    assert_eq!(rows.len(), 1);
+    let row = rows.first().unwrap();
+    assert_eq!(row.get::<i32, _>("id"), 1);
+    assert_eq!(
+        row.get::<String, _>("oauth_token"),
+        fixtures::test_access_token().oauth_token
+    );
+
+    // And forward again
+    db.run_migrations().await.unwrap();
+    let token = storage.get_access_token().await.unwrap().unwrap();
+    assert_eq!(token.oauth_token, fixtures::test_access_token().oauth_token);
+
+    cleanup_test_data(&db).await;
     cleanup_encryption();
 }
 
+// =============================================================================
+// DiarySyncStorage Tests
+// =============================================================================
+
+#[tokio::test]
+#[ignore = "requires database connection"]
+#[serial]
+async fn test_diary_sync_claim_is_idempotent() {
+    let db = create_test_pool().await;
+    cleanup_test_data(&db).await;
+
+    let ledger = DiarySyncStorage::new(db.clone());
+    ledger.ensure_schema().await.unwrap();
+    assert!(!ledger.is_synced("plan-1:20088").await.unwrap());
+
+    assert!(ledger.claim("plan-1:20088", 1, 20088).await.unwrap());
+    assert!(!ledger.claim("plan-1:20088", 1, 20088).await.unwrap());
+    assert!(ledger.is_synced("plan-1:20088").await.unwrap());
+
+    ledger.complete("plan-1:20088", "entry-42").await.unwrap();
+    let food_entry_id = fetch_scalar!(
+        db,
+        Option<String>,
+        "SELECT food_entry_id FROM fatsecret_diary_sync WHERE idempotency_key = $1",
+        "plan-1:20088"
+    );
+    assert_eq!(food_entry_id.as_deref(), Some("entry-42"));
+
+    cleanup_test_data(&db).await;
+}
+
+#[tokio::test]
+#[ignore = "requires database connection"]
+#[serial]
+async fn test_diary_sync_release_only_drops_incomplete_claims() {
+    let db = create_test_pool().await;
+    cleanup_test_data(&db).await;
+
+    let ledger = DiarySyncStorage::new(db.clone());
+    ledger.claim("failed", 1, 20088).await.unwrap();
+    ledger.claim("done", 2, 20088).await.unwrap();
+    ledger.complete("done", "entry-7").await.unwrap();
+
+    ledger.release("failed").await.unwrap();
+    ledger.release("done").await.unwrap();
+
+    assert!(!ledger.is_synced("failed").await.unwrap());
+    assert!(ledger.is_synced("done").await.unwrap());
+    // A released key can be claimed again on the next run
+    assert!(ledger.claim("failed", 1, 20088).await.unwrap());
+
+    cleanup_test_data(&db).await;
+}
+
 // =============================================================================
 // Edge Cases and Data Integrity Tests
 // =============================================================================
@@ -1138,10 +1701,10 @@ async fn test_rate_limited_concurrent_access() {
 #[serial]
 async fn test_unicode_in_token_secrets() {
     let _key = setup_encryption();
-    let pool = create_test_pool().await;
-    cleanup_test_data(&pool).await;
+    let db = create_test_pool().await;
+    cleanup_test_data(&db).await;
 
-    let storage = TokenStorage::new(pool.clone());
+    let storage = TokenStorage::new(db.clone());
 
     let token = AccessToken {
         oauth_token: "unicode_token".to_string(),
@@ -1154,7 +1717,7 @@ async fn test_unicode_in_token_secrets() {
 
     assert_eq!(retrieved.oauth_token_secret, token.oauth_token_secret);
 
-    cleanup_test_data(&pool).await;
+    cleanup_test_data(&db).await;
     cleanup_encryption();
 }
 
@@ -1163,10 +1726,10 @@ async fn test_unicode_in_token_secrets() {
 #[serial]
 async fn test_very_long_token_secrets() {
     let _key = setup_encryption();
-    let pool = create_test_pool().await;
-    cleanup_test_data(&pool).await;
+    let db = create_test_pool().await;
+    cleanup_test_data(&db).await;
 
-    let storage = TokenStorage::new(pool.clone());
+    let storage = TokenStorage::new(db.clone());
 
     // Create a very long secret (1000 chars)
     let long_secret = "x".repeat(1000);
@@ -1183,7 +1746,7 @@ async fn test_very_long_token_secrets() {
     assert_eq!(retrieved.oauth_token_secret, long_secret);
     assert_eq!(retrieved.oauth_token_secret.len(), 1000);
 
-    cleanup_test_data(&pool).await;
+    cleanup_test_data(&db).await;
     cleanup_encryption();
 }
 
@@ -1192,10 +1755,10 @@ async fn test_very_long_token_secrets() {
 #[serial]
 async fn test_empty_token_secret() {
     let _key = setup_encryption();
-    let pool = create_test_pool().await;
-    cleanup_test_data(&pool).await;
+    let db = create_test_pool().await;
+    cleanup_test_data(&db).await;
 
-    let storage = TokenStorage::new(pool.clone());
+    let storage = TokenStorage::new(db.clone());
 
     let token = AccessToken {
         oauth_token: "empty_secret_token".to_string(),
@@ -1208,7 +1771,7 @@ async fn test_empty_token_secret() {
 
     assert_eq!(retrieved.oauth_token_secret, "");
 
-    cleanup_test_data(&pool).await;
+    cleanup_test_data(&db).await;
     cleanup_encryption();
 }
 
@@ -1217,10 +1780,10 @@ async fn test_empty_token_secret() {
 #[serial]
 async fn test_special_characters_in_token() {
     let _key = setup_encryption();
-    let pool = create_test_pool().await;
-    cleanup_test_data(&pool).await;
+    let db = create_test_pool().await;
+    cleanup_test_data(&db).await;
 
-    let storage = TokenStorage::new(pool.clone());
+    let storage = TokenStorage::new(db.clone());
 
     let token = RequestToken {
         oauth_token: "token-with.special_chars~123".to_string(),
@@ -1239,7 +1802,7 @@ async fn test_special_characters_in_token() {
     assert_eq!(retrieved.oauth_token, token.oauth_token);
     assert_eq!(retrieved.oauth_token_secret, token.oauth_token_secret);
 
-    cleanup_test_data(&pool).await;
+    cleanup_test_data(&db).await;
     cleanup_encryption();
 }
 
@@ -0,0 +1,193 @@
+//! Tests for recipe export against a mock Tandoor server
+//!
+//! `tests/fixtures/export/tandoor_recipe.json` is a full `/api/recipe/{id}/`
+//! response. Covers:
+//! - Exporting a recipe book in entry order, skipping recipes that fail
+//! - Exporting by keyword name
+//! - JSON-LD that the schema.org scraper reads back
+
+#![allow(clippy::unwrap_used, clippy::indexing_slicing)]
+
+use meal_planner::export::{
+    book_recipe_ids, export_recipes, keyword_recipe_ids, ExportError, ExportFormat,
+};
+use meal_planner::scrape::recipe_from_schema;
+use meal_planner::tandoor::{RecipeDetail, TandoorClient, TandoorConfig};
+use serde_json::json;
+use std::path::PathBuf;
+use wiremock::matchers::{method, path, query_param};
+use wiremock::{Mock, MockServer, ResponseTemplate};
+
+const RECIPE: &str = include_str!("fixtures/export/tandoor_recipe.json");
+
+fn client(uri: String) -> TandoorClient {
+    TandoorClient::new(&TandoorConfig {
+        base_url: uri,
+        api_token: "test_token".to_string(),
+    })
+    .unwrap()
+}
+
+fn output_dir(name: &str) -> PathBuf {
+    let dir = std::env::temp_dir().join(format!("{name}-{}", std::process::id()));
+    if dir.exists() {
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+    dir
+}
+
+async fn mount_json(server: &MockServer, url_path: &str, body: serde_json::Value) {
+    Mock::given(method("GET"))
+        .and(path(url_path))
+        .respond_with(ResponseTemplate::new(200).set_body_json(body))
+        .mount(server)
+        .await;
+}
+
+/// Book 3 holds recipes 7 and 8 (by entry position); recipe 8 no longer exists
+async fn mount_book(server: &MockServer) {
+    let page = |results: serde_json::Value| json!({"count": 3, "next": null, "previous": null, "results": results});
+    mount_json(
+        server,
+        "/api/recipe-book-entry/",
+        page(json!([
+            {"id": 1, "recipe_book": 3, "recipe": 8, "position": 2},
+            {"id": 2, "recipe_book": 4, "recipe": 9, "position": 0},
+            {"id": 3, "recipe_book": 3, "recipe": 7, "position": 1}
+        ])),
+    )
+    .await;
+    mount_json(
+        server,
+        "/api/recipe/7/",
+        serde_json::from_str(RECIPE).unwrap(),
+    )
+    .await;
+    Mock::given(method("GET"))
+        .and(path("/api/recipe/8/"))
+        .respond_with(ResponseTemplate::new(404).set_body_string("Not found"))
+        .mount(server)
+        .await;
+}
+
+#[tokio::test]
+async fn test_export_recipe_book() {
+    let server = MockServer::start().await;
+    mount_book(&server).await;
+
+    let dir = output_dir("export-book");
+    let task_dir = dir.clone();
+    let uri = server.uri();
+    let (ids, report) = tokio::task::spawn_blocking(move || {
+        let client = client(uri);
+        let ids = book_recipe_ids(&client, 3).unwrap();
+        let report = export_recipes(&client, &ids, &task_dir, &ExportFormat::ALL).unwrap();
+        (ids, report)
+    })
+    .await
+    .unwrap();
+
+    assert_eq!(ids, [7, 8]);
+    assert_eq!(report.exported.len(), 1);
+    assert_eq!(report.failed.len(), 1);
+    assert_eq!(report.failed[0].id, 8);
+
+    let files = &report.exported[0].files;
+    assert_eq!(
+        files,
+        &[
+            dir.join("7-weeknight-chili.json"),
+            dir.join("7-weeknight-chili.md"),
+            dir.join("7-weeknight-chili.cook"),
+        ]
+    );
+    let markdown = std::fs::read_to_string(&files[1]).unwrap();
+    assert!(markdown.starts_with("# Weeknight Chili\n"));
+    assert!(markdown.contains("- 28 oz crushed tomatoes\n"));
+    let cook = std::fs::read_to_string(&files[2]).unwrap();
+    assert!(cook.contains("Brown the @ground beef{1%lb} with the @onion{1}(diced)."));
+
+    std::fs::remove_dir_all(&dir).unwrap();
+}
+
+#[tokio::test]
+async fn test_keyword_recipe_ids() {
+    let server = MockServer::start().await;
+    mount_json(
+        &server,
+        "/api/keyword/",
+        json!({"count": 2, "next": null, "previous": null, "results": [
+            {"id": 11, "name": "chili", "label": "chili"},
+            {"id": 12, "name": "weeknight", "label": "weeknight"}
+        ]}),
+    )
+    .await;
+    Mock::given(method("GET"))
+        .and(path("/api/recipe/"))
+        .and(query_param("keywords", "12"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+            "count": 2, "next": null, "previous": null,
+            "results": [{"id": 7, "name": "Weeknight Chili"}, {"id": 21, "name": "Fried Rice"}]
+        })))
+        .mount(&server)
+        .await;
+
+    let uri = server.uri();
+    let (found, missing) = tokio::task::spawn_blocking(move || {
+        let client = client(uri);
+        (
+            keyword_recipe_ids(&client, "Weeknight").unwrap(),
+            keyword_recipe_ids(&client, "dessert"),
+        )
+    })
+    .await
+    .unwrap();
+
+    assert_eq!(found, [7, 21]);
+    assert!(matches!(missing, Err(ExportError::KeywordNotFound(k)) if k == "dessert"));
+}
+
+#[test]
+fn test_json_ld_round_trips_through_scraper() {
+    let recipe: RecipeDetail = serde_json::from_str(RECIPE).unwrap();
+    let json = ExportFormat::JsonLd.render(&recipe, None);
+    let scraped = recipe_from_schema(&serde_json::from_str(&json).unwrap(), None).unwrap();
+
+    assert_eq!(scraped.name, "Weeknight Chili");
+    assert_eq!(
+        scraped.source_url.as_deref(),
+        Some("https://quickeats.example/chili")
+    );
+    assert_eq!(scraped.servings, 6);
+    assert_eq!((scraped.working_time, scraped.waiting_time), (15, 20));
+    // The scraper takes one step per instruction line
+    let instructions: Vec<&str> = scraped
+        .steps
+        .iter()
+        .map(|s| s.instruction.as_str())
+        .collect();
+    assert_eq!(
+        instructions,
+        [
+            "Brown the ground beef with the onion.",
+            "Add the tomatoes and chili powder.",
+            "Simmer 20 minutes."
+        ]
+    );
+    let ingredients: Vec<&str> = scraped.steps[0]
+        .ingredients
+        .iter()
+        .map(|i| i.original_text.as_str())
+        .collect();
+    assert_eq!(
+        ingredients,
+        [
+            "1 lb ground beef",
+            "1 onion, diced",
+            "28 oz crushed tomatoes",
+            "2 tbsp chili powder"
+        ]
+    );
+    let keywords: Vec<&str> = scraped.keywords.iter().map(|k| k.name.as_str()).collect();
+    assert_eq!(keywords, ["chili", "weeknight"]);
+}
@@ -21,6 +21,11 @@ fn test_all_binaries_exist() {
         "fatsecret_food_entry_create",
         "fatsecret_food_entry_edit",
         "fatsecret_food_entry_delete",
+        "fatsecret_food_entries_copy",
+        "fatsecret_food_entries_copy_meal",
+        "fatsecret_food_entries_commit_day",
+        "fatsecret_food_entries_save_template",
+        "fatsecret_meal_repeat",
         "fatsecret_exercise_entries_get",
         "fatsecret_exercise_entry_create",
         "fatsecret_exercise_entry_edit",
@@ -546,9 +546,12 @@ fn test_decrypt_with_wrong_key() {
     let different_key = "ffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffff";
     env::set_var("OAUTH_ENCRYPTION_KEY", different_key);
     let result = decrypt(&encrypted);
+    // Unversioned ciphertexts carry no key ID, so the key itself must fail
+    let legacy_result = decrypt(encrypted.rsplit(':').next().expect("payload"));
     env::remove_var("OAUTH_ENCRYPTION_KEY");
 
-    assert!(matches!(result, Err(CryptoError::DecryptionFailed)));
+    assert!(matches!(result, Err(CryptoError::UnknownKeyId(_))));
+    assert!(matches!(legacy_result, Err(CryptoError::DecryptionFailed)));
 }
 
 // =============================================================================
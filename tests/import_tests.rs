@@ -0,0 +1,211 @@
+//! Tests for recipe import from Cooklang and Markdown files
+//!
+//! Covers:
+//! - Cooklang written by the exporter reads back into the same recipe
+//! - Directory sync against a mock Tandoor server: creating, skipping,
+//!   updating, failed files, missing foods and units, and deleted files
+
+#![allow(clippy::unwrap_used, clippy::indexing_slicing)]
+
+use meal_planner::export::cooklang::to_cooklang;
+use meal_planner::import::sync::SyncAction;
+use meal_planner::import::{recipe_from_cooklang, sync_directory, SyncOptions, SyncReport};
+use meal_planner::tandoor::{RecipeDetail, TandoorClient, TandoorConfig};
+use serde_json::json;
+use std::path::PathBuf;
+use wiremock::matchers::{method, path};
+use wiremock::{Mock, MockServer, ResponseTemplate};
+
+const RECIPE: &str = include_str!("fixtures/export/tandoor_recipe.json");
+
+const CHILI: &str = "---\ntitle: Chili\nservings: 4\n---\n\
+    Brown @ground beef{1%lb} with @onion{1}.\n\
+    Add @cumin{1%tsp} and simmer for ~{30%minutes}.\n";
+
+const BREAD: &str = "# Bread\n\n## Ingredients\n\n- 500 g flour\n- 1 cup water\n\n\
+    ## Instructions\n\n1. Mix.\n2. Bake.\n";
+
+fn recipe_dir(name: &str) -> PathBuf {
+    let dir = std::env::temp_dir().join(format!("{name}-{}", std::process::id()));
+    if dir.exists() {
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+    std::fs::create_dir_all(dir.join("mains")).unwrap();
+    dir
+}
+
+fn page(results: &serde_json::Value) -> serde_json::Value {
+    json!({"count": 4, "next": null, "previous": null, "results": results})
+}
+
+/// Catalog without cumin and tsp; recipes are created as 42
+async fn mount_tandoor(server: &MockServer) {
+    let foods = json!([
+        {"id": 1, "name": "ground beef"},
+        {"id": 2, "name": "onion", "plural_name": "onions"},
+        {"id": 3, "name": "flour"},
+        {"id": 4, "name": "water"}
+    ]);
+    let units = json!([
+        {"id": 1, "name": "lb"},
+        {"id": 2, "name": "g"},
+        {"id": 3, "name": "cup", "plural_name": "cups"}
+    ]);
+    let responses = [
+        ("GET", "/api/food/", 200, page(&foods)),
+        ("GET", "/api/unit/", 200, page(&units)),
+        ("POST", "/api/food/", 201, json!({"id": 5, "name": "cumin"})),
+        ("POST", "/api/unit/", 201, json!({"id": 4, "name": "tsp"})),
+        (
+            "POST",
+            "/api/recipe/",
+            201,
+            json!({"id": 42, "name": "Chili"}),
+        ),
+        (
+            "PATCH",
+            "/api/recipe/42/",
+            200,
+            json!({"id": 42, "name": "Chili"}),
+        ),
+    ];
+    for (verb, url_path, status, body) in responses {
+        Mock::given(method(verb))
+            .and(path(url_path))
+            .respond_with(ResponseTemplate::new(status).set_body_json(body))
+            .mount(server)
+            .await;
+    }
+}
+
+async fn sync(server: &MockServer, options: SyncOptions) -> SyncReport {
+    let uri = server.uri();
+    tokio::task::spawn_blocking(move || {
+        let client = TandoorClient::new(&TandoorConfig {
+            base_url: uri,
+            api_token: "test_token".to_string(),
+        })
+        .unwrap();
+        sync_directory(&client, &options).unwrap()
+    })
+    .await
+    .unwrap()
+}
+
+fn actions(report: &SyncReport) -> Vec<(&str, SyncAction)> {
+    report
+        .files
+        .iter()
+        .map(|file| (file.path.as_str(), file.action))
+        .collect()
+}
+
+#[test]
+fn test_exported_cooklang_round_trip() {
+    let recipe: RecipeDetail = serde_json::from_str(RECIPE).unwrap();
+    let request = recipe_from_cooklang(&to_cooklang(&recipe, None), "fallback").unwrap();
+    let json = serde_json::to_value(&request).unwrap();
+
+    assert_eq!(request.name, recipe.name);
+    assert_eq!(request.servings, Some(6));
+    let ingredients: Vec<(&str, Option<&str>, f64)> = json["steps"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .flat_map(|step| step["ingredients"].as_array().unwrap())
+        .map(|i| {
+            (
+                i["food"]["name"].as_str().unwrap(),
+                i["unit"]["name"].as_str(),
+                i["amount"].as_f64().unwrap(),
+            )
+        })
+        .collect();
+    assert_eq!(
+        ingredients,
+        [
+            ("ground beef", Some("lb"), 1.0),
+            ("onion", None, 1.0),
+            ("crushed tomatoes", Some("oz"), 28.0),
+            ("chili powder", Some("tbsp"), 2.0)
+        ]
+    );
+}
+
+#[tokio::test]
+async fn test_sync_directory() {
+    let server = MockServer::start().await;
+    mount_tandoor(&server).await;
+    let dir = recipe_dir("import-sync");
+    std::fs::write(dir.join("mains/chili.cook"), CHILI).unwrap();
+    std::fs::write(dir.join("bread.md"), BREAD).unwrap();
+    std::fs::write(dir.join("notes.md"), "# Notes\n\nNo recipe here.\n").unwrap();
+
+    let first = sync(&server, SyncOptions::new(&dir)).await;
+    assert_eq!(
+        actions(&first),
+        [
+            ("bread.md", SyncAction::Created),
+            ("mains/chili.cook", SyncAction::Created),
+            ("notes.md", SyncAction::Failed)
+        ]
+    );
+    assert_eq!(first.files[1].recipe_id, Some(42));
+    assert_eq!(first.files[1].name.as_deref(), Some("Chili"));
+    assert!(first.files[2].error.is_some());
+    assert_eq!(first.resolution.created_foods, ["cumin"]);
+    assert_eq!(first.resolution.created_units, ["tsp"]);
+
+    std::fs::write(dir.join("mains/chili.cook"), CHILI.replace("30", "45")).unwrap();
+    std::fs::remove_file(dir.join("bread.md")).unwrap();
+    let second = sync(&server, SyncOptions::new(&dir)).await;
+    std::fs::remove_dir_all(&dir).unwrap();
+
+    assert_eq!(
+        actions(&second),
+        [
+            ("mains/chili.cook", SyncAction::Updated),
+            ("notes.md", SyncAction::Failed)
+        ]
+    );
+    assert_eq!(second.missing, ["bread.md"]);
+
+    let requests = server.received_requests().await.unwrap();
+    let count = |verb: &str, url_path: &str| {
+        requests
+            .iter()
+            .filter(|r| r.method.as_str() == verb && r.url.path() == url_path)
+            .count()
+    };
+    assert_eq!(count("POST", "/api/recipe/"), 2);
+    assert_eq!(count("PATCH", "/api/recipe/42/"), 1);
+}
+
+#[tokio::test]
+async fn test_sync_unchanged_and_dry_run() {
+    let server = MockServer::start().await;
+    mount_tandoor(&server).await;
+    let dir = recipe_dir("import-unchanged");
+    std::fs::write(dir.join("bread.md"), BREAD).unwrap();
+
+    let mut options = SyncOptions::new(&dir);
+    options.dry_run = true;
+    let dry_run = sync(&server, options).await;
+    assert_eq!(actions(&dry_run), [("bread.md", SyncAction::Created)]);
+    assert!(!dir.join(".tandoor-sync.json").exists());
+
+    sync(&server, SyncOptions::new(&dir)).await;
+    let again = sync(&server, SyncOptions::new(&dir)).await;
+    std::fs::remove_dir_all(&dir).unwrap();
+
+    assert_eq!(actions(&again), [("bread.md", SyncAction::Unchanged)]);
+    assert_eq!(again.files[0].recipe_id, Some(42));
+    let requests = server.received_requests().await.unwrap();
+    assert_eq!(
+        requests
+            .iter()
+            .filter(|r| r.method.as_str() == "POST")
+            .count(),
+        1
+    );
+}
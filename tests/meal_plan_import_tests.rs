@@ -0,0 +1,232 @@
+//! Tests for importing meal plans from iCalendar files and CSVs
+//!
+//! Covers:
+//! - Dry-run and real CSV imports against a mock Tandoor server: creating
+//!   meal plans (next to other recipes already in the slot), leaving
+//!   matching ones alone, and reporting ambiguous and unknown recipes
+//! - Re-importing an exported calendar event onto the meal plan it came from,
+//!   and ignoring the IDs in events from another instance
+
+#![allow(clippy::unwrap_used, clippy::indexing_slicing)]
+
+use meal_planner::import::plan_sync::MealPlanAction;
+use meal_planner::import::{
+    import_meal_plans, MealPlanImportOptions, MealPlanImportReport, PlanFile, PlanFormat,
+};
+use meal_planner::tandoor::{TandoorClient, TandoorConfig};
+use serde_json::json;
+use wiremock::matchers::{method, path};
+use wiremock::{Mock, MockServer, ResponseTemplate};
+
+const WEEK: &str = "\
+date,meal_type,recipe,servings
+2026-10-19,Dinner,chili con carne,4
+2026-10-20,Dinner,Chili con Carne,6
+2026-10-21,,Lentil soup,
+2026-10-21,Breakfast,chicken curry,
+2026-10-22,Dinner,Pancakes,
+2026-10-23,Brunch,Lentil Soup,
+";
+
+fn page(results: &serde_json::Value) -> serde_json::Value {
+    json!({"count": 1, "next": null, "previous": null, "results": results})
+}
+
+fn meal_plan(id: i64, date: &str, recipe: (i64, &str), servings: f64) -> serde_json::Value {
+    json!({
+        "id": id, "title": "", "recipe": {"id": recipe.0, "name": recipe.1},
+        "servings": servings, "note": "",
+        "from_date": format!("{date}T00:00:00+02:00"), "to_date": format!("{date}T00:00:00+02:00"),
+        "meal_type": {"id": 3, "name": "Dinner"}, "created_by": 1,
+        "recipe_name": recipe.1, "meal_type_name": "Dinner"
+    })
+}
+
+/// Tandoor with Chili planned for Monday's and Lentil Soup for Tuesday's
+/// dinner; new meal plans are created as 50
+async fn mount_tandoor(server: &MockServer) {
+    let recipes = json!([
+        {"id": 12, "name": "Chili con Carne", "servings": 4},
+        {"id": 13, "name": "Chicken Curry"},
+        {"id": 14, "name": "Chicken Curry"},
+        {"id": 15, "name": "Lentil Soup"}
+    ]);
+    let meal_types = json!([
+        {"id": 1, "name": "Breakfast", "time": "08:00:00"},
+        {"id": 3, "name": "Dinner", "time": "19:00:00", "default": true}
+    ]);
+    let plans = json!([
+        meal_plan(40, "2026-10-19", (12, "Chili con Carne"), 4.0),
+        meal_plan(41, "2026-10-20", (15, "Lentil Soup"), 2.0)
+    ]);
+    let responses = [
+        ("GET", "/api/recipe/", page(&recipes)),
+        ("GET", "/api/meal-type/", page(&meal_types)),
+        ("GET", "/api/meal-plan/", page(&plans)),
+        (
+            "POST",
+            "/api/meal-plan/",
+            meal_plan(50, "2026-10-21", (15, "Lentil Soup"), 1.0),
+        ),
+        (
+            "PATCH",
+            "/api/meal-plan/41/",
+            meal_plan(41, "2026-10-20", (12, "Chili con Carne"), 6.0),
+        ),
+    ];
+    for (verb, url_path, body) in responses {
+        Mock::given(method(verb))
+            .and(path(url_path))
+            .respond_with(ResponseTemplate::new(200).set_body_json(body))
+            .mount(server)
+            .await;
+    }
+}
+
+async fn import(server: &MockServer, file: PlanFile, dry_run: bool) -> MealPlanImportReport {
+    let uri = server.uri();
+    tokio::task::spawn_blocking(move || {
+        let client = TandoorClient::new(&TandoorConfig {
+            base_url: uri,
+            api_token: "test_token".to_string(),
+        })
+        .unwrap();
+        let options = MealPlanImportOptions {
+            dry_run,
+            ..MealPlanImportOptions::default()
+        };
+        import_meal_plans(&client, file, &options).unwrap()
+    })
+    .await
+    .unwrap()
+}
+
+fn actions(report: &MealPlanImportReport) -> Vec<(MealPlanAction, Option<i64>, Option<i64>)> {
+    report
+        .meals
+        .iter()
+        .map(|meal| (meal.action, meal.meal_plan_id, meal.recipe_id))
+        .collect()
+}
+
+#[tokio::test]
+async fn test_csv_dry_run() {
+    let server = MockServer::start().await;
+    mount_tandoor(&server).await;
+
+    let file = PlanFormat::Csv.read(WEEK.as_bytes()).unwrap();
+    let report = import(&server, file, true).await;
+    assert!(report.dry_run);
+    assert_eq!(
+        actions(&report),
+        [
+            (MealPlanAction::Unchanged, Some(40), Some(12)),
+            (MealPlanAction::Created, None, Some(12)),
+            (MealPlanAction::Created, None, Some(15)),
+            (MealPlanAction::Ambiguous, None, None),
+            (MealPlanAction::Unresolved, None, None),
+            (MealPlanAction::Failed, None, None),
+        ]
+    );
+    assert_eq!(report.meals[2].meal_type.as_deref(), Some("Dinner"));
+    let candidates: Vec<i64> = report.meals[3].candidates.iter().map(|c| c.id).collect();
+    assert_eq!(candidates, [13, 14]);
+    assert_eq!(
+        report.meals[5].error.as_deref(),
+        Some("no meal type named Brunch")
+    );
+    assert_eq!(report.needs_review().count(), 2);
+
+    let requests = server.received_requests().await.unwrap();
+    assert!(requests.iter().all(|r| r.method.as_str() == "GET"));
+}
+
+#[tokio::test]
+async fn test_csv_import() {
+    let server = MockServer::start().await;
+    mount_tandoor(&server).await;
+
+    let file = PlanFormat::Csv.read(WEEK.as_bytes()).unwrap();
+    let report = import(&server, file, false).await;
+    assert_eq!(report.meals[1].action, MealPlanAction::Created);
+    assert_eq!(report.meals[2].meal_plan_id, Some(50));
+
+    let requests = server.received_requests().await.unwrap();
+    let written: Vec<(&str, &str, serde_json::Value)> = requests
+        .iter()
+        .filter(|r| r.method.as_str() != "GET")
+        .map(|r| {
+            (
+                r.method.as_str(),
+                r.url.path(),
+                serde_json::from_slice(&r.body).unwrap(),
+            )
+        })
+        .collect();
+    assert_eq!(
+        written,
+        [
+            (
+                "POST",
+                "/api/meal-plan/",
+                json!({"recipe": 12, "meal_type": 3, "from_date": "2026-10-20", "servings": 6.0})
+            ),
+            (
+                "POST",
+                "/api/meal-plan/",
+                json!({"recipe": 15, "meal_type": 3, "from_date": "2026-10-21", "servings": 1.0})
+            ),
+        ]
+    );
+}
+
+#[tokio::test]
+async fn test_exported_calendar_event() {
+    let server = MockServer::start().await;
+    mount_tandoor(&server).await;
+    let host = server.address().ip().to_string();
+
+    // Exported from this instance: matched by IDs, even with a new summary.
+    // Then one from another instance with the same IDs, matched by summary,
+    // and one without a known summary, left alone.
+    let calendar = format!(
+        "BEGIN:VCALENDAR\r\n\
+        BEGIN:VEVENT\r\n\
+        UID:tandoor-meal-plan-41@{host}\r\n\
+        DTSTART:20261020T183000\r\n\
+        SUMMARY:Dinner: Soup\r\n\
+        DESCRIPTION:Servings: 3\r\n\
+        URL:{uri}/view/recipe/15\r\n\
+        END:VEVENT\r\n\
+        BEGIN:VEVENT\r\n\
+        UID:tandoor-meal-plan-40@tandoor.example\r\n\
+        DTSTART:20261019T190000\r\n\
+        SUMMARY:Dinner: Lentil Soup\r\n\
+        URL:https://tandoor.example/view/recipe/12\r\n\
+        END:VEVENT\r\n\
+        BEGIN:VEVENT\r\n\
+        UID:tandoor-meal-plan-40@tandoor.example\r\n\
+        DTSTART:20261019T190000\r\n\
+        URL:https://tandoor.example/view/recipe/12\r\n\
+        END:VEVENT\r\n\
+        BEGIN:VEVENT\r\n\
+        DTSTART:20261019T073000\r\n\
+        SUMMARY:Chili con carne\r\n\
+        END:VEVENT\r\n\
+        END:VCALENDAR\r\n",
+        uri = server.uri()
+    );
+    let file = PlanFormat::Ics.read(calendar.as_bytes()).unwrap();
+    let report = import(&server, file, true).await;
+    assert_eq!(
+        actions(&report),
+        [
+            (MealPlanAction::Updated, Some(41), Some(15)),
+            (MealPlanAction::Created, None, Some(15)),
+            (MealPlanAction::Unresolved, None, None),
+            (MealPlanAction::Created, None, Some(12)),
+        ]
+    );
+    assert_eq!(report.meals[0].meal_type.as_deref(), Some("Dinner"));
+    assert_eq!(report.meals[3].meal_type.as_deref(), Some("Breakfast"));
+}
@@ -0,0 +1,269 @@
+//! Tests for migrating from Paprika, Mealie and MyFitnessPal
+//!
+//! Covers:
+//! - Reading a Paprika archive (zip of gzipped JSON) and a Mealie API page
+//! - Dry-run and real recipe imports against a mock Tandoor server, skipping
+//!   recipes that already exist
+//! - Logging a MyFitnessPal diary to a stub `FatSecret` server, skipping
+//!   meals already logged
+
+#![allow(clippy::unwrap_used, clippy::indexing_slicing)]
+
+use meal_planner::fatsecret::core::{AccessToken, FatSecretConfig};
+use meal_planner::import::mfp::{import_diary, read_mfp_diary, DiaryAction};
+use meal_planner::import::migrate::MigrationAction;
+use meal_planner::import::{import_recipes, App, AppExport, MigrationReport};
+use meal_planner::tandoor::{TandoorClient, TandoorConfig};
+use serde_json::json;
+use std::io::Write;
+use wiremock::matchers::{body_string_contains, method, path};
+use wiremock::{Mock, MockServer, ResponseTemplate};
+
+const MEALIE: &str = include_str!("fixtures/import/mealie_recipes.json");
+
+/// A `.paprikarecipes` archive of the given recipes, plus one broken entry
+fn paprika_archive(recipes: &[serde_json::Value]) -> Vec<u8> {
+    let mut archive = zip::ZipWriter::new(std::io::Cursor::new(Vec::new()));
+    let options = zip::write::SimpleFileOptions::default();
+    for (index, recipe) in recipes.iter().enumerate() {
+        let mut gzip = flate2::write::GzEncoder::new(Vec::new(), flate2::Compression::default());
+        gzip.write_all(recipe.to_string().as_bytes()).unwrap();
+        archive
+            .start_file(format!("recipe-{index}.paprikarecipe"), options)
+            .unwrap();
+        archive.write_all(&gzip.finish().unwrap()).unwrap();
+    }
+    archive.start_file("broken.paprikarecipe", options).unwrap();
+    archive.write_all(b"\x1f\x8bnot gzip").unwrap();
+    archive.finish().unwrap().into_inner()
+}
+
+fn page(results: &serde_json::Value) -> serde_json::Value {
+    json!({"count": 1, "next": null, "previous": null, "results": results})
+}
+
+/// Tandoor with "Garlic Bread" already imported; new recipes are created as 42
+async fn mount_tandoor(server: &MockServer) {
+    let responses = [
+        (
+            "GET",
+            "/api/recipe/",
+            200,
+            page(&json!([{"id": 7, "name": "garlic bread"}])),
+        ),
+        (
+            "GET",
+            "/api/food/",
+            200,
+            page(&json!([{"id": 1, "name": "milk"}])),
+        ),
+        (
+            "GET",
+            "/api/unit/",
+            200,
+            page(&json!([{"id": 1, "name": "cup", "plural_name": "cups"}])),
+        ),
+        (
+            "POST",
+            "/api/food/",
+            201,
+            json!({"id": 2, "name": "rolled oats"}),
+        ),
+        ("POST", "/api/unit/", 201, json!({"id": 2, "name": "tbsp"})),
+        (
+            "POST",
+            "/api/recipe/",
+            201,
+            json!({"id": 42, "name": "Overnight Oats"}),
+        ),
+    ];
+    for (verb, url_path, status, body) in responses {
+        Mock::given(method(verb))
+            .and(path(url_path))
+            .respond_with(ResponseTemplate::new(status).set_body_json(body))
+            .mount(server)
+            .await;
+    }
+}
+
+async fn import(server: &MockServer, export: AppExport, dry_run: bool) -> MigrationReport {
+    let uri = server.uri();
+    tokio::task::spawn_blocking(move || {
+        let client = TandoorClient::new(&TandoorConfig {
+            base_url: uri,
+            api_token: "test_token".to_string(),
+        })
+        .unwrap();
+        import_recipes(&client, export, dry_run).unwrap()
+    })
+    .await
+    .unwrap()
+}
+
+fn paprika_export() -> AppExport {
+    let archive = paprika_archive(&[
+        json!({
+            "name": "Overnight Oats",
+            "ingredients": "1 cup rolled oats\n1 cup milk\n2 tbsp chia seeds",
+            "directions": "Stir together.\nRefrigerate overnight.",
+            "servings": "2"
+        }),
+        json!({"name": "Garlic Bread", "ingredients": "1 baguette"}),
+    ]);
+    App::Paprika.read(&archive).unwrap()
+}
+
+#[tokio::test]
+async fn test_paprika_dry_run() {
+    let server = MockServer::start().await;
+    mount_tandoor(&server).await;
+
+    let report = import(&server, paprika_export(), true).await;
+    assert!(report.dry_run);
+    let recipes: Vec<_> = report
+        .recipes
+        .iter()
+        .map(|r| {
+            (
+                r.name.as_str(),
+                r.action,
+                r.recipe_id,
+                r.ingredients,
+                r.steps,
+            )
+        })
+        .collect();
+    assert_eq!(
+        recipes,
+        [
+            ("Overnight Oats", MigrationAction::Created, None, 3, 2),
+            ("Garlic Bread", MigrationAction::Exists, Some(7), 1, 1)
+        ]
+    );
+    assert_eq!(
+        report.resolution.created_foods,
+        ["rolled oats", "chia seeds"]
+    );
+    assert_eq!(report.resolution.created_units, ["tbsp"]);
+    assert_eq!(report.unreadable.len(), 1);
+    assert_eq!(report.unreadable[0].entry, "broken.paprikarecipe");
+
+    let requests = server.received_requests().await.unwrap();
+    assert!(requests.iter().all(|r| r.method.as_str() == "GET"));
+}
+
+#[tokio::test]
+async fn test_paprika_import() {
+    let server = MockServer::start().await;
+    mount_tandoor(&server).await;
+
+    let report = import(&server, paprika_export(), false).await;
+    assert_eq!(report.recipes[0].action, MigrationAction::Created);
+    assert_eq!(report.recipes[0].recipe_id, Some(42));
+
+    let requests = server.received_requests().await.unwrap();
+    let created: Vec<serde_json::Value> = requests
+        .iter()
+        .filter(|r| r.method.as_str() == "POST" && r.url.path() == "/api/recipe/")
+        .map(|r| serde_json::from_slice(&r.body).unwrap())
+        .collect();
+    assert_eq!(created.len(), 1);
+    assert_eq!(created[0]["name"], "Overnight Oats");
+    assert_eq!(
+        created[0]["steps"][0]["ingredients"][1]["unit"]["name"],
+        "cup"
+    );
+}
+
+#[test]
+fn test_read_mealie_page() {
+    let export = App::Mealie.read(MEALIE.as_bytes()).unwrap();
+    assert!(export.unreadable.is_empty());
+    let names: Vec<&str> = export.recipes.iter().map(|r| r.name.as_str()).collect();
+    assert_eq!(names, ["Sheet Pan Gnocchi", "Garlic Bread"]);
+
+    let bread = serde_json::to_value(&export.recipes[1]).unwrap();
+    assert_eq!(bread["servings"], 8);
+    assert_eq!(
+        bread["steps"][0]["instruction"],
+        "## Bake\n\nSpread the garlic butter and bake until golden."
+    );
+    assert_eq!(
+        bread["steps"][0]["ingredients"][1],
+        json!({"amount": 4.0, "food": {"name": "garlic"}, "unit": {"name": "cloves"}, "note": "minced"})
+    );
+}
+
+const DIARY: &str = "\
+Date,Meal,Calories,Fat (g),Carbohydrates (g),Protein (g),Note
+2024-01-15,Breakfast,412,14,52,18,
+2024-01-15,Dinner,780,30,70,45,
+";
+
+/// Stub `FatSecret`: breakfast on 2024-01-15 was already imported
+async fn stub_fatsecret() -> (MockServer, FatSecretConfig) {
+    let server = MockServer::start().await;
+    let logged = json!({"food_entries": {"food_entry": {
+        "food_entry_id": "900", "food_entry_name": "MyFitnessPal Breakfast",
+        "food_entry_description": "1 meal", "food_id": "0", "serving_id": "0",
+        "number_of_units": "1.00", "meal": "breakfast", "date_int": "19737",
+        "calories": "412", "carbohydrate": "52", "protein": "18", "fat": "14"
+    }}});
+    Mock::given(method("POST"))
+        .and(body_string_contains("method=food_entries.get"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(logged))
+        .mount(&server)
+        .await;
+    Mock::given(method("POST"))
+        .and(body_string_contains("method=food_entry.create"))
+        .respond_with(
+            ResponseTemplate::new(200).set_body_json(json!({"food_entry_id": {"value": "901"}})),
+        )
+        .mount(&server)
+        .await;
+
+    let mut config =
+        FatSecretConfig::new("stub-client-abcdefg", "abcdefghijklmnopqrstuvwxyz").unwrap();
+    config.api_host = Some(server.uri());
+    (server, config)
+}
+
+#[tokio::test]
+async fn test_mfp_diary_import() {
+    let (server, config) = stub_fatsecret().await;
+    let token = AccessToken::new("user-token", "user-secret");
+
+    let dry_run = import_diary(
+        &config,
+        &token,
+        read_mfp_diary(DIARY.as_bytes()).unwrap(),
+        true,
+    )
+    .await
+    .unwrap();
+    let actions: Vec<_> = dry_run.meals.iter().map(|m| m.action).collect();
+    assert_eq!(actions, [DiaryAction::AlreadyLogged, DiaryAction::Created]);
+    assert!(dry_run.meals[1].food_entry_id.is_none());
+
+    let report = import_diary(
+        &config,
+        &token,
+        read_mfp_diary(DIARY.as_bytes()).unwrap(),
+        false,
+    )
+    .await
+    .unwrap();
+    assert_eq!(report.meals[1].food_entry_id.as_deref(), Some("901"));
+
+    let creates: Vec<String> = server
+        .received_requests()
+        .await
+        .unwrap()
+        .iter()
+        .map(|r| String::from_utf8_lossy(&r.body).to_string())
+        .filter(|body| body.contains("method=food_entry.create"))
+        .collect();
+    assert_eq!(creates.len(), 1);
+    assert!(creates[0].contains("meal=dinner"));
+    assert!(creates[0].contains("calories=780"));
+}
@@ -26,7 +26,7 @@ use meal_planner::fatsecret::core::{
     FatSecretConfig,
 };
 use meal_planner::fatsecret::crypto::{decrypt, encrypt, CryptoError, StorageError};
-use meal_planner::fatsecret::storage::TokenStorage;
+use meal_planner::fatsecret::storage::{TokenStorage, TokenStore};
 use sqlx::PgPool;
 use std::env;
 
@@ -515,7 +515,7 @@ fn test_token_decryption_with_wrong_key() {
     env::set_var("OAUTH_ENCRYPTION_KEY", key2);
     let result = decrypt(&encrypted);
     assert!(
-        matches!(result, Err(CryptoError::DecryptionFailed)),
+        matches!(result, Err(CryptoError::UnknownKeyId(_))),
         "wrong key should fail decryption"
     );
 
@@ -554,9 +554,14 @@ async fn setup_test_db() -> PgPool {
     let database_url = env::var("DATABASE_URL")
         .unwrap_or_else(|_| "postgres://localhost/meal_planner_test".to_string());
 
-    PgPool::connect(&database_url)
+    let pool = PgPool::connect(&database_url)
         .await
-        .expect("Failed to connect to test database")
+        .expect("Failed to connect to test database");
+    meal_planner::db::Database::from(pool.clone())
+        .run_migrations()
+        .await
+        .expect("Failed to run migrations");
+    pool
 }
 
 /// Helper function to clean up test data
@@ -0,0 +1,296 @@
+//! Tests for the site crawler against mock recipe site and Tandoor servers
+//!
+//! The site's robots.txt disallows `/recipes/private/` and names a sitemap
+//! index; its recipes cover every outcome:
+//! - `texas-style-brisket`: duplicate by name of Tandoor recipe 1
+//! - `cottage-pie`: duplicate by source URL of Tandoor recipe 2
+//! - `private/secret-sauce`: disallowed by robots.txt
+//! - `smoked-queso` and `brisket-flat`: imported
+//!
+//! The index also lists a sitemap that 404s and one under the disallowed
+//! path; both are reported and skipped.
+
+#![allow(clippy::unwrap_used, clippy::indexing_slicing)]
+
+use meal_planner::scrape::crawler::{CrawlState, DuplicateMatch, UrlOutcome};
+use meal_planner::scrape::{crawl, http_client, CrawlOptions, CrawlReport, PoliteFetcher, Source};
+use meal_planner::tandoor::{TandoorClient, TandoorConfig};
+use serde_json::json;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+use wiremock::matchers::{method, path};
+use wiremock::{Mock, MockServer, ResponseTemplate};
+
+async fn mount_text(server: &MockServer, url_path: &str, body: String) {
+    Mock::given(method("GET"))
+        .and(path(url_path))
+        .respond_with(ResponseTemplate::new(200).set_body_string(body))
+        .mount(server)
+        .await;
+}
+
+async fn recipe_site() -> MockServer {
+    let site = MockServer::start().await;
+    let uri = site.uri();
+
+    mount_text(
+        &site,
+        "/robots.txt",
+        format!("User-agent: *\nDisallow: /recipes/private/\n\nSitemap: {uri}/sitemap.xml\n"),
+    )
+    .await;
+    mount_text(
+        &site,
+        "/sitemap.xml",
+        format!(
+            r#"<?xml version="1.0" encoding="UTF-8"?>
+<sitemapindex xmlns="http://www.sitemaps.org/schemas/sitemap/0.9">
+  <sitemap><loc>{uri}/sitemap_pages.xml</loc></sitemap>
+  <sitemap><loc>{uri}/sitemap_gone.xml</loc></sitemap>
+  <sitemap><loc>{uri}/sitemap_recipes.xml</loc></sitemap>
+  <sitemap><loc>{uri}/recipes/private/sitemap.xml</loc></sitemap>
+</sitemapindex>"#
+        ),
+    )
+    .await;
+    mount_text(
+        &site,
+        "/sitemap_pages.xml",
+        format!(r#"<urlset><url><loc>{uri}/about</loc></url></urlset>"#),
+    )
+    .await;
+    mount_text(
+        &site,
+        "/sitemap_recipes.xml",
+        format!(
+            r#"<?xml version="1.0" encoding="UTF-8"?>
+<urlset xmlns="http://www.sitemaps.org/schemas/sitemap/0.9" xmlns:image="http://www.google.com/schemas/sitemap-image/1.1">
+  <url>
+    <loc>{uri}/recipes/texas-style-brisket</loc>
+    <image:image><image:title>Texas Style Brisket</image:title></image:image>
+  </url>
+  <url><loc>{uri}/recipes/cottage-pie</loc></url>
+  <url><loc>{uri}/recipes/private/secret-sauce</loc></url>
+  <url><loc>{uri}/recipes/smoked-queso</loc></url>
+  <url><loc>{uri}/recipes/brisket-flat</loc></url>
+</urlset>"#
+        ),
+    )
+    .await;
+
+    site
+}
+
+async fn tandoor(site_uri: &str) -> MockServer {
+    let tandoor = MockServer::start().await;
+
+    Mock::given(method("GET"))
+        .and(path("/api/recipe/"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+            "count": 2,
+            "next": null,
+            "previous": null,
+            "results": [
+                {"id": 1, "name": "Texas-Style Brisket"},
+                {"id": 2, "name": "Shepherd's Pie"}
+            ]
+        })))
+        .mount(&tandoor)
+        .await;
+    // Recipe details are fetched once; resumed runs use the cached source URLs
+    for (id, source_url) in [
+        (1, json!(null)),
+        (2, json!(format!("{site_uri}/recipes/cottage-pie/"))),
+    ] {
+        Mock::given(method("GET"))
+            .and(path(format!("/api/recipe/{id}/")))
+            .respond_with(
+                ResponseTemplate::new(200)
+                    .set_body_json(json!({"id": id, "source_url": source_url})),
+            )
+            .expect(1)
+            .mount(&tandoor)
+            .await;
+    }
+    Mock::given(method("POST"))
+        .and(path("/api/recipe-from-source/"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+            "error": false,
+            "msg": "",
+            "recipe": {"name": "Smoked Queso", "steps": [], "keywords": []}
+        })))
+        .expect(2)
+        .mount(&tandoor)
+        .await;
+    Mock::given(method("POST"))
+        .and(path("/api/recipe/"))
+        .respond_with(
+            ResponseTemplate::new(201).set_body_json(json!({"id": 10, "name": "Smoked Queso"})),
+        )
+        .expect(2)
+        .mount(&tandoor)
+        .await;
+
+    tandoor
+}
+
+fn state_path(name: &str) -> PathBuf {
+    let path = std::env::temp_dir().join(format!("{name}-{}.json", std::process::id()));
+    if path.exists() {
+        std::fs::remove_file(&path).unwrap();
+    }
+    path
+}
+
+fn options(site_uri: &str) -> CrawlOptions {
+    CrawlOptions {
+        sources: vec![Source::Site(site_uri.to_string())],
+        url_pattern: Some("/recipes/".to_string()),
+        ..CrawlOptions::default()
+    }
+}
+
+async fn run(tandoor_uri: &str, options: CrawlOptions, state_path: &Path) -> CrawlReport {
+    let tandoor_uri = tandoor_uri.to_string();
+    let state_path = state_path.to_path_buf();
+    tokio::task::spawn_blocking(move || {
+        let tandoor = TandoorClient::new(&TandoorConfig {
+            base_url: tandoor_uri,
+            api_token: "test_token".to_string(),
+        })
+        .unwrap();
+        let mut fetcher = PoliteFetcher::new(http_client().unwrap(), Duration::ZERO);
+        crawl(&mut fetcher, &tandoor, &options, &state_path).unwrap()
+    })
+    .await
+    .unwrap()
+}
+
+fn outcome<'a>(report: &'a CrawlReport, slug: &str) -> &'a UrlOutcome {
+    &report
+        .results
+        .iter()
+        .find(|r| r.url.ends_with(slug))
+        .unwrap()
+        .outcome
+}
+
+#[tokio::test]
+async fn test_crawl_dedupes_and_resumes() {
+    let site = recipe_site().await;
+    let tandoor = tandoor(&site.uri()).await;
+    let state_path = state_path("crawl-resume");
+
+    let first = run(
+        &tandoor.uri(),
+        CrawlOptions {
+            max_imports: Some(1),
+            ..options(&site.uri())
+        },
+        &state_path,
+    )
+    .await;
+    assert_first_run(&first);
+
+    // The second run picks up where the first stopped
+    let second = run(&tandoor.uri(), options(&site.uri()), &state_path).await;
+    assert_eq!(second.already_done, 4);
+    assert_eq!(second.imported, 1);
+    assert_eq!(second.results.len(), 1);
+    assert!(second.results[0].url.ends_with("/recipes/brisket-flat"));
+
+    let state = CrawlState::load(&state_path).unwrap();
+    std::fs::remove_file(&state_path).unwrap();
+    assert_eq!(state.outcomes.len(), 5);
+    assert_eq!(state.recipe_sources.len(), 2);
+}
+
+fn assert_first_run(first: &CrawlReport) {
+    assert_eq!(first.discovered, 5);
+    let mut failed_sitemaps: Vec<_> = first
+        .failed_sitemaps
+        .iter()
+        .map(|failed| failed.url.splitn(4, '/').last().unwrap())
+        .collect();
+    failed_sitemaps.sort_unstable();
+    assert_eq!(
+        failed_sitemaps,
+        ["recipes/private/sitemap.xml", "sitemap_gone.xml"]
+    );
+    assert_eq!(
+        (
+            first.imported,
+            first.duplicates,
+            first.disallowed,
+            first.failed
+        ),
+        (1, 2, 1, 0)
+    );
+    assert_eq!(first.remaining, 1);
+    assert!(matches!(
+        outcome(first, "texas-style-brisket"),
+        UrlOutcome::Duplicate {
+            recipe_id: 1,
+            matched: DuplicateMatch::Name { .. }
+        }
+    ));
+    assert_eq!(
+        outcome(first, "cottage-pie"),
+        &UrlOutcome::Duplicate {
+            recipe_id: 2,
+            matched: DuplicateMatch::SourceUrl
+        }
+    );
+    assert_eq!(outcome(first, "secret-sauce"), &UrlOutcome::Disallowed);
+    assert_eq!(
+        outcome(first, "smoked-queso"),
+        &UrlOutcome::Imported {
+            recipe_id: Some(10),
+            name: Some("Smoked Queso".to_string())
+        }
+    );
+}
+
+#[tokio::test]
+async fn test_dry_run_imports_and_saves_nothing() {
+    let site = recipe_site().await;
+    let tandoor = MockServer::start().await;
+    Mock::given(method("GET"))
+        .and(path("/api/recipe/"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+            "count": 0, "next": null, "previous": null, "results": []
+        })))
+        .mount(&tandoor)
+        .await;
+    Mock::given(method("POST"))
+        .respond_with(ResponseTemplate::new(500))
+        .expect(0)
+        .mount(&tandoor)
+        .await;
+    let state_path = state_path("crawl-dry-run");
+
+    let report = run(
+        &tandoor.uri(),
+        CrawlOptions {
+            dry_run: true,
+            ..options(&site.uri())
+        },
+        &state_path,
+    )
+    .await;
+
+    assert_eq!((report.imported, report.disallowed), (4, 1));
+    assert_eq!(
+        outcome(&report, "texas-style-brisket"),
+        &UrlOutcome::WouldImport {
+            name: "Texas Style Brisket".to_string()
+        }
+    );
+    assert_eq!(
+        outcome(&report, "cottage-pie"),
+        &UrlOutcome::WouldImport {
+            name: "cottage pie".to_string()
+        }
+    );
+    assert!(!state_path.exists());
+}
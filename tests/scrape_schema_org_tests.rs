@@ -0,0 +1,243 @@
+//! Tests for schema.org recipe scraping against saved HTML pages
+//!
+//! Fixtures in `tests/fixtures/scrape/` cover:
+//! - JSON-LD in a Yoast-style `@graph`, with `HowToSection`s and `ImageObject`s
+//! - Plain JSON-LD wrapped in an HTML comment, with HTML instructions
+//! - Microdata with nested `HowToStep` items
+//! - Pages without a recipe
+//!
+//! Ingredient lines are split into amount, unit, food and note.
+
+#![allow(clippy::unwrap_used, clippy::indexing_slicing)]
+
+use meal_planner::scrape::{parse_recipe_html, ScrapeError};
+use meal_planner::tandoor::SourceImportRecipe;
+
+fn parse(html: &str, page_url: &str) -> SourceImportRecipe {
+    parse_recipe_html(html, Some(page_url)).unwrap()
+}
+
+fn instructions(recipe: &SourceImportRecipe) -> Vec<&str> {
+    recipe
+        .steps
+        .iter()
+        .map(|s| s.instruction.as_str())
+        .collect()
+}
+
+fn ingredient_lines(recipe: &SourceImportRecipe) -> Vec<&str> {
+    recipe
+        .steps
+        .iter()
+        .flat_map(|s| &s.ingredients)
+        .map(|i| i.original_text.as_str())
+        .collect()
+}
+
+fn keyword_names(recipe: &SourceImportRecipe) -> Vec<&str> {
+    recipe.keywords.iter().map(|k| k.name.as_str()).collect()
+}
+
+fn brisket() -> SourceImportRecipe {
+    parse(
+        include_str!("fixtures/scrape/json_ld_graph.html"),
+        "https://smokeandfire.example/texas-brisket/",
+    )
+}
+
+fn cornbread() -> SourceImportRecipe {
+    parse(
+        include_str!("fixtures/scrape/microdata.html"),
+        "https://grandma.example/recipes/cornbread",
+    )
+}
+
+#[test]
+fn test_json_ld_graph_metadata() {
+    let recipe = brisket();
+
+    assert_eq!(recipe.name, "Texas Style Brisket");
+    assert_eq!(
+        recipe.description,
+        "Salt, pepper & post oak smoke. Nothing else."
+    );
+    assert_eq!(
+        recipe.source_url.as_deref(),
+        Some("https://smokeandfire.example/texas-brisket/")
+    );
+    assert_eq!(
+        recipe.image.as_deref(),
+        Some("https://smokeandfire.example/wp-content/uploads/brisket-1x1.jpg")
+    );
+    assert_eq!(recipe.servings, 12);
+    assert_eq!(recipe.servings_text, "servings");
+    assert_eq!(recipe.working_time, 30);
+    assert_eq!(recipe.waiting_time, 720);
+}
+
+#[test]
+fn test_json_ld_graph_sections_and_ingredients() {
+    let recipe = brisket();
+
+    assert_eq!(
+        instructions(&recipe),
+        [
+            "## Prep\n\nTrim the hard fat down to 1/4 inch.",
+            "Season all sides with the salt and pepper.",
+            "## Cook\n\nSmoke at 250°F until the bark is set, about 8 hours.",
+            "Wrap in butcher paper and cook to 203°F. Rest 1 hour.",
+        ]
+    );
+    assert_eq!(
+        ingredient_lines(&recipe),
+        [
+            "1 whole packer brisket (12-14 lb)",
+            "1/4 cup coarse kosher salt",
+            "1/4 cup 16 mesh black pepper",
+        ]
+    );
+    assert_eq!(recipe.steps[0].ingredients.len(), 3);
+    assert!(recipe.steps[1].ingredients.is_empty());
+}
+
+#[test]
+fn test_json_ld_ingredients_are_split() {
+    let recipe = brisket();
+    let split: Vec<_> = recipe.steps[0]
+        .ingredients
+        .iter()
+        .map(|i| {
+            (
+                i.amount,
+                i.unit.as_ref().map(|u| u.name.as_str()),
+                i.food.as_ref().map(|f| f.name.as_str()),
+                i.note.as_str(),
+            )
+        })
+        .collect();
+    assert_eq!(
+        split,
+        [
+            (Some(1.0), Some("whole"), Some("packer brisket"), "12-14 lb"),
+            (Some(0.25), Some("cup"), Some("coarse kosher salt"), ""),
+            (Some(0.25), Some("cup"), Some("16 mesh black pepper"), ""),
+        ]
+    );
+}
+
+#[test]
+fn test_json_ld_graph_keywords() {
+    assert_eq!(
+        keyword_names(&brisket()),
+        [
+            "brisket",
+            "bbq",
+            "smoked beef",
+            "Main Course",
+            "Texas",
+            "American"
+        ]
+    );
+}
+
+#[test]
+fn test_json_ld_in_comment_with_html_instructions() {
+    let recipe = parse(
+        include_str!("fixtures/scrape/json_ld_simple.html"),
+        "https://quickeats.example/chili?utm_source=feed",
+    );
+
+    assert_eq!(recipe.name, "Weeknight Chili");
+    assert_eq!(
+        recipe.source_url.as_deref(),
+        Some("https://quickeats.example/chili?utm_source=feed")
+    );
+    assert_eq!(recipe.servings, 6);
+    assert_eq!(recipe.servings_text, "");
+    // Only totalTime and cookTime: the rest is working time
+    assert_eq!(recipe.working_time, 15);
+    assert_eq!(recipe.waiting_time, 35);
+    assert_eq!(
+        instructions(&recipe),
+        [
+            "Brown the beef.",
+            "Add tomatoes & spices.",
+            "Simmer 30 minutes."
+        ]
+    );
+    assert_eq!(ingredient_lines(&recipe).len(), 3);
+    assert_eq!(keyword_names(&recipe), ["chili", "weeknight"]);
+}
+
+#[test]
+fn test_json_ld_without_page_url_uses_recipe_url() {
+    let recipe =
+        parse_recipe_html(include_str!("fixtures/scrape/json_ld_simple.html"), None).unwrap();
+    assert_eq!(
+        recipe.source_url.as_deref(),
+        Some("https://quickeats.example/chili")
+    );
+}
+
+#[test]
+fn test_microdata_metadata() {
+    let recipe = cornbread();
+
+    assert_eq!(recipe.name, "Grandma's Cornbread");
+    assert_eq!(recipe.description, "Crispy-edged skillet cornbread.");
+    assert_eq!(
+        recipe.image.as_deref(),
+        Some("https://grandma.example/images/cornbread.jpg")
+    );
+    assert_eq!(recipe.servings, 8);
+    assert_eq!(recipe.servings_text, "wedges");
+    assert_eq!(recipe.working_time, 10);
+    assert_eq!(recipe.waiting_time, 25);
+    // The author's name belongs to the nested Person item
+    assert_eq!(keyword_names(&recipe), ["Side Dish"]);
+}
+
+#[test]
+fn test_microdata_steps_and_ingredients() {
+    let recipe = cornbread();
+
+    assert_eq!(
+        instructions(&recipe),
+        [
+            "Heat the drippings in a cast iron skillet at 425°F.",
+            "Stir everything together and pour into the hot skillet.",
+            "Bake 25 minutes.",
+        ]
+    );
+    assert_eq!(
+        ingredient_lines(&recipe),
+        [
+            "1 cup cornmeal",
+            "1 cup buttermilk",
+            "2 tbsp bacon drippings"
+        ]
+    );
+}
+
+#[test]
+fn test_page_without_recipe() {
+    let result = parse_recipe_html(include_str!("fixtures/scrape/no_recipe.html"), None);
+    assert!(matches!(result, Err(ScrapeError::NoRecipe)));
+}
+
+#[test]
+fn test_scraped_recipe_matches_tandoor_import_shape() {
+    let recipe = cornbread();
+
+    // Round-trips through the same JSON that Tandoor's scraper returns,
+    // so it can be passed to tandoor_create_recipe as-is
+    let json = serde_json::to_value(&recipe).unwrap();
+    let parsed: SourceImportRecipe = serde_json::from_value(json).unwrap();
+    assert_eq!(parsed.name, recipe.name);
+    assert_eq!(parsed.steps.len(), 3);
+    assert_eq!(
+        parsed.steps[0].ingredients[0].food.as_ref().unwrap().name,
+        "cornmeal"
+    );
+    assert_eq!(parsed.steps[0].ingredients[0].amount, Some(1.0));
+}
@@ -1,11 +1,11 @@
 //! Meal plan list tests for Tandoor API client
 //!
-//! Tests: list_meal_plans
+//! Tests: list_meal_plans, get_meal_plans_with_recipes
 
 #![allow(clippy::unwrap_used)]
 
 use meal_planner::tandoor::{TandoorClient, TandoorConfig};
-use serde_json::json;
+use serde_json::{json, Value};
 use wiremock::{
     matchers::{method, path, query_param},
     Mock, MockServer, ResponseTemplate,
@@ -122,3 +122,67 @@ async fn test_list_meal_plans_with_pagination() {
     let response = result.expect("Should succeed");
     assert_eq!(response.count, 25);
 }
+
+fn meal_plan(id: i64, recipe_id: i64) -> Value {
+    json!({
+        "id": id,
+        "recipe": {"id": recipe_id, "name": format!("Recipe {recipe_id}")},
+        "servings": 2.0,
+        "from_date": "2026-10-19T00:00:00+02:00",
+        "to_date": "2026-10-19T00:00:00+02:00",
+        "meal_type": {"id": 1, "name": "Dinner"},
+        "created_by": 1,
+        "recipe_name": format!("Recipe {recipe_id}"),
+        "meal_type_name": "Dinner"
+    })
+}
+
+#[tokio::test]
+#[allow(clippy::expect_used, clippy::indexing_slicing)]
+async fn test_get_meal_plans_with_recipes() {
+    let mock_server = MockServer::start().await;
+
+    for (page, next, results) in [
+        (
+            "1",
+            json!("http://localhost/api/meal-plan/?page=2"),
+            json!([meal_plan(1, 7)]),
+        ),
+        ("2", Value::Null, json!([meal_plan(2, 7), meal_plan(3, 8)])),
+    ] {
+        Mock::given(method("GET"))
+            .and(path("/api/meal-plan/"))
+            .and(query_param("from_date", "2026-10-19"))
+            .and(query_param("to_date", "2026-10-25"))
+            .and(query_param("page", page))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+                "count": 3, "next": next, "previous": null, "results": results
+            })))
+            .mount(&mock_server)
+            .await;
+    }
+    // Each recipe is fetched once, however often it's planned
+    for recipe_id in [7, 8] {
+        Mock::given(method("GET"))
+            .and(path(format!("/api/recipe/{recipe_id}/")))
+            .respond_with(
+                ResponseTemplate::new(200).set_body_json(json!({"id": recipe_id, "steps": []})),
+            )
+            .expect(1)
+            .mount(&mock_server)
+            .await;
+    }
+
+    let uri = mock_server.uri();
+    let data = tokio::task::spawn_blocking(move || {
+        create_test_client(&uri).get_meal_plans_with_recipes("2026-10-19", "2026-10-25")
+    })
+    .await
+    .expect("Task should complete")
+    .expect("Should succeed");
+
+    let ids: Vec<_> = data.plans.iter().map(|plan| plan.id).collect();
+    assert_eq!(ids, [1, 2, 3]);
+    assert_eq!(data.recipes.len(), 2);
+    assert_eq!(data.recipes[&8]["id"], 8);
+}
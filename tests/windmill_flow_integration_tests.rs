@@ -43,7 +43,7 @@ fn test_coverage() {
     println!("   [x] import_recipe.flow (3 modules: scrape, derive tag, create)");
     println!("   [x] batch_import_recipes.flow (2 modules: loop, import_recipe)");
     println!(
-        "   [x] weekly_meal_plan.flow (6 modules: select, create×2, format, add to shopping×2)"
+        "   [x] weekly_meal_plan.flow (7 modules: select, create×2, format, render menu, add to shopping×2)"
     );
     println!();
 
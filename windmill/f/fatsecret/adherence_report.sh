@@ -0,0 +1,36 @@
+# shellcheck shell=bash
+# Planned vs eaten nutrition adherence report (JSON + Markdown)
+# Arguments: tandoor (resource), fatsecret (resource), access_token, access_secret, date,
+#            mode (day|week, optional), macro_target (object, optional)
+
+tandoor="$1"
+fatsecret="$2"
+access_token="$3"
+access_secret="$4"
+date="$5"
+mode="${6:-}"
+macro_target="${7:-}"
+
+# Build input dynamically
+input=$(jq -n \
+	--argjson tandoor "$tandoor" \
+	--argjson fatsecret "$fatsecret" \
+	--arg access_token "$access_token" \
+	--arg access_secret "$access_secret" \
+	--arg date "$date" \
+	'{
+		tandoor: $tandoor,
+		fatsecret: $fatsecret,
+		access_token: $access_token,
+		access_secret: $access_secret,
+		date: $date
+	}')
+if [ -n "$mode" ]; then
+	input=$(echo "$input" | jq --arg mode "$mode" '. + {mode: $mode}')
+fi
+if [ -n "$macro_target" ]; then
+	input=$(echo "$input" | jq --argjson macro_target "$macro_target" '. + {macro_target: $macro_target}')
+fi
+
+# Call binary and capture output
+echo "$input" | /usr/local/bin/meal-planner/fatsecret_adherence_report >./result.json
@@ -0,0 +1,26 @@
+# shellcheck shell=bash
+# List FatSecret food brands starting with the given characters
+# Arguments: fatsecret (resource), starts_with (string), brand_type (optional string),
+#            region (optional string), language (optional string)
+
+fatsecret="$1"
+starts_with="$2"
+brand_type="${3:-}"
+region="${4:-}"
+language="${5:-}"
+
+input=$(jq -n \
+	--argjson fatsecret "$fatsecret" \
+	--arg starts_with "$starts_with" \
+	'{fatsecret: $fatsecret, starts_with: $starts_with}')
+if [ -n "$brand_type" ]; then
+	input=$(echo "$input" | jq --arg brand_type "$brand_type" '. + {brand_type: $brand_type}')
+fi
+if [ -n "$region" ]; then
+	input=$(echo "$input" | jq --arg region "$region" '. + {region: $region}')
+fi
+if [ -n "$language" ]; then
+	input=$(echo "$input" | jq --arg language "$language" '. + {language: $language}')
+fi
+
+echo "$input" | /usr/local/bin/meal-planner/fatsecret_food_brands_get >./result.json
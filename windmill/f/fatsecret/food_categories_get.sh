@@ -0,0 +1,17 @@
+# shellcheck shell=bash
+# List FatSecret food categories
+# Arguments: fatsecret (resource), region (optional string), language (optional string)
+
+fatsecret="$1"
+region="${2:-}"
+language="${3:-}"
+
+input=$(jq -n --argjson fatsecret "$fatsecret" '{fatsecret: $fatsecret}')
+if [ -n "$region" ]; then
+	input=$(echo "$input" | jq --arg region "$region" '. + {region: $region}')
+fi
+if [ -n "$language" ]; then
+	input=$(echo "$input" | jq --arg language "$language" '. + {language: $language}')
+fi
+
+echo "$input" | /usr/local/bin/meal-planner/fatsecret_food_categories_get >./result.json
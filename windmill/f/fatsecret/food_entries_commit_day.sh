@@ -0,0 +1,19 @@
+# shellcheck shell=bash
+# Commit a FatSecret food diary day
+# Arguments: fatsecret (resource), access_token (string), access_secret (string), date (optional)
+
+fatsecret="$1"
+access_token="$2"
+access_secret="$3"
+date="${4:-}"
+
+input=$(jq -n \
+	--argjson fatsecret "$fatsecret" \
+	--arg access_token "$access_token" \
+	--arg access_secret "$access_secret" \
+	'{fatsecret: $fatsecret, access_token: $access_token, access_secret: $access_secret}')
+if [ -n "$date" ]; then
+	input=$(echo "$input" | jq --arg date "$date" '. + {date: $date}')
+fi
+
+echo "$input" | /usr/local/bin/meal-planner/fatsecret_food_entries_commit_day >./result.json
@@ -0,0 +1,22 @@
+# shellcheck shell=bash
+# Copy all FatSecret food diary entries from one day to another
+# Arguments: fatsecret (resource), access_token (string), access_secret (string),
+#            from_date (YYYY-MM-DD), to_date (optional, defaults to today)
+
+fatsecret="$1"
+access_token="$2"
+access_secret="$3"
+from_date="$4"
+to_date="${5:-}"
+
+input=$(jq -n \
+	--argjson fatsecret "$fatsecret" \
+	--arg access_token "$access_token" \
+	--arg access_secret "$access_secret" \
+	--arg from_date "$from_date" \
+	'{fatsecret: $fatsecret, access_token: $access_token, access_secret: $access_secret, from_date: $from_date}')
+if [ -n "$to_date" ]; then
+	input=$(echo "$input" | jq --arg to_date "$to_date" '. + {to_date: $to_date}')
+fi
+
+echo "$input" | /usr/local/bin/meal-planner/fatsecret_food_entries_copy >./result.json
@@ -0,0 +1,28 @@
+# shellcheck shell=bash
+# Copy one meal of a FatSecret food diary day to another day or meal
+# Arguments: fatsecret (resource), access_token (string), access_secret (string),
+#            from_date (YYYY-MM-DD), from_meal (string), to_date (optional), to_meal (optional)
+
+fatsecret="$1"
+access_token="$2"
+access_secret="$3"
+from_date="$4"
+from_meal="$5"
+to_date="${6:-}"
+to_meal="${7:-}"
+
+input=$(jq -n \
+	--argjson fatsecret "$fatsecret" \
+	--arg access_token "$access_token" \
+	--arg access_secret "$access_secret" \
+	--arg from_date "$from_date" \
+	--arg from_meal "$from_meal" \
+	'{fatsecret: $fatsecret, access_token: $access_token, access_secret: $access_secret, from_date: $from_date, from_meal: $from_meal}')
+if [ -n "$to_date" ]; then
+	input=$(echo "$input" | jq --arg to_date "$to_date" '. + {to_date: $to_date}')
+fi
+if [ -n "$to_meal" ]; then
+	input=$(echo "$input" | jq --arg to_meal "$to_meal" '. + {to_meal: $to_meal}')
+fi
+
+echo "$input" | /usr/local/bin/meal-planner/fatsecret_food_entries_copy_meal >./result.json
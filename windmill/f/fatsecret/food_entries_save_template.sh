@@ -0,0 +1,22 @@
+# shellcheck shell=bash
+# Save a FatSecret food diary day as a reusable template
+# Arguments: fatsecret (resource), access_token (string), access_secret (string),
+#            template_name (string), date (optional, defaults to today)
+
+fatsecret="$1"
+access_token="$2"
+access_secret="$3"
+template_name="$4"
+date="${5:-}"
+
+input=$(jq -n \
+	--argjson fatsecret "$fatsecret" \
+	--arg access_token "$access_token" \
+	--arg access_secret "$access_secret" \
+	--arg template_name "$template_name" \
+	'{fatsecret: $fatsecret, access_token: $access_token, access_secret: $access_secret, template_name: $template_name}')
+if [ -n "$date" ]; then
+	input=$(echo "$input" | jq --arg date "$date" '. + {date: $date}')
+fi
+
+echo "$input" | /usr/local/bin/meal-planner/fatsecret_food_entries_save_template >./result.json
@@ -0,0 +1,22 @@
+# shellcheck shell=bash
+# List FatSecret food sub-categories for a category
+# Arguments: fatsecret (resource), food_category_id (string), region (optional string),
+#            language (optional string)
+
+fatsecret="$1"
+food_category_id="$2"
+region="${3:-}"
+language="${4:-}"
+
+input=$(jq -n \
+	--argjson fatsecret "$fatsecret" \
+	--arg food_category_id "$food_category_id" \
+	'{fatsecret: $fatsecret, food_category_id: $food_category_id}')
+if [ -n "$region" ]; then
+	input=$(echo "$input" | jq --arg region "$region" '. + {region: $region}')
+fi
+if [ -n "$language" ]; then
+	input=$(echo "$input" | jq --arg language "$language" '. + {language: $language}')
+fi
+
+echo "$input" | /usr/local/bin/meal-planner/fatsecret_food_sub_categories_get >./result.json
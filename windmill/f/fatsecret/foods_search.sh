@@ -1,11 +1,15 @@
 # shellcheck shell=bash
 # Search FatSecret foods database
-# Arguments: fatsecret (resource), query (string), page (int), max_results (int)
+# Arguments: fatsecret (resource), query (string), page (int), max_results (int),
+#            brand (optional string), category (optional string), generic_only (optional bool)
 
 fatsecret="$1"
 query="$2"
 page="${3:-0}"
 max_results="${4:-20}"
+brand="${5:-}"
+category="${6:-}"
+generic_only="${7:-}"
 
 # Build JSON input for binary
 input=$(jq -n \
@@ -14,6 +18,15 @@ input=$(jq -n \
 	--argjson page "$page" \
 	--argjson max_results "$max_results" \
 	'{fatsecret: $fatsecret, query: $query, page: $page, max_results: $max_results}')
+if [ -n "$brand" ]; then
+	input=$(echo "$input" | jq --arg brand "$brand" '. + {brand: $brand}')
+fi
+if [ -n "$category" ]; then
+	input=$(echo "$input" | jq --arg category "$category" '. + {category: $category}')
+fi
+if [ -n "$generic_only" ]; then
+	input=$(echo "$input" | jq --argjson generic_only "$generic_only" '. + {generic_only: $generic_only}')
+fi
 
 # Call binary and capture output
 echo "$input" | /usr/local/bin/meal-planner/fatsecret_foods_search >./result.json
@@ -0,0 +1,45 @@
+# shellcheck shell=bash
+# Sync Tandoor meal plans into the FatSecret food diary (idempotent)
+# Arguments: tandoor (resource), fatsecret (resource), access_token, access_secret, from_date,
+#            to_date (optional), meal_type_map (object, optional), dry_run (bool, optional),
+#            database_url (optional, falls back to DATABASE_URL)
+
+tandoor="$1"
+fatsecret="$2"
+access_token="$3"
+access_secret="$4"
+from_date="$5"
+to_date="${6:-}"
+meal_type_map="${7:-}"
+dry_run="${8:-}"
+database_url="${9:-}"
+
+# Build input dynamically
+input=$(jq -n \
+	--argjson tandoor "$tandoor" \
+	--argjson fatsecret "$fatsecret" \
+	--arg access_token "$access_token" \
+	--arg access_secret "$access_secret" \
+	--arg from_date "$from_date" \
+	'{
+		tandoor: $tandoor,
+		fatsecret: $fatsecret,
+		access_token: $access_token,
+		access_secret: $access_secret,
+		from_date: $from_date
+	}')
+if [ -n "$to_date" ]; then
+	input=$(echo "$input" | jq --arg to_date "$to_date" '. + {to_date: $to_date}')
+fi
+if [ -n "$meal_type_map" ]; then
+	input=$(echo "$input" | jq --argjson meal_type_map "$meal_type_map" '. + {meal_type_map: $meal_type_map}')
+fi
+if [ -n "$dry_run" ]; then
+	input=$(echo "$input" | jq --argjson dry_run "$dry_run" '. + {dry_run: $dry_run}')
+fi
+if [ -n "$database_url" ]; then
+	input=$(echo "$input" | jq --arg database_url "$database_url" '. + {database_url: $database_url}')
+fi
+
+# Call binary and capture output
+echo "$input" | /usr/local/bin/meal-planner/fatsecret_meal_plan_sync >./result.json
@@ -0,0 +1,37 @@
+# shellcheck shell=bash
+# Repeat a logged FatSecret meal on the following days (e.g. yesterday's breakfast for 5 days)
+# Arguments: fatsecret (resource), access_token (string), access_secret (string), days (number),
+#            meal (optional), from_date (optional), start_date (optional), to_meal (optional),
+#            dry_run (optional bool)
+
+fatsecret="$1"
+access_token="$2"
+access_secret="$3"
+days="$4"
+meal="${5:-}"
+from_date="${6:-}"
+start_date="${7:-}"
+to_meal="${8:-}"
+dry_run="${9:-false}"
+
+input=$(jq -n \
+	--argjson fatsecret "$fatsecret" \
+	--arg access_token "$access_token" \
+	--arg access_secret "$access_secret" \
+	--argjson days "$days" \
+	--argjson dry_run "$dry_run" \
+	'{fatsecret: $fatsecret, access_token: $access_token, access_secret: $access_secret, days: $days, dry_run: $dry_run}')
+if [ -n "$meal" ]; then
+	input=$(echo "$input" | jq --arg meal "$meal" '. + {meal: $meal}')
+fi
+if [ -n "$from_date" ]; then
+	input=$(echo "$input" | jq --arg from_date "$from_date" '. + {from_date: $from_date}')
+fi
+if [ -n "$start_date" ]; then
+	input=$(echo "$input" | jq --arg start_date "$start_date" '. + {start_date: $start_date}')
+fi
+if [ -n "$to_meal" ]; then
+	input=$(echo "$input" | jq --arg to_meal "$to_meal" '. + {to_meal: $to_meal}')
+fi
+
+echo "$input" | /usr/local/bin/meal-planner/fatsecret_meal_repeat >./result.json
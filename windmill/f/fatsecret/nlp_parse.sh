@@ -0,0 +1,28 @@
+# shellcheck shell=bash
+# Parse a free-text meal description into FatSecret foods, optionally logging them
+# Arguments: fatsecret (resource), user_input (string), meal (string), date (optional),
+#            commit (optional bool), access_token (optional), access_secret (optional)
+
+fatsecret="$1"
+user_input="$2"
+meal="$3"
+date="${4:-}"
+commit="${5:-false}"
+access_token="${6:-}"
+access_secret="${7:-}"
+
+input=$(jq -n \
+	--argjson fatsecret "$fatsecret" \
+	--arg user_input "$user_input" \
+	--arg meal "$meal" \
+	--argjson commit "$commit" \
+	'{fatsecret: $fatsecret, user_input: $user_input, meal: $meal, commit: $commit}')
+if [ -n "$date" ]; then
+	input=$(echo "$input" | jq --arg date "$date" '. + {date: $date}')
+fi
+if [ -n "$access_token" ]; then
+	input=$(echo "$input" | jq --arg access_token "$access_token" --arg access_secret "$access_secret" \
+		'. + {access_token: $access_token, access_secret: $access_secret}')
+fi
+
+echo "$input" | /usr/local/bin/meal-planner/fatsecret_nlp_parse >./result.json
@@ -1,11 +1,13 @@
 # shellcheck shell=bash
 # Complete FatSecret OAuth flow - exchanges verifier for access token
-# Arguments: fatsecret (resource), oauth_token (string), oauth_token_secret (string), oauth_verifier (string)
+# Arguments: fatsecret (resource), oauth_token (string), oauth_token_secret (string), oauth_verifier (string),
+#            user (optional)
 
 fatsecret="$1"
 oauth_token="$2"
 oauth_token_secret="$3"
 oauth_verifier="$4"
+user="${5:-}"
 
 # Build JSON input for binary
 input=$(jq -n \
@@ -14,6 +16,9 @@ input=$(jq -n \
 	--arg oauth_token_secret "$oauth_token_secret" \
 	--arg oauth_verifier "$oauth_verifier" \
 	'{fatsecret: $fatsecret, oauth_token: $oauth_token, oauth_token_secret: $oauth_token_secret, oauth_verifier: $oauth_verifier}')
+if [ -n "$user" ]; then
+	input=$(echo "$input" | jq --arg user "$user" '. + {user: $user}')
+fi
 
 # Call binary and capture output
 echo "$input" | /usr/local/bin/meal-planner/fatsecret_oauth_complete >./result.json
@@ -1,15 +1,19 @@
 # shellcheck shell=bash
 # Start FatSecret OAuth flow - calls binary with JSON input
-# Arguments: fatsecret (resource), callback_url (string)
+# Arguments: fatsecret (resource), callback_url (string), user (optional)
 
 fatsecret="$1"
 callback_url="${2:-oob}"
+user="${3:-}"
 
 # Build JSON input for binary
 input=$(jq -n \
 	--argjson fatsecret "$fatsecret" \
 	--arg callback_url "$callback_url" \
 	'{fatsecret: $fatsecret, callback_url: $callback_url}')
+if [ -n "$user" ]; then
+	input=$(echo "$input" | jq --arg user "$user" '. + {user: $user}')
+fi
 
 # Call binary and capture output
 echo "$input" | /usr/local/bin/meal-planner/fatsecret_oauth_start >./result.json
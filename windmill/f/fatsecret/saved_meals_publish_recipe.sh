@@ -0,0 +1,40 @@
+# shellcheck shell=bash
+# Publish a Tandoor recipe as a FatSecret saved meal (updates in place on re-run)
+# Arguments: tandoor (resource), fatsecret (resource), access_token, access_secret, recipe_id,
+#            portions (optional), meals (optional), food_overrides (object, optional)
+
+tandoor="$1"
+fatsecret="$2"
+access_token="$3"
+access_secret="$4"
+recipe_id="$5"
+portions="${6:-}"
+meals="${7:-}"
+food_overrides="${8:-}"
+
+# Build input dynamically
+input=$(jq -n \
+	--argjson tandoor "$tandoor" \
+	--argjson fatsecret "$fatsecret" \
+	--arg access_token "$access_token" \
+	--arg access_secret "$access_secret" \
+	--argjson recipe_id "$recipe_id" \
+	'{
+		tandoor: $tandoor,
+		fatsecret: $fatsecret,
+		access_token: $access_token,
+		access_secret: $access_secret,
+		recipe_id: $recipe_id
+	}')
+if [ -n "$portions" ]; then
+	input=$(echo "$input" | jq --argjson portions "$portions" '. + {portions: $portions}')
+fi
+if [ -n "$meals" ]; then
+	input=$(echo "$input" | jq --arg meals "$meals" '. + {meals: $meals}')
+fi
+if [ -n "$food_overrides" ]; then
+	input=$(echo "$input" | jq --argjson food_overrides "$food_overrides" '. + {food_overrides: $food_overrides}')
+fi
+
+# Call binary and capture output
+echo "$input" | /usr/local/bin/meal-planner/fatsecret_saved_meals_publish_recipe >./result.json
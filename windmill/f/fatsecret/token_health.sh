@@ -0,0 +1,20 @@
+# shellcheck shell=bash
+# Check a user's stored FatSecret token and start re-authorization if it is revoked or missing
+# Arguments: fatsecret (resource, optional - falls back to env vars), user (optional),
+#            callback_url (optional, defaults to oob)
+
+fatsecret="${1:-}"
+user="${2:-}"
+callback_url="${3:-oob}"
+
+# Build JSON input for binary
+input=$(jq -n --arg callback_url "$callback_url" '{callback_url: $callback_url}')
+if [ -n "$fatsecret" ]; then
+	input=$(echo "$input" | jq --argjson fatsecret "$fatsecret" '. + {fatsecret: $fatsecret}')
+fi
+if [ -n "$user" ]; then
+	input=$(echo "$input" | jq --arg user "$user" '. + {user: $user}')
+fi
+
+# Call binary and capture output
+echo "$input" | /usr/local/bin/meal-planner/fatsecret_token_health >./result.json
@@ -0,0 +1,32 @@
+# shellcheck shell=bash
+# FatSecret weight trend: smoothed trend, weekly rate, energy balance and goal projection
+# Arguments: fatsecret (resource), access_token, access_secret, date (optional), months (optional),
+#            goal_weight_kg (optional), rate_window_days (optional)
+
+fatsecret="$1"
+access_token="$2"
+access_secret="$3"
+date="${4:-}"
+months="${5:-}"
+goal_weight_kg="${6:-}"
+rate_window_days="${7:-}"
+
+input=$(jq -n \
+	--argjson fatsecret "$fatsecret" \
+	--arg access_token "$access_token" \
+	--arg access_secret "$access_secret" \
+	'{fatsecret: $fatsecret, access_token: $access_token, access_secret: $access_secret}')
+if [ -n "$date" ]; then
+	input=$(echo "$input" | jq --arg date "$date" '. + {date: $date}')
+fi
+if [ -n "$months" ]; then
+	input=$(echo "$input" | jq --argjson months "$months" '. + {months: $months}')
+fi
+if [ -n "$goal_weight_kg" ]; then
+	input=$(echo "$input" | jq --argjson goal_weight_kg "$goal_weight_kg" '. + {goal_weight_kg: $goal_weight_kg}')
+fi
+if [ -n "$rate_window_days" ]; then
+	input=$(echo "$input" | jq --argjson rate_window_days "$rate_window_days" '. + {rate_window_days: $rate_window_days}')
+fi
+
+echo "$input" | /usr/local/bin/meal-planner/fatsecret_weight_trend >./result.json
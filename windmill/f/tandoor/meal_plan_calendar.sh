@@ -0,0 +1,20 @@
+# shellcheck shell=bash
+# Export meal plans for a date range as an iCal feed with reminders
+
+tandoor="$1"
+from_date="$2"
+to_date="${3:-}"
+reminder_minutes="${4:-}"
+prep_reminders="${5:-true}"
+
+# Build input dynamically
+input=$(jq -n --argjson tandoor "$tandoor" --arg from_date "$from_date" --argjson prep_reminders "$prep_reminders" \
+	'{tandoor: $tandoor, from_date: $from_date, prep_reminders: $prep_reminders}')
+if [ -n "$to_date" ]; then
+	input=$(echo "$input" | jq --arg to_date "$to_date" '. + {to_date: $to_date}')
+fi
+if [ -n "$reminder_minutes" ]; then
+	input=$(echo "$input" | jq --argjson reminder_minutes "$reminder_minutes" '. + {reminder_minutes: $reminder_minutes}')
+fi
+
+echo "$input" | /usr/local/bin/meal-planner/tandoor_meal_plan_calendar >./result.json
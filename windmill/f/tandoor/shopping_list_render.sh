@@ -0,0 +1,32 @@
+# shellcheck shell=bash
+# Render shopping list grouped and ordered by supermarket aisle
+# Arguments: tandoor (resource), mealplan_ids (array), supermarket_id (int, optional),
+#            format (markdown|text|html, optional), title (optional), food_categories (object, optional)
+
+tandoor="$1"
+mealplan_ids="$2"
+supermarket_id="${3:-}"
+format="${4:-}"
+title="${5:-}"
+food_categories="${6:-}"
+
+# Build input dynamically
+input=$(jq -n \
+	--argjson tandoor "$tandoor" \
+	--argjson mealplan_ids "$mealplan_ids" \
+	'{tandoor: $tandoor, mealplan_ids: $mealplan_ids}')
+if [ -n "$supermarket_id" ]; then
+	input=$(echo "$input" | jq --argjson supermarket_id "$supermarket_id" '. + {supermarket_id: $supermarket_id}')
+fi
+if [ -n "$format" ]; then
+	input=$(echo "$input" | jq --arg format "$format" '. + {format: $format}')
+fi
+if [ -n "$title" ]; then
+	input=$(echo "$input" | jq --arg title "$title" '. + {title: $title}')
+fi
+if [ -n "$food_categories" ]; then
+	input=$(echo "$input" | jq --argjson food_categories "$food_categories" '. + {food_categories: $food_categories}')
+fi
+
+# Call binary and capture output
+echo "$input" | /usr/local/bin/meal-planner/tandoor_shopping_list_render >./result.json
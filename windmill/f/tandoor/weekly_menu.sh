@@ -0,0 +1,24 @@
+# shellcheck shell=bash
+# Render a printable weekly menu and prep sheet for a date range
+
+tandoor="$1"
+from_date="$2"
+to_date="${3:-}"
+prep_date="${4:-}"
+format="${5:-html}"
+title="${6:-}"
+
+# Build input dynamically
+input=$(jq -n --argjson tandoor "$tandoor" --arg from_date "$from_date" --arg format "$format" \
+	'{tandoor: $tandoor, from_date: $from_date, format: $format}')
+if [ -n "$to_date" ]; then
+	input=$(echo "$input" | jq --arg to_date "$to_date" '. + {to_date: $to_date}')
+fi
+if [ -n "$prep_date" ]; then
+	input=$(echo "$input" | jq --arg prep_date "$prep_date" '. + {prep_date: $prep_date}')
+fi
+if [ -n "$title" ]; then
+	input=$(echo "$input" | jq --arg title "$title" '. + {title: $title}')
+fi
+
+echo "$input" | /usr/local/bin/meal-planner/tandoor_weekly_menu >./result.json